@@ -0,0 +1,63 @@
+//! Anime listing DTOs.
+
+use serde::{Deserialize, Serialize};
+
+/// An airing series with its latest episode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct OngoingAnimeItem {
+    pub title: String,
+    pub slug: String,
+    pub poster: String,
+    pub current_episode: String,
+    pub anime_url: String,
+}
+
+/// A finished series with its episode count.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CompleteAnimeItem {
+    pub title: String,
+    pub slug: String,
+    pub poster: String,
+    pub episode_count: String,
+    pub anime_url: String,
+}
+
+/// The anime home page: `data` of `GET /api/anime` and `GET /api/anime2`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HomeData {
+    pub ongoing_anime: Vec<OngoingAnimeItem>,
+    pub complete_anime: Vec<CompleteAnimeItem>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_api_envelope_data() {
+        let body = serde_json::json!({
+            "ongoing_anime": [{
+                "title": "Frieren",
+                "slug": "frieren",
+                "poster": "https://cdn.test/frieren.jpg",
+                "current_episode": "Episode 12",
+                "anime_url": "https://otakudesu.test/anime/frieren/"
+            }],
+            "complete_anime": [{
+                "title": "Mushishi",
+                "slug": "mushishi",
+                "poster": "https://cdn.test/mushishi.jpg",
+                "episode_count": "26",
+                "anime_url": "https://otakudesu.test/anime/mushishi/"
+            }]
+        });
+
+        let data: HomeData = serde_json::from_value(body.clone()).unwrap();
+        assert_eq!(data.ongoing_anime[0].current_episode, "Episode 12");
+        assert_eq!(data.complete_anime[0].episode_count, "26");
+        assert_eq!(serde_json::to_value(&data).unwrap(), body);
+    }
+}
@@ -0,0 +1,28 @@
+//! Komik listing DTOs.
+
+use serde::{Deserialize, Serialize};
+
+use crate::pagination::Pagination;
+
+/// A title in the manga, manhwa and manhua listings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct KomikItem {
+    pub title: String,
+    pub poster: String,
+    pub chapter: String,
+    pub date: String,
+    /// Empty when the source doesn't show a reader count.
+    #[serde(default)]
+    pub reader_count: String,
+    pub r#type: String,
+    pub slug: String,
+}
+
+/// One page of `GET /api/komik/{manga,manhwa,manhua}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct KomikList {
+    pub data: Vec<KomikItem>,
+    pub pagination: Pagination,
+}
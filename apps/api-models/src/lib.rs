@@ -0,0 +1,15 @@
+//! Response types shared by the API server and the Leptos client.
+//!
+//! The server serializes these and the client deserializes the very same
+//! types, so a field renamed on one side is a compile error on the other
+//! instead of a silently empty page. Keep this crate free of server-only
+//! dependencies: it is also compiled for `wasm32-unknown-unknown`. OpenAPI
+//! schemas are derived only with the `openapi` feature.
+
+pub mod anime;
+pub mod komik;
+pub mod pagination;
+
+pub use anime::{CompleteAnimeItem, HomeData, OngoingAnimeItem};
+pub use komik::{KomikItem, KomikList};
+pub use pagination::Pagination;
@@ -0,0 +1,40 @@
+//! Page metadata for the scraper list endpoints.
+
+use serde::{Deserialize, Serialize};
+
+/// Page metadata returned by the scraper list endpoints.
+///
+/// Pages are 1-indexed; `next_page`/`previous_page` are `null` at the ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Pagination {
+    pub current_page: u32,
+    pub last_visible_page: u32,
+    pub has_next_page: bool,
+    pub next_page: Option<u32>,
+    pub has_previous_page: bool,
+    pub previous_page: Option<u32>,
+}
+
+impl Pagination {
+    /// Builds the metadata for page `current` of a listing whose last known
+    /// page is `last`. `last` is raised to cover `current` (and the next page
+    /// when `has_next` is set), since upstream pagers often only show a window.
+    pub fn from_page(current: u32, last: u32, has_next: bool) -> Self {
+        let current = current.max(1);
+        let last = if has_next { last.max(current + 1) } else { last.max(current) };
+        Self {
+            current_page: current,
+            last_visible_page: last,
+            has_next_page: has_next,
+            next_page: has_next.then_some(current + 1),
+            has_previous_page: current > 1,
+            previous_page: (current > 1).then(|| current - 1),
+        }
+    }
+
+    /// A single page with nothing before or after it.
+    pub fn single() -> Self {
+        Self::from_page(1, 1, false)
+    }
+}
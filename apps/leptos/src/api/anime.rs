@@ -5,14 +5,7 @@ use serde::{Deserialize, Serialize};
 use urlencoding;
 
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct Anime1OngoingItem {
-    pub title: String,
-    pub slug: String,
-    pub poster: String,
-    pub current_episode: String,
-    pub anime_url: String,
-}
+pub use api_models::anime::{CompleteAnimeItem, HomeData, OngoingAnimeItem};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Anime2OngoingItem {
@@ -23,55 +16,34 @@ pub struct Anime2OngoingItem {
     pub anime_url: String,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct Anime2CompleteItem {
-    pub title: String,
-    pub slug: String,
-    pub poster: String,
-    pub episode_count: String,
-    pub anime_url: String,
-}
-
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct OngoingAnime1Response {
-    pub data: Vec<Anime1OngoingItem>,
+    pub data: Vec<OngoingAnimeItem>,
     pub pagination: Pagination,
     pub status: String,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct Anime1Data {
-    pub ongoing_anime: Vec<Anime1OngoingItem>,
-    pub complete_anime: Vec<Anime2CompleteItem>,
-}
-
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct Anime2Data {
-    pub ongoing_anime: Vec<Anime1OngoingItem>, // OpenAPI says OngoingAnimeItem for Source 2 Index too
-    pub complete_anime: Vec<Anime2CompleteItem>,
-}
-
-pub async fn fetch_anime1_index() -> Result<Anime1Data, String> {
+pub async fn fetch_anime1_index() -> Result<HomeData, String> {
     let client = Client::new();
     let url = format!("{}/anime", API_BASE_URL);
     let response = client.get(&url).send().await.map_err(|e| e.to_string())?;
 
     if response.status().is_success() {
-        let api_res = response.json::<ApiResponse<Anime1Data>>().await.map_err(|e| e.to_string())?;
+        let api_res = response.json::<ApiResponse<HomeData>>().await.map_err(|e| e.to_string())?;
         api_res.data.ok_or_else(|| "No data found".to_string())
     } else {
         Err("Failed to fetch anime 1 index".to_string())
     }
 }
 
-pub async fn fetch_anime2_index() -> Result<Anime2Data, String> {
+pub async fn fetch_anime2_index() -> Result<HomeData, String> {
     let client = Client::new();
     let url = format!("{}/anime2", API_BASE_URL);
     let response = client.get(&url).send().await.map_err(|e| e.to_string())?;
 
     if response.status().is_success() {
         #[derive(Deserialize)]
-        struct Res { data: Anime2Data }
+        struct Res { data: HomeData }
         let api_res = response.json::<Res>().await.map_err(|e| e.to_string())?;
         Ok(api_res.data)
     } else {
@@ -92,9 +64,7 @@ pub async fn fetch_anime2_ongoing(page: u32) -> Result<(Vec<Anime2OngoingItem>,
     if response.status().is_success() {
         let api_response = response.json::<ApiResponse<Vec<Anime2OngoingItem>>>().await.map_err(|e| e.to_string())?;
         if let Some(data) = api_response.data {
-             let pagination = api_response.pagination.unwrap_or_else(|| Pagination {
-                current_page: 1, last_visible_page: 1, has_next_page: false, next_page: None, has_previous_page: false, previous_page: None
-            });
+             let pagination = api_response.pagination.unwrap_or_else(Pagination::single);
             Ok((data, pagination))
         } else {
             Err("No data returned".to_string())
@@ -104,7 +74,7 @@ pub async fn fetch_anime2_ongoing(page: u32) -> Result<(Vec<Anime2OngoingItem>,
     }
 }
 
-pub async fn fetch_anime1_ongoing(page: u32) -> Result<(Vec<Anime1OngoingItem>, Pagination), String> {
+pub async fn fetch_anime1_ongoing(page: u32) -> Result<(Vec<OngoingAnimeItem>, Pagination), String> {
     let client = Client::new();
     let url = format!("{}/anime/ongoing-anime/{}", API_BASE_URL, page);
 
@@ -122,7 +92,7 @@ pub async fn fetch_anime1_ongoing(page: u32) -> Result<(Vec<Anime1OngoingItem>,
     }
 }
 
-pub async fn fetch_anime2_complete(page: u32) -> Result<(Vec<Anime2CompleteItem>, Pagination), String> {
+pub async fn fetch_anime2_complete(page: u32) -> Result<(Vec<CompleteAnimeItem>, Pagination), String> {
     let client = Client::new();
     let url = format!("{}/anime2/complete-anime/{}", API_BASE_URL, page);
 
@@ -133,11 +103,9 @@ pub async fn fetch_anime2_complete(page: u32) -> Result<(Vec<Anime2CompleteItem>
         .map_err(|e| e.to_string())?;
 
     if response.status().is_success() {
-        let api_response = response.json::<ApiResponse<Vec<Anime2CompleteItem>>>().await.map_err(|e| e.to_string())?;
+        let api_response = response.json::<ApiResponse<Vec<CompleteAnimeItem>>>().await.map_err(|e| e.to_string())?;
          if let Some(data) = api_response.data {
-             let pagination = api_response.pagination.unwrap_or_else(|| Pagination {
-                current_page: 1, last_visible_page: 1, has_next_page: false, next_page: None, has_previous_page: false, previous_page: None
-            });
+             let pagination = api_response.pagination.unwrap_or_else(Pagination::single);
             Ok((data, pagination))
         } else {
              Err("No data returned".to_string())
@@ -147,7 +115,7 @@ pub async fn fetch_anime2_complete(page: u32) -> Result<(Vec<Anime2CompleteItem>
     }
 }
 
-pub async fn fetch_anime1_complete(page: u32) -> Result<(Vec<Anime2CompleteItem>, Pagination), String> {
+pub async fn fetch_anime1_complete(page: u32) -> Result<(Vec<CompleteAnimeItem>, Pagination), String> {
     let client = Client::new();
     let url = format!("{}/anime/complete-anime/{}", API_BASE_URL, page);
 
@@ -159,18 +127,16 @@ pub async fn fetch_anime1_complete(page: u32) -> Result<(Vec<Anime2CompleteItem>
 
     if response.status().is_success() {
         // Source 1 uses ListResponse { message, data, pagination, total }
-        // We reuse Anime2CompleteItem because fields match (title, slug, poster, episode_count, anime_url)
+        // We reuse CompleteAnimeItem because fields match (title, slug, poster, episode_count, anime_url)
         // message field is ignored by deserialize if not present in struct
         #[derive(Deserialize)]
         struct ListRes {
-            data: Vec<Anime2CompleteItem>,
+            data: Vec<CompleteAnimeItem>,
             pagination: Option<Pagination>,
         }
         let api_response = response.json::<ListRes>().await.map_err(|e| e.to_string())?;
         
-        let pagination = api_response.pagination.unwrap_or_else(|| Pagination {
-            current_page: 1, last_visible_page: 1, has_next_page: false, next_page: None, has_previous_page: false, previous_page: None
-        });
+        let pagination = api_response.pagination.unwrap_or_else(Pagination::single);
 
         Ok((api_response.data, pagination))
     } else {
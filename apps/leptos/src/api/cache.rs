@@ -0,0 +1,46 @@
+//! localStorage-backed copies of API responses, shown when the API is unreachable.
+
+use gloo_storage::{LocalStorage, Storage};
+use serde::{de::DeserializeOwned, Serialize};
+
+const KEY_PREFIX: &str = "api-cache:";
+
+/// Store `value` as the last good response for `key`. A no-op when
+/// rendering on the server, which has no localStorage.
+pub fn store<T: Serialize>(key: &str, value: &T) {
+    if cfg!(feature = "ssr") {
+        return;
+    }
+    let _ = LocalStorage::set(format!("{}{}", KEY_PREFIX, key), value);
+}
+
+/// Last good response for `key`, if any; always `None` on the server.
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    if cfg!(feature = "ssr") {
+        return None;
+    }
+    LocalStorage::get(format!("{}{}", KEY_PREFIX, key)).ok()
+}
+
+/// Falls back to `cached` when `fresh` failed; the flag is `true` when the
+/// returned value came from the cache.
+pub fn fallback_to_cached<T>(
+    fresh: Result<T, String>,
+    cached: impl FnOnce() -> Option<T>,
+) -> Result<(T, bool), String> {
+    match fresh {
+        Ok(value) => Ok((value, false)),
+        Err(e) => cached().map(|value| (value, true)).ok_or(e),
+    }
+}
+
+/// Remembers successful responses under `key` and serves the stored copy on failure.
+pub fn with_cache<T: Serialize + DeserializeOwned>(
+    key: &str,
+    fresh: Result<T, String>,
+) -> Result<(T, bool), String> {
+    if let Ok(value) = &fresh {
+        store(key, value);
+    }
+    fallback_to_cached(fresh, || load(key))
+}
@@ -4,6 +4,8 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use leptos::logging;
 
+pub use api_models::komik::{KomikItem, KomikList};
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct MangaItem {
     pub title: String,
@@ -22,7 +24,7 @@ pub struct MangaResponse {
     pub pagination: Pagination,
 }
 
-async fn fetch_komik_type(type_: &str, page: u32) -> Result<MangaResponse, String> {
+async fn fetch_komik_type(type_: &str, page: u32) -> Result<KomikList, String> {
     let client = Client::new();
     // Komik endpoint: /api/komik/{type}?page={page}
     let url = format!("{}/komik/{}?page={}", API_BASE_URL, type_, page);
@@ -34,7 +36,7 @@ async fn fetch_komik_type(type_: &str, page: u32) -> Result<MangaResponse, Strin
         .map_err(|e| e.to_string())?;
 
     if response.status().is_success() {
-        let res = response.json::<MangaResponse>().await.map_err(|e| e.to_string())?;
+        let res = response.json::<KomikList>().await.map_err(|e| e.to_string())?;
         logging::log!("Fetched {} {} items", type_, res.data.len());
         Ok(res)
     } else {
@@ -42,15 +44,15 @@ async fn fetch_komik_type(type_: &str, page: u32) -> Result<MangaResponse, Strin
     }
 }
 
-pub async fn fetch_manga(page: u32) -> Result<MangaResponse, String> {
+pub async fn fetch_manga(page: u32) -> Result<KomikList, String> {
     fetch_komik_type("manga", page).await
 }
 
-pub async fn fetch_manhwa(page: u32) -> Result<MangaResponse, String> {
+pub async fn fetch_manhwa(page: u32) -> Result<KomikList, String> {
     fetch_komik_type("manhwa", page).await
 }
 
-pub async fn fetch_manhua(page: u32) -> Result<MangaResponse, String> {
+pub async fn fetch_manhua(page: u32) -> Result<KomikList, String> {
     fetch_komik_type("manhua", page).await
 }
 
@@ -1,6 +1,9 @@
 pub mod anime;
 pub mod auth;
+pub mod cache;
 pub mod komik;
+pub mod page_cache;
+pub mod preferences;
 pub mod social;
 pub mod types;
 
@@ -0,0 +1,51 @@
+//! In-memory copies of page data, so navigating back to a page in the
+//! browser doesn't fetch it again.
+//!
+//! Only the browser keeps them. Under `ssr` nothing is stored, so one
+//! request's data can't leak into another's render on a shared server
+//! thread; there the data reaches the client as the serialized resource
+//! the page hydrates from instead.
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        /// Page data stored under `key` (never, on the server).
+        pub fn get<T: Clone + 'static>(_key: &str) -> Option<T> {
+            None
+        }
+
+        /// Keeps `value` for the next render of the page (not on the server).
+        pub fn put<T: Clone + 'static>(_key: &str, _value: &T) {}
+    } else {
+        use std::any::Any;
+        use std::cell::RefCell;
+        use std::collections::HashMap;
+
+        thread_local! {
+            static PAGES: RefCell<HashMap<String, Box<dyn Any>>> = RefCell::new(HashMap::new());
+        }
+
+        /// Page data stored under `key`, if it was stored as a `T`.
+        pub fn get<T: Clone + 'static>(key: &str) -> Option<T> {
+            PAGES.with(|pages| pages.borrow().get(key)?.downcast_ref::<T>().cloned())
+        }
+
+        /// Keeps `value` for the next render of the page.
+        pub fn put<T: Clone + 'static>(key: &str, value: &T) {
+            PAGES.with(|pages| pages.borrow_mut().insert(key.to_string(), Box::new(value.clone())));
+        }
+    }
+}
+
+#[cfg(all(test, not(feature = "ssr")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_page_is_returned_for_its_type_only() {
+        assert_eq!(get::<Vec<u8>>("test:page"), None);
+        put("test:page", &vec![1u8, 2]);
+
+        assert_eq!(get::<Vec<u8>>("test:page"), Some(vec![1, 2]));
+        assert_eq!(get::<String>("test:page"), None);
+    }
+}
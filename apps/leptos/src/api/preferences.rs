@@ -0,0 +1,41 @@
+use crate::api::types::{Preferences, UpdatePreferencesRequest};
+use crate::api::API_BASE_URL;
+use reqwest::Client;
+
+pub async fn get_preferences(token: &str) -> Result<Preferences, String> {
+    let client = Client::new();
+    let url = format!("{}/me/preferences", API_BASE_URL);
+
+    let response = client
+        .get(&url)
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        response.json::<Preferences>().await.map_err(|e| e.to_string())
+    } else {
+        Err("Failed to fetch preferences".to_string())
+    }
+}
+
+pub async fn update_preferences(token: &str, theme: &str) -> Result<Preferences, String> {
+    let client = Client::new();
+    let url = format!("{}/me/preferences", API_BASE_URL);
+    let request = UpdatePreferencesRequest { theme: theme.to_string() };
+
+    let response = client
+        .put(&url)
+        .bearer_auth(token)
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        response.json::<Preferences>().await.map_err(|e| e.to_string())
+    } else {
+        Err("Failed to save preferences".to_string())
+    }
+}
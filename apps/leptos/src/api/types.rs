@@ -27,16 +27,22 @@ pub struct LoginResponse {
     pub expires_in: i64,
 }
 
+/// The signed-in user's saved preferences (`/api/me/preferences`).
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct Pagination {
-    pub current_page: u32,
-    pub last_visible_page: u32,
-    pub has_next_page: bool,
-    pub next_page: Option<u32>,
-    pub has_previous_page: bool,
-    pub previous_page: Option<u32>,
+pub struct Preferences {
+    /// `light`, `dark` or `system`.
+    pub theme: String,
+    /// `None` if the user never saved preferences.
+    pub updated_at: Option<String>,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UpdatePreferencesRequest {
+    pub theme: String,
+}
+
+pub use api_models::Pagination;
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ApiResponse<T> {
     #[serde(default)]
@@ -48,6 +54,23 @@ pub struct ApiResponse<T> {
     pub meta: Option<serde_json::Value>,
 }
 
+/// Why a page resource has nothing to show.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, thiserror::Error)]
+pub enum LoadError {
+    /// The API couldn't be reached or answered with an error.
+    #[error("{0}")]
+    Network(String),
+    /// The API answered, but with no items.
+    #[error("No titles available right now")]
+    Empty,
+}
+
+impl LoadError {
+    pub fn is_network(&self) -> bool {
+        matches!(self, LoadError::Network(_))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ApiError {
     pub message: String,
@@ -14,6 +14,7 @@ use crate::components::layout::ClientLayout;
 use crate::components::ui::{ErrorFallback, PageTransition};
 
 use crate::pages::anime::AnimePage;
+use crate::pages::anime::list::{AnimeListPage, ListKind};
 use crate::pages::komik::KomikPage;
 
 #[component]
@@ -41,12 +42,17 @@ pub fn App() -> impl IntoView {
                             <Route path="anime/watch/:slug" view=crate::pages::anime::watch::WatchPage/>
                             <Route path="anime2/detail/:slug" view=crate::pages::anime::detail::AnimeDetailPage/>
                             <Route path="anime2/watch/:slug" view=crate::pages::anime::watch::WatchPage/>
+                            <Route path="anime/ongoing-anime/:page" view=|| view! { <AnimeListPage source=1 kind=ListKind::Ongoing/> }/>
+                            <Route path="anime/complete-anime/:page" view=|| view! { <AnimeListPage source=1 kind=ListKind::Complete/> }/>
+                            <Route path="anime2/ongoing-anime/:page" view=|| view! { <AnimeListPage source=2 kind=ListKind::Ongoing/> }/>
+                            <Route path="anime2/complete-anime/:page" view=|| view! { <AnimeListPage source=2 kind=ListKind::Complete/> }/>
                             <Route path="anime/search" view=crate::pages::anime::search::AnimeSearchPage/>
                             <Route path="anime2/search" view=crate::pages::anime::search::AnimeSearchPage/>
                             <Route path="komik" view=KomikPage/>
                             <Route path="komik/detail" view=crate::pages::komik::detail::KomikDetailPage/>
                             <Route path="komik/read/:slug" view=crate::pages::komik::read::ReadPage/>
                             <Route path="komik/search" view=crate::pages::komik::search::KomikSearchPage/>
+                            <Route path="komik/:kind/page/:page" view=crate::pages::komik::list::KomikListPage/>
                             <Route path="/*any" view=NotFound/>
                         </Routes>
                     </PageTransition>
@@ -69,7 +75,7 @@ fn NotFound() -> impl IntoView {
         // this can be done inline because it's synchronous
         // if it were async, we'd use a server function
         let resp = expect_context::<leptos_axum::ResponseOptions>();
-        resp.set_status(StatusCode::NOT_FOUND);
+        resp.set_status(http::StatusCode::NOT_FOUND);
     }
 
     view! {
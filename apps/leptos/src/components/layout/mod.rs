@@ -1,13 +1,15 @@
 use leptos::*;
 use crate::components::navbar::Navbar;
 use crate::components::ui::navigation_progress::NavigationProgress;
-use crate::providers::{provide_theme, provide_auth};
+use crate::components::ui::OfflineBanner;
+use crate::providers::{provide_theme, provide_auth, provide_online};
 
 #[component]
 pub fn ClientLayout(children: Children) -> impl IntoView {
     // Provide contexts at the layout level
     provide_theme();
     provide_auth();
+    provide_online();
 
     view! {
         <div class="min-h-screen flex flex-col relative overflow-x-hidden bg-background text-foreground transition-colors duration-700">
@@ -28,6 +30,7 @@ pub fn ClientLayout(children: Children) -> impl IntoView {
 
             <NavigationProgress/>
             <Navbar/>
+            <OfflineBanner/>
             
             <main class="relative z-10 flex-1 flex flex-col max-w-[100vw]">
                 {children()}
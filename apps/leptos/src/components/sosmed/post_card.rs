@@ -174,7 +174,7 @@ pub fn PostCard(
                     >
                         <path stroke-linecap="round" stroke-linejoin="round" d="M4.318 6.318a4.5 4.5 0 000 6.364L12 20.364l7.682-7.682a4.5 4.5 0 00-6.364-6.364L12 7.636l-1.318-1.318a4.5 4.5 0 00-6.364 0z" />
                     </svg>
-                    <span class="text-[10px] font-black uppercase tracking-widest relative z-10">{move || post.get().likes.len()}</span>
+                    <span class="text-[10px] font-black uppercase tracking-widest relative z-10">{move || post.get().like_count}</span>
                 </button>
 
                 <button
@@ -184,7 +184,7 @@ pub fn PostCard(
                     <svg class="w-5 h-5" fill="none" stroke="currentColor" stroke-width="2.5" viewBox="0 0 24 24">
                         <path stroke-linecap="round" stroke-linejoin="round" d="M8 12h.01M12 12h.01M16 12h.01M21 12c0 4.418-4.03 8-9 8a9.863 9.863 0 01-4.255-.949L3 20l1.395-3.72C3.512 15.042 3 13.574 3 12c0-4.418 4.03-8 9-8s9 3.582 9 8z" />
                     </svg>
-                    <span class="text-[10px] font-black uppercase tracking-widest">{move || post.get().comments.len()}</span>
+                    <span class="text-[10px] font-black uppercase tracking-widest">{move || post.get().comment_count}</span>
                 </button>
             </div>
 
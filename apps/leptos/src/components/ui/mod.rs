@@ -4,9 +4,17 @@ pub mod error_fallback;
 pub mod page_transition;
 pub mod loading_overlay;
 pub mod glitch_text;
+pub mod offline_banner;
+pub mod pagination;
+pub mod proxy_image;
+pub mod retry_card;
 
 // Re-exports for ergonomic imports (allow `components::ui::ErrorFallback`)
 pub use error_fallback::ErrorFallback;
 pub use page_transition::PageTransition;
 pub use loading_overlay::LoadingOverlay;
 pub use glitch_text::GlitchText;
+pub use offline_banner::OfflineBanner;
+pub use pagination::Pagination;
+pub use proxy_image::ProxyImage;
+pub use retry_card::RetryCard;
@@ -0,0 +1,20 @@
+use leptos::*;
+use crate::providers::use_offline;
+
+/// Thin strip under the navbar shown while the API is unreachable.
+#[component]
+pub fn OfflineBanner() -> impl IntoView {
+    let offline = use_offline();
+
+    view! {
+        <Show when=move || offline.get()>
+            <div
+                role="status"
+                class="sticky top-16 z-40 mx-auto mt-2 flex items-center gap-3 px-4 py-2 rounded-full glass border border-amber-500/30 text-amber-500 text-xs font-bold uppercase tracking-widest shadow-lg animate-fade-in"
+            >
+                <div class="w-2 h-2 rounded-full bg-amber-500 animate-pulse" />
+                "Offline mode — showing cached data"
+            </div>
+        </Show>
+    }
+}
@@ -0,0 +1,92 @@
+use leptos::*;
+use leptos_router::A;
+use crate::api::types::Pagination as PageInfo;
+
+/// Page numbers shown around `current`: the first and last page, up to two
+/// on each side of `current`, and `None` where a run of pages is skipped.
+pub fn page_window(current: u32, last: u32) -> Vec<Option<u32>> {
+    let last = last.max(current).max(1);
+    let from = current.saturating_sub(2).max(1);
+    let to = (current + 2).min(last);
+
+    let mut pages = Vec::new();
+    if from > 1 {
+        pages.push(Some(1));
+        if from > 2 {
+            pages.push(None);
+        }
+    }
+    pages.extend((from..=to).map(Some));
+    if to < last {
+        if to + 1 < last {
+            pages.push(None);
+        }
+        pages.push(Some(last));
+    }
+    pages
+}
+
+/// Prev / page numbers / next for a paged API listing. Each control links to
+/// `href(page)`, so clicking updates the route and the page's resource.
+#[component]
+pub fn Pagination(
+    pagination: PageInfo,
+    /// Route of page `n` of the listing.
+    #[prop(into)] href: Callback<u32, String>,
+) -> impl IntoView {
+    let current = pagination.current_page;
+    let control = "min-w-12 px-5 py-3 rounded-2xl glass border border-white/10 text-xs font-black uppercase tracking-widest text-center transition-all";
+    let enabled = "hover:border-white/40 hover:-translate-y-0.5";
+    let disabled = "opacity-30 cursor-not-allowed";
+
+    let step = move |target: Option<u32>, label: &'static str| match target {
+        Some(page) => view! {
+            <A href=href.call(page) class=format!("{} {}", control, enabled)>{label}</A>
+        }
+        .into_view(),
+        None => view! {
+            <span aria-disabled="true" class=format!("{} {}", control, disabled)>{label}</span>
+        }
+        .into_view(),
+    };
+
+    view! {
+        <nav aria-label="Pagination" class="flex flex-wrap items-center justify-center gap-3">
+            {step(pagination.previous_page.filter(|_| pagination.has_previous_page), "← Prev")}
+            {page_window(current, pagination.last_visible_page)
+                .into_iter()
+                .map(|page| match page {
+                    Some(page) if page == current => view! {
+                        <span aria-current="page" class=format!("{} bg-foreground text-background", control)>
+                            {page}
+                        </span>
+                    }
+                    .into_view(),
+                    Some(page) => view! {
+                        <A href=href.call(page) class=format!("{} {}", control, enabled)>{page}</A>
+                    }
+                    .into_view(),
+                    None => view! { <span class="px-2 text-muted-foreground">"…"</span> }.into_view(),
+                })
+                .collect_view()}
+            {step(pagination.next_page.filter(|_| pagination.has_next_page), "Next →")}
+        </nav>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_skips_distant_pages() {
+        assert_eq!(page_window(1, 1), vec![Some(1)]);
+        assert_eq!(page_window(1, 3), vec![Some(1), Some(2), Some(3)]);
+        assert_eq!(
+            page_window(6, 20),
+            vec![Some(1), None, Some(4), Some(5), Some(6), Some(7), Some(8), None, Some(20)]
+        );
+        // No ellipsis for a gap of one page.
+        assert_eq!(page_window(4, 6), vec![Some(1), Some(2), Some(3), Some(4), Some(5), Some(6)]);
+    }
+}
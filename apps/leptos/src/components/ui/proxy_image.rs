@@ -0,0 +1,125 @@
+use leptos::*;
+use crate::api::API_BASE_URL;
+
+/// `src` routed through the API's `/imageproxy`, which fetches it with the
+/// source's referer so hotlink-protected posters still load. Local, inline,
+/// already proxied and [`serves_directly`] URLs are returned unchanged.
+pub fn proxied_src(src: &str) -> String {
+    let proxy = format!("{}/imageproxy", API_BASE_URL);
+    let remote = src.starts_with("http://") || src.starts_with("https://");
+    if !remote || src.starts_with(&proxy) || serves_directly(src) {
+        return src.to_string();
+    }
+    format!("{}?url={}", proxy, urlencoding::encode(src))
+}
+
+/// Whether `src` is on one of the image CDNs the API caches posters to.
+/// They don't check referers, and the proxy's host allowlist refuses them.
+fn serves_directly(src: &str) -> bool {
+    let host = src
+        .split_once("://")
+        .map_or(src, |(_, rest)| rest)
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default();
+    let host = host.rsplit('@').next().unwrap_or_default();
+    let host = host.split(':').next().unwrap_or_default().to_ascii_lowercase();
+    host == "picser.asepharyana.tech"
+        || host == "cdn.jsdelivr.net"
+        || (host.starts_with("picser")
+            && [".vercel.app", ".pages.dev", ".leapcell.dev"]
+                .iter()
+                .any(|suffix| host.ends_with(suffix)))
+}
+
+/// A remote poster loaded through [`proxied_src`]: lazily, behind a blurred
+/// placeholder that clears once it loads, and replaced by a placeholder
+/// tile if it still fails.
+#[component]
+pub fn ProxyImage(
+    src: String,
+    alt: String,
+    /// Classes of the `<img>`, e.g. sizing and hover transforms.
+    #[prop(optional, into)] class: String,
+) -> impl IntoView {
+    let (loaded, set_loaded) = create_signal(false);
+    let (failed, set_failed) = create_signal(false);
+    let src = proxied_src(&src);
+    let placeholder_alt = alt.clone();
+
+    view! {
+        <Show when=move || !loaded.get() && !failed.get()>
+            <div class="absolute inset-0 bg-gradient-to-br from-white/10 to-white/5 blur-xl animate-pulse" />
+        </Show>
+        <Show
+            when=move || !failed.get()
+            fallback=move || view! {
+                <div
+                    role="img"
+                    aria-label=placeholder_alt.clone()
+                    class="absolute inset-0 flex items-center justify-center bg-muted text-4xl text-muted-foreground"
+                >
+                    "🖼"
+                </div>
+            }
+        >
+            <img
+                src=src.clone()
+                alt=alt.clone()
+                class={
+                    let class = class.clone();
+                    move || format!(
+                        "{} transition-[opacity,filter] duration-700 {}",
+                        class,
+                        if loaded.get() { "opacity-100 blur-0" } else { "opacity-0 blur-md" }
+                    )
+                }
+                loading="lazy"
+                decoding="async"
+                on:load=move |_| set_loaded.set(true)
+                on:error=move |_| set_failed.set(true)
+            />
+        </Show>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remote_posters_go_through_the_proxy() {
+        let src = "https://komikcast.test/wp-content/poster 1.jpg?w=300";
+        assert_eq!(
+            proxied_src(src),
+            format!(
+                "{}/imageproxy?url=https%3A%2F%2Fkomikcast.test%2Fwp-content%2Fposter%201.jpg%3Fw%3D300",
+                API_BASE_URL
+            )
+        );
+        assert_eq!(proxied_src(&proxied_src(src)), proxied_src(src));
+    }
+
+    #[test]
+    fn picser_cdn_images_are_left_alone() {
+        for src in [
+            "https://picser.asepharyana.tech/uploads/poster.jpg",
+            "https://picser-two.vercel.app/uploads/poster.jpg",
+            "https://picser.pages.dev/uploads/poster.jpg?w=300",
+            "https://picser-mytheclipse8647-ahoqi9ef.leapcell.dev/uploads/poster.jpg",
+            "https://cdn.jsdelivr.net/gh/sh20raj/picser@main/uploads/poster.jpg",
+        ] {
+            assert_eq!(proxied_src(src), src);
+        }
+        // Only picser deployments on those hosts skip the proxy.
+        let other = "https://someone-else.vercel.app/poster.jpg";
+        assert_ne!(proxied_src(other), other);
+    }
+
+    #[test]
+    fn local_and_inline_images_are_left_alone() {
+        assert_eq!(proxied_src("/public/project-rust.png"), "/public/project-rust.png");
+        assert_eq!(proxied_src("data:image/png;base64,AA=="), "data:image/png;base64,AA==");
+        assert_eq!(proxied_src(""), "");
+    }
+}
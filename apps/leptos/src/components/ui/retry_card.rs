@@ -0,0 +1,53 @@
+use leptos::*;
+use crate::api::types::LoadError;
+
+/// Fallback for a page section's `ErrorBoundary`: says whether the API was
+/// unreachable or simply returned nothing, and offers a retry that
+/// re-runs the section's resource instead of reloading the page.
+#[component]
+pub fn RetryCard(
+    errors: RwSignal<Errors>,
+    /// What failed to load, e.g. "anime database".
+    what: &'static str,
+    #[prop(into)] on_retry: Callback<()>,
+) -> impl IntoView {
+    let network = move || {
+        errors.with(|errors| {
+            errors
+                .iter()
+                .any(|(_, e)| e.downcast_ref::<LoadError>().map_or(true, LoadError::is_network))
+        })
+    };
+    let detail = move || {
+        errors.with(|errors| errors.iter().next().map(|(_, e)| e.to_string()).unwrap_or_default())
+    };
+
+    view! {
+        <div class=move || if network() {
+            "glass-card p-12 rounded-[2rem] text-center border border-red-500/20"
+        } else {
+            "glass-card p-12 rounded-[2rem] text-center border border-white/10"
+        }>
+            <div class="text-4xl mb-4">{move || if network() { "❌" } else { "📭" }}</div>
+            <h3 class="text-xl font-black uppercase italic">
+                {move || if network() { "Connection Error" } else { "Nothing Here Yet" }}
+            </h3>
+            <p class="text-muted-foreground">
+                {move || if network() {
+                    format!("Unable to load {}. The source may be briefly down.", what)
+                } else {
+                    format!("The {} came back empty.", what)
+                }}
+            </p>
+            <Show when=network>
+                <p class="mt-2 text-xs font-mono text-red-500/70">{detail}</p>
+            </Show>
+            <button
+                on:click=move |_| on_retry.call(())
+                class="mt-8 px-10 py-4 rounded-[2rem] bg-foreground text-background font-black uppercase tracking-widest hover:scale-95 transition-transform"
+            >
+                "Retry"
+            </button>
+        </div>
+    }
+}
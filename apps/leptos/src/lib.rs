@@ -7,3 +7,15 @@ pub mod types;
 pub mod app;
 
 pub use crate::app::App as AppRoot;
+
+/// Entry point of the WASM bundle when the page was rendered by the `ssr`
+/// server: picks up the server's HTML and serialized resources instead of
+/// rendering and fetching again.
+#[cfg(feature = "hydrate")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn hydrate() {
+    _ = console_log::init_with_level(log::Level::Debug);
+    console_error_panic_hook::set_once();
+
+    leptos::mount_to_body(AppRoot);
+}
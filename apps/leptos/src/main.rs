@@ -1,6 +1,35 @@
 use apps_leptos::AppRoot;
 use leptos::*;
 
+/// Server-side rendering: every route is rendered with its resources
+/// resolved, so the initial HTML already carries the page data.
+#[cfg(feature = "ssr")]
+#[tokio::main]
+async fn main() {
+    use axum::Router;
+    use leptos_axum::{generate_route_list, LeptosRoutes};
+    use tower_http::services::ServeDir;
+
+    let conf = get_configuration(None).await.expect("Invalid Leptos configuration");
+    let leptos_options = conf.leptos_options;
+    let addr = leptos_options.site_addr;
+    let routes = generate_route_list(AppRoot);
+
+    let app = Router::new()
+        .leptos_routes(&leptos_options, routes, AppRoot)
+        .fallback_service(ServeDir::new(leptos_options.site_root.clone()))
+        .with_state(leptos_options);
+
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .expect("Failed to bind the site address");
+    log::info!("listening on http://{}", addr);
+    axum::serve(listener, app.into_make_service())
+        .await
+        .expect("Server error");
+}
+
+#[cfg(not(feature = "ssr"))]
 fn main() {
     // set up logging
     _ = console_log::init_with_level(log::Level::Debug);
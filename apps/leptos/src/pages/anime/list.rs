@@ -0,0 +1,177 @@
+use leptos::*;
+use leptos_meta::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+use crate::api::anime::{
+    fetch_anime1_complete, fetch_anime1_ongoing, fetch_anime2_complete, fetch_anime2_ongoing,
+};
+use crate::api::types::{LoadError, Pagination as PageInfo};
+use crate::components::ui::{Pagination, RetryCard};
+use super::AnimeCard;
+
+/// Which catalog a list page walks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListKind {
+    Ongoing,
+    Complete,
+}
+
+impl ListKind {
+    /// Path segment of the list, as in `/anime/ongoing-anime/2`.
+    pub fn path(self) -> &'static str {
+        match self {
+            ListKind::Ongoing => "ongoing-anime",
+            ListKind::Complete => "complete-anime",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            ListKind::Ongoing => "Ongoing",
+            ListKind::Complete => "Complete",
+        }
+    }
+}
+
+/// One card of a list page, whichever source it came from.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct ListEntry {
+    title: String,
+    slug: String,
+    poster: String,
+    current_episode: Option<String>,
+    episode_count: Option<String>,
+}
+
+async fn fetch_list(
+    source: u8,
+    kind: ListKind,
+    page: u32,
+) -> Result<(Vec<ListEntry>, PageInfo), LoadError> {
+    let (entries, pagination): (Vec<ListEntry>, PageInfo) = match (source, kind) {
+        (2, ListKind::Ongoing) => {
+            let (items, pagination) = fetch_anime2_ongoing(page).await.map_err(LoadError::Network)?;
+            let entries = items
+                .into_iter()
+                .map(|item| ListEntry {
+                    title: item.title,
+                    slug: item.slug,
+                    poster: item.poster,
+                    current_episode: None,
+                    episode_count: None,
+                })
+                .collect();
+            (entries, pagination)
+        }
+        (_, ListKind::Ongoing) => {
+            let (items, pagination) = fetch_anime1_ongoing(page).await.map_err(LoadError::Network)?;
+            let entries = items
+                .into_iter()
+                .map(|item| ListEntry {
+                    title: item.title,
+                    slug: item.slug,
+                    poster: item.poster,
+                    current_episode: Some(item.current_episode),
+                    episode_count: None,
+                })
+                .collect();
+            (entries, pagination)
+        }
+        (_, ListKind::Complete) => {
+            let fetched = if source == 2 {
+                fetch_anime2_complete(page).await
+            } else {
+                fetch_anime1_complete(page).await
+            };
+            let (items, pagination) = fetched.map_err(LoadError::Network)?;
+            let entries = items
+                .into_iter()
+                .map(|item| ListEntry {
+                    title: item.title,
+                    slug: item.slug,
+                    poster: item.poster,
+                    current_episode: None,
+                    episode_count: Some(item.episode_count),
+                })
+                .collect();
+            (entries, pagination)
+        }
+    };
+    if entries.is_empty() {
+        return Err(LoadError::Empty);
+    }
+    Ok((entries, pagination))
+}
+
+/// `/anime{,2}/{ongoing,complete}-anime/:page`: one page of a catalog with
+/// controls to move through the rest.
+#[component]
+pub fn AnimeListPage(#[prop(default = 1)] source: u8, kind: ListKind) -> impl IntoView {
+    let params = use_params_map();
+    let page = move || {
+        params.with(|p| p.get("page").and_then(|p| p.parse::<u32>().ok()).unwrap_or(1).max(1))
+    };
+    // Bumped by the retry button to run the resource again.
+    let (attempt, set_attempt) = create_signal(0u32);
+    let data = create_resource(
+        move || (page(), attempt.get()),
+        move |(page, _)| fetch_list(source, kind, page),
+    );
+
+    let prefix = if source == 2 { "anime2" } else { "anime" };
+    let href = move |n: u32| format!("/{}/{}/{}", prefix, kind.path(), n);
+
+    view! {
+        <Title text=move || format!("{} Anime · Page {} | Media Hub", kind.title(), page())/>
+        <main class="min-h-screen py-24 px-6 md:px-12 relative overflow-hidden">
+            <div class="max-w-7xl mx-auto space-y-16">
+                <header class="flex flex-col md:flex-row md:items-end justify-between gap-6 animate-fade-in">
+                    <div class="space-y-4">
+                        <a href=format!("/{}", prefix) class="text-[10px] font-black uppercase tracking-[0.2em] text-blue-500 hover:underline">
+                            "← Anime Hub"
+                        </a>
+                        <h1 class="text-5xl md:text-7xl font-black tracking-tighter uppercase italic">
+                            {kind.title()}
+                        </h1>
+                    </div>
+                    <span class="text-xs font-black uppercase tracking-widest text-muted-foreground">
+                        {move || format!("Page {}", page())}
+                    </span>
+                </header>
+
+                <Suspense fallback=move || view! {
+                    <div class="grid grid-cols-2 md:grid-cols-4 lg:grid-cols-6 gap-8">
+                        {(0..12).map(|_| view! { <div class="aspect-[3/4.2] rounded-[2rem] bg-white/5 animate-pulse" /> }).collect_view()}
+                    </div>
+                }>
+                    <ErrorBoundary fallback=move |errors| view! {
+                        <RetryCard
+                            errors=errors
+                            what="this page"
+                            on_retry=move |_| set_attempt.update(|n| *n += 1)
+                        />
+                    }>
+                        {move || data.get().map(|result| result.map(|(entries, pagination)| view! {
+                            <div class="space-y-16">
+                                <div class="grid grid-cols-2 md:grid-cols-3 lg:grid-cols-4 xl:grid-cols-5 2xl:grid-cols-6 gap-8">
+                                    {entries.into_iter().enumerate().map(|(i, entry)| view! {
+                                        <AnimeCard
+                                            title=entry.title
+                                            slug=entry.slug
+                                            poster=entry.poster
+                                            current_episode=entry.current_episode
+                                            episode_count=entry.episode_count
+                                            index=i
+                                            source=source
+                                        />
+                                    }).collect_view()}
+                                </div>
+                                <Pagination pagination=pagination href=href/>
+                            </div>
+                        }))}
+                    </ErrorBoundary>
+                </Suspense>
+            </div>
+        </main>
+    }
+}
@@ -1,105 +1,56 @@
 pub mod detail;
+pub mod list;
 pub mod search;
 pub mod watch;
 use leptos::*;
 use leptos_meta::*;
-use serde::{Serialize, Deserialize};
 use crate::api::anime::{
-    fetch_anime1_index, fetch_anime2_index
+    fetch_anime1_index, fetch_anime2_index, CompleteAnimeItem, HomeData, OngoingAnimeItem
 };
+use crate::api::cache::with_cache;
+use crate::api::page_cache;
+use crate::api::types::LoadError;
+use crate::components::ui::{ProxyImage, RetryCard};
+use crate::providers::OnlineContext;
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct AnimeItem {
-    pub title: String,
-    pub slug: String,
-    pub poster: String,
-    pub current_episode: Option<String>,
-    pub episode_count: Option<String>,
-    pub score: Option<String>,
-}
-
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct HomeData {
-    pub ongoing_anime: Vec<AnimeItem>,
-    pub complete_anime: Vec<AnimeItem>,
-}
-
-thread_local! {
-    static ANIME1_CACHE: std::cell::RefCell<Option<HomeData>> = std::cell::RefCell::new(None);
-    static ANIME2_CACHE: std::cell::RefCell<Option<HomeData>> = std::cell::RefCell::new(None);
-}
-
-async fn fetch_anime_data(source: u8) -> Option<HomeData> {
-    #[cfg(feature = "csr")]
-    {
-        let cached = if source == 2 {
-            ANIME2_CACHE.with(|cache| cache.borrow().clone())
-        } else {
-            ANIME1_CACHE.with(|cache| cache.borrow().clone())
-        };
-        if cached.is_some() {
-            return cached;
-        }
+async fn fetch_anime_data(source: u8, online: Option<OnlineContext>) -> Result<HomeData, LoadError> {
+    let key = if source == 2 { "anime2:index" } else { "anime1:index" };
+    if let Some(cached) = page_cache::get::<HomeData>(key) {
+        return Ok(cached);
     }
 
-    if source == 2 {
-        let data = fetch_anime2_index().await.ok()?;
-        let mapped = HomeData {
-            ongoing_anime: data.ongoing_anime.into_iter().map(|item| AnimeItem {
-                title: item.title,
-                slug: item.slug,
-                poster: item.poster,
-                current_episode: Some(item.current_episode),
-                episode_count: None,
-                score: None,
-            }).collect(),
-            complete_anime: data.complete_anime.into_iter().map(|item| AnimeItem {
-                title: item.title,
-                slug: item.slug,
-                poster: item.poster,
-                current_episode: None,
-                episode_count: Some(item.episode_count),
-                score: None,
-            }).collect(),
-        };
-        #[cfg(feature = "csr")]
-        ANIME2_CACHE.with(|cache| *cache.borrow_mut() = Some(mapped.clone()));
-        Some(mapped)
+    let fresh = if source == 2 {
+        fetch_anime2_index().await
     } else {
-        let data = fetch_anime1_index().await.ok()?;
-        let mapped = HomeData {
-            ongoing_anime: data.ongoing_anime.into_iter().map(|item| AnimeItem {
-                title: item.title,
-                slug: item.slug,
-                poster: item.poster,
-                current_episode: Some(item.current_episode),
-                episode_count: None,
-                score: None,
-            }).collect(),
-            complete_anime: data.complete_anime.into_iter().map(|item| AnimeItem {
-                title: item.title,
-                slug: item.slug,
-                poster: item.poster,
-                current_episode: None,
-                episode_count: Some(item.episode_count),
-                score: None,
-            }).collect(),
-        };
-        #[cfg(feature = "csr")]
-        ANIME1_CACHE.with(|cache| *cache.borrow_mut() = Some(mapped.clone()));
-        Some(mapped)
+        fetch_anime1_index().await
+    };
+    if let Some(online) = online {
+        online.report_fetch(fresh.is_ok());
+    }
+    let (data, _stale) = with_cache(key, fresh).map_err(LoadError::Network)?;
+    if data.ongoing_anime.is_empty() && data.complete_anime.is_empty() {
+        return Err(LoadError::Empty);
     }
+
+    page_cache::put(key, &data);
+    Ok(data)
 }
 
 #[component]
-fn AnimeCard(item: AnimeItem, index: usize, source: u8) -> impl IntoView {
+pub(crate) fn AnimeCard(
+    title: String,
+    slug: String,
+    poster: String,
+    current_episode: Option<String>,
+    episode_count: Option<String>,
+    index: usize,
+    source: u8,
+) -> impl IntoView {
     let delay_style = format!("animation-delay: {}ms", index * 50);
-    let has_episode = item.current_episode.is_some();
-    let current_episode_text = item.current_episode.clone();
-    let has_score = item.score.is_some();
-    let score_text = item.score.clone();
-    let has_count = item.episode_count.is_some();
-    let count_text = item.episode_count.clone();
+    let has_episode = current_episode.is_some();
+    let current_episode_text = current_episode.clone();
+    let has_count = episode_count.is_some();
+    let count_text = episode_count.clone();
 
     let prefix = if source == 2 { "anime2" } else { "anime" };
 
@@ -109,16 +60,15 @@ fn AnimeCard(item: AnimeItem, index: usize, source: u8) -> impl IntoView {
             style=delay_style
         >
              <a
-                href=format!("/{}/detail/{}", prefix, item.slug)
+                href=format!("/{}/detail/{}", prefix, slug)
                 class="block relative group/card perspective-1000"
             >
                 <div class="relative aspect-[3/4.2] rounded-[2rem] overflow-hidden bg-muted border border-white/5 shadow-2xl transition-all duration-700 hover-tilt group-hover:shadow-blue-500/20 group-hover:border-white/20">
                     // Poster with parallax-like zoom
-                    <img
-                        src=item.poster
-                        alt=item.title.clone()
+                    <ProxyImage
+                        src=poster
+                        alt=title.clone()
                         class="w-full h-full object-cover transition-transform duration-1000 ease-out group-hover:scale-115"
-                        loading="lazy"
                     />
                     
                     // Glassy Overlay for Info
@@ -126,11 +76,6 @@ fn AnimeCard(item: AnimeItem, index: usize, source: u8) -> impl IntoView {
                     
                     // Top Badges
                     <div class="absolute top-4 left-4 right-4 flex justify-between items-start pointer-events-none">
-                        <Show when=move || has_score>
-                            <div class="glass-subtle px-3 py-1.5 rounded-xl border border-white/20 text-xs font-black text-yellow-500 flex items-center gap-1.5 shadow-2xl">
-                                "⭐" {score_text.clone()}
-                            </div>
-                        </Show>
                         <Show when=move || has_count>
                             <div class="glass-subtle px-3 py-1.5 rounded-xl border border-white/20 text-[10px] font-black uppercase tracking-widest text-white/90 shadow-2xl">
                                 {count_text.clone()} " EPS"
@@ -148,7 +93,7 @@ fn AnimeCard(item: AnimeItem, index: usize, source: u8) -> impl IntoView {
                         </Show>
                         
                         <h3 class="text-lg font-black text-white leading-tight line-clamp-2 [text-shadow:0_4px_12px_rgba(0,0,0,0.5)] group-hover:text-blue-200 transition-colors">
-                            {item.title}
+                            {title}
                         </h3>
                     </div>
 
@@ -164,10 +109,39 @@ fn AnimeCard(item: AnimeItem, index: usize, source: u8) -> impl IntoView {
 }
 
 #[component]
-fn AnimeGrid(items: Vec<AnimeItem>, source: u8) -> impl IntoView {
+fn OngoingGrid(items: Vec<OngoingAnimeItem>, source: u8) -> impl IntoView {
+    view! {
+        <div class="grid grid-cols-2 md:grid-cols-3 lg:grid-cols-4 xl:grid-cols-5 2xl:grid-cols-6 gap-8">
+            {items.into_iter().enumerate().map(|(i, item)| view! {
+                <AnimeCard
+                    title=item.title
+                    slug=item.slug
+                    poster=item.poster
+                    current_episode=Some(item.current_episode)
+                    episode_count=None
+                    index=i
+                    source=source
+                />
+            }).collect_view()}
+        </div>
+    }
+}
+
+#[component]
+fn CompleteGrid(items: Vec<CompleteAnimeItem>, source: u8) -> impl IntoView {
     view! {
         <div class="grid grid-cols-2 md:grid-cols-3 lg:grid-cols-4 xl:grid-cols-5 2xl:grid-cols-6 gap-8">
-            {items.into_iter().enumerate().map(|(i, item)| view! { <AnimeCard item=item index=i source=source/> }).collect_view()}
+            {items.into_iter().enumerate().map(|(i, item)| view! {
+                <AnimeCard
+                    title=item.title
+                    slug=item.slug
+                    poster=item.poster
+                    current_episode=None
+                    episode_count=Some(item.episode_count)
+                    index=i
+                    source=source
+                />
+            }).collect_view()}
         </div>
     }
 }
@@ -211,7 +185,13 @@ fn SectionHeader(
 
 #[component]
 pub fn AnimePage(#[prop(default = 1)] source: u8) -> impl IntoView {
-    let data = create_resource(move || source, |s| fetch_anime_data(s));
+    let online = use_context::<OnlineContext>();
+    // Bumped by the retry button to run the resource again.
+    let (attempt, set_attempt) = create_signal(0u32);
+    let data = create_resource(
+        move || (source, attempt.get()),
+        move |(s, _)| fetch_anime_data(s, online),
+    );
     let source_title = if source == 2 { "Source 2" } else { "Source 1" };
 
     view! {
@@ -258,15 +238,14 @@ pub fn AnimePage(#[prop(default = 1)] source: u8) -> impl IntoView {
                         {(0..12).map(|_| view! { <div class="aspect-[3/4.2] rounded-[2rem] bg-white/5 animate-pulse" /> }).collect_view()}
                     </div>
                 }>
-                    <Show when=move || data.get().flatten().is_some() fallback=move || view! { 
-                        <div class="glass-card p-12 rounded-[2rem] text-center border border-red-500/20">
-                            <div class="text-4xl mb-4">"❌"</div>
-                            <h3 class="text-xl font-black uppercase italic">"Connection Error"</h3>
-                            <p class="text-muted-foreground">"Unable to load anime database. Please try again later."</p>
-                        </div>
+                    <ErrorBoundary fallback=move |errors| view! {
+                        <RetryCard
+                            errors=errors
+                            what="anime database"
+                            on_retry=move |_| set_attempt.update(|n| *n += 1)
+                        />
                     }>
-                        {move || {
-                            let d = data.get().flatten().unwrap();
+                        {move || data.get().map(|result| result.map(|d| {
                             let prefix = if source == 2 { "anime2" } else { "anime" };
                             view! {
                                 <div class="space-y-32">
@@ -278,7 +257,7 @@ pub fn AnimePage(#[prop(default = 1)] source: u8) -> impl IntoView {
                                             link=format!("/{}/ongoing-anime/1", prefix)
                                             link_gradient="from-blue-500 to-indigo-500"
                                         />
-                                        <AnimeGrid items=d.ongoing_anime source=source/>
+                                        <OngoingGrid items=d.ongoing_anime source=source/>
                                     </section>
 
                                     <section>
@@ -289,12 +268,12 @@ pub fn AnimePage(#[prop(default = 1)] source: u8) -> impl IntoView {
                                             link=format!("/{}/complete-anime/1", prefix)
                                             link_gradient="from-purple-500 to-pink-500"
                                         />
-                                        <AnimeGrid items=d.complete_anime source=source/>
+                                        <CompleteGrid items=d.complete_anime source=source/>
                                     </section>
                                 </div>
                             }
-                        }}
-                    </Show>
+                        }))}
+                    </ErrorBoundary>
                 </Suspense>
             </div>
         </main>
@@ -0,0 +1,93 @@
+use leptos::*;
+use leptos_meta::*;
+use leptos_router::*;
+use crate::api::komik::{fetch_manga, fetch_manhua, fetch_manhwa, KomikList};
+use crate::api::types::LoadError;
+use crate::components::ui::{Pagination, RetryCard};
+use super::KomikGrid;
+
+/// Title of a comic type from the route, or `None` if there is no such type.
+fn type_title(kind: &str) -> Option<&'static str> {
+    match kind {
+        "manga" => Some("Manga"),
+        "manhwa" => Some("Manhwa"),
+        "manhua" => Some("Manhua"),
+        _ => None,
+    }
+}
+
+async fn fetch_list(kind: String, page: u32) -> Result<KomikList, LoadError> {
+    let list = match kind.as_str() {
+        "manga" => fetch_manga(page).await,
+        "manhwa" => fetch_manhwa(page).await,
+        "manhua" => fetch_manhua(page).await,
+        _ => return Err(LoadError::Empty),
+    }
+    .map_err(LoadError::Network)?;
+    if list.data.is_empty() {
+        return Err(LoadError::Empty);
+    }
+    Ok(list)
+}
+
+/// `/komik/:kind/page/:page`: one page of the manga, manhwa or manhua
+/// catalog with controls to move through the rest.
+#[component]
+pub fn KomikListPage() -> impl IntoView {
+    let params = use_params_map();
+    let kind = move || params.with(|p| p.get("kind").cloned().unwrap_or_default());
+    let page = move || {
+        params.with(|p| p.get("page").and_then(|p| p.parse::<u32>().ok()).unwrap_or(1).max(1))
+    };
+    // Bumped by the retry button to run the resource again.
+    let (attempt, set_attempt) = create_signal(0u32);
+    let data = create_resource(
+        move || (kind(), page(), attempt.get()),
+        |(kind, page, _)| fetch_list(kind, page),
+    );
+
+    let title = move || type_title(&kind()).unwrap_or("Comics");
+    let href = move |n: u32| format!("/komik/{}/page/{}", kind(), n);
+
+    view! {
+        <Title text=move || format!("{} · Page {} | Media Hub", title(), page())/>
+        <main class="min-h-screen py-24 px-6 md:px-12 relative overflow-hidden">
+            <div class="max-w-7xl mx-auto space-y-16">
+                <header class="flex flex-col md:flex-row md:items-end justify-between gap-6 animate-fade-in">
+                    <div class="space-y-4">
+                        <a href="/komik" class="text-[10px] font-black uppercase tracking-[0.2em] text-orange-500 hover:underline">
+                            "← Comics Hub"
+                        </a>
+                        <h1 class="text-5xl md:text-7xl font-black tracking-tighter uppercase italic">
+                            {title}
+                        </h1>
+                    </div>
+                    <span class="text-xs font-black uppercase tracking-widest text-muted-foreground">
+                        {move || format!("Page {}", page())}
+                    </span>
+                </header>
+
+                <Suspense fallback=move || view! {
+                    <div class="grid grid-cols-2 md:grid-cols-4 lg:grid-cols-6 gap-8">
+                        {(0..12).map(|_| view! { <div class="aspect-[3/4.2] rounded-[2rem] bg-white/5 animate-pulse" /> }).collect_view()}
+                    </div>
+                }>
+                    <ErrorBoundary fallback=move |errors| view! {
+                        <RetryCard
+                            errors=errors
+                            what="this page"
+                            on_retry=move |_| set_attempt.update(|n| *n += 1)
+                        />
+                    }>
+                        {move || data.get().map(|result| result.map(|list| view! {
+                            <div class="space-y-16">
+                                <KomikGrid items=list.data/>
+                                <Pagination pagination=list.pagination href=href/>
+                            </div>
+                        }))}
+                    </ErrorBoundary>
+                </Suspense>
+            </div>
+        </main>
+    }
+}
@@ -1,20 +1,14 @@
 pub mod detail;
+pub mod list;
 pub mod search;
 pub mod read;
 use leptos::*;
 use leptos_meta::*;
 use serde::{Serialize, Deserialize};
-use crate::api::komik::{fetch_manga, fetch_manhwa, fetch_manhua};
-
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct KomikItem {
-    pub title: String,
-    pub slug: String,
-    pub poster: String,
-    pub r#type: String,
-    pub chapter: Option<String>,
-    pub score: Option<String>,
-}
+use crate::api::page_cache;
+use crate::api::types::LoadError;
+use crate::components::ui::{ProxyImage, RetryCard};
+use crate::api::komik::{fetch_manga, fetch_manhwa, fetch_manhua, KomikItem, KomikList};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct HomeData {
@@ -23,40 +17,28 @@ pub struct HomeData {
     pub manhua: Vec<KomikItem>,
 }
 
-thread_local! {
-    static KOMIK_CACHE: std::cell::RefCell<Option<HomeData>> = std::cell::RefCell::new(None);
-}
+const PAGE_KEY: &str = "komik:home";
 
-async fn fetch_komik_data() -> Option<HomeData> {
-    #[cfg(feature = "csr")]
-    {
-        let cached = KOMIK_CACHE.with(|cache| cache.borrow().clone());
-        if cached.is_some() {
-            return cached;
-        }
+async fn fetch_komik_data() -> Result<HomeData, LoadError> {
+    if let Some(cached) = page_cache::get::<HomeData>(PAGE_KEY) {
+        return Ok(cached);
     }
      // Fetch all 3 sequentially for now
     let manga_res = fetch_manga(1).await;
     let manhwa_res = fetch_manhwa(1).await;
     let manhua_res = fetch_manhua(1).await;
 
-    let convert = |res: Result<crate::api::komik::MangaResponse, String>| -> Vec<KomikItem> {
-        match res {
-            Ok(r) => r.data.into_iter().map(|i| KomikItem {
-                title: i.title,
-                slug: i.slug,
-                poster: i.poster,
-                r#type: i.r#type,
-                chapter: Some(i.chapter),
-                score: i.score,
-            }).collect(),
-            Err(_) => vec![],
-        }
-    };
-
-    let manga = convert(manga_res);
-    let manhwa = convert(manhwa_res);
-    let manhua = convert(manhua_res);
+    // Only a network error if every list failed; otherwise show what loaded.
+    if let (Err(e), Err(_), Err(_)) = (&manga_res, &manhwa_res, &manhua_res) {
+        return Err(LoadError::Network(e.clone()));
+    }
+    let items = |res: Result<KomikList, String>| res.map(|list| list.data).unwrap_or_default();
+    let manga = items(manga_res);
+    let manhwa = items(manhwa_res);
+    let manhua = items(manhua_res);
+    if manga.is_empty() && manhwa.is_empty() && manhua.is_empty() {
+        return Err(LoadError::Empty);
+    }
 
     let data = HomeData {
         manga,
@@ -64,10 +46,9 @@ async fn fetch_komik_data() -> Option<HomeData> {
         manhua,
     };
     
-    #[cfg(feature = "csr")]
-    KOMIK_CACHE.with(|cache| *cache.borrow_mut() = Some(data.clone()));
+    page_cache::put(PAGE_KEY, &data);
 
-    Some(data)
+    Ok(data)
 }
 
 #[component]
@@ -80,9 +61,9 @@ fn KomikCard(item: KomikItem, index: usize) -> impl IntoView {
         _ => "from-primary to-primary/80 shadow-primary/20",
     };
 
-    let has_score = item.score.is_some();
-    let score_text = item.score.clone();
-    let has_chapter = item.chapter.is_some();
+    let has_readers = !item.reader_count.is_empty();
+    let reader_text = item.reader_count.clone();
+    let has_chapter = !item.chapter.is_empty();
     let chapter_text = item.chapter.clone();
 
     view! {
@@ -96,11 +77,10 @@ fn KomikCard(item: KomikItem, index: usize) -> impl IntoView {
             >
                 <div class="relative aspect-[3/4.2] rounded-[2rem] overflow-hidden bg-muted border border-white/5 shadow-2xl transition-all duration-700 hover-tilt group-hover:shadow-orange-500/20 group-hover:border-white/20">
                     // Poster with parallax zoom
-                    <img
+                    <ProxyImage
                         src=item.poster
                         alt=item.title.clone()
                         class="w-full h-full object-cover transition-transform duration-1000 ease-out group-hover:scale-115"
-                        loading="lazy"
                     />
                     
                     // Glassy Overlay
@@ -108,9 +88,9 @@ fn KomikCard(item: KomikItem, index: usize) -> impl IntoView {
                     
                     // Top Badges
                     <div class="absolute top-4 left-4 right-4 flex justify-between items-start pointer-events-none">
-                        <Show when=move || has_score>
+                        <Show when=move || has_readers>
                             <div class="glass-subtle px-3 py-1.5 rounded-xl border border-white/20 text-xs font-black text-yellow-500 flex items-center gap-1.5 shadow-2xl">
-                                "⭐" {score_text.clone()}
+                                "👁" {reader_text.clone()}
                             </div>
                         </Show>
                         <div class=format!("glass px-3 py-1.5 rounded-xl border border-white/10 text-[10px] font-black uppercase tracking-widest text-white shadow-2xl bg-gradient-to-br {}", type_bg)>
@@ -144,7 +124,7 @@ fn KomikCard(item: KomikItem, index: usize) -> impl IntoView {
 }
 
 #[component]
-fn KomikGrid(items: Vec<KomikItem>) -> impl IntoView {
+pub(crate) fn KomikGrid(items: Vec<KomikItem>) -> impl IntoView {
     view! {
         <div class="grid grid-cols-2 md:grid-cols-3 lg:grid-cols-4 xl:grid-cols-5 2xl:grid-cols-6 gap-8">
             {items.into_iter().enumerate().map(|(i, item)| view! { <KomikCard item=item index=i/> }).collect_view()}
@@ -190,7 +170,9 @@ fn SectionHeader(
 
 #[component]
 pub fn KomikPage() -> impl IntoView {
-    let data = create_resource(|| (), |_| fetch_komik_data());
+    // Bumped by the retry button to run the resource again.
+    let (attempt, set_attempt) = create_signal(0u32);
+    let data = create_resource(move || attempt.get(), |_| fetch_komik_data());
     let (search_query, set_search_query) = create_signal("".to_string());
 
     view! {
@@ -252,15 +234,14 @@ pub fn KomikPage() -> impl IntoView {
                         {(0..12).map(|_| view! { <div class="aspect-[3/4.2] rounded-[2rem] bg-white/5 animate-pulse" /> }).collect_view()}
                     </div>
                 }>
-                    <Show when=move || data.get().flatten().is_some() fallback=move || view! { 
-                        <div class="glass-card p-12 rounded-[2rem] text-center border border-red-500/20">
-                            <div class="text-4xl mb-4">"❌"</div>
-                            <h3 class="text-xl font-black uppercase italic">"Connection Error"</h3>
-                            <p class="text-muted-foreground">"Unable to load comic database. Please try again later."</p>
-                        </div>
+                    <ErrorBoundary fallback=move |errors| view! {
+                        <RetryCard
+                            errors=errors
+                            what="comic database"
+                            on_retry=move |_| set_attempt.update(|n| *n += 1)
+                        />
                     }>
-                        {move || {
-                            let d = data.get().flatten().unwrap();
+                        {move || data.get().map(|result| result.map(|d| {
                             view! {
                                 <div class="space-y-32">
                                     <section>
@@ -277,8 +258,8 @@ pub fn KomikPage() -> impl IntoView {
                                     </section>
                                 </div>
                             }
-                        }}
-                    </Show>
+                        }))}
+                    </ErrorBoundary>
                 </Suspense>
             </div>
         </main>
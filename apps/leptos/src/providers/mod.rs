@@ -1,3 +1,5 @@
+use crate::api::preferences::{get_preferences, update_preferences};
+use gloo_storage::{LocalStorage, Storage};
 use leptos::*;
 
 // --- Theme Provider ---
@@ -17,6 +19,27 @@ impl Theme {
             Theme::System => "system",
         }
     }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "light" => Some(Theme::Light),
+            "dark" => Some(Theme::Dark),
+            "system" => Some(Theme::System),
+            _ => None,
+        }
+    }
+}
+
+/// LocalStorage key holding the last chosen theme.
+const THEME_STORAGE_KEY: &str = "theme";
+
+fn stored_theme() -> Option<Theme> {
+    if cfg!(feature = "ssr") {
+        return None;
+    }
+    LocalStorage::get::<String>(THEME_STORAGE_KEY)
+        .ok()
+        .and_then(|t| Theme::parse(&t))
 }
 
 #[derive(Clone)]
@@ -25,11 +48,44 @@ pub struct ThemeContext {
     pub set_theme: WriteSignal<Theme>,
 }
 
+/// Provides the theme, starting from the one saved in LocalStorage (else
+/// `System`) and switching to the server's once it loads for a signed-in
+/// user. Changes are saved locally and, when signed in, to the server.
 pub fn provide_theme() {
-    let (theme, set_theme) = create_signal(Theme::System);
+    let (theme, set_theme) = create_signal(stored_theme().unwrap_or(Theme::System));
+    // The theme the server is known to hold, so loading it doesn't PUT it back.
+    let server_theme = store_value(None::<Theme>);
 
-    create_effect(move |_| {
+    #[cfg(not(feature = "ssr"))]
+    spawn_local(async move {
+        let Ok(token) = LocalStorage::get::<String>("access_token") else {
+            return;
+        };
+        if let Ok(prefs) = get_preferences(&token).await {
+            // Never saved on the server: keep the local choice.
+            if prefs.updated_at.is_some() {
+                if let Some(saved) = Theme::parse(&prefs.theme) {
+                    server_theme.set_value(Some(saved));
+                    set_theme.set(saved);
+                }
+            }
+        }
+    });
+
+    create_effect(move |previous: Option<Theme>| {
         let theme_val = theme.get();
+        let _ = LocalStorage::set(THEME_STORAGE_KEY, theme_val.as_str());
+        if previous.is_some_and(|p| p != theme_val)
+            && server_theme.get_value() != Some(theme_val)
+        {
+            if let Ok(token) = LocalStorage::get::<String>("access_token") {
+                spawn_local(async move {
+                    if update_preferences(&token, theme_val.as_str()).await.is_ok() {
+                        server_theme.set_value(Some(theme_val));
+                    }
+                });
+            }
+        }
         if let Some(doc) = document().document_element() {
             let _ = match theme_val {
                 Theme::Dark => doc.class_list().add_1("dark"),
@@ -49,6 +105,7 @@ pub fn provide_theme() {
                 }
             };
         }
+        theme_val
     });
 
     provide_context(ThemeContext { theme: theme.into(), set_theme });
@@ -58,12 +115,78 @@ pub fn use_theme() -> ThemeContext {
     use_context::<ThemeContext>().expect("ThemeContext not found")
 }
 
+// --- Online Provider ---
+
+/// Consecutive failed API calls before we treat the API as unreachable.
+const OFFLINE_AFTER_FAILURES: u32 = 2;
+
+/// Connectivity as seen by the app: the browser's online/offline events plus
+/// whether recent API calls have been failing.
+#[derive(Clone, Copy)]
+pub struct OnlineContext {
+    browser_online: RwSignal<bool>,
+    failed_fetches: RwSignal<u32>,
+    /// `true` when pages may be showing cached data.
+    pub offline: Signal<bool>,
+}
+
+impl OnlineContext {
+    pub fn new() -> Self {
+        let browser_online = create_rw_signal(true);
+        let failed_fetches = create_rw_signal(0u32);
+        let offline = Signal::derive(move || {
+            !browser_online.get() || failed_fetches.get() >= OFFLINE_AFTER_FAILURES
+        });
+        Self { browser_online, failed_fetches, offline }
+    }
+
+    pub fn set_browser_online(&self, online: bool) {
+        self.browser_online.set(online);
+    }
+
+    /// Record the outcome of an API call; one success clears the failure streak.
+    pub fn report_fetch(&self, ok: bool) {
+        if ok {
+            self.failed_fetches.set(0);
+        } else {
+            self.failed_fetches.update(|n| *n += 1);
+        }
+    }
+}
+
+impl Default for OnlineContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn provide_online() {
+    let ctx = OnlineContext::new();
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        ctx.set_browser_online(window().navigator().on_line());
+        let _ = window_event_listener_untyped("online", move |_| ctx.set_browser_online(true));
+        let _ = window_event_listener_untyped("offline", move |_| ctx.set_browser_online(false));
+    }
+
+    provide_context(ctx);
+}
+
+pub fn use_online() -> OnlineContext {
+    use_context::<OnlineContext>().expect("OnlineContext not found")
+}
+
+/// Shorthand for `use_online().offline`.
+pub fn use_offline() -> Signal<bool> {
+    use_online().offline
+}
+
 // --- Auth Provider ---
 
 
 use crate::api::types::{UserResponse, LoginRequest};
 use crate::api::auth::{login as api_login, me as api_me};
-use gloo_storage::{LocalStorage, Storage};
 
 #[derive(Clone)] // Removed Copy, UserResponse is not Copy
 pub struct AuthContext {
@@ -94,27 +217,12 @@ pub fn provide_auth() {
     let login = create_action(move |req: &LoginRequest| {
         let req = req.clone();
         async move {
-            match api_login(req).await {
-                Ok(res) => {
-                    let _ = LocalStorage::set("access_token", res.access_token);
-                    let _ = LocalStorage::set("refresh_token", res.refresh_token);
-                    // We need to fetch the user profile or use the one from login response if available.
-                    // The API login structure returns UserResponse inside LoginResponse.
-                    // However, we can't easily set the signal from here due to ownership in async block.
-                    // But we can return the user and let the consumer set it? 
-                    // Or typically, we trigger a re-fetch or use the action value.
-                    // Let's refetch me or just use the response data if we can propagate it.
-                    // For simplicity, let's just trigger a re-fetch or set it if we can access the setter.
-                    // We can't access set_user easily in this action closure if it's not moved properly.
-                    // Actually, actions are good for this.
-                    // Let's rely on the action result.
-                    
-                    // But wait, the context needs to update the global state.
-                    // We can use create_effect on the action.value() to update the user signal.
-                    Ok(())
-                }
-                Err(e) => Err(e),
-            }
+            let res = api_login(req).await?;
+            let _ = LocalStorage::set("access_token", res.access_token);
+            let _ = LocalStorage::set("refresh_token", res.refresh_token);
+            // The login response already carries the profile; no `/me` round trip.
+            set_user.set(Some(res.user));
+            Ok(())
         }
     });
 
@@ -124,20 +232,6 @@ pub fn provide_auth() {
         // We will clear the user signal via effect or manual set if we could.
     });
 
-    // Effect to update user state on login success
-    create_effect(move |_| {
-        let val = login.value().get();
-        if let Some(Ok(_)) = val {
-             spawn_local(async move {
-                if let Ok(token) = LocalStorage::get::<String>("access_token") {
-                     if let Ok(u) = api_me(&token).await {
-                         set_user.set(Some(u));
-                     }
-                }
-            });
-        }
-    });
-
     // Effect to clear user on logout
     create_effect(move |_| {
         if logout.version().get() > 0 {
@@ -157,3 +251,36 @@ pub fn provide_auth() {
 pub fn use_auth() -> AuthContext {
     use_context::<AuthContext>().expect("AuthContext not found")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::cache::fallback_to_cached;
+
+    #[test]
+    fn offline_signal_toggles_banner_and_keeps_cached_data() {
+        let runtime = create_runtime();
+        let ctx = OnlineContext::new();
+        assert!(!ctx.offline.get());
+
+        ctx.set_browser_online(false);
+        assert!(ctx.offline.get());
+        ctx.set_browser_online(true);
+        assert!(!ctx.offline.get());
+
+        // Repeated API failures also flip to offline until a call succeeds.
+        ctx.report_fetch(false);
+        assert!(!ctx.offline.get());
+        ctx.report_fetch(false);
+        assert!(ctx.offline.get());
+
+        let shown = fallback_to_cached(Err::<Vec<&str>, _>("network".to_string()), || {
+            Some(vec!["cached item"])
+        });
+        assert_eq!(shown, Ok((vec!["cached item"], true)));
+
+        ctx.report_fetch(true);
+        assert!(!ctx.offline.get());
+        runtime.dispose();
+    }
+}
@@ -29,6 +29,10 @@ pub struct Post {
     pub image_url: Option<String>,
     pub created_at: String,
     pub likes: Vec<Like>,
+    #[serde(default)]
+    pub like_count: u64,
     pub comments: Vec<Comment>,
+    #[serde(default)]
+    pub comment_count: u64,
     pub user: Option<User>,
 }
@@ -34,6 +34,8 @@ use crate::routes::AppState;
 "#;
 
 pub const SECURITY_ADDON_CODE: &str = r#"
+    /// Registers the `bearer_auth` scheme that protected paths reference with
+    /// `security(("bearer_auth" = []))`; public paths declare no requirement.
     struct SecurityAddon;
 
     impl utoipa::Modify for SecurityAddon {
@@ -65,9 +67,6 @@ pub const OPENAPI_DOC_TEMPLATE: &str = r#"#[derive(utoipa::OpenApi)]
             )
         ),
         modifiers(&SecurityAddon),
-        security((
-            "bearer_auth" = []
-        )),
         servers(
             (url = "https://ws.asepharyana.tech", description = "Production Server"),
             (url = "http://rust-api:4091", description = "Docker Service"),
@@ -76,10 +75,12 @@ pub const OPENAPI_DOC_TEMPLATE: &str = r#"#[derive(utoipa::OpenApi)]
         info(
             title = "Freefire API",
             version = "0.0.1",
-            description = "Free API for anime, manga, and more"
+            description = "Free API for anime, manga, and more. Every `/api/...` path is also served at `/api/v1/...` and `/api/v2/...`; the unversioned paths are deprecated aliases of v1."
         ),
         tags(
-            (name = "auth", description = "Authentication endpoints")
+            (name = "auth", description = "Authentication endpoints"),
+            (name = "bookmarks", description = "Bookmarks and resume progress of the signed-in user"),
+            (name = "preferences", description = "UI preferences of the signed-in user")
         )
     )]
     #[allow(dead_code)]
@@ -26,6 +26,8 @@ pub struct HandlerRouteInfo {
     pub http_method: String,
     pub route_path: String,
     pub is_protected: bool,
+    /// Path of the const the route's `DefaultBodyLimit` is raised to, if any.
+    pub body_limit: Option<String>,
 }
 
 pub fn update_handler_file(
@@ -55,14 +57,12 @@ pub fn update_handler_file(
         has_utoipa = true;
         let macro_content = &cap[1];
         let func_name = &cap[2];
+        let params = handler_params(&content[cap.get(0).unwrap().end()..]);
 
         // Parse individual handler metadata
         let mut metadata = HashMap::new();
-        for method in ["get", "post", "put", "delete", "patch"] {
-            if regex::Regex::new(&format!(r"\b{}\b", method)).unwrap().is_match(macro_content) {
-                metadata.insert("ENDPOINT_METHOD".to_string(), method.to_string());
-                break;
-            }
+        if let Some(method) = detect_http_method(macro_content) {
+            metadata.insert("ENDPOINT_METHOD".to_string(), method);
         }
         let kv_regex = regex::Regex::new(r#"(path|tag|operation_id)\s*=\s*"([^"]*)""#).unwrap();
         for kv_cap in kv_regex.captures_iter(macro_content) {
@@ -89,8 +89,9 @@ pub fn update_handler_file(
             func_name: func_name.to_string(),
             handler_module_path: format!("{}::{}", module_path_prefix, file_stem),
             http_method,
-            route_path: normalize_route_path(&route_path),
-            is_protected: is_handler_protected(&content),
+            route_path: apply_catch_all_params(&normalize_route_path(&route_path), macro_content),
+            is_protected: is_handler_protected(&content) || params.contains("CurrentUser"),
+            body_limit: upload_body_limit(&content, params, &format!("{}::{}", module_path_prefix, file_stem)),
         });
     }
 
@@ -135,11 +136,44 @@ pub fn update_handler_file(
         http_method: metadata.http_method,
         route_path: metadata.route_path,
         is_protected: is_handler_protected(&content),
+        body_limit: None,
     };
 
     Ok(vec![res])
 }
 
+const HTTP_METHODS: [&str; 6] = ["get", "post", "put", "delete", "patch", "head"];
+
+/// The HTTP method of a `#[utoipa::path(...)]` body.
+///
+/// The method is normally the leading bare word (`get, path = ...`); otherwise
+/// fall back to the first method name found anywhere in the macro.
+fn detect_http_method(macro_content: &str) -> Option<String> {
+    let leading = macro_content.trim_start().split(',').next().unwrap_or("").trim().to_lowercase();
+    if HTTP_METHODS.contains(&leading.as_str()) {
+        return Some(leading);
+    }
+    HTTP_METHODS
+        .iter()
+        .find(|method| regex::Regex::new(&format!(r"\b{}\b", method)).unwrap().is_match(macro_content))
+        .map(|method| method.to_string())
+}
+
+/// Turns `{name}` into axum's catch-all `{*name}` for every path param declared
+/// with `allow_reserved`, i.e. one whose value may contain `/`.
+///
+/// ```text
+/// params(("file_name" = String, Path, allow_reserved, description = "..."))
+/// ```
+fn apply_catch_all_params(route_path: &str, macro_content: &str) -> String {
+    let param_regex = Regex::new(r#"\(\s*"([^"]+)"\s*=[^()]*\bPath\b[^()]*\ballow_reserved\b"#).unwrap();
+    param_regex
+        .captures_iter(macro_content)
+        .fold(route_path.to_string(), |path, cap| {
+            path.replace(&format!("{{{}}}", &cap[1]), &format!("{{*{}}}", &cap[1]))
+        })
+}
+
 #[allow(dead_code)]
 fn is_scaffolded_file(content: &str) -> bool {
     content.contains("//! Handler for the")
@@ -255,6 +289,35 @@ fn parse_and_normalize_metadata_from_map(
     })
 }
 
+/// The parameter list of the handler whose signature continues at `rest`
+/// (right after its name), without the enclosing parentheses.
+fn handler_params(rest: &str) -> &str {
+    let Some(start) = rest.find('(') else {
+        return "";
+    };
+    let mut depth = 0;
+    for (i, c) in rest[start..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &rest[start + 1..start + i];
+                }
+            }
+            _ => {}
+        }
+    }
+    ""
+}
+
+/// Multipart handlers in a module declaring `pub const MAX_UPLOAD_SIZE` accept
+/// bodies up to that size instead of axum's 2 MB default.
+fn upload_body_limit(content: &str, params: &str, module_path: &str) -> Option<String> {
+    (params.contains("Multipart") && content.contains("pub const MAX_UPLOAD_SIZE:"))
+        .then(|| format!("{}::MAX_UPLOAD_SIZE", module_path))
+}
+
 fn is_handler_protected(content: &str) -> bool {
     // Check if register_routes contains AuthMiddleware::layer()
     content.contains("AuthMiddleware::layer()") ||
@@ -441,6 +504,7 @@ fn inject_schemas_and_return_info(
         http_method: http_method.to_string(),
         route_path: route_path.to_string(),
         is_protected,
+        body_limit: None,
     }))
 }
 
@@ -468,12 +532,8 @@ fn extract_and_normalize_metadata(
         // 5. responses(...) -> body = Type
 
         // Method finding
-        for method in ["get", "post", "put", "delete", "patch"] {
-            // Check if method exists as a standalone word (roughly)
-            if regex::Regex::new(&format!(r"\b{}\b", method)).unwrap().is_match(macro_content) {
-                metadata.insert("ENDPOINT_METHOD".to_string(), method.to_string());
-                break;
-            }
+        if let Some(method) = detect_http_method(macro_content) {
+            metadata.insert("ENDPOINT_METHOD".to_string(), method);
         }
 
         // Key-Value parsing
@@ -74,7 +74,7 @@ fn generate_root_api_mod_internal(
         
         use utoipa::OpenApi;
         #[derive(utoipa::OpenApi)]
-        #[openapi(paths(), components(schemas()), modifiers(&SecurityAddon), security(("bearer_auth" = [])), servers((url = "https://ws.asepharyana.tech", description = "Production Server"), (url = "http://localhost:4091", description = "Local Development")), tags())]
+        #[openapi(paths(), components(schemas()), modifiers(&SecurityAddon), servers((url = "https://ws.asepharyana.tech", description = "Production Server"), (url = "http://localhost:4091", description = "Local Development")), tags())]
         struct TempApiDoc;
         struct SecurityAddon;
         impl utoipa::Modify for SecurityAddon {
@@ -109,17 +109,23 @@ fn generate_router_creation_code(modules: &[String], all_handlers: &[HandlerRout
     // 2. Add automatic route registrations for all handlers
     for handler in all_handlers {
         let auth_layer = if handler.is_protected {
-            ".layer(crate::middleware::auth::AuthMiddleware::layer())"
+            ".route_layer(axum::middleware::from_fn(crate::middleware::auth::require_auth))"
         } else {
             ""
         };
+        let body_limit = handler
+            .body_limit
+            .as_ref()
+            .map(|limit| format!(".layer(axum::extract::DefaultBodyLimit::max({}))", limit))
+            .unwrap_or_default();
 
         registrations.push(format!(
-            "    router = router.route(\"{}\", axum::routing::{}({}::{}){});",
+            "    router = router.route(\"{}\", axum::routing::{}({}::{}){}{});",
             handler.route_path,
             handler.http_method.to_lowercase(),
             handler.handler_module_path,
             handler.func_name,
+            body_limit,
             auth_layer
         ));
     }
@@ -0,0 +1,15 @@
+//! Writes TypeScript declarations for every API schema.
+//!
+//! Usage: `cargo run --bin gen-types [output_path]`
+//! (defaults to the SolidJS client's `src/types/api.d.ts`).
+
+use rustexpress::typescript::write_types;
+
+const DEFAULT_OUTPUT: &str = "../solidjs/src/types/api.d.ts";
+
+fn main() -> anyhow::Result<()> {
+    let output = std::env::args().nth(1).unwrap_or_else(|| DEFAULT_OUTPUT.to_string());
+    write_types(&output)?;
+    println!("✓ Wrote {}", output);
+    Ok(())
+}
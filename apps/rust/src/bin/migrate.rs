@@ -1,4 +1,4 @@
-use rustexpress::infra::db_setup;
+use rustexpress::infra::migrations;
 use sea_orm::{Database, ConnectOptions};
 use std::env;
 use dotenvy::dotenv;
@@ -20,7 +20,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let db = Database::connect(opt).await?;
     println!("✓ Connected to database");
 
-    db_setup::init(&db).await?;
+    migrations::migrate(&db).await?;
     println!("✓ Database migration completed successfully.");
 
     Ok(())
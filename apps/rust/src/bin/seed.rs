@@ -0,0 +1,71 @@
+//! Database seeding CLI.
+//!
+//! ```bash
+//! cargo run --bin seed                   # default chat rooms, if none exist
+//! cargo run --bin seed -- --force        # clear the seeded rooms and re-seed them
+//! cargo run --bin seed -- --demo         # also add demo users, rooms and messages
+//! ```
+//!
+//! Reads `DATABASE_URL` from the environment or `.env`; run the migrations
+//! first on a fresh database. With `APP__ENVIRONMENT` (or `APP_ENV`) set to
+//! `production`, `--force` and `--demo` are refused unless `--yes-really` is
+//! passed as well.
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use dotenvy::dotenv;
+use rustexpress::seeder::seed::{seed_chat_data, seed_demo_content, DEMO_PASSWORD};
+use sea_orm::{ConnectOptions, Database};
+use std::env;
+
+#[derive(Parser)]
+#[command(name = "seed")]
+#[command(about = "Seed the database with default and demo data", long_about = None)]
+struct Args {
+    /// Clear the chat rooms, members and messages and seed them again
+    #[arg(long, short = 'f')]
+    force: bool,
+
+    /// Also insert demo users, rooms and messages (skipping any that exist)
+    #[arg(long)]
+    demo: bool,
+
+    /// Allow --force and --demo against a production environment
+    #[arg(long)]
+    yes_really: bool,
+}
+
+/// Whether `APP__ENVIRONMENT` or `APP_ENV` names production.
+fn is_production() -> bool {
+    ["APP__ENVIRONMENT", "APP_ENV"]
+        .iter()
+        .filter_map(|key| env::var(key).ok())
+        .any(|value| value.trim().eq_ignore_ascii_case("production"))
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+    tracing_subscriber::fmt().with_target(false).init();
+    let args = Args::parse();
+
+    if (args.force || args.demo) && is_production() && !args.yes_really {
+        bail!("Refusing --force/--demo in production; pass --yes-really to run them anyway");
+    }
+
+    let database_url = env::var("DATABASE_URL").context("DATABASE_URL must be set in .env")?;
+    let mut opt = ConnectOptions::new(database_url);
+    opt.max_connections(5).sqlx_logging(false);
+    let db = Database::connect(opt).await.context("Failed to connect to the database")?;
+    println!("✓ Connected to database");
+
+    seed_chat_data(&db, args.force).await.context("Seeding chat data failed")?;
+    println!("✓ Chat data seeded{}", if args.force { " (forced)" } else { "" });
+
+    if args.demo {
+        seed_demo_content(&db).await.context("Seeding demo content failed")?;
+        println!("✓ Demo content seeded (demo users log in with \"{}\")", DEMO_PASSWORD);
+    }
+
+    Ok(())
+}
@@ -4,8 +4,6 @@ use tokio::net::TcpListener;
 
 use axum::Router;
 use sea_orm::{Database, DatabaseConnection};
-use tower_http::compression::{CompressionLayer, CompressionLevel};
-use tower_http::cors::CorsLayer;
 use tracing_subscriber::EnvFilter;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
@@ -13,14 +11,19 @@ use utoipa_swagger_ui::SwaggerUi;
 use crate::core::config::CONFIG;
 use crate::infra::redis::REDIS_POOL;
 use crate::routes::api::{create_api_routes, ApiDoc};
-use crate::routes::AppState;
+use crate::routes::{create_v2_routes, AppState};
 
 pub struct Application {
     pub port: u16,
     router: Router,
     listener: TcpListener,
+    state: Arc<AppState>,
 }
 
+/// How long shutdown waits for in-flight chat message saves before closing the
+/// database pool anyway.
+const CHAT_SAVE_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
 impl Application {
     pub async fn build() -> anyhow::Result<Self> {
         // Initialize tracing
@@ -43,6 +46,11 @@ impl Application {
             worker_threads
         );
 
+        // Metrics
+        if let Err(e) = crate::observability::setup_metrics() {
+            tracing::warn!("Failed to initialize metrics: {}", e);
+        }
+
         // Redis
         let _ = REDIS_POOL.get().await;
 
@@ -51,28 +59,18 @@ impl Application {
         let mut browser_config = crate::browser::BrowserPoolConfig::default();
         browser_config.headless = true;
         browser_config.sandbox = false;
+        browser_config.proxies = CONFIG.browser_proxies.clone();
         match crate::browser::pool::init_browser_pool(browser_config).await {
             Ok(_) => tracing::info!("✓ Browser pool initialized"),
             Err(e) => tracing::error!("⚠️ Failed to initialize browser pool: {}", e),
         }
 
         // Database
-        let mut opt = sea_orm::ConnectOptions::new(CONFIG.database_url.clone());
-        opt.max_connections(CONFIG.db.max_connections)
-            .min_connections(CONFIG.db.min_connections)
-            .connect_timeout(std::time::Duration::from_secs(CONFIG.db.connect_timeout_seconds))
-            .idle_timeout(std::time::Duration::from_secs(CONFIG.db.idle_timeout_seconds))
-            .acquire_timeout(std::time::Duration::from_secs(CONFIG.db.acquire_timeout_seconds))
-            .max_lifetime(std::time::Duration::from_secs(CONFIG.db.max_lifetime_seconds))
-            .sqlx_logging(CONFIG.log_level == "debug");
-
-        let db = Database::connect(opt).await
-            .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
-        tracing::info!("✓ SeaORM database connection established");
+        let db = Self::connect_database().await?;
 
         // Schema & Seeding
-        if let Err(e) = crate::infra::db_setup::init(&db).await {
-            tracing::error!("Failed to init DB schema: {}", e);
+        if let Err(e) = crate::infra::migrations::migrate(&db).await {
+            tracing::error!("Failed to run database migrations: {}", e);
         }
         if let Err(e) = crate::seeder::seed::seed_chat_data_if_empty(&db).await {
             tracing::warn!("Failed to seed chat data: {}", e);
@@ -84,6 +82,14 @@ impl Application {
         let image_processing_semaphore = Arc::new(tokio::sync::Semaphore::new(CONFIG.image_processing_concurrency));
         let room_manager = Arc::new(crate::ws::room::RoomManager::new());
 
+        let services = crate::di::AppServices {
+            db: db_arc.clone(),
+            redis_pool: REDIS_POOL.clone(),
+            storage: crate::services::storage::profile::get_storage(),
+            http_client: crate::infra::HTTP_CLIENT.clone(),
+            source_breakers: crate::circuit_breaker::SOURCE_BREAKERS.clone(),
+        };
+
         let app_state = Arc::new(AppState {
             jwt_secret: CONFIG.jwt_secret.clone(),
             redis_pool: REDIS_POOL.clone(),
@@ -92,18 +98,73 @@ impl Application {
             chat_tx,
             image_processing_semaphore,
             room_manager: room_manager.clone(),
+            chat_rooms: Arc::new(crate::routes::ws::chat::ChatRooms::new()),
+            source_breakers: crate::circuit_breaker::SOURCE_BREAKERS.clone(),
+            services: Arc::new(services.into_container()),
         });
 
+        // Register a breaker per scrape source so /metrics reports them from the start.
+        for (_, url) in crate::scraping::urls::scrape_sources() {
+            app_state
+                .source_breakers
+                .for_source(&crate::observability::metrics::source_label(&url));
+        }
+
+        // Event subscribers
+        crate::jobs::upload_log::register(&crate::events::EVENT_BUS).await;
+        crate::jobs::webhook::register(&crate::events::EVENT_BUS).await;
+
+        // Chat messages posted on other instances
+        if !CONFIG.redis_url.is_empty() {
+            crate::routes::ws::relay::spawn_subscriber(app_state.chat_rooms.clone(), CONFIG.redis_url.clone());
+        }
+
         // Scheduler
-        Self::init_scheduler(db_arc.clone(), room_manager).await?;
+        Self::init_scheduler(db_arc.clone(), room_manager, app_state.clone()).await?;
+
+        // GraphQL
+        let graphql_schema = crate::graphql::create_schema(db_arc.clone());
+        let graphql_routes = Router::new()
+            .route(
+                "/graphql",
+                axum::routing::get(crate::graphql::graphql_playground)
+                    .post(crate::graphql::graphql_handler),
+            )
+            .with_state(graphql_schema);
+
+        // Health
+        let health_routes = Router::new()
+            .route("/health", axum::routing::get(crate::health::health_check))
+            .route("/health/ready", axum::routing::get(crate::health::readiness_check))
+            .route("/health/deep", axum::routing::get(crate::health::deep_health_check))
+            .route("/api/version", axum::routing::get(crate::health::version_info))
+            .route("/metrics", axum::routing::get(crate::observability::MetricsHandler::handle))
+            .with_state(app_state.clone());
 
         // Router
         let app = Router::new()
-            .merge(create_api_routes().with_state(app_state.clone()))
-            .merge(crate::routes::ws::register_routes(Router::new()).with_state(app_state))
+            // `/api/v1/*`, `/api/v2/*` and the deprecated unversioned `/api/*`
+            .merge(crate::routing::versioned_api(
+                create_api_routes().with_state(app_state.clone()),
+                create_v2_routes().with_state(app_state.clone()),
+            ))
+            .merge(health_routes)
+            .merge(graphql_routes)
+            .merge(crate::routes::ws::register_routes(Router::new()).with_state(app_state.clone()))
             .merge(SwaggerUi::new("/docs").url("/api-docs/openapi.json", ApiDoc::openapi()))
-            .layer(CompressionLayer::new().quality(CompressionLevel::Fastest))
-            .layer(CorsLayer::permissive());
+            .fallback(crate::middleware::json_errors::not_found)
+            .method_not_allowed_fallback(crate::middleware::json_errors::method_not_allowed)
+            // A panicking handler answers 500 instead of dropping the connection
+            .layer(axum::middleware::from_fn(crate::middleware::catch_panic::catch_panic))
+            // Every 4xx/5xx as `{ status, code, message }`
+            .layer(axum::middleware::from_fn(crate::middleware::json_errors::json_error_bodies))
+            .layer(axum::middleware::from_fn(crate::observability::http_metrics_middleware))
+            .layer(axum::middleware::from_fn(crate::core::ratelimit::route_rate_limit_middleware))
+            .layer(axum::middleware::from_fn(crate::core::ratelimit::keyed_rate_limit_middleware))
+            .layer(crate::middleware::compression::compression_layer())
+            .layer(crate::middleware::cors::cors_layer(&CONFIG))
+            // Outermost, so the request id and its span cover every layer above
+            .layer(axum::middleware::from_fn(crate::observability::request_id_middleware));
 
         // Listener
         let port = CONFIG.server_port;
@@ -111,12 +172,75 @@ impl Application {
         let listener = TcpListener::bind(&addr).await?;
         tracing::info!("Server listening on {}", listener.local_addr()?);
 
-        Ok(Self { port, router: app, listener })
+        Ok(Self { port, router: app, listener, state: app_state })
+    }
+
+    /// Connect to the database using the pool settings from CONFIG.
+    async fn connect_database() -> anyhow::Result<DatabaseConnection> {
+        let pool = &CONFIG.db;
+        let max_connections = pool.max_connections.max(1);
+        if pool.min_connections > max_connections {
+            tracing::warn!(
+                "db.min_connections ({}) exceeds db.max_connections ({}); using {}",
+                pool.min_connections,
+                max_connections,
+                max_connections
+            );
+        }
+        let min_connections = pool.min_connections.min(max_connections);
+        let sqlx_logging = pool.sqlx_logging_enabled(&CONFIG.log_level);
+
+        let mut opt = sea_orm::ConnectOptions::new(CONFIG.database_url.clone());
+        opt.max_connections(max_connections)
+            .min_connections(min_connections)
+            .connect_timeout(std::time::Duration::from_secs(pool.connect_timeout_seconds))
+            .idle_timeout(std::time::Duration::from_secs(pool.idle_timeout_seconds))
+            .acquire_timeout(std::time::Duration::from_secs(pool.acquire_timeout_seconds))
+            .max_lifetime(std::time::Duration::from_secs(pool.max_lifetime_seconds))
+            .sqlx_logging(sqlx_logging);
+        tracing::info!(
+            max_connections,
+            min_connections,
+            connect_timeout_s = pool.connect_timeout_seconds,
+            idle_timeout_s = pool.idle_timeout_seconds,
+            acquire_timeout_s = pool.acquire_timeout_seconds,
+            max_lifetime_s = pool.max_lifetime_seconds,
+            sqlx_logging,
+            "Database pool settings"
+        );
+
+        // Retry transient connection failures (DB still starting, brief network blip)
+        let db = crate::helpers::retry(crate::helpers::db_backoff(), || async {
+            Database::connect(opt.clone()).await.map_err(|e| {
+                tracing::warn!("Database connection attempt failed: {}", e);
+                crate::helpers::transient(e)
+            })
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to connect to database: {}", e))?;
+        tracing::info!("✓ SeaORM database connection established");
+        Ok(db)
+    }
+
+    /// Run pending migrations and exit without starting the server (`--migrate`).
+    pub async fn migrate_only() -> anyhow::Result<()> {
+        if std::env::var("RUST_LOG").is_err() {
+            tracing_subscriber::fmt()
+                .with_env_filter(EnvFilter::new(&CONFIG.log_level))
+                .init();
+        }
+
+        let db = Self::connect_database().await?;
+        crate::infra::migrations::migrate(&db)
+            .await
+            .map_err(|e| anyhow::anyhow!("Migration failed: {}", e))?;
+        Ok(())
     }
 
     async fn init_scheduler(
         db: Arc<DatabaseConnection>,
         room_manager: Arc<crate::ws::room::RoomManager>,
+        app_state: Arc<AppState>,
     ) -> anyhow::Result<()> {
         let scheduler = crate::scheduler::Scheduler::new().await.expect("Failed to create scheduler");
         
@@ -126,12 +250,85 @@ impl Application {
         let room_cleanup = crate::scheduler::CleanupEmptyRooms::new(room_manager);
         scheduler.add(room_cleanup).await.expect("Failed to add room cleanup");
 
+        if CONFIG.prewarm_enabled {
+            let prewarm = Arc::new(crate::scheduler::PrewarmListCaches::new(
+                app_state.clone(),
+                CONFIG.prewarm_interval_minutes,
+            ));
+            let job = prewarm.clone();
+            scheduler
+                .add_job(prewarm.name(), &prewarm.schedule(), move || {
+                    let job = job.clone();
+                    async move {
+                        job.run().await;
+                    }
+                })
+                .await
+                .expect("Failed to add cache pre-warm");
+
+            // Warm once at startup instead of waiting for the first tick.
+            tokio::spawn(async move {
+                prewarm.run().await;
+            });
+        }
+
+        if CONFIG.prune_enabled {
+            let prune = Arc::new(crate::scheduler::PruneStaleData::new(
+                app_state.clone(),
+                crate::scheduler::PruneSettings::from_config(),
+            ));
+            let job = prune.clone();
+            scheduler
+                .add_job(prune.name(), &prune.schedule(), move || {
+                    let job = job.clone();
+                    async move {
+                        job.run().await;
+                    }
+                })
+                .await
+                .expect("Failed to add stale data prune");
+        }
+
         scheduler.start().await.expect("Failed to start scheduler");
         tracing::info!("✓ Scheduler started");
         Ok(())
     }
 
+    /// Serves until SIGTERM/SIGINT, then: stop accepting connections, refuse
+    /// new WebSocket upgrades and send close frames, wait up to
+    /// `CONFIG.shutdown_drain_timeout_seconds` for in-flight requests, wait
+    /// (bounded) for pending chat saves, and finally close the database and
+    /// Redis pools.
     pub async fn run(self) -> std::io::Result<()> {
-        axum::serve(self.listener, self.router.into_make_service()).await
+        let state = self.state;
+        let chat_rooms = state.chat_rooms.clone();
+
+        crate::graceful::serve_with_drain(
+            self.listener,
+            self.router,
+            async move {
+                crate::graceful::shutdown_signal().await;
+                chat_rooms.begin_shutdown();
+            },
+            std::time::Duration::from_secs(CONFIG.shutdown_drain_timeout_seconds),
+        )
+        .await?;
+
+        if !state.chat_rooms.wait_for_saves(CHAT_SAVE_DRAIN_TIMEOUT).await {
+            tracing::warn!(
+                "Timed out with {} chat message save(s) still pending",
+                state.chat_rooms.saves_in_flight()
+            );
+        }
+
+        if let Err(e) = state.db.close_by_ref().await {
+            tracing::warn!("Failed to close database pool: {}", e);
+        } else {
+            tracing::info!("✓ Database pool closed");
+        }
+
+        state.redis_pool.close();
+        tracing::info!("✓ Redis pool closed");
+        Ok(())
     }
 }
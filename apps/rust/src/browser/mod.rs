@@ -4,5 +4,9 @@
 //! process with multiple reusable tabs for efficient web scraping.
 
 pub mod pool;
+pub mod proxy;
+pub mod stealth;
 
 pub use pool::{BrowserPool, BrowserPoolConfig, PooledTab};
+pub use proxy::ProxyRotation;
+pub use stealth::{StealthConfig, StealthProfile};
@@ -3,10 +3,17 @@
 //! This pool maintains one headless Chrome instance and provides tabs
 //! on-demand for scraping. Tabs are returned to the pool after use.
 
+use super::proxy::{redacted, ProxyRotation, DEFAULT_PROXY_COOLDOWN};
+use super::stealth::{StealthConfig, StealthProfile};
 use crate::helpers::uuid_v4;
+use chromiumoxide::cdp::browser_protocol::browser::BrowserContextId;
+use chromiumoxide::cdp::browser_protocol::emulation::SetDeviceMetricsOverrideParams;
+use chromiumoxide::cdp::browser_protocol::target::{CreateBrowserContextParams, CreateTargetParams};
 use chromiumoxide::{Browser, BrowserConfig, Page};
 use futures::StreamExt;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{Mutex, Semaphore};
 use tracing::{debug, info, warn};
 
@@ -31,6 +38,12 @@ pub struct BrowserPoolConfig {
     pub user_agent: Option<String>,
     /// Window dimensions
     pub window_size: Option<(u32, u32)>,
+    /// User-Agent rotation, viewport and delay applied on every navigation
+    pub stealth: StealthConfig,
+    /// Proxy URLs assigned round-robin to new tabs (empty = direct)
+    pub proxies: Vec<String>,
+    /// How long a proxy marked bad is skipped
+    pub proxy_cooldown: Duration,
 }
 
 impl Default for BrowserPoolConfig {
@@ -74,6 +87,9 @@ impl Default for BrowserPoolConfig {
             sandbox: false,
             user_agent: None,
             window_size: Some((1920, 1080)),
+            stealth: StealthConfig::default(),
+            proxies: Vec::new(),
+            proxy_cooldown: DEFAULT_PROXY_COOLDOWN,
         }
     }
 }
@@ -99,8 +115,12 @@ impl Default for BrowserPoolConfig {
 pub struct BrowserPool {
     /// The browser instance (single process)
     browser: Arc<Browser>,
-    /// Available (idle) tabs
-    available_tabs: Mutex<Vec<Arc<Page>>>,
+    /// Available (idle) tabs, with the proxy each was opened through
+    available_tabs: Mutex<Vec<(Arc<Page>, Option<usize>)>>,
+    /// Proxy rotation for new tabs
+    proxies: ProxyRotation,
+    /// Browser context per proxy index, created on first use
+    proxy_contexts: Mutex<HashMap<usize, BrowserContextId>>,
     /// Semaphore to limit concurrent tabs
     semaphore: Arc<Semaphore>,
     /// Configuration
@@ -114,6 +134,9 @@ impl BrowserPool {
     /// an already-running Chrome/Chromium instance via the Chrome DevTools
     /// Protocol WebSocket endpoint (e.g. a `browserless/chrome` Docker sidecar).
     /// Otherwise it **launches** a local Chrome/Chromium process.
+    ///
+    /// Tabs are spread round-robin over `config.proxies`, each proxy in its
+    /// own browser context.
     pub async fn new(config: BrowserPoolConfig) -> anyhow::Result<Arc<Self>> {
         info!(
             "🌐 Initializing browser pool with max {} tabs",
@@ -204,6 +227,8 @@ impl BrowserPool {
         let pool = Arc::new(Self {
             browser: Arc::new(browser),
             available_tabs: Mutex::new(Vec::new()),
+            proxies: ProxyRotation::new(&config.proxies, config.proxy_cooldown),
+            proxy_contexts: Mutex::new(HashMap::new()),
             semaphore: Arc::new(Semaphore::new(config.max_tabs)),
             config: config.clone(),
         });
@@ -212,9 +237,10 @@ impl BrowserPool {
         // Dynamic "pool dinamis" as requested: warm up half of max tabs or at least 5
         let warm_count = std::cmp::min(5, config.max_tabs);
         for i in 0..warm_count {
-            match pool.create_new_tab().await {
+            let proxy = pool.proxies.next();
+            match pool.create_new_tab(proxy).await {
                 Ok(tab) => {
-                    pool.available_tabs.lock().await.push(tab);
+                    pool.available_tabs.lock().await.push((tab, proxy));
                     debug!("Pre-warmed tab {}/{}", i + 1, warm_count);
                 }
                 Err(e) => {
@@ -239,10 +265,13 @@ impl BrowserPool {
         // Acquire semaphore permit (limits concurrent tabs)
         let permit = self.semaphore.clone().acquire_owned().await?;
 
-        // Try to get an existing tab
+        // Next proxy in rotation, then an idle tab already opened through it
+        let proxy = self.proxies.next();
         let page = {
             let mut tabs = self.available_tabs.lock().await;
-            tabs.pop()
+            tabs.iter()
+                .rposition(|(_, p)| *p == proxy)
+                .map(|i| tabs.remove(i).0)
         };
 
         let page = match page {
@@ -251,29 +280,68 @@ impl BrowserPool {
                 // Navigate to blank page to reset state
                 if let Err(e) = page.goto("about:blank").await {
                     warn!("Failed to reset tab, creating new one: {}", e);
-                    self.create_new_tab().await?
+                    self.create_new_tab(proxy).await?
                 } else {
                     page
                 }
             }
             None => {
                 debug!("Creating new tab");
-                self.create_new_tab().await?
+                self.create_new_tab(proxy).await?
             }
         };
 
-        Ok(PooledTab {
+        let tab = PooledTab {
             page,
             pool: Arc::clone(self),
+            proxy,
             _permit: permit,
-        })
+        };
+        if let Some(url) = tab.proxy() {
+            debug!("Tab served through proxy #{} ({})", proxy.unwrap_or_default(), redacted(url));
+        }
+        Ok(tab)
+    }
+
+    /// Skip proxy `index` for new tabs until its cooldown has passed.
+    pub fn mark_proxy_bad(&self, index: usize) {
+        if let Some(url) = self.proxies.url(index) {
+            warn!("Proxy #{} ({}) marked bad, skipping it for a while", index, redacted(url));
+        }
+        self.proxies.mark_bad(index);
     }
 
-    /// Create a new tab.
-    async fn create_new_tab(&self) -> anyhow::Result<Arc<Page>> {
+    /// Browser context routing through proxy `index`, created on first use.
+    async fn proxy_context(&self, index: usize) -> anyhow::Result<BrowserContextId> {
+        let mut contexts = self.proxy_contexts.lock().await;
+        if let Some(id) = contexts.get(&index) {
+            return Ok(id.clone());
+        }
+        let url = self
+            .proxies
+            .url(index)
+            .ok_or_else(|| anyhow::anyhow!("Unknown proxy #{}", index))?;
+        let params = CreateBrowserContextParams::builder().proxy_server(url).build();
+        let id = self
+            .browser
+            .execute(params)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to create context for proxy #{}: {}", index, e))?
+            .result
+            .browser_context_id;
+        contexts.insert(index, id.clone());
+        Ok(id)
+    }
+
+    /// Create a new tab, in the context of `proxy` if given.
+    async fn create_new_tab(&self, proxy: Option<usize>) -> anyhow::Result<Arc<Page>> {
+        let mut params = CreateTargetParams::new("about:blank");
+        if let Some(index) = proxy {
+            params.browser_context_id = Some(self.proxy_context(index).await?);
+        }
         let page = self
             .browser
-            .new_page("about:blank")
+            .new_page(params)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to create new tab: {}", e))?;
 
@@ -284,12 +352,12 @@ impl BrowserPool {
     }
 
     /// Return a tab to the pool for reuse.
-    async fn return_tab(&self, page: Arc<Page>) {
+    async fn return_tab(&self, page: Arc<Page>, proxy: Option<usize>) {
         let mut tabs = self.available_tabs.lock().await;
 
         // Only keep up to max_tabs in the pool
         if tabs.len() < self.config.max_tabs {
-            tabs.push(page);
+            tabs.push((page, proxy));
             debug!("Tab returned to pool (available: {})", tabs.len());
         } else {
             debug!("Pool full, discarding tab");
@@ -318,12 +386,15 @@ impl BrowserPool {
 pub struct PooledTab {
     page: Arc<Page>,
     pool: Arc<BrowserPool>,
+    proxy: Option<usize>,
     _permit: tokio::sync::OwnedSemaphorePermit,
 }
 
 impl PooledTab {
-    /// Navigate to a URL.
+    /// Navigate to a URL, after applying the pool's next stealth profile.
     pub async fn goto(&self, url: &str) -> anyhow::Result<()> {
+        let profile = self.pool.config.stealth.next_profile();
+        self.apply_stealth(&profile).await;
         self.page
             .goto(url)
             .await
@@ -331,6 +402,25 @@ impl PooledTab {
         Ok(())
     }
 
+    /// Waits out the profile's delay and sets its User-Agent and viewport.
+    /// A setting the browser rejects is logged and skipped.
+    async fn apply_stealth(&self, profile: &StealthProfile) {
+        if !profile.delay.is_zero() {
+            tokio::time::sleep(profile.delay).await;
+        }
+        if let Some(user_agent) = &profile.user_agent {
+            if let Err(e) = self.page.set_user_agent(user_agent.as_str()).await {
+                warn!("Failed to set tab User-Agent: {}", e);
+            }
+        }
+        if let Some((width, height)) = profile.viewport {
+            let metrics = SetDeviceMetricsOverrideParams::new(i64::from(width), i64::from(height), 1.0, false);
+            if let Err(e) = self.page.execute(metrics).await {
+                warn!("Failed to set tab viewport: {}", e);
+            }
+        }
+    }
+
     /// Wait for navigation to complete.
     pub async fn wait_for_navigation(&self) -> anyhow::Result<()> {
         self.page
@@ -419,6 +509,17 @@ impl PooledTab {
             .map(|u| u.map(|url| url.to_string()).unwrap_or_default())
     }
 
+    /// Index of the proxy this tab goes through, for [`BrowserPool::mark_proxy_bad`].
+    pub fn proxy_index(&self) -> Option<usize> {
+        self.proxy
+    }
+
+    /// URL of the proxy this tab goes through; may include credentials, so
+    /// pass it through [`redacted`] before logging.
+    pub fn proxy(&self) -> Option<&str> {
+        self.proxy.and_then(|i| self.pool.proxies.url(i))
+    }
+
     /// Get access to the underlying Page for advanced operations.
     pub fn page(&self) -> &Page {
         &self.page
@@ -430,13 +531,14 @@ impl Drop for PooledTab {
         // Return the tab to the pool
         let page = Arc::clone(&self.page);
         let pool = Arc::clone(&self.pool);
+        let proxy = self.proxy;
 
         // Use a blocking channel to ensure the tab is returned
         // This prevents memory leaks from dropped tasks
         let rt = tokio::runtime::Handle::try_current();
         if let Ok(handle) = rt {
             handle.spawn(async move {
-                pool.return_tab(page).await;
+                pool.return_tab(page, proxy).await;
             });
         } else {
             // Fallback: if we can't get runtime, just log warning
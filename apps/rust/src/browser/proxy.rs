@@ -0,0 +1,136 @@
+//! Round-robin proxy assignment for pooled tabs.
+//!
+//! Each proxy gets its own browser context (Chrome applies a proxy per
+//! context), and every new tab is opened in the context of the next proxy in
+//! rotation. A proxy marked bad is skipped until its cooldown runs out; when
+//! every proxy is cooling down the rotation carries on regardless rather than
+//! refusing to hand out tabs.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// How long a proxy marked bad is skipped by default.
+pub const DEFAULT_PROXY_COOLDOWN: Duration = Duration::from_secs(120);
+
+/// Rotation state over a fixed list of proxy URLs.
+#[derive(Debug)]
+pub struct ProxyRotation {
+    proxies: Vec<String>,
+    cooldown: Duration,
+    next: AtomicUsize,
+    bad_until: Mutex<Vec<Option<Instant>>>,
+}
+
+impl ProxyRotation {
+    /// Rotation over `proxies`; blank entries are dropped.
+    pub fn new(proxies: &[String], cooldown: Duration) -> Self {
+        let proxies: Vec<String> = proxies
+            .iter()
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+        Self {
+            bad_until: Mutex::new(vec![None; proxies.len()]),
+            proxies,
+            cooldown,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.proxies.is_empty()
+    }
+
+    /// URL of proxy `index`.
+    pub fn url(&self, index: usize) -> Option<&str> {
+        self.proxies.get(index).map(String::as_str)
+    }
+
+    /// Index of the proxy the next tab should use, skipping proxies in
+    /// cooldown. `None` when no proxies are configured.
+    pub fn next(&self) -> Option<usize> {
+        if self.proxies.is_empty() {
+            return None;
+        }
+        let len = self.proxies.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let now = Instant::now();
+        let healthy = match self.bad_until.lock() {
+            Ok(bad_until) => (0..len)
+                .map(|offset| (start + offset) % len)
+                .find(|&i| bad_until[i].is_none_or(|until| until <= now)),
+            Err(_) => None,
+        };
+        let index = healthy.unwrap_or(start % len);
+        // Continue the rotation after the proxy actually handed out.
+        self.next.store(index + 1, Ordering::Relaxed);
+        Some(index)
+    }
+
+    /// Skips proxy `index` until the cooldown has passed.
+    pub fn mark_bad(&self, index: usize) {
+        if let Ok(mut bad_until) = self.bad_until.lock() {
+            if let Some(slot) = bad_until.get_mut(index) {
+                *slot = Some(Instant::now() + self.cooldown);
+            }
+        }
+    }
+}
+
+/// `url` with any credentials removed, for logs.
+pub fn redacted(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(mut parsed) if !parsed.username().is_empty() || parsed.password().is_some() => {
+            let _ = parsed.set_username("");
+            let _ = parsed.set_password(None);
+            parsed.to_string()
+        }
+        _ => url.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rotation(cooldown: Duration) -> ProxyRotation {
+        let proxies = ["http://p0:8080", " ", "http://p1:8080", "socks5://p2:1080"];
+        ProxyRotation::new(&proxies.map(String::from), cooldown)
+    }
+
+    #[test]
+    fn proxies_are_assigned_round_robin() {
+        let proxies = rotation(DEFAULT_PROXY_COOLDOWN);
+        let assigned: Vec<usize> = (0..7).filter_map(|_| proxies.next()).collect();
+        assert_eq!(assigned, [0, 1, 2, 0, 1, 2, 0]);
+        assert_eq!(proxies.url(2), Some("socks5://p2:1080"));
+        assert_eq!(ProxyRotation::new(&[], DEFAULT_PROXY_COOLDOWN).next(), None);
+    }
+
+    #[test]
+    fn bad_proxies_are_skipped_until_the_cooldown_ends() {
+        let proxies = rotation(Duration::from_millis(50));
+        proxies.mark_bad(1);
+        let assigned: Vec<usize> = (0..4).filter_map(|_| proxies.next()).collect();
+        assert_eq!(assigned, [0, 2, 0, 2]);
+
+        // With every proxy cooling down, rotation carries on regardless.
+        proxies.mark_bad(0);
+        proxies.mark_bad(2);
+        assert!(proxies.next().is_some());
+
+        std::thread::sleep(Duration::from_millis(60));
+        let assigned: Vec<usize> = (0..3).filter_map(|_| proxies.next()).collect();
+        let mut sorted = assigned.clone();
+        sorted.sort();
+        assert_eq!(sorted, [0, 1, 2]);
+    }
+
+    #[test]
+    fn credentials_are_redacted() {
+        assert_eq!(redacted("http://user:secret@p0:8080"), "http://p0:8080/");
+        assert_eq!(redacted("http://p0:8080"), "http://p0:8080");
+    }
+}
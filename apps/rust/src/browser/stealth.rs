@@ -0,0 +1,148 @@
+//! Per-navigation fingerprint variation for pooled tabs.
+//!
+//! Every [`PooledTab::goto`](super::PooledTab::goto) asks the pool's
+//! [`StealthConfig`] for a [`StealthProfile`]: the next User-Agent from a
+//! rotating pool, a random viewport, and a random pause before navigating.
+//! Rotation is round-robin, so consecutive navigations never reuse the same
+//! User-Agent while the pool has more than one.
+
+use rand::Rng;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Desktop User-Agents rotated through by default.
+const DEFAULT_USER_AGENTS: [&str; 4] = [
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/119.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:121.0) Gecko/20100101 Firefox/121.0",
+];
+
+/// Common desktop viewports picked from at random.
+const DEFAULT_VIEWPORTS: [(u32, u32); 4] = [(1920, 1080), (1536, 864), (1440, 900), (1366, 768)];
+
+/// What a single navigation looks like to the site.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StealthProfile {
+    pub user_agent: Option<String>,
+    pub viewport: Option<(u32, u32)>,
+    /// Pause before navigating.
+    pub delay: Duration,
+}
+
+/// Stealth settings of a [`BrowserPool`](super::BrowserPool). Clones share
+/// the User-Agent rotation.
+#[derive(Debug, Clone)]
+pub struct StealthConfig {
+    user_agents: Vec<String>,
+    viewports: Vec<(u32, u32)>,
+    delay_range: (Duration, Duration),
+    next_agent: Arc<AtomicUsize>,
+}
+
+impl Default for StealthConfig {
+    fn default() -> Self {
+        Self {
+            user_agents: DEFAULT_USER_AGENTS.iter().map(|ua| ua.to_string()).collect(),
+            viewports: DEFAULT_VIEWPORTS.to_vec(),
+            delay_range: (Duration::from_millis(250), Duration::from_millis(1200)),
+            // Start somewhere random so restarted instances don't all lead
+            // with the same User-Agent.
+            next_agent: Arc::new(AtomicUsize::new(rand::thread_rng().gen_range(0..DEFAULT_USER_AGENTS.len()))),
+        }
+    }
+}
+
+impl StealthConfig {
+    /// No rotation, no viewport changes and no delays.
+    pub fn disabled() -> Self {
+        Self {
+            user_agents: Vec::new(),
+            viewports: Vec::new(),
+            delay_range: (Duration::ZERO, Duration::ZERO),
+            next_agent: Arc::default(),
+        }
+    }
+
+    /// User-Agents to rotate through, in order. Blank entries are dropped; an
+    /// empty list leaves the browser's own User-Agent alone.
+    pub fn rotating_user_agents(mut self, user_agents: Vec<String>) -> Self {
+        self.user_agents = user_agents
+            .into_iter()
+            .map(|ua| ua.trim().to_string())
+            .filter(|ua| !ua.is_empty())
+            .collect();
+        self.next_agent = Arc::default();
+        self
+    }
+
+    /// Random pause before each navigation, between `min` and `max`.
+    pub fn random_delay_range(mut self, min: Duration, max: Duration) -> Self {
+        self.delay_range = if min <= max { (min, max) } else { (max, min) };
+        self
+    }
+
+    /// Viewports picked from at random; empty keeps the window size.
+    pub fn random_viewports(mut self, viewports: Vec<(u32, u32)>) -> Self {
+        self.viewports = viewports.into_iter().filter(|(w, h)| *w > 0 && *h > 0).collect();
+        self
+    }
+
+    pub fn user_agents(&self) -> &[String] {
+        &self.user_agents
+    }
+
+    /// Settings for the next navigation.
+    pub fn next_profile(&self) -> StealthProfile {
+        let user_agent = (!self.user_agents.is_empty()).then(|| {
+            let index = self.next_agent.fetch_add(1, Ordering::Relaxed) % self.user_agents.len();
+            self.user_agents[index].clone()
+        });
+
+        let mut rng = rand::thread_rng();
+        let viewport = (!self.viewports.is_empty()).then(|| self.viewports[rng.gen_range(0..self.viewports.len())]);
+        let (min, max) = self.delay_range;
+        let delay = if max > min { rng.gen_range(min..=max) } else { min };
+
+        StealthProfile { user_agent, viewport, delay }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agents(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn consecutive_navigations_rotate_user_agents() {
+        let config = StealthConfig::default()
+            .rotating_user_agents(agents(&["UA-1", " ", "UA-2", "UA-3"]))
+            .random_delay_range(Duration::from_millis(50), Duration::from_millis(10));
+        assert_eq!(config.user_agents(), ["UA-1", "UA-2", "UA-3"]);
+
+        // The pool's copy of the config and the one a tab uses share rotation.
+        let tab_view = config.clone();
+        let first = config.next_profile();
+        let second = tab_view.next_profile();
+        assert_ne!(first.user_agent, second.user_agent);
+        assert_eq!(first.user_agent.as_deref(), Some("UA-1"));
+        assert_eq!(second.user_agent.as_deref(), Some("UA-2"));
+        assert_eq!(config.next_profile().user_agent.as_deref(), Some("UA-3"));
+        assert_eq!(config.next_profile().user_agent.as_deref(), Some("UA-1"));
+
+        for profile in [first, second] {
+            assert!((Duration::from_millis(10)..=Duration::from_millis(50)).contains(&profile.delay));
+            assert!(DEFAULT_VIEWPORTS.contains(&profile.viewport.unwrap()));
+        }
+    }
+
+    #[test]
+    fn disabled_stealth_changes_nothing() {
+        let profile = StealthConfig::disabled().next_profile();
+        assert_eq!(profile, StealthProfile { user_agent: None, viewport: None, delay: Duration::ZERO });
+    }
+}
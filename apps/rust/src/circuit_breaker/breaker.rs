@@ -6,6 +6,8 @@ use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{info, warn};
 
+use crate::observability::metrics::set_circuit_breaker_state;
+
 /// Circuit breaker state.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CircuitState {
@@ -62,6 +64,7 @@ pub struct CircuitBreaker {
 impl CircuitBreaker {
     /// Create a new circuit breaker.
     pub fn new(name: &str, config: CircuitBreakerConfig) -> Arc<Self> {
+        set_circuit_breaker_state(name, CircuitState::Closed);
         Arc::new(Self {
             name: name.to_string(),
             config,
@@ -106,6 +109,11 @@ impl CircuitBreaker {
         }
     }
 
+    async fn transition(&self, to: CircuitState) {
+        *self.state.write().await = to;
+        set_circuit_breaker_state(&self.name, to);
+    }
+
     async fn check_transition(&self) {
         let state = *self.state.read().await;
         if state == CircuitState::Open {
@@ -113,7 +121,7 @@ impl CircuitBreaker {
             if let Some(time) = *last_failure {
                 if time.elapsed() >= self.config.reset_timeout {
                     info!("Circuit breaker '{}' transitioning to HALF_OPEN", self.name);
-                    *self.state.write().await = CircuitState::HalfOpen;
+                    self.transition(CircuitState::HalfOpen).await;
                     self.success_count.store(0, Ordering::SeqCst);
                 }
             }
@@ -135,7 +143,7 @@ impl CircuitBreaker {
                         "Circuit breaker '{}' closing after {} successes",
                         self.name, count
                     );
-                    *self.state.write().await = CircuitState::Closed;
+                    self.transition(CircuitState::Closed).await;
                     self.failure_count.store(0, Ordering::SeqCst);
                 }
             }
@@ -154,7 +162,7 @@ impl CircuitBreaker {
                         "Circuit breaker '{}' opening after {} failures",
                         self.name, count
                     );
-                    *self.state.write().await = CircuitState::Open;
+                    self.transition(CircuitState::Open).await;
                     *self.last_failure_time.write().await = Some(Instant::now());
                 }
             }
@@ -163,7 +171,7 @@ impl CircuitBreaker {
                     "Circuit breaker '{}' reopening after failure in half-open",
                     self.name
                 );
-                *self.state.write().await = CircuitState::Open;
+                self.transition(CircuitState::Open).await;
                 *self.last_failure_time.write().await = Some(Instant::now());
             }
             CircuitState::Open => {}
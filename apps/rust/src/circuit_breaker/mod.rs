@@ -4,5 +4,7 @@
 //! to failing services.
 
 pub mod breaker;
+pub mod sources;
 
 pub use breaker::{CircuitBreaker, CircuitState};
+pub use sources::{SourceBreakers, SOURCE_BREAKERS};
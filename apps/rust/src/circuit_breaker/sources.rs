@@ -0,0 +1,179 @@
+//! Per-source circuit breakers for upstream scrape fetches.
+//!
+//! One [`CircuitBreaker`] per upstream host, created on first use. The
+//! process-wide registry is [`SOURCE_BREAKERS`]; `AppState` holds the same
+//! `Arc` so handlers, `/health/deep` and the proxy layer see one state.
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerError, CircuitState};
+use crate::core::config::CONFIG;
+use crate::core::error::AppError;
+
+/// Breakers keyed by upstream source (host name).
+pub struct SourceBreakers {
+    config: CircuitBreakerConfig,
+    breakers: DashMap<String, Arc<CircuitBreaker>>,
+}
+
+impl SourceBreakers {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            breakers: DashMap::new(),
+        }
+    }
+
+    /// Returns the breaker for `source`, creating a closed one on first use.
+    pub fn for_source(&self, source: &str) -> Arc<CircuitBreaker> {
+        self.breakers
+            .entry(source.to_string())
+            .or_insert_with(|| CircuitBreaker::new(source, self.config.clone()))
+            .clone()
+    }
+
+    /// Current state of every known source, sorted by name.
+    pub async fn states(&self) -> BTreeMap<String, CircuitState> {
+        let breakers: Vec<(String, Arc<CircuitBreaker>)> = self
+            .breakers
+            .iter()
+            .map(|e| (e.key().clone(), e.value().clone()))
+            .collect();
+
+        let mut states = BTreeMap::new();
+        for (name, breaker) in breakers {
+            states.insert(name, breaker.state().await);
+        }
+        states
+    }
+
+    /// Runs `fetch` through the breaker for `source`.
+    ///
+    /// Only errors that point at the source being unhealthy (connection
+    /// failures, timeouts, 5xx) count towards opening it; a 4xx for one page
+    /// or a blocked target is passed through without tripping the circuit.
+    /// While open, fails immediately with [`AppError::ServiceUnavailable`].
+    pub async fn call<T, Fut>(&self, source: &str, fetch: Fut) -> Result<T, AppError>
+    where
+        Fut: std::future::Future<Output = Result<T, AppError>>,
+    {
+        let breaker = self.for_source(source);
+        let outcome = breaker
+            .call(|| async move {
+                match fetch.await {
+                    Err(e) if counts_as_failure(&e) => Err(e),
+                    other => Ok(other),
+                }
+            })
+            .await;
+
+        match outcome {
+            Ok(result) => result,
+            Err(CircuitBreakerError::ServiceError(e)) => Err(e),
+            Err(CircuitBreakerError::CircuitOpen) => Err(AppError::ServiceUnavailable(format!(
+                "Upstream {} is temporarily unavailable",
+                source
+            ))),
+        }
+    }
+}
+
+/// Whether an upstream error should count against the source's breaker.
+fn counts_as_failure(error: &AppError) -> bool {
+    match error {
+        AppError::BlockedTarget(_) | AppError::BadRequest(_) | AppError::NotFound(_) => false,
+        other => !other.to_string().contains("status 4"),
+    }
+}
+
+fn breaker_config() -> CircuitBreakerConfig {
+    CircuitBreakerConfig {
+        failure_threshold: CONFIG.source_breaker_failures.max(1),
+        reset_timeout: Duration::from_secs(CONFIG.source_breaker_cooldown_seconds),
+        ..CircuitBreakerConfig::default()
+    }
+}
+
+/// Process-wide per-source breakers, configured from `CONFIG`.
+pub static SOURCE_BREAKERS: Lazy<Arc<SourceBreakers>> =
+    Lazy::new(|| Arc::new(SourceBreakers::new(breaker_config())));
+
+/// Lowercase name of a breaker state, as reported by `/health/deep`.
+pub fn state_name(state: CircuitState) -> &'static str {
+    match state {
+        CircuitState::Closed => "closed",
+        CircuitState::HalfOpen => "half_open",
+        CircuitState::Open => "open",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breakers() -> SourceBreakers {
+        SourceBreakers::new(CircuitBreakerConfig {
+            failure_threshold: 2,
+            reset_timeout: Duration::from_millis(50),
+            success_threshold: 1,
+        })
+    }
+
+    fn upstream_down() -> AppError {
+        AppError::Other("Direct fetch failed with status 502 Bad Gateway".to_string())
+    }
+
+    #[tokio::test]
+    async fn opens_short_circuits_and_recovers() {
+        let breakers = breakers();
+        let source = "otakudesu.test";
+
+        for _ in 0..2 {
+            let r: Result<(), _> = breakers.call(source, async { Err(upstream_down()) }).await;
+            assert!(matches!(r, Err(AppError::Other(_))));
+        }
+        assert_eq!(breakers.states().await[source], CircuitState::Open);
+
+        // Open: the fetch is never polled.
+        let ran = std::sync::atomic::AtomicBool::new(false);
+        let r = breakers
+            .call(source, async {
+                ran.store(true, std::sync::atomic::Ordering::SeqCst);
+                Ok(())
+            })
+            .await;
+        assert!(matches!(r, Err(AppError::ServiceUnavailable(_))));
+        assert!(!ran.load(std::sync::atomic::Ordering::SeqCst));
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let r = breakers.call(source, async { Ok("page") }).await;
+        assert_eq!(r.unwrap(), "page");
+        assert_eq!(breakers.states().await[source], CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn client_errors_and_other_sources_are_isolated() {
+        let breakers = breakers();
+
+        for _ in 0..3 {
+            let r: Result<(), _> = breakers
+                .call("a.test", async {
+                    Err(AppError::Other("Direct fetch failed with status 404 Not Found".to_string()))
+                })
+                .await;
+            assert!(r.is_err());
+        }
+        assert_eq!(breakers.states().await["a.test"], CircuitState::Closed);
+
+        for _ in 0..2 {
+            let _: Result<(), _> = breakers.call("b.test", async { Err(upstream_down()) }).await;
+        }
+        let states = breakers.states().await;
+        assert_eq!(states["a.test"], CircuitState::Closed);
+        assert_eq!(states["b.test"], CircuitState::Open);
+    }
+}
@@ -8,6 +8,7 @@
 use config::{Config, ConfigError, Environment, File};
 use once_cell::sync::Lazy;
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::env;
 
 /// Application configuration loaded at startup.
@@ -32,7 +33,9 @@ pub struct AppConfig {
     #[serde(default = "default_env")]
     pub environment: String,
 
-    /// Allowed CORS origins (comma-separated)
+    /// Origins allowed to call the API from a browser, with credentials
+    /// (comma-separated). Empty means permissive in development and no
+    /// cross-origin access elsewhere.
     #[serde(default)]
     pub cors_origins: Vec<String>,
 
@@ -50,6 +53,191 @@ pub struct AppConfig {
     /// Max concurrent image processing tasks
     #[serde(default = "default_image_processing_concurrency")]
     pub image_processing_concurrency: usize,
+
+    /// Seconds to wait for an image processing permit before returning 503
+    #[serde(default = "default_image_processing_wait_seconds")]
+    pub image_processing_wait_seconds: u64,
+
+    /// Per-user / per-IP rate limiting configuration
+    #[serde(default)]
+    pub rate_limit: RateLimitSettings,
+
+    /// Peers whose `X-Forwarded-For` / `X-Real-IP` headers are believed
+    /// (comma-separated IPs or CIDR ranges). Requests from any other peer are
+    /// attributed to the peer's own address.
+    #[serde(default = "default_trusted_proxies")]
+    pub trusted_proxies: Vec<String>,
+
+    /// Domains the proxy endpoints may fetch from (comma-separated).
+    /// Empty means any public host is allowed.
+    #[serde(default)]
+    pub proxy_allowed_domains: Vec<String>,
+
+    /// Hosts `/api/imageproxy` fetches posters from (comma-separated; entries
+    /// also match their subdomains)
+    #[serde(default = "default_image_proxy_hosts")]
+    pub image_proxy_hosts: Vec<String>,
+
+    /// Seconds a proxy endpoint waits for its upstream before answering 504
+    #[serde(default = "default_proxy_timeout_seconds")]
+    pub proxy_timeout_seconds: u64,
+
+    /// Largest upstream body a proxy endpoint passes on; bigger ones get 413
+    /// or, when streamed, are cut off
+    #[serde(default = "default_proxy_max_body_bytes")]
+    pub proxy_max_body_bytes: u64,
+
+    /// Global cap on outbound scrape requests per minute (0 = unlimited)
+    #[serde(default = "default_scrape_budget_per_minute")]
+    pub scrape_budget_per_minute: u32,
+
+    /// Cap on fetches per minute the public proxy makes for its callers,
+    /// kept apart from the scrape budget (0 = unlimited)
+    #[serde(default = "default_proxy_budget_per_minute")]
+    pub proxy_budget_per_minute: u32,
+
+    /// Scrape sources that need session cookies kept between requests
+    /// (comma-separated hosts; `host=persist` also saves the jar to Redis)
+    #[serde(default)]
+    pub scrape_cookie_sources: Vec<String>,
+
+    /// User-Agent sent to scrape sources (defaults to a desktop browser)
+    #[serde(default)]
+    pub scrape_user_agent: Option<String>,
+
+    /// Contact sent as the `From` header on scrape requests (e.g. an email)
+    #[serde(default)]
+    pub scrape_contact: Option<String>,
+
+    /// Request settings per scrape source, keyed by source name
+    /// (`otakudesu`, `alqanime`, `komiku`, `komiku_api`)
+    #[serde(default)]
+    pub scrape_sources: BTreeMap<String, ScrapeSourceConfig>,
+
+    /// Scrape sources whose robots.txt is honored (comma-separated hosts;
+    /// empty disables robots checks)
+    #[serde(default)]
+    pub robots_respect_sources: Vec<String>,
+
+    /// Scrape sources behind a Cloudflare challenge, fetched through the
+    /// browser pool instead of reqwest (comma-separated hosts)
+    #[serde(default)]
+    pub browser_sources: Vec<String>,
+
+    /// Proxy URLs the browser pool spreads its tabs over (comma-separated)
+    #[serde(default)]
+    pub browser_proxies: Vec<String>,
+
+    /// JSON file of scraping selector overrides (`{ "anime2.title": ".tt h2" }`)
+    #[serde(default)]
+    pub selectors_file: Option<String>,
+
+    /// Only keep scraped download/stream links to these hosts
+    /// (comma-separated; empty keeps every host)
+    #[serde(default)]
+    pub link_host_allowlist: Vec<String>,
+
+    /// Drop scraped download/stream links to these hosts (comma-separated)
+    #[serde(default)]
+    pub link_host_denylist: Vec<String>,
+
+    /// Consecutive upstream failures before a scrape source's circuit opens
+    #[serde(default = "default_source_breaker_failures")]
+    pub source_breaker_failures: u32,
+
+    /// Seconds an open source circuit waits before letting a probe through
+    #[serde(default = "default_source_breaker_cooldown_seconds")]
+    pub source_breaker_cooldown_seconds: u64,
+
+    /// Most complete-list pages one `/api/anime/export` run walks
+    #[serde(default = "default_export_max_pages")]
+    pub export_max_pages: u32,
+
+    /// Milliseconds `/api/anime/export` waits between upstream pages
+    #[serde(default = "default_export_page_delay_ms")]
+    pub export_page_delay_ms: u64,
+
+    /// Entries kept in the in-process cache in front of Redis; 0 disables it
+    #[serde(default = "default_cache_l1_capacity")]
+    pub cache_l1_capacity: usize,
+
+    /// Seconds an entry stays in the in-process cache, capped by its Redis TTL
+    #[serde(default = "default_cache_l1_ttl_seconds")]
+    pub cache_l1_ttl_seconds: u64,
+
+    /// Seconds to wait for in-flight requests after SIGTERM/SIGINT
+    #[serde(default = "default_shutdown_drain_timeout_seconds")]
+    pub shutdown_drain_timeout_seconds: u64,
+
+    /// Periodically refresh the cached anime/komik list responses
+    #[serde(default = "default_prewarm_enabled")]
+    pub prewarm_enabled: bool,
+
+    /// Minutes between cache pre-warm runs
+    #[serde(default = "default_prewarm_interval_minutes")]
+    pub prewarm_interval_minutes: u64,
+
+    /// Periodically delete stale cache entries and expired DB rows
+    #[serde(default = "default_prune_enabled")]
+    pub prune_enabled: bool,
+
+    /// Minutes between prune runs
+    #[serde(default = "default_prune_interval_minutes")]
+    pub prune_interval_minutes: u64,
+
+    /// Cache entries nobody has read or written for this many hours are deleted
+    #[serde(default = "default_prune_cache_soft_ttl_hours")]
+    pub prune_cache_soft_ttl_hours: u64,
+
+    /// Image cache rows, sessions and tokens are deleted this many days after
+    /// they were created or expired
+    #[serde(default = "default_prune_retention_days")]
+    pub prune_retention_days: u64,
+
+    /// Webhook endpoints (comma-separated `url` or `url|event+event`)
+    #[serde(default)]
+    pub webhook_endpoints: Vec<String>,
+
+    /// Secret for the `X-Webhook-Signature` HMAC (unsigned when unset)
+    #[serde(default)]
+    pub webhook_secret: Option<String>,
+
+    /// Retries per webhook delivery before it is counted as failed
+    #[serde(default = "default_webhook_max_retries")]
+    pub webhook_max_retries: u32,
+
+    /// Seconds between pings the server sends on each chat WebSocket
+    #[serde(default = "default_ws_ping_interval_seconds")]
+    pub ws_ping_interval_seconds: u64,
+
+    /// Chat WebSockets silent (no frame, not even a pong) for this many
+    /// seconds are closed
+    #[serde(default = "default_ws_idle_timeout_seconds")]
+    pub ws_idle_timeout_seconds: u64,
+
+    /// Seconds a dropped chat client has to resume its session with the
+    /// token it was given
+    #[serde(default = "default_ws_resume_ttl_seconds")]
+    pub ws_resume_ttl_seconds: u64,
+
+    /// Lifetime in seconds of access tokens issued by `/api/auth/refresh`
+    #[serde(default = "default_access_token_ttl_seconds")]
+    pub access_token_ttl_seconds: u64,
+}
+
+/// How requests to one scrape source are made. Unset fields fall back to
+/// the shared client's timeout and the global scraper identity.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct ScrapeSourceConfig {
+    /// Per-request timeout in seconds
+    #[serde(default)]
+    pub timeout_seconds: Option<u64>,
+    /// User-Agent for this source, over `scrape_user_agent`
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// Extra headers sent to this source (e.g. `referer`)
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -66,6 +254,16 @@ pub struct DbConfig {
     pub acquire_timeout_seconds: u64,
     #[serde(default = "default_db_max_lifetime")]
     pub max_lifetime_seconds: u64,
+    /// Log every SQL statement; unset means only when `log_level` is `debug`
+    #[serde(default)]
+    pub sqlx_logging: Option<bool>,
+}
+
+impl DbConfig {
+    /// Whether SQL statements are logged under `log_level`.
+    pub fn sqlx_logging_enabled(&self, log_level: &str) -> bool {
+        self.sqlx_logging.unwrap_or(log_level == "debug")
+    }
 }
 
 impl Default for DbConfig {
@@ -77,10 +275,47 @@ impl Default for DbConfig {
             idle_timeout_seconds: default_db_idle_timeout(),
             acquire_timeout_seconds: default_db_acquire_timeout(),
             max_lifetime_seconds: default_db_max_lifetime(),
+            sqlx_logging: None,
         }
     }
 }
 
+/// Keyed rate limit configuration.
+///
+/// Authenticated requests are counted per user id, anonymous requests per
+/// client IP, each in its own Redis bucket.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateLimitSettings {
+    #[serde(default = "default_rate_limit_enabled")]
+    pub enabled: bool,
+    /// Requests allowed per window for an authenticated user
+    #[serde(default = "default_rate_limit_user_requests")]
+    pub user_requests: u32,
+    /// Requests allowed per window for an anonymous client IP
+    #[serde(default = "default_rate_limit_ip_requests")]
+    pub ip_requests: u32,
+    #[serde(default = "default_rate_limit_window")]
+    pub window_seconds: u64,
+    /// Requests per minute per client IP on the proxy endpoints
+    #[serde(default = "default_rate_limit_proxy_ip_requests")]
+    pub proxy_ip_requests: u32,
+    /// Requests per minute per client IP on the anime/komik scraper endpoints
+    #[serde(default = "default_rate_limit_scraper_ip_requests")]
+    pub scraper_ip_requests: u32,
+}
+
+impl Default for RateLimitSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_rate_limit_enabled(),
+            user_requests: default_rate_limit_user_requests(),
+            ip_requests: default_rate_limit_ip_requests(),
+            window_seconds: default_rate_limit_window(),
+            proxy_ip_requests: default_rate_limit_proxy_ip_requests(),
+            scraper_ip_requests: default_rate_limit_scraper_ip_requests(),
+        }
+    }
+}
 
 /// SMTP configuration for sending emails
 #[derive(Debug, Clone, Deserialize)]
@@ -162,6 +397,144 @@ fn default_image_processing_concurrency() -> usize {
     5
 }
 
+fn default_image_processing_wait_seconds() -> u64 {
+    10
+}
+
+fn default_trusted_proxies() -> Vec<String> {
+    ["127.0.0.0/8", "::1", "10.0.0.0/8", "172.16.0.0/12", "192.168.0.0/16", "fc00::/7"]
+        .iter()
+        .map(|range| range.to_string())
+        .collect()
+}
+
+fn default_image_proxy_hosts() -> Vec<String> {
+    [
+        "otakudesu.cloud",
+        "otakudesu.best",
+        "alqanime.net",
+        "alqanime.si",
+        "komiku.org",
+        "komikcast.site",
+        "wp.com",
+    ]
+    .iter()
+    .map(|host| host.to_string())
+    .collect()
+}
+
+fn default_proxy_timeout_seconds() -> u64 {
+    20
+}
+
+fn default_proxy_max_body_bytes() -> u64 {
+    100 * 1024 * 1024
+}
+
+fn default_scrape_budget_per_minute() -> u32 {
+    600
+}
+
+fn default_proxy_budget_per_minute() -> u32 {
+    300
+}
+
+fn default_source_breaker_failures() -> u32 {
+    5
+}
+
+fn default_source_breaker_cooldown_seconds() -> u64 {
+    30
+}
+
+fn default_shutdown_drain_timeout_seconds() -> u64 {
+    30
+}
+
+fn default_prewarm_enabled() -> bool {
+    true
+}
+
+fn default_prewarm_interval_minutes() -> u64 {
+    4
+}
+
+fn default_prune_enabled() -> bool {
+    true
+}
+
+fn default_prune_interval_minutes() -> u64 {
+    60
+}
+
+fn default_prune_cache_soft_ttl_hours() -> u64 {
+    72
+}
+
+fn default_prune_retention_days() -> u64 {
+    30
+}
+
+fn default_ws_ping_interval_seconds() -> u64 {
+    30
+}
+
+fn default_ws_idle_timeout_seconds() -> u64 {
+    90
+}
+
+fn default_ws_resume_ttl_seconds() -> u64 {
+    300
+}
+
+fn default_access_token_ttl_seconds() -> u64 {
+    900
+}
+
+fn default_export_max_pages() -> u32 {
+    200
+}
+
+fn default_export_page_delay_ms() -> u64 {
+    500
+}
+
+fn default_cache_l1_capacity() -> usize {
+    512
+}
+
+fn default_cache_l1_ttl_seconds() -> u64 {
+    5
+}
+
+fn default_webhook_max_retries() -> u32 {
+    5
+}
+
+fn default_rate_limit_enabled() -> bool {
+    true
+}
+
+fn default_rate_limit_user_requests() -> u32 {
+    600
+}
+
+fn default_rate_limit_ip_requests() -> u32 {
+    120
+}
+
+fn default_rate_limit_window() -> u64 {
+    60
+}
+
+fn default_rate_limit_proxy_ip_requests() -> u32 {
+    60
+}
+
+fn default_rate_limit_scraper_ip_requests() -> u32 {
+    120
+}
+
 fn default_db_max_connections() -> u32 {
     100
 }
@@ -211,7 +584,18 @@ impl AppConfig {
                 Environment::with_prefix("APP")
                     .separator("__")
                     .try_parsing(true)
-                    .list_separator(","),
+                    .list_separator(",")
+                    .with_list_parse_key("cors_origins")
+                    .with_list_parse_key("trusted_proxies")
+                    .with_list_parse_key("proxy_allowed_domains")
+                    .with_list_parse_key("image_proxy_hosts")
+                    .with_list_parse_key("scrape_cookie_sources")
+                    .with_list_parse_key("link_host_allowlist")
+                    .with_list_parse_key("link_host_denylist")
+                    .with_list_parse_key("robots_respect_sources")
+                    .with_list_parse_key("browser_sources")
+                    .with_list_parse_key("browser_proxies")
+                    .with_list_parse_key("webhook_endpoints"),
             )
             // Map legacy env vars to new config structure
             .set_override_option("database_url", env::var("DATABASE_URL").ok())?
@@ -219,6 +603,7 @@ impl AppConfig {
             .set_override_option("redis_url", env::var("REDIS_URL").ok())?
             .build()?;
 
+        validate(&config)?;
         config.try_deserialize()
     }
 
@@ -233,6 +618,76 @@ impl AppConfig {
     }
 }
 
+/// Minimum length of `JWT_SECRET`.
+pub const MIN_JWT_SECRET_LEN: usize = 32;
+
+/// Database URL schemes SeaORM can connect to.
+const DATABASE_SCHEMES: [&str; 4] = ["mysql", "postgres", "postgresql", "sqlite"];
+
+fn check_database_url(raw: &str) -> Result<(), String> {
+    let url = url::Url::parse(raw.trim()).map_err(|e| e.to_string())?;
+    if !DATABASE_SCHEMES.contains(&url.scheme()) {
+        return Err(format!(
+            "unsupported scheme `{}` (expected one of {})",
+            url.scheme(),
+            DATABASE_SCHEMES.join(", ")
+        ));
+    }
+    if url.scheme() != "sqlite" && url.host_str().is_none_or(str::is_empty) {
+        return Err("missing host".to_string());
+    }
+    Ok(())
+}
+
+/// Checks the settings every request depends on before deserializing, so a
+/// misconfigured server refuses to start instead of failing on its first
+/// request. Every missing or invalid value is listed in the one error.
+fn validate(config: &Config) -> Result<(), ConfigError> {
+    let mut problems: Vec<String> = Vec::new();
+
+    match config.get_string("database_url") {
+        Ok(url) if !url.trim().is_empty() => {
+            if let Err(e) = check_database_url(&url) {
+                problems.push(format!("DATABASE_URL is invalid: {}", e));
+            }
+        }
+        _ => problems.push("DATABASE_URL is not set".to_string()),
+    }
+
+    match config.get_string("jwt_secret") {
+        Ok(secret) if !secret.is_empty() => {
+            if secret.chars().count() < MIN_JWT_SECRET_LEN {
+                problems.push(format!(
+                    "JWT_SECRET must be at least {} characters (got {})",
+                    MIN_JWT_SECRET_LEN,
+                    secret.chars().count()
+                ));
+            }
+        }
+        _ => problems.push("JWT_SECRET is not set".to_string()),
+    }
+
+    match config.get_int("server_port") {
+        Ok(port) if !(1..=i64::from(u16::MAX)).contains(&port) => problems.push(format!(
+            "APP__SERVER_PORT must be between 1 and {} (got {})",
+            u16::MAX,
+            port
+        )),
+        Ok(_) | Err(ConfigError::NotFound(_)) => {}
+        Err(e) => problems.push(format!("APP__SERVER_PORT is not a number: {}", e)),
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(ConfigError::Message(format!(
+            "{} invalid setting(s):\n   - {}",
+            problems.len(),
+            problems.join("\n   - ")
+        )))
+    }
+}
+
 /// Global configuration instance, loaded once at startup.
 /// Panics if configuration is invalid - this is intentional for fail-fast behavior.
 pub static CONFIG: Lazy<AppConfig> = Lazy::new(|| {
@@ -276,3 +731,74 @@ pub static CONFIG_MAP: Lazy<HashMap<String, String>> = Lazy::new(|| {
     }
     map
 });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+    fn config(values: &[(&str, &str)]) -> Config {
+        values
+            .iter()
+            .fold(Config::builder(), |builder, (key, value)| builder.set_override(*key, *value).unwrap())
+            .build()
+            .unwrap()
+    }
+
+    fn problems(values: &[(&str, &str)]) -> String {
+        match validate(&config(values)) {
+            Err(ConfigError::Message(msg)) => msg,
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn valid_settings_pass() {
+        let values = [
+            ("database_url", "mysql://root:pw@localhost:3306/app"),
+            ("jwt_secret", SECRET),
+            ("server_port", "4091"),
+        ];
+        assert!(validate(&config(&values)).is_ok());
+        // The port is optional.
+        assert!(validate(&config(&values[..2])).is_ok());
+    }
+
+    #[test]
+    fn every_missing_setting_is_reported_at_once() {
+        let msg = problems(&[]);
+        assert!(msg.starts_with("2 invalid setting(s)"), "{}", msg);
+        assert!(msg.contains("DATABASE_URL is not set"), "{}", msg);
+        assert!(msg.contains("JWT_SECRET is not set"), "{}", msg);
+    }
+
+    #[test]
+    fn malformed_values_are_described() {
+        let msg = problems(&[
+            ("database_url", "localhost:3306/app"),
+            ("jwt_secret", "short"),
+            ("server_port", "70000"),
+        ]);
+        assert!(msg.starts_with("3 invalid setting(s)"), "{}", msg);
+        assert!(msg.contains("DATABASE_URL is invalid: unsupported scheme `localhost`"), "{}", msg);
+        assert!(msg.contains("JWT_SECRET must be at least 32 characters (got 5)"), "{}", msg);
+        assert!(msg.contains("APP__SERVER_PORT must be between 1 and 65535 (got 70000)"), "{}", msg);
+
+        let msg = problems(&[("database_url", "not a url"), ("jwt_secret", SECRET), ("server_port", "http")]);
+        assert!(msg.contains("DATABASE_URL is invalid: relative URL without a base"), "{}", msg);
+        assert!(msg.contains("APP__SERVER_PORT is not a number"), "{}", msg);
+        assert!(problems(&[("database_url", "mysql:///app"), ("jwt_secret", SECRET)]).contains("missing host"));
+    }
+
+    #[test]
+    fn sqlx_logging_follows_log_level_unless_set() {
+        let mut db = DbConfig::default();
+        assert!(db.sqlx_logging_enabled("debug"));
+        assert!(!db.sqlx_logging_enabled("info"));
+        db.sqlx_logging = Some(true);
+        assert!(db.sqlx_logging_enabled("warn"));
+        db.sqlx_logging = Some(false);
+        assert!(!db.sqlx_logging_enabled("debug"));
+    }
+}
@@ -61,6 +61,20 @@ pub enum AppError {
     Forbidden,
     #[error("Not Found: {0}")]
     NotFound(String),
+    #[error("Blocked proxy target: {0}")]
+    BlockedTarget(String),
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+    #[error("Scrape budget exhausted for {0}")]
+    ScrapeBudgetExhausted(String),
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+    #[error("Unsupported media type: {0}")]
+    UnsupportedMediaType(String),
+    #[error("Bad gateway: {0}")]
+    BadGateway(String),
 }
 
 impl From<failure::Error> for AppError {
@@ -83,7 +97,10 @@ impl From<String> for AppError {
 
 impl From<Box<dyn std::error::Error + Send + Sync>> for AppError {
     fn from(err: Box<dyn std::error::Error + Send + Sync>) -> Self {
-        AppError::Other(err.to_string())
+        match err.downcast::<AppError>() {
+            Ok(err) => *err,
+            Err(err) => AppError::Other(err.to_string()),
+        }
     }
 }
 
@@ -127,6 +144,17 @@ impl IntoResponse for AppError {
             AppError::Unauthorized => (http::StatusCode::UNAUTHORIZED, self.to_string()),
             AppError::Forbidden => (http::StatusCode::FORBIDDEN, self.to_string()),
             AppError::NotFound(_) => (http::StatusCode::NOT_FOUND, self.to_string()),
+            AppError::BlockedTarget(_) => (http::StatusCode::FORBIDDEN, self.to_string()),
+            AppError::BadRequest(_) => (http::StatusCode::BAD_REQUEST, self.to_string()),
+            AppError::ServiceUnavailable(_) | AppError::ScrapeBudgetExhausted(_) => {
+                (http::StatusCode::SERVICE_UNAVAILABLE, self.to_string())
+            }
+            AppError::PayloadTooLarge(_) => (http::StatusCode::PAYLOAD_TOO_LARGE, self.to_string()),
+            AppError::UnsupportedMediaType(_) => {
+                (http::StatusCode::UNSUPPORTED_MEDIA_TYPE, self.to_string())
+            }
+            AppError::BadGateway(_) => (http::StatusCode::BAD_GATEWAY, self.to_string()),
+            AppError::TimeoutError(_) => (http::StatusCode::GATEWAY_TIMEOUT, self.to_string()),
             AppError::DatabaseError(_) => {
                 (http::StatusCode::INTERNAL_SERVER_ERROR, self.to_string())
             }
@@ -1,11 +1,16 @@
 //! JWT utilities for signing and verifying tokens.
 //!
-//! Uses the type-safe CONFIG for JWT secret.
+//! Uses the type-safe CONFIG for JWT secret. The encoding/decoding keys are
+//! built once and shared across requests; call [`rotate_jwt_secret`] to swap
+//! them when the secret changes.
 
 use crate::core::config::CONFIG;
 use crate::core::error::AppError;
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
@@ -15,25 +20,127 @@ pub struct Claims {
     pub exp: usize,
 }
 
+/// Key pair derived from a single HS256 secret.
+pub struct JwtKeys {
+    pub encoding: EncodingKey,
+    pub decoding: DecodingKey,
+}
+
+/// Holds the current [`JwtKeys`] behind a lock so they can be rotated at runtime.
+///
+/// Readers clone the inner `Arc` and release the lock immediately, so a rotation
+/// never blocks in-flight encode/decode calls.
+pub struct JwtKeyStore {
+    keys: RwLock<Arc<JwtKeys>>,
+    builds: AtomicUsize,
+}
+
+impl JwtKeyStore {
+    pub fn new(secret: &str) -> Self {
+        Self {
+            keys: RwLock::new(Arc::new(Self::build(secret))),
+            builds: AtomicUsize::new(1),
+        }
+    }
+
+    fn build(secret: &str) -> JwtKeys {
+        JwtKeys {
+            encoding: EncodingKey::from_secret(secret.as_bytes()),
+            decoding: DecodingKey::from_secret(secret.as_bytes()),
+        }
+    }
+
+    /// Returns the keys currently in use.
+    pub fn current(&self) -> Arc<JwtKeys> {
+        match self.keys.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Replaces the keys with ones derived from `secret`.
+    ///
+    /// Tokens signed with the previous secret stop validating immediately.
+    pub fn rotate(&self, secret: &str) {
+        let keys = Arc::new(Self::build(secret));
+        self.builds.fetch_add(1, Ordering::Relaxed);
+        match self.keys.write() {
+            Ok(mut guard) => *guard = keys,
+            Err(poisoned) => *poisoned.into_inner() = keys,
+        }
+    }
+
+    /// Number of times keys have been constructed for this store.
+    pub fn build_count(&self) -> usize {
+        self.builds.load(Ordering::Relaxed)
+    }
+
+    pub fn encode(&self, claims: &Claims) -> Result<String, AppError> {
+        encode(&Header::default(), claims, &self.current().encoding).map_err(AppError::from)
+    }
+
+    pub fn decode(&self, token: &str) -> Result<Claims, AppError> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.validate_exp = true;
+        decode::<Claims>(token, &self.current().decoding, &validation)
+            .map(|data| data.claims)
+            .map_err(AppError::from)
+    }
+}
+
+/// Process-wide key store, initialised from `CONFIG.jwt_secret` on first use.
+pub static JWT_KEYS: Lazy<JwtKeyStore> = Lazy::new(|| JwtKeyStore::new(&CONFIG.jwt_secret));
+
+/// Rotates the process-wide signing secret.
+pub fn rotate_jwt_secret(secret: &str) {
+    JWT_KEYS.rotate(secret);
+}
+
 pub fn encode_jwt(claims: Claims) -> Result<String, AppError> {
-    let secret = &CONFIG.jwt_secret;
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
-    )
-    .map_err(AppError::from)
+    JWT_KEYS.encode(&claims)
 }
 
 pub fn decode_jwt(token: &str) -> Result<Claims, AppError> {
-    let secret = &CONFIG.jwt_secret;
-    let mut validation = Validation::new(Algorithm::HS256);
-    validation.validate_exp = true;
-    decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(secret.as_bytes()),
-        &validation,
-    )
-    .map(|data| data.claims)
-    .map_err(AppError::from)
+    JWT_KEYS.decode(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims() -> Claims {
+        Claims {
+            user_id: "u1".to_string(),
+            email: "u1@example.com".to_string(),
+            name: "User".to_string(),
+            exp: (chrono::Utc::now().timestamp() + 3600) as usize,
+        }
+    }
+
+    #[test]
+    fn keys_are_built_once_and_reused() {
+        let store = JwtKeyStore::new("first-secret");
+        let first = store.current();
+
+        let token = store.encode(&claims()).unwrap();
+        for _ in 0..10 {
+            assert_eq!(store.decode(&token).unwrap().user_id, "u1");
+        }
+
+        assert_eq!(store.build_count(), 1);
+        assert!(Arc::ptr_eq(&first, &store.current()));
+    }
+
+    #[test]
+    fn rotation_invalidates_old_tokens() {
+        let store = JwtKeyStore::new("first-secret");
+        let old = store.encode(&claims()).unwrap();
+
+        store.rotate("second-secret");
+        assert_eq!(store.build_count(), 2);
+        assert!(store.decode(&old).is_err());
+
+        let new = store.encode(&claims()).unwrap();
+        assert_eq!(store.decode(&new).unwrap().email, "u1@example.com");
+    }
 }
@@ -11,7 +11,7 @@ pub mod ratelimit;
 pub use config::CONFIG;
 
 pub use jwt::{encode_jwt, decode_jwt, Claims};
-pub use ratelimit::rate_limit_middleware;
+pub use ratelimit::{keyed_rate_limit_middleware, rate_limit_middleware};
 
 // Re-export AppError from utils for backward compatibility
 pub use self::error::AppError;
@@ -2,10 +2,16 @@
 //!
 //! Provides token-bucket based rate limiting with configurable limits.
 //! Default: 1000 requests per second (1ms minimum interval).
+//!
+//! Also provides keyed rate limiting: authenticated users are counted per
+//! user id and anonymous callers per client IP, in separate Redis buckets.
+//!
+//! On top of that, the public proxy and scraper routes get their own per-IP
+//! sliding-window limits (see [`route_rate_limit_middleware`]).
 
 use axum::{
-    extract::Request,
-    http::StatusCode,
+    extract::{ConnectInfo, Request},
+    http::{header::RETRY_AFTER, HeaderValue, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
     Json,
@@ -17,9 +23,15 @@ use governor::{
 };
 use once_cell::sync::Lazy;
 use serde_json::json;
-use std::{num::NonZeroU32, sync::Arc, time::Duration};
+use std::{net::SocketAddr, num::NonZeroU32, sync::Arc, time::Duration};
 use tracing::warn;
 
+use crate::core::config::CONFIG;
+use crate::core::error::AppError;
+use crate::core::jwt::{decode_jwt, Claims};
+use crate::helpers::request::{bearer_token, forwarded_client_ip, TrustedProxies};
+use crate::infra::redis::REDIS_POOL;
+
 /// Global rate limiter instance.
 /// Configured for 1000 requests per second (1ms minimum interval).
 static GLOBAL_LIMITER: Lazy<Arc<GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>>> =
@@ -82,3 +94,601 @@ pub async fn rate_limit_middleware(req: Request, next: Next) -> Response {
         }
     }
 }
+
+// ============================================================================
+// Keyed (per-user / per-IP) rate limiting
+// ============================================================================
+
+/// Identity a request is counted against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RateLimitKey {
+    /// Authenticated user (JWT `user_id`).
+    User(String),
+    /// Anonymous client, identified by IP.
+    Ip(String),
+}
+
+impl RateLimitKey {
+    /// Redis bucket key. Users and IPs never share a bucket.
+    pub fn bucket(&self) -> String {
+        match self {
+            RateLimitKey::User(id) => format!("ratelimit:user:{}", id),
+            RateLimitKey::Ip(ip) => format!("ratelimit:ip:{}", ip),
+        }
+    }
+}
+
+/// Outcome of a keyed rate limit check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    /// Seconds until the current window resets.
+    pub reset_after_secs: u64,
+}
+
+/// Counter storage for keyed rate limiting.
+#[async_trait::async_trait]
+pub trait RateLimitBackend: Send + Sync {
+    /// Increment the counter for `bucket`, starting a new window of
+    /// `window_secs` if none exists. Returns `(count, seconds_until_reset)`.
+    async fn hit(&self, bucket: &str, window_secs: u64) -> Result<(u64, u64), AppError>;
+}
+
+/// Redis-backed fixed window counters (`INCR` + `EXPIRE`).
+pub struct RedisRateLimitBackend {
+    pool: deadpool_redis::Pool,
+}
+
+impl RedisRateLimitBackend {
+    pub fn new(pool: deadpool_redis::Pool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl RateLimitBackend for RedisRateLimitBackend {
+    async fn hit(&self, bucket: &str, window_secs: u64) -> Result<(u64, u64), AppError> {
+        use redis::AsyncCommands;
+
+        let mut conn = self.pool.get().await?;
+        let count: u64 = conn.incr(bucket, 1).await?;
+        if count == 1 {
+            let _: () = conn.expire(bucket, window_secs as i64).await?;
+            return Ok((count, window_secs));
+        }
+
+        let ttl: i64 = conn.ttl(bucket).await?;
+        if ttl < 0 {
+            // Key lost its expiry (e.g. EXPIRE failed earlier); re-arm it.
+            let _: () = conn.expire(bucket, window_secs as i64).await?;
+            return Ok((count, window_secs));
+        }
+        Ok((count, ttl as u64))
+    }
+}
+
+/// In-process fixed window counters (single instance / tests).
+#[derive(Default)]
+pub struct MemoryRateLimitBackend {
+    windows: dashmap::DashMap<String, (std::time::Instant, u64)>,
+}
+
+impl MemoryRateLimitBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl RateLimitBackend for MemoryRateLimitBackend {
+    async fn hit(&self, bucket: &str, window_secs: u64) -> Result<(u64, u64), AppError> {
+        let window = Duration::from_secs(window_secs);
+        let mut entry = self
+            .windows
+            .entry(bucket.to_string())
+            .or_insert_with(|| (std::time::Instant::now(), 0));
+
+        if entry.0.elapsed() >= window {
+            *entry = (std::time::Instant::now(), 0);
+        }
+        entry.1 += 1;
+
+        let reset_after = window.saturating_sub(entry.0.elapsed()).as_secs();
+        Ok((entry.1, reset_after))
+    }
+}
+
+/// Rate limiter with separate limits for authenticated users and anonymous IPs.
+pub struct KeyedRateLimiter<B: RateLimitBackend> {
+    backend: B,
+    user_limit: u32,
+    ip_limit: u32,
+    window_secs: u64,
+}
+
+impl<B: RateLimitBackend> KeyedRateLimiter<B> {
+    pub fn new(backend: B, user_limit: u32, ip_limit: u32, window_secs: u64) -> Self {
+        Self {
+            backend,
+            user_limit,
+            ip_limit,
+            window_secs: window_secs.max(1),
+        }
+    }
+
+    /// Limit that applies to the given key.
+    pub fn limit_for(&self, key: &RateLimitKey) -> u32 {
+        match key {
+            RateLimitKey::User(_) => self.user_limit,
+            RateLimitKey::Ip(_) => self.ip_limit,
+        }
+    }
+
+    /// Count a request against `key` and decide whether it may proceed.
+    ///
+    /// Fails open if the backend is unavailable so a Redis outage does not
+    /// take the whole API down.
+    pub async fn check(&self, key: &RateLimitKey) -> RateLimitDecision {
+        let limit = self.limit_for(key);
+        match self.backend.hit(&key.bucket(), self.window_secs).await {
+            Ok((count, reset_after_secs)) => RateLimitDecision {
+                allowed: count <= limit as u64,
+                limit,
+                remaining: (limit as u64).saturating_sub(count) as u32,
+                reset_after_secs,
+            },
+            Err(e) => {
+                warn!("Rate limit backend error for {}: {}", key.bucket(), e);
+                RateLimitDecision {
+                    allowed: true,
+                    limit,
+                    remaining: limit,
+                    reset_after_secs: self.window_secs,
+                }
+            }
+        }
+    }
+}
+
+/// Global keyed rate limiter, configured from `CONFIG.rate_limit`.
+static KEYED_LIMITER: Lazy<KeyedRateLimiter<RedisRateLimitBackend>> = Lazy::new(|| {
+    let settings = &CONFIG.rate_limit;
+    KeyedRateLimiter::new(
+        RedisRateLimitBackend::new(REDIS_POOL.clone()),
+        settings.user_requests,
+        settings.ip_requests,
+        settings.window_seconds,
+    )
+});
+
+/// Resolve the rate limit identity for a request.
+///
+/// Uses the JWT `user_id` when a valid bearer token is present (or claims were
+/// already attached by the auth layer), otherwise the client IP.
+pub fn rate_limit_key(req: &Request) -> RateLimitKey {
+    if let Some(claims) = req.extensions().get::<Claims>() {
+        return RateLimitKey::User(claims.user_id.clone());
+    }
+
+    if let Some(claims) = bearer_token(req.headers()).and_then(|t| decode_jwt(&t).ok()) {
+        return RateLimitKey::User(claims.user_id);
+    }
+
+    RateLimitKey::Ip(request_ip(req))
+}
+
+/// Per-user / per-IP rate limiting middleware.
+///
+/// Returns 429 Too Many Requests once the caller's bucket is exhausted and
+/// sets `X-RateLimit-*` headers on every response.
+pub async fn keyed_rate_limit_middleware(req: Request, next: Next) -> Response {
+    if !CONFIG.rate_limit.enabled {
+        return next.run(req).await;
+    }
+
+    let key = rate_limit_key(&req);
+    let decision = KEYED_LIMITER.check(&key).await;
+
+    let mut response = if decision.allowed {
+        next.run(req).await
+    } else {
+        warn!("Rate limit exceeded for {}", key.bucket());
+        (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(json!({
+                "error": "Too many requests",
+                "code": "RATE_LIMIT_EXCEEDED",
+                "retry_after_ms": decision.reset_after_secs * 1000
+            })),
+        )
+            .into_response()
+    };
+
+    let headers = response.headers_mut();
+    headers.insert("X-RateLimit-Limit", HeaderValue::from(decision.limit));
+    headers.insert("X-RateLimit-Remaining", HeaderValue::from(decision.remaining));
+    headers.insert("X-RateLimit-Reset", HeaderValue::from(decision.reset_after_secs));
+    if !decision.allowed {
+        headers.insert(RETRY_AFTER, HeaderValue::from(decision.reset_after_secs));
+    }
+
+    response
+}
+
+// ============================================================================
+// Per-route sliding window limits
+// ============================================================================
+
+/// Seconds since the Unix epoch; swappable so window rollover can be tested.
+pub trait Clock: Send + Sync {
+    fn now_secs(&self) -> u64;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_secs(&self) -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    }
+}
+
+/// Counters addressed by explicit window keys.
+#[async_trait::async_trait]
+pub trait WindowCounterStore: Send + Sync {
+    /// Increment `key`, expiring it after `ttl_secs`. Returns the new count.
+    async fn incr(&self, key: &str, ttl_secs: u64) -> Result<u64, AppError>;
+    /// Current value of `key`, 0 if missing.
+    async fn get(&self, key: &str) -> Result<u64, AppError>;
+}
+
+#[async_trait::async_trait]
+impl WindowCounterStore for RedisRateLimitBackend {
+    async fn incr(&self, key: &str, ttl_secs: u64) -> Result<u64, AppError> {
+        use redis::AsyncCommands;
+
+        let mut conn = self.pool.get().await?;
+        let count: u64 = conn.incr(key, 1).await?;
+        if count == 1 {
+            let _: () = conn.expire(key, ttl_secs as i64).await?;
+        }
+        Ok(count)
+    }
+
+    async fn get(&self, key: &str) -> Result<u64, AppError> {
+        use redis::AsyncCommands;
+
+        let mut conn = self.pool.get().await?;
+        let count: Option<u64> = conn.get(key).await?;
+        Ok(count.unwrap_or(0))
+    }
+}
+
+#[async_trait::async_trait]
+impl WindowCounterStore for MemoryRateLimitBackend {
+    async fn incr(&self, key: &str, _ttl_secs: u64) -> Result<u64, AppError> {
+        let mut entry = self
+            .windows
+            .entry(key.to_string())
+            .or_insert_with(|| (std::time::Instant::now(), 0));
+        entry.1 += 1;
+        Ok(entry.1)
+    }
+
+    async fn get(&self, key: &str) -> Result<u64, AppError> {
+        Ok(self.windows.get(key).map(|e| e.1).unwrap_or(0))
+    }
+}
+
+/// Public endpoint groups with their own per-IP limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteGroup {
+    Proxy,
+    Scraper,
+}
+
+impl RouteGroup {
+    /// Group a request path belongs to, if it is rate limited per route.
+    /// Versioned paths (`/api/v1/...`) share their unversioned path's group.
+    pub fn for_path(path: &str) -> Option<Self> {
+        let path = crate::routing::unversioned_path(path);
+        if path.starts_with("/api/proxy/") {
+            Some(RouteGroup::Proxy)
+        } else if path.starts_with("/api/anime") || path.starts_with("/api/komik") {
+            Some(RouteGroup::Scraper)
+        } else {
+            None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RouteGroup::Proxy => "proxy",
+            RouteGroup::Scraper => "scraper",
+        }
+    }
+
+    /// Requests per minute from `CONFIG.rate_limit`.
+    pub fn limit(&self) -> u32 {
+        match self {
+            RouteGroup::Proxy => CONFIG.rate_limit.proxy_ip_requests,
+            RouteGroup::Scraper => CONFIG.rate_limit.scraper_ip_requests,
+        }
+    }
+}
+
+/// Sliding window estimate: the previous window's count weighted by how much
+/// of it still overlaps the trailing window, plus the current count.
+pub fn sliding_window_count(previous: u64, current: u64, elapsed_in_window: u64, window_secs: u64) -> u64 {
+    let overlap = window_secs.saturating_sub(elapsed_in_window);
+    previous * overlap / window_secs.max(1) + current
+}
+
+/// Per-IP sliding window limiter over fixed window counters.
+///
+/// Unlike a plain fixed window, a client can't double its rate by bursting
+/// on both sides of a window boundary.
+pub struct SlidingWindowLimiter<S: WindowCounterStore, C: Clock> {
+    store: S,
+    clock: C,
+    window_secs: u64,
+}
+
+impl<S: WindowCounterStore, C: Clock> SlidingWindowLimiter<S, C> {
+    pub fn new(store: S, clock: C, window_secs: u64) -> Self {
+        Self {
+            store,
+            clock,
+            window_secs: window_secs.max(1),
+        }
+    }
+
+    /// Count a request from `ip` against `group` with `limit` per window.
+    /// Fails open when the store is unavailable.
+    pub async fn check(&self, group: &str, ip: &str, limit: u32) -> RateLimitDecision {
+        let now = self.clock.now_secs();
+        let index = now / self.window_secs;
+        let elapsed = now % self.window_secs;
+        let reset_after_secs = (self.window_secs - elapsed).max(1);
+        let key = |i: u64| format!("ratelimit:route:{}:{}:{}", group, ip, i);
+
+        let counts = async {
+            let current = self.store.incr(&key(index), self.window_secs * 2).await?;
+            let previous = match index.checked_sub(1) {
+                Some(prev) => self.store.get(&key(prev)).await?,
+                None => 0,
+            };
+            Ok::<_, AppError>((previous, current))
+        };
+
+        match counts.await {
+            Ok((previous, current)) => {
+                let estimate = sliding_window_count(previous, current, elapsed, self.window_secs);
+                RateLimitDecision {
+                    allowed: estimate <= limit as u64,
+                    limit,
+                    remaining: (limit as u64).saturating_sub(estimate) as u32,
+                    reset_after_secs,
+                }
+            }
+            Err(e) => {
+                warn!("Route rate limit store error for {}: {}", group, e);
+                RateLimitDecision {
+                    allowed: true,
+                    limit,
+                    remaining: limit,
+                    reset_after_secs,
+                }
+            }
+        }
+    }
+}
+
+static ROUTE_LIMITER: Lazy<SlidingWindowLimiter<RedisRateLimitBackend, SystemClock>> = Lazy::new(|| {
+    SlidingWindowLimiter::new(RedisRateLimitBackend::new(REDIS_POOL.clone()), SystemClock, 60)
+});
+
+static TRUSTED_PROXIES: Lazy<TrustedProxies> =
+    Lazy::new(|| TrustedProxies::parse(&CONFIG.trusted_proxies));
+
+/// The caller's address: the socket peer, or the client a trusted proxy in
+/// front of us reports (see [`forwarded_client_ip`]).
+pub fn request_ip(req: &Request) -> String {
+    request_ip_with(req, &TRUSTED_PROXIES)
+}
+
+fn request_ip_with(req: &Request, trusted: &TrustedProxies) -> String {
+    req.extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ci| forwarded_client_ip(ci.0.ip(), req.headers(), trusted).to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Per-IP limits for the proxy and scraper routes, configured via
+/// `CONFIG.rate_limit.{proxy,scraper}_ip_requests` (requests per minute).
+///
+/// Other paths pass straight through.
+pub async fn route_rate_limit_middleware(req: Request, next: Next) -> Response {
+    if !CONFIG.rate_limit.enabled {
+        return next.run(req).await;
+    }
+    let Some(group) = RouteGroup::for_path(req.uri().path()) else {
+        return next.run(req).await;
+    };
+
+    let ip = request_ip(&req);
+    let decision = ROUTE_LIMITER.check(group.name(), &ip, group.limit()).await;
+
+    let mut response = if decision.allowed {
+        next.run(req).await
+    } else {
+        warn!("{} rate limit exceeded for {}", group.name(), ip);
+        (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(json!({
+                "error": "Too many requests",
+                "code": "RATE_LIMIT_EXCEEDED",
+                "retry_after_ms": decision.reset_after_secs * 1000
+            })),
+        )
+            .into_response()
+    };
+
+    let headers = response.headers_mut();
+    headers.insert("X-RateLimit-Limit", HeaderValue::from(decision.limit));
+    headers.insert("X-RateLimit-Remaining", HeaderValue::from(decision.remaining));
+    if !decision.allowed {
+        headers.insert(RETRY_AFTER, HeaderValue::from(decision.reset_after_secs));
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter() -> KeyedRateLimiter<MemoryRateLimitBackend> {
+        KeyedRateLimiter::new(MemoryRateLimitBackend::new(), 5, 2, 60)
+    }
+
+    #[test]
+    fn test_bucket_keys_are_separate() {
+        let user = RateLimitKey::User("42".to_string());
+        let ip = RateLimitKey::Ip("42".to_string());
+        assert_ne!(user.bucket(), ip.bucket());
+    }
+
+    #[tokio::test]
+    async fn test_authenticated_user_gets_higher_limit() {
+        let limiter = limiter();
+        let user = RateLimitKey::User("user-1".to_string());
+
+        for _ in 0..5 {
+            assert!(limiter.check(&user).await.allowed);
+        }
+        let decision = limiter.check(&user).await;
+        assert!(!decision.allowed);
+        assert_eq!(decision.limit, 5);
+        assert_eq!(decision.remaining, 0);
+    }
+
+    #[tokio::test]
+    async fn test_anonymous_ip_hits_lower_limit_independently() {
+        let limiter = limiter();
+        let ip = RateLimitKey::Ip("203.0.113.7".to_string());
+        let user = RateLimitKey::User("user-1".to_string());
+
+        assert!(limiter.check(&ip).await.allowed);
+        assert!(limiter.check(&ip).await.allowed);
+        assert!(!limiter.check(&ip).await.allowed);
+
+        // Exhausting the IP bucket must not touch the user bucket
+        let decision = limiter.check(&user).await;
+        assert!(decision.allowed);
+        assert_eq!(decision.remaining, 4);
+
+        // Another anonymous IP has its own bucket
+        let other_ip = RateLimitKey::Ip("198.51.100.1".to_string());
+        assert!(limiter.check(&other_ip).await.allowed);
+    }
+
+    struct MockClock(std::sync::atomic::AtomicU64);
+
+    impl MockClock {
+        fn at(secs: u64) -> Self {
+            Self(std::sync::atomic::AtomicU64::new(secs))
+        }
+
+        fn set(&self, secs: u64) {
+            self.0.store(secs, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    impl Clock for &MockClock {
+        fn now_secs(&self) -> u64 {
+            self.0.load(std::sync::atomic::Ordering::SeqCst)
+        }
+    }
+
+    fn request_from(peer: &str, forwarded_for: &str) -> Request {
+        let mut req = Request::builder()
+            .header("x-forwarded-for", forwarded_for)
+            .body(axum::body::Body::empty())
+            .unwrap();
+        req.extensions_mut()
+            .insert(ConnectInfo(SocketAddr::new(peer.parse().unwrap(), 4000)));
+        req
+    }
+
+    #[test]
+    fn test_spoofed_forwarded_for_does_not_change_ip() {
+        let trusted = TrustedProxies::parse(&["10.0.0.0/8"]);
+
+        // Behind our proxy: only the hop it appended counts.
+        let honest = request_from("10.0.0.2", "203.0.113.7");
+        let spoofed = request_from("10.0.0.2", "198.51.100.1, 203.0.113.7");
+        assert_eq!(request_ip_with(&honest, &trusted), "203.0.113.7");
+        assert_eq!(request_ip_with(&spoofed, &trusted), "203.0.113.7");
+
+        // Direct connections cannot claim another address at all.
+        let direct = request_from("192.0.2.9", "198.51.100.1");
+        assert_eq!(request_ip_with(&direct, &trusted), "192.0.2.9");
+    }
+
+    #[test]
+    fn test_route_groups() {
+        assert_eq!(RouteGroup::for_path("/api/proxy/croxy"), Some(RouteGroup::Proxy));
+        assert_eq!(RouteGroup::for_path("/api/anime2/detail/x"), Some(RouteGroup::Scraper));
+        assert_eq!(RouteGroup::for_path("/api/komik/chapter"), Some(RouteGroup::Scraper));
+        assert_eq!(RouteGroup::for_path("/api/auth/login"), None);
+        assert_eq!(RouteGroup::for_path("/api/v1/anime/detail/x"), Some(RouteGroup::Scraper));
+        assert_eq!(RouteGroup::for_path("/api/v2/proxy/croxy"), Some(RouteGroup::Proxy));
+    }
+
+    #[tokio::test]
+    async fn test_sliding_window_rollover() {
+        let clock = MockClock::at(600);
+        let limiter = SlidingWindowLimiter::new(MemoryRateLimitBackend::new(), &clock, 60);
+        let ip = "203.0.113.7";
+
+        assert!(limiter.check("proxy", ip, 2).await.allowed);
+        assert!(limiter.check("proxy", ip, 2).await.allowed);
+        let denied = limiter.check("proxy", ip, 2).await;
+        assert!(!denied.allowed);
+        assert_eq!(denied.remaining, 0);
+        assert_eq!(denied.reset_after_secs, 60);
+
+        // Right after rollover the previous window still counts in full, so
+        // bursting across the boundary is not allowed.
+        clock.set(660);
+        assert!(!limiter.check("proxy", ip, 2).await.allowed);
+
+        // Halfway through, the previous window (3 hits) weighs 1.
+        let clock_mid = MockClock::at(690);
+        let limiter = SlidingWindowLimiter::new(MemoryRateLimitBackend::new(), &clock_mid, 60);
+        for _ in 0..3 {
+            limiter.check("proxy", ip, 2).await;
+        }
+        clock_mid.set(750);
+        let decision = limiter.check("proxy", ip, 2).await;
+        assert!(decision.allowed);
+        assert_eq!(decision.reset_after_secs, 30);
+        assert!(!limiter.check("proxy", ip, 2).await.allowed);
+
+        // Other groups and IPs are independent.
+        assert!(limiter.check("scraper", ip, 2).await.allowed);
+        assert!(limiter.check("proxy", "198.51.100.1", 2).await.allowed);
+    }
+
+    #[test]
+    fn test_sliding_window_count() {
+        assert_eq!(sliding_window_count(10, 0, 0, 60), 10);
+        assert_eq!(sliding_window_count(10, 1, 30, 60), 6);
+        assert_eq!(sliding_window_count(10, 1, 59, 60), 1);
+    }
+}
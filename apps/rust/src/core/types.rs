@@ -7,6 +7,9 @@ pub struct ApiResponse<T> {
     pub message: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<T>,
+    /// `x-request-id` of the failed request; only on errors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl<T> ApiResponse<T> {
@@ -15,6 +18,7 @@ impl<T> ApiResponse<T> {
             success: true,
             message: None,
             data: Some(data),
+            request_id: None,
         }
     }
 
@@ -23,6 +27,7 @@ impl<T> ApiResponse<T> {
             success: false,
             message: Some(message),
             data: None,
+            request_id: crate::observability::request_id::current_request_id(),
         }
     }
 }
@@ -0,0 +1,47 @@
+//! Registration of the application's shared infrastructure.
+//!
+//! Bootstrap builds one [`AppServices`] and hands the resulting container to
+//! `AppState`; handlers then take what they need with
+//! [`Inject`](super::Inject) instead of reaching into the whole state.
+
+use deadpool_redis::Pool;
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+
+use super::{ContainerBuilder, ServiceContainer, ServiceProvider};
+use crate::circuit_breaker::SourceBreakers;
+use crate::infra::HttpClient;
+use crate::storage::Storage;
+
+/// Services every instance shares.
+///
+/// Registered as `DatabaseConnection`, `deadpool_redis::Pool`, `Storage`
+/// (only when object storage is configured), `HttpClient` and
+/// `SourceBreakers`.
+#[derive(Clone)]
+pub struct AppServices {
+    pub db: Arc<DatabaseConnection>,
+    pub redis_pool: Pool,
+    pub storage: Option<Arc<Storage>>,
+    pub http_client: Arc<HttpClient>,
+    pub source_breakers: Arc<SourceBreakers>,
+}
+
+impl ServiceProvider for AppServices {
+    fn register(&self, container: &ServiceContainer) {
+        container.register_arc(self.db.clone());
+        container.register(self.redis_pool.clone());
+        if let Some(storage) = &self.storage {
+            container.register_arc(storage.clone());
+        }
+        container.register_arc(self.http_client.clone());
+        container.register_arc(self.source_breakers.clone());
+    }
+}
+
+impl AppServices {
+    /// A container holding these services.
+    pub fn into_container(self) -> ServiceContainer {
+        ContainerBuilder::new().with_provider(self).build()
+    }
+}
@@ -0,0 +1,97 @@
+//! Axum extractor resolving a service from the container.
+
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use super::ServiceContainer;
+use crate::core::error::AppError;
+
+/// Router state that carries a [`ServiceContainer`].
+pub trait HasServices {
+    fn services(&self) -> &ServiceContainer;
+}
+
+impl HasServices for Arc<ServiceContainer> {
+    fn services(&self) -> &ServiceContainer {
+        self
+    }
+}
+
+/// A `T` resolved from the state's [`ServiceContainer`].
+///
+/// Works with any router state the container can be taken from, so a handler
+/// written against `Inject<Storage>` runs unchanged on `Arc<AppState>` and on a
+/// bare container holding a test double. Rejects with 503 when `T` was never
+/// registered (e.g. storage that isn't configured).
+pub struct Inject<T>(pub Arc<T>);
+
+impl<T> Deref for Inject<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+/// `T`'s unqualified type name, for error messages.
+fn short_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+impl<S, T> FromRequestParts<S> for Inject<T>
+where
+    S: HasServices + Send + Sync,
+    T: Send + Sync + 'static,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(_: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        state
+            .services()
+            .resolve::<T>()
+            .map(Inject)
+            .ok_or_else(|| AppError::ServiceUnavailable(format!("{} is not configured", short_name::<T>())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Router};
+    use http::StatusCode;
+    use tower::ServiceExt;
+
+    struct Greeter(&'static str);
+
+    async fn greet(greeter: Inject<Greeter>) -> &'static str {
+        greeter.0.0
+    }
+
+    async fn status_of(container: ServiceContainer) -> (StatusCode, String) {
+        let app = Router::new().route("/", get(greet)).with_state(Arc::new(container));
+        let response = app
+            .oneshot(axum::http::Request::get("/").body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8_lossy(&body).into_owned())
+    }
+
+    #[tokio::test]
+    async fn registered_services_are_injected() {
+        let container = ServiceContainer::new();
+        container.register(Greeter("hello"));
+        assert_eq!(status_of(container).await, (StatusCode::OK, "hello".to_string()));
+    }
+
+    #[tokio::test]
+    async fn missing_services_are_503() {
+        let (status, body) = status_of(ServiceContainer::new()).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(body.contains("Greeter is not configured"), "{}", body);
+    }
+}
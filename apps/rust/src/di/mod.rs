@@ -3,6 +3,10 @@
 //! Provides a type-safe container for registering and resolving services,
 //! similar to Laravel's Service Container or NestJS's DI system.
 
+pub mod app;
 pub mod container;
+pub mod inject;
 
-pub use container::{ServiceContainer, ServiceProvider};
+pub use app::AppServices;
+pub use container::{ContainerBuilder, ServiceContainer, ServiceProvider};
+pub use inject::{HasServices, Inject};
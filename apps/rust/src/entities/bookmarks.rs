@@ -13,6 +13,7 @@ pub struct Model {
     pub slug: String,
     pub title: String,
     pub poster: String,
+    pub episode_or_chapter: Option<String>,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
@@ -20,4 +20,5 @@ pub mod role;
 pub mod role_permission;
 pub mod session;
 pub mod user;
+pub mod user_preferences;
 pub mod user_role;
@@ -18,4 +18,5 @@ pub use super::role::Entity as Role;
 pub use super::role_permission::Entity as RolePermission;
 pub use super::session::Entity as Session;
 pub use super::user::Entity as User;
+pub use super::user_preferences::Entity as UserPreferences;
 pub use super::user_role::Entity as UserRole;
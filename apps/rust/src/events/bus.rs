@@ -1,12 +1,22 @@
 //! Event bus implementation.
 
 use async_trait::async_trait;
+use futures::Stream;
+use serde::Serialize;
 
 use std::{any::TypeId, collections::HashMap, sync::Arc};
 use tokio::sync::{broadcast, RwLock};
 use tracing::{debug, info};
 
 /// Trait for events that can be published.
+///
+/// Each event is its own type; subscribers pick the types they care about.
+/// Domain events published by the app:
+///
+/// | Event | Name | Published by |
+/// |-------|------|--------------|
+/// | [`FileUploaded`] | `file.uploaded` | `POST /api/uploader` after the file is stored |
+/// | [`ChatMessageSaved`] | `chat.message_saved` | `services::chat::save_message` after the insert |
 pub trait Event: Clone + Send + Sync + 'static {
     /// Event name for logging/debugging.
     const NAME: &'static str;
@@ -52,29 +62,42 @@ impl EventBus {
         // Check if channel exists
         {
             let channels = self.channels.read().await;
-            if let Some(sender) = channels.get(&type_id) {
-                if let Some(tx) = sender.downcast_ref::<broadcast::Sender<E>>() {
-                    return tx.subscribe();
-                }
+            if let Some(tx) = channels
+                .get(&type_id)
+                .and_then(|sender| sender.downcast_ref::<broadcast::Sender<E>>())
+            {
+                return tx.subscribe();
             }
         }
 
-        // Create new channel
-        let (tx, rx) = broadcast::channel::<E>(100);
-        {
-            let mut channels = self.channels.write().await;
-            channels.insert(type_id, Box::new(tx));
+        // Create the channel unless another subscriber raced us to it
+        let mut channels = self.channels.write().await;
+        let sender = channels
+            .entry(type_id)
+            .or_insert_with(|| Box::new(broadcast::channel::<E>(100).0));
+        match sender.downcast_ref::<broadcast::Sender<E>>() {
+            Some(tx) => tx.subscribe(),
+            None => broadcast::channel::<E>(1).1,
         }
+    }
 
-        // Re-get the receiver from the stored sender
-        let channels = self.channels.read().await;
-        if let Some(sender) = channels.get(&type_id) {
-            if let Some(tx) = sender.downcast_ref::<broadcast::Sender<E>>() {
-                return tx.subscribe();
+    /// Subscribe to events of a specific type as a stream.
+    ///
+    /// Events missed because the subscriber fell behind are skipped with a
+    /// warning; the stream ends when the bus is dropped.
+    pub async fn subscribe_stream<E: Event>(&self) -> impl Stream<Item = E> + Send + 'static {
+        let rx = self.subscribe::<E>().await;
+        futures::stream::unfold(rx, |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => return Some((event, rx)),
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        tracing::warn!("{} subscriber lagged by {} events", E::NAME, n);
+                    }
+                }
             }
-        }
-
-        rx
+        })
     }
 
     /// Register a handler for a specific event type.
@@ -109,7 +132,7 @@ impl Default for EventBus {
 
 // Common events
 /// User registered event.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct UserRegistered {
     pub user_id: String,
     pub email: String,
@@ -142,3 +165,72 @@ pub struct OrderCreated {
 impl Event for OrderCreated {
     const NAME: &'static str = "order.created";
 }
+
+/// A file was stored by the uploader.
+#[derive(Clone, Debug, Serialize)]
+pub struct FileUploaded {
+    pub url: String,
+    /// Storage key of the file.
+    pub path: String,
+    pub size: usize,
+    pub mime: String,
+    pub user_id: String,
+}
+
+impl Event for FileUploaded {
+    const NAME: &'static str = "file.uploaded";
+}
+
+/// A chat message was persisted.
+#[derive(Clone, Debug, Serialize)]
+pub struct ChatMessageSaved {
+    pub room: String,
+    pub id: String,
+    pub user_id: String,
+}
+
+impl Event for ChatMessageSaved {
+    const NAME: &'static str = "chat.message_saved";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn subscriber_receives_published_event() {
+        let bus = EventBus::new();
+        let stream = bus.subscribe_stream::<ChatMessageSaved>().await;
+        let mut stream = Box::pin(stream);
+        let mut other = bus.subscribe::<FileUploaded>().await;
+
+        bus.publish(ChatMessageSaved {
+            room: "lobby".to_string(),
+            id: "m1".to_string(),
+            user_id: "u1".to_string(),
+        })
+        .await;
+
+        let event = tokio::time::timeout(std::time::Duration::from_secs(1), stream.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!((event.room.as_str(), event.id.as_str()), ("lobby", "m1"));
+
+        // Other event types are not delivered.
+        assert!(other.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn subscribers_share_one_channel() {
+        let bus = EventBus::new();
+        let mut a = bus.subscribe::<UserLoggedIn>().await;
+        let mut b = bus.subscribe::<UserLoggedIn>().await;
+
+        bus.publish(UserLoggedIn { user_id: "u1".to_string(), ip_address: None }).await;
+
+        assert_eq!(a.recv().await.unwrap().user_id, "u1");
+        assert_eq!(b.recv().await.unwrap().user_id, "u1");
+    }
+}
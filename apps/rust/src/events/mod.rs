@@ -1,7 +1,14 @@
 //! Event system for pub/sub communication between modules.
 //!
 //! Provides a simple in-process event bus for decoupled communication.
+//! [`EVENT_BUS`] is the process-wide bus the handlers publish domain events
+//! ([`FileUploaded`], [`ChatMessageSaved`]) to; see [`Event`] for the list.
 
 pub mod bus;
 
-pub use bus::{Event, EventBus, EventHandler};
+use once_cell::sync::Lazy;
+
+pub use bus::{ChatMessageSaved, Event, EventBus, EventHandler, FileUploaded};
+
+/// Process-wide event bus.
+pub static EVENT_BUS: Lazy<EventBus> = Lazy::new(EventBus::new);
@@ -180,9 +180,74 @@ where
     }
 }
 
+// ============================================================================
+// Shared validators
+// ============================================================================
+
+/// Rejects empty or whitespace-only strings.
+///
+/// Use with `#[validate(custom(function = "not_blank"))]`.
+pub fn not_blank(value: &str) -> Result<(), validator::ValidationError> {
+    if value.trim().is_empty() {
+        return Err(validator::ValidationError::new("blank")
+            .with_message("must not be empty".into()));
+    }
+    Ok(())
+}
+
 // ============================================================================
 // Convenience type aliases
 // ============================================================================
 
 /// Result type for handlers that may return a validation error
 pub type ValidatedResult<T> = Result<T, ValidationError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::to_bytes, routing::get, Router};
+    use serde::Deserialize;
+    use tower::ServiceExt;
+
+    #[derive(Deserialize, Validate)]
+    struct Search {
+        #[serde(default)]
+        #[validate(custom(function = "not_blank"))]
+        q: String,
+        #[validate(range(min = 1, message = "page must be at least 1"))]
+        page: Option<u32>,
+    }
+
+    async fn status_and_body(uri: &str) -> (StatusCode, serde_json::Value) {
+        let app = Router::new().route(
+            "/search",
+            get(|ValidatedQuery(s): ValidatedQuery<Search>| async move { s.q }),
+        );
+        let response = app
+            .oneshot(Request::builder().uri(uri).body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn missing_or_blank_query_is_422_with_field_errors() {
+        for uri in ["/search", "/search?q=%20%20", "/search?q=one&page=0"] {
+            let (status, body) = status_and_body(uri).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{}", uri);
+            assert_eq!(body["code"], "VALIDATION_ERROR");
+        }
+
+        let (_, body) = status_and_body("/search?page=0").await;
+        assert_eq!(body["details"]["q"][0], "must not be empty");
+        assert_eq!(body["details"]["page"][0], "page must be at least 1");
+    }
+
+    #[tokio::test]
+    async fn valid_query_passes_through() {
+        let (status, _) = status_and_body("/search?q=naruto&page=2").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
@@ -0,0 +1,181 @@
+//! Serving with a bounded drain of in-flight requests on shutdown.
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+    Router,
+};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// Number of requests currently being handled.
+#[derive(Clone, Default)]
+pub struct InFlight(Arc<AtomicUsize>);
+
+impl InFlight {
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+struct InFlightGuard(InFlight);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        (self.0).0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Middleware counting a request as in flight until its response is produced.
+pub async fn track_in_flight(State(in_flight): State<InFlight>, req: Request, next: Next) -> Response {
+    in_flight.0.fetch_add(1, Ordering::SeqCst);
+    let _guard = InFlightGuard(in_flight);
+    next.run(req).await
+}
+
+/// What happened to the requests that were running when shutdown began.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrainReport {
+    /// Requests in flight when the shutdown signal fired.
+    pub in_flight: usize,
+    /// Requests that finished within the drain timeout.
+    pub drained: usize,
+    /// Requests cut off when the timeout elapsed.
+    pub abandoned: usize,
+}
+
+/// Serves `router` until `signal` resolves, then stops accepting connections
+/// and waits up to `drain_timeout` for in-flight requests before returning.
+pub async fn serve_with_drain<F>(
+    listener: TcpListener,
+    router: Router,
+    signal: F,
+    drain_timeout: Duration,
+) -> std::io::Result<DrainReport>
+where
+    F: Future<Output = ()> + Send,
+{
+    let in_flight = InFlight::default();
+    let app = router.layer(axum::middleware::from_fn_with_state(
+        in_flight.clone(),
+        track_in_flight,
+    ));
+
+    let stop = CancellationToken::new();
+    let stopped = stop.clone();
+    let mut server = tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+            .with_graceful_shutdown(async move { stopped.cancelled().await })
+            .await
+    });
+
+    tokio::select! {
+        result = &mut server => {
+            result.map_err(std::io::Error::other)??;
+            return Ok(DrainReport::default());
+        }
+        _ = signal => {}
+    }
+
+    let pending = in_flight.count();
+    info!(
+        "⏳ Draining {} in-flight request(s) (timeout {}s)...",
+        pending,
+        drain_timeout.as_secs()
+    );
+    stop.cancel();
+
+    match tokio::time::timeout(drain_timeout, &mut server).await {
+        Ok(result) => result.map_err(std::io::Error::other)??,
+        Err(_) => server.abort(),
+    }
+
+    let abandoned = in_flight.count();
+    let report = DrainReport {
+        in_flight: pending,
+        drained: pending.saturating_sub(abandoned),
+        abandoned,
+    };
+    if abandoned > 0 {
+        warn!(
+            "Drain timed out: {} request(s) drained, {} abandoned",
+            report.drained, abandoned
+        );
+    } else {
+        info!("✓ Drained {} in-flight request(s)", report.drained);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use tokio::sync::{oneshot, Notify};
+
+    /// Serves a `/slow` route taking 300ms; the returned `Notify` fires once
+    /// a request is inside it, so shutdown can be sent with it in flight.
+    async fn start(
+        drain_timeout: Duration,
+    ) -> (String, Arc<Notify>, oneshot::Sender<()>, tokio::task::JoinHandle<DrainReport>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let entered = Arc::new(Notify::new());
+        let slow = {
+            let entered = entered.clone();
+            move || async move {
+                entered.notify_one();
+                tokio::time::sleep(Duration::from_millis(300)).await;
+                "done"
+            }
+        };
+        let (tx, rx) = oneshot::channel::<()>();
+        let server = tokio::spawn(async move {
+            let signal = async {
+                let _ = rx.await;
+            };
+            serve_with_drain(listener, Router::new().route("/slow", get(slow)), signal, drain_timeout)
+                .await
+                .unwrap()
+        });
+        (base, entered, tx, server)
+    }
+
+    #[tokio::test]
+    async fn slow_request_completes_after_shutdown() {
+        let (base, entered, shutdown, server) = start(Duration::from_secs(5)).await;
+
+        let request = tokio::spawn(reqwest::get(format!("{}/slow", base)));
+        entered.notified().await;
+        shutdown.send(()).unwrap();
+
+        let response = request.await.unwrap().unwrap();
+        assert_eq!(response.text().await.unwrap(), "done");
+
+        let report = server.await.unwrap();
+        assert_eq!(report, DrainReport { in_flight: 1, drained: 1, abandoned: 0 });
+
+        // No longer accepting connections.
+        assert!(reqwest::get(format!("{}/slow", base)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn drain_is_bounded_by_timeout() {
+        let (base, entered, shutdown, server) = start(Duration::from_millis(50)).await;
+
+        let request = tokio::spawn(reqwest::get(format!("{}/slow", base)));
+        entered.notified().await;
+        shutdown.send(()).unwrap();
+
+        let report = server.await.unwrap();
+        assert_eq!(report.abandoned, 1);
+        let _ = request.await;
+    }
+}
@@ -1,7 +1,9 @@
 //! Graceful shutdown utilities.
 
 pub mod cleanup;
+pub mod drain;
 pub mod shutdown;
 
+pub use drain::{serve_with_drain, DrainReport, InFlight};
 pub use cleanup::{wait_for_shutdown_and_cleanup, ShutdownCoordinator, ShutdownHandle};
 pub use shutdown::{shutdown_signal, GracefulShutdown};
@@ -70,8 +70,8 @@ impl QueryRoot {
 
         let users = user::Entity::find()
             .order_by_asc(user::Column::Id)
-            .offset(offset as u64)
-            .limit(limit as u64)
+            .offset(offset.max(0) as u64)
+            .limit((limit.max(1) as u64).min(crate::helpers::query::MAX_PER_PAGE))
             .all(db.as_ref())
             .await?;
 
@@ -85,6 +85,24 @@ impl QueryRoot {
             })
             .collect())
     }
+
+    /// Get anime detail by slug (otakudesu), served from the REST cache when warm.
+    async fn anime_detail(&self, slug: String) -> async_graphql::Result<AnimeDetail> {
+        use crate::helpers::Cache;
+        use crate::infra::redis::REDIS_POOL;
+        use crate::routes::api::anime::detail::slug::{fetch_anime_detail, DetailResponse};
+
+        let cache_key = format!("anime:detail:{}", slug);
+        if let Some(cached) = Cache::new(&REDIS_POOL).get::<DetailResponse>(&cache_key).await {
+            return Ok(cached.data.into());
+        }
+
+        let data = fetch_anime_detail(slug)
+            .await
+            .map_err(|e| async_graphql::Error::new(format!("Failed to fetch anime detail: {}", e)))?;
+
+        Ok(data.into())
+    }
 }
 
 // ============================================================================
@@ -203,3 +221,100 @@ pub struct HealthResponse {
     pub status: String,
     pub timestamp: String,
 }
+
+/// Anime genre.
+#[derive(SimpleObject)]
+pub struct AnimeGenre {
+    pub name: String,
+    pub slug: String,
+    pub anime_url: String,
+}
+
+/// Anime episode (or batch) link.
+#[derive(SimpleObject)]
+pub struct AnimeEpisode {
+    pub episode: String,
+    pub slug: String,
+}
+
+/// Recommended anime.
+#[derive(SimpleObject)]
+pub struct AnimeRecommendation {
+    pub title: String,
+    pub slug: String,
+    pub poster: String,
+    pub status: Option<String>,
+    #[graphql(name = "type")]
+    pub kind: Option<String>,
+}
+
+/// Anime detail type for GraphQL.
+#[derive(SimpleObject)]
+pub struct AnimeDetail {
+    pub title: String,
+    pub alternative_title: String,
+    pub poster: String,
+    #[graphql(name = "type")]
+    pub kind: Option<String>,
+    pub status: Option<String>,
+    pub release_date: String,
+    pub studio: String,
+    pub synopsis: String,
+    pub producers: Vec<String>,
+    pub genres: Vec<AnimeGenre>,
+    pub episodes: Vec<AnimeEpisode>,
+    pub batch: Vec<AnimeEpisode>,
+    pub recommendations: Vec<AnimeRecommendation>,
+}
+
+impl From<crate::routes::api::anime::detail::slug::AnimeDetailData> for AnimeDetail {
+    fn from(data: crate::routes::api::anime::detail::slug::AnimeDetailData) -> Self {
+        Self {
+            title: data.title,
+            alternative_title: data.alternative_title,
+            poster: data.poster,
+            kind: data.r#type,
+            status: data.status,
+            release_date: data.release_date,
+            studio: data.studio,
+            synopsis: data.synopsis,
+            producers: data.producers,
+            genres: data
+                .genres
+                .into_iter()
+                .map(|g| AnimeGenre {
+                    name: g.name,
+                    slug: g.slug,
+                    anime_url: g.anime_url,
+                })
+                .collect(),
+            episodes: data
+                .episode_lists
+                .into_iter()
+                .map(|e| AnimeEpisode {
+                    episode: e.episode,
+                    slug: e.slug,
+                })
+                .collect(),
+            batch: data
+                .batch
+                .into_iter()
+                .map(|e| AnimeEpisode {
+                    episode: e.episode,
+                    slug: e.slug,
+                })
+                .collect(),
+            recommendations: data
+                .recommendations
+                .into_iter()
+                .map(|r| AnimeRecommendation {
+                    title: r.title,
+                    slug: r.slug,
+                    poster: r.poster,
+                    status: r.status,
+                    kind: r.r#type,
+                })
+                .collect(),
+        }
+    }
+}
@@ -1,8 +1,13 @@
 //! Health check endpoint implementations.
 
-use axum::{http::StatusCode, response::IntoResponse, Json};
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use sea_orm::{ConnectionTrait, DatabaseConnection, Statement};
 use serde::Serialize;
-use std::time::Instant;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::routes::AppState;
 
 use once_cell::sync::Lazy;
 
@@ -83,6 +88,154 @@ pub async fn readiness_check() -> impl IntoResponse {
     (status_code, Json(status))
 }
 
+/// Build information returned by `/api/version`.
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionInfo {
+    pub name: &'static str,
+    pub version: &'static str,
+}
+
+/// Package name and version of the running build.
+pub async fn version_info() -> impl IntoResponse {
+    Json(VersionInfo {
+        name: env!("CARGO_PKG_NAME"),
+        version: env!("CARGO_PKG_VERSION"),
+    })
+}
+
+/// Deep health response: one entry per dependency.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeepHealthStatus {
+    pub status: &'static str,
+    pub version: &'static str,
+    pub uptime_seconds: u64,
+    pub dependencies: BTreeMap<String, CheckResult>,
+    /// Circuit state per upstream host: `closed`, `half_open` or `open`.
+    pub circuit_breakers: BTreeMap<String, &'static str>,
+}
+
+/// Timeout for each upstream HEAD probe.
+const UPSTREAM_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long a deep check result is reused, so polling `/health/deep` can't
+/// turn into a HEAD request per scrape source on every call.
+const DEEP_HEALTH_TTL: Duration = Duration::from_secs(30);
+
+/// The last deep check and when it ran.
+static DEEP_HEALTH: Lazy<tokio::sync::Mutex<Option<(Instant, DeepHealthStatus)>>> =
+    Lazy::new(|| tokio::sync::Mutex::new(None));
+
+/// Deep health check covering the database, Redis and every scrape source.
+/// Returns 503 if any dependency fails; use to gate deploys (`/health/deep`).
+///
+/// Results are reused for [`DEEP_HEALTH_TTL`]; callers arriving while a
+/// check runs wait for it instead of starting their own.
+pub async fn deep_health_check(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let mut cached = DEEP_HEALTH.lock().await;
+    let mut status = match cached.as_ref() {
+        Some((checked_at, status)) if checked_at.elapsed() < DEEP_HEALTH_TTL => status.clone(),
+        _ => {
+            let status = probe_dependencies(&state).await;
+            *cached = Some((Instant::now(), status.clone()));
+            status
+        }
+    };
+    drop(cached);
+    status.uptime_seconds = START_TIME.elapsed().as_secs();
+
+    let status_code = if status.status == "ok" {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status_code, Json(status))
+}
+
+/// Runs every deep check once.
+async fn probe_dependencies(state: &AppState) -> DeepHealthStatus {
+    let client = reqwest::Client::builder()
+        .timeout(UPSTREAM_PROBE_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+
+    let sources = crate::scraping::urls::scrape_sources();
+    let upstream_checks = futures::future::join_all(
+        sources.iter().map(|(_, url)| check_upstream(&client, url)),
+    );
+
+    let (db_check, redis_check, upstream_results) =
+        tokio::join!(check_database(&state.db), check_redis(), upstream_checks);
+
+    let mut dependencies = BTreeMap::new();
+    dependencies.insert("database".to_string(), db_check);
+    dependencies.insert("redis".to_string(), redis_check);
+    for ((name, _), result) in sources.iter().zip(upstream_results) {
+        dependencies.insert(format!("upstream:{}", name), result);
+    }
+
+    let circuit_breakers: BTreeMap<String, &'static str> = state
+        .source_breakers
+        .states()
+        .await
+        .into_iter()
+        .map(|(source, s)| (source, crate::circuit_breaker::sources::state_name(s)))
+        .collect();
+
+    let all_healthy = dependencies.values().all(|c| c.status == "ok")
+        && circuit_breakers.values().all(|s| *s != "open");
+    DeepHealthStatus {
+        status: if all_healthy { "ok" } else { "degraded" },
+        version: env!("CARGO_PKG_VERSION"),
+        uptime_seconds: START_TIME.elapsed().as_secs(),
+        dependencies,
+        circuit_breakers,
+    }
+}
+
+/// Check database connectivity with `SELECT 1`.
+async fn check_database(db: &DatabaseConnection) -> CheckResult {
+    let start = Instant::now();
+    let stmt = Statement::from_string(db.get_database_backend(), "SELECT 1");
+
+    match db.execute(stmt).await {
+        Ok(_) => CheckResult {
+            status: "ok",
+            latency_ms: Some(start.elapsed().as_millis() as u64),
+            error: None,
+        },
+        Err(e) => CheckResult {
+            status: "error",
+            latency_ms: Some(start.elapsed().as_millis() as u64),
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+/// Check an upstream scrape source with a HEAD request.
+/// Any non-5xx response counts as reachable (Cloudflare often answers 403 to HEAD).
+async fn check_upstream(client: &reqwest::Client, url: &str) -> CheckResult {
+    let start = Instant::now();
+
+    match client.head(url).send().await {
+        Ok(resp) if !resp.status().is_server_error() => CheckResult {
+            status: "ok",
+            latency_ms: Some(start.elapsed().as_millis() as u64),
+            error: None,
+        },
+        Ok(resp) => CheckResult {
+            status: "error",
+            latency_ms: Some(start.elapsed().as_millis() as u64),
+            error: Some(format!("HTTP {}", resp.status())),
+        },
+        Err(e) => CheckResult {
+            status: "error",
+            latency_ms: Some(start.elapsed().as_millis() as u64),
+            error: Some(e.to_string()),
+        },
+    }
+}
+
 /// Check Redis connectivity using PING command.
 async fn check_redis() -> CheckResult {
     use crate::infra::redis::REDIS_POOL;
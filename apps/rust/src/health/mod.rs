@@ -4,5 +4,10 @@
 //! load balancers and orchestration systems.
 
 pub mod endpoints;
+pub mod smoke;
 
-pub use endpoints::{health_check, readiness_check, HealthStatus};
+pub use endpoints::{
+    deep_health_check, health_check, readiness_check, version_info, DeepHealthStatus, HealthStatus,
+    VersionInfo,
+};
+pub use smoke::{run_smoke_test, SmokeReport};
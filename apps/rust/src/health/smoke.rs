@@ -0,0 +1,216 @@
+//! Post-deploy smoke test (`--smoke-test <base_url>`).
+//!
+//! Hits a handful of key endpoints on a running instance and checks status
+//! codes and response shapes. Used by deploys before switching traffic.
+
+use serde_json::Value;
+use std::fmt::Write as _;
+use std::time::{Duration, Instant};
+
+/// Timeout per smoke request; scraping endpoints may hit upstream cold.
+const SMOKE_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// One endpoint to probe and how to judge its JSON body.
+pub struct SmokeCheck {
+    pub name: &'static str,
+    pub path: &'static str,
+    pub validate: fn(&Value) -> Result<(), String>,
+}
+
+/// Outcome of a single [`SmokeCheck`].
+#[derive(Debug, Clone)]
+pub struct SmokeResult {
+    pub name: &'static str,
+    pub path: &'static str,
+    pub passed: bool,
+    pub latency_ms: u64,
+    pub detail: String,
+}
+
+/// Results of a full smoke run.
+#[derive(Debug, Clone)]
+pub struct SmokeReport {
+    pub base_url: String,
+    pub results: Vec<SmokeResult>,
+}
+
+impl SmokeReport {
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|r| r.passed)
+    }
+
+    /// Human-readable summary, one line per check.
+    pub fn summary(&self) -> String {
+        let mut out = format!("Smoke test against {}\n", self.base_url);
+        for r in &self.results {
+            let _ = writeln!(
+                out,
+                "  {} {:<10} {:<14} {:>5}ms  {}",
+                if r.passed { "✓" } else { "✗" },
+                r.name,
+                r.path,
+                r.latency_ms,
+                r.detail
+            );
+        }
+        let failed = self.results.iter().filter(|r| !r.passed).count();
+        let _ = write!(
+            out,
+            "{} passed, {} failed",
+            self.results.len() - failed,
+            failed
+        );
+        out
+    }
+}
+
+fn has_string(body: &Value, field: &str) -> Result<(), String> {
+    match body.get(field) {
+        Some(Value::String(s)) if !s.is_empty() => Ok(()),
+        _ => Err(format!("missing `{}`", field)),
+    }
+}
+
+fn validate_ready(body: &Value) -> Result<(), String> {
+    match body.get("status").and_then(Value::as_str) {
+        Some("ok") => Ok(()),
+        Some(other) => Err(format!("status is `{}`", other)),
+        None => Err("missing `status`".to_string()),
+    }
+}
+
+fn validate_scrape(body: &Value) -> Result<(), String> {
+    if body.get("success").and_then(Value::as_bool) != Some(true) {
+        return Err("`success` is not true".to_string());
+    }
+    let data = body.get("data").ok_or("missing `data`")?;
+    let ongoing = data
+        .get("ongoing_anime")
+        .and_then(Value::as_array)
+        .ok_or("missing `data.ongoing_anime`")?;
+    if ongoing.is_empty() {
+        return Err("`data.ongoing_anime` is empty".to_string());
+    }
+    Ok(())
+}
+
+fn validate_version(body: &Value) -> Result<(), String> {
+    has_string(body, "version")
+}
+
+/// The checks run by `--smoke-test`.
+pub fn default_checks() -> Vec<SmokeCheck> {
+    vec![
+        SmokeCheck { name: "ready", path: "/health/ready", validate: validate_ready },
+        SmokeCheck { name: "scrape", path: "/api/anime", validate: validate_scrape },
+        SmokeCheck { name: "version", path: "/api/version", validate: validate_version },
+    ]
+}
+
+async fn run_check(client: &reqwest::Client, base_url: &str, check: &SmokeCheck) -> SmokeResult {
+    let start = Instant::now();
+    let url = format!("{}{}", base_url, check.path);
+
+    let outcome = async {
+        let response = client.get(&url).send().await.map_err(|e| e.to_string())?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("HTTP {}", status.as_u16()));
+        }
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("invalid JSON: {}", e))?;
+        (check.validate)(&body)
+    }
+    .await;
+
+    SmokeResult {
+        name: check.name,
+        path: check.path,
+        passed: outcome.is_ok(),
+        latency_ms: start.elapsed().as_millis() as u64,
+        detail: outcome.err().unwrap_or_else(|| "ok".to_string()),
+    }
+}
+
+/// Runs `checks` against `base_url` concurrently.
+pub async fn run_checks(base_url: &str, checks: &[SmokeCheck]) -> SmokeReport {
+    let base_url = base_url.trim_end_matches('/').to_string();
+    let client = reqwest::Client::builder()
+        .timeout(SMOKE_REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+
+    let results =
+        futures::future::join_all(checks.iter().map(|c| run_check(&client, &base_url, c))).await;
+    SmokeReport { base_url, results }
+}
+
+/// Runs the default smoke checks against `base_url`.
+pub async fn run_smoke_test(base_url: &str) -> SmokeReport {
+    run_checks(base_url, &default_checks()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::get, Json, Router};
+    use serde_json::json;
+
+    async fn serve(router: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://{}/", addr)
+    }
+
+    #[tokio::test]
+    async fn healthy_instance_passes() {
+        let base = serve(
+            Router::new()
+                .route("/health/ready", get(|| async { Json(json!({"status": "ok"})) }))
+                .route(
+                    "/api/anime",
+                    get(|| async {
+                        Json(json!({"success": true, "data": {"ongoing_anime": [{"slug": "x"}]}}))
+                    }),
+                )
+                .route("/api/version", get(|| async { Json(json!({"version": "1.2.3"})) })),
+        )
+        .await;
+
+        let report = run_smoke_test(&base).await;
+        assert!(report.passed(), "{}", report.summary());
+        assert_eq!(report.results.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn unhealthy_instance_reports_each_failure() {
+        let base = serve(
+            Router::new()
+                .route(
+                    "/health/ready",
+                    get(|| async {
+                        (StatusCode::SERVICE_UNAVAILABLE, Json(json!({"status": "degraded"})))
+                    }),
+                )
+                .route(
+                    "/api/anime",
+                    get(|| async { Json(json!({"success": true, "data": {"ongoing_anime": []}})) }),
+                ),
+        )
+        .await;
+
+        let report = run_smoke_test(&base).await;
+        assert!(!report.passed());
+
+        let detail = |name: &str| {
+            report.results.iter().find(|r| r.name == name).unwrap().detail.clone()
+        };
+        assert_eq!(detail("ready"), "HTTP 503");
+        assert_eq!(detail("scrape"), "`data.ongoing_anime` is empty");
+        assert_eq!(detail("version"), "HTTP 404");
+        assert!(report.summary().ends_with("0 passed, 3 failed"));
+    }
+}
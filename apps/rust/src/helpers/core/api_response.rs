@@ -61,6 +61,9 @@ pub struct ErrorDetails {
     /// Field-level validation errors
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fields: Option<Vec<FieldError>>,
+    /// `x-request-id` of the failed request, for correlating with server logs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 /// Field-level validation error.
@@ -254,6 +257,7 @@ impl IntoResponse for ApiError {
                 code: self.code,
                 message: self.message,
                 fields: self.fields,
+                request_id: crate::observability::request_id::current_request_id(),
             }),
             pagination: None,
             meta: None,
@@ -2,6 +2,8 @@
 
 use axum::http::StatusCode;
 
+use crate::core::error::AppError;
+
 /// Shorthand for creating error tuples for Axum handlers.
 pub type HandlerError = (StatusCode, String);
 
@@ -15,6 +17,20 @@ pub fn internal_err<E: std::fmt::Display>(e: E) -> HandlerError {
     (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
 }
 
+/// Create an error response for a failed scrape.
+///
+/// An exhausted scrape budget or an open source breaker is a 503 so clients
+/// know to retry later; anything else is a 500.
+pub fn upstream_err(e: &AppError) -> HandlerError {
+    match e {
+        AppError::ScrapeBudgetExhausted(_) | AppError::ServiceUnavailable(_) => {
+            (StatusCode::SERVICE_UNAVAILABLE, e.to_string())
+        }
+        AppError::Other(msg) => internal_err(msg),
+        _ => internal_err(e),
+    }
+}
+
 /// Create bad request error.
 pub fn bad_request(msg: impl Into<String>) -> HandlerError {
     (StatusCode::BAD_REQUEST, msg.into())
@@ -2,6 +2,10 @@
 
 use serde::{Deserialize, Serialize};
 
+/// Page metadata returned by the scraper list endpoints; shared with the
+/// Leptos client.
+pub use api_models::Pagination;
+
 /// Pagination query parameters.
 #[derive(Debug, Clone, Deserialize)]
 pub struct PaginationParams {
@@ -103,3 +107,44 @@ impl<T> Paginatable<T> for Vec<T> {
         Paginated::from_params(self, params, total)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_page_fills_neighbours() {
+        let first = Pagination::from_page(1, 5, true);
+        assert_eq!(first.next_page, Some(2));
+        assert_eq!(first.previous_page, None);
+        assert!(!first.has_previous_page);
+
+        let last = Pagination::from_page(5, 5, false);
+        assert_eq!(last.next_page, None);
+        assert_eq!(last.previous_page, Some(4));
+        assert!(last.has_previous_page);
+    }
+
+    #[test]
+    fn last_page_covers_current_and_next() {
+        assert_eq!(Pagination::from_page(3, 1, false).last_visible_page, 3);
+        assert_eq!(Pagination::from_page(3, 3, true).last_visible_page, 4);
+        assert_eq!(Pagination::from_page(0, 0, false), Pagination::single());
+    }
+
+    #[test]
+    fn serializes_numeric_pages() {
+        let json = serde_json::to_value(Pagination::from_page(2, 9, true)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "current_page": 2,
+                "last_visible_page": 9,
+                "has_next_page": true,
+                "next_page": 3,
+                "has_previous_page": true,
+                "previous_page": 1
+            })
+        );
+    }
+}
@@ -142,3 +142,112 @@ impl Debouncer {
         f().await;
     }
 }
+
+/// A spawned task that is aborted when this handle is dropped.
+///
+/// Handlers use it for long-running work: when the client disconnects axum
+/// drops the handler future, which drops this handle and aborts the task.
+pub struct AbortOnDrop<T>(tokio::task::JoinHandle<T>);
+
+impl<T: Send + 'static> AbortOnDrop<T> {
+    pub fn spawn<F>(future: F) -> Self
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        Self(tokio::spawn(future))
+    }
+}
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+impl<T> Future for AbortOnDrop<T> {
+    type Output = Result<T, tokio::task::JoinError>;
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        std::pin::Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::file::TempFileGuard;
+    use axum::{routing::get, Router};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn client_disconnect_aborts_work_and_removes_temp_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("work.tmp");
+        let finished = Arc::new(AtomicBool::new(false));
+
+        let (work_path, work_finished) = (path.clone(), finished.clone());
+        let app = Router::new().route(
+            "/slow",
+            get(move || {
+                let (path, finished) = (work_path.clone(), work_finished.clone());
+                async move {
+                    let work = AbortOnDrop::spawn(async move {
+                        let temp = TempFileGuard::new(path);
+                        tokio::fs::write(temp.path(), b"partial").await.unwrap();
+                        tokio::time::sleep(Duration::from_secs(10)).await;
+                        finished.store(true, Ordering::SeqCst);
+                        temp.keep();
+                    });
+                    let _ = work.await;
+                    "done"
+                }
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(300))
+            .build()
+            .unwrap();
+        let request = client.get(format!("http://{}/slow", addr)).send();
+        let watcher = async {
+            while !path.exists() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        };
+        let (response, _) = tokio::join!(request, watcher);
+        assert!(response.is_err(), "client should give up first");
+
+        // The server notices the closed connection and drops the handler.
+        let mut removed = false;
+        for _ in 0..100 {
+            if !path.exists() {
+                removed = true;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(removed, "temp file should be removed after disconnect");
+        assert!(!finished.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn kept_temp_file_survives_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("result.bin");
+        std::fs::write(&path, b"ok").unwrap();
+
+        let kept = TempFileGuard::new(path.clone()).keep();
+        assert!(kept.exists());
+
+        drop(TempFileGuard::new(path.clone()));
+        assert!(!path.exists());
+    }
+}
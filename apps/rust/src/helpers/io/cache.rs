@@ -1,27 +1,85 @@
 //! Redis caching helpers.
+//!
+//! Reads go through the in-process [`L1`] layer first; see
+//! [`cache_l1`](super::cache_l1).
 
+use std::sync::Arc;
+
+use crate::core::error::AppError;
+use crate::helpers::cache_l1::L1;
 use crate::helpers::cache_ttl::CACHE_TTL_VERY_SHORT;
+use crate::observability::metrics::{record_cache_layer_lookup, record_cache_lookup};
 use deadpool_redis::redis::AsyncCommands;
 use deadpool_redis::Pool;
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tracing::{debug, error};
 
+use crate::helpers::conditional::weak_etag;
+
 /// Default cache TTL in seconds (5 minutes).
 pub const DEFAULT_CACHE_TTL: u64 = CACHE_TTL_VERY_SHORT;
 
+/// A cached value together with the weak `ETag` of its JSON, so every
+/// instance serving the entry sends the same validator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tagged<T> {
+    pub etag: String,
+    pub value: T,
+}
+
+impl<T: Serialize> Tagged<T> {
+    /// Tags `value` with the [`weak_etag`] of its serialized form.
+    pub fn new(value: T) -> Result<Self, String> {
+        let body = serde_json::to_vec(&value).map_err(|e| e.to_string())?;
+        Ok(Self { etag: weak_etag(&body), value })
+    }
+}
+
 /// Cache helper for Redis operations.
 pub struct Cache<'a> {
     pool: &'a Pool,
+    /// Whether reads and writes go through the in-process [`L1`] layer.
+    l1: bool,
 }
 
 impl<'a> Cache<'a> {
     /// Create a new cache helper.
     pub fn new(pool: &'a Pool) -> Self {
-        Self { pool }
+        Self { pool, l1: true }
+    }
+
+    /// Bypass the in-process layer, so every read sees Redis. For keys other
+    /// instances may change or revoke, like sessions and auth state.
+    pub fn without_l1(mut self) -> Self {
+        self.l1 = false;
+        self
     }
 
-    /// Get a value from cache, deserializing JSON.
+    /// Get a value from cache, deserializing JSON. Checks the in-process
+    /// layer before Redis and keeps Redis hits there for the L1 TTL.
     pub async fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        if !self.l1 {
+            let json = self.get_l2(key).await?;
+            return serde_json::from_str(&json).ok();
+        }
+
+        let l1_hit = L1.get(key);
+        if L1.is_enabled() {
+            record_cache_layer_lookup("l1", key_prefix(key), l1_hit.is_some());
+        }
+        if let Some(json) = l1_hit {
+            debug!("Cache L1 hit: {}", key);
+            return serde_json::from_str(&json).ok();
+        }
+
+        let json = self.get_l2(key).await?;
+        let value = serde_json::from_str(&json).ok()?;
+        L1.insert(key, Arc::from(json), u64::MAX);
+        Some(value)
+    }
+
+    /// The raw JSON stored under `key` in Redis.
+    async fn get_l2(&self, key: &str) -> Option<String> {
         let mut conn = match self.pool.get().await {
             Ok(c) => c,
             Err(e) => {
@@ -37,12 +95,14 @@ impl<'a> Cache<'a> {
         } else {
             debug!("Cache miss: {}", key);
         }
+        record_cache_layer_lookup("l2", key_prefix(key), cached.is_some());
 
-        cached.and_then(|json| serde_json::from_str(&json).ok())
+        cached
     }
 
     /// Get multiple values from cache, deserializing JSON.
-    /// Returns a vector of Options, preserving order of keys.
+    /// Returns a vector of Options, preserving order of keys. Reads Redis
+    /// only; the in-process layer is for single hot keys.
     pub async fn mget<T: DeserializeOwned>(&self, keys: &[String]) -> Vec<Option<T>> {
         if keys.is_empty() {
             return Vec::new();
@@ -88,17 +148,22 @@ impl<'a> Cache<'a> {
         let mut conn = self.pool.get().await.map_err(|e| e.to_string())?;
 
         let json = serde_json::to_string(value).map_err(|e| e.to_string())?;
-
-        conn.set_ex::<_, _, ()>(key, json, ttl_secs)
+        conn.set_ex::<_, _, ()>(key, &json, ttl_secs)
             .await
             .map_err(|e| e.to_string())?;
+        // Only once Redis has it, so this instance never serves a value the
+        // others can't see.
+        if self.l1 {
+            L1.insert(key, Arc::from(json), ttl_secs);
+        }
 
         debug!("Cache: set key {} with TTL {}s", key, ttl_secs);
         Ok(())
     }
 
-    /// Delete a key from cache.
+    /// Delete a key from both cache layers.
     pub async fn delete(&self, key: &str) -> Result<(), String> {
+        L1.remove(key);
         let mut conn = self.pool.get().await.map_err(|e| e.to_string())?;
         conn.del::<_, ()>(key).await.map_err(|e| e.to_string())?;
         debug!("Cache: deleted key {}", key);
@@ -114,6 +179,13 @@ impl<'a> Cache<'a> {
         conn.exists::<_, bool>(key).await.unwrap_or(false)
     }
 
+    /// Remaining TTL of `key` in seconds; `None` if it is missing or has no expiry.
+    pub async fn ttl(&self, key: &str) -> Option<u64> {
+        let mut conn = self.pool.get().await.ok()?;
+        let ttl: i64 = conn.ttl(key).await.ok()?;
+        u64::try_from(ttl).ok()
+    }
+
     /// Get or set: returns cached value or computes and caches new value.
     pub async fn get_or_set<T, F, Fut>(
         &self,
@@ -125,14 +197,44 @@ impl<'a> Cache<'a> {
         T: Serialize + DeserializeOwned,
         F: FnOnce() -> Fut,
         Fut: std::future::Future<Output = Result<T, String>>,
+    {
+        self.get_or_compute(key, ttl_secs, compute).await
+    }
+
+    /// [`get_or_set`](Self::get_or_set) for a scrape: the [`AppError`] it
+    /// fails with comes back as is, so the handler can tell an exhausted
+    /// scrape budget (503) from a broken page (500).
+    pub async fn get_or_fetch<T, F, Fut>(
+        &self,
+        key: &str,
+        ttl_secs: u64,
+        compute: F,
+    ) -> Result<T, AppError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<T, AppError>>,
+    {
+        self.get_or_compute(key, ttl_secs, compute).await
+    }
+
+    async fn get_or_compute<T, E, F, Fut>(&self, key: &str, ttl_secs: u64, compute: F) -> Result<T, E>
+    where
+        T: Serialize + DeserializeOwned,
+        E: From<String>,
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<T, E>>,
     {
         // Try cache first
+        let prefix = key_prefix(key);
         if let Some(cached) = self.get::<T>(key).await {
             debug!("Cache hit: {}", key);
+            record_cache_lookup(prefix, true);
             return Ok(cached);
         }
 
         debug!("Cache miss: {}", key);
+        record_cache_lookup(prefix, false);
 
         // Compute the value
         let value = compute().await?;
@@ -142,6 +244,28 @@ impl<'a> Cache<'a> {
 
         Ok(value)
     }
+
+    /// [`get_or_set`](Self::get_or_set) for a [`Tagged`] entry: the `ETag` is
+    /// computed once when the value is and stored with it.
+    pub async fn get_or_set_tagged<T, F, Fut>(
+        &self,
+        key: &str,
+        ttl_secs: u64,
+        compute: F,
+    ) -> Result<Tagged<T>, String>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<T, String>>,
+    {
+        self.get_or_set(key, ttl_secs, || async { Tagged::new(compute().await?) })
+            .await
+    }
+}
+
+/// The part of `key` before the first `:`, used as the metrics label.
+fn key_prefix(key: &str) -> &str {
+    key.split(':').next().unwrap_or(key)
 }
 
 /// Create a cache key with prefix.
@@ -153,3 +277,110 @@ pub fn cache_key(prefix: &str, id: &str) -> String {
 pub fn cache_key_multi(parts: &[&str]) -> String {
     parts.join(":")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deadpool_redis::{Manager, Runtime};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// Key prefix [`mock_redis`] refuses to write.
+    const READONLY: &str = "test-l1:readonly:";
+
+    /// A Redis stand-in that answers `GET key` with `value`, echoes `PING`,
+    /// refuses writes to keys under [`READONLY`], acknowledges everything
+    /// else and counts the `GET`s it serves.
+    async fn mock_redis(key: &'static str, value: &'static str) -> (Pool, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("redis://{}", listener.local_addr().unwrap());
+        let gets = Arc::new(AtomicUsize::new(0));
+        let counter = gets.clone();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                let counter = counter.clone();
+                tokio::spawn(async move {
+                    let (read, mut write) = socket.into_split();
+                    let mut lines = BufReader::new(read).lines();
+                    while let Ok(Some(header)) = lines.next_line().await {
+                        let argc: usize = header.trim_start_matches('*').parse().unwrap_or(0);
+                        let mut args = Vec::with_capacity(argc);
+                        for _ in 0..argc {
+                            lines.next_line().await.ok().flatten(); // `$len`
+                            args.push(lines.next_line().await.ok().flatten().unwrap_or_default());
+                        }
+                        let bulk = |s: &str| format!("${}\r\n{}\r\n", s.len(), s);
+                        let reply = match args.first().map(|c| c.to_ascii_uppercase()).as_deref() {
+                            Some("GET") => {
+                                counter.fetch_add(1, Ordering::SeqCst);
+                                if args.get(1).map(String::as_str) == Some(key) {
+                                    bulk(value)
+                                } else {
+                                    "$-1\r\n".to_string()
+                                }
+                            }
+                            Some("SETEX") if args.get(1).is_some_and(|k| k.starts_with(READONLY)) => {
+                                "-READONLY You can't write against a read only replica.\r\n".to_string()
+                            }
+                            Some("PING") => {
+                                args.get(1).map_or("+PONG\r\n".to_string(), |a| bulk(a))
+                            }
+                            _ => "+OK\r\n".to_string(),
+                        };
+                        if write.write_all(reply.as_bytes()).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        let pool = Pool::builder(Manager::new(url).unwrap())
+            .runtime(Runtime::Tokio1)
+            .build()
+            .unwrap();
+        (pool, gets)
+    }
+
+    #[tokio::test]
+    async fn second_get_is_served_from_l1_without_redis() {
+        let key = "test-l1:home";
+        let (pool, gets) = mock_redis(key, r#"{"title":"Frieren"}"#).await;
+        let cache = Cache::new(&pool);
+
+        let first: Option<serde_json::Value> = cache.get(key).await;
+        let second: Option<serde_json::Value> = cache.get(key).await;
+
+        assert_eq!(first, Some(serde_json::json!({ "title": "Frieren" })));
+        assert_eq!(second, first);
+        assert_eq!(gets.load(Ordering::SeqCst), 1);
+
+        cache.delete(key).await.unwrap();
+        let _: Option<serde_json::Value> = cache.get(key).await;
+        assert_eq!(gets.load(Ordering::SeqCst), 2, "delete clears L1 too");
+    }
+
+    #[tokio::test]
+    async fn without_l1_every_get_reads_redis() {
+        let key = "test-l1:session";
+        let (pool, gets) = mock_redis(key, r#"{"user":"u1"}"#).await;
+        let cache = Cache::new(&pool).without_l1();
+
+        cache.set_with_ttl(key, &serde_json::json!({ "user": "u1" }), 60).await.unwrap();
+        for _ in 0..2 {
+            let value: Option<serde_json::Value> = cache.get(key).await;
+            assert_eq!(value, Some(serde_json::json!({ "user": "u1" })));
+        }
+        assert_eq!(gets.load(Ordering::SeqCst), 2);
+        assert!(L1.get(key).is_none());
+    }
+
+    #[tokio::test]
+    async fn failed_sets_stay_out_of_l1() {
+        let key = "test-l1:readonly:unsaved";
+        let (pool, _) = mock_redis("test-l1:other", "1").await;
+
+        assert!(Cache::new(&pool).set_with_ttl(key, &1, 60).await.is_err());
+        assert!(L1.get(key).is_none());
+    }
+}
@@ -0,0 +1,183 @@
+//! In-process LRU cache in front of Redis.
+//!
+//! [`Cache`](super::cache::Cache) checks this layer before Redis, so the
+//! hottest keys (homepages, list pages) requested again within a few seconds
+//! skip the Redis round trip. Entries live for `APP__CACHE_L1_TTL_SECONDS`,
+//! never longer than their Redis TTL, and the least recently used entry is
+//! evicted once `APP__CACHE_L1_CAPACITY` is reached. Values are kept as the
+//! JSON stored in Redis, so both layers always deserialize the same bytes.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+
+use crate::core::config::CONFIG;
+
+/// The process-wide L1 layer, sized from config.
+pub static L1: Lazy<L1Cache> = Lazy::new(|| {
+    L1Cache::new(
+        CONFIG.cache_l1_capacity,
+        Duration::from_secs(CONFIG.cache_l1_ttl_seconds),
+    )
+});
+
+struct Entry {
+    json: Arc<str>,
+    expires_at: Instant,
+    /// Position in [`Inner::order`]; higher is more recently used.
+    tick: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: HashMap<String, Entry>,
+    /// Keys by last use, least recent first.
+    order: BTreeMap<u64, String>,
+    next_tick: u64,
+}
+
+impl Inner {
+    fn touch(&mut self) -> u64 {
+        self.next_tick += 1;
+        self.next_tick
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Entry> {
+        let entry = self.entries.remove(key)?;
+        self.order.remove(&entry.tick);
+        Some(entry)
+    }
+}
+
+/// A small LRU map of key to JSON with a per-entry expiry.
+pub struct L1Cache {
+    capacity: usize,
+    ttl: Duration,
+    inner: Mutex<Inner>,
+}
+
+impl L1Cache {
+    /// A cache of at most `capacity` entries living at most `ttl` each. A zero
+    /// capacity or TTL disables it.
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0 && !self.ttl.is_zero()
+    }
+
+    /// The JSON cached under `key`, unless it is missing or expired.
+    pub fn get(&self, key: &str) -> Option<Arc<str>> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = inner.remove(key)?;
+        if entry.expires_at <= Instant::now() {
+            return None;
+        }
+        let tick = inner.touch();
+        let json = entry.json.clone();
+        inner.order.insert(tick, key.to_string());
+        inner
+            .entries
+            .insert(key.to_string(), Entry { tick, ..entry });
+        Some(json)
+    }
+
+    /// Caches `json` under `key` for the L1 TTL, or `ttl_secs` if shorter.
+    pub fn insert(&self, key: &str, json: Arc<str>, ttl_secs: u64) {
+        let ttl = self.ttl.min(Duration::from_secs(ttl_secs));
+        if !self.is_enabled() || ttl.is_zero() {
+            return;
+        }
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.remove(key);
+        while inner.entries.len() >= self.capacity {
+            let Some((_, oldest)) = inner.order.pop_first() else {
+                break;
+            };
+            inner.entries.remove(&oldest);
+        }
+        let tick = inner.touch();
+        inner.order.insert(tick, key.to_string());
+        inner.entries.insert(
+            key.to_string(),
+            Entry {
+                json,
+                expires_at: Instant::now() + ttl,
+                tick,
+            },
+        );
+    }
+
+    pub fn remove(&self, key: &str) {
+        self.inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(key);
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entries
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json(s: &str) -> Arc<str> {
+        Arc::from(s)
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used_entry() {
+        let cache = L1Cache::new(2, Duration::from_secs(60));
+        cache.insert("a", json("1"), 60);
+        cache.insert("b", json("2"), 60);
+        assert!(cache.get("a").is_some());
+
+        cache.insert("c", json("3"), 60);
+
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a").as_deref(), Some("1"));
+        assert_eq!(cache.get("c").as_deref(), Some("3"));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn entries_expire_after_the_shorter_ttl() {
+        let cache = L1Cache::new(8, Duration::from_millis(20));
+        cache.insert("a", json("1"), 60);
+        assert!(cache.get("a").is_some());
+
+        std::thread::sleep(Duration::from_millis(30));
+
+        assert_eq!(cache.get("a"), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn zero_capacity_disables_the_layer() {
+        let cache = L1Cache::new(0, Duration::from_secs(60));
+        cache.insert("a", json("1"), 60);
+
+        assert_eq!(cache.get("a"), None);
+    }
+}
@@ -130,3 +130,39 @@ pub fn mime_from_extension(ext: &str) -> &'static str {
         _ => "application/octet-stream",
     }
 }
+
+/// Deletes a temporary file when dropped, including when the owning future
+/// is cancelled mid-operation. Call [`TempFileGuard::keep`] to retain it.
+#[derive(Debug)]
+pub struct TempFileGuard {
+    path: std::path::PathBuf,
+    keep: bool,
+}
+
+impl TempFileGuard {
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            keep: false,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Disarms the guard and returns the path.
+    pub fn keep(mut self) -> std::path::PathBuf {
+        self.keep = true;
+        std::mem::take(&mut self.path)
+    }
+}
+
+impl Drop for TempFileGuard {
+    fn drop(&mut self) {
+        if !self.keep {
+            // Sync removal: Drop may run outside a runtime or during abort.
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod cache;
+pub mod cache_l1;
 pub mod cache_tags;
 pub mod cache_ttl;
 pub mod file;
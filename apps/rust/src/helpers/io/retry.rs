@@ -1,7 +1,9 @@
 //! HTTP retry utilities with exponential backoff.
 
+use backoff::backoff::Backoff;
 use backoff::ExponentialBackoff;
-use std::time::Duration;
+use rand::Rng;
+use std::time::{Duration, Instant};
 
 /// Default retry configuration for HTTP requests.
 pub fn default_backoff() -> ExponentialBackoff {
@@ -52,6 +54,80 @@ pub fn slow_backoff() -> ExponentialBackoff {
     }
 }
 
+/// Exponential backoff with "equal jitter": each delay is drawn uniformly from
+/// `[nominal / 2, nominal]`, where `nominal = min(max, base * factor^attempt)`.
+///
+/// Spreads retries from clients that failed at the same moment instead of
+/// having them all retry in lockstep after an outage.
+#[derive(Debug, Clone)]
+pub struct JitteredBackoff {
+    base: Duration,
+    max: Duration,
+    factor: f64,
+    max_elapsed: Option<Duration>,
+    attempt: u32,
+    started: Instant,
+}
+
+impl JitteredBackoff {
+    /// Stop retrying after `max_elapsed` (default 30s); `None` retries forever.
+    pub fn with_max_elapsed(mut self, max_elapsed: Option<Duration>) -> Self {
+        self.max_elapsed = max_elapsed;
+        self
+    }
+
+    /// Un-jittered delay for the given attempt.
+    fn nominal(&self, attempt: u32) -> Duration {
+        let scaled = self.base.as_secs_f64() * self.factor.powi(attempt as i32);
+        Duration::from_secs_f64(scaled.min(self.max.as_secs_f64()))
+    }
+}
+
+impl Backoff for JitteredBackoff {
+    fn reset(&mut self) {
+        self.attempt = 0;
+        self.started = Instant::now();
+    }
+
+    fn next_backoff(&mut self) -> Option<Duration> {
+        if let Some(limit) = self.max_elapsed {
+            if self.started.elapsed() >= limit {
+                return None;
+            }
+        }
+
+        let nominal = self.nominal(self.attempt);
+        self.attempt = self.attempt.saturating_add(1);
+
+        let half = nominal / 2;
+        let jitter = rand::thread_rng().gen_range(0.0..=1.0);
+        Some(half + half.mul_f64(jitter))
+    }
+}
+
+/// Create a jittered exponential backoff (see [`JitteredBackoff`]).
+pub fn jittered_backoff(base: Duration, max: Duration, factor: f64) -> JitteredBackoff {
+    JitteredBackoff {
+        base,
+        max,
+        factor,
+        max_elapsed: Some(Duration::from_secs(30)),
+        attempt: 0,
+        started: Instant::now(),
+    }
+}
+
+/// Jittered backoff for upstream scrape fetches (500ms base, 10s cap, x2).
+pub fn scrape_backoff() -> JitteredBackoff {
+    jittered_backoff(Duration::from_millis(500), Duration::from_secs(10), 2.0)
+}
+
+/// Jittered backoff for transient database errors (200ms base, 5s cap, x2, 20s total).
+pub fn db_backoff() -> JitteredBackoff {
+    jittered_backoff(Duration::from_millis(200), Duration::from_secs(5), 2.0)
+        .with_max_elapsed(Some(Duration::from_secs(20)))
+}
+
 /// Make an error transient (will be retried).
 pub fn transient<E>(err: E) -> backoff::Error<E> {
     backoff::Error::transient(err)
@@ -64,3 +140,55 @@ pub fn permanent<E>(err: E) -> backoff::Error<E> {
 
 // Re-export retry function for convenience
 pub use backoff::future::retry;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jittered_delays_stay_within_bounds() {
+        let mut backoff =
+            jittered_backoff(Duration::from_millis(100), Duration::from_secs(2), 2.0)
+                .with_max_elapsed(None);
+
+        for attempt in 0..10 {
+            let nominal = backoff.nominal(attempt);
+            let delay = backoff.next_backoff().unwrap();
+            assert!(delay >= nominal / 2 && delay <= nominal, "attempt {}: {:?}", attempt, delay);
+        }
+        // Capped at max.
+        assert!(backoff.next_backoff().unwrap() <= Duration::from_secs(2));
+    }
+
+    #[test]
+    fn jittered_delays_grow_exponentially_on_average() {
+        const RUNS: usize = 200;
+        const ATTEMPTS: usize = 5;
+        let mut totals = [0.0f64; ATTEMPTS];
+
+        for _ in 0..RUNS {
+            let mut backoff =
+                jittered_backoff(Duration::from_millis(100), Duration::from_secs(60), 2.0)
+                    .with_max_elapsed(None);
+            for total in totals.iter_mut() {
+                *total += backoff.next_backoff().unwrap().as_secs_f64();
+            }
+        }
+
+        for pair in totals.windows(2) {
+            let ratio = pair[1] / pair[0];
+            assert!((1.7..2.3).contains(&ratio), "ratio {}", ratio);
+        }
+    }
+
+    #[test]
+    fn jittered_backoff_stops_after_max_elapsed() {
+        let mut backoff = jittered_backoff(Duration::from_millis(10), Duration::from_secs(1), 2.0)
+            .with_max_elapsed(Some(Duration::ZERO));
+        assert!(backoff.next_backoff().is_none());
+
+        backoff.reset();
+        backoff.max_elapsed = None;
+        assert!(backoff.next_backoff().is_some());
+    }
+}
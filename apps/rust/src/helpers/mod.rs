@@ -33,6 +33,7 @@ pub use data::text;
 
 // IO
 pub use io::cache;
+pub use io::cache_l1;
 pub use io::cache_tags;
 pub use io::cache_ttl;
 pub use io::file;
@@ -40,6 +41,7 @@ pub use io::retry;
 pub use io::soft_delete;
 
 // Web
+pub use web::conditional;
 pub use web::query;
 pub use web::request;
 pub use web::scraping;
@@ -96,21 +98,23 @@ pub use response::*;
 // Error helpers
 pub use errors::{
     bad_request, db_error, forbidden, internal_err, internal_error, not_found, redis_error,
-    unauthorized, HandlerError, ResultExt,
+    unauthorized, upstream_err, HandlerError, ResultExt,
 };
 
 // Retry/Backoff
 pub use retry::{
-    custom_backoff, default_backoff, permanent, quick_backoff, retry, slow_backoff, transient,
+    custom_backoff, db_backoff, default_backoff, jittered_backoff, permanent, quick_backoff, retry,
+    scrape_backoff, slow_backoff, transient, JitteredBackoff,
 };
 
 // Caching
-pub use cache::{cache_key, cache_key_multi, Cache, DEFAULT_CACHE_TTL};
+pub use cache::{cache_key, cache_key_multi, Cache, Tagged, DEFAULT_CACHE_TTL};
 
 // Scraping
 pub use scraping::{
-    attr_from, attr_from_or, extract_number, extract_slug, fetch_html_with_retry, parse_html,
-    select_attr, select_text, selector, strip_tags, text, text_from, text_from_or, Scraper,
+    attr_from, attr_from_or, extract_img_src, extract_number, extract_slug, fetch_html_pair, fetch_html_with_retry, parse_html,
+    select_attr, select_text, selector, split_alternative_titles, split_labeled_list, strip_tags, text, text_from, text_from_or,
+    Scraper,
 };
 
 // Strings
@@ -195,8 +199,8 @@ pub use result_ext::{err, flatten_option, flatten_result, ok, some, OptionExt, R
 
 // HTTP Request helpers
 pub use request::{
-    accepts_gzip, bearer_token, client_ip, content_type, header_value, is_form, is_json, origin,
-    referer, request_id, user_agent,
+    accepts_gzip, bearer_token, client_ip, content_type, forwarded_client_ip, header_value,
+    is_form, is_json, origin, referer, request_id, user_agent, TrustedProxies,
 };
 
 // Environment
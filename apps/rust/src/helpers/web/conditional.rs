@@ -0,0 +1,119 @@
+//! Validators for conditional requests (`ETag`, `Last-Modified`).
+
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// Weak `ETag` for a serialized body: `W/"<16 hex chars of its SHA-256>"`.
+pub fn weak_etag(body: &[u8]) -> String {
+    let digest = Sha256::digest(body);
+    format!("W/\"{}\"", &hex::encode(digest)[..16])
+}
+
+/// IMF-fixdate form used by `Last-Modified`, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+pub fn http_date(at: DateTime<Utc>) -> String {
+    at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Whether the request's `If-None-Match` lists `etag` (or is `*`), using the
+/// weak comparison `If-None-Match` calls for.
+pub fn etag_matches(headers: &HeaderMap, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let wanted = opaque(etag);
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|tag| tag.trim() == "*" || opaque(tag) == wanted)
+}
+
+/// `body` as JSON with `ETag: etag` and `Cache-Control: max-age`, or an empty
+/// `304 Not Modified` with the same headers when the client already has it.
+pub fn conditional_json<T: Serialize>(
+    request_headers: &HeaderMap,
+    etag: &str,
+    max_age: u64,
+    body: &T,
+) -> Response {
+    let mut headers = HeaderMap::new();
+    if let Ok(value) = HeaderValue::from_str(etag) {
+        headers.insert(header::ETAG, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&format!("public, max-age={}", max_age)) {
+        headers.insert(header::CACHE_CONTROL, value);
+    }
+
+    if etag_matches(request_headers, etag) {
+        (StatusCode::NOT_MODIFIED, headers).into_response()
+    } else {
+        (headers, Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn if_none_match(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn etag_is_weak_and_stable() {
+        let etag = weak_etag(br#"{"a":1}"#);
+        assert!(etag.starts_with("W/\"") && etag.ends_with('"'));
+        assert_eq!(etag.len(), 20);
+        assert_eq!(etag, weak_etag(br#"{"a":1}"#));
+        assert_ne!(etag, weak_etag(br#"{"a":2}"#));
+    }
+
+    #[test]
+    fn formats_imf_fixdate() {
+        let at = Utc.with_ymd_and_hms(1994, 11, 6, 8, 49, 37).unwrap();
+        assert_eq!(http_date(at), "Sun, 06 Nov 1994 08:49:37 GMT");
+    }
+
+    #[test]
+    fn if_none_match_uses_weak_comparison() {
+        let etag = weak_etag(b"body");
+        let opaque = etag.trim_start_matches("W/");
+        assert!(etag_matches(&if_none_match(&etag), &etag));
+        assert!(etag_matches(&if_none_match(opaque), &etag));
+        assert!(etag_matches(&if_none_match(&format!("\"other\", {}", etag)), &etag));
+        assert!(etag_matches(&if_none_match("*"), &etag));
+        assert!(!etag_matches(&if_none_match("W/\"other\""), &etag));
+        assert!(!etag_matches(&HeaderMap::new(), &etag));
+    }
+
+    #[tokio::test]
+    async fn unchanged_bodies_are_304() {
+        let body = serde_json::json!({ "status": "Ok", "data": [1, 2, 3] });
+        let etag = weak_etag(&serde_json::to_vec(&body).unwrap());
+
+        let fresh = conditional_json(&HeaderMap::new(), &etag, 60, &body);
+        assert_eq!(fresh.status(), StatusCode::OK);
+        assert_eq!(fresh.headers()[header::ETAG], etag.as_str());
+        assert_eq!(fresh.headers()[header::CACHE_CONTROL], "public, max-age=60");
+        let bytes = axum::body::to_bytes(fresh.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(weak_etag(&bytes), etag);
+
+        let revalidated = conditional_json(&if_none_match(&etag), &etag, 60, &body);
+        assert_eq!(revalidated.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(revalidated.headers()[header::ETAG], etag.as_str());
+        assert_eq!(revalidated.headers()[header::CACHE_CONTROL], "public, max-age=60");
+        let bytes = axum::body::to_bytes(revalidated.into_body(), usize::MAX).await.unwrap();
+        assert!(bytes.is_empty());
+
+        let changed = conditional_json(&if_none_match("W/\"0000000000000000\""), &etag, 60, &body);
+        assert_eq!(changed.status(), StatusCode::OK);
+    }
+}
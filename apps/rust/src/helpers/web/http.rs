@@ -1,4 +1,11 @@
-use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use reqwest::header::{self, HeaderMap, HeaderName, HeaderValue, FROM, USER_AGENT};
+use reqwest::RequestBuilder;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::core::config::ScrapeSourceConfig;
+use crate::scraping::urls::{scrape_sources, source_for};
 
 pub fn common_headers() -> HeaderMap {
     let mut headers = HeaderMap::new();
@@ -7,6 +14,106 @@ pub fn common_headers() -> HeaderMap {
     headers
 }
 
+/// Sets `User-Agent` (when `user_agent` is given) and a `From` contact
+/// header so upstream operators can identify and reach us.
+pub fn with_identity(mut headers: HeaderMap, user_agent: Option<&str>, contact: Option<&str>) -> HeaderMap {
+    if let Some(ua) = user_agent.and_then(|ua| HeaderValue::from_str(ua.trim()).ok()) {
+        if !ua.is_empty() {
+            headers.insert(USER_AGENT, ua);
+        }
+    }
+    if let Some(from) = contact.and_then(|c| HeaderValue::from_str(c.trim()).ok()) {
+        if !from.is_empty() {
+            headers.insert(FROM, from);
+        }
+    }
+    headers
+}
+
+/// [`common_headers`] with the configured scraper identity
+/// (`CONFIG.scrape_user_agent`, `CONFIG.scrape_contact`) applied.
+pub fn scraper_headers() -> HeaderMap {
+    let config = &crate::core::config::CONFIG;
+    with_identity(
+        common_headers(),
+        config.scrape_user_agent.as_deref(),
+        config.scrape_contact.as_deref(),
+    )
+}
+
+/// Headers and timeout for one upstream request, tuned per scrape source
+/// through `CONFIG.scrape_sources`.
+#[derive(Debug, Clone)]
+pub struct SourceRequest {
+    pub headers: HeaderMap,
+    /// Overrides the client's timeout when set.
+    pub timeout: Option<Duration>,
+}
+
+impl SourceRequest {
+    /// Settings for fetching `url`: [`scraper_headers`] with the overrides of
+    /// the scrape source `url` belongs to, if any.
+    pub fn for_url(url: &str) -> Self {
+        let config = &crate::core::config::CONFIG;
+        Self::resolve(
+            url,
+            &scrape_sources(),
+            &config.scrape_sources,
+            scraper_headers(),
+        )
+    }
+
+    /// `base` with the user agent and extra headers configured for `url`'s
+    /// source applied on top. Invalid header names or values are skipped.
+    pub fn resolve(
+        url: &str,
+        sources: &[(&'static str, String)],
+        configs: &BTreeMap<String, ScrapeSourceConfig>,
+        base: HeaderMap,
+    ) -> Self {
+        let Some((name, config)) = source_for(url, sources)
+            .and_then(|name| configs.get(name).map(|config| (name, config)))
+        else {
+            return Self {
+                headers: base,
+                timeout: None,
+            };
+        };
+
+        let mut headers = with_identity(base, config.user_agent.as_deref(), None);
+        for (key, value) in &config.headers {
+            match (
+                HeaderName::from_bytes(key.trim().as_bytes()),
+                HeaderValue::from_str(value.trim()),
+            ) {
+                (Ok(key), Ok(value)) => {
+                    headers.insert(key, value);
+                }
+                _ => warn!(
+                    "Ignoring invalid header {:?} configured for source {}",
+                    key, name
+                ),
+            }
+        }
+        Self {
+            headers,
+            timeout: config
+                .timeout_seconds
+                .filter(|s| *s > 0)
+                .map(Duration::from_secs),
+        }
+    }
+
+    /// Sets these headers and timeout on `request`.
+    pub fn apply(self, request: RequestBuilder) -> RequestBuilder {
+        let request = request.headers(self.headers);
+        match self.timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+}
+
 pub fn common_image_headers() -> HeaderMap {
     let mut headers = HeaderMap::new();
     headers.insert(USER_AGENT, HeaderValue::from_static("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36"));
@@ -14,6 +121,155 @@ pub fn common_image_headers() -> HeaderMap {
     headers
 }
 
+/// Headers that only apply to a single connection (RFC 9110 §7.6.1).
+const HOP_BY_HOP: [&str; 8] = [
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// `upstream`'s response headers minus hop-by-hop ones (including any the
+/// `Connection` header names) and `Set-Cookie`, so a proxied response can't
+/// plant the upstream's cookies on our origin.
+pub fn forwardable_response_headers(upstream: &HeaderMap) -> HeaderMap {
+    let named_by_connection: Vec<HeaderName> = upstream
+        .get_all(header::CONNECTION)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .filter_map(|name| HeaderName::from_bytes(name.trim().as_bytes()).ok())
+        .collect();
+
+    let mut headers = upstream.clone();
+    for name in HOP_BY_HOP {
+        headers.remove(name);
+    }
+    for name in &named_by_connection {
+        headers.remove(name);
+    }
+    headers.remove(header::SET_COOKIE);
+    headers
+}
+
 pub fn is_internet_baik_block_page(content: &str) -> bool {
     content.contains("Internet Baik") || content.contains("TrustPositif") || content.contains("Mercusuar")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::http_client::http_client_fast;
+    use axum::{http::HeaderMap as AxumHeaderMap, routing::get, Router};
+
+    /// Answers with the `User-Agent` and `X-Source-Key` it received.
+    async fn serve_echo() -> String {
+        let app = Router::new().route(
+            "/",
+            get(|headers: AxumHeaderMap| async move {
+                let get = |name: &str| {
+                    headers
+                        .get(name)
+                        .and_then(|v| v.to_str().ok())
+                        .unwrap_or("-")
+                        .to_string()
+                };
+                format!("{}|{}", get("user-agent"), get("x-source-key"))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    fn source(user_agent: Option<&str>, headers: &[(&str, &str)]) -> ScrapeSourceConfig {
+        ScrapeSourceConfig {
+            timeout_seconds: None,
+            user_agent: user_agent.map(str::to_string),
+            headers: headers
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn each_source_is_sent_its_own_user_agent_and_headers() {
+        let (alpha, beta, other) = (serve_echo().await, serve_echo().await, serve_echo().await);
+        let sources = vec![("alpha", alpha.clone()), ("beta", beta.clone())];
+        let configs = BTreeMap::from([
+            (
+                "alpha".to_string(),
+                source(Some("AlphaBot/1.0"), &[("X-Source-Key", "alpha-key")]),
+            ),
+            (
+                "beta".to_string(),
+                source(Some("BetaBot/2.0"), &[("Bad Header", "x")]),
+            ),
+        ]);
+
+        let default_ua = common_headers()[USER_AGENT].to_str().unwrap().to_string();
+
+        for (url, expected) in [
+            (&alpha, "AlphaBot/1.0|alpha-key".to_string()),
+            (&beta, "BetaBot/2.0|-".to_string()),
+            (&other, format!("{}|-", default_ua)),
+        ] {
+            let request = SourceRequest::resolve(url, &sources, &configs, common_headers());
+            let body = request
+                .apply(http_client_fast().client().get(url.as_str()))
+                .send()
+                .await
+                .unwrap()
+                .text()
+                .await
+                .unwrap();
+            assert_eq!(body, expected, "{}", url);
+        }
+    }
+
+    #[test]
+    fn only_a_positive_timeout_overrides_the_client() {
+        let sources = vec![("slow", "https://slow.example".to_string())];
+        let mut config = source(None, &[]);
+        config.timeout_seconds = Some(45);
+        let configs = BTreeMap::from([("slow".to_string(), config.clone())]);
+        let resolve = |configs: &BTreeMap<String, ScrapeSourceConfig>| {
+            SourceRequest::resolve(
+                "https://slow.example/x",
+                &sources,
+                configs,
+                common_headers(),
+            )
+        };
+        assert_eq!(resolve(&configs).timeout, Some(Duration::from_secs(45)));
+
+        config.timeout_seconds = Some(0);
+        let configs = BTreeMap::from([("slow".to_string(), config)]);
+        assert_eq!(resolve(&configs).timeout, None);
+        assert_eq!(resolve(&configs).headers, common_headers());
+    }
+
+    #[test]
+    fn hop_by_hop_and_cookie_headers_are_not_forwarded() {
+        let mut upstream = HeaderMap::new();
+        upstream.insert(header::CONTENT_TYPE, HeaderValue::from_static("image/png"));
+        upstream.insert(header::CACHE_CONTROL, HeaderValue::from_static("max-age=60"));
+        upstream.insert(header::CONNECTION, HeaderValue::from_static("keep-alive, X-Trace"));
+        upstream.insert("keep-alive", HeaderValue::from_static("timeout=5"));
+        upstream.insert("x-trace", HeaderValue::from_static("abc"));
+        upstream.insert(header::TRANSFER_ENCODING, HeaderValue::from_static("chunked"));
+        upstream.append(header::SET_COOKIE, HeaderValue::from_static("sid=1"));
+        upstream.append(header::SET_COOKIE, HeaderValue::from_static("cf=2"));
+
+        let forwarded = forwardable_response_headers(&upstream);
+        let mut names: Vec<&str> = forwarded.keys().map(HeaderName::as_str).collect();
+        names.sort();
+        assert_eq!(names, ["cache-control", "content-type"]);
+    }
+}
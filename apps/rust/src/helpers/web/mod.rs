@@ -1,3 +1,4 @@
+pub mod conditional;
 pub mod query;
 pub mod request;
 pub mod scraping;
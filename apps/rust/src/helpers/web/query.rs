@@ -12,9 +12,19 @@
 //!     .apply_sort("created_at", SortOrder::Desc);
 //! ```
 
-use sea_orm::{entity::prelude::*, Order, QuerySelect, Select};
+use sea_orm::sea_query::{Alias, Expr, Query};
+use sea_orm::{entity::prelude::*, ConnectionTrait, Order, QuerySelect, QueryTrait, Select};
 use serde::{Deserialize, Serialize};
 
+/// Hard ceiling on rows per page for every DB-backed list, whatever the client asks for.
+pub const MAX_PER_PAGE: u64 = 100;
+
+/// Most rows [`count_capped`] will count; larger totals are reported as this.
+pub const MAX_COUNTED_ROWS: u64 = 10_000;
+
+/// Default page size when the client doesn't specify one.
+pub const DEFAULT_PER_PAGE: u64 = 20;
+
 /// Pagination parameters.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Pagination {
@@ -32,14 +42,14 @@ impl Pagination {
     pub fn new(page: u64, per_page: u64) -> Self {
         Self {
             page: page.max(1),
-            per_page: per_page.clamp(1, 100),
+            per_page: per_page.clamp(1, MAX_PER_PAGE),
             total: None,
         }
     }
 
     /// Default pagination (page 1, 20 per page).
     pub fn default_page() -> Self {
-        Self::new(1, 20)
+        Self::new(1, DEFAULT_PER_PAGE)
     }
 
     /// Calculate offset for query.
@@ -78,6 +88,27 @@ impl Default for Pagination {
     }
 }
 
+/// `?page=&per_page=` query string for list handlers.
+///
+/// Always go through [`PageQuery::pagination`] so `per_page` is clamped to
+/// [`MAX_PER_PAGE`].
+#[derive(Debug, Clone, Default, Deserialize, utoipa::IntoParams)]
+pub struct PageQuery {
+    /// Page number, 1-indexed.
+    pub page: Option<u64>,
+    /// Items per page (max 100).
+    pub per_page: Option<u64>,
+}
+
+impl PageQuery {
+    pub fn pagination(&self) -> Pagination {
+        Pagination::new(
+            self.page.unwrap_or(1),
+            self.per_page.unwrap_or(DEFAULT_PER_PAGE),
+        )
+    }
+}
+
 /// Sort order.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -209,7 +240,7 @@ impl QueryParams {
     }
 
     pub fn per_page(mut self, per_page: u64) -> Self {
-        self.pagination.per_page = per_page.clamp(1, 100);
+        self.pagination.per_page = per_page.clamp(1, MAX_PER_PAGE);
         self
     }
 
@@ -230,14 +261,15 @@ impl QueryParams {
 }
 
 /// Paginated result.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct PaginatedResult<T> {
     pub data: Vec<T>,
     pub pagination: PaginationMeta,
 }
 
 /// Pagination metadata for response.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+#[schema(as = query::PaginationMeta)]
 pub struct PaginationMeta {
     pub page: u64,
     pub per_page: u64,
@@ -247,21 +279,36 @@ pub struct PaginationMeta {
     pub has_prev: bool,
 }
 
+impl PaginationMeta {
+    /// Response headers for list endpoints whose body must stay a bare array.
+    pub fn headers(&self) -> [(&'static str, String); 4] {
+        [
+            ("x-page", self.page.to_string()),
+            ("x-per-page", self.per_page.to_string()),
+            ("x-total-count", self.total.to_string()),
+            ("x-total-pages", self.total_pages.to_string()),
+        ]
+    }
+}
+
+impl From<&Pagination> for PaginationMeta {
+    fn from(pagination: &Pagination) -> Self {
+        Self {
+            page: pagination.page,
+            per_page: pagination.per_page,
+            total: pagination.total.unwrap_or(0),
+            total_pages: pagination.total_pages(),
+            has_next: pagination.has_next(),
+            has_prev: pagination.has_prev(),
+        }
+    }
+}
+
 impl<T> PaginatedResult<T> {
     pub fn new(data: Vec<T>, pagination: Pagination) -> Self {
-        let total = pagination.total.unwrap_or(0);
-        let total_pages = pagination.total_pages();
-
         Self {
             data,
-            pagination: PaginationMeta {
-                page: pagination.page,
-                per_page: pagination.per_page,
-                total,
-                total_pages,
-                has_next: pagination.has_next(),
-                has_prev: pagination.has_prev(),
-            },
+            pagination: PaginationMeta::from(&pagination),
         }
     }
 }
@@ -276,3 +323,101 @@ impl<E: EntityTrait> PaginateExt<E> for Select<E> {
         self.offset(pagination.offset()).limit(pagination.per_page)
     }
 }
+
+/// Counts the rows `select` matches, stopping at `cap` so a page total never
+/// scans a whole large table.
+pub async fn count_capped<E, C>(select: Select<E>, db: &C, cap: u64) -> Result<u64, DbErr>
+where
+    E: EntityTrait,
+    C: ConnectionTrait,
+{
+    let matching = select.select_only().expr(Expr::val(1)).limit(cap).into_query();
+    let stmt = Query::select()
+        .expr_as(Expr::cust("COUNT(*)"), Alias::new("num_items"))
+        .from_subquery(matching, Alias::new("capped"))
+        .to_owned();
+    let backend = db.get_database_backend();
+    let counted: Option<i64> = match db.query_one(backend.build(&stmt)).await? {
+        Some(row) => Some(row.try_get("", "num_items")?),
+        None => None,
+    };
+    Ok(counted.unwrap_or(0).max(0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oversized_per_page_is_clamped_and_paged() {
+        let query = PageQuery {
+            page: None,
+            per_page: Some(10_000),
+        };
+        let pagination = query.pagination().with_total(250);
+        assert_eq!(pagination.per_page, MAX_PER_PAGE);
+
+        let meta = PaginationMeta::from(&pagination);
+        assert_eq!(meta.total_pages, 3);
+        assert!(meta.has_next);
+        assert!(!meta.has_prev);
+    }
+
+    #[test]
+    fn missing_params_use_defaults() {
+        let pagination = PageQuery::default().pagination();
+        assert_eq!((pagination.page, pagination.per_page), (1, DEFAULT_PER_PAGE));
+        assert_eq!(pagination.offset(), 0);
+    }
+
+    #[tokio::test]
+    async fn counting_stops_at_the_cap() {
+        use crate::entities::posts;
+        use sea_orm::{DatabaseBackend, MockDatabase};
+        use std::collections::BTreeMap;
+
+        let db = MockDatabase::new(DatabaseBackend::MySql)
+            .append_query_results([vec![BTreeMap::from([(
+                "num_items".to_string(),
+                sea_orm::Value::BigInt(Some(25)),
+            )])]])
+            .into_connection();
+        let total = count_capped(posts::Entity::find(), &db, 25).await.unwrap();
+        assert_eq!(total, 25);
+
+        let sql = db.into_transaction_log()[0].statements()[0].sql.clone();
+        assert!(sql.starts_with("SELECT COUNT(*) AS `num_items` FROM (SELECT"), "{}", sql);
+        assert!(sql.contains("LIMIT ?) AS `capped`"), "{}", sql);
+    }
+
+    #[tokio::test]
+    async fn capped_count_matches_real_rows_up_to_the_cap() {
+        use crate::entities::posts;
+        use sea_orm::{ActiveModelTrait, Database, IntoActiveModel, Schema};
+
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        db.execute_unprepared("PRAGMA foreign_keys = OFF").await.unwrap();
+        let backend = db.get_database_backend();
+        db.execute(backend.build(&Schema::new(backend).create_table_from_entity(posts::Entity)))
+            .await
+            .unwrap();
+        for id in ["p1", "p2", "p3"] {
+            posts::Model {
+                id: id.to_string(),
+                author_id: "u1".to_string(),
+                user_id: "u1".to_string(),
+                content: "hi".to_string(),
+                image_url: None,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            }
+            .into_active_model()
+            .insert(&db)
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(count_capped(posts::Entity::find(), &db, 2).await.unwrap(), 2);
+        assert_eq!(count_capped(posts::Entity::find(), &db, 10).await.unwrap(), 3);
+    }
+}
@@ -1,14 +1,21 @@
 //! HTTP request helpers.
 
+use std::net::IpAddr;
+
 use axum::http::{HeaderMap, HeaderValue};
 
 /// Extract client IP from headers (X-Forwarded-For, X-Real-IP).
+///
+/// Takes the rightmost `X-Forwarded-For` hop, the one our own proxy appended;
+/// entries to its left are whatever the client sent. The headers are only as
+/// trustworthy as the peer that set them, so anything that keys on the client
+/// (rate limits, allowlists) should use [`forwarded_client_ip`] instead.
 pub fn client_ip(headers: &HeaderMap) -> Option<String> {
     // Try X-Forwarded-For first
     if let Some(forwarded) = headers.get("x-forwarded-for") {
         if let Ok(value) = forwarded.to_str() {
-            if let Some(ip) = value.split(',').next() {
-                return Some(ip.trim().to_string());
+            if let Some(ip) = value.rsplit(',').map(str::trim).find(|ip| !ip.is_empty()) {
+                return Some(ip.to_string());
             }
         }
     }
@@ -30,6 +37,84 @@ pub fn client_ip(headers: &HeaderMap) -> Option<String> {
     None
 }
 
+/// Proxies (single addresses or CIDR ranges) whose forwarding headers are
+/// believed.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Vec<(IpAddr, u8)>);
+
+impl TrustedProxies {
+    /// Parses entries like `127.0.0.1`, `::1` or `10.0.0.0/8`, skipping
+    /// invalid ones.
+    pub fn parse<S: AsRef<str>>(entries: &[S]) -> Self {
+        let ranges = entries
+            .iter()
+            .filter_map(|entry| {
+                let entry = entry.as_ref().trim();
+                let (addr, prefix) = match entry.split_once('/') {
+                    Some((addr, prefix)) => (addr, Some(prefix)),
+                    None => (entry, None),
+                };
+                let addr: IpAddr = addr.parse().ok()?;
+                let max = if addr.is_ipv4() { 32 } else { 128 };
+                let prefix = match prefix {
+                    Some(prefix) => prefix.parse().ok().filter(|p| *p <= max)?,
+                    None => max,
+                };
+                Some((addr, prefix))
+            })
+            .collect();
+        Self(ranges)
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.0.iter().any(|&(net, prefix)| match (ip, net) {
+            (IpAddr::V4(ip), IpAddr::V4(net)) => {
+                let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+                u32::from(ip) & mask == u32::from(net) & mask
+            }
+            (IpAddr::V6(ip), IpAddr::V6(net)) => {
+                let mask = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+                u128::from(ip) & mask == u128::from(net) & mask
+            }
+            _ => false,
+        })
+    }
+}
+
+/// The client behind a connection from `peer`.
+///
+/// Forwarding headers count only when `peer` is a trusted proxy. Then the
+/// client is the rightmost `X-Forwarded-For` hop that is not itself a trusted
+/// proxy (everything left of it is client-supplied and ignored), falling back
+/// to `X-Real-IP`. Otherwise the client is `peer` itself.
+pub fn forwarded_client_ip(peer: IpAddr, headers: &HeaderMap, trusted: &TrustedProxies) -> IpAddr {
+    if !trusted.contains(peer) {
+        return peer;
+    }
+
+    let forwarded = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect::<Vec<_>>();
+    for hop in forwarded.iter().rev() {
+        match hop.parse::<IpAddr>() {
+            Ok(ip) if trusted.contains(ip) => continue,
+            Ok(ip) => return ip,
+            Err(_) => break,
+        }
+    }
+
+    headers
+        .get("x-real-ip")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(peer)
+}
+
 /// Extract User-Agent from headers.
 pub fn user_agent(headers: &HeaderMap) -> Option<String> {
     headers
@@ -1,25 +1,34 @@
 //! HTML scraping helpers using scraper crate.
 
-use crate::helpers::{default_backoff, transient};
-use crate::infra::proxy::fetch_with_proxy;
+use crate::core::error::AppError;
+use crate::helpers::{permanent, scrape_backoff, transient};
+use crate::scraping::parse_report;
+use crate::scraping::render::fetch_source_html;
 use backoff::future::retry;
+use dashmap::DashMap;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use scraper::{ElementRef, Html, Selector};
-use tracing::{info, warn};
+use std::sync::Arc;
+use tracing::{error, info, warn};
 
-/// Fetch HTML from URL with retry backoff and proxy support.
+pub use crate::selectors;
+
+/// Fetch HTML from URL with retry backoff and proxy support. Sources in
+/// `CONFIG.browser_sources` are rendered in the browser pool instead.
 pub async fn fetch_html_with_retry(
     url: &str,
 ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    let backoff = default_backoff();
+    let backoff = scrape_backoff();
     let fetch_operation = || async {
         info!("Fetching: {}", url);
-        match fetch_with_proxy(url).await {
-            Ok(response) => {
+        match fetch_source_html(url).await {
+            Ok(html) => {
                 info!("Successfully fetched: {}", url);
-                Ok(response.data)
+                Ok(html)
             }
+            // Refused by robots.txt, or out of budget; retrying won't change that.
+            Err(e @ (AppError::BlockedTarget(_) | AppError::ScrapeBudgetExhausted(_))) => Err(permanent(e)),
             Err(e) => {
                 warn!("Failed to fetch: {}, error: {:?}", url, e);
                 Err(transient(e))
@@ -30,14 +39,83 @@ pub async fn fetch_html_with_retry(
     Ok(retry(backoff, fetch_operation).await?)
 }
 
+/// Fetches two pages concurrently with [`fetch_html_with_retry`], failing as
+/// soon as either fetch fails.
+pub async fn fetch_html_pair(
+    first: &str,
+    second: &str,
+) -> Result<(String, String), Box<dyn std::error::Error + Send + Sync>> {
+    fetch_pair_with(first, second, |url| async move { fetch_html_with_retry(&url).await }).await
+}
+
+/// [`fetch_html_pair`] with the page fetcher supplied by the caller.
+pub async fn fetch_pair_with<F, Fut, E>(first: &str, second: &str, fetch: F) -> Result<(String, String), E>
+where
+    F: Fn(String) -> Fut,
+    Fut: std::future::Future<Output = Result<String, E>>,
+{
+    tokio::try_join!(fetch(first.to_string()), fetch(second.to_string()))
+}
+
 /// Parse HTML string into a document.
 pub fn parse_html(html: &str) -> Html {
     Html::parse_document(html)
 }
 
-/// Safely create a CSS selector.
+/// Safely create a CSS selector. A malformed one is logged and `None`.
 pub fn selector(css: &str) -> Option<Selector> {
-    Selector::parse(css).ok()
+    match Selector::parse(css) {
+        Ok(selector) => Some(selector),
+        Err(e) => {
+            error!("Invalid CSS selector {:?}: {}", css, e);
+            None
+        }
+    }
+}
+
+/// Compiles `css`, never panicking: a malformed selector is logged and
+/// replaced by one that matches nothing, so it shows up as empty results
+/// (and a selector miss) instead of a 500.
+pub fn compile_selector(css: &str) -> Selector {
+    static MATCH_NOTHING: Lazy<Selector> =
+        Lazy::new(|| Selector::parse(":not(*)").expect("`:not(*)` is a valid selector"));
+    selector(css).unwrap_or_else(|| MATCH_NOTHING.clone())
+}
+
+/// [`compile_selector`] with the result kept for the life of the process,
+/// for helpers that take CSS as a string.
+pub fn cached_selector(css: &str) -> Arc<Selector> {
+    static COMPILED: Lazy<DashMap<String, Arc<Selector>>> = Lazy::new(DashMap::new);
+    if let Some(compiled) = COMPILED.get(css) {
+        return compiled.clone();
+    }
+    COMPILED
+        .entry(css.to_string())
+        .or_insert_with(|| Arc::new(compile_selector(css)))
+        .clone()
+}
+
+/// Declares selectors compiled once, on first use, with
+/// [`compile_selector`]:
+///
+/// ```ignore
+/// selectors! {
+///     ITEM_SELECTOR = "article.bs";
+///     TITLE_SELECTOR = ".tt h2";
+/// }
+/// document.select(&ITEM_SELECTOR)
+/// ```
+///
+/// Works at module level and inside function bodies.
+#[macro_export]
+macro_rules! selectors {
+    ($($(#[$meta:meta])* $vis:vis $name:ident = $css:expr;)*) => {
+        $(
+            $(#[$meta])*
+            $vis static $name: ::once_cell::sync::Lazy<::scraper::Selector> =
+                ::once_cell::sync::Lazy::new(|| $crate::helpers::scraping::compile_selector($css));
+        )*
+    };
 }
 
 /// Extract text content from an element, trimmed.
@@ -45,10 +123,13 @@ pub fn text(element: &ElementRef) -> String {
     element.text().collect::<String>().trim().to_string()
 }
 
-/// Extract text from first matching element.
+/// Extract text from first matching element. A miss is recorded in the
+/// current [`parse_report`].
 pub fn select_text(element: &ElementRef, css: &str) -> Option<String> {
-    let sel = selector(css)?;
-    element.select(&sel).next().map(|e| text(&e))
+    let sel = cached_selector(css);
+    let found = element.select(&sel).next();
+    parse_report::record(css, found.is_some());
+    found.map(|e| text(&e))
 }
 
 /// Extract text from first matching element using a pre-compiled selector.
@@ -61,14 +142,13 @@ pub fn text_from_or(element: &ElementRef, selector: &Selector, default: &str) ->
     text_from(element, selector).unwrap_or_else(|| default.to_string())
 }
 
-/// Extract attribute from first matching element.
+/// Extract attribute from first matching element. A miss is recorded in
+/// the current [`parse_report`].
 pub fn select_attr(element: &ElementRef, css: &str, attr: &str) -> Option<String> {
-    let sel = selector(css)?;
-    element
-        .select(&sel)
-        .next()
-        .and_then(|e| e.value().attr(attr))
-        .map(String::from)
+    let sel = cached_selector(css);
+    let found = element.select(&sel).next();
+    parse_report::record(css, found.is_some());
+    found.and_then(|e| e.value().attr(attr)).map(String::from)
 }
 
 /// Extract attribute from first matching element using a pre-compiled selector.
@@ -95,11 +175,27 @@ pub fn attr(element: &ElementRef, name: &str) -> Option<String> {
     element.value().attr(name).map(String::from)
 }
 
+/// Extract the real image URL from an `<img>`, preferring lazy-load attributes.
+///
+/// Checks `data-src`, `data-lazy-src`, `data-original`, the first `srcset`
+/// candidate, then `src`, skipping empty values and inline `data:` placeholders.
+pub fn extract_img_src(element: &ElementRef) -> Option<String> {
+    const ATTRS: [&str; 5] = ["data-src", "data-lazy-src", "data-original", "srcset", "src"];
+
+    ATTRS.iter().find_map(|name| {
+        let value = element.value().attr(name)?;
+        let value = if *name == "srcset" {
+            value.split_whitespace().next()?
+        } else {
+            value.trim()
+        };
+        (!value.is_empty() && !value.starts_with("data:")).then(|| value.to_string())
+    })
+}
+
 /// Select all matching elements.
 pub fn select_all<'a>(document: &'a Html, css: &str) -> Vec<ElementRef<'a>> {
-    selector(css)
-        .map(|s| document.select(&s).collect())
-        .unwrap_or_default()
+    document.select(&cached_selector(css)).collect()
 }
 
 /// Extract slug from URL (last path segment).
@@ -134,6 +230,30 @@ pub fn extract_parentheses(text: &str) -> Option<String> {
         .map(|m| m.as_str().to_string())
 }
 
+/// Split a labelled info line such as `"Produser: A, B"` into its trimmed,
+/// non-empty comma-separated values.
+pub fn split_labeled_list(text: &str, label: &str) -> Vec<String> {
+    let value = text.split_once(label).map(|(_, rest)| rest).unwrap_or(text);
+    value
+        .split(',')
+        .map(|part| part.trim().to_string())
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// Split alternative-title fields (`"A, B; C"`) into trimmed titles,
+/// deduplicated case-insensitively in first-seen order.
+pub fn split_alternative_titles<'a>(fields: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut titles: Vec<String> = Vec::new();
+    for part in fields.into_iter().flat_map(|f| f.split([',', ';', '|'])) {
+        let part = part.trim();
+        if !part.is_empty() && !titles.iter().any(|t| t.eq_ignore_ascii_case(part)) {
+            titles.push(part.to_string());
+        }
+    }
+    titles
+}
+
 /// Builder for scraping elements.
 pub struct Scraper<'a> {
     element: ElementRef<'a>,
@@ -174,3 +294,101 @@ impl<'a> Scraper<'a> {
         self.attr(css, "src")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::http_client::http_client_fast;
+    use axum::{extract::State, http::StatusCode, routing::get, Router};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    const PAGE_DELAY: Duration = Duration::from_millis(300);
+
+    type Arrivals = Arc<Mutex<Vec<(&'static str, Instant)>>>;
+
+    /// Serves `/ongoing` and `/complete`, each answering after `PAGE_DELAY`
+    /// and recording when its request arrived. `/broken` fails immediately.
+    async fn serve_lists() -> (String, Arrivals) {
+        let arrivals: Arrivals = Arc::default();
+        let page = |name: &'static str| {
+            move |State(arrivals): State<Arrivals>| async move {
+                arrivals.lock().unwrap().push((name, Instant::now()));
+                tokio::time::sleep(PAGE_DELAY).await;
+                format!("<html>{}</html>", name)
+            }
+        };
+        let app = Router::new()
+            .route("/ongoing", get(page("ongoing")))
+            .route("/complete", get(page("complete")))
+            .route("/broken", get(|| async { StatusCode::INTERNAL_SERVER_ERROR }))
+            .with_state(arrivals.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}", addr), arrivals)
+    }
+
+    async fn fetch_page(url: String) -> Result<String, reqwest::Error> {
+        http_client_fast().client().get(url).send().await?.error_for_status()?.text().await
+    }
+
+    selectors! {
+        TITLE_SELECTOR = ".tt h2";
+        BROKEN_SELECTOR = "div[";
+    }
+
+    #[test]
+    fn malformed_selectors_match_nothing_instead_of_panicking() {
+        let document = parse_html(r#"<div class="tt"><h2>Frieren</h2></div>"#);
+        let root = document.root_element();
+
+        assert_eq!(text_from(&root, &TITLE_SELECTOR).as_deref(), Some("Frieren"));
+        assert_eq!(text_from(&root, &BROKEN_SELECTOR), None);
+        assert_eq!(select_text(&root, "div["), None);
+        assert_eq!(select_attr(&root, "h2[", "class"), None);
+        assert!(select_all(&document, ":::").is_empty());
+        assert!(selector("div[").is_none());
+    }
+
+    #[test]
+    fn string_selectors_are_compiled_once() {
+        let first = cached_selector(".entry-title");
+        let second = cached_selector(".entry-title");
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(!Arc::ptr_eq(&first, &cached_selector(".entry-content")));
+    }
+
+    #[tokio::test]
+    async fn both_pages_are_requested_before_either_answers() {
+        let (base, arrivals) = serve_lists().await;
+        let started = Instant::now();
+
+        let (ongoing, complete) = fetch_pair_with(
+            &format!("{}/ongoing", base),
+            &format!("{}/complete", base),
+            fetch_page,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(ongoing, "<html>ongoing</html>");
+        assert_eq!(complete, "<html>complete</html>");
+        let arrivals = arrivals.lock().unwrap();
+        assert_eq!(arrivals.len(), 2);
+        let gap = arrivals[1].1.duration_since(arrivals[0].1);
+        assert!(gap < PAGE_DELAY, "second request arrived {:?} after the first", gap);
+        assert!(started.elapsed() < PAGE_DELAY * 2, "took {:?}", started.elapsed());
+    }
+
+    #[tokio::test]
+    async fn a_failed_page_does_not_wait_for_the_other() {
+        let (base, _) = serve_lists().await;
+        let started = Instant::now();
+
+        let result = fetch_pair_with(&format!("{}/ongoing", base), &format!("{}/broken", base), fetch_page).await;
+
+        assert!(result.is_err());
+        assert!(started.elapsed() < PAGE_DELAY, "took {:?}", started.elapsed());
+    }
+}
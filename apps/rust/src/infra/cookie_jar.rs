@@ -0,0 +1,254 @@
+//! Per-source cookie jars for upstreams that gate pages behind a session.
+//!
+//! Sources listed in `CONFIG.scrape_cookie_sources` get their own client whose
+//! [`Jar`] keeps cookies between requests, so a session cookie set on the
+//! first visit is echoed on later ones. An entry written as `host=persist`
+//! also snapshots the jar to Redis, so the session survives restarts.
+//! Every other host uses the shared [`http_client`] as before.
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use redis::AsyncCommands;
+use reqwest::cookie::{CookieStore, Jar};
+use reqwest::{Client, ClientBuilder};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+use url::Url;
+
+use crate::core::config::CONFIG;
+use crate::infra::http_client::http_client;
+use crate::infra::redis::get_redis_conn;
+
+/// How long a persisted cookie snapshot is kept in Redis.
+const COOKIE_SNAPSHOT_TTL: u64 = 60 * 60 * 24;
+
+/// Cookie handling configured for one source host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CookieMode {
+    /// Cookies live in memory for the lifetime of the process.
+    Session,
+    /// Like `Session`, and the jar is snapshotted to Redis after each fetch.
+    Persist,
+}
+
+/// Parses `host` / `host=persist` entries into `(host, mode)` pairs.
+pub fn parse_cookie_sources(entries: &[String]) -> Vec<(String, CookieMode)> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let (host, mode) = match entry.trim().split_once('=') {
+                Some((host, "persist")) => (host, CookieMode::Persist),
+                Some((host, _)) => (host, CookieMode::Session),
+                None => (entry.trim(), CookieMode::Session),
+            };
+            let host = host.trim().trim_start_matches("www.").to_ascii_lowercase();
+            (!host.is_empty()).then_some((host, mode))
+        })
+        .collect()
+}
+
+/// Builds a scrape client that stores cookies in `jar`.
+pub fn session_client(jar: Arc<Jar>) -> reqwest::Result<Client> {
+    ClientBuilder::new()
+        .timeout(Duration::from_secs(30))
+        .connect_timeout(Duration::from_secs(10))
+        .cookie_provider(jar)
+        .build()
+}
+
+/// Loads `name=value; other=value` pairs (as returned by
+/// [`CookieStore::cookies`]) back into `jar` for `url`.
+pub fn seed_jar(jar: &Jar, url: &Url, header: &str) {
+    for pair in header.split(';').map(str::trim).filter(|p| p.contains('=')) {
+        jar.add_cookie_str(&format!("{}; Path=/", pair), url);
+    }
+}
+
+struct SourceSession {
+    jar: Arc<Jar>,
+    client: Client,
+}
+
+/// Session clients keyed by configured source host.
+pub struct SourceCookieJars {
+    sources: Vec<(String, CookieMode)>,
+    sessions: DashMap<String, Arc<SourceSession>>,
+}
+
+impl SourceCookieJars {
+    pub fn new(sources: Vec<(String, CookieMode)>) -> Self {
+        Self {
+            sources,
+            sessions: DashMap::new(),
+        }
+    }
+
+    fn source_for(&self, url: &Url) -> Option<(String, CookieMode)> {
+        let host = url.host_str()?.trim_start_matches("www.").to_ascii_lowercase();
+        self.sources
+            .iter()
+            .find(|(source, _)| host == *source || host.ends_with(&format!(".{}", source)))
+            .cloned()
+    }
+
+    async fn session(&self, url: &Url) -> Option<Arc<SourceSession>> {
+        let (source, mode) = self.source_for(url)?;
+        if let Some(existing) = self.sessions.get(&source) {
+            return Some(existing.clone());
+        }
+
+        let jar = Arc::new(Jar::default());
+        if mode == CookieMode::Persist {
+            if let Some(snapshot) = load_snapshot(&source).await {
+                debug!("[cookies] Restored session cookies for {}", source);
+                seed_jar(&jar, url, &snapshot);
+            }
+        }
+        let client = match session_client(jar.clone()) {
+            Ok(client) => client,
+            Err(e) => {
+                warn!("[cookies] Failed to build session client for {}: {}", source, e);
+                return None;
+            }
+        };
+
+        let session = Arc::new(SourceSession { jar, client });
+        Some(self.sessions.entry(source).or_insert(session).clone())
+    }
+
+    /// Client to use for `url`: the source's session client if it is
+    /// configured for cookies, otherwise the shared client.
+    pub async fn client_for(&self, url: &str) -> Client {
+        let session = match Url::parse(url) {
+            Ok(parsed) => self.session(&parsed).await,
+            Err(_) => None,
+        };
+        session
+            .map(|s| s.client.clone())
+            .unwrap_or_else(|| http_client().client().clone())
+    }
+
+    /// Snapshots the jar for `url`'s source to Redis if it is persisted.
+    pub async fn persist(&self, url: &str) {
+        let Ok(parsed) = Url::parse(url) else { return };
+        let Some((source, CookieMode::Persist)) = self.source_for(&parsed) else { return };
+        let Some(session) = self.sessions.get(&source).map(|s| s.clone()) else { return };
+
+        let Some(header) = session.jar.cookies(&parsed) else { return };
+        let Ok(header) = header.to_str() else { return };
+        if let Err(e) = save_snapshot(&source, header).await {
+            warn!("[cookies] Failed to persist cookies for {}: {}", source, e);
+        }
+    }
+}
+
+fn snapshot_key(source: &str) -> String {
+    format!("scrape:cookies:{}", source)
+}
+
+async fn load_snapshot(source: &str) -> Option<String> {
+    let mut conn = get_redis_conn().await.ok()?;
+    conn.get(snapshot_key(source)).await.ok()?
+}
+
+async fn save_snapshot(source: &str, header: &str) -> Result<(), crate::core::error::AppError> {
+    let mut conn = get_redis_conn().await?;
+    conn.set_ex::<_, _, ()>(snapshot_key(source), header, COOKIE_SNAPSHOT_TTL)
+        .await?;
+    Ok(())
+}
+
+/// Process-wide jars, configured from `CONFIG.scrape_cookie_sources`.
+pub static SOURCE_COOKIE_JARS: Lazy<SourceCookieJars> =
+    Lazy::new(|| SourceCookieJars::new(parse_cookie_sources(&CONFIG.scrape_cookie_sources)));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        http::{header, HeaderMap, StatusCode},
+        response::IntoResponse,
+        routing::get,
+        Router,
+    };
+
+    async fn serve(router: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    fn session_gated() -> Router {
+        Router::new()
+            .route(
+                "/",
+                get(|| async { ([(header::SET_COOKIE, "session=abc123; Path=/")], "welcome") }),
+            )
+            .route(
+                "/chapter",
+                get(|headers: HeaderMap| async move {
+                    let cookie = headers
+                        .get(header::COOKIE)
+                        .and_then(|v| v.to_str().ok())
+                        .unwrap_or_default();
+                    if cookie.contains("session=abc123") {
+                        (StatusCode::OK, "pages").into_response()
+                    } else {
+                        StatusCode::FORBIDDEN.into_response()
+                    }
+                }),
+            )
+    }
+
+    #[tokio::test]
+    async fn second_request_echoes_session_cookie() {
+        let base = serve(session_gated()).await;
+        let client = session_client(Arc::new(Jar::default())).unwrap();
+
+        // Without the first visit the page is refused.
+        let fresh = session_client(Arc::new(Jar::default())).unwrap();
+        let denied = fresh.get(format!("{}/chapter", base)).send().await.unwrap();
+        assert_eq!(denied.status(), reqwest::StatusCode::FORBIDDEN);
+
+        client.get(format!("{}/", base)).send().await.unwrap();
+        let page = client.get(format!("{}/chapter", base)).send().await.unwrap();
+        assert_eq!(page.status(), reqwest::StatusCode::OK);
+        assert_eq!(page.text().await.unwrap(), "pages");
+    }
+
+    #[tokio::test]
+    async fn seeded_jar_restores_session() {
+        let base = serve(session_gated()).await;
+        let url = Url::parse(&base).unwrap();
+
+        let jar = Arc::new(Jar::default());
+        seed_jar(&jar, &url, "session=abc123; theme=dark");
+        let client = session_client(jar).unwrap();
+
+        let page = client.get(format!("{}/chapter", base)).send().await.unwrap();
+        assert_eq!(page.status(), reqwest::StatusCode::OK);
+    }
+
+    #[test]
+    fn parses_per_source_modes() {
+        let sources = parse_cookie_sources(&[
+            "www.Komiku.org=persist".to_string(),
+            "otakudesu.cloud".to_string(),
+            " ".to_string(),
+        ]);
+        assert_eq!(
+            sources,
+            vec![
+                ("komiku.org".to_string(), CookieMode::Persist),
+                ("otakudesu.cloud".to_string(), CookieMode::Session),
+            ]
+        );
+
+        let jars = SourceCookieJars::new(sources);
+        let api = Url::parse("https://api.komiku.org/manga").unwrap();
+        assert_eq!(jars.source_for(&api).map(|s| s.1), Some(CookieMode::Persist));
+        assert!(jars.source_for(&Url::parse("https://example.com").unwrap()).is_none());
+    }
+}
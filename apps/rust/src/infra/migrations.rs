@@ -0,0 +1,416 @@
+//! Versioned, idempotent schema migrations.
+//!
+//! Each [`Migration`] runs at most once; applied versions are recorded in the
+//! `_migrations` table. The runner is invoked at startup, by `--migrate`, and
+//! its status is exposed at `GET /api/admin/migrations`.
+
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr, Statement};
+use serde::Serialize;
+use tracing::info;
+use utoipa::ToSchema;
+
+/// A single schema migration.
+pub struct Migration {
+    /// Sortable version, e.g. `20250101000000`.
+    pub version: &'static str,
+    pub name: &'static str,
+    pub up: for<'a> fn(&'a DatabaseConnection) -> BoxFuture<'a, Result<(), DbErr>>,
+}
+
+/// A migration version recorded as applied.
+#[derive(Debug, Clone)]
+pub struct AppliedMigration {
+    pub version: String,
+    pub applied_at: String,
+}
+
+/// Applied/pending state of a known migration.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MigrationStatus {
+    pub version: String,
+    pub name: String,
+    pub applied: bool,
+    pub applied_at: Option<String>,
+}
+
+/// Where applied migration versions are recorded.
+#[async_trait]
+pub trait MigrationStore: Send + Sync {
+    async fn ensure_table(&self) -> Result<(), DbErr>;
+    async fn applied(&self) -> Result<Vec<AppliedMigration>, DbErr>;
+    async fn record(&self, version: &str, name: &str) -> Result<(), DbErr>;
+}
+
+/// [`MigrationStore`] backed by the `_migrations` table.
+pub struct DbMigrationStore<'a> {
+    db: &'a DatabaseConnection,
+}
+
+impl<'a> DbMigrationStore<'a> {
+    pub fn new(db: &'a DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl MigrationStore for DbMigrationStore<'_> {
+    async fn ensure_table(&self) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS _migrations (
+                version VARCHAR(32) NOT NULL PRIMARY KEY,
+                name VARCHAR(255) NOT NULL,
+                applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+            ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+        "#;
+        self.db
+            .execute(Statement::from_string(self.db.get_database_backend(), sql))
+            .await
+            .map(|_| ())
+    }
+
+    async fn applied(&self) -> Result<Vec<AppliedMigration>, DbErr> {
+        let rows = self
+            .db
+            .query_all(Statement::from_string(
+                self.db.get_database_backend(),
+                "SELECT version, CAST(applied_at AS CHAR) AS applied_at FROM _migrations ORDER BY version",
+            ))
+            .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(AppliedMigration {
+                    version: row.try_get("", "version")?,
+                    applied_at: row.try_get("", "applied_at")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn record(&self, version: &str, name: &str) -> Result<(), DbErr> {
+        self.db
+            .execute(Statement::from_sql_and_values(
+                self.db.get_database_backend(),
+                "INSERT INTO _migrations (version, name) VALUES (?, ?)",
+                [version.into(), name.into()],
+            ))
+            .await
+            .map(|_| ())
+    }
+}
+
+/// Runs an ordered list of migrations against a store.
+pub struct MigrationRunner {
+    migrations: Vec<Migration>,
+}
+
+impl MigrationRunner {
+    pub fn new(mut migrations: Vec<Migration>) -> Self {
+        migrations.sort_by_key(|m| m.version);
+        Self { migrations }
+    }
+
+    /// Lists every known migration with its applied state.
+    pub async fn status(&self, store: &dyn MigrationStore) -> Result<Vec<MigrationStatus>, DbErr> {
+        store.ensure_table().await?;
+        let applied = store.applied().await?;
+
+        Ok(self
+            .migrations
+            .iter()
+            .map(|m| {
+                let record = applied.iter().find(|a| a.version == m.version);
+                MigrationStatus {
+                    version: m.version.to_string(),
+                    name: m.name.to_string(),
+                    applied: record.is_some(),
+                    applied_at: record.map(|a| a.applied_at.clone()),
+                }
+            })
+            .collect())
+    }
+
+    /// Applies pending migrations in version order, returning the versions run.
+    /// Stops at the first failure; earlier migrations stay recorded.
+    pub async fn run(
+        &self,
+        db: &DatabaseConnection,
+        store: &dyn MigrationStore,
+    ) -> Result<Vec<String>, DbErr> {
+        let pending: Vec<&Migration> = {
+            let status = self.status(store).await?;
+            self.migrations
+                .iter()
+                .zip(status)
+                .filter(|(_, s)| !s.applied)
+                .map(|(m, _)| m)
+                .collect()
+        };
+
+        let mut ran = Vec::new();
+        for migration in pending {
+            info!("   ↻ Applying migration {} ({})", migration.version, migration.name);
+            (migration.up)(db).await?;
+            store.record(migration.version, migration.name).await?;
+            ran.push(migration.version.to_string());
+        }
+        Ok(ran)
+    }
+}
+
+fn initial_schema(db: &DatabaseConnection) -> BoxFuture<'_, Result<(), DbErr>> {
+    Box::pin(crate::infra::db_setup::init(db))
+}
+
+fn create_chat_message_room(db: &DatabaseConnection) -> BoxFuture<'_, Result<(), DbErr>> {
+    Box::pin(async move {
+        let backend = db.get_database_backend();
+        let stmt = sea_orm::Schema::new(backend)
+            .create_table_from_entity(crate::entities::chat_message_room::Entity)
+            .if_not_exists()
+            .to_owned();
+        db.execute(backend.build(&stmt)).await.map(|_| ())
+    })
+}
+
+/// Covers the history query: filter by room, walk backwards by time.
+fn index_chat_message_room_created(db: &DatabaseConnection) -> BoxFuture<'_, Result<(), DbErr>> {
+    Box::pin(async move {
+        db.execute(Statement::from_string(
+            db.get_database_backend(),
+            "CREATE INDEX idx_chat_message_room_room_created ON ChatMessage_room (room_id, created_at)",
+        ))
+        .await
+        .map(|_| ())
+    })
+}
+
+/// Whether `sql`, a `SELECT COUNT(*) AS n ...`, counts anything.
+async fn exists(db: &DatabaseConnection, sql: &str) -> Result<bool, DbErr> {
+    let row = db
+        .query_one(Statement::from_string(db.get_database_backend(), sql))
+        .await?;
+    Ok(match row {
+        Some(row) => row.try_get::<i64>("", "n")? > 0,
+        None => false,
+    })
+}
+
+/// Resume progress on bookmarks, and one bookmark per user and title so
+/// re-bookmarking updates the existing row.
+///
+/// Existing duplicates are collapsed to the most recently updated row before
+/// the unique index goes on, and each step checks `information_schema` first
+/// so a run interrupted halfway can simply be repeated.
+fn bookmark_progress(db: &DatabaseConnection) -> BoxFuture<'_, Result<(), DbErr>> {
+    Box::pin(async move {
+        let backend = db.get_database_backend();
+        let has_column = exists(
+            db,
+            "SELECT COUNT(*) AS n FROM information_schema.COLUMNS \
+             WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = 'bookmarks' \
+             AND COLUMN_NAME = 'episode_or_chapter'",
+        )
+        .await?;
+        if !has_column {
+            db.execute(Statement::from_string(
+                backend,
+                "ALTER TABLE bookmarks ADD COLUMN episode_or_chapter VARCHAR(255) NULL",
+            ))
+            .await?;
+        }
+
+        let has_index = exists(
+            db,
+            "SELECT COUNT(*) AS n FROM information_schema.STATISTICS \
+             WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = 'bookmarks' \
+             AND INDEX_NAME = 'uq_bookmarks_user_content'",
+        )
+        .await?;
+        if !has_index {
+            for sql in [
+                "DELETE older FROM bookmarks older JOIN bookmarks newer \
+                 ON newer.user_id = older.user_id AND newer.content_type = older.content_type \
+                 AND newer.slug = older.slug \
+                 AND (newer.updated_at > older.updated_at \
+                 OR (newer.updated_at = older.updated_at AND newer.id > older.id))",
+                "CREATE UNIQUE INDEX uq_bookmarks_user_content ON bookmarks (user_id, content_type, slug)",
+            ] {
+                db.execute(Statement::from_string(backend, sql)).await?;
+            }
+        }
+        Ok(())
+    })
+}
+
+fn create_user_preferences(db: &DatabaseConnection) -> BoxFuture<'_, Result<(), DbErr>> {
+    Box::pin(async move {
+        let backend = db.get_database_backend();
+        let stmt = sea_orm::Schema::new(backend)
+            .create_table_from_entity(crate::entities::user_preferences::Entity)
+            .if_not_exists()
+            .to_owned();
+        db.execute(backend.build(&stmt)).await.map(|_| ())
+    })
+}
+
+/// All migrations known to this build, oldest first.
+pub fn all_migrations() -> Vec<Migration> {
+    vec![
+        Migration {
+            version: "20250101000000",
+            name: "initial_schema",
+            up: initial_schema,
+        },
+        Migration {
+            version: "20250201000000",
+            name: "create_chat_message_room",
+            up: create_chat_message_room,
+        },
+        Migration {
+            version: "20250215000000",
+            name: "index_chat_message_room_created",
+            up: index_chat_message_room_created,
+        },
+        Migration {
+            version: "20250301000000",
+            name: "bookmark_progress",
+            up: bookmark_progress,
+        },
+        Migration {
+            version: "20250315000000",
+            name: "create_user_preferences",
+            up: create_user_preferences,
+        },
+    ]
+}
+
+/// Applies all pending migrations against `db`.
+pub async fn migrate(db: &DatabaseConnection) -> Result<Vec<String>, DbErr> {
+    let runner = MigrationRunner::new(all_migrations());
+    let ran = runner.run(db, &DbMigrationStore::new(db)).await?;
+    if ran.is_empty() {
+        info!("✓ Database schema up to date");
+    } else {
+        info!("✓ Applied {} migration(s): {}", ran.len(), ran.join(", "));
+    }
+    Ok(ran)
+}
+
+/// Applied/pending status of all migrations against `db`.
+pub async fn status(db: &DatabaseConnection) -> Result<Vec<MigrationStatus>, DbErr> {
+    MigrationRunner::new(all_migrations())
+        .status(&DbMigrationStore::new(db))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DatabaseBackend, MockDatabase};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryStore {
+        applied: Mutex<Vec<AppliedMigration>>,
+    }
+
+    #[async_trait]
+    impl MigrationStore for MemoryStore {
+        async fn ensure_table(&self) -> Result<(), DbErr> {
+            Ok(())
+        }
+
+        async fn applied(&self) -> Result<Vec<AppliedMigration>, DbErr> {
+            Ok(self.applied.lock().unwrap().clone())
+        }
+
+        async fn record(&self, version: &str, _name: &str) -> Result<(), DbErr> {
+            self.applied.lock().unwrap().push(AppliedMigration {
+                version: version.to_string(),
+                applied_at: "2025-01-01 00:00:00".to_string(),
+            });
+            Ok(())
+        }
+    }
+
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+    fn counting(_: &DatabaseConnection) -> BoxFuture<'_, Result<(), DbErr>> {
+        Box::pin(async {
+            RUNS.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+    }
+
+    fn migrations() -> Vec<Migration> {
+        vec![
+            Migration { version: "002", name: "second", up: counting },
+            Migration { version: "001", name: "first", up: counting },
+        ]
+    }
+
+    #[tokio::test]
+    async fn reports_pending_then_marks_applied() {
+        let db = MockDatabase::new(DatabaseBackend::MySql).into_connection();
+        let store = MemoryStore::default();
+        let runner = MigrationRunner::new(migrations());
+
+        let before = runner.status(&store).await.unwrap();
+        assert_eq!(before.len(), 2);
+        assert!(before.iter().all(|m| !m.applied));
+        assert_eq!(before[0].version, "001");
+
+        let ran = runner.run(&db, &store).await.unwrap();
+        assert_eq!(ran, ["001", "002"]);
+        assert_eq!(RUNS.load(Ordering::SeqCst), 2);
+
+        let after = runner.status(&store).await.unwrap();
+        assert!(after.iter().all(|m| m.applied && m.applied_at.is_some()));
+
+        // Idempotent: a second run applies nothing.
+        assert!(runner.run(&db, &store).await.unwrap().is_empty());
+        assert_eq!(RUNS.load(Ordering::SeqCst), 2);
+    }
+
+    fn count(n: i64) -> Vec<std::collections::BTreeMap<String, sea_orm::Value>> {
+        vec![[("n".to_string(), sea_orm::Value::BigInt(Some(n)))].into()]
+    }
+
+    fn executed(log: Vec<sea_orm::Transaction>) -> Vec<String> {
+        log.iter()
+            .flat_map(|t| t.statements())
+            .map(|s| s.sql.clone())
+            .filter(|sql| !sql.starts_with("SELECT"))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn bookmark_progress_dedupes_before_indexing() {
+        let db = MockDatabase::new(DatabaseBackend::MySql)
+            .append_query_results([count(0), count(0)])
+            .append_exec_results((0..3).map(|_| sea_orm::MockExecResult { last_insert_id: 0, rows_affected: 0 }))
+            .into_connection();
+        bookmark_progress(&db).await.unwrap();
+
+        let sql = executed(db.into_transaction_log());
+        assert_eq!(sql.len(), 3);
+        assert!(sql[0].starts_with("ALTER TABLE bookmarks ADD COLUMN"));
+        assert!(sql[1].starts_with("DELETE older FROM bookmarks"));
+        assert!(sql[2].starts_with("CREATE UNIQUE INDEX uq_bookmarks_user_content"));
+    }
+
+    #[tokio::test]
+    async fn bookmark_progress_can_be_rerun() {
+        // Column and index already exist, e.g. after a run that failed to
+        // record itself: nothing is altered again.
+        let db = MockDatabase::new(DatabaseBackend::MySql)
+            .append_query_results([count(1), count(1)])
+            .into_connection();
+        bookmark_progress(&db).await.unwrap();
+        assert!(executed(db.into_transaction_log()).is_empty());
+    }
+}
@@ -1,10 +1,13 @@
 //! Infrastructure utilities - Redis, HTTP clients, proxies.
 
+pub mod cookie_jar;
 pub mod db_setup;
 pub mod http_client;
-pub mod image_proxy;
+pub mod migrations;
 pub mod proxy;
+pub mod proxy_limits;
 pub mod redis;
+pub mod scrape_budget;
 
 pub use http_client::{http_client, HttpClient, HTTP_CLIENT};
 pub use redis::REDIS_POOL;
@@ -4,15 +4,26 @@
 use dashmap::DashMap;
 use once_cell::sync::Lazy;
 use redis::AsyncCommands;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use tokio::sync::broadcast;
 use tracing::{debug, error, warn};
+use url::{Host, Url};
 
-use crate::helpers::cache_ttl::CACHE_TTL_VERY_SHORT;
+use crate::circuit_breaker::SOURCE_BREAKERS;
+use crate::core::config::CONFIG;
+use crate::helpers::cache_ttl::{CACHE_TTL_VERY_LONG, CACHE_TTL_VERY_SHORT};
+use crate::infra::cookie_jar::SOURCE_COOKIE_JARS;
 use crate::infra::http_client::http_client;
+use crate::infra::proxy_limits::ProxyLimits;
 use crate::infra::redis::get_redis_conn;
+use crate::infra::scrape_budget::{ScrapeBudget, PROXY_BUDGET, SCRAPE_BUDGET};
 use crate::core::error::AppError;
-use crate::helpers::http::common_headers;
+use crate::helpers::http::SourceRequest;
 use crate::helpers::http::is_internet_baik_block_page;
+use crate::observability::metrics::{
+    record_cache_lookup, record_scrape_failure, record_upstream_fetch, source_label,
+};
+use crate::scraping::robots::ROBOTS;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct FetchResult {
@@ -34,7 +45,7 @@ impl std::fmt::Display for FetchResult {
 
 // Global In-Flight Request Map for Request Coalescing
 // Maps URL slug -> Broadcast Sender
-static IN_FLIGHT: Lazy<DashMap<String, broadcast::Sender<Result<FetchResult, String>>>> =
+static IN_FLIGHT: Lazy<DashMap<String, broadcast::Sender<Result<FetchResult, InFlightError>>>> =
     Lazy::new(DashMap::new);
 
 // --- REDIS CACHE WRAPPER START ---
@@ -48,17 +59,47 @@ async fn get_cached_fetch(slug: &str) -> Result<Option<FetchResult>, AppError> {
 
     let cached: Option<String> = conn.get(&key).await?;
 
-    if let Some(cached_str) = cached {
-        match serde_json::from_str::<FetchResult>(&cached_str) {
-            Ok(parsed) => {
-                debug!("[fetchWithProxy] Returning cached response for {}", slug);
-                Ok(Some(parsed))
-            }
-            Err(_) => Ok(None),
-        }
-    } else {
-        Ok(None)
+    let parsed = cached.and_then(|cached_str| serde_json::from_str::<FetchResult>(&cached_str).ok());
+    record_cache_lookup("fetch", parsed.is_some());
+    if parsed.is_some() {
+        debug!("[fetchWithProxy] Returning cached response for {}", slug);
+    }
+    Ok(parsed)
+}
+
+/// Metrics label for fetches of URLs a proxy caller chose.
+const USER_TARGET_SOURCE: &str = "user_target";
+
+/// Runs an upstream fetch through its source's circuit breaker, recording
+/// its duration per source host. Short-circuited calls are not recorded.
+///
+/// User targets skip the breakers and are recorded under one fixed label:
+/// arbitrary hosts would otherwise mint a breaker and a metrics series each,
+/// and a caller could trip the breaker of a real source by pointing the
+/// proxy at its host.
+async fn timed_fetch<Fut>(slug: &str, origin: FetchOrigin, fetch: Fut) -> Result<FetchResult, AppError>
+where
+    Fut: std::future::Future<Output = Result<FetchResult, AppError>>,
+{
+    if origin == FetchOrigin::UserTarget {
+        let start = std::time::Instant::now();
+        let result = fetch.await;
+        record_upstream_fetch(USER_TARGET_SOURCE, result.is_ok(), start.elapsed().as_secs_f64());
+        return result;
     }
+
+    let source = source_label(slug);
+    SOURCE_BREAKERS
+        .call(&source, async {
+            let start = std::time::Instant::now();
+            let result = fetch.await;
+            record_upstream_fetch(&source, result.is_ok(), start.elapsed().as_secs_f64());
+            if let Err(e) = &result {
+                record_scrape_failure(&source, slug, &e.to_string());
+            }
+            result
+        })
+        .await
 }
 
 async fn set_cached_fetch(slug: &str, value: &FetchResult) -> Result<(), AppError> {
@@ -69,12 +110,372 @@ async fn set_cached_fetch(slug: &str, value: &FetchResult) -> Result<(), AppErro
     // Use standardized TTL
     conn.set_ex::<_, _, ()>(&key, &json_string, CACHE_TTL_VERY_SHORT)
         .await?;
+    // Long-lived copy served when the scrape budget is exhausted
+    conn.set_ex::<_, _, ()>(get_stale_cache_key(slug), &json_string, CACHE_TTL_VERY_LONG)
+        .await?;
     Ok(())
 }
+
+fn get_stale_cache_key(slug: &str) -> String {
+    format!("fetch:stale:{slug}")
+}
+
+async fn get_stale_fetch(slug: &str) -> Option<FetchResult> {
+    let mut conn = get_redis_conn().await.ok()?;
+    let cached: Option<String> = conn.get(get_stale_cache_key(slug)).await.ok()?;
+    cached.and_then(|s| serde_json::from_str(&s).ok())
+}
 // --- REDIS CACHE WRAPPER END ---
 
+/// Run `fetch` if the scrape budget has a token left; otherwise serve the
+/// stale copy from `stale`, or fail with 503 when there is none.
+async fn within_budget<F, Fut, S, SFut>(
+    budget: &ScrapeBudget,
+    slug: &str,
+    stale: S,
+    fetch: F,
+) -> Result<FetchResult, AppError>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<FetchResult, AppError>>,
+    S: FnOnce() -> SFut,
+    SFut: std::future::Future<Output = Option<FetchResult>>,
+{
+    if budget.try_acquire() {
+        return fetch().await;
+    }
+
+    match stale().await {
+        Some(cached) => {
+            warn!("[ScrapeBudget] Budget exhausted, serving stale copy of {}", slug);
+            Ok(cached)
+        }
+        None => {
+            warn!("[ScrapeBudget] Budget exhausted, no stale copy of {}", slug);
+            Err(AppError::ScrapeBudgetExhausted(slug.to_string()))
+        }
+    }
+}
+
+// --- TARGET VALIDATION START ---
+
+/// Validate a user-supplied proxy target before fetching it.
+///
+/// Rejects non-http(s) schemes, hosts that resolve to loopback, private,
+/// link-local or otherwise non-public addresses, and (when
+/// `CONFIG.proxy_allowed_domains` is set) hosts outside the allowlist.
+/// Every resolved address is checked, so a public hostname that also
+/// resolves to an internal IP is rejected.
+pub async fn validate_target(raw: &str) -> Result<Url, AppError> {
+    Ok(resolve_target(raw).await?.url)
+}
+
+/// A validated target and the checked addresses its host resolved to.
+struct ResolvedTarget {
+    url: Url,
+    /// Empty for IP-literal hosts, which need no pinning.
+    addrs: Vec<SocketAddr>,
+}
+
+async fn resolve_target(raw: &str) -> Result<ResolvedTarget, AppError> {
+    let url = Url::parse(raw.trim())
+        .map_err(|e| AppError::BlockedTarget(format!("invalid URL '{}': {}", raw, e)))?;
+
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(AppError::BlockedTarget(format!(
+            "scheme '{}' is not allowed",
+            url.scheme()
+        )));
+    }
+
+    let host = url
+        .host()
+        .ok_or_else(|| AppError::BlockedTarget("URL has no host".to_string()))?;
+
+    let mut pinned = Vec::new();
+    match host {
+        Host::Ipv4(ip) => check_ip(IpAddr::V4(ip))?,
+        Host::Ipv6(ip) => check_ip(IpAddr::V6(ip))?,
+        Host::Domain(domain) => {
+            if !is_domain_allowed(domain, &CONFIG.proxy_allowed_domains) {
+                return Err(AppError::BlockedTarget(format!(
+                    "host '{}' is not in the proxy allowlist",
+                    domain
+                )));
+            }
+
+            let port = url.port_or_known_default().unwrap_or(80);
+            pinned = tokio::net::lookup_host((domain, port))
+                .await
+                .map_err(|e| {
+                    AppError::BlockedTarget(format!("could not resolve host '{}': {}", domain, e))
+                })?
+                .collect();
+
+            let addrs: Vec<IpAddr> = pinned.iter().map(|addr| addr.ip()).collect();
+            check_resolved(domain, &addrs)?;
+        }
+    }
+
+    Ok(ResolvedTarget { url, addrs: pinned })
+}
+
+/// Most redirects [`send_validated`] follows before giving up.
+const MAX_TARGET_REDIRECTS: usize = 5;
+
+/// GET a user-supplied URL, validating every hop.
+///
+/// Redirects are followed here rather than by reqwest, so each `Location`
+/// goes through [`validate_target`] again. Every hop also runs on a client
+/// pinned to the addresses that passed the check, so a second DNS answer
+/// (rebinding) can't send the connection to an internal host. `prepare` adds
+/// headers to each hop's request.
+pub async fn send_validated<F>(raw: &str, prepare: F) -> Result<reqwest::Response, AppError>
+where
+    F: Fn(reqwest::RequestBuilder) -> reqwest::RequestBuilder,
+{
+    send_validated_within(raw, &[], prepare).await
+}
+
+/// [`send_validated`] that also keeps every hop on `hosts` (and their
+/// subdomains); an empty list allows any public host.
+pub async fn send_validated_within<F>(
+    raw: &str,
+    hosts: &[String],
+    prepare: F,
+) -> Result<reqwest::Response, AppError>
+where
+    F: Fn(reqwest::RequestBuilder) -> reqwest::RequestBuilder,
+{
+    let mut target = resolve_target(raw).await?;
+    for _ in 0..=MAX_TARGET_REDIRECTS {
+        let host = target.url.host_str().unwrap_or_default();
+        if !is_domain_allowed(host, hosts) {
+            return Err(AppError::BlockedTarget(format!("host '{}' is not allowed for this endpoint", host)));
+        }
+        let response = prepare(pinned_client(&target)?.get(target.url.clone())).send().await?;
+        let Some(next) = redirect_target(&target.url, response.status(), response.headers())? else {
+            return Ok(response);
+        };
+        debug!("[send_validated] {} redirected to {}", target.url, next);
+        target = resolve_target(next.as_str()).await?;
+    }
+    Err(AppError::BlockedTarget(format!(
+        "'{}' redirected more than {} times",
+        raw, MAX_TARGET_REDIRECTS
+    )))
+}
+
+/// Where a redirect points, resolved against the URL that answered; `None`
+/// for anything that isn't a redirect with a `Location`.
+fn redirect_target(
+    current: &Url,
+    status: reqwest::StatusCode,
+    headers: &reqwest::header::HeaderMap,
+) -> Result<Option<Url>, AppError> {
+    let location = headers
+        .get(reqwest::header::LOCATION)
+        .and_then(|value| value.to_str().ok());
+    match location {
+        Some(location) if status.is_redirection() => current
+            .join(location)
+            .map(Some)
+            .map_err(|e| AppError::BlockedTarget(format!("invalid redirect '{}': {}", location, e))),
+        _ => Ok(None),
+    }
+}
+
+/// A client that never follows redirects and only connects to `target`'s
+/// checked addresses.
+fn pinned_client(target: &ResolvedTarget) -> Result<reqwest::Client, AppError> {
+    let mut builder = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .connect_timeout(std::time::Duration::from_secs(10))
+        .user_agent("RustExpress/1.0");
+    if let Some(domain) = target.url.domain() {
+        builder = builder.resolve_to_addrs(domain, &target.addrs);
+    }
+    Ok(builder.build()?)
+}
+
+/// Reject a host whose resolved address set is empty or contains any
+/// non-public address.
+fn check_resolved(host: &str, addrs: &[IpAddr]) -> Result<(), AppError> {
+    if addrs.is_empty() {
+        return Err(AppError::BlockedTarget(format!(
+            "host '{}' did not resolve to any address",
+            host
+        )));
+    }
+    for ip in addrs {
+        check_ip(*ip).map_err(|_| {
+            AppError::BlockedTarget(format!(
+                "host '{}' resolves to non-public address {}",
+                host, ip
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+fn check_ip(ip: IpAddr) -> Result<(), AppError> {
+    if is_forbidden_ip(ip) {
+        Err(AppError::BlockedTarget(format!(
+            "address {} is not publicly routable",
+            ip
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// True for loopback, private, link-local, unspecified and other addresses
+/// that must never be reachable through the proxy. IPv6 addresses carrying
+/// an IPv4 one are judged by the IPv4 address.
+pub fn is_forbidden_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_forbidden_ipv4(v4),
+        IpAddr::V6(v6) => {
+            if let Some(embedded) = embedded_ipv4(v6) {
+                return is_forbidden_ipv4(embedded);
+            }
+            let first = v6.segments()[0];
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || (first & 0xfe00) == 0xfc00 // unique local fc00::/7
+                || (first & 0xffc0) == 0xfe80 // link-local fe80::/10
+        }
+    }
+}
+
+/// The IPv4 address inside an IPv4-mapped (`::ffff:a.b.c.d`),
+/// IPv4-compatible (`::a.b.c.d`), NAT64 (`64:ff9b::/96`) or 6to4
+/// (`2002::/16`) address.
+fn embedded_ipv4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    if let Some(v4) = ip.to_ipv4_mapped().or_else(|| ip.to_ipv4()) {
+        return Some(v4);
+    }
+    let segments = ip.segments();
+    let octets = ip.octets();
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        return Some(Ipv4Addr::new(octets[12], octets[13], octets[14], octets[15]));
+    }
+    if segments[0] == 0x2002 {
+        return Some(Ipv4Addr::new(octets[2], octets[3], octets[4], octets[5]));
+    }
+    None
+}
+
+fn is_forbidden_ipv4(ip: Ipv4Addr) -> bool {
+    let octets = ip.octets();
+    ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || octets[0] == 0 // "this network" 0.0.0.0/8
+        || (octets[0] == 100 && (octets[1] & 0xc0) == 64) // CGNAT 100.64.0.0/10
+        || (octets[0] == 198 && (octets[1] & 0xfe) == 18) // benchmarking 198.18.0.0/15
+        || octets[0] >= 240 // reserved 240.0.0.0/4
+}
+
+/// Check `host` against the allowlist. An empty allowlist allows every host;
+/// entries also match their subdomains.
+fn is_domain_allowed(host: &str, allowed: &[String]) -> bool {
+    if allowed.is_empty() {
+        return true;
+    }
+    let host = host.trim_end_matches('.').to_lowercase();
+    allowed.iter().any(|entry| {
+        let entry = entry.trim().trim_start_matches("*.").to_lowercase();
+        !entry.is_empty() && (host == entry || host.ends_with(&format!(".{}", entry)))
+    })
+}
+// --- TARGET VALIDATION END ---
+
+/// The error a leader hands its followers through [`IN_FLIGHT`]. It keeps
+/// the variants the proxy answers with their own status; anything else is
+/// passed on as its message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum InFlightError {
+    BlockedTarget(String),
+    Timeout(String),
+    PayloadTooLarge(String),
+    BudgetExhausted(String),
+    Other(String),
+}
+
+impl From<&AppError> for InFlightError {
+    fn from(err: &AppError) -> Self {
+        match err {
+            AppError::BlockedTarget(reason) => InFlightError::BlockedTarget(reason.clone()),
+            AppError::TimeoutError(reason) => InFlightError::Timeout(reason.clone()),
+            AppError::PayloadTooLarge(reason) => InFlightError::PayloadTooLarge(reason.clone()),
+            AppError::ScrapeBudgetExhausted(slug) => InFlightError::BudgetExhausted(slug.clone()),
+            other => InFlightError::Other(other.to_string()),
+        }
+    }
+}
+
+impl From<InFlightError> for AppError {
+    fn from(err: InFlightError) -> Self {
+        match err {
+            InFlightError::BlockedTarget(reason) => AppError::BlockedTarget(reason),
+            InFlightError::Timeout(reason) => AppError::TimeoutError(reason),
+            InFlightError::PayloadTooLarge(reason) => AppError::PayloadTooLarge(reason),
+            InFlightError::BudgetExhausted(slug) => AppError::ScrapeBudgetExhausted(slug),
+            InFlightError::Other(message) => AppError::Other(message),
+        }
+    }
+}
+
+/// Who chose the URL being fetched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FetchOrigin {
+    /// A scraper building a URL for one of its sources.
+    Source,
+    /// A caller of the public proxy; every hop goes through [`send_validated`].
+    UserTarget,
+}
+
+impl FetchOrigin {
+    /// Budget the fetch draws on: user targets have their own, so proxy
+    /// callers can't spend the scrapers' share.
+    fn budget(self) -> &'static ScrapeBudget {
+        match self {
+            FetchOrigin::Source => &SCRAPE_BUDGET,
+            FetchOrigin::UserTarget => &PROXY_BUDGET,
+        }
+    }
+
+    /// Key in [`IN_FLIGHT`], so a user target never joins a source fetch that
+    /// follows redirects unchecked.
+    fn in_flight_key(self, slug: &str) -> String {
+        match self {
+            FetchOrigin::Source => slug.to_string(),
+            FetchOrigin::UserTarget => format!("target:{}", slug),
+        }
+    }
+}
+
 /// Main entry point: Fetches with proxy, using Cache and Request Coalescing
 pub async fn fetch_with_proxy(slug: &str) -> Result<FetchResult, AppError> {
+    fetch_coalesced(slug, FetchOrigin::Source).await
+}
+
+/// [`fetch_with_proxy`] for a URL a client asked the proxy for: every redirect
+/// hop is validated and connections are pinned to the checked addresses.
+pub async fn fetch_target_with_proxy(slug: &str) -> Result<FetchResult, AppError> {
+    fetch_coalesced(slug, FetchOrigin::UserTarget).await
+}
+
+async fn fetch_coalesced(slug: &str, origin: FetchOrigin) -> Result<FetchResult, AppError> {
+    // 0. Refuse paths the source's robots.txt disallows (when configured)
+    ROBOTS.check(slug).await?;
+
     // 1. Try Cache First
     if let Ok(Some(cached)) = get_cached_fetch(slug).await {
         return Ok(cached);
@@ -82,14 +483,15 @@ pub async fn fetch_with_proxy(slug: &str) -> Result<FetchResult, AppError> {
 
     // 2. Request Coalescing (SingleFlight)
     // Check if there is already an in-flight request for this slug
+    let key = origin.in_flight_key(slug);
     let tx = {
-        if let Some(in_flight) = IN_FLIGHT.get(slug) {
+        if let Some(in_flight) = IN_FLIGHT.get(&key) {
             debug!("[Coalesce] Joining in-flight request for {}", slug);
             in_flight.value().clone()
         } else {
             // No in-flight request, create a new channel
             let (tx, _) = broadcast::channel(1); // Capacity 1 is enough for single result
-            IN_FLIGHT.insert(slug.to_string(), tx.clone());
+            IN_FLIGHT.insert(key.clone(), tx.clone());
             debug!("[Coalesce] Starting leader request for {}", slug);
 
             // We are the leader, we must execute the fetch
@@ -101,17 +503,22 @@ pub async fn fetch_with_proxy(slug: &str) -> Result<FetchResult, AppError> {
             let tx_clone = tx.clone();
 
             tokio::spawn(async move {
-                let result = perform_fetch(&slug_clone).await;
-
-                // Map AppError to String for broadcast (since AppError might not be Clone)
-                // FetchResult is Clone.
+                let result = within_budget(
+                    origin.budget(),
+                    &slug_clone,
+                    || get_stale_fetch(&slug_clone),
+                    || timed_fetch(&slug_clone, origin, perform_fetch(&slug_clone, origin)),
+                )
+                .await;
+
+                // AppError isn't Clone; followers each get their own copy.
                 let broadcast_result = match &result {
                     Ok(res) => Ok(res.clone()),
-                    Err(e) => Err(e.to_string()),
+                    Err(e) => Err(InFlightError::from(e)),
                 };
 
                 // Remove from map BEFORE broadcasting to allow retries if needed
-                IN_FLIGHT.remove(&slug_clone);
+                IN_FLIGHT.remove(&key);
 
                 // Broadcast result to all waiting subscribers
                 let _ = tx_clone.send(broadcast_result);
@@ -125,7 +532,7 @@ pub async fn fetch_with_proxy(slug: &str) -> Result<FetchResult, AppError> {
     let mut rx = tx.subscribe();
     match rx.recv().await {
         Ok(Ok(res)) => Ok(res),
-        Ok(Err(e_str)) => Err(AppError::Other(e_str)),
+        Ok(Err(e)) => Err(e.into()),
         Err(e) => {
             warn!("[Coalesce] Receive mismatch for {}: {:?}", slug, e);
             Err(AppError::Other("Request coalescing error".to_string()))
@@ -134,17 +541,38 @@ pub async fn fetch_with_proxy(slug: &str) -> Result<FetchResult, AppError> {
 }
 
 /// The actual fetch logic (Direct -> Retry)
-async fn perform_fetch(slug: &str) -> Result<FetchResult, AppError> {
-    // Use shared global HTTP client
-    let client = http_client().client();
-    let headers = common_headers();
+async fn perform_fetch(slug: &str, origin: FetchOrigin) -> Result<FetchResult, AppError> {
+    match origin {
+        FetchOrigin::Source => fetch_direct(slug, origin, None).await,
+        FetchOrigin::UserTarget => {
+            // The whole exchange, body included, runs under the proxy limits.
+            // The deadline drops the fetch inside the leader task itself, so
+            // nothing that arrives late can still land in the cache.
+            let limits = ProxyLimits::from_config();
+            limits.within_timeout(slug, fetch_direct(slug, origin, Some(&limits))).await
+        }
+    }
+}
 
-    match client
-        .get(slug)
-        .headers(headers)
-        .send() // Timeout handled by client
-        .await
-    {
+/// One direct fetch of `slug`; the body is read within `limits` when given.
+async fn fetch_direct(
+    slug: &str,
+    origin: FetchOrigin,
+    limits: Option<&ProxyLimits>,
+) -> Result<FetchResult, AppError> {
+    let sent = match origin {
+        FetchOrigin::Source => {
+            // Shared global client, or the source's session client if it needs cookies
+            let client = SOURCE_COOKIE_JARS.client_for(slug).await;
+            // Per-source user agent, headers and timeout from `APP__SCRAPE_SOURCES__*`
+            let request = SourceRequest::for_url(slug).apply(client.get(slug));
+            // Timeout handled by client unless the source overrides it
+            request.send().await.map_err(AppError::from)
+        }
+        FetchOrigin::UserTarget => send_validated(slug, |request| request).await,
+    };
+
+    match sent {
         Ok(res) => {
             debug!(
                 "[fetchWithProxy] Direct fetch response: url={}, status={}",
@@ -158,7 +586,10 @@ async fn perform_fetch(slug: &str) -> Result<FetchResult, AppError> {
                     .and_then(|h| h.to_str().ok())
                     .map(|s| s.to_string());
 
-                let bytes = res.bytes().await?;
+                let bytes = match limits {
+                    Some(limits) => limits.read_body(slug, res).await?,
+                    None => res.bytes().await?,
+                };
 
                 // Check if response is Gzip compressed (magic header 1f 8b)
                 let text_data = if bytes.len() > 2 && bytes[0] == 0x1f && bytes[1] == 0x8b {
@@ -204,6 +635,9 @@ async fn perform_fetch(slug: &str) -> Result<FetchResult, AppError> {
                     if let Err(e) = set_cached_fetch(slug, &result).await {
                         warn!("Failed to cache result for {}: {:?}", slug, e);
                     }
+                    if origin == FetchOrigin::Source {
+                        SOURCE_COOKIE_JARS.persist(slug).await;
+                    }
                     Ok(result)
                 }
             } else {
@@ -220,6 +654,10 @@ async fn perform_fetch(slug: &str) -> Result<FetchResult, AppError> {
                 Err(AppError::Other(error_msg))
             }
         }
+        Err(e @ AppError::BlockedTarget(_)) => {
+            warn!("Refused redirect while fetching {}: {}", slug, e);
+            Err(e)
+        }
         Err(e) => {
             let error_msg = format!("Direct fetch failed for {}: {:?}", slug, e);
             warn!("{}", error_msg);
@@ -229,11 +667,19 @@ async fn perform_fetch(slug: &str) -> Result<FetchResult, AppError> {
 }
 
 pub async fn fetch_with_proxy_only(slug: &str) -> Result<FetchResult, AppError> {
+    ROBOTS.check(slug).await?;
+
     if let Ok(Some(cached)) = get_cached_fetch(slug).await {
         return Ok(cached);
     }
 
-    fetch_from_single_proxy(slug).await
+    within_budget(
+        &SCRAPE_BUDGET,
+        slug,
+        || get_stale_fetch(slug),
+        || timed_fetch(slug, FetchOrigin::Source, fetch_from_single_proxy(slug)),
+    )
+    .await
 }
 
 async fn fetch_from_single_proxy(slug: &str) -> Result<FetchResult, AppError> {
@@ -293,3 +739,262 @@ async fn fetch_from_single_proxy(slug: &str) -> Result<FetchResult, AppError> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocked(result: Result<Url, AppError>) -> bool {
+        matches!(result, Err(AppError::BlockedTarget(_)))
+    }
+
+    #[tokio::test]
+    async fn test_validated_send_never_contacts_internal_hosts() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let app = axum::Router::new().fallback(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async { "internal" }
+        });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let result = send_validated(&format!("http://{}/admin", addr), |request| request).await;
+        assert!(matches!(result, Err(AppError::BlockedTarget(_))));
+        assert_eq!(hits.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_host_list_is_enforced_before_fetching() {
+        let posters = vec!["komiku.org".to_string()];
+        let result = send_validated_within("http://93.184.216.34/cover.jpg", &posters, |r| r).await;
+        assert!(matches!(result, Err(AppError::BlockedTarget(_))));
+    }
+
+    #[test]
+    fn test_redirects_resolve_against_the_answering_hop() {
+        use reqwest::header::{HeaderMap, HeaderValue, LOCATION};
+        use reqwest::StatusCode;
+
+        let current = Url::parse("https://cdn.example.com/a/b.jpg").unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(LOCATION, HeaderValue::from_static("/c.jpg"));
+        assert_eq!(
+            redirect_target(&current, StatusCode::FOUND, &headers).unwrap().unwrap().as_str(),
+            "https://cdn.example.com/c.jpg"
+        );
+
+        headers.insert(LOCATION, HeaderValue::from_static("http://169.254.169.254/latest"));
+        let next = redirect_target(&current, StatusCode::MOVED_PERMANENTLY, &headers).unwrap().unwrap();
+        assert_eq!(next.host_str(), Some("169.254.169.254"));
+
+        assert!(redirect_target(&current, StatusCode::OK, &headers).unwrap().is_none());
+        assert!(redirect_target(&current, StatusCode::FOUND, &HeaderMap::new()).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_non_http_schemes() {
+        assert!(blocked(validate_target("file:///etc/passwd").await));
+        assert!(blocked(validate_target("gopher://example.com/").await));
+        assert!(blocked(validate_target("not a url").await));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_internal_ip_literals() {
+        assert!(blocked(validate_target("http://169.254.169.254/latest/meta-data").await));
+        assert!(blocked(validate_target("http://localhost:6379").await));
+        assert!(blocked(validate_target("http://127.0.0.1/").await));
+        assert!(blocked(validate_target("http://0.0.0.0:4091/").await));
+        assert!(blocked(validate_target("http://10.0.0.5/").await));
+        assert!(blocked(validate_target("http://192.168.1.1/").await));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_ipv6_loopback_and_mapped() {
+        assert!(blocked(validate_target("http://[::1]/").await));
+        assert!(blocked(validate_target("http://[::]/").await));
+        assert!(blocked(validate_target("http://[::ffff:127.0.0.1]/").await));
+        assert!(blocked(validate_target("http://[fe80::1]/").await));
+        assert!(blocked(validate_target("http://[fd00::1]/").await));
+    }
+
+    #[test]
+    fn test_ipv6_forms_of_internal_ipv4_are_rejected() {
+        let forbidden = |ip: &str| is_forbidden_ip(ip.parse().unwrap());
+        // NAT64
+        assert!(forbidden("64:ff9b::7f00:1"));
+        assert!(forbidden("64:ff9b::a9fe:a9fe"));
+        assert!(!forbidden("64:ff9b::808:808"));
+        // 6to4
+        assert!(forbidden("2002:7f00:1::"));
+        assert!(forbidden("2002:c0a8:101::1"));
+        assert!(!forbidden("2002:808:808::1"));
+        // IPv4-compatible
+        assert!(forbidden("::127.0.0.1"));
+        assert!(forbidden("::10.0.0.5"));
+        assert!(!forbidden("::8.8.8.8"));
+    }
+
+    #[test]
+    fn test_reserved_ipv4_ranges_are_rejected() {
+        let forbidden = |ip: &str| is_forbidden_ip(ip.parse().unwrap());
+        // Benchmarking 198.18.0.0/15
+        assert!(forbidden("198.18.0.1"));
+        assert!(forbidden("198.19.255.254"));
+        assert!(!forbidden("198.20.0.1"));
+        assert!(!forbidden("198.17.255.255"));
+        // Reserved 240.0.0.0/4
+        assert!(forbidden("240.0.0.1"));
+        assert!(forbidden("250.1.2.3"));
+        assert!(!forbidden("223.255.255.1"));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_obfuscated_ipv4_hosts() {
+        // Decimal and hex forms are normalised to 127.0.0.1 by the URL parser
+        assert!(blocked(validate_target("http://2130706433/").await));
+        assert!(blocked(validate_target("http://0x7f000001/").await));
+    }
+
+    #[test]
+    fn test_rebind_style_resolution_is_rejected() {
+        // A hostname that resolves to both a public and an internal address
+        let addrs: Vec<IpAddr> = vec![
+            "93.184.216.34".parse().unwrap(),
+            "127.0.0.1".parse().unwrap(),
+        ];
+        assert!(check_resolved("rebind.example.com", &addrs).is_err());
+
+        let addrs: Vec<IpAddr> = vec!["::1".parse().unwrap()];
+        assert!(check_resolved("v6.rebind.example.com", &addrs).is_err());
+
+        let addrs: Vec<IpAddr> = vec!["93.184.216.34".parse().unwrap()];
+        assert!(check_resolved("example.com", &addrs).is_ok());
+
+        assert!(check_resolved("nxdomain.example.com", &[]).is_err());
+    }
+
+    #[test]
+    fn test_public_ips_are_allowed() {
+        assert!(!is_forbidden_ip("8.8.8.8".parse().unwrap()));
+        assert!(!is_forbidden_ip("2606:4700:4700::1111".parse().unwrap()));
+        assert!(is_forbidden_ip("100.64.0.1".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn user_targets_bypass_source_breakers() {
+        let url = "https://user-target-breaker.example/page";
+        for _ in 0..10 {
+            let failing = async { Err(AppError::Other("upstream down".to_string())) };
+            let result = timed_fetch(url, FetchOrigin::UserTarget, failing).await;
+            assert!(matches!(result, Err(AppError::Other(_))));
+        }
+        let states = SOURCE_BREAKERS.states().await;
+        assert!(!states.contains_key("user-target-breaker.example"), "{:?}", states.keys());
+    }
+
+    #[test]
+    fn in_flight_errors_keep_their_status() {
+        for err in [
+            AppError::BlockedTarget("http://10.0.0.1/".to_string()),
+            AppError::TimeoutError("slow.example did not respond".to_string()),
+            AppError::PayloadTooLarge("big.example is too large".to_string()),
+        ] {
+            let shared = InFlightError::from(&err);
+            let message = err.to_string();
+            let status = axum::response::IntoResponse::into_response(err).status();
+            let rebuilt = AppError::from(shared);
+            assert_eq!(rebuilt.to_string(), message);
+            assert_eq!(axum::response::IntoResponse::into_response(rebuilt).status(), status);
+        }
+        // A message that merely looks like a typed error stays generic.
+        let lookalike = AppError::Other("Timeout error: boom".to_string());
+        assert!(matches!(AppError::from(InFlightError::from(&lookalike)), AppError::Other(_)));
+    }
+
+    fn fetched(data: &str) -> FetchResult {
+        FetchResult {
+            data: data.to_string(),
+            content_type: Some("text/html".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_budget_short_circuits_to_stale_or_503() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let budget = ScrapeBudget::new(2);
+        let upstream_calls = AtomicUsize::new(0);
+        let calls = &upstream_calls;
+        let upstream = move || async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(fetched("fresh"))
+        };
+
+        for _ in 0..2 {
+            let res = within_budget(&budget, "https://a.example", || async { None }, upstream).await;
+            assert_eq!(res.ok().map(|r| r.data), Some("fresh".to_string()));
+        }
+
+        // Budget exhausted: a route with a cached copy still serves it
+        let res = within_budget(
+            &budget,
+            "https://a.example",
+            || async { Some(fetched("stale")) },
+            upstream,
+        )
+        .await;
+        assert_eq!(res.ok().map(|r| r.data), Some("stale".to_string()));
+
+        // ...and one without a cached copy gets 503
+        let res = within_budget(&budget, "https://b.example", || async { None }, upstream).await;
+        let err = res.err().expect("expected budget error");
+        assert!(matches!(err, AppError::ScrapeBudgetExhausted(_)));
+        assert_eq!(
+            axum::response::IntoResponse::into_response(err).status(),
+            axum::http::StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        assert_eq!(upstream_calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_budget_error_maps_to_503_in_handlers() {
+        // The error survives the boxing the scrape helpers put it through.
+        let boxed: Box<dyn std::error::Error + Send + Sync> =
+            Box::new(AppError::ScrapeBudgetExhausted("https://b.example".to_string()));
+        let (status, _) = crate::helpers::upstream_err(&AppError::from(boxed));
+        assert_eq!(status, axum::http::StatusCode::SERVICE_UNAVAILABLE);
+
+        // Only the variant counts, not a message that happens to mention it.
+        let parse = AppError::Other("Scrape budget exhausted for nothing".to_string());
+        let (status, _) = crate::helpers::upstream_err(&parse);
+        assert_eq!(status, axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+
+        // ...and the hand-off from a coalescing leader to its followers.
+        let shared = InFlightError::from(&AppError::ScrapeBudgetExhausted("https://b.example".to_string()));
+        assert!(matches!(AppError::from(shared), AppError::ScrapeBudgetExhausted(_)));
+    }
+
+    #[test]
+    fn test_disabled_budget_never_exhausts() {
+        let budget = ScrapeBudget::new(0);
+        assert!(!budget.is_enabled());
+        assert!((0..10_000).all(|_| budget.try_acquire()));
+    }
+
+    #[test]
+    fn test_domain_allowlist() {
+        let allowed = vec!["otakudesu.cloud".to_string(), "*.komikindo.ch".to_string()];
+        assert!(is_domain_allowed("otakudesu.cloud", &allowed));
+        assert!(is_domain_allowed("cdn.otakudesu.cloud", &allowed));
+        assert!(is_domain_allowed("img.komikindo.ch", &allowed));
+        assert!(!is_domain_allowed("evil-otakudesu.cloud", &allowed));
+        assert!(!is_domain_allowed("example.com", &allowed));
+        assert!(is_domain_allowed("anything.example", &[]));
+    }
+}
@@ -0,0 +1,245 @@
+//! Time and size limits for the proxy endpoints.
+//!
+//! The proxies fetch whatever URL a client hands them, so a slow or huge
+//! upstream would otherwise tie up a worker and its memory indefinitely.
+//! Every proxied fetch is bounded by [`ProxyLimits::timeout`] (exceeding it is
+//! a 504) and every body by [`ProxyLimits::max_body_bytes`]: buffered bodies
+//! are refused with a 413, streamed ones are cut off once the budget is spent.
+
+use bytes::{Bytes, BytesMut};
+use futures::{future, stream, Stream, StreamExt};
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::core::config::CONFIG;
+use crate::core::error::AppError;
+
+/// Limits applied to one proxied fetch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProxyLimits {
+    pub timeout: Duration,
+    pub max_body_bytes: u64,
+}
+
+impl ProxyLimits {
+    /// Limits from `APP__PROXY_TIMEOUT_SECONDS` and `APP__PROXY_MAX_BODY_BYTES`.
+    pub fn from_config() -> Self {
+        Self {
+            timeout: Duration::from_secs(CONFIG.proxy_timeout_seconds),
+            max_body_bytes: CONFIG.proxy_max_body_bytes,
+        }
+    }
+
+    /// Runs `fetch`, failing with [`AppError::TimeoutError`] if it takes
+    /// longer than the timeout.
+    pub async fn within_timeout<T, F>(&self, url: &str, fetch: F) -> Result<T, AppError>
+    where
+        F: Future<Output = Result<T, AppError>>,
+    {
+        tokio::time::timeout(self.timeout, fetch).await.map_err(|_| {
+            AppError::TimeoutError(format!("{} did not respond within {:?}", url, self.timeout))
+        })?
+    }
+
+    /// Refuses a body of `len` bytes (when known) over the limit.
+    pub fn check_len(&self, url: &str, len: Option<u64>) -> Result<(), AppError> {
+        match len {
+            Some(len) if len > self.max_body_bytes => Err(self.too_large(url)),
+            _ => Ok(()),
+        }
+    }
+
+    fn too_large(&self, url: &str) -> AppError {
+        AppError::PayloadTooLarge(format!(
+            "{} is larger than the {} byte proxy limit",
+            url, self.max_body_bytes
+        ))
+    }
+
+    /// Reads `response`'s body, refusing it as soon as it passes the limit,
+    /// whether or not the upstream announced a `Content-Length`.
+    pub async fn read_body(&self, url: &str, mut response: reqwest::Response) -> Result<Bytes, AppError> {
+        self.check_len(url, response.content_length())?;
+        let mut body = BytesMut::new();
+        while let Some(chunk) = response.chunk().await? {
+            if (body.len() + chunk.len()) as u64 > self.max_body_bytes {
+                return Err(self.too_large(url));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body.freeze())
+    }
+
+    /// Passes `stream` through until it has yielded more than the limit,
+    /// then ends it with an error so the response is aborted.
+    pub fn limit_stream<S, E>(&self, stream: S) -> impl Stream<Item = Result<Bytes, std::io::Error>> + Send
+    where
+        S: Stream<Item = Result<Bytes, E>> + Send,
+        E: std::fmt::Display,
+    {
+        let max = self.max_body_bytes;
+        stream.scan((0u64, false), move |(seen, done), chunk| {
+            if *done {
+                return future::ready(None);
+            }
+            let item = match chunk {
+                Ok(bytes) => {
+                    *seen += bytes.len() as u64;
+                    if *seen > max {
+                        *done = true;
+                        Err(std::io::Error::other(format!(
+                            "upstream body exceeded the {} byte proxy limit",
+                            max
+                        )))
+                    } else {
+                        Ok(bytes)
+                    }
+                }
+                Err(e) => {
+                    *done = true;
+                    Err(std::io::Error::other(e.to_string()))
+                }
+            };
+            future::ready(Some(item))
+        })
+    }
+
+    /// [`limit_stream`](Self::limit_stream) that also ends with an error once
+    /// `deadline` passes, for bodies expected to arrive within the timeout
+    /// (images, unlike video).
+    pub fn limit_stream_until<S, E>(
+        &self,
+        deadline: Instant,
+        stream: S,
+    ) -> impl Stream<Item = Result<Bytes, std::io::Error>> + Send
+    where
+        S: Stream<Item = Result<Bytes, E>> + Send,
+        E: std::fmt::Display,
+    {
+        let timeout = self.timeout;
+        let limited = Box::pin(self.limit_stream(stream));
+        stream::unfold(Some(limited), move |limited| async move {
+            let mut limited = limited?;
+            match tokio::time::timeout_at(deadline, limited.next()).await {
+                Ok(Some(item)) => Some((item, Some(limited))),
+                Ok(None) => None,
+                Err(_) => Some((
+                    Err(std::io::Error::new(
+                        std::io::ErrorKind::TimedOut,
+                        format!("upstream body did not arrive within {:?}", timeout),
+                    )),
+                    None,
+                )),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, response::IntoResponse, routing::get, Router};
+    use http::StatusCode;
+
+    const SLOW: Duration = Duration::from_millis(500);
+
+    fn limits(max_body_bytes: u64) -> ProxyLimits {
+        ProxyLimits { timeout: Duration::from_millis(100), max_body_bytes }
+    }
+
+    /// `/slow` answers after [`SLOW`], `/big` sends 4 KiB with a
+    /// `Content-Length`, `/chunked` streams 4 KiB without one.
+    async fn serve_upstream() -> String {
+        let app = Router::new()
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(SLOW).await;
+                    "late"
+                }),
+            )
+            .route("/big", get(|| async { vec![b'x'; 4096] }))
+            .route(
+                "/chunked",
+                get(|| async {
+                    let chunks = (0..4).map(|_| Ok::<_, std::io::Error>(Bytes::from(vec![b'x'; 1024])));
+                    Body::from_stream(futures::stream::iter(chunks))
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    async fn get_upstream(url: &str) -> Result<reqwest::Response, AppError> {
+        Ok(reqwest::get(url).await?)
+    }
+
+    #[tokio::test]
+    async fn slow_upstreams_time_out_with_504() {
+        let base = serve_upstream().await;
+        let url = format!("{}/slow", base);
+
+        let err = limits(1 << 20).within_timeout(&url, get_upstream(&url)).await.unwrap_err();
+        assert!(matches!(err, AppError::TimeoutError(_)), "{:?}", err);
+        assert_eq!(err.into_response().status(), StatusCode::GATEWAY_TIMEOUT);
+
+        let generous = ProxyLimits { timeout: SLOW * 4, max_body_bytes: 1 << 20 };
+        let response = generous.within_timeout(&url, get_upstream(&url)).await.unwrap();
+        assert_eq!(generous.read_body(&url, response).await.unwrap(), "late");
+    }
+
+    #[tokio::test]
+    async fn oversized_bodies_are_rejected_with_413() {
+        let base = serve_upstream().await;
+        let limits = limits(1024);
+
+        for path in ["/big", "/chunked"] {
+            let url = format!("{}{}", base, path);
+            let response = get_upstream(&url).await.unwrap();
+            let err = limits.read_body(&url, response).await.unwrap_err();
+            assert!(matches!(err, AppError::PayloadTooLarge(_)), "{}: {:?}", path, err);
+            assert_eq!(err.into_response().status(), StatusCode::PAYLOAD_TOO_LARGE);
+        }
+
+        // Exactly at the limit is fine.
+        let url = format!("{}/big", base);
+        let response = get_upstream(&url).await.unwrap();
+        let body = ProxyLimits { max_body_bytes: 4096, ..limits }.read_body(&url, response).await.unwrap();
+        assert_eq!(body.len(), 4096);
+    }
+
+    #[tokio::test]
+    async fn streams_are_cut_off_past_the_budget() {
+        let base = serve_upstream().await;
+        let response = get_upstream(&format!("{}/chunked", base)).await.unwrap();
+        assert_eq!(response.content_length(), None);
+
+        let mut stream = Box::pin(limits(2048).limit_stream(response.bytes_stream()));
+        let mut passed = 0;
+        let mut error = None;
+        while let Some(chunk) = stream.next().await {
+            match chunk {
+                Ok(bytes) => passed += bytes.len(),
+                Err(e) => error = Some(e),
+            }
+        }
+        assert!(passed <= 2048, "{} bytes passed", passed);
+        assert!(error.unwrap().to_string().contains("2048 byte proxy limit"));
+    }
+
+    #[tokio::test]
+    async fn stalled_streams_end_at_the_deadline() {
+        let chunks = stream::iter([Ok::<_, std::io::Error>(Bytes::from_static(b"head"))])
+            .chain(stream::pending());
+        let limits = limits(1 << 20);
+        let mut stream = Box::pin(limits.limit_stream_until(Instant::now() + limits.timeout, chunks));
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), "head");
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+        assert!(stream.next().await.is_none());
+    }
+}
@@ -1,12 +1,15 @@
 //! Redis connection utility with tracing for connection lifecycle and errors.
 //!
-//! Uses the type-safe CONFIG for Redis connection parameters.
+//! Uses the type-safe CONFIG for Redis connection parameters. Also home to
+//! [`DistLock`], a lock for work only one instance should do at a time.
 
 use crate::core::config::CONFIG;
 use crate::core::error::AppError;
+use deadpool_redis::redis::{cmd, Script};
 use deadpool_redis::{Manager, Pool};
 use once_cell::sync::Lazy;
-use tracing::{debug, error, info};
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
 
 /// Create a lazy static Redis connection pool.
 /// Uses REDIS_URL from config, or falls back to constructing from host/port.
@@ -61,3 +64,246 @@ pub async fn get_redis_conn() -> Result<deadpool_redis::Connection, AppError> {
         }
     }
 }
+
+/// Deletes the lock only while it still holds the caller's token.
+pub(crate) const RELEASE_LOCK_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+end
+return 0
+"#;
+
+/// Resets the lock's TTL only while it still holds the caller's token.
+pub(crate) const EXTEND_LOCK_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("PEXPIRE", KEYS[1], ARGV[2])
+end
+return 0
+"#;
+
+/// Single-flight across instances: at most one holder per key at a time.
+///
+/// Locks are `SET key token NX PX ttl`, so a holder that crashes frees the
+/// key once `ttl` runs out. Releasing and extending go through Lua scripts
+/// that compare the token first, so a holder whose lock already expired can
+/// never release or extend the next holder's.
+#[derive(Clone)]
+pub struct DistLock {
+    pool: Pool,
+}
+
+impl DistLock {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+
+    /// Takes `key` for `ttl`, or returns `None` if someone else holds it.
+    pub async fn try_acquire(
+        &self,
+        key: &str,
+        ttl: Duration,
+    ) -> Result<Option<DistLockGuard>, AppError> {
+        let token = uuid::Uuid::new_v4().to_string();
+        let mut conn = self.pool.get().await?;
+        let reply: Option<String> = cmd("SET")
+            .arg(key)
+            .arg(&token)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl_millis(ttl))
+            .query_async(&mut *conn)
+            .await?;
+        Ok(reply.map(|_| DistLockGuard {
+            pool: self.pool.clone(),
+            key: key.to_string(),
+            token,
+            released: false,
+        }))
+    }
+}
+
+/// A held [`DistLock`]. Released when dropped; call [`release`] instead to
+/// wait for the release and see its outcome.
+///
+/// [`release`]: DistLockGuard::release
+#[must_use = "the lock is released as soon as the guard is dropped"]
+pub struct DistLockGuard {
+    pool: Pool,
+    key: String,
+    token: String,
+    released: bool,
+}
+
+impl DistLockGuard {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Resets the lock to expire `ttl` from now. Returns `false` if it
+    /// already expired and may be someone else's.
+    pub async fn extend(&self, ttl: Duration) -> Result<bool, AppError> {
+        let mut conn = self.pool.get().await?;
+        let extended: i64 = Script::new(EXTEND_LOCK_SCRIPT)
+            .key(&self.key)
+            .arg(&self.token)
+            .arg(ttl_millis(ttl))
+            .invoke_async(&mut *conn)
+            .await?;
+        Ok(extended == 1)
+    }
+
+    /// Releases the lock. Returns `false` if it had already expired, in
+    /// which case whoever holds the key now keeps it.
+    pub async fn release(mut self) -> Result<bool, AppError> {
+        self.released = true;
+        release_lock(&self.pool, &self.key, &self.token).await
+    }
+}
+
+impl Drop for DistLockGuard {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!(
+                "Lock {} dropped outside the runtime; it will expire on its own",
+                self.key
+            );
+            return;
+        };
+        let pool = self.pool.clone();
+        let key = std::mem::take(&mut self.key);
+        let token = std::mem::take(&mut self.token);
+        runtime.spawn(async move {
+            if let Err(e) = release_lock(&pool, &key, &token).await {
+                warn!(
+                    "Failed to release lock {} (it will expire on its own): {}",
+                    key, e
+                );
+            }
+        });
+    }
+}
+
+async fn release_lock(pool: &Pool, key: &str, token: &str) -> Result<bool, AppError> {
+    let mut conn = pool.get().await?;
+    let deleted: i64 = Script::new(RELEASE_LOCK_SCRIPT)
+        .key(key)
+        .arg(token)
+        .invoke_async(&mut *conn)
+        .await?;
+    Ok(deleted == 1)
+}
+
+/// `PX` takes whole milliseconds and rejects 0.
+fn ttl_millis(ttl: Duration) -> u64 {
+    u64::try_from(ttl.as_millis()).unwrap_or(u64::MAX).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::redis::{fake_redis, Store};
+    use std::time::Instant;
+
+    fn holder(store: &Store, key: &str) -> Option<String> {
+        let store = store.lock().unwrap();
+        store
+            .get(key)
+            .filter(|(_, expires)| *expires > Instant::now())
+            .map(|(value, _)| value.clone())
+    }
+
+    async fn acquire(lock: &DistLock, key: &str, millis: u64) -> Option<DistLockGuard> {
+        lock.try_acquire(key, Duration::from_millis(millis))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn one_holder_at_a_time() {
+        let (pool, store) = fake_redis().await;
+        let lock = DistLock::new(pool);
+
+        let guard = acquire(&lock, "lock:job", 10_000).await.unwrap();
+        assert_eq!(guard.key(), "lock:job");
+        assert!(acquire(&lock, "lock:job", 10_000).await.is_none());
+        assert!(acquire(&lock, "lock:other", 10_000).await.is_some());
+
+        assert!(guard.release().await.unwrap());
+        assert_eq!(holder(&store, "lock:job"), None);
+        assert!(acquire(&lock, "lock:job", 10_000).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn dropping_the_guard_releases() {
+        let (pool, store) = fake_redis().await;
+        let lock = DistLock::new(pool);
+
+        drop(acquire(&lock, "lock:job", 10_000).await.unwrap());
+        for _ in 0..50 {
+            if holder(&store, "lock:job").is_none() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("dropped guard did not release its lock");
+    }
+
+    #[tokio::test]
+    async fn expired_holder_cannot_touch_the_next_holders_lock() {
+        let (pool, store) = fake_redis().await;
+        let lock = DistLock::new(pool);
+
+        let stale = acquire(&lock, "lock:job", 50).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        let current = acquire(&lock, "lock:job", 10_000).await.unwrap();
+        let current_token = holder(&store, "lock:job").unwrap();
+
+        assert!(!stale.extend(Duration::from_secs(10)).await.unwrap());
+        assert!(!stale.release().await.unwrap());
+        assert_eq!(holder(&store, "lock:job"), Some(current_token));
+
+        assert!(current.extend(Duration::from_millis(200)).await.unwrap());
+        assert!(current.release().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn extend_keeps_the_lock_past_its_first_ttl() {
+        let (pool, _store) = fake_redis().await;
+        let lock = DistLock::new(pool);
+
+        let guard = acquire(&lock, "lock:job", 60).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(guard.extend(Duration::from_secs(10)).await.unwrap());
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(acquire(&lock, "lock:job", 10_000).await.is_none());
+        assert!(guard.release().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn releasing_twice_leaves_a_new_holder_alone() {
+        let (pool, store) = fake_redis().await;
+        let lock = DistLock::new(pool.clone());
+
+        let first = acquire(&lock, "lock:job", 10_000).await.unwrap();
+        let token = holder(&store, "lock:job").unwrap();
+        assert!(first.release().await.unwrap());
+
+        let second = acquire(&lock, "lock:job", 10_000).await.unwrap();
+        // A second release with the first token, as a retried or late drop
+        // would send, is a no-op.
+        assert!(!release_lock(&pool, "lock:job", &token).await.unwrap());
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(holder(&store, "lock:job").is_some());
+        assert!(second.release().await.unwrap());
+    }
+
+    #[test]
+    fn ttls_round_to_whole_milliseconds() {
+        assert_eq!(ttl_millis(Duration::ZERO), 1);
+        assert_eq!(ttl_millis(Duration::from_micros(1500)), 1);
+        assert_eq!(ttl_millis(Duration::from_secs(3)), 3000);
+    }
+}
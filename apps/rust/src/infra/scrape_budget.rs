@@ -0,0 +1,62 @@
+//! Global scrape budget.
+//!
+//! A single token bucket shared by every scrape source that caps the total
+//! number of outbound upstream requests per minute. When the budget is
+//! exhausted, callers fall back to stale cache or fail with 503
+//! (`AppError::ScrapeBudgetExhausted`) instead of hammering upstreams (and
+//! getting our server IP blocked). The public proxy draws on a bucket of its
+//! own, [`PROXY_BUDGET`].
+
+use governor::{
+    clock::DefaultClock,
+    state::{InMemoryState, NotKeyed},
+    Quota, RateLimiter,
+};
+use once_cell::sync::Lazy;
+use std::num::NonZeroU32;
+
+use crate::core::config::CONFIG;
+
+/// Token bucket for outbound upstream requests.
+pub struct ScrapeBudget {
+    limiter: Option<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>,
+}
+
+impl ScrapeBudget {
+    /// Create a budget allowing `per_minute` upstream requests per minute.
+    /// `0` disables the budget.
+    pub fn new(per_minute: u32) -> Self {
+        Self {
+            limiter: NonZeroU32::new(per_minute)
+                .map(|n| RateLimiter::direct(Quota::per_minute(n))),
+        }
+    }
+
+    /// Take one token. Returns `false` if the budget is exhausted.
+    pub fn try_acquire(&self) -> bool {
+        match &self.limiter {
+            Some(limiter) => limiter.check().is_ok(),
+            None => true,
+        }
+    }
+
+    /// Whether a budget is being enforced.
+    pub fn is_enabled(&self) -> bool {
+        self.limiter.is_some()
+    }
+}
+
+/// Global scrape budget, configured from `CONFIG.scrape_budget_per_minute`.
+pub static SCRAPE_BUDGET: Lazy<ScrapeBudget> =
+    Lazy::new(|| ScrapeBudget::new(CONFIG.scrape_budget_per_minute));
+
+/// Budget for fetches the public proxy makes for its callers, configured
+/// from `CONFIG.proxy_budget_per_minute`. Kept apart from [`SCRAPE_BUDGET`]
+/// so proxy traffic can't starve the scrapers.
+pub static PROXY_BUDGET: Lazy<ScrapeBudget> =
+    Lazy::new(|| ScrapeBudget::new(CONFIG.proxy_budget_per_minute));
+
+/// Take one token from the global scrape budget.
+pub fn try_acquire() -> bool {
+    SCRAPE_BUDGET.try_acquire()
+}
@@ -4,6 +4,8 @@
 //! long-running or deferred tasks outside of the request lifecycle.
 
 pub mod queue;
+pub mod upload_log;
+pub mod webhook;
 pub mod worker;
 
 pub use queue::{Job, JobDispatcher, JobStatus};
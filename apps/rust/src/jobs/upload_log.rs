@@ -0,0 +1,24 @@
+//! Example event subscriber: logs every stored upload.
+
+use async_trait::async_trait;
+use tracing::info;
+
+use crate::events::{EventBus, EventHandler, FileUploaded};
+
+/// Logs [`FileUploaded`] events.
+pub struct LogUploads;
+
+#[async_trait]
+impl EventHandler<FileUploaded> for LogUploads {
+    async fn handle(&self, event: FileUploaded) {
+        info!(
+            "📤 Upload stored: {} ({} bytes, {}) by {}",
+            event.url, event.size, event.mime, event.user_id
+        );
+    }
+}
+
+/// Registers [`LogUploads`] on `bus`.
+pub async fn register(bus: &EventBus) {
+    bus.on::<FileUploaded, _>(LogUploads).await;
+}
@@ -0,0 +1,358 @@
+//! Webhook dispatcher: forwards domain events to external HTTP endpoints.
+//!
+//! Endpoints come from `CONFIG.webhook_endpoints`, one entry per endpoint:
+//! `https://hooks.example.com/a` receives every forwarded event, while
+//! `https://hooks.example.com/b|file.uploaded+chat.message_saved` only
+//! receives the listed ones. Each delivery is a JSON `POST`:
+//!
+//! ```json
+//! { "event": "file.uploaded", "timestamp": "2024-01-01T00:00:00Z", "data": { ... } }
+//! ```
+//!
+//! When `CONFIG.webhook_secret` is set the body is signed with HMAC-SHA256 and
+//! sent as `X-Webhook-Signature: sha256=<hex>`. Network errors, `429` and `5xx`
+//! responses are retried with jittered backoff up to
+//! `CONFIG.webhook_max_retries` times; other `4xx` responses are not retried.
+//! Deliveries that still fail are logged and counted in
+//! `webhook_deliveries_total{outcome="error"}`.
+
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use reqwest::{Client, StatusCode};
+use serde::Serialize;
+use sha2::Sha256;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::core::config::CONFIG;
+use crate::events::bus::UserRegistered;
+use crate::events::{ChatMessageSaved, Event, EventBus, EventHandler, FileUploaded};
+use crate::helpers::io::retry::{jittered_backoff, permanent, retry, transient};
+use crate::infra::http_client::http_client_fast;
+use crate::observability::metrics::record_webhook_delivery;
+
+/// Header carrying the HMAC-SHA256 signature of the body.
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+/// Header carrying the event name.
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+
+/// One configured webhook endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookEndpoint {
+    pub url: String,
+    /// Event names delivered to this endpoint; empty means all of them.
+    pub events: Vec<String>,
+}
+
+impl WebhookEndpoint {
+    /// Whether `event` should be delivered to this endpoint.
+    pub fn wants(&self, event: &str) -> bool {
+        self.events.is_empty() || self.events.iter().any(|e| e == event)
+    }
+}
+
+/// Parses `url` / `url|event+event` entries into endpoints.
+pub fn parse_webhook_endpoints(entries: &[String]) -> Vec<WebhookEndpoint> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let (url, events) = match entry.trim().split_once('|') {
+                Some((url, events)) => (url, events),
+                None => (entry.trim(), ""),
+            };
+            let url = url.trim();
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                if !url.is_empty() {
+                    warn!("Ignoring webhook endpoint without http(s) scheme: {}", url);
+                }
+                return None;
+            }
+            let events = events
+                .split('+')
+                .map(str::trim)
+                .filter(|e| !e.is_empty())
+                .map(str::to_string)
+                .collect();
+            Some(WebhookEndpoint { url: url.to_string(), events })
+        })
+        .collect()
+}
+
+/// Hex HMAC-SHA256 of `body`, as sent in [`SIGNATURE_HEADER`] after `sha256=`.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = match Hmac::<Sha256>::new_from_slice(secret.as_bytes()) {
+        Ok(mac) => mac,
+        // HMAC accepts keys of any length.
+        Err(_) => return String::new(),
+    };
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[derive(Serialize)]
+struct Payload<'a, E: Serialize> {
+    event: &'a str,
+    timestamp: String,
+    data: &'a E,
+}
+
+/// Delivers events to the configured endpoints.
+pub struct WebhookDispatcher {
+    client: Client,
+    endpoints: Vec<WebhookEndpoint>,
+    secret: Option<String>,
+    max_retries: u32,
+    retry_base: Duration,
+}
+
+impl WebhookDispatcher {
+    pub fn new(endpoints: Vec<WebhookEndpoint>, secret: Option<String>, max_retries: u32) -> Self {
+        Self {
+            client: http_client_fast().client().clone(),
+            endpoints,
+            secret: secret.filter(|s| !s.is_empty()),
+            max_retries,
+            retry_base: Duration::from_millis(500),
+        }
+    }
+
+    /// Dispatcher for the endpoints in `CONFIG`.
+    pub fn from_config() -> Self {
+        Self::new(
+            parse_webhook_endpoints(&CONFIG.webhook_endpoints),
+            CONFIG.webhook_secret.clone(),
+            CONFIG.webhook_max_retries,
+        )
+    }
+
+    /// Override the first retry delay (mainly for tests).
+    pub fn with_retry_base(mut self, base: Duration) -> Self {
+        self.retry_base = base;
+        self
+    }
+
+    pub fn endpoints(&self) -> &[WebhookEndpoint] {
+        &self.endpoints
+    }
+
+    /// Sends `event` to every endpoint that wants it, concurrently.
+    /// Returns how many deliveries succeeded.
+    pub async fn dispatch<E: Event + Serialize>(&self, event: &E) -> usize {
+        let targets: Vec<_> = self.endpoints.iter().filter(|e| e.wants(E::NAME)).collect();
+        if targets.is_empty() {
+            return 0;
+        }
+
+        let payload = Payload {
+            event: E::NAME,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            data: event,
+        };
+        let body = match serde_json::to_vec(&payload) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("Failed to serialize {} webhook payload: {}", E::NAME, e);
+                return 0;
+            }
+        };
+
+        let results = futures::future::join_all(
+            targets.into_iter().map(|endpoint| self.deliver(endpoint, E::NAME, &body)),
+        )
+        .await;
+        results.into_iter().filter(|ok| *ok).count()
+    }
+
+    async fn deliver(&self, endpoint: &WebhookEndpoint, event: &str, body: &[u8]) -> bool {
+        let attempts = AtomicU32::new(0);
+        let signature = self.secret.as_deref().map(|s| format!("sha256={}", sign(s, body)));
+        let backoff = jittered_backoff(self.retry_base, Duration::from_secs(30), 2.0)
+            .with_max_elapsed(None);
+        let (counter, signature) = (&attempts, signature.as_deref());
+
+        let result = retry(backoff, || async move {
+            let attempt = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            let give_up = attempt > self.max_retries;
+
+            let mut request = self
+                .client
+                .post(&endpoint.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(EVENT_HEADER, event)
+                .body(body.to_vec());
+            if let Some(signature) = signature {
+                request = request.header(SIGNATURE_HEADER, signature);
+            }
+
+            let error = match request.send().await {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) => {
+                    let status = response.status();
+                    let error = format!("HTTP {}", status);
+                    if status.is_client_error() && status != StatusCode::TOO_MANY_REQUESTS {
+                        return Err(permanent(error));
+                    }
+                    error
+                }
+                Err(e) => e.to_string(),
+            };
+
+            debug!("Webhook {} to {} attempt {} failed: {}", event, endpoint.url, attempt, error);
+            Err(if give_up { permanent(error) } else { transient(error) })
+        })
+        .await;
+
+        let attempts = attempts.load(Ordering::SeqCst);
+        match result {
+            Ok(()) => {
+                record_webhook_delivery(event, true);
+                debug!("Delivered webhook {} to {}", event, endpoint.url);
+                true
+            }
+            Err(e) => {
+                record_webhook_delivery(event, false);
+                warn!(
+                    "Webhook {} to {} failed after {} attempt(s): {}",
+                    event, endpoint.url, attempts, e
+                );
+                false
+            }
+        }
+    }
+}
+
+/// Forwards events of one type to the dispatcher without blocking the bus.
+struct Forward(Arc<WebhookDispatcher>);
+
+#[async_trait]
+impl<E: Event + Serialize> EventHandler<E> for Forward {
+    async fn handle(&self, event: E) {
+        let dispatcher = self.0.clone();
+        tokio::spawn(async move {
+            dispatcher.dispatch(&event).await;
+        });
+    }
+}
+
+/// Subscribes the webhook dispatcher to the forwarded events on `bus`.
+/// Does nothing when no endpoints are configured.
+pub async fn register(bus: &EventBus) {
+    let dispatcher = WebhookDispatcher::from_config();
+    if dispatcher.endpoints().is_empty() {
+        return;
+    }
+    info!("🪝 Webhooks enabled for {} endpoint(s)", dispatcher.endpoints().len());
+
+    let dispatcher = Arc::new(dispatcher);
+    bus.on::<FileUploaded, _>(Forward(dispatcher.clone())).await;
+    bus.on::<ChatMessageSaved, _>(Forward(dispatcher.clone())).await;
+    bus.on::<UserRegistered, _>(Forward(dispatcher)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, http::HeaderMap, routing::post, Router};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Received {
+        /// Status codes to answer with, in order; 200 once exhausted.
+        script: Mutex<Vec<u16>>,
+        requests: Mutex<Vec<(HeaderMap, Vec<u8>)>>,
+    }
+
+    async fn hook(State(state): State<Arc<Received>>, headers: HeaderMap, body: axum::body::Bytes) -> StatusCode {
+        state.requests.lock().unwrap().push((headers, body.to_vec()));
+        let mut script = state.script.lock().unwrap();
+        let code = if script.is_empty() { 200 } else { script.remove(0) };
+        StatusCode::from_u16(code).unwrap()
+    }
+
+    async fn serve(script: Vec<u16>) -> (String, Arc<Received>) {
+        let state = Arc::new(Received { script: Mutex::new(script), ..Default::default() });
+        let app = Router::new().route("/hook", post(hook)).with_state(state.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}/hook", addr), state)
+    }
+
+    fn upload() -> FileUploaded {
+        FileUploaded {
+            url: "https://cdn.example.com/a.png".to_string(),
+            path: "a.png".to_string(),
+            size: 3,
+            mime: "image/png".to_string(),
+            user_id: "u1".to_string(),
+        }
+    }
+
+    #[test]
+    fn endpoints_parse_with_event_filters() {
+        let endpoints = parse_webhook_endpoints(&[
+            "https://a.example.com/hook".to_string(),
+            " https://b.example.com/hook?x=1 | file.uploaded + chat.message_saved ".to_string(),
+            "not-a-url".to_string(),
+        ]);
+
+        assert_eq!(endpoints.len(), 2);
+        assert!(endpoints[0].wants("user.registered"));
+        assert_eq!(endpoints[1].url, "https://b.example.com/hook?x=1");
+        assert!(endpoints[1].wants("chat.message_saved"));
+        assert!(!endpoints[1].wants("user.registered"));
+    }
+
+    #[tokio::test]
+    async fn delivery_is_signed_and_retried() {
+        let (url, received) = serve(vec![500, 503]).await;
+        let dispatcher = WebhookDispatcher::new(
+            parse_webhook_endpoints(&[url]),
+            Some("s3cret".to_string()),
+            3,
+        )
+        .with_retry_base(Duration::from_millis(1));
+
+        assert_eq!(dispatcher.dispatch(&upload()).await, 1);
+
+        let requests = received.requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        let (headers, body) = &requests[2];
+        assert_eq!(headers[EVENT_HEADER], "file.uploaded");
+        assert_eq!(
+            headers[SIGNATURE_HEADER].to_str().unwrap(),
+            format!("sha256={}", sign("s3cret", body))
+        );
+        let json: serde_json::Value = serde_json::from_slice(body).unwrap();
+        assert_eq!(json["event"], "file.uploaded");
+        assert_eq!(json["data"]["path"], "a.png");
+    }
+
+    #[tokio::test]
+    async fn delivery_gives_up_after_max_retries() {
+        let (url, received) = serve(vec![500; 10]).await;
+        let dispatcher = WebhookDispatcher::new(parse_webhook_endpoints(&[url]), None, 2)
+            .with_retry_base(Duration::from_millis(1));
+
+        assert_eq!(dispatcher.dispatch(&upload()).await, 0);
+        let requests = received.requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].0.get(SIGNATURE_HEADER).is_none());
+    }
+
+    #[tokio::test]
+    async fn client_errors_are_not_retried_and_filters_apply() {
+        let (url, received) = serve(vec![400]).await;
+        let dispatcher = WebhookDispatcher::new(
+            parse_webhook_endpoints(&[url.clone(), format!("{}|chat.message_saved", url)]),
+            None,
+            5,
+        )
+        .with_retry_base(Duration::from_millis(1));
+
+        assert_eq!(dispatcher.dispatch(&upload()).await, 0);
+        assert_eq!(received.requests.lock().unwrap().len(), 1);
+    }
+}
@@ -4,6 +4,7 @@
 //! the job queue for work.
 
 use super::queue::{JobMeta, JobStatus};
+use crate::observability::metrics::record_job;
 use deadpool_redis::Pool;
 use redis::AsyncCommands;
 use std::time::Duration;
@@ -147,7 +148,10 @@ impl Worker {
         };
 
         // Execute job
-        match handler.process(&payload).await {
+        let start = std::time::Instant::now();
+        let result = handler.process(&payload).await;
+        record_job(&meta.job_type, result.is_ok(), start.elapsed().as_secs_f64());
+        match result {
             Ok(()) => {
                 meta.status = JobStatus::Completed;
                 meta.completed_at = Some(chrono::Utc::now());
@@ -9,7 +9,7 @@ pub mod core; // config, error, jwt, ratelimit
 // ============================================================================
 // Infrastructure
 // ============================================================================
-pub mod infra; // redis, http_client, proxy, image_proxy
+pub mod infra; // redis, http_client, proxy
 
 // ============================================================================
 // Features
@@ -3,6 +3,22 @@ use rustexpress::bootstrap::Application;
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> anyhow::Result<()> {
+    // `--migrate` applies pending migrations and exits without serving.
+    if std::env::args().any(|arg| arg == "--migrate") {
+        return Application::migrate_only().await;
+    }
+
+    // `--smoke-test <base_url>` probes a running instance and exits non-zero on failure.
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(pos) = args.iter().position(|arg| arg == "--smoke-test") {
+        let base_url = args
+            .get(pos + 1)
+            .ok_or_else(|| anyhow::anyhow!("usage: --smoke-test <base_url>"))?;
+        let report = rustexpress::health::run_smoke_test(base_url).await;
+        println!("{}", report.summary());
+        std::process::exit(if report.passed() { 0 } else { 1 });
+    }
+
     // Application setup and server logic is now encapsulated in `startup.rs`
     // This makes the main function clean and the app easier to integration test.
     let app = Application::build().await?;
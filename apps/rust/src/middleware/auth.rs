@@ -1,18 +1,26 @@
 // JWT Authentication middleware for Axum
 
 use crate::routes::AppState;
-use crate::core::jwt::{decode_jwt, Claims};
+use crate::core::error::AppError;
+use crate::core::jwt::{decode_jwt, Claims, JwtKeyStore, JWT_KEYS};
+use crate::infra::redis::REDIS_POOL;
 use axum::{
     extract::{FromRequestParts, Request, State},
-    http::{request::Parts, HeaderMap, StatusCode},
+    http::{header, request::Parts, HeaderMap, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
     Json,
 };
+use axum_extra::extract::cookie::CookieJar;
+use deadpool_redis::Pool;
+use jsonwebtoken::errors::ErrorKind;
 use redis::AsyncCommands;
 use serde_json::json;
 use std::sync::Arc;
 
+/// Cookie carrying the JWT for browser clients that can't set headers (e.g. WebSockets).
+pub const TOKEN_COOKIE: &str = "token";
+
 pub struct AuthMiddleware(pub Claims);
 
 impl<S> FromRequestParts<S> for AuthMiddleware
@@ -40,9 +48,11 @@ where
     }
 }
 
+#[derive(Debug)]
 pub enum AuthError {
     MissingToken,
     InvalidToken,
+    TokenExpired,
     TokenRevoked,
     AccountInactive,
     UserNotFound,
@@ -54,6 +64,7 @@ impl IntoResponse for AuthError {
         let (status, message) = match self {
             AuthError::MissingToken => (StatusCode::UNAUTHORIZED, "Missing authorization token"),
             AuthError::InvalidToken => (StatusCode::UNAUTHORIZED, "Invalid token"),
+            AuthError::TokenExpired => (StatusCode::UNAUTHORIZED, "Token expired"),
             AuthError::TokenRevoked => (StatusCode::UNAUTHORIZED, "Token has been revoked"),
             AuthError::AccountInactive => (StatusCode::FORBIDDEN, "Account is inactive"),
             AuthError::UserNotFound => (StatusCode::UNAUTHORIZED, "User not found"),
@@ -79,6 +90,116 @@ fn extract_token(headers: &HeaderMap) -> Result<String, AuthError> {
     Ok(auth_header[7..].to_string())
 }
 
+/// The authenticated caller, inserted into request extensions by [`require_auth`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrentUser {
+    pub user_id: String,
+    pub email: String,
+    pub name: String,
+}
+
+impl From<Claims> for CurrentUser {
+    fn from(claims: Claims) -> Self {
+        Self {
+            user_id: claims.user_id,
+            email: claims.email,
+            name: claims.name,
+        }
+    }
+}
+
+impl<S> FromRequestParts<S> for CurrentUser
+where
+    S: Send + Sync,
+{
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<CurrentUser>()
+            .cloned()
+            .ok_or(AuthError::MissingToken)
+    }
+}
+
+/// Token from `Authorization: Bearer`, falling back to the [`TOKEN_COOKIE`] cookie.
+fn bearer_or_cookie(headers: &HeaderMap) -> Option<String> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+
+    bearer.or_else(|| {
+        CookieJar::from_headers(headers)
+            .get(TOKEN_COOKIE)
+            .map(|c| c.value().to_string())
+            .filter(|t| !t.is_empty())
+    })
+}
+
+/// Validates the request's token against `keys`.
+pub fn authenticate_with(keys: &JwtKeyStore, headers: &HeaderMap) -> Result<CurrentUser, AuthError> {
+    let token = bearer_or_cookie(headers).ok_or(AuthError::MissingToken)?;
+    match keys.decode(&token) {
+        Ok(claims) => Ok(claims.into()),
+        Err(AppError::JwtError(e)) if matches!(e.kind(), ErrorKind::ExpiredSignature) => {
+            Err(AuthError::TokenExpired)
+        }
+        Err(_) => Err(AuthError::InvalidToken),
+    }
+}
+
+/// Validates the request's token against the process-wide keys.
+pub fn authenticate(headers: &HeaderMap) -> Result<CurrentUser, AuthError> {
+    authenticate_with(&JWT_KEYS, headers)
+}
+
+/// Redis key marking `token` as revoked, written by logout and account deletion.
+fn blacklist_key(token: &str) -> String {
+    format!("blacklist:token:{}", token)
+}
+
+/// Whether `token` was revoked. A Redis outage counts as not revoked, so
+/// protected routes keep working without Redis; it is logged.
+async fn is_revoked(pool: &Pool, token: &str) -> bool {
+    let mut conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            tracing::warn!("Skipping token blacklist check, Redis unavailable: {}", e);
+            return false;
+        }
+    };
+    conn.exists(blacklist_key(token)).await.unwrap_or_else(|e| {
+        tracing::warn!("Skipping token blacklist check: {}", e);
+        false
+    })
+}
+
+/// JWT check for protected routes.
+///
+/// Accepts a Bearer token or the `token` cookie, injects [`CurrentUser`] into
+/// request extensions and rejects with 401 otherwise, including for tokens
+/// blacklisted in Redis. Unlike [`auth_layer`] this does not touch the
+/// database.
+pub async fn require_auth(req: Request, next: Next) -> Result<Response, AuthError> {
+    require_auth_with(&REDIS_POOL, req, next).await
+}
+
+/// [`require_auth`] checking the blacklist in `pool`.
+pub async fn require_auth_with(pool: &Pool, mut req: Request, next: Next) -> Result<Response, AuthError> {
+    let user = authenticate(req.headers())?;
+    if let Some(token) = bearer_or_cookie(req.headers()) {
+        if is_revoked(pool, &token).await {
+            return Err(AuthError::TokenRevoked);
+        }
+    }
+    req.extensions_mut().insert(user);
+    Ok(next.run(req).await)
+}
+
 /// Advanced authentication middleware with Redis blacklist check
 pub async fn auth_layer(
     State(state): State<Arc<AppState>>,
@@ -102,8 +223,7 @@ pub async fn auth_layer(
             .into_response()
     })?;
 
-    let blacklist_key = format!("blacklist:token:{}", token);
-    let is_blacklisted: bool = redis_conn.exists(&blacklist_key).await.unwrap_or(false);
+    let is_blacklisted: bool = redis_conn.exists(blacklist_key(&token)).await.unwrap_or(false);
 
     if is_blacklisted {
         return Err(AuthError::TokenRevoked.into_response());
@@ -141,8 +261,8 @@ pub async fn optional_auth_layer(
         if let Ok(claims) = decode_jwt(&token) {
             // Check if token is blacklisted
             if let Ok(mut redis_conn) = state.redis_pool.get().await {
-                let blacklist_key = format!("blacklist:token:{}", token);
-                let is_blacklisted: bool = redis_conn.exists(&blacklist_key).await.unwrap_or(false);
+                let is_blacklisted: bool =
+                    redis_conn.exists(blacklist_key(&token)).await.unwrap_or(false);
 
                 if !is_blacklisted {
                     // Check if user still exists using SeaORM
@@ -168,3 +288,128 @@ pub async fn optional_auth_layer(
 pub fn get_claims_from_request(req: &Request) -> Option<&Claims> {
     req.extensions().get::<Claims>()
 }
+
+/// Require that the authenticated user `user_id` has the `admin` role.
+pub async fn require_admin(
+    db: &sea_orm::DatabaseConnection,
+    user_id: &str,
+) -> Result<(), crate::core::error::AppError> {
+    use crate::core::error::AppError;
+    use crate::entities::user;
+    use sea_orm::EntityTrait;
+
+    let user = user::Entity::find_by_id(user_id)
+        .one(db)
+        .await
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?
+        .ok_or(AppError::Unauthorized)?;
+
+    if user.role.eq_ignore_ascii_case("admin") {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn token(store: &JwtKeyStore, exp_offset: i64) -> String {
+        store
+            .encode(&Claims {
+                user_id: "u1".to_string(),
+                email: "u1@example.com".to_string(),
+                name: "User".to_string(),
+                exp: (chrono::Utc::now().timestamp() + exp_offset) as usize,
+            })
+            .unwrap()
+    }
+
+    fn bearer(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", token)).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn valid_token_round_trips() {
+        let store = JwtKeyStore::new("test-secret");
+        let user = authenticate_with(&store, &bearer(&token(&store, 3600))).unwrap();
+        assert_eq!(user.user_id, "u1");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_str(&format!("theme=dark; token={}", token(&store, 3600))).unwrap(),
+        );
+        assert_eq!(authenticate_with(&store, &headers).unwrap().email, "u1@example.com");
+    }
+
+    #[test]
+    fn expired_token_is_rejected() {
+        let store = JwtKeyStore::new("test-secret");
+        // Beyond the default 60s leeway.
+        let result = authenticate_with(&store, &bearer(&token(&store, -600)));
+        assert!(matches!(result, Err(AuthError::TokenExpired)));
+    }
+
+    #[test]
+    fn bad_signature_and_missing_token_are_rejected() {
+        let signer = JwtKeyStore::new("other-secret");
+        let store = JwtKeyStore::new("test-secret");
+        let result = authenticate_with(&store, &bearer(&token(&signer, 3600)));
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+
+        let result = authenticate_with(&store, &HeaderMap::new());
+        assert!(matches!(result, Err(AuthError::MissingToken)));
+        assert_eq!(
+            AuthError::MissingToken.into_response().status(),
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn blacklisted_tokens_are_rejected() {
+        use crate::testing::redis::fake_redis;
+        use axum::{body::Body, routing::get, Router};
+        use std::time::{Duration, Instant};
+        use tower::ServiceExt;
+
+        crate::testing::app::init_test_env();
+        let (pool, store) = fake_redis().await;
+        let app = Router::new()
+            .route("/", get(|user: CurrentUser| async move { user.user_id }))
+            .route_layer(axum::middleware::from_fn(move |req: Request, next: Next| {
+                let pool = pool.clone();
+                async move { require_auth_with(&pool, req, next).await }
+            }));
+        let token = crate::core::jwt::encode_jwt(Claims {
+            user_id: "u1".to_string(),
+            email: "u1@example.com".to_string(),
+            name: "User".to_string(),
+            exp: (chrono::Utc::now().timestamp() + 3600) as usize,
+        })
+        .unwrap();
+        let status = |app: Router| {
+            let request = Request::builder()
+                .uri("/")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap();
+            async move { app.oneshot(request).await.unwrap().status() }
+        };
+
+        assert_eq!(status(app.clone()).await, StatusCode::OK);
+
+        store.lock().unwrap().insert(
+            blacklist_key(&token),
+            ("1".to_string(), Instant::now() + Duration::from_secs(60)),
+        );
+        assert_eq!(status(app).await, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,151 @@
+//! Turns a panicking handler into a 500 instead of a dropped connection.
+//!
+//! Scraper parsers index into markup that can change under them; when one
+//! panics, hyper would otherwise close the connection without a response.
+//! [`catch_panic`] catches the unwind, logs it with the request id and
+//! answers with the shared [`ErrorBody`](super::json_errors::ErrorBody).
+//! The panic message stays in the logs; clients only see a generic error.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use rustexpress::middleware::catch_panic::catch_panic;
+//!
+//! let app = Router::new()
+//!     .route("/api/test", get(handler))
+//!     .layer(axum::middleware::from_fn(catch_panic));
+//! ```
+
+use axum::{
+    extract::{MatchedPath, Request},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+use futures::FutureExt;
+use std::any::Any;
+use std::panic::AssertUnwindSafe;
+
+use super::json_errors::error_response;
+use crate::observability::metrics::record_handler_panic;
+use crate::observability::request_id::current_request_id;
+
+/// Middleware answering a handler panic with a JSON 500.
+pub async fn catch_panic(req: Request, next: Next) -> Response {
+    let method = req.method().clone();
+    let path = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+
+    // Nothing the handler shared is used after it unwinds: the request is
+    // moved in and only the response comes back out.
+    match AssertUnwindSafe(next.run(req)).catch_unwind().await {
+        Ok(response) => response,
+        Err(payload) => {
+            tracing::error!(
+                request_id = current_request_id().as_deref().unwrap_or("-"),
+                "Handler for {} {} panicked: {}",
+                method,
+                path,
+                panic_message(payload.as_ref())
+            );
+            record_handler_panic(&path);
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+        }
+    }
+}
+
+/// The message passed to `panic!`, when it was a string.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("non-string panic payload")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::request_id::{request_id_middleware, REQUEST_ID_HEADER};
+    use axum::{body::Body, routing::get, Router};
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/ok", get(|| async { "fine" }))
+            .route(
+                "/panic",
+                get(|| async {
+                    let missing: Option<&str> = None;
+                    missing.expect("selector matched nothing")
+                }),
+            )
+            .layer(axum::middleware::from_fn(catch_panic))
+            .layer(axum::middleware::from_fn(request_id_middleware))
+    }
+
+    async fn get_path(app: &Router, uri: &str) -> (StatusCode, Option<String>, Value) {
+        let request = Request::builder()
+            .uri(uri)
+            .header(REQUEST_ID_HEADER, "req-panic")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let request_id = response
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .map(|v| v.to_str().unwrap().to_string());
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            request_id,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn a_panicking_handler_gets_a_structured_500() {
+        let (status, request_id, body) = get_path(&app(), "/panic").await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(request_id.as_deref(), Some("req-panic"));
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "status": "Error",
+                "code": 500,
+                "message": "Internal server error",
+                "request_id": "req-panic",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn the_router_keeps_serving_after_a_panic() {
+        let app = app();
+        for (uri, expected) in [
+            ("/ok", StatusCode::OK),
+            ("/panic", StatusCode::INTERNAL_SERVER_ERROR),
+            ("/ok", StatusCode::OK),
+        ] {
+            assert_eq!(get_path(&app, uri).await.0, expected, "{}", uri);
+        }
+    }
+
+    #[test]
+    fn panic_messages_are_read_from_either_string_type() {
+        let owned: Box<dyn Any + Send> = Box::new(format!("index {} out of range", 3));
+        let borrowed: Box<dyn Any + Send> = Box::new("boom");
+        let other: Box<dyn Any + Send> = Box::new(42);
+        assert_eq!(panic_message(owned.as_ref()), "index 3 out of range");
+        assert_eq!(panic_message(borrowed.as_ref()), "boom");
+        assert_eq!(panic_message(other.as_ref()), "non-string panic payload");
+    }
+}
@@ -0,0 +1,110 @@
+//! Response compression.
+//!
+//! JSON and other text responses are gzip/brotli/zstd encoded according to
+//! the client's `Accept-Encoding`. Responses that already carry a
+//! `Content-Encoding` (e.g. an upstream's gzip body passed through by a proxy)
+//! are never compressed again, and neither are media and opaque binary
+//! streams, which don't shrink.
+
+use tower_http::compression::{
+    predicate::{And, DefaultPredicate, NotForContentType, Predicate},
+    CompressionLayer, CompressionLevel,
+};
+
+/// Which responses [`compression_layer`] compresses.
+pub type CompressionPredicate =
+    And<And<And<DefaultPredicate, NotForContentType>, NotForContentType>, NotForContentType>;
+
+/// The application's compression layer.
+///
+/// [`DefaultPredicate`] already skips responses with a `Content-Encoding`,
+/// images, gRPC, SSE and bodies under 32 bytes; video, audio and
+/// `application/octet-stream` are skipped on top of that.
+pub fn compression_layer() -> CompressionLayer<CompressionPredicate> {
+    CompressionLayer::new()
+        .quality(CompressionLevel::Fastest)
+        .compress_when(
+            DefaultPredicate::new()
+                .and(NotForContentType::const_new("video/"))
+                .and(NotForContentType::const_new("audio/"))
+                .and(NotForContentType::const_new("application/octet-stream")),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+        response::IntoResponse,
+        routing::get,
+        Json, Router,
+    };
+    use std::io::Read;
+    use tower::ServiceExt;
+
+    fn large_json() -> serde_json::Value {
+        let items: Vec<_> = (0..2000)
+            .map(|i| serde_json::json!({ "title": format!("Anime {}", i), "slug": format!("anime-{}", i) }))
+            .collect();
+        serde_json::json!({ "status": "Ok", "data": items })
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/list", get(|| async { Json(large_json()) }))
+            .route(
+                "/proxied",
+                get(|| async {
+                    ([(header::CONTENT_ENCODING, "gzip"), (header::CONTENT_TYPE, "text/html")], vec![0x1f; 4096])
+                        .into_response()
+                }),
+            )
+            .route(
+                "/segment",
+                get(|| async { ([(header::CONTENT_TYPE, "video/mp2t")], vec![0x47; 4096]).into_response() }),
+            )
+            .layer(compression_layer())
+    }
+
+    async fn fetch(uri: &str, accept_encoding: &str) -> (http::HeaderMap, Vec<u8>) {
+        let request = Request::get(uri)
+            .header(header::ACCEPT_ENCODING, accept_encoding)
+            .body(Body::empty())
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers().clone();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (headers, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn large_json_is_gzipped_when_accepted() {
+        let (headers, body) = fetch("/list", "gzip").await;
+        assert_eq!(headers[header::CONTENT_ENCODING], "gzip");
+
+        let mut decoded = Vec::new();
+        flate2::read::GzDecoder::new(&body[..]).read_to_end(&mut decoded).unwrap();
+        assert!(body.len() < decoded.len());
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(&decoded).unwrap(), large_json());
+
+        let (headers, _) = fetch("/list", "br;q=1.0, gzip;q=0.5").await;
+        assert_eq!(headers[header::CONTENT_ENCODING], "br");
+        let (headers, body) = fetch("/list", "identity").await;
+        assert!(headers.get(header::CONTENT_ENCODING).is_none());
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(&body).unwrap(), large_json());
+    }
+
+    #[tokio::test]
+    async fn encoded_and_media_bodies_pass_through() {
+        let (headers, body) = fetch("/proxied", "gzip, br").await;
+        assert_eq!(headers[header::CONTENT_ENCODING], "gzip");
+        assert_eq!(body, vec![0x1f; 4096]);
+
+        let (headers, body) = fetch("/segment", "gzip, br").await;
+        assert!(headers.get(header::CONTENT_ENCODING).is_none());
+        assert_eq!(body, vec![0x47; 4096]);
+    }
+}
@@ -0,0 +1,217 @@
+//! Cross-origin policy.
+//!
+//! Browsers may only call the API from the origins listed in
+//! `APP__CORS_ORIGINS`. Those get credentialed requests (the auth cookies),
+//! so the list is reflected origin by origin, never as `*`. With no list,
+//! development stays permissive and every other environment allows no
+//! cross-origin calls at all.
+
+use axum::http::{header, HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowCredentials, AllowOrigin, CorsLayer};
+
+use crate::core::config::AppConfig;
+
+/// Methods cross-origin callers may use.
+const ALLOWED_METHODS: [Method; 7] = [
+    Method::GET,
+    Method::HEAD,
+    Method::POST,
+    Method::PUT,
+    Method::PATCH,
+    Method::DELETE,
+    Method::OPTIONS,
+];
+
+/// Request headers cross-origin callers may send.
+const ALLOWED_HEADERS: [HeaderName; 5] = [
+    header::ACCEPT,
+    header::AUTHORIZATION,
+    header::CONTENT_TYPE,
+    header::IF_NONE_MATCH,
+    HeaderName::from_static("x-request-id"),
+];
+
+/// Response headers cross-origin callers may read.
+const EXPOSED_HEADERS: [HeaderName; 7] = [
+    header::RETRY_AFTER,
+    header::ETAG,
+    HeaderName::from_static("x-request-id"),
+    HeaderName::from_static("x-page"),
+    HeaderName::from_static("x-per-page"),
+    HeaderName::from_static("x-total-count"),
+    HeaderName::from_static("x-total-pages"),
+];
+
+/// `origin` as browsers send it (`scheme://host[:port]`), or `None` if it
+/// isn't an http(s) origin. Paths and trailing slashes are dropped.
+fn normalize_origin(origin: &str) -> Option<HeaderValue> {
+    let url = url::Url::parse(origin.trim()).ok()?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return None;
+    }
+    HeaderValue::from_str(&url.origin().ascii_serialization()).ok()
+}
+
+/// The layer for `origins` in `environment`.
+pub fn cors_layer_for(environment: &str, origins: &[String]) -> CorsLayer {
+    let mut allowed = Vec::new();
+    for origin in origins.iter().filter(|o| !o.trim().is_empty()) {
+        match normalize_origin(origin) {
+            Some(value) if !allowed.contains(&value) => allowed.push(value),
+            Some(_) => {}
+            None => tracing::warn!("Ignoring invalid CORS origin {:?}", origin),
+        }
+    }
+
+    if allowed.is_empty() {
+        if environment == "development" {
+            return CorsLayer::permissive();
+        }
+        tracing::warn!("APP__CORS_ORIGINS is empty; cross-origin requests are refused");
+    }
+
+    // Credentials only for listed origins; refused ones get no CORS headers.
+    let credentialed = allowed.clone();
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(allowed))
+        .allow_credentials(AllowCredentials::predicate(move |origin, _| {
+            credentialed.contains(origin)
+        }))
+        .allow_methods(ALLOWED_METHODS)
+        .allow_headers(ALLOWED_HEADERS)
+        .expose_headers(EXPOSED_HEADERS)
+}
+
+/// The application's CORS layer, from `APP__ENVIRONMENT` and
+/// `APP__CORS_ORIGINS`.
+pub fn cors_layer(config: &AppConfig) -> CorsLayer {
+    cors_layer_for(&config.environment, &config.cors_origins)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{Request, Response, StatusCode},
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    const APP: &str = "https://app.asepharyana.tech";
+
+    async fn send(layer: CorsLayer, request: Request<Body>) -> Response<Body> {
+        Router::new()
+            .route("/api/anime", get(|| async { "ok" }))
+            .layer(layer)
+            .oneshot(request)
+            .await
+            .unwrap()
+    }
+
+    fn from_origin(origin: &str) -> Request<Body> {
+        Request::get("/api/anime")
+            .header(header::ORIGIN, origin)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    fn production(origins: &[&str]) -> CorsLayer {
+        let origins: Vec<String> = origins.iter().map(|o| o.to_string()).collect();
+        cors_layer_for("production", &origins)
+    }
+
+    #[tokio::test]
+    async fn listed_origins_are_reflected_with_credentials() {
+        let response = send(production(&[APP]), from_origin(APP)).await;
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], APP);
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_CREDENTIALS],
+            "true"
+        );
+    }
+
+    #[tokio::test]
+    async fn disallowed_origins_get_no_allow_origin() {
+        let response = send(production(&[APP]), from_origin("https://evil.example")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .is_none());
+        assert!(response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_CREDENTIALS)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn preflights_list_only_allowed_methods_and_headers() {
+        let preflight = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/api/anime")
+            .header(header::ORIGIN, APP)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "DELETE")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "authorization")
+            .body(Body::empty())
+            .unwrap();
+        let response = send(production(&[APP]), preflight).await;
+
+        let headers = response.headers();
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], APP);
+        let methods = headers[header::ACCESS_CONTROL_ALLOW_METHODS]
+            .to_str()
+            .unwrap();
+        assert!(
+            methods.contains("DELETE") && !methods.contains("TRACE"),
+            "{}",
+            methods
+        );
+        let allowed = headers[header::ACCESS_CONTROL_ALLOW_HEADERS]
+            .to_str()
+            .unwrap();
+        assert!(
+            allowed.contains("authorization") && !allowed.contains('*'),
+            "{}",
+            allowed
+        );
+    }
+
+    #[tokio::test]
+    async fn no_origins_is_permissive_only_in_development() {
+        let response = send(cors_layer_for("development", &[]), from_origin(APP)).await;
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+
+        for environment in ["staging", "production"] {
+            let response = send(cors_layer_for(environment, &[]), from_origin(APP)).await;
+            assert!(
+                response
+                    .headers()
+                    .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                    .is_none(),
+                "{}",
+                environment
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn origins_are_normalized_and_junk_is_dropped() {
+        let layer = production(&[
+            "https://app.asepharyana.tech/",
+            "*",
+            "ftp://files.example",
+            "",
+        ]);
+        let response = send(layer, from_origin(APP)).await;
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], APP);
+
+        assert_eq!(
+            normalize_origin("http://localhost:3000/login").unwrap(),
+            "http://localhost:3000"
+        );
+        assert!(normalize_origin("*").is_none());
+        assert!(normalize_origin("app.asepharyana.tech").is_none());
+    }
+}
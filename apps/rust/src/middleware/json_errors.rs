@@ -0,0 +1,215 @@
+//! One JSON shape for every error response.
+//!
+//! Handlers fail in different ways: bare status codes, `AppError`'s
+//! `ApiResponse`, `{ message, error }` objects, plain-text tuples, and axum's
+//! own empty 404/405. [`json_error_bodies`] rewrites any response with a 4xx
+//! or 5xx status into
+//!
+//! ```json
+//! { "status": "Error", "code": 404, "message": "No route for GET /api/nope" }
+//! ```
+//!
+//! keeping the status and headers (`Allow`, `Retry-After`, ...) and the most
+//! specific message the original body carried. Success responses pass through
+//! untouched.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use rustexpress::middleware::json_errors::{json_error_bodies, method_not_allowed, not_found};
+//!
+//! let app = Router::new()
+//!     .route("/api/test", get(handler))
+//!     .fallback(not_found)
+//!     .method_not_allowed_fallback(method_not_allowed)
+//!     .layer(axum::middleware::from_fn(json_error_bodies));
+//! ```
+
+use axum::{
+    body::Body,
+    extract::{OriginalUri, Request},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::observability::request_id::current_request_id;
+
+/// Largest error body read back to recover its message; bigger bodies are
+/// replaced with the status's reason phrase.
+const MAX_ERROR_BODY: usize = 64 * 1024;
+
+/// Body of every error response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorBody {
+    /// Always `"Error"`.
+    pub status: &'static str,
+    /// HTTP status code.
+    pub code: u16,
+    pub message: String,
+    /// `x-request-id` of the failed request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl ErrorBody {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status: "Error",
+            code: status.as_u16(),
+            message: message.into(),
+            request_id: current_request_id(),
+        }
+    }
+}
+
+/// An error response with the shared JSON body.
+pub fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(ErrorBody::new(status, message))).into_response()
+}
+
+/// Fallback for paths no route matches.
+pub async fn not_found(method: Method, OriginalUri(uri): OriginalUri) -> Response {
+    error_response(StatusCode::NOT_FOUND, format!("No route for {} {}", method, uri.path()))
+}
+
+/// Fallback for a known path requested with a method it doesn't serve.
+pub async fn method_not_allowed(method: Method, OriginalUri(uri): OriginalUri) -> Response {
+    error_response(
+        StatusCode::METHOD_NOT_ALLOWED,
+        format!("Method {} is not allowed for {}", method, uri.path()),
+    )
+}
+
+/// What an error body had to say: the `message` or `error` field of a JSON
+/// object, a JSON string, or non-empty text. Also returns a `request_id`
+/// found in a JSON body.
+fn original_message(body: &[u8]) -> (Option<String>, Option<String>) {
+    if let Ok(value) = serde_json::from_slice::<serde_json::Value>(body) {
+        if let Some(text) = value.as_str() {
+            return (Some(text.to_string()), None);
+        }
+        let field = |name: &str| {
+            value
+                .get(name)
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        let message = field("message").or_else(|| field("error"));
+        return (message, field("request_id"));
+    }
+    let text = String::from_utf8_lossy(body).trim().to_string();
+    ((!text.is_empty()).then_some(text), None)
+}
+
+/// Middleware giving every 4xx/5xx response the [`ErrorBody`] shape.
+pub async fn json_error_bodies(req: Request, next: Next) -> Response {
+    let response = next.run(req).await;
+    let status = response.status();
+    if !(status.is_client_error() || status.is_server_error()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let (message, request_id) = match axum::body::to_bytes(body, MAX_ERROR_BODY).await {
+        Ok(bytes) => original_message(&bytes),
+        Err(_) => (None, None),
+    };
+
+    let mut error = ErrorBody::new(
+        status,
+        message.unwrap_or_else(|| status.canonical_reason().unwrap_or("Error").to_string()),
+    );
+    error.request_id = error.request_id.or(request_id);
+    let body = serde_json::to_vec(&error).unwrap_or_default();
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts
+        .headers
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::error::AppError;
+    use axum::{routing::get, Router};
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/ok", get(|| async { "fine" }))
+            .route("/bare", get(|| async { StatusCode::BAD_GATEWAY }))
+            .route("/text", get(|| async { (StatusCode::NOT_FOUND, "Anime 'x' not found") }))
+            .route("/app-error", get(|| async { AppError::BadRequest("slug must not be empty".to_string()) }))
+            .route(
+                "/legacy",
+                get(|| async {
+                    (StatusCode::FORBIDDEN, Json(serde_json::json!({ "message": "", "error": "blocked" })))
+                }),
+            )
+            .fallback(not_found)
+            .method_not_allowed_fallback(method_not_allowed)
+            .layer(axum::middleware::from_fn(json_error_bodies))
+    }
+
+    async fn send(method: Method, uri: &str) -> (StatusCode, Value) {
+        let request = axum::http::Request::builder().method(method).uri(uri).body(Body::empty()).unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn unknown_routes_are_json_404s() {
+        let (status, body) = send(Method::GET, "/api/nope?x=1").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(
+            body,
+            serde_json::json!({ "status": "Error", "code": 404, "message": "No route for GET /api/nope" })
+        );
+    }
+
+    #[tokio::test]
+    async fn wrong_methods_are_json_405s() {
+        let (status, body) = send(Method::DELETE, "/ok").await;
+        assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(body["status"], "Error");
+        assert_eq!(body["code"], 405);
+        assert_eq!(body["message"], "Method DELETE is not allowed for /ok");
+    }
+
+    #[tokio::test]
+    async fn handler_errors_keep_their_message() {
+        for (uri, code, message) in [
+            ("/bare", 502, "Bad Gateway"),
+            ("/text", 404, "Anime 'x' not found"),
+            ("/app-error", 400, "Bad request: slug must not be empty"),
+            ("/legacy", 403, "blocked"),
+        ] {
+            let (status, body) = send(Method::GET, uri).await;
+            assert_eq!(status.as_u16(), code, "{}", uri);
+            assert_eq!(body["status"], "Error", "{}", uri);
+            assert_eq!(body["code"], code, "{}", uri);
+            assert_eq!(body["message"], message, "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn success_bodies_are_untouched() {
+        let response = app()
+            .oneshot(axum::http::Request::get("/ok").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&bytes[..], b"fine");
+    }
+}
@@ -17,7 +17,8 @@ use axum::{body::Body, extract::Request, http::StatusCode, middleware::Next, res
 use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Instant;
-use uuid::Uuid;
+
+use crate::observability::request_id::REQUEST_ID_HEADER;
 
 /// Logging configuration.
 #[derive(Debug, Clone)]
@@ -90,26 +91,7 @@ impl LoggingConfig {
 }
 
 /// Request ID extension for correlation.
-#[derive(Clone, Debug)]
-pub struct RequestId(pub String);
-
-impl RequestId {
-    /// Generate a new request ID.
-    pub fn new() -> Self {
-        Self(Uuid::new_v4().to_string())
-    }
-
-    /// Get the request ID as a string.
-    pub fn as_str(&self) -> &str {
-        &self.0
-    }
-}
-
-impl Default for RequestId {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+pub use crate::observability::RequestId;
 
 /// Logging middleware.
 pub async fn logging_middleware(config: Arc<LoggingConfig>, req: Request, next: Next) -> Response {
@@ -120,12 +102,17 @@ pub async fn logging_middleware(config: Arc<LoggingConfig>, req: Request, next:
         return next.run(req).await;
     }
 
-    // Generate or extract request ID
+    // Reuse the id assigned by `request_id_middleware`, else extract or generate one
     let request_id = req
-        .headers()
-        .get("X-Request-ID")
-        .and_then(|h| h.to_str().ok())
-        .map(|s| RequestId(s.to_string()))
+        .extensions()
+        .get::<RequestId>()
+        .cloned()
+        .or_else(|| {
+            req.headers()
+                .get(REQUEST_ID_HEADER)
+                .and_then(|h| h.to_str().ok())
+                .map(|s| RequestId(s.to_string()))
+        })
         .unwrap_or_else(RequestId::new);
 
     let method = req.method().clone();
@@ -30,6 +30,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::sync::Arc;
 
+use crate::core::ratelimit::request_ip;
+
 const MAINTENANCE_KEY: &str = "app:maintenance";
 
 /// Maintenance mode configuration.
@@ -207,15 +209,8 @@ pub fn maintenance_middleware(
             }
 
             // Check allowed IPs
-            if let Some(addr) = req
-                .headers()
-                .get("X-Forwarded-For")
-                .and_then(|h| h.to_str().ok())
-                .and_then(|s| s.split(',').next())
-            {
-                if config.allowed_ips.contains(addr.trim()) {
-                    return next.run(req).await;
-                }
+            if config.allowed_ips.contains(&request_ip(&req)) {
+                return next.run(req).await;
             }
 
             // Return maintenance page
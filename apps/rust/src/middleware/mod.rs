@@ -1,4 +1,8 @@
 pub mod auth;
+pub mod catch_panic;
+pub mod compression;
+pub mod cors;
+pub mod json_errors;
 pub mod logging;
 pub mod maintenance;
 pub mod registry;
@@ -6,3 +6,6 @@ pub mod user;
 
 pub use types::*;
 pub use user::*;
+
+/// DTOs shared with the Leptos client.
+pub use api_models::HomeData;
@@ -36,3 +36,34 @@ pub struct LoginResponse {
     pub token_type: String,
     pub expires_in: i64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn login_response_carries_user_next_to_tokens() {
+        let response = LoginResponse {
+            user: UserResponse {
+                id: "u1".to_string(),
+                email: Some("u1@example.com".to_string()),
+                name: Some("User".to_string()),
+                image: None,
+                email_verified: true,
+                role: "user".to_string(),
+            },
+            access_token: "access".to_string(),
+            refresh_token: "refresh".to_string(),
+            token_type: "Bearer".to_string(),
+            expires_in: 3600,
+        };
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["user"]["id"], "u1");
+        assert_eq!(json["user"]["name"], "User");
+        assert_eq!(json["user"]["email"], "u1@example.com");
+        assert_eq!(json["user"]["role"], "user");
+        assert_eq!(json["access_token"], "access");
+        assert_eq!(json["refresh_token"], "refresh");
+    }
+}
@@ -1,6 +1,31 @@
 //! Prometheus metrics endpoint and utilities.
+//!
+//! Exposed at `GET /metrics` in Prometheus text format:
+//!
+//! | Metric | Type | Labels |
+//! |--------|------|--------|
+//! | `http_requests_total` | counter | `method`, `path` (matched route template), `status` |
+//! | `http_request_duration_seconds` | histogram | `method`, `path`, `status` |
+//! | `upstream_fetch_duration_seconds` | histogram | `source` (upstream host), `outcome` |
+//! | `upstream_fetches_total` | counter | `source`, `outcome` (`ok` / `error`) |
+//! | `cache_requests_total` | counter | `cache` (key prefix), `result` (`hit` / `miss`) |
+//! | `cache_layer_requests_total` | counter | `layer` (`l1` in-process / `l2` Redis), `cache`, `result` |
+//! | `circuit_breaker_state` | gauge | `breaker`; 0 = closed, 1 = half-open, 2 = open |
+//! | `prewarm_runs_total` | counter | `source` (pre-warmed list), `outcome` (`ok` / `error`) |
+//! | `webhook_deliveries_total` | counter | `event`, `outcome` (`ok` / `error`, after retries) |
+//! | `prune_deleted_total` | counter | `target` (`redis`, `image_cache`, `session`, ...) |
+//!
+//! The recording helpers below also feed [`STATS`](super::stats::STATS), the
+//! in-process view behind the admin dashboard.
 
-use axum::{http::StatusCode, response::IntoResponse};
+use axum::{
+    extract::{MatchedPath, Request},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use super::stats::STATS;
+use crate::circuit_breaker::CircuitState;
 use metrics::{counter, gauge, histogram};
 use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
 use once_cell::sync::OnceCell;
@@ -22,6 +47,11 @@ pub fn setup_metrics() -> anyhow::Result<()> {
         ],
     )?;
 
+    let builder = builder.set_buckets_for_metric(
+        Matcher::Full("upstream_fetch_duration_seconds".to_string()),
+        &[0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 20.0, 30.0],
+    )?;
+
     let handle = builder.install_recorder()?;
 
     METRICS_HANDLE
@@ -65,6 +95,123 @@ pub fn record_http_request(method: &str, path: &str, status: u16, duration_secs:
 
     counter!("http_requests_total", &labels).increment(1);
     histogram!("http_request_duration_seconds", &labels).record(duration_secs);
+    STATS.record_request(status, duration_secs);
+}
+
+/// Middleware recording `http_requests_total` / `http_request_duration_seconds`
+/// for every request, labelled by the matched route template (e.g.
+/// `/api/anime/detail/{slug}`) so slugs don't explode label cardinality.
+pub async fn http_metrics_middleware(req: Request, next: Next) -> Response {
+    let path = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let timer = RequestTimer::new(req.method().as_str(), &path);
+
+    let response = next.run(req).await;
+    timer.record(response.status().as_u16());
+    response
+}
+
+/// Label for an upstream URL: its host, or `unknown` if it doesn't parse.
+pub fn source_label(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Record an upstream scrape fetch.
+pub fn record_upstream_fetch(source: &str, success: bool, duration_secs: f64) {
+    let labels = [
+        ("source", source.to_string()),
+        ("outcome", if success { "ok" } else { "error" }.to_string()),
+    ];
+
+    counter!("upstream_fetches_total", &labels).increment(1);
+    histogram!("upstream_fetch_duration_seconds", &labels).record(duration_secs);
+    STATS.record_upstream_fetch(source, success);
+}
+
+/// Record why an upstream fetch of `url` failed, for the dashboard's recent
+/// failures. The failure itself is counted by [`record_upstream_fetch`].
+pub fn record_scrape_failure(source: &str, url: &str, error: &str) {
+    STATS.record_scrape_failure(source, url, error);
+}
+
+/// Record a cache lookup. `cache` is the key prefix (e.g. `fetch`, `anime`).
+pub fn record_cache_lookup(cache: &str, hit: bool) {
+    let labels = [
+        ("cache", cache.to_string()),
+        ("result", if hit { "hit" } else { "miss" }.to_string()),
+    ];
+
+    counter!("cache_requests_total", &labels).increment(1);
+    STATS.record_cache_lookup(cache, hit);
+}
+
+/// Record a lookup in one cache layer: `l1` (in-process) or `l2` (Redis).
+pub fn record_cache_layer_lookup(layer: &'static str, cache: &str, hit: bool) {
+    let labels = [
+        ("layer", layer.to_string()),
+        ("cache", cache.to_string()),
+        ("result", if hit { "hit" } else { "miss" }.to_string()),
+    ];
+
+    counter!("cache_layer_requests_total", &labels).increment(1);
+}
+
+/// Record one cache pre-warm of `source` (e.g. `komik:manhwa`).
+pub fn record_prewarm_run(source: &str, success: bool, duration_secs: f64) {
+    let labels = [
+        ("source", source.to_string()),
+        ("outcome", if success { "ok" } else { "error" }.to_string()),
+    ];
+
+    counter!("prewarm_runs_total", &labels).increment(1);
+    STATS.record_job(&format!("prewarm:{}", source), success, duration_secs);
+}
+
+/// Record `deleted` stale entries removed from `target` (e.g. `redis`,
+/// `image_cache`) by the prune job.
+pub fn record_pruned(target: &str, deleted: u64) {
+    counter!("prune_deleted_total", "target" => target.to_string()).increment(deleted);
+}
+
+/// Record that a critical `selector` matched nothing on a scraped page.
+pub fn record_selector_miss(selector: &str) {
+    counter!("selector_miss_total", "selector" => selector.to_string()).increment(1);
+}
+
+/// Record a handler panic caught while serving `path` (the matched route).
+pub fn record_handler_panic(path: &str) {
+    counter!("http_handler_panics_total", "path" => path.to_string()).increment(1);
+}
+
+/// Record a chat WebSocket closed because its peer stopped responding.
+pub fn record_ws_idle_disconnect() {
+    counter!("ws_idle_disconnects_total").increment(1);
+}
+
+/// Record the final outcome of one webhook delivery of `event`.
+pub fn record_webhook_delivery(event: &str, success: bool) {
+    let labels = [
+        ("event", event.to_string()),
+        ("outcome", if success { "ok" } else { "error" }.to_string()),
+    ];
+
+    counter!("webhook_deliveries_total", &labels).increment(1);
+}
+
+/// Record the current state of a circuit breaker.
+pub fn set_circuit_breaker_state(name: &str, state: CircuitState) {
+    let value = match state {
+        CircuitState::Closed => 0.0,
+        CircuitState::HalfOpen => 1.0,
+        CircuitState::Open => 2.0,
+    };
+    gauge!("circuit_breaker_state", "breaker" => name.to_string()).set(value);
 }
 
 /// Record active connections.
@@ -94,6 +241,7 @@ pub fn record_job(job_type: &str, success: bool, duration_secs: f64) {
 
     counter!("jobs_processed_total", &labels).increment(1);
     histogram!("job_duration_seconds", &labels).record(duration_secs);
+    STATS.record_job(job_type, success, duration_secs);
 }
 
 /// Request timing helper.
@@ -117,3 +265,92 @@ impl RequestTimer {
         record_http_request(&self.method, &self.path, status, duration);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metrics_exporter_prometheus::PrometheusBuilder;
+
+    /// Minimal exposition-format check: every sample line is
+    /// `name{labels} value` with a float value and a declared `# TYPE`.
+    fn parse_exposition(text: &str) -> Vec<(String, f64)> {
+        let mut typed = std::collections::HashSet::new();
+        let mut samples = Vec::new();
+
+        for line in text.lines().filter(|l| !l.trim().is_empty()) {
+            if let Some(rest) = line.strip_prefix("# TYPE ") {
+                let mut parts = rest.split_whitespace();
+                let name = parts.next().unwrap();
+                let kind = parts.next().unwrap();
+                assert!(
+                    ["counter", "gauge", "histogram", "summary"].contains(&kind),
+                    "bad type line: {}",
+                    line
+                );
+                typed.insert(name.to_string());
+                continue;
+            }
+            if line.starts_with('#') {
+                continue;
+            }
+
+            let (series, value) = line.rsplit_once(' ').expect("sample has a value");
+            let value: f64 = value.parse().expect("sample value is a float");
+            let name = series.split('{').next().unwrap();
+            if let Some(labels) = series.strip_prefix(name) {
+                if !labels.is_empty() {
+                    assert!(labels.starts_with('{') && labels.ends_with('}'), "bad labels: {}", line);
+                }
+            }
+            let family = ["_bucket", "_sum", "_count"]
+                .iter()
+                .find_map(|s| name.strip_suffix(s))
+                .filter(|base| typed.contains(*base))
+                .unwrap_or(name);
+            assert!(typed.contains(family), "sample without TYPE: {}", line);
+            samples.push((series.to_string(), value));
+        }
+        samples
+    }
+
+    #[test]
+    fn exposition_format_parses() {
+        let recorder = PrometheusBuilder::new()
+            .set_buckets_for_metric(
+                Matcher::Full("upstream_fetch_duration_seconds".to_string()),
+                &[0.1, 1.0],
+            )
+            .unwrap()
+            .build_recorder();
+        let handle = recorder.handle();
+
+        metrics::with_local_recorder(&recorder, || {
+            record_http_request("GET", "/api/anime/detail/{slug}", 200, 0.02);
+            record_upstream_fetch("otakudesu.best", true, 0.4);
+            record_cache_lookup("fetch", true);
+            record_cache_lookup("fetch", false);
+            set_circuit_breaker_state("otakudesu", CircuitState::Open);
+            record_prewarm_run("komik:manhwa", false, 1.5);
+        });
+
+        let samples = parse_exposition(&handle.render());
+        let find = |needle: &str| {
+            samples
+                .iter()
+                .find(|(s, _)| s.contains(needle))
+                .map(|(_, v)| *v)
+        };
+
+        assert_eq!(find("http_requests_total{"), Some(1.0));
+        assert_eq!(find("upstream_fetch_duration_seconds_count"), Some(1.0));
+        assert_eq!(find("result=\"hit\""), Some(1.0));
+        assert_eq!(find("circuit_breaker_state{"), Some(2.0));
+        assert_eq!(find("prewarm_runs_total{"), Some(1.0));
+    }
+
+    #[test]
+    fn source_label_uses_host() {
+        assert_eq!(source_label("https://otakudesu.best/anime/x"), "otakudesu.best");
+        assert_eq!(source_label("not a url"), "unknown");
+    }
+}
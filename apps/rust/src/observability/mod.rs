@@ -2,6 +2,8 @@
 
 pub mod metrics;
 pub mod request_id;
+pub mod stats;
 
-pub use metrics::{setup_metrics, MetricsHandler};
+pub use metrics::{http_metrics_middleware, setup_metrics, MetricsHandler};
 pub use request_id::{request_id_middleware, RequestId};
+pub use stats::{RuntimeStats, STATS};
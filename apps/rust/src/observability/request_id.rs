@@ -1,11 +1,16 @@
 //! Request ID middleware for request tracing.
 
 use axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response};
+use std::time::Instant;
+use tracing::Instrument;
 use uuid::Uuid;
 
 /// Request ID header name.
 pub const REQUEST_ID_HEADER: &str = "x-request-id";
 
+/// Longer client-supplied ids are replaced rather than echoed into logs.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
 /// Extension to access request ID in handlers.
 #[derive(Clone, Debug)]
 pub struct RequestId(pub String);
@@ -34,13 +39,31 @@ impl std::fmt::Display for RequestId {
     }
 }
 
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: RequestId;
+}
+
+/// Request ID of the request being handled on this task, if any.
+///
+/// Lets code without access to the request (such as `AppError` responses)
+/// report the id the client will see in `x-request-id`.
+pub fn current_request_id() -> Option<String> {
+    CURRENT_REQUEST_ID.try_with(|id| id.0.clone()).ok()
+}
+
 /// Middleware that adds a unique request ID to each request.
 ///
 /// The request ID is:
 /// - Taken from the `x-request-id` header if present
 /// - Generated as a new UUID if not present
 /// - Added to the response headers
-/// - Available via the `RequestId` extension in handlers
+/// - Available via the `RequestId` extension in handlers and
+///   [`current_request_id`] elsewhere
+/// - Recorded on the `request` span, so every log line emitted while handling
+///   the request carries it
+///
+/// Completion is logged with structured `method`, `path`, `status` and
+/// `latency_ms` fields: 5xx at `error`, 4xx at `warn`, the rest at `info`.
 ///
 /// # Example
 ///
@@ -49,7 +72,7 @@ impl std::fmt::Display for RequestId {
 /// use rustexpress::observability::RequestId;
 ///
 /// async fn handler(Extension(req_id): Extension<RequestId>) {
-///     println!("Request ID: {}", req_id);
+///     tracing::info!("handling");  // logged with request_id
 /// }
 /// ```
 pub async fn request_id_middleware(mut req: Request, next: Next) -> Response {
@@ -58,23 +81,35 @@ pub async fn request_id_middleware(mut req: Request, next: Next) -> Response {
         .headers()
         .get(REQUEST_ID_HEADER)
         .and_then(|v| v.to_str().ok())
+        .filter(|s| !s.is_empty() && s.len() <= MAX_REQUEST_ID_LEN)
         .map(|s| RequestId(s.to_string()))
         .unwrap_or_else(RequestId::new);
 
-    // Add to tracing span
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
     let span = tracing::info_span!(
         "request",
         request_id = %request_id,
-        method = %req.method(),
-        uri = %req.uri(),
+        method = %method,
+        path = %path,
     );
-    let _guard = span.enter();
 
     // Insert as extension for handlers
     req.extensions_mut().insert(request_id.clone());
 
-    // Process request
-    let mut response = next.run(req).await;
+    let start = Instant::now();
+    let mut response = CURRENT_REQUEST_ID
+        .scope(request_id.clone(), next.run(req))
+        .instrument(span.clone())
+        .await;
+    let latency_ms = start.elapsed().as_millis() as u64;
+    let status = response.status().as_u16();
+
+    span.in_scope(|| match status {
+        500.. => tracing::error!(%method, %path, status, latency_ms, "request failed"),
+        400..=499 => tracing::warn!(%method, %path, status, latency_ms, "request rejected"),
+        _ => tracing::info!(%method, %path, status, latency_ms, "request completed"),
+    });
 
     // Add request ID to response headers
     if let Ok(value) = HeaderValue::from_str(&request_id.0) {
@@ -83,3 +118,48 @@ pub async fn request_id_middleware(mut req: Request, next: Next) -> Response {
 
     response
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/fail",
+                get(|| async { crate::core::error::AppError::Other("boom".to_string()) }),
+            )
+            .layer(axum::middleware::from_fn(request_id_middleware))
+    }
+
+    async fn body_json(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn propagates_the_client_id_into_headers_and_error_bodies() {
+        let request = Request::builder()
+            .uri("/fail")
+            .header(REQUEST_ID_HEADER, "req-123")
+            .body(Body::empty())
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "req-123");
+        assert_eq!(body_json(response).await["request_id"], "req-123");
+    }
+
+    #[tokio::test]
+    async fn generates_an_id_when_none_is_sent() {
+        let request = Request::builder().uri("/fail").body(Body::empty()).unwrap();
+        let response = app().oneshot(request).await.unwrap();
+
+        let header = response.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string();
+        assert!(Uuid::parse_str(&header).is_ok());
+        assert_eq!(body_json(response).await["request_id"], header.as_str());
+        assert!(current_request_id().is_none());
+    }
+}
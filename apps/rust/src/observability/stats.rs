@@ -0,0 +1,296 @@
+//! In-process figures behind the admin dashboard.
+//!
+//! Prometheus only gets cumulative counters and histograms; the dashboard
+//! wants windowed values (request rate over the last minute, when a source
+//! last answered, how the last job run went) without a Prometheus server to
+//! query. The `record_*` helpers in [`super::metrics`] therefore also feed
+//! [`STATS`], so both views always see the same events.
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+/// Window request rate and latency percentiles are computed over.
+pub const REQUEST_WINDOW: Duration = Duration::from_secs(60);
+
+/// Most requests kept for the window; older ones drop out first.
+const MAX_WINDOW_REQUESTS: usize = 10_000;
+
+/// How many recent scrape failures are kept.
+const MAX_RECENT_FAILURES: usize = 20;
+
+/// Request rate and latency over [`REQUEST_WINDOW`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct RequestSummary {
+    /// Requests seen since startup.
+    pub total: u64,
+    /// 5xx responses since startup.
+    pub server_errors: u64,
+    pub window_seconds: u64,
+    /// Requests in the window.
+    pub window_requests: u64,
+    pub rate_per_second: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Hits and misses of one cache prefix.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, ToSchema)]
+pub struct CacheCounts {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// Cache lookups since startup.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct CacheSummary {
+    pub hits: u64,
+    pub misses: u64,
+    /// `hits / (hits + misses)`; `None` before the first lookup.
+    pub hit_rate: Option<f64>,
+    /// Counts per key prefix (`fetch`, `anime`, ...).
+    pub by_cache: BTreeMap<String, CacheCounts>,
+}
+
+/// Upstream fetch outcomes of one source since startup.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct SourceActivity {
+    pub successes: u64,
+    pub failures: u64,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_failure_at: Option<DateTime<Utc>>,
+}
+
+/// One failed upstream fetch.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ScrapeFailure {
+    pub source: String,
+    pub url: String,
+    pub error: String,
+    pub at: DateTime<Utc>,
+}
+
+/// The most recent run of a scheduled task or background job.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct JobRun {
+    pub last_run_at: DateTime<Utc>,
+    pub last_success: bool,
+    pub last_duration_ms: f64,
+    pub runs: u64,
+    pub failures: u64,
+}
+
+/// Windowed and cumulative counters fed by the metrics helpers.
+#[derive(Default)]
+pub struct RuntimeStats {
+    requests_total: AtomicU64,
+    server_errors: AtomicU64,
+    window: Mutex<VecDeque<(Instant, f64)>>,
+    cache: DashMap<String, CacheCounts>,
+    sources: DashMap<String, SourceActivity>,
+    failures: Mutex<VecDeque<ScrapeFailure>>,
+    jobs: DashMap<String, JobRun>,
+}
+
+/// Value at quantile `q` of ascending `sorted`, or 0 when empty.
+fn percentile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((sorted.len() as f64 * q).ceil() as usize).clamp(1, sorted.len());
+    sorted[rank - 1]
+}
+
+impl RuntimeStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_request(&self, status: u16, duration_secs: f64) {
+        self.requests_total.fetch_add(1, Ordering::Relaxed);
+        if status >= 500 {
+            self.server_errors.fetch_add(1, Ordering::Relaxed);
+        }
+        if let Ok(mut window) = self.window.lock() {
+            if window.len() == MAX_WINDOW_REQUESTS {
+                window.pop_front();
+            }
+            window.push_back((Instant::now(), duration_secs));
+        }
+    }
+
+    pub fn record_cache_lookup(&self, cache: &str, hit: bool) {
+        let mut counts = self.cache.entry(cache.to_string()).or_default();
+        if hit {
+            counts.hits += 1;
+        } else {
+            counts.misses += 1;
+        }
+    }
+
+    pub fn record_upstream_fetch(&self, source: &str, success: bool) {
+        let mut activity = self.sources.entry(source.to_string()).or_default();
+        if success {
+            activity.successes += 1;
+            activity.last_success_at = Some(Utc::now());
+        } else {
+            activity.failures += 1;
+            activity.last_failure_at = Some(Utc::now());
+        }
+    }
+
+    /// Keeps `url`'s failure among the most recent ones.
+    pub fn record_scrape_failure(&self, source: &str, url: &str, error: &str) {
+        if let Ok(mut failures) = self.failures.lock() {
+            if failures.len() == MAX_RECENT_FAILURES {
+                failures.pop_front();
+            }
+            failures.push_back(ScrapeFailure {
+                source: source.to_string(),
+                url: url.to_string(),
+                error: error.to_string(),
+                at: Utc::now(),
+            });
+        }
+    }
+
+    pub fn record_job(&self, name: &str, success: bool, duration_secs: f64) {
+        let now = Utc::now();
+        let mut run = self.jobs.entry(name.to_string()).or_insert_with(|| JobRun {
+            last_run_at: now,
+            last_success: success,
+            last_duration_ms: 0.0,
+            runs: 0,
+            failures: 0,
+        });
+        run.last_run_at = now;
+        run.last_success = success;
+        run.last_duration_ms = duration_secs * 1000.0;
+        run.runs += 1;
+        if !success {
+            run.failures += 1;
+        }
+    }
+
+    pub fn requests(&self) -> RequestSummary {
+        let cutoff = Instant::now().checked_sub(REQUEST_WINDOW);
+        let mut latencies: Vec<f64> = match self.window.lock() {
+            Ok(mut window) => {
+                if let Some(cutoff) = cutoff {
+                    while window.front().is_some_and(|(at, _)| *at < cutoff) {
+                        window.pop_front();
+                    }
+                }
+                window.iter().map(|(_, secs)| secs * 1000.0).collect()
+            }
+            Err(_) => Vec::new(),
+        };
+        latencies.sort_by(|a, b| a.total_cmp(b));
+
+        RequestSummary {
+            total: self.requests_total.load(Ordering::Relaxed),
+            server_errors: self.server_errors.load(Ordering::Relaxed),
+            window_seconds: REQUEST_WINDOW.as_secs(),
+            window_requests: latencies.len() as u64,
+            rate_per_second: latencies.len() as f64 / REQUEST_WINDOW.as_secs_f64(),
+            p50_ms: percentile(&latencies, 0.5),
+            p95_ms: percentile(&latencies, 0.95),
+            max_ms: latencies.last().copied().unwrap_or(0.0),
+        }
+    }
+
+    pub fn cache(&self) -> CacheSummary {
+        let by_cache: BTreeMap<String, CacheCounts> =
+            self.cache.iter().map(|e| (e.key().clone(), *e.value())).collect();
+        let hits: u64 = by_cache.values().map(|c| c.hits).sum();
+        let misses: u64 = by_cache.values().map(|c| c.misses).sum();
+        let lookups = hits + misses;
+
+        CacheSummary {
+            hits,
+            misses,
+            hit_rate: (lookups > 0).then(|| hits as f64 / lookups as f64),
+            by_cache,
+        }
+    }
+
+    /// Fetch outcomes per source, sorted by name.
+    pub fn sources(&self) -> BTreeMap<String, SourceActivity> {
+        self.sources.iter().map(|e| (e.key().clone(), e.value().clone())).collect()
+    }
+
+    /// Recent scrape failures, newest first.
+    pub fn recent_failures(&self) -> Vec<ScrapeFailure> {
+        self.failures
+            .lock()
+            .map(|failures| failures.iter().rev().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Last run of every job seen, sorted by name.
+    pub fn jobs(&self) -> BTreeMap<String, JobRun> {
+        self.jobs.iter().map(|e| (e.key().clone(), e.value().clone())).collect()
+    }
+}
+
+/// Process-wide stats fed by [`super::metrics`].
+pub static STATS: Lazy<RuntimeStats> = Lazy::new(RuntimeStats::new);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summaries_reflect_recorded_events() {
+        let stats = RuntimeStats::new();
+        for ms in 1..=20 {
+            stats.record_request(if ms == 20 { 503 } else { 200 }, ms as f64 / 1000.0);
+        }
+        stats.record_cache_lookup("fetch", true);
+        stats.record_cache_lookup("fetch", true);
+        stats.record_cache_lookup("anime", false);
+        stats.record_upstream_fetch("otakudesu.best", true);
+        stats.record_upstream_fetch("otakudesu.best", false);
+        stats.record_job("prewarm:anime:ongoing", true, 0.5);
+        stats.record_job("prewarm:anime:ongoing", false, 0.25);
+
+        let requests = stats.requests();
+        assert_eq!(requests.total, 20);
+        assert_eq!(requests.server_errors, 1);
+        assert_eq!(requests.window_requests, 20);
+        assert_eq!(requests.p50_ms, 10.0);
+        assert_eq!(requests.p95_ms, 19.0);
+        assert_eq!(requests.max_ms, 20.0);
+
+        let cache = stats.cache();
+        assert_eq!((cache.hits, cache.misses), (2, 1));
+        assert!((cache.hit_rate.unwrap() - 2.0 / 3.0).abs() < 1e-9);
+
+        let source = &stats.sources()["otakudesu.best"];
+        assert_eq!((source.successes, source.failures), (1, 1));
+        assert!(source.last_success_at.is_some() && source.last_failure_at.is_some());
+
+        let job = &stats.jobs()["prewarm:anime:ongoing"];
+        assert_eq!((job.runs, job.failures, job.last_success), (2, 1, false));
+        assert_eq!(job.last_duration_ms, 250.0);
+    }
+
+    #[test]
+    fn only_the_latest_failures_are_kept() {
+        let stats = RuntimeStats::new();
+        assert_eq!(stats.cache().hit_rate, None);
+        for i in 0..MAX_RECENT_FAILURES + 5 {
+            stats.record_scrape_failure("komiku.org", &format!("https://komiku.org/{}", i), "timeout");
+        }
+        let failures = stats.recent_failures();
+        assert_eq!(failures.len(), MAX_RECENT_FAILURES);
+        assert_eq!(failures[0].url, format!("https://komiku.org/{}", MAX_RECENT_FAILURES + 4));
+    }
+}
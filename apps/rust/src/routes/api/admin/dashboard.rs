@@ -0,0 +1,201 @@
+//! Handler for the operator dashboard snapshot.
+
+use axum::{extract::State, response::IntoResponse, Json, Router};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+use crate::circuit_breaker::sources::state_name;
+use crate::circuit_breaker::{CircuitState, SourceBreakers};
+use crate::core::error::AppError;
+use crate::middleware::auth::{require_admin, AuthMiddleware};
+use crate::observability::stats::{CacheSummary, JobRun, RequestSummary, RuntimeStats, ScrapeFailure, STATS};
+use crate::routes::AppState;
+
+/// Breaker and freshness of one upstream source.
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct SourceStatus {
+    pub source: String,
+    /// `closed`, `half_open` or `open`.
+    pub breaker: String,
+    pub successes: u64,
+    pub failures: u64,
+    pub last_success_at: Option<DateTime<Utc>>,
+    /// Seconds since the last successful fetch; `None` if there was none.
+    pub data_age_seconds: Option<i64>,
+}
+
+/// Last run of one scheduled task or job.
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct JobStatusEntry {
+    pub name: String,
+    #[serde(flatten)]
+    pub run: JobRun,
+}
+
+/// Open WebSocket connections on this instance.
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct WebSocketSummary {
+    pub chat_connections: usize,
+    pub chat_rooms: usize,
+    pub room_members: usize,
+}
+
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct DashboardResponse {
+    pub generated_at: DateTime<Utc>,
+    pub requests: RequestSummary,
+    pub sources: Vec<SourceStatus>,
+    pub cache: CacheSummary,
+    /// Newest first.
+    pub recent_failures: Vec<ScrapeFailure>,
+    pub jobs: Vec<JobStatusEntry>,
+    pub websocket: WebSocketSummary,
+}
+
+/// Builds the snapshot from `stats` and `breakers`. Sources known to either
+/// are listed; one that has a breaker but no fetches yet reports no age.
+pub async fn collect(
+    stats: &RuntimeStats,
+    breakers: &SourceBreakers,
+    websocket: WebSocketSummary,
+) -> DashboardResponse {
+    let now = Utc::now();
+    let states = breakers.states().await;
+    let activity = stats.sources();
+
+    let names: BTreeSet<&String> = states.keys().chain(activity.keys()).collect();
+    let sources = names
+        .into_iter()
+        .map(|name| {
+            let seen = activity.get(name).cloned().unwrap_or_default();
+            let state = states.get(name).copied().unwrap_or(CircuitState::Closed);
+            SourceStatus {
+                source: name.clone(),
+                breaker: state_name(state).to_string(),
+                successes: seen.successes,
+                failures: seen.failures,
+                last_success_at: seen.last_success_at,
+                data_age_seconds: seen.last_success_at.map(|at| (now - at).num_seconds().max(0)),
+            }
+        })
+        .collect();
+
+    DashboardResponse {
+        generated_at: now,
+        requests: stats.requests(),
+        sources,
+        cache: stats.cache(),
+        recent_failures: stats.recent_failures(),
+        jobs: stats
+            .jobs()
+            .into_iter()
+            .map(|(name, run)| JobStatusEntry { name, run })
+            .collect(),
+        websocket,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/dashboard",
+    tag = "admin",
+    operation_id = "admin_dashboard",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Request, source, cache, job and WebSocket snapshot of this instance", body = DashboardResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin role required"),
+        (status = 500, description = "Internal Server Error", body = String)
+    )
+)]
+pub async fn dashboard(
+    State(state): State<Arc<AppState>>,
+    auth: AuthMiddleware,
+) -> Result<impl IntoResponse, AppError> {
+    require_admin(state.sea_orm(), &auth.0.user_id).await?;
+
+    let websocket = WebSocketSummary {
+        chat_connections: state.chat_rooms.connection_count(),
+        chat_rooms: state.chat_rooms.room_count(),
+        room_members: state.room_manager.total_members(),
+    };
+    Ok(Json(collect(&STATS, &state.source_breakers, websocket).await))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit_breaker::breaker::CircuitBreakerConfig;
+
+    #[tokio::test]
+    async fn dashboard_reports_every_section_after_traffic() {
+        let stats = RuntimeStats::new();
+        let breakers = SourceBreakers::new(CircuitBreakerConfig {
+            failure_threshold: 1,
+            ..CircuitBreakerConfig::default()
+        });
+
+        for ms in [5.0, 12.0, 40.0, 250.0] {
+            stats.record_request(200, ms / 1000.0);
+        }
+        stats.record_request(502, 0.9);
+        stats.record_cache_lookup("fetch", true);
+        stats.record_cache_lookup("fetch", false);
+        stats.record_cache_lookup("anime", true);
+        stats.record_upstream_fetch("otakudesu.best", true);
+        stats.record_upstream_fetch("komiku.org", false);
+        stats.record_scrape_failure("komiku.org", "https://komiku.org/manga/x/", "Upstream returned status 503");
+        stats.record_job("prewarm:anime:ongoing", true, 1.2);
+
+        // Trip komiku's breaker; otakudesu's stays closed.
+        let _ = breakers
+            .call("komiku.org", async { Err::<(), _>(AppError::Other("status 503".to_string())) })
+            .await;
+        let _ = breakers.call("otakudesu.best", async { Ok::<_, AppError>(()) }).await;
+
+        let websocket = WebSocketSummary { chat_connections: 3, chat_rooms: 1, room_members: 0 };
+        let snapshot = collect(&stats, &breakers, websocket).await;
+        let json = serde_json::to_value(&snapshot).unwrap();
+
+        let requests = &json["requests"];
+        assert_eq!(requests["total"], 5);
+        assert_eq!(requests["server_errors"], 1);
+        assert!(requests["rate_per_second"].as_f64().unwrap() > 0.0);
+        assert_eq!(requests["p50_ms"], 40.0);
+        assert_eq!(requests["max_ms"], 900.0);
+
+        let sources = json["sources"].as_array().unwrap();
+        assert_eq!(sources.len(), 2);
+        let komiku = &sources[0];
+        assert_eq!(komiku["source"], "komiku.org");
+        assert_eq!(komiku["breaker"], "open");
+        assert!(komiku["data_age_seconds"].is_null());
+        let otakudesu = &sources[1];
+        assert_eq!(otakudesu["breaker"], "closed");
+        let age = otakudesu["data_age_seconds"].as_i64().unwrap();
+        assert!((0..5).contains(&age), "{}", age);
+
+        let cache = &json["cache"];
+        assert_eq!(cache["hits"], 2);
+        assert_eq!(cache["misses"], 1);
+        assert!((cache["hit_rate"].as_f64().unwrap() - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(cache["by_cache"]["fetch"]["misses"], 1);
+
+        assert_eq!(json["recent_failures"][0]["url"], "https://komiku.org/manga/x/");
+
+        let job = &json["jobs"][0];
+        assert_eq!(job["name"], "prewarm:anime:ongoing");
+        assert_eq!(job["last_success"], true);
+        assert_eq!(job["runs"], 1);
+        assert!(job["last_run_at"].is_string());
+
+        assert_eq!(json["websocket"]["chat_connections"], 3);
+    }
+}
+
+pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
+    router
+}
\ No newline at end of file
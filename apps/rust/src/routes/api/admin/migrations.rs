@@ -0,0 +1,53 @@
+//! Handler for the admin migration status endpoint.
+
+use axum::{extract::State, response::IntoResponse, Json, Router};
+use serde::Serialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+use crate::core::error::AppError;
+use crate::infra::migrations::{self, MigrationStatus};
+use crate::middleware::auth::{require_admin, AuthMiddleware};
+use crate::routes::AppState;
+
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct MigrationsResponse {
+    pub applied: usize,
+    pub pending: usize,
+    pub migrations: Vec<MigrationStatus>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/migrations",
+    tag = "admin",
+    operation_id = "admin_migrations",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Applied and pending database migrations", body = MigrationsResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin role required"),
+        (status = 500, description = "Internal Server Error", body = String)
+    )
+)]
+pub async fn migrations(
+    State(state): State<Arc<AppState>>,
+    auth: AuthMiddleware,
+) -> Result<impl IntoResponse, AppError> {
+    require_admin(state.sea_orm(), &auth.0.user_id).await?;
+
+    let migrations = migrations::status(state.sea_orm())
+        .await
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    let applied = migrations.iter().filter(|m| m.applied).count();
+
+    Ok(Json(MigrationsResponse {
+        applied,
+        pending: migrations.len() - applied,
+        migrations,
+    }))
+}
+
+pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
+    router
+}
\ No newline at end of file
@@ -0,0 +1,15 @@
+/// THIS FILE IS AUTOMATICALLY GENERATED BY build.rs
+/// DO NOT EDIT THIS FILE MANUALLY
+
+pub mod dashboard;
+pub mod migrations;
+pub mod selectors;
+
+/// Register routes for this directory
+use axum::Router;
+use std::sync::Arc;
+use crate::routes::AppState;
+
+pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
+    dashboard::register_routes(migrations::register_routes(selectors::register_routes(router)))
+}
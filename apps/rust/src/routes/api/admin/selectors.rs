@@ -0,0 +1,45 @@
+//! Handler for hot-reloading scraping selectors.
+
+use axum::{extract::State, response::IntoResponse, Json, Router};
+use serde::Serialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+use crate::core::error::AppError;
+use crate::middleware::auth::{require_admin, AuthMiddleware};
+use crate::routes::AppState;
+use crate::scraping::selectors::{SelectorChange, SELECTORS};
+
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct SelectorReloadResponse {
+    /// Selectors whose CSS changed; empty when nothing did.
+    pub changed: Vec<SelectorChange>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/selectors/reload",
+    tag = "admin",
+    operation_id = "admin_selectors_reload",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Selectors reloaded from the config file and Redis overrides", body = SelectorReloadResponse),
+        (status = 400, description = "An override is unknown or not valid CSS; nothing was changed"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin role required"),
+        (status = 500, description = "Internal Server Error", body = String)
+    )
+)]
+pub async fn reload(
+    State(state): State<Arc<AppState>>,
+    auth: AuthMiddleware,
+) -> Result<impl IntoResponse, AppError> {
+    require_admin(state.sea_orm(), &auth.0.user_id).await?;
+
+    let changed = SELECTORS.reload().await?;
+    Ok(Json(SelectorReloadResponse { changed }))
+}
+
+pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
+    router
+}
\ No newline at end of file
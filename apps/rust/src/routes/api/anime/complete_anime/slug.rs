@@ -3,11 +3,13 @@ use std::sync::Arc;
 
 // External crate imports
 use crate::helpers::{
-    internal_err, Cache, fetch_html_with_retry, text_from_or, attr_from_or, extract_slug,
+    upstream_err, Cache, fetch_html_with_retry, text_from_or, attr_from_or, extract_slug,
     parse_html, selector
 };
+use crate::core::error::AppError;
 use crate::routes::AppState;
-use crate::scraping::urls::OTAKUDESU_BASE_URL;
+use crate::scraping::pagination::extract_pagination;
+use crate::scraping::urls::get_otakudesu_url;
 use axum::{
     extract::{Path, State},
     http::StatusCode,
@@ -27,15 +29,7 @@ pub struct CompleteAnimeItem {
     pub anime_url: String,
 }
 
-#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
-pub struct Pagination {
-    pub current_page: u32,
-    pub last_visible_page: u32,
-    pub has_next_page: bool,
-    pub next_page: Option<u32>,
-    pub has_previous_page: bool,
-    pub previous_page: Option<u32>,
-}
+pub use crate::helpers::pagination::Pagination;
 
 #[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
 pub struct ListResponse {
@@ -68,34 +62,43 @@ pub async fn slug(
     let _start = std::time::Instant::now();
     info!("Starting request for complete_anime slug: {}", slug);
 
-    let cache_key = format!("anime:complete:{}", slug);
+    let cache_key = page_cache_key(&slug);
     let cache = Cache::new(&app_state.redis_pool);
 
     let response = cache
-        .get_or_set(&cache_key, CACHE_TTL, || async {
-            let url = format!("{}/complete-anime/page/{}/", OTAKUDESU_BASE_URL, slug);
-
-            let html = fetch_html_with_retry(&url).await.map_err(|e| format!("Failed to fetch HTML: {}", e))?;
-
-            let (anime_list, pagination) =
-                tokio::task::spawn_blocking(move || parse_anime_page(&html, &slug))
-                    .await
-                    .map_err(|e| e.to_string())?
-                    .map_err(|e| e.to_string())?;
-
-            let total = anime_list.len() as i64;
-            Ok(ListResponse {
-                message: "Success".to_string(),
-                data: anime_list,
-                total: Some(total),
-                pagination: Some(pagination),
-            })
-        })
+        .get_or_fetch(&cache_key, CACHE_TTL, || build_complete_page(slug))
         .await
-        .map_err(|e| internal_err(&e))?;
+        .map_err(|e| upstream_err(&e))?;
 
     return Ok(Json(response).into_response());
 }
+
+/// Cache key for page `slug` of the complete anime list.
+pub fn page_cache_key(slug: &str) -> String {
+    format!("anime:complete:{}", slug)
+}
+
+/// Fetches and parses page `slug` of the complete anime list.
+pub async fn build_complete_page(slug: String) -> Result<ListResponse, AppError> {
+    let url = format!("{}/complete-anime/page/{}/", get_otakudesu_url(), slug);
+
+    let html = fetch_html_with_retry(&url).await?;
+
+    let (anime_list, pagination) =
+        tokio::task::spawn_blocking(move || parse_anime_page(&html, &slug))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?;
+
+    let total = anime_list.len() as i64;
+    Ok(ListResponse {
+        message: "Success".to_string(),
+        data: anime_list,
+        total: Some(total),
+        pagination: Some(pagination),
+    })
+}
+
 /// Parses HTML document to extract anime items and pagination information
 fn parse_anime_page(
     html: &str,
@@ -109,8 +112,6 @@ fn parse_anime_page(
     let link_selector = selector("a").unwrap();
     let img_selector = selector("img").unwrap();
     let episode_selector = selector(".epz").unwrap();
-    let pagination_selector = selector(".pagenavix .page-numbers:not(.next)").unwrap();
-    let next_selector = selector(".pagenavix .next.page-numbers").unwrap();
 
     // Extract anime items
     for element in document.select(&item_selector) {
@@ -133,33 +134,7 @@ fn parse_anime_page(
 
     // Extract pagination information
     let current_page = slug.parse::<u32>().unwrap_or(1);
-    let last_visible_page = document
-        .select(&pagination_selector)
-        .next_back()
-        .and_then(|e| e.text().collect::<String>().trim().parse::<u32>().ok())
-        .unwrap_or(1);
-
-    let has_next_page = document.select(&next_selector).next().is_some();
-    let next_page = if has_next_page {
-        Some(current_page + 1)
-    } else {
-        None
-    };
-    let has_previous_page = current_page > 1;
-    let previous_page = if has_previous_page {
-        Some(current_page - 1)
-    } else {
-        None
-    };
-
-    let pagination = Pagination {
-        current_page,
-        last_visible_page,
-        has_next_page,
-        next_page,
-        has_previous_page,
-        previous_page,
-    };
+    let pagination = extract_pagination(&document, current_page, "otakudesu");
 
     Ok((anime_list, pagination))
 }
@@ -0,0 +1,215 @@
+//! Several anime details in one request.
+//!
+//! Each slug goes through the same cache as `GET /api/anime/detail/{slug}`,
+//! so cached titles cost no upstream fetch. A slug that fails is reported in
+//! its own entry; the rest of the batch is still returned.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json, Router};
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use utoipa::ToSchema;
+
+use super::slug::{load_detail, DetailResponse};
+use crate::core::error::AppError;
+use crate::routes::AppState;
+
+/// Most slugs one batch may ask for.
+pub const MAX_BATCH_SLUGS: usize = 10;
+
+/// Details fetched at the same time within one batch.
+const BATCH_CONCURRENCY: usize = 4;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchDetailRequest {
+    /// Anime slugs; duplicates are looked up once.
+    pub slugs: Vec<String>,
+}
+
+/// Why one slug of a batch has no detail.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+pub struct BatchDetailError {
+    /// Status the single-detail endpoint would have answered with.
+    pub code: u16,
+    pub error: String,
+}
+
+/// A slug's detail, or why it couldn't be loaded.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum BatchDetailResult {
+    Ok(Box<DetailResponse>),
+    Err(BatchDetailError),
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchDetailResponse {
+    /// One entry per requested slug.
+    pub results: BTreeMap<String, BatchDetailResult>,
+}
+
+/// Trimmed, non-empty, de-duplicated slugs in request order.
+fn normalize_slugs(slugs: Vec<String>) -> Result<Vec<String>, AppError> {
+    let mut unique: Vec<String> = Vec::new();
+    for slug in slugs {
+        let slug = slug.trim().to_string();
+        if !slug.is_empty() && !unique.contains(&slug) {
+            unique.push(slug);
+        }
+    }
+
+    if unique.is_empty() {
+        return Err(AppError::BadRequest("slugs must contain at least one slug".to_string()));
+    }
+    if unique.len() > MAX_BATCH_SLUGS {
+        return Err(AppError::BadRequest(format!(
+            "At most {} slugs per batch (got {})",
+            MAX_BATCH_SLUGS,
+            unique.len()
+        )));
+    }
+    Ok(unique)
+}
+
+/// Runs `load` for every slug, at most `concurrency` at a time, and collects
+/// each outcome under its slug.
+async fn load_batch<F, Fut>(slugs: Vec<String>, concurrency: usize, load: F) -> BTreeMap<String, BatchDetailResult>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<DetailResponse, (StatusCode, String)>>,
+{
+    let permits = Semaphore::new(concurrency.max(1));
+    let results = join_all(slugs.into_iter().map(|slug| {
+        let permits = &permits;
+        let load = &load;
+        async move {
+            let result = match permits.acquire().await {
+                Ok(_permit) => load(slug.clone()).await,
+                Err(_) => Err((StatusCode::SERVICE_UNAVAILABLE, "Batch was cancelled".to_string())),
+            };
+            let result = match result {
+                Ok(detail) => BatchDetailResult::Ok(Box::new(detail)),
+                Err((status, error)) => BatchDetailResult::Err(BatchDetailError { code: status.as_u16(), error }),
+            };
+            (slug, result)
+        }
+    }))
+    .await;
+
+    results.into_iter().collect()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/anime/detail/batch",
+    tag = "anime",
+    operation_id = "anime_detail_batch",
+    request_body = BatchDetailRequest,
+    responses(
+        (status = 200, description = "Detail or error per slug", body = BatchDetailResponse),
+        (status = 400, description = "No slugs, or more than 10", body = String)
+    )
+)]
+pub async fn batch(
+    State(app_state): State<Arc<AppState>>,
+    Json(payload): Json<BatchDetailRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let slugs = normalize_slugs(payload.slugs)?;
+    let results = load_batch(slugs, BATCH_CONCURRENCY, |slug| {
+        let app_state = app_state.clone();
+        async move { load_detail(&app_state, &slug).await }
+    })
+    .await;
+    Ok(Json(BatchDetailResponse { results }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::api::anime::detail::slug::AnimeDetailData;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn detail(title: &str) -> DetailResponse {
+        DetailResponse {
+            status: Some("Ok".to_string()),
+            data: AnimeDetailData {
+                title: title.to_string(),
+                alternative_title: String::new(),
+                alternative_titles: vec![],
+                poster: String::new(),
+                r#type: None,
+                status: None,
+                release_date: String::new(),
+                studio: String::new(),
+                genres: vec![],
+                synopsis: String::new(),
+                episode_lists: vec![],
+                batch: vec![],
+                producers: vec![],
+                recommendations: vec![],
+            },
+            source: None,
+            fetched_url: None,
+            selector_misses: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn failures_are_reported_per_slug() {
+        let slugs = vec!["frieren".to_string(), "missing".to_string(), "naruto".to_string()];
+        let results = load_batch(slugs, 2, |slug| async move {
+            match slug.as_str() {
+                "missing" => Err((StatusCode::NOT_FOUND, "Anime 'missing' not found".to_string())),
+                _ => Ok(detail(&slug)),
+            }
+        })
+        .await;
+
+        assert_eq!(results.len(), 3);
+        assert!(matches!(&results["frieren"], BatchDetailResult::Ok(d) if d.data.title == "frieren"));
+        assert!(matches!(&results["naruto"], BatchDetailResult::Ok(_)));
+        let json = serde_json::to_value(&results["missing"]).unwrap();
+        assert_eq!(json, serde_json::json!({ "code": 404, "error": "Anime 'missing' not found" }));
+    }
+
+    #[tokio::test]
+    async fn fetches_are_bounded_by_the_semaphore() {
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let slugs: Vec<String> = (0..8).map(|i| format!("anime-{}", i)).collect();
+
+        let results = load_batch(slugs, 3, |slug| {
+            let (running, peak) = (&running, &peak);
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                Ok(detail(&slug))
+            }
+        })
+        .await;
+
+        assert_eq!(results.len(), 8);
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn slugs_are_deduplicated_and_capped() {
+        let slugs = normalize_slugs(vec![" frieren ".into(), "frieren".into(), "".into(), "naruto".into()]).unwrap();
+        assert_eq!(slugs, vec!["frieren", "naruto"]);
+
+        assert!(matches!(normalize_slugs(vec!["  ".into()]), Err(AppError::BadRequest(_))));
+        let too_many = (0..=MAX_BATCH_SLUGS).map(|i| format!("anime-{}", i)).collect();
+        assert!(matches!(normalize_slugs(too_many), Err(AppError::BadRequest(_))));
+    }
+}
+
+pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
+    router
+}
\ No newline at end of file
@@ -1,6 +1,7 @@
 /// THIS FILE IS AUTOMATICALLY GENERATED BY build.rs
 /// DO NOT EDIT THIS FILE MANUALLY
 
+pub mod batch;
 pub mod slug;
 
 /// Register routes for this directory
@@ -9,5 +10,5 @@ use std::sync::Arc;
 use crate::routes::AppState;
 
 pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
-    slug::register_routes(router)
+    batch::register_routes(slug::register_routes(router))
 }
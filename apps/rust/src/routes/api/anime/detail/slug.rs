@@ -2,22 +2,34 @@
 use std::sync::Arc;
 
 // External crate imports
-use crate::helpers::{
-    default_backoff, internal_err, parse_html, transient, Cache,
-};
+use crate::helpers::{parse_html, scrape_backoff, transient, upstream_err, Cache};
+use crate::helpers::conditional::{http_date, weak_etag};
+use crate::helpers::http::SourceRequest;
 use crate::services::images::cache::{get_cached_or_original, cache_image_urls_batch_lazy};
-use crate::helpers::scraping::{attr, attr_from_or, extract_slug, selector, text, text_from_or};
+use crate::services::search_index::{self, SearchEntry, SearchKind};
+use crate::helpers::scraping::{
+    attr, attr_from_or, extract_slug, select_attr, select_text, selector, split_alternative_titles,
+    split_labeled_list, text, text_from_or,
+};
+use crate::infra::http_client::http_client_fast;
 use crate::infra::proxy::fetch_with_proxy;
+use crate::observability::metrics::record_cache_lookup;
 use crate::routes::AppState;
-use crate::scraping::urls::OTAKUDESU_BASE_URL;
+use crate::scraping::urls::get_otakudesu_url;
 use crate::core::error::AppError;
+use crate::scraping::debug::DebugQuery;
+use crate::scraping::parse_report::{self, ParseReport};
+use crate::scraping::robots::ROBOTS;
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::IntoResponse,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
     Json, Router,
 };
+use chrono::Utc;
 use backoff::future::retry;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 use utoipa::ToSchema;
@@ -50,7 +62,11 @@ pub struct Recommendation {
 #[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
 pub struct AnimeDetailData {
     pub title: String,
+    /// Deprecated: first Japanese title only; use `alternative_titles`.
     pub alternative_title: String,
+    /// Japanese, English and synonym titles, split and deduplicated.
+    #[serde(default)]
+    pub alternative_titles: Vec<String>,
     pub poster: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub r#type: Option<String>,
@@ -74,79 +90,343 @@ pub struct DetailResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<String>,
     pub data: AnimeDetailData,
+    /// Scrape source; only with `?debug=1`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Upstream URL the data was scraped from; only with `?debug=1`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fetched_url: Option<String>,
+    /// Selectors that matched nothing in a fresh scrape; only with
+    /// `?debug=selectors`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selector_misses: Option<ParseReport>,
+}
+
+/// Source name reported by `?debug=1`.
+const SOURCE: &str = "otakudesu";
+
+const INFO_SELECTOR: &str = ".infozingle p";
+const POSTER_SELECTOR: &str = ".fotoanime img";
+const SYNOPSIS_SELECTOR: &str = ".sinopc";
+const EPISODE_LIST_SELECTOR: &str = ".episodelist ul li a";
+
+/// Selectors that match on every real detail page; a miss means the
+/// layout changed.
+const CRITICAL_SELECTORS: [&str; 3] = [INFO_SELECTOR, POSTER_SELECTOR, EPISODE_LIST_SELECTOR];
+
+/// Upstream page a detail is scraped from.
+pub fn detail_url(slug: &str) -> String {
+    format!("{}/anime/{}", get_otakudesu_url(), slug)
+}
+
+impl DetailResponse {
+    /// Adds `source`/`fetched_url` when `debug` is on.
+    fn with_origin(mut self, debug: &DebugQuery, slug: &str) -> Self {
+        (self.source, self.fetched_url) = debug.origin(SOURCE, &detail_url(slug));
+        self
+    }
 }
 
 const CACHE_TTL: u64 = 300; // 5 minutes
+const NEGATIVE_CACHE_TTL: u64 = 30;
 
 #[utoipa::path(
     get,
     params(
-        ("slug" = String, Path, description = "URL-friendly identifier for the resource (typically lowercase with hyphens)", example = "naruto-shippuden-episode-1")
+        ("slug" = String, Path, description = "URL-friendly identifier for the resource (typically lowercase with hyphens)", example = "naruto-shippuden-episode-1"),
+        DebugQuery
     ),
     path = "/api/anime/detail/{slug}",
     tag = "anime",
     operation_id = "anime_detail_slug",
     responses(
         (status = 200, description = "Handles GET requests for the anime/detail/{slug} endpoint.", body = DetailResponse),
+        (status = 404, description = "Anime not found upstream or page had no content", body = String),
         (status = 500, description = "Internal Server Error", body = String)
     )
 )]
 pub async fn slug(
     State(app_state): State<Arc<AppState>>,
     Path(slug): Path<String>,
+    Query(debug): Query<DebugQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let _start = std::time::Instant::now();
     info!("Starting request for detail slug: {}", slug);
+    let response = if debug.selectors() {
+        // Cached responses carry no report, so this always scrapes.
+        let (data, report) = fetch_anime_detail_reported(slug.clone())
+            .await
+            .map_err(|e| upstream_err(&AppError::from(e)))?;
+        DetailResponse {
+            status: Some("Ok".to_string()),
+            data,
+            source: None,
+            fetched_url: None,
+            selector_misses: Some(report),
+        }
+    } else {
+        load_detail(&app_state, &slug).await?
+    };
+    let etag = detail_etag(&response);
+    Ok(([(header::ETAG, etag)], Json(response.with_origin(&debug, &slug))))
+}
+
+fn cache_key(slug: &str) -> String {
+    format!("anime:detail:{}", slug)
+}
+
+fn negative_cache_key(slug: &str) -> String {
+    format!("anime:detail:missing:{}", slug)
+}
+
+/// `ETag` of a detail response, as sent by both `GET` and `HEAD`.
+fn detail_etag(response: &DetailResponse) -> String {
+    weak_etag(&serde_json::to_vec(response).unwrap_or_default())
+}
+
+/// What a `HEAD` learned about a slug without parsing its page.
+#[derive(Debug, PartialEq, Eq)]
+enum Presence {
+    /// Detail is cached; carries the validators of the cached body.
+    Cached { etag: String, last_modified: Option<String> },
+    /// Page exists upstream but nothing is cached yet.
+    Upstream { last_modified: Option<String> },
+    Missing,
+}
+
+impl Presence {
+    fn cached(response: &DetailResponse, last_modified: Option<String>) -> Self {
+        Self::Cached { etag: detail_etag(response), last_modified }
+    }
+}
+
+/// Body-less response for `presence`.
+fn head_response(presence: Presence) -> Response {
+    let (status, etag, last_modified) = match presence {
+        Presence::Cached { etag, last_modified } => (StatusCode::OK, Some(etag), last_modified),
+        Presence::Upstream { last_modified } => (StatusCode::OK, None, last_modified),
+        Presence::Missing => (StatusCode::NOT_FOUND, None, None),
+    };
+
+    let mut headers = HeaderMap::new();
+    if let Some(value) = etag.and_then(|v| HeaderValue::from_str(&v).ok()) {
+        headers.insert(header::ETAG, value);
+    }
+    if let Some(value) = last_modified.and_then(|v| HeaderValue::from_str(&v).ok()) {
+        headers.insert(header::LAST_MODIFIED, value);
+    }
+    (status, headers).into_response()
+}
+
+/// Result of an upstream `HEAD` of a detail page.
+#[derive(Debug, PartialEq, Eq)]
+enum Probe {
+    Found { last_modified: Option<String> },
+    NotFound,
+    /// HEAD not supported, blocked or failed; only a full fetch can tell.
+    Unknown,
+}
+
+async fn probe_upstream(client: &reqwest::Client, url: &str) -> Probe {
+    match SourceRequest::for_url(url).apply(client.head(url)).send().await {
+        Ok(res) if res.status().is_success() => Probe::Found {
+            last_modified: res
+                .headers()
+                .get(header::LAST_MODIFIED)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string),
+        },
+        Ok(res) if res.status() == StatusCode::NOT_FOUND => Probe::NotFound,
+        Ok(res) => {
+            info!("Upstream HEAD {} returned {}, falling back to GET", url, res.status());
+            Probe::Unknown
+        }
+        Err(e) => {
+            warn!("Upstream HEAD {} failed: {}", url, e);
+            Probe::Unknown
+        }
+    }
+}
 
-    let cache_key = format!("anime:detail:{}", slug);
+/// Cheap existence check: answers from the cache or an upstream `HEAD`, and
+/// only fetches the full page when the upstream can't answer `HEAD`.
+#[utoipa::path(
+    head,
+    params(
+        ("slug" = String, Path, description = "URL-friendly identifier for the resource (typically lowercase with hyphens)", example = "naruto-shippuden-episode-1")
+    ),
+    path = "/api/anime/detail/{slug}",
+    tag = "anime",
+    operation_id = "anime_detail_head",
+    responses(
+        (status = 200, description = "Anime exists; `ETag`/`Last-Modified` are set when the detail is cached."),
+        (status = 404, description = "Anime not found upstream"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn head(
+    State(app_state): State<Arc<AppState>>,
+    Path(slug): Path<String>,
+) -> Result<Response, (StatusCode, String)> {
     let cache = Cache::new(&app_state.redis_pool);
+    let key = cache_key(&slug);
+
+    if let Some(cached) = cache.get::<DetailResponse>(&key).await {
+        let cached_at = cache
+            .ttl(&key)
+            .await
+            .map(|left| Utc::now() - chrono::Duration::seconds(CACHE_TTL.saturating_sub(left) as i64));
+        return Ok(head_response(Presence::cached(&cached, cached_at.map(http_date))));
+    }
+    if cache.exists(&negative_cache_key(&slug)).await {
+        return Ok(head_response(Presence::Missing));
+    }
 
-    let response = cache
-        .get_or_set(&cache_key, CACHE_TTL, || async {
-            let mut data = fetch_anime_detail(slug.clone())
-                .await
-                .map_err(|e| e.to_string())?;
-
-            // 1. Individual cache for main poster
-            data.poster = get_cached_or_original(
-                app_state.db.clone(),
-                &app_state.redis_pool,
-                &data.poster,
-                Some(app_state.image_processing_semaphore.clone()),
-            ).await;
-
-            // 2. Batch cache for recommendations
-            let rec_posters: Vec<String> = data.recommendations.iter().map(|r| r.poster.clone()).collect();
-            let cached_rec_posters = cache_image_urls_batch_lazy(
-                app_state.db.clone(),
-                &app_state.redis_pool,
-                rec_posters,
-                Some(app_state.image_processing_semaphore.clone()),
-            ).await;
-
-            for (i, rec) in data.recommendations.iter_mut().enumerate() {
-                if let Some(url) = cached_rec_posters.get(i) {
-                    rec.poster = url.clone();
-                }
+    let url = detail_url(&slug);
+    if let Err(e) = ROBOTS.check(&url).await {
+        return Err((StatusCode::FORBIDDEN, e.to_string()));
+    }
+    match probe_upstream(http_client_fast().client(), &url).await {
+        Probe::Found { last_modified } => Ok(head_response(Presence::Upstream { last_modified })),
+        Probe::NotFound => {
+            if let Err(e) = cache.set_with_ttl(&negative_cache_key(&slug), &true, NEGATIVE_CACHE_TTL).await {
+                warn!("Failed to store negative cache for {}: {}", slug, e);
             }
+            Ok(head_response(Presence::Missing))
+        }
+        Probe::Unknown => match load_detail(&app_state, &slug).await {
+            Ok(response) => Ok(head_response(Presence::cached(&response, Some(http_date(Utc::now()))))),
+            Err((StatusCode::NOT_FOUND, _)) => Ok(head_response(Presence::Missing)),
+            Err(e) => Err(e),
+        },
+    }
+}
+
+/// Cached detail for `slug`, fetching and caching it on a miss.
+pub(crate) async fn load_detail(
+    app_state: &Arc<AppState>,
+    slug: &str,
+) -> Result<DetailResponse, (StatusCode, String)> {
+    let key = cache_key(slug);
+    let negative_key = negative_cache_key(slug);
+    let cache = Cache::new(&app_state.redis_pool);
+
+    if let Some(cached) = cache.get::<DetailResponse>(&key).await {
+        record_cache_lookup("anime", true);
+        return Ok(cached);
+    }
+    record_cache_lookup("anime", false);
+
+    if cache.exists(&negative_key).await {
+        return Err((StatusCode::NOT_FOUND, format!("Anime '{}' not found", slug)));
+    }
 
-            Ok(DetailResponse {
-                status: Some("Ok".to_string()),
-                data,
-            })
-        })
+    let result = fetch_anime_detail(slug.to_string())
         .await
-        .map_err(|e| internal_err(&e))?;
+        .map_err(AppError::from);
+
+    let mut data = match (detail_cache_decision(&result), result) {
+        (CacheDecision::Store, Ok(data)) => data,
+        (CacheDecision::Skip, Err(e)) => return Err(upstream_err(&e)),
+        _ => {
+            // Remember the miss briefly so repeated lookups don't hammer upstream,
+            // but never let it shadow a valid entry for long.
+            if let Err(e) = cache.set_with_ttl(&negative_key, &true, NEGATIVE_CACHE_TTL).await {
+                warn!("Failed to store negative cache for {}: {}", slug, e);
+            }
+            return Err((StatusCode::NOT_FOUND, format!("Anime '{}' not found", slug)));
+        }
+    };
 
-    return Ok(Json(response).into_response());
+    // 1. Individual cache for main poster
+    data.poster = get_cached_or_original(
+        app_state.db.clone(),
+        &app_state.redis_pool,
+        &data.poster,
+        Some(app_state.image_processing_semaphore.clone()),
+    ).await;
+
+    // 2. Batch cache for recommendations
+    let rec_posters: Vec<String> = data.recommendations.iter().map(|r| r.poster.clone()).collect();
+    let cached_rec_posters = cache_image_urls_batch_lazy(
+        app_state.db.clone(),
+        &app_state.redis_pool,
+        rec_posters,
+        Some(app_state.image_processing_semaphore.clone()),
+    ).await;
+
+    for (i, rec) in data.recommendations.iter_mut().enumerate() {
+        if let Some(url) = cached_rec_posters.get(i) {
+            rec.poster = url.clone();
+        }
+    }
+
+    let response = DetailResponse {
+        status: Some("Ok".to_string()),
+        data,
+        source: None,
+        fetched_url: None,
+        selector_misses: None,
+    };
+
+    if let Err(e) = cache.set_with_ttl(&key, &response, CACHE_TTL).await {
+        warn!("Failed to cache anime detail for {}: {}", slug, e);
+    }
+
+    // Detail pages are the only place alternative titles show up.
+    let entry = SearchEntry::new(SearchKind::Anime, slug, &response.data.title, &response.data.poster)
+        .with_alternative_titles(response.data.alternative_titles.clone());
+    if let Err(e) = search_index::index_entries(&app_state.redis_pool, &[entry]).await {
+        warn!("Failed to index anime {} for search: {}", slug, e);
+    }
+
+    Ok(response)
+}
+
+impl AnimeDetailData {
+    /// A parse is only worth caching if it found the page's core content;
+    /// block pages and layout changes parse "successfully" into empty data.
+    pub fn is_valid(&self) -> bool {
+        !self.title.trim().is_empty() && (!self.episode_lists.is_empty() || !self.synopsis.is_empty())
+    }
+}
+
+/// What to do with a detail fetch result.
+#[derive(Debug, PartialEq, Eq)]
+enum CacheDecision {
+    /// Valid data: serve and cache with the normal TTL.
+    Store,
+    /// Upstream 404 or empty parse: serve 404 and negative-cache briefly.
+    Negative,
+    /// Transient failure: serve the error, cache nothing.
+    Skip,
+}
+
+fn detail_cache_decision(result: &Result<AnimeDetailData, AppError>) -> CacheDecision {
+    match result {
+        Ok(data) if data.is_valid() => CacheDecision::Store,
+        Ok(_) => CacheDecision::Negative,
+        Err(e) if e.to_string().contains("status 404") => CacheDecision::Negative,
+        Err(_) => CacheDecision::Skip,
+    }
 }
 
-async fn fetch_anime_detail(
+/// Fetch and parse an otakudesu anime detail page.
+///
+/// Shared by the REST handler and the GraphQL `animeDetail` resolver.
+pub async fn fetch_anime_detail(
     slug: String,
 ) -> Result<AnimeDetailData, Box<dyn std::error::Error + Send + Sync>> {
-    let url = format!("{}/anime/{}", OTAKUDESU_BASE_URL, slug);
+    fetch_anime_detail_reported(slug).await.map(|(data, _)| data)
+}
+
+/// [`fetch_anime_detail`] plus the parse's selector misses. Misses of
+/// [`CRITICAL_SELECTORS`] are logged and counted.
+pub async fn fetch_anime_detail_reported(
+    slug: String,
+) -> Result<(AnimeDetailData, ParseReport), Box<dyn std::error::Error + Send + Sync>> {
+    let url = detail_url(&slug);
 
-    let backoff = default_backoff();
+    let backoff = scrape_backoff();
 
     let fetch_operation = || async {
         info!("Fetching URL: {}", url);
@@ -162,43 +442,49 @@ async fn fetch_anime_detail(
         }
     };
 
-    let html = retry(backoff, fetch_operation)
-        .await
-        .map_err(|e| format!("Failed to fetch HTML with retry: {}", e))?;
+    let html = retry(backoff, fetch_operation).await?;
 
-    match tokio::task::spawn_blocking(move || parse_anime_detail_document(&html)).await
-    {
-        Ok(inner_result) => inner_result.map_err(|e| e.into()),
-        Err(join_err) => Err(Box::new(join_err) as Box<dyn std::error::Error + Send + Sync>),
-    }
+    let (result, report) =
+        tokio::task::spawn_blocking(move || parse_report::collect(|| parse_anime_detail(&html)))
+            .await?;
+    report.warn_critical(SOURCE, &CRITICAL_SELECTORS);
+    Ok((result?, report))
 }
 
-fn parse_anime_detail_document(html: &str) -> Result<AnimeDetailData, AppError> {
+/// Parse an otakudesu anime detail page into `AnimeDetailData`.
+pub fn parse_anime_detail(html: &str) -> Result<AnimeDetailData, AppError> {
     let document = parse_html(html);
     
-    let info_selector = selector(".infozingle p").unwrap();
-    let poster_selector = selector(".fotoanime img").unwrap();
-    let synopsis_selector = selector(".sinopc").unwrap();
+    let root = document.root_element();
+    let info_selector = selector(INFO_SELECTOR).unwrap();
     let genre_link_selector = selector("a").unwrap();
-    let episode_list_selector = selector(".episodelist ul li a").unwrap();
+    let episode_list_selector = selector(EPISODE_LIST_SELECTOR).unwrap();
     let recommendation_selector = selector("#recommend-anime-series .isi-anime").unwrap();
     let recommendation_title_selector = selector(".judul-anime a").unwrap();
     let recommendation_img_selector = selector("img").unwrap();
 
     let mut title = String::new();
     let mut alternative_title = String::new();
+    let mut alternative_fields: Vec<String> = Vec::new();
     let mut r#type: Option<String> = None;
     let mut status: Option<String> = None;
     let mut release_date = String::new();
     let mut studio = String::new();
-    let producers = Vec::new(); // Not present in the original HTML, keeping empty
+    let mut producers = Vec::new();
 
+    parse_report::record(INFO_SELECTOR, document.select(&info_selector).next().is_some());
     for element in document.select(&info_selector) {
         let text = text(&element);
         if text.contains("Judul:") {
             title = text.replace("Judul:", "").trim().to_string();
         } else if text.contains("Japanese:") {
             alternative_title = text.replace("Japanese:", "").trim().to_string();
+            alternative_fields.push(alternative_title.clone());
+        } else if let Some((_, value)) = ["English:", "Sinonim:", "Synonyms:"]
+            .iter()
+            .find_map(|label| text.split_once(label))
+        {
+            alternative_fields.push(value.to_string());
         } else if text.contains("Type:") {
             let type_str = text.replace("Type:", "").trim().to_string();
             if !type_str.is_empty() {
@@ -211,19 +497,15 @@ fn parse_anime_detail_document(html: &str) -> Result<AnimeDetailData, AppError>
             }
         } else if text.contains("Tanggal Rilis:") {
             release_date = text.replace("Tanggal Rilis:", "").trim().to_string();
+        } else if text.contains("Produser:") {
+            producers = split_labeled_list(&text, "Produser:");
         } else if text.contains("Studio:") {
             studio = text.replace("Studio:", "").trim().to_string();
         }
     }
 
-    let poster = document
-        .select(&poster_selector)
-        .next()
-        .and_then(|e| e.value().attr("src"))
-        .unwrap_or("")
-        .to_string();
-
-    let synopsis = text_from_or(&document.root_element(), &synopsis_selector, "");
+    let poster = select_attr(&root, POSTER_SELECTOR, "src").unwrap_or_default();
+    let synopsis = select_text(&root, SYNOPSIS_SELECTOR).unwrap_or_default();
 
     let mut genres = Vec::new();
     if let Some(genres_element) = document
@@ -242,16 +524,18 @@ fn parse_anime_detail_document(html: &str) -> Result<AnimeDetailData, AppError>
         }
     }
 
-    let mut episode_lists = Vec::new();
+    let mut episodes = Vec::new();
+    parse_report::record(
+        EPISODE_LIST_SELECTOR,
+        document.select(&episode_list_selector).next().is_some(),
+    );
     for element in document.select(&episode_list_selector) {
         let episode = text(&element);
         let href = attr(&element, "href").unwrap_or_default();
         let slug = extract_slug(&href);
-        episode_lists.push(EpisodeList { episode, slug });
+        episodes.push(EpisodeList { episode, slug });
     }
-
-    // Batch and producers are not directly parsable from the provided HTML structure
-    // Keeping them empty as per previous implementation for anime/full/slug.rs
+    let (episode_lists, batch) = sort_episodes(episodes);
 
     let mut recommendations = Vec::new();
     for element in document.select(&recommendation_selector) {
@@ -274,9 +558,17 @@ fn parse_anime_detail_document(html: &str) -> Result<AnimeDetailData, AppError>
         }); // Status and type not available from this selector
     }
 
+    let alternative_titles = split_alternative_titles(
+        alternative_fields.iter().map(String::as_str),
+    )
+    .into_iter()
+    .filter(|t| *t != title)
+    .collect();
+
     Ok(AnimeDetailData {
         title,
         alternative_title,
+        alternative_titles,
         poster,
         r#type,
         status,
@@ -285,12 +577,280 @@ fn parse_anime_detail_document(html: &str) -> Result<AnimeDetailData, AppError>
         genres,
         synopsis,
         episode_lists,
-        batch: vec![],
+        batch,
         producers,
         recommendations,
     })
 }
 
+static EPISODE_NUMBER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\b(?:episode|eps?)\.?\s*(\d+(?:[.,]\d+)?)").unwrap());
+
+/// Episode number in a label such as "Frieren Episode 12 Subtitle Indonesia".
+/// Specials and OVAs have none, so they sort after the numbered episodes.
+fn episode_number(label: &str) -> Option<f64> {
+    let lower = label.to_lowercase();
+    if lower
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|w| matches!(w, "special" | "ova" | "oad" | "movie"))
+    {
+        return None;
+    }
+    EPISODE_NUMBER
+        .captures(label)
+        .and_then(|c| c[1].replace(',', ".").parse().ok())
+}
+
+/// Splits batch links off and sorts the rest ascending by episode number,
+/// keeping page order among specials and equal numbers.
+fn sort_episodes(episodes: Vec<EpisodeList>) -> (Vec<EpisodeList>, Vec<EpisodeList>) {
+    let (batch, mut episodes): (Vec<_>, Vec<_>) = episodes
+        .into_iter()
+        .partition(|e| e.episode.to_lowercase().contains("batch"));
+
+    episodes.sort_by(|a, b| match (episode_number(&a.episode), episode_number(&b.episode)) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    (episodes, batch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detail(title: &str, episodes: usize) -> AnimeDetailData {
+        AnimeDetailData {
+            title: title.to_string(),
+            alternative_title: String::new(),
+            alternative_titles: vec![],
+            poster: String::new(),
+            r#type: None,
+            status: None,
+            release_date: String::new(),
+            studio: String::new(),
+            genres: vec![],
+            synopsis: String::new(),
+            episode_lists: (0..episodes)
+                .map(|i| EpisodeList {
+                    episode: format!("Episode {}", i + 1),
+                    slug: format!("ep-{}", i + 1),
+                })
+                .collect(),
+            batch: vec![],
+            producers: vec![],
+            recommendations: vec![],
+        }
+    }
+
+    #[test]
+    fn only_valid_details_are_cached() {
+        assert_eq!(detail_cache_decision(&Ok(detail("Naruto", 3))), CacheDecision::Store);
+        assert_eq!(detail_cache_decision(&Ok(detail("", 0))), CacheDecision::Negative);
+        assert_eq!(detail_cache_decision(&Ok(detail("Naruto", 0))), CacheDecision::Negative);
+        assert_eq!(
+            detail_cache_decision(&Err(AppError::Other(
+                "Direct fetch failed with status 404 Not Found for https://x".to_string()
+            ))),
+            CacheDecision::Negative
+        );
+        assert_eq!(
+            detail_cache_decision(&Err(AppError::Other("connection reset".to_string()))),
+            CacheDecision::Skip
+        );
+    }
+
+    #[test]
+    fn alternative_titles_are_split_and_deduped() {
+        let html = r#"
+            <div class="infozingle">
+                <p><span><b>Judul</b>: Sousou no Frieren</span></p>
+                <p><span><b>Japanese</b>: 葬送のフリーレン</span></p>
+                <p><span><b>English</b>: Frieren: Beyond Journey's End; Frieren</span></p>
+                <p><span><b>Sinonim</b>: Frieren at the Funeral,  葬送のフリーレン , frieren ,Sousou no Frieren</span></p>
+            </div>
+        "#;
+        let data = parse_anime_detail(html).unwrap();
+
+        assert_eq!(data.alternative_title, "葬送のフリーレン");
+        assert_eq!(
+            data.alternative_titles,
+            vec![
+                "葬送のフリーレン",
+                "Frieren: Beyond Journey's End",
+                "Frieren",
+                "Frieren at the Funeral",
+            ]
+        );
+    }
+
+    #[test]
+    fn producers_are_split_from_the_info_block() {
+        let data = parse_anime_detail(include_str!("../../../../scraping/fixtures/anime_detail_frieren.html")).unwrap();
+        assert_eq!(
+            data.producers,
+            ["Aniplex", "Dentsu", "Shogakukan-Shueisha Productions", "TOHO animation"]
+        );
+        assert_eq!(data.studio, "Madhouse");
+
+        let bare = parse_anime_detail(r#"<div class="infozingle"><p><span><b>Judul</b>: Naruto</span></p></div>"#).unwrap();
+        assert!(bare.producers.is_empty());
+    }
+
+    #[test]
+    fn episodes_are_sorted_numerically_with_batch_separated() {
+        let html = r#"
+            <div class="episodelist"><ul>
+                <li><a href="https://otakudesu.best/batch/frieren-batch/">Frieren Batch Episode 1 – 28</a></li>
+            </ul></div>
+            <div class="episodelist"><ul>
+                <li><a href="https://otakudesu.best/episode/frieren-special/">Frieren Special Subtitle Indonesia</a></li>
+                <li><a href="https://otakudesu.best/episode/frieren-episode-10/">Frieren Episode 10 Subtitle Indonesia</a></li>
+                <li><a href="https://otakudesu.best/episode/frieren-episode-2/">Frieren Episode 2 Subtitle Indonesia</a></li>
+                <li><a href="https://otakudesu.best/episode/frieren-episode-1/">Frieren Episode 1 Subtitle Indonesia</a></li>
+            </ul></div>
+        "#;
+        let data = parse_anime_detail(html).unwrap();
+
+        let slugs: Vec<_> = data.episode_lists.iter().map(|e| e.slug.as_str()).collect();
+        assert_eq!(
+            slugs,
+            vec!["frieren-episode-1", "frieren-episode-2", "frieren-episode-10", "frieren-special"]
+        );
+        assert_eq!(data.episode_lists[2].episode, "Frieren Episode 10 Subtitle Indonesia");
+        assert_eq!(data.batch.len(), 1);
+        assert_eq!(data.batch[0].slug, "frieren-batch");
+    }
+
+    #[test]
+    fn debug_query_adds_source_and_fetched_url() {
+        let response = || DetailResponse {
+            status: Some("Ok".to_string()),
+            data: detail("Naruto", 1),
+            source: None,
+            fetched_url: None,
+            selector_misses: None,
+        };
+
+        let debug = DebugQuery { debug: Some("1".to_string()) };
+        let json = serde_json::to_value(response().with_origin(&debug, "naruto-sub-indo")).unwrap();
+        assert_eq!(json["source"], "otakudesu");
+        assert_eq!(
+            json["fetched_url"],
+            format!("{}/anime/naruto-sub-indo", get_otakudesu_url())
+        );
+
+        let json = serde_json::to_value(response().with_origin(&DebugQuery::default(), "naruto-sub-indo")).unwrap();
+        assert!(json.get("source").is_none());
+        assert!(json.get("fetched_url").is_none());
+    }
+
+    #[test]
+    fn redesigned_pages_report_critical_selector_misses() {
+        let page = r#"<div class="infozingle"><p><span><b>Judul</b>: Naruto</span></p></div>
+            <div class="sinopc">A ninja.</div>"#;
+        let (data, report) = parse_report::collect(|| parse_anime_detail(page));
+
+        assert_eq!(data.unwrap().synopsis, "A ninja.");
+        assert_eq!(
+            report.warn_critical(SOURCE, &CRITICAL_SELECTORS),
+            vec![POSTER_SELECTOR, EPISODE_LIST_SELECTOR]
+        );
+        assert!(!report.missed(INFO_SELECTOR) && !report.missed(SYNOPSIS_SELECTOR));
+    }
+
+    async fn serve_upstream() -> String {
+        use axum::routing::any;
+
+        let app = Router::new()
+            .route(
+                "/anime/naruto-sub-indo",
+                any(|| async { ([(header::LAST_MODIFIED, "Sun, 06 Nov 1994 08:49:37 GMT")], "") }),
+            )
+            .route("/anime/cloudflared", any(|| async { StatusCode::METHOD_NOT_ALLOWED }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn head_is_404_for_a_missing_slug() {
+        let base = serve_upstream().await;
+        let client = reqwest::Client::new();
+
+        let probe = probe_upstream(&client, &format!("{}/anime/no-such-anime", base)).await;
+        assert_eq!(probe, Probe::NotFound);
+        let probe = probe_upstream(&client, &format!("{}/anime/cloudflared", base)).await;
+        assert_eq!(probe, Probe::Unknown);
+
+        let response = head_response(Presence::Missing);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(response.headers().get(header::ETAG).is_none());
+    }
+
+    #[tokio::test]
+    async fn head_is_200_with_validators_for_an_existing_slug() {
+        let base = serve_upstream().await;
+        let probe = probe_upstream(&reqwest::Client::new(), &format!("{}/anime/naruto-sub-indo", base)).await;
+        assert_eq!(
+            probe,
+            Probe::Found { last_modified: Some("Sun, 06 Nov 1994 08:49:37 GMT".to_string()) }
+        );
+
+        let cached = DetailResponse {
+            status: Some("Ok".to_string()),
+            data: detail("Naruto", 2),
+            source: None,
+            fetched_url: None,
+            selector_misses: None,
+        };
+        let response = head_response(Presence::cached(&cached, Some("Sun, 06 Nov 1994 08:49:37 GMT".to_string())));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::ETAG], detail_etag(&cached).as_str());
+        assert!(response.headers()[header::ETAG].to_str().unwrap().starts_with("W/\""));
+        assert_eq!(response.headers()[header::LAST_MODIFIED], "Sun, 06 Nov 1994 08:49:37 GMT");
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn detail_route_scrapes_the_mock_upstream() {
+        use crate::testing::TestAppBuilder;
+        use axum::http::Method;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let upstream = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/anime/frieren-sub-indo"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(include_str!("../../../../scraping/fixtures/anime_detail_frieren.html"), "text/html"),
+            )
+            .mount(&upstream)
+            .await;
+
+        // Poster lookups need a database; an empty one makes them all misses.
+        let app = TestAppBuilder::new()
+            .with_mock_upstream(upstream.uri())
+            .with_sqlite_memory()
+            .build_api()
+            .await;
+        let (status, body) = app.request_json(Method::GET, "/api/anime/detail/frieren-sub-indo").await;
+
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        let data = &body["data"];
+        assert_eq!(data["title"], "Sousou no Frieren");
+        assert_eq!(data["studio"], "Madhouse");
+        assert_eq!(data["genres"][1]["slug"], "fantasy");
+        assert_eq!(data["episode_lists"][0]["slug"], "frieren-episode-1-sub-indo");
+        assert_eq!(data["batch"][0]["slug"], "frieren-batch-sub-indo");
+    }
+}
+
 pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
     router
 }
\ No newline at end of file
@@ -0,0 +1,325 @@
+//! `GET /api/anime/export`: a source's whole completed-anime catalog as NDJSON.
+//!
+//! Walks the complete-anime list page by page, at most
+//! `APP__EXPORT_MAX_PAGES` pages with `APP__EXPORT_PAGE_DELAY_MS` between
+//! them, and writes each item as one JSON line as soon as its page is parsed,
+//! so memory use doesn't grow with the catalog. Page 1 is fetched before
+//! answering, so an unreachable source gets a normal error response; a later
+//! failure, or the source's circuit opening mid-run, ends the stream with an
+//! `{"error": ..., "page": n}` line.
+
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::{Body, Bytes};
+use axum::extract::Query;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use futures::{stream, Stream};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use crate::circuit_breaker::{CircuitState, SOURCE_BREAKERS};
+use crate::core::config::CONFIG;
+use crate::core::error::AppError;
+use crate::helpers::{fetch_html_with_retry, internal_err, upstream_err};
+use crate::observability::metrics::source_label;
+use crate::routes::api::anime::complete_anime::slug::build_complete_page;
+use crate::routes::api::anime2::complete_anime::slug::{page_url, parse_anime_page};
+use crate::routes::AppState;
+use crate::scraping::urls::get_otakudesu_url;
+
+pub const NDJSON: &str = "application/x-ndjson";
+
+#[derive(Deserialize, ToSchema)]
+pub struct ExportQuery {
+    /// `1` for the `/api/anime` source (otakudesu), `2` for `/api/anime2`
+    /// (alqanime). Defaults to 1.
+    pub source: Option<u8>,
+}
+
+/// Catalog the export walks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportSource {
+    Otakudesu,
+    Alqanime,
+}
+
+impl ExportSource {
+    pub fn from_param(source: u8) -> Option<Self> {
+        match source {
+            1 => Some(Self::Otakudesu),
+            2 => Some(Self::Alqanime),
+            _ => None,
+        }
+    }
+
+    fn page_url(self, page: u32) -> String {
+        match self {
+            Self::Otakudesu => format!("{}/complete-anime/page/{}/", get_otakudesu_url(), page),
+            Self::Alqanime => page_url(&page.to_string()),
+        }
+    }
+
+    /// Whether the source's circuit is open, i.e. fetching would fail fast.
+    async fn circuit_open(self) -> bool {
+        let source = source_label(&self.page_url(1));
+        SOURCE_BREAKERS.for_source(&source).state().await == CircuitState::Open
+    }
+
+    /// Page `page` of the catalog as NDJSON lines.
+    async fn fetch_page(self, page: u32) -> Result<ExportPage, AppError> {
+        if self.circuit_open().await {
+            return Err(AppError::ServiceUnavailable(format!(
+                "Upstream {} is temporarily unavailable",
+                source_label(&self.page_url(page))
+            )));
+        }
+        match self {
+            Self::Otakudesu => {
+                let list = build_complete_page(page.to_string()).await?;
+                Ok(ExportPage {
+                    lines: ndjson(&list.data)?,
+                    has_next: list.pagination.is_some_and(|p| p.has_next_page),
+                })
+            }
+            Self::Alqanime => {
+                let html = fetch_html_with_retry(&self.page_url(page)).await?;
+                let (items, pagination) =
+                    tokio::task::spawn_blocking(move || parse_anime_page(&html, &page.to_string()))
+                        .await??;
+                Ok(ExportPage {
+                    lines: ndjson(&items)?,
+                    has_next: pagination.has_next_page,
+                })
+            }
+        }
+    }
+}
+
+/// How far and how politely one export walks the catalog.
+#[derive(Debug, Clone, Copy)]
+struct ExportLimits {
+    pub max_pages: u32,
+    pub page_delay: Duration,
+}
+
+impl ExportLimits {
+    pub fn from_config() -> Self {
+        Self {
+            max_pages: CONFIG.export_max_pages.max(1),
+            page_delay: Duration::from_millis(CONFIG.export_page_delay_ms),
+        }
+    }
+}
+
+struct ExportPage {
+    lines: Bytes,
+    has_next: bool,
+}
+
+/// One JSON line per item.
+fn ndjson<T: Serialize>(items: &[T]) -> Result<Bytes, String> {
+    let mut out = Vec::new();
+    for item in items {
+        serde_json::to_writer(&mut out, item).map_err(|e| e.to_string())?;
+        out.push(b'\n');
+    }
+    Ok(Bytes::from(out))
+}
+
+fn error_line(page: u32, message: &str) -> Bytes {
+    let mut line = serde_json::json!({ "error": message, "page": page }).to_string();
+    line.push('\n');
+    Bytes::from(line)
+}
+
+enum Step {
+    /// Emit a fetched page, then move on to the next one.
+    Emit(u32, ExportPage),
+    /// Wait, then fetch this page.
+    Fetch(u32),
+    Done,
+}
+
+/// The rest of the catalog after `first` (page 1), one chunk per page.
+fn catalog_stream(
+    source: ExportSource,
+    first: ExportPage,
+    limits: ExportLimits,
+) -> impl Stream<Item = Result<Bytes, Infallible>> {
+    stream::unfold(Step::Emit(1, first), move |step| async move {
+        let (page, fetched) = match step {
+            Step::Done => return None,
+            Step::Emit(page, fetched) => (page, fetched),
+            Step::Fetch(page) => {
+                tokio::time::sleep(limits.page_delay).await;
+                match source.fetch_page(page).await {
+                    Ok(fetched) => (page, fetched),
+                    Err(e) => {
+                        warn!("Export of {:?} stopped at page {}: {}", source, page, e);
+                        return Some((Ok(error_line(page, &e.to_string())), Step::Done));
+                    }
+                }
+            }
+        };
+        let next = if fetched.has_next && page < limits.max_pages {
+            Step::Fetch(page + 1)
+        } else {
+            info!("Export of {:?} finished after page {}", source, page);
+            Step::Done
+        };
+        Some((Ok(fetched.lines), next))
+    })
+}
+
+#[utoipa::path(
+    get,
+    params(
+        ("source" = Option<u8>, Query, description = "1 = /api/anime source, 2 = /api/anime2 source", example = 1, minimum = 1, maximum = 2)
+    ),
+    path = "/api/anime/export",
+    tag = "anime",
+    operation_id = "anime_export",
+    responses(
+        (status = 200, description = "Every completed anime of the source, one JSON object per line", content_type = "application/x-ndjson", body = String),
+        (status = 400, description = "Unknown source", body = String),
+        (status = 503, description = "The source's circuit is open", body = String),
+        (status = 500, description = "Internal Server Error", body = String)
+    )
+)]
+pub async fn export(
+    Query(params): Query<ExportQuery>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let raw = params.source.unwrap_or(1);
+    let source = ExportSource::from_param(raw).ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Unknown source {}; use 1 or 2", raw),
+        )
+    })?;
+    info!("Starting catalog export for {:?}", source);
+
+    if source.circuit_open().await {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("Upstream for {:?} is temporarily unavailable", source),
+        ));
+    }
+    let first = source.fetch_page(1).await.map_err(|e| upstream_err(&e))?;
+
+    let body = Body::from_stream(catalog_stream(source, first, ExportLimits::from_config()));
+    Response::builder()
+        .header(header::CONTENT_TYPE, NDJSON)
+        .body(body)
+        .map_err(internal_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestAppBuilder;
+    use futures::StreamExt;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// A complete-anime page with two items and, unless it is the last
+    /// page, a next link.
+    fn complete_page(page: u32, last: u32) -> String {
+        let items: String = (1..=2)
+            .map(|i| {
+                format!(
+                    r#"<li><a href="https://otakudesu.test/anime/show-{page}-{i}/">
+                        <div class="thumbz"><img src="https://img.test/{page}-{i}.jpg">
+                        <h2 class="jdlflm">Show {page}-{i}</h2></div></a>
+                        <div class="epz">12 Episode</div></li>"#
+                )
+            })
+            .collect();
+        let next = if page < last {
+            format!(
+                r#"<a class="next page-numbers" href="/complete-anime/page/{}/">Next</a>"#,
+                page + 1
+            )
+        } else {
+            String::new()
+        };
+        format!(
+            r#"<div class="venz"><ul>{items}</ul></div>
+               <div class="pagenavix"><span class="page-numbers current">{page}</span>{next}</div>"#
+        )
+    }
+
+    fn slugs(body: &str) -> Vec<String> {
+        body.lines()
+            .map(|line| {
+                let record: serde_json::Value = serde_json::from_str(line).unwrap();
+                record["slug"].as_str().unwrap().to_string()
+            })
+            .collect()
+    }
+
+    // One test, since the mock upstream override is process-wide.
+    #[tokio::test]
+    async fn export_streams_every_record_and_stops_at_max_pages() {
+        let upstream = MockServer::start().await;
+        for page in 1..=3 {
+            Mock::given(method("GET"))
+                .and(path(format!("/complete-anime/page/{}/", page)))
+                .respond_with(
+                    ResponseTemplate::new(200).set_body_raw(complete_page(page, 3), "text/html"),
+                )
+                .mount(&upstream)
+                .await;
+        }
+        let app = TestAppBuilder::new()
+            .with_mock_upstream(upstream.uri())
+            .build_api()
+            .await;
+
+        let response = app.get("/api/anime/export?source=1").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.header("content-type"), Some(NDJSON));
+        assert_eq!(
+            slugs(&response.text().await),
+            ["show-1-1", "show-1-2", "show-2-1", "show-2-2", "show-3-1", "show-3-2"]
+        );
+
+        let limits = ExportLimits {
+            max_pages: 2,
+            page_delay: Duration::ZERO,
+        };
+        let first = ExportSource::Otakudesu.fetch_page(1).await.unwrap();
+        let chunks: Vec<Bytes> = catalog_stream(ExportSource::Otakudesu, first, limits)
+            .map(|chunk| chunk.unwrap())
+            .collect()
+            .await;
+        let body = String::from_utf8(chunks.concat()).unwrap();
+        assert_eq!(
+            slugs(&body),
+            ["show-1-1", "show-1-2", "show-2-1", "show-2-2"]
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_source_is_rejected() {
+        let app = TestAppBuilder::new().build_api().await;
+
+        let response = app.get("/api/anime/export?source=3").await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn only_sources_one_and_two_exist() {
+        assert_eq!(ExportSource::from_param(1), Some(ExportSource::Otakudesu));
+        assert_eq!(ExportSource::from_param(2), Some(ExportSource::Alqanime));
+        assert_eq!(ExportSource::from_param(3), None);
+    }
+}
+
+pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
+    router
+}
\ No newline at end of file
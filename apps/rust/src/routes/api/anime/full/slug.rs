@@ -1,7 +1,11 @@
-use crate::helpers::{internal_err, Cache, fetch_html_with_retry, parse_html};
+use crate::helpers::{upstream_err, Cache, fetch_html_with_retry, parse_html};
 use crate::helpers::scraping::{selector, text, attr};
+use crate::core::error::AppError;
 use crate::routes::AppState;
-use crate::scraping::urls::OTAKUDESU_BASE_URL;
+use crate::scraping::embed::StreamSource;
+use crate::scraping::link_filter::LINK_HOST_FILTER;
+use crate::scraping::resolver::resolve_direct;
+use crate::scraping::urls::get_otakudesu_url;
 use axum::http::StatusCode;
 use axum::{
     extract::{Path, State},
@@ -40,7 +44,11 @@ pub struct AnimeFullData {
     pub next_episode: Option<EpisodeInfo>,
     pub has_previous_episode: bool,
     pub previous_episode: Option<EpisodeInfo>,
+    /// Embed page of the player (an iframe `src`, not a playable file)
     pub stream_url: String,
+    /// Direct `.m3u8`/`.mp4` links behind `stream_url`; empty for unsupported players
+    #[serde(default)]
+    pub stream_sources: Vec<StreamSource>,
     pub download_urls: std::collections::HashMap<String, Vec<DownloadLink>>,
     pub image_url: String,
 }
@@ -77,36 +85,38 @@ pub async fn slug(
     let cache = Cache::new(&app_state.redis_pool);
 
     let response = cache
-        .get_or_set(&cache_key, CACHE_TTL, || async {
+        .get_or_fetch(&cache_key, CACHE_TTL, || async {
             let data = fetch_anime_full(slug.clone())
-                .await
-                .map_err(|e| e.to_string())?;
+                .await?;
             Ok(FullResponse {
                 status: "Ok".to_string(),
                 data,
             })
         })
         .await
-        .map_err(|e| internal_err(&e))?;
+        .map_err(|e| upstream_err(&e))?;
 
     return Ok(Json(response).into_response());
 }
 
-async fn fetch_anime_full(slug: String) -> Result<AnimeFullData, String> {
-    let url = format!("{}/episode/{}", OTAKUDESU_BASE_URL, slug);
+async fn fetch_anime_full(slug: String) -> Result<AnimeFullData, AppError> {
+    let url = format!("{}/episode/{}", get_otakudesu_url(), slug);
 
-    let html = fetch_html_with_retry(&url)
-        .await
-        .map_err(|e| format!("Failed to fetch HTML with retry: {}", e))?;
+    let html = fetch_html_with_retry(&url).await?;
 
-    match tokio::task::spawn_blocking(move || {
+    let mut data = match tokio::task::spawn_blocking(move || {
         parse_anime_full_document(&html, &slug)
     })
     .await
     {
-        Ok(inner_result) => inner_result.map_err(|e| e.to_string()),
-        Err(join_err) => Err(format!("Failed to spawn blocking task: {}", join_err)),
-    }
+        Ok(inner_result) => inner_result?,
+        Err(join_err) => {
+            return Err(AppError::Other(format!("Failed to spawn blocking task: {}", join_err)))
+        }
+    };
+
+    data.stream_sources = resolve_direct(&data.stream_url).await;
+    Ok(data)
 }
 
 fn parse_anime_full_document(
@@ -161,6 +171,9 @@ fn parse_anime_full_document(
         for link_element in element.select(&link_selector) {
             let server = text(&link_element);
             let url = attr(&link_element, "href").unwrap_or_default();
+            if !LINK_HOST_FILTER.permits(&url) {
+                continue;
+            }
             links.push(DownloadLink { server, url });
         }
 
@@ -200,6 +213,7 @@ fn parse_anime_full_document(
         has_previous_episode: previous_episode_slug.is_some(),
         previous_episode: previous_episode_slug.map(|s| EpisodeInfo { slug: s }),
         stream_url,
+        stream_sources: Vec::new(),
         download_urls,
         image_url,
     })
@@ -1,8 +1,9 @@
 use crate::helpers::{
-    internal_err, Cache, fetch_html_with_retry, text_from_or, attr_from_or, extract_slug,
+    upstream_err, Cache, fetch_html_with_retry, text_from_or, attr_from_or, extract_slug,
     parse_html, selector
 };
 use crate::routes::AppState;
+use crate::scraping::pagination::extract_pagination;
 use crate::scraping::urls::get_otakudesu_url;
 use axum::extract::{Query, State};
 use axum::http::StatusCode;
@@ -23,15 +24,7 @@ pub struct AnimeItem {
     pub anime_url: String,
 }
 
-#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
-pub struct Pagination {
-    pub current_page: u32,
-    pub last_visible_page: u32,
-    pub has_next_page: bool,
-    pub next_page: Option<u32>,
-    pub has_previous_page: bool,
-    pub previous_page: Option<u32>,
-}
+pub use crate::helpers::pagination::Pagination;
 
 #[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
 pub struct GenreAnimeResponse {
@@ -67,17 +60,45 @@ pub async fn slug(
     Path(genre_slug): Path<String>,
     Query(params): Query<GenreQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let page = params.page.unwrap_or(1);
+    genre_page(&app_state, genre_slug, params.page.unwrap_or(1)).await
+}
+
+#[utoipa::path(
+    get,
+    params(
+        ("slug" = String, Path, description = "Genre slug from `/api/anime/genres`", example = "action"),
+        ("page" = u32, Path, description = "Page number (starts from 1)", example = 2, minimum = 1)
+    ),
+    path = "/api/anime/genre/{slug}/{page}",
+    tag = "anime",
+    operation_id = "anime_genre_page",
+    responses(
+        (status = 200, description = "One page of anime in a genre", body = GenreAnimeResponse),
+        (status = 500, description = "Internal Server Error", body = String)
+    )
+)]
+pub async fn page(
+    State(app_state): State<Arc<AppState>>,
+    Path((genre_slug, page)): Path<(String, u32)>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    genre_page(&app_state, genre_slug, page).await
+}
+
+async fn genre_page(
+    app_state: &AppState,
+    genre_slug: String,
+    page: u32,
+) -> Result<axum::response::Response, (StatusCode, String)> {
+    let page = page.max(1);
     info!("Handling request for genre: {}, page: {}", genre_slug, page);
 
     let cache_key = format!("anime:genre:{}:{}", genre_slug, page);
     let cache = Cache::new(&app_state.redis_pool);
 
     let response = cache
-        .get_or_set(&cache_key, CACHE_TTL, || async {
+        .get_or_fetch(&cache_key, CACHE_TTL, || async {
             let (anime_list, pagination) = fetch_genre_anime(&genre_slug, page)
-                .await
-                .map_err(|e| e.to_string())?;
+                .await?;
 
             Ok(GenreAnimeResponse {
                 status: "Ok".to_string(),
@@ -87,7 +108,7 @@ pub async fn slug(
             })
         })
         .await
-        .map_err(|e| internal_err(&e))?;
+        .map_err(|e| upstream_err(&e))?;
 
     Ok(Json(response).into_response())
 }
@@ -107,7 +128,7 @@ async fn fetch_genre_anime(
         )
     };
 
-    let html = fetch_html_with_retry(&url).await.map_err(|e| format!("Failed to fetch HTML: {}", e))?;
+    let html = fetch_html_with_retry(&url).await?;
 
     let (anime_list, pagination) =
         tokio::task::spawn_blocking(move || parse_genre_page(&html, page)).await??;
@@ -122,23 +143,23 @@ fn parse_genre_page(
     let document = parse_html(html);
     let mut anime_list = Vec::new();
 
-    let venz_selector = selector(".venz ul li").unwrap();
-    let title_selector = selector(".thumbz h2.jdlflm").unwrap();
+    // `.listupd .bs` cards, with the older `.venz` list as a fallback.
+    let item_selector = selector(".listupd .bs, .venz ul li").unwrap();
+    let title_selector = selector(".tt h2, .thumbz h2.jdlflm").unwrap();
     let img_selector = selector("img").unwrap();
-    let ep_selector = selector(".epz").unwrap();
+    let score_selector = selector(".numscore, .epz").unwrap();
+    let status_selector = selector(".status, .epx").unwrap();
     let link_selector = selector("a").unwrap();
-    let pagination_selector = selector(".pagination .page-numbers:not(.next)").unwrap();
-    let next_selector = selector(".pagination .next").unwrap();
-    
-    for element in document.select(&venz_selector) {
+
+    for element in document.select(&item_selector) {
         let title = text_from_or(&element, &title_selector, "");
         let poster = attr_from_or(&element, &img_selector, "src", "");
-        let score = text_from_or(&element, &ep_selector, "N/A");
+        let score = text_from_or(&element, &score_selector, "N/A");
         let anime_url = attr_from_or(&element, &link_selector, "href", "");
         let slug = extract_slug(&anime_url);
 
-        // Try to determine status from score text
-        let status_text = score.to_lowercase();
+        let status_text = format!("{} {}", text_from_or(&element, &status_selector, ""), score)
+            .to_lowercase();
         let status = if status_text.contains("ongoing") {
             "Ongoing".to_string()
         } else if status_text.contains("completed") || status_text.contains("eps") {
@@ -159,45 +180,53 @@ fn parse_genre_page(
         }
     }
 
-    let last_visible_page = document
-        .select(&pagination_selector)
-        .next_back()
-        .map(|e| {
-            e.text()
-                .collect::<String>()
-                .trim()
-                .parse::<u32>()
-                .unwrap_or(1)
-        })
-        .unwrap_or(1);
-
-    let has_next_page = document.select(&next_selector).next().is_some();
-    let next_page = if has_next_page {
-        Some(current_page + 1)
-    } else {
-        None
-    };
-
-    let has_previous_page = current_page > 1;
-    let previous_page = if has_previous_page {
-        Some(current_page - 1)
-    } else {
-        None
-    };
-
-    let pagination = Pagination {
-        current_page,
-        last_visible_page,
-        has_next_page,
-        next_page,
-        has_previous_page,
-        previous_page,
-    };
+    let pagination = extract_pagination(&document, current_page, "otakudesu");
 
     info!("Parsed {} anime items", anime_list.len());
     Ok((anime_list, pagination))
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn genre_page_parses_cards_and_pagination() {
+        let html = r#"
+            <div class="listupd">
+                <article class="bs"><a href="https://otakudesu.best/anime/frieren-sub-indo/">
+                    <img src="https://img.example.com/frieren.jpg">
+                    <div class="tt"><h2>Sousou no Frieren</h2></div>
+                    <span class="status">Completed</span><span class="numscore">9.1</span>
+                </a></article>
+                <article class="bs"><a href="https://otakudesu.best/anime/one-piece-sub-indo/">
+                    <img src="https://img.example.com/op.jpg">
+                    <div class="tt"><h2>One Piece</h2></div>
+                    <span class="status">Ongoing</span><span class="numscore">8.7</span>
+                </a></article>
+            </div>
+            <div class="pagination">
+                <a class="page-numbers" href="/genres/action/page/1/">1</a>
+                <span class="page-numbers current">2</span>
+                <a class="page-numbers" href="/genres/action/page/3/">3</a>
+                <a class="page-numbers" href="/genres/action/page/12/">12</a>
+                <a class="next page-numbers" href="/genres/action/page/3/">Next</a>
+            </div>
+        "#;
+
+        let (items, pagination) = parse_genre_page(html, 2).unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].title, "Sousou no Frieren");
+        assert_eq!(items[0].slug, "frieren-sub-indo");
+        assert_eq!(items[0].poster, "https://img.example.com/frieren.jpg");
+        assert_eq!(items[0].score, "9.1");
+        assert_eq!(items[0].status, "Completed");
+        assert_eq!(items[1].status, "Ongoing");
+        assert_eq!(pagination, Pagination::from_page(2, 12, true));
+    }
+}
+
 pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
     router
 }
\ No newline at end of file
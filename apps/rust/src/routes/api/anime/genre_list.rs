@@ -1,4 +1,4 @@
-use crate::helpers::{internal_err, Cache, fetch_html_with_retry, parse_html};
+use crate::helpers::{upstream_err, Cache, fetch_html_with_retry, parse_html};
 use crate::helpers::scraping::{selector, extract_slug, text, attr};
 use crate::routes::AppState;
 use crate::scraping::urls::get_otakudesu_url;
@@ -45,8 +45,8 @@ pub async fn genres(
     let cache = Cache::new(&app_state.redis_pool);
 
     let response = cache
-        .get_or_set(cache_key, CACHE_TTL, || async {
-            let genres = fetch_genres().await.map_err(|e| e.to_string())?;
+        .get_or_fetch(cache_key, CACHE_TTL, || async {
+            let genres = fetch_genres().await?;
 
             Ok(GenresResponse {
                 status: "Ok".to_string(),
@@ -54,7 +54,7 @@ pub async fn genres(
             })
         })
         .await
-        .map_err(|e| internal_err(&e))?;
+        .map_err(|e| upstream_err(&e))?;
 
     Ok(Json(response).into_response())
 }
@@ -62,7 +62,7 @@ pub async fn genres(
 async fn fetch_genres() -> Result<Vec<Genre>, Box<dyn std::error::Error + Send + Sync>> {
     let url = format!("{}/genre-list/", get_otakudesu_url());
 
-    let html = fetch_html_with_retry(&url).await.map_err(|e| format!("Failed to fetch HTML: {}", e))?;
+    let html = fetch_html_with_retry(&url).await?;
 
     let genres = tokio::task::spawn_blocking(move || parse_genres(&html)).await??;
 
@@ -88,6 +88,27 @@ fn parse_genres(html: &str) -> Result<Vec<Genre>, Box<dyn std::error::Error + Se
     Ok(genres)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn genre_index_parses_names_and_slugs() {
+        let html = r##"
+            <ul class="genres"><li>
+                <a href="https://otakudesu.best/genres/action/">Action</a>
+                <a href="https://otakudesu.best/genres/slice-of-life/">Slice of Life</a>
+                <a href="#"></a>
+            </li></ul>
+        "##;
+
+        let genres = parse_genres(html).unwrap();
+
+        let pairs: Vec<_> = genres.iter().map(|g| (g.name.as_str(), g.slug.as_str())).collect();
+        assert_eq!(pairs, vec![("Action", "action"), ("Slice of Life", "slice-of-life")]);
+    }
+}
+
 pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
     router
 }
\ No newline at end of file
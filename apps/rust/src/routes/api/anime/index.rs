@@ -1,134 +1,122 @@
 use crate::core::types::ApiResponse;
-use crate::helpers::{parse_html, Cache, fetch_html_with_retry, text_from_or, attr_from_or, selector, extract_slug, attr_from};
+use crate::helpers::{parse_html, Cache, fetch_html_pair, text_from_or, attr_from_or, selector, extract_slug, attr_from};
+use crate::helpers::conditional::conditional_json;
 
 use crate::routes::AppState;
 use crate::core::error::AppError;
 use crate::scraping::urls::get_otakudesu_url;
 use axum::extract::State;
-use axum::{response::IntoResponse, Json, Router};
+use axum::http::HeaderMap;
+use axum::{response::Response, Router};
 
-use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{info};
-use utoipa::ToSchema;
 
 
-#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
-pub struct OngoingAnimeItem {
-    pub title: String,
-    pub slug: String,
-    pub poster: String,
-    pub current_episode: String,
-    pub anime_url: String,
-}
+pub use api_models::{CompleteAnimeItem, HomeData, OngoingAnimeItem};
 
-#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
-pub struct CompleteAnimeItem {
-    pub title: String,
-    pub slug: String,
-    pub poster: String,
-    pub episode_count: String,
-    pub anime_url: String,
-}
-
-#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
-pub struct AnimeData {
-    pub ongoing_anime: Vec<OngoingAnimeItem>,
-    pub complete_anime: Vec<CompleteAnimeItem>,
-}
+/// `data` of the index response; the client deserializes the same type.
+pub type AnimeData = HomeData;
 
 pub type AnimeDataResponse = ApiResponse<AnimeData>;
 pub type EmptyResponse = ApiResponse<()>;
 
 use crate::helpers::cache_ttl::CACHE_TTL_VERY_SHORT;
 const CACHE_TTL: u64 = CACHE_TTL_VERY_SHORT; // 5 minutes
+/// `Cache-Control: max-age` of the response; pollers revalidate after this.
+const CLIENT_MAX_AGE: u64 = 60;
 
 #[utoipa::path(
     get,
     path = "/api/anime",
     tag = "anime",
     operation_id = "anime_index",
+    params(
+        ("If-None-Match" = Option<String>, Header, description = "ETag of a previous response")
+    ),
     responses(
         (status = 200, description = "Handles GET requests for the anime endpoint.", body = AnimeDataResponse),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag"),
         (status = 500, description = "Internal Server Error", body = String)
     )
 )]
 pub async fn anime(
     State(app_state): State<Arc<AppState>>,
-) -> Result<impl IntoResponse, AppError> {
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
     let start_time = std::time::Instant::now();
     info!("Handling request for anime index");
 
     let cache = Cache::new(&app_state.redis_pool);
 
-    // Clean caching with get_or_set pattern
     let response = cache
-        .get_or_set("anime:index", CACHE_TTL, || async {
-            let mut data = fetch_anime_data()
-                .await
-                .map_err(|e| format!("Fetch error: {}", e))?;
-
-            // Convert all poster URLs to CDN URLs
-            // Fire-and-forget background caching for posters to ensure max API speed
-            let db = app_state.db.clone();
-            let redis = app_state.redis_pool.clone();
-
-            let ongoing_posters: Vec<String> = data
-                .ongoing_anime
-                .iter()
-                .map(|i| i.poster.clone())
-                .collect();
-            let complete_posters: Vec<String> = data
-                .complete_anime
-                .iter()
-                .map(|i| i.poster.clone())
-                .collect();
-
-            let ongoing_len = ongoing_posters.len();
-
-            let all_posters = [ongoing_posters, complete_posters].concat();
-            let cached_posters = crate::services::images::cache::cache_image_urls_batch_lazy(
-                db.clone(),
-                &redis,
-                all_posters,
-                Some(app_state.image_processing_semaphore.clone()),
-            )
-            .await;
-
-            // Update ongoing anime posters
-            for (i, item) in data.ongoing_anime.iter_mut().enumerate() {
-                if let Some(url) = cached_posters.get(i) {
-                    item.poster = url.clone();
-                }
-            }
-
-            // Update complete anime posters
-            for (i, item) in data.complete_anime.iter_mut().enumerate() {
-                if let Some(url) = cached_posters.get(ongoing_len + i) {
-                    item.poster = url.clone();
-                }
-            }
-
-            Ok(ApiResponse::success(data))
-        })
+        .get_or_set_tagged(INDEX_CACHE_KEY, CACHE_TTL, || build_anime_index(&app_state))
         .await
         .map_err(|e| AppError::Other(e.to_string()))?;
 
     info!("Anime index completed in {:?}", start_time.elapsed());
-    Ok(Json(response))
+    Ok(conditional_json(&headers, &response.etag, CLIENT_MAX_AGE, &response.value))
+}
+
+/// Cache key of the `/api/anime` response, stored as a
+/// [`Tagged`](crate::helpers::Tagged) entry.
+pub const INDEX_CACHE_KEY: &str = "anime:index";
+
+/// Fetches the ongoing/complete lists with poster URLs rewritten to the CDN.
+pub async fn build_anime_index(app_state: &AppState) -> Result<AnimeDataResponse, String> {
+    let mut data = fetch_anime_data()
+        .await
+        .map_err(|e| format!("Fetch error: {}", e))?;
+
+    // Convert all poster URLs to CDN URLs
+    // Fire-and-forget background caching for posters to ensure max API speed
+    let db = app_state.db.clone();
+    let redis = app_state.redis_pool.clone();
+
+    let ongoing_posters: Vec<String> = data
+        .ongoing_anime
+        .iter()
+        .map(|i| i.poster.clone())
+        .collect();
+    let complete_posters: Vec<String> = data
+        .complete_anime
+        .iter()
+        .map(|i| i.poster.clone())
+        .collect();
+
+    let ongoing_len = ongoing_posters.len();
+
+    let all_posters = [ongoing_posters, complete_posters].concat();
+    let cached_posters = crate::services::images::cache::cache_image_urls_batch_lazy(
+        db.clone(),
+        &redis,
+        all_posters,
+        Some(app_state.image_processing_semaphore.clone()),
+    )
+    .await;
+
+    // Update ongoing anime posters
+    for (i, item) in data.ongoing_anime.iter_mut().enumerate() {
+        if let Some(url) = cached_posters.get(i) {
+            item.poster = url.clone();
+        }
+    }
+
+    // Update complete anime posters
+    for (i, item) in data.complete_anime.iter_mut().enumerate() {
+        if let Some(url) = cached_posters.get(ongoing_len + i) {
+            item.poster = url.clone();
+        }
+    }
+
+    Ok(ApiResponse::success(data))
 }
 
 async fn fetch_anime_data() -> Result<AnimeData, Box<dyn std::error::Error + Send + Sync>> {
     let ongoing_url = format!("{}/ongoing-anime/", get_otakudesu_url());
     let complete_url = format!("{}/complete-anime/", get_otakudesu_url());
 
-    let (ongoing_html, complete_html) = tokio::join!(
-        fetch_html_with_retry(&ongoing_url),
-        fetch_html_with_retry(&complete_url)
-    );
-
-    let ongoing_html = ongoing_html?;
-    let complete_html = complete_html?;
+    let (ongoing_html, complete_html) = fetch_html_pair(&ongoing_url, &complete_url).await?;
 
     let ongoing_anime =
         tokio::task::spawn_blocking(move || parse_ongoing_anime(&ongoing_html)).await??;
@@ -270,6 +258,27 @@ mod tests {
         assert_eq!(result[0].episode_count, "500 Episodes");
         assert_eq!(result[0].poster, "https://example.com/naruto.jpg");
     }
+
+    #[test]
+    fn response_deserializes_into_the_shared_home_data() {
+        let ongoing = parse_ongoing_anime(
+            r#"<div class="venz"><ul><li><div class="thumbz"><h2 class="jdlflm">One Piece</h2></div>
+            <div class="epz">Episode 1000</div><a href="https://otakudesu.cloud/anime/one-piece-slug/"></a>
+            <img src="https://example.com/op.jpg" /></li></ul></div>"#,
+        )
+        .unwrap();
+        let response: AnimeDataResponse = ApiResponse::success(AnimeData {
+            ongoing_anime: ongoing,
+            complete_anime: Vec::new(),
+        });
+
+        // What the Leptos client does with the body of `GET /api/anime`.
+        let body = serde_json::to_string(&response).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let client: api_models::HomeData = serde_json::from_value(json["data"].clone()).unwrap();
+
+        assert_eq!(Some(client), response.data);
+    }
 }
 
 pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
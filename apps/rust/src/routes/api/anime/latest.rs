@@ -1,5 +1,6 @@
-use crate::helpers::{internal_err, Cache, fetch_html_with_retry, text_from_or, attr_from_or};
+use crate::helpers::{upstream_err, Cache, fetch_html_with_retry, text_from_or, attr_from_or};
 use crate::routes::AppState;
+use crate::scraping::pagination::extract_pagination;
 use crate::scraping::urls::get_otakudesu_url;
 use axum::extract::{Query, State};
 use axum::http::StatusCode;
@@ -21,15 +22,7 @@ pub struct LatestAnimeItem {
     pub anime_url: String,
 }
 
-#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
-pub struct Pagination {
-    pub current_page: u32,
-    pub last_visible_page: u32,
-    pub has_next_page: bool,
-    pub next_page: Option<u32>,
-    pub has_previous_page: bool,
-    pub previous_page: Option<u32>,
-}
+pub use crate::helpers::pagination::Pagination;
 
 #[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
 pub struct LatestAnimeResponse {
@@ -67,9 +60,9 @@ pub async fn latest(
     let cache = Cache::new(&app_state.redis_pool);
 
     let response = cache
-        .get_or_set(&cache_key, CACHE_TTL, || async {
+        .get_or_fetch(&cache_key, CACHE_TTL, || async {
             let (mut anime_list, pagination) =
-                fetch_latest_anime(page).await.map_err(|e| e.to_string())?;
+                fetch_latest_anime(page).await?;
 
             // Convert all poster URLs to CDN URLs
             // Fire-and-forget background caching for posters to ensure max API speed
@@ -101,7 +94,7 @@ pub async fn latest(
             })
         })
         .await
-        .map_err(|e| internal_err(&e))?;
+        .map_err(|e| upstream_err(&e))?;
 
     Ok(Json(response).into_response())
 }
@@ -115,9 +108,7 @@ async fn fetch_latest_anime(
         format!("{}/ongoing-anime/page/{}/", get_otakudesu_url(), page)
     };
 
-    let html = fetch_html_with_retry(&url)
-        .await
-        .map_err(|e| format!("Failed to fetch HTML: {}", e))?;
+    let html = fetch_html_with_retry(&url).await?;
 
     let (anime_list, pagination) =
         tokio::task::spawn_blocking(move || parse_latest_page(&html, page)).await??;
@@ -137,9 +128,6 @@ fn parse_latest_page(
     let img_selector = crate::helpers::scraping::selector("img").unwrap();
     let ep_selector = crate::helpers::scraping::selector(".epz").unwrap();
     let link_selector = crate::helpers::scraping::selector("a").unwrap();
-    let pagination_selector =
-        crate::helpers::scraping::selector(".pagination .page-numbers:not(.next)").unwrap();
-    let next_selector = crate::helpers::scraping::selector(".pagination .next").unwrap();
     
     // We can use compile_regex from helpers if available, or just use the Lazy one from scraping.rs 
     // But since SLUG_REGEX is already defined in scraping.rs, we can use extract_slug but need to be careful
@@ -168,40 +156,7 @@ fn parse_latest_page(
         }
     }
 
-    let last_visible_page = document
-        .select(&pagination_selector)
-        .next_back()
-        .map(|e| {
-            e.text()
-                .collect::<String>()
-                .trim()
-                .parse::<u32>()
-                .unwrap_or(1)
-        })
-        .unwrap_or(1);
-
-    let has_next_page = document.select(&next_selector).next().is_some();
-    let next_page = if has_next_page {
-        Some(current_page + 1)
-    } else {
-        None
-    };
-
-    let has_previous_page = current_page > 1;
-    let previous_page = if has_previous_page {
-        Some(current_page - 1)
-    } else {
-        None
-    };
-
-    let pagination = Pagination {
-        current_page,
-        last_visible_page,
-        has_next_page,
-        next_page,
-        has_previous_page,
-        previous_page,
-    };
+    let pagination = extract_pagination(&document, current_page, "otakudesu");
 
     info!("Parsed {} latest anime items", anime_list.len());
     Ok((anime_list, pagination))
@@ -3,12 +3,14 @@
 
 pub mod complete_anime;
 pub mod detail;
+pub mod export;
 pub mod full;
 pub mod genre;
 pub mod genre_list;
 pub mod index;
 pub mod latest;
 pub mod ongoing_anime;
+pub mod schedule;
 pub mod search;
 
 /// Register routes for this directory
@@ -17,5 +19,5 @@ use std::sync::Arc;
 use crate::routes::AppState;
 
 pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
-    complete_anime::register_routes(detail::register_routes(full::register_routes(genre::register_routes(genre_list::register_routes(index::register_routes(latest::register_routes(ongoing_anime::register_routes(search::register_routes(router)))))))))
+    complete_anime::register_routes(detail::register_routes(export::register_routes(full::register_routes(genre::register_routes(genre_list::register_routes(index::register_routes(latest::register_routes(ongoing_anime::register_routes(schedule::register_routes(search::register_routes(router)))))))))))
 }
@@ -3,11 +3,13 @@ use std::sync::Arc;
 
 // External crate imports
 use crate::helpers::{
-    internal_err, Cache, fetch_html_with_retry, text_from_or, attr_from_or, extract_slug,
+    upstream_err, Cache, fetch_html_with_retry, text_from_or, attr_from_or, extract_slug,
     parse_html, selector
 };
+use crate::core::error::AppError;
 use crate::routes::AppState;
-use crate::scraping::urls::OTAKUDESU_BASE_URL;
+use crate::scraping::pagination::extract_pagination;
+use crate::scraping::urls::get_otakudesu_url;
 use axum::{
     extract::{Path, State},
     http::StatusCode,
@@ -28,15 +30,7 @@ pub struct OngoingAnimeItem {
     pub anime_url: String,
 }
 
-#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
-pub struct Pagination {
-    pub current_page: u32,
-    pub last_visible_page: u32,
-    pub has_next_page: bool,
-    pub next_page: Option<u32>,
-    pub has_previous_page: bool,
-    pub previous_page: Option<u32>,
-}
+pub use crate::helpers::pagination::Pagination;
 
 #[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
 pub struct OngoingAnimeResponse {
@@ -67,32 +61,39 @@ pub async fn slug(
     let _start = std::time::Instant::now();
     info!("Starting request for ongoing_anime slug: {}", slug);
 
-    let cache_key = format!("anime:ongoing:{}", slug);
+    let cache_key = page_cache_key(&slug);
     let cache = Cache::new(&app_state.redis_pool);
 
     let response = cache
-        .get_or_set(&cache_key, CACHE_TTL, || async {
-            let (anime_list, pagination) = fetch_ongoing_anime_page(slug.clone())
-                .await
-                .map_err(|e| e.to_string())?;
-            Ok(OngoingAnimeResponse {
-                status: "Ok".to_string(),
-                data: anime_list,
-                pagination,
-            })
-        })
+        .get_or_fetch(&cache_key, CACHE_TTL, || build_ongoing_page(slug))
         .await
-        .map_err(|e| internal_err(&e))?;
+        .map_err(|e| upstream_err(&e))?;
 
     return Ok(Json(response).into_response());
 }
 
+/// Cache key for page `slug` of the ongoing anime list.
+pub fn page_cache_key(slug: &str) -> String {
+    format!("anime:ongoing:{}", slug)
+}
+
+/// Fetches and parses page `slug` of the ongoing anime list.
+pub async fn build_ongoing_page(slug: String) -> Result<OngoingAnimeResponse, AppError> {
+    let (anime_list, pagination) = fetch_ongoing_anime_page(slug)
+        .await?;
+    Ok(OngoingAnimeResponse {
+        status: "Ok".to_string(),
+        data: anime_list,
+        pagination,
+    })
+}
+
 async fn fetch_ongoing_anime_page(
     slug: String,
 ) -> Result<(Vec<OngoingAnimeItem>, Pagination), Box<dyn std::error::Error + Send + Sync>> {
-    let url = format!("{}/ongoing-anime/page/{}/", OTAKUDESU_BASE_URL, slug);
+    let url = format!("{}/ongoing-anime/page/{}/", get_otakudesu_url(), slug);
 
-    let html = fetch_html_with_retry(&url).await.map_err(|e| format!("Failed to fetch HTML: {}", e))?;
+    let html = fetch_html_with_retry(&url).await?;
     let slug_clone = slug.clone();
 
     match tokio::task::spawn_blocking(move || {
@@ -123,8 +124,6 @@ fn parse_ongoing_anime_document(
     let img_selector = selector("img").unwrap();
     let ep_selector = selector(".epz").unwrap();
     let link_selector = selector("a").unwrap();
-    let pagination_selector = selector(".pagination .page-numbers:not(.next)").unwrap();
-    let next_selector = selector(".pagination .next").unwrap();
     
     for element in document.select(&venz_selector) {
         let title = text_from_or(&element, &title_selector, "");
@@ -148,41 +147,7 @@ fn parse_ongoing_anime_document(
 
     let current_page = slug.parse::<u32>().unwrap_or(1);
 
-    let last_visible_page = document
-        .select(&pagination_selector)
-        .next_back()
-        .map(|e| {
-            e.text()
-                .collect::<String>()
-                .trim()
-                .parse::<u32>()
-                .unwrap_or(1)
-        })
-        .unwrap_or(1);
-
-    let has_next_page = document.select(&next_selector).next().is_some();
-
-    let next_page = if has_next_page {
-        Some(current_page + 1)
-    } else {
-        None
-    };
-
-    let has_previous_page = current_page > 1;
-    let previous_page = if has_previous_page {
-        Some(current_page - 1)
-    } else {
-        None
-    };
-
-    let pagination = Pagination {
-        current_page,
-        last_visible_page,
-        has_next_page,
-        next_page,
-        has_previous_page,
-        previous_page,
-    };
+    let pagination = extract_pagination(&document, current_page, "otakudesu");
 
     let duration = start_time.elapsed();
     info!(
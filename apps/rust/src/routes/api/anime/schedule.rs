@@ -0,0 +1,245 @@
+//! Weekly release schedule from otakudesu's `jadwal-rilis` page.
+
+use crate::helpers::cache_ttl::CACHE_TTL_LONG;
+use crate::helpers::scraping::{attr, extract_slug, selector, text};
+use crate::helpers::{fetch_html_with_retry, upstream_err, parse_html, Cache};
+use crate::routes::AppState;
+use crate::scraping::urls::get_otakudesu_url;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::{response::IntoResponse, Json, Router};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::info;
+use utoipa::ToSchema;
+
+pub const ENDPOINT_METHOD: &str = "get";
+pub const ENDPOINT_PATH: &str = "/api/anime/schedule";
+pub const ENDPOINT_DESCRIPTION: &str = "Weekly anime release schedule, grouped by weekday";
+pub const ENDPOINT_TAG: &str = "anime";
+pub const OPERATION_ID: &str = "anime_schedule";
+pub const SUCCESS_RESPONSE_BODY: &str = "Json<ScheduleResponse>";
+
+/// The schedule changes when a season starts or a show goes on break.
+const CACHE_TTL: u64 = CACHE_TTL_LONG;
+const CACHE_KEY: &str = "anime:schedule";
+
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleItem {
+    pub title: String,
+    pub slug: String,
+    /// Air time as `HH:MM` (WIB), when the page lists one.
+    pub time: Option<String>,
+}
+
+/// Shows airing on each weekday.
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, Default, PartialEq, Eq)]
+pub struct WeeklySchedule {
+    pub monday: Vec<ScheduleItem>,
+    pub tuesday: Vec<ScheduleItem>,
+    pub wednesday: Vec<ScheduleItem>,
+    pub thursday: Vec<ScheduleItem>,
+    pub friday: Vec<ScheduleItem>,
+    pub saturday: Vec<ScheduleItem>,
+    pub sunday: Vec<ScheduleItem>,
+    /// Shows without a fixed day.
+    pub random: Vec<ScheduleItem>,
+}
+
+impl WeeklySchedule {
+    /// The list for an (Indonesian or English) day heading, if it names one.
+    fn day_mut(&mut self, heading: &str) -> Option<&mut Vec<ScheduleItem>> {
+        match heading.trim().to_lowercase().as_str() {
+            "senin" | "monday" => Some(&mut self.monday),
+            "selasa" | "tuesday" => Some(&mut self.tuesday),
+            "rabu" | "wednesday" => Some(&mut self.wednesday),
+            "kamis" | "thursday" => Some(&mut self.thursday),
+            "jumat" | "jum'at" | "friday" => Some(&mut self.friday),
+            "sabtu" | "saturday" => Some(&mut self.saturday),
+            "minggu" | "sunday" => Some(&mut self.sunday),
+            "random" | "acak" => Some(&mut self.random),
+            _ => None,
+        }
+    }
+
+    fn count(&self) -> usize {
+        [
+            &self.monday,
+            &self.tuesday,
+            &self.wednesday,
+            &self.thursday,
+            &self.friday,
+            &self.saturday,
+            &self.sunday,
+            &self.random,
+        ]
+        .iter()
+        .map(|day| day.len())
+        .sum()
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+pub struct ScheduleResponse {
+    pub status: String,
+    pub data: WeeklySchedule,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/anime/schedule",
+    tag = "anime",
+    operation_id = "anime_schedule",
+    responses(
+        (status = 200, description = "Weekly anime release schedule, grouped by weekday", body = ScheduleResponse),
+        (status = 500, description = "Internal Server Error", body = String)
+    )
+)]
+pub async fn schedule(
+    State(app_state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    info!("Handling request for anime schedule");
+
+    let cache = Cache::new(&app_state.redis_pool);
+    let response = cache
+        .get_or_fetch(CACHE_KEY, CACHE_TTL, || async {
+            let data = fetch_schedule().await?;
+            Ok(ScheduleResponse {
+                status: "Ok".to_string(),
+                data,
+            })
+        })
+        .await
+        .map_err(|e| upstream_err(&e))?;
+
+    Ok(Json(response).into_response())
+}
+
+async fn fetch_schedule() -> Result<WeeklySchedule, Box<dyn std::error::Error + Send + Sync>> {
+    let url = format!("{}/jadwal-rilis/", get_otakudesu_url());
+    let html = fetch_html_with_retry(&url).await?;
+
+    let schedule = tokio::task::spawn_blocking(move || parse_schedule(&html)).await?;
+    // An empty week means the layout changed; don't cache that for hours.
+    if schedule.count() == 0 {
+        return Err("No schedule found on the release calendar page".into());
+    }
+    Ok(schedule)
+}
+
+/// `HH:MM` from text like `21:00` or `(23.30)`.
+fn parse_time(raw: &str) -> Option<String> {
+    static TIME: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"\b([01]?\d|2[0-3])[:.]([0-5]\d)\b").unwrap());
+    let caps = TIME.captures(raw)?;
+    Some(format!("{:0>2}:{}", &caps[1], &caps[2]))
+}
+
+fn parse_schedule(html: &str) -> WeeklySchedule {
+    let document = parse_html(html);
+    let day_selector = selector(".kglist321").unwrap();
+    let heading_selector = selector("h2").unwrap();
+    let item_selector = selector("ul li").unwrap();
+    let link_selector = selector("a").unwrap();
+
+    let mut schedule = WeeklySchedule::default();
+    for day in document.select(&day_selector) {
+        let heading = day
+            .select(&heading_selector)
+            .next()
+            .map(|h| text(&h))
+            .unwrap_or_default();
+        let Some(items) = schedule.day_mut(&heading) else {
+            info!("Skipping unknown schedule heading {:?}", heading);
+            continue;
+        };
+
+        for item in day.select(&item_selector) {
+            let Some(link) = item.select(&link_selector).next() else {
+                continue;
+            };
+            let title = text(&link);
+            let slug = extract_slug(&attr(&link, "href").unwrap_or_default());
+            if title.is_empty() || slug.is_empty() {
+                continue;
+            }
+            let time = parse_time(&text(&item).replacen(&title, "", 1));
+            items.push(ScheduleItem { title, slug, time });
+        }
+    }
+
+    info!("Parsed {} scheduled anime", schedule.count());
+    schedule
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(title: &str, slug: &str, time: Option<&str>) -> ScheduleItem {
+        ScheduleItem {
+            title: title.to_string(),
+            slug: slug.to_string(),
+            time: time.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn fixture_is_grouped_by_weekday() {
+        let schedule = parse_schedule(include_str!("../../../scraping/fixtures/anime_schedule.html"));
+
+        assert_eq!(
+            schedule.monday,
+            vec![
+                item(
+                    "Kusuriya no Hitorigoto Season 2",
+                    "kusuriya-hitorigoto-s2-sub-indo",
+                    Some("21:00")
+                ),
+                item("Dandadan Season 2", "dandadan-s2-sub-indo", None),
+            ]
+        );
+        assert_eq!(
+            schedule.tuesday,
+            vec![item(
+                "Kaijuu 8-gou Season 2",
+                "kaiju-8-s2-sub-indo",
+                Some("23:30")
+            )]
+        );
+        assert!(schedule.wednesday.is_empty());
+        assert!(schedule.thursday.is_empty());
+        // The empty link is dropped.
+        assert_eq!(schedule.friday.len(), 1);
+        assert!(schedule.saturday.is_empty());
+        assert_eq!(
+            schedule.sunday,
+            vec![item("One Piece", "one-piece-sub-indo", Some("08:30"))]
+        );
+        assert_eq!(schedule.random.len(), 1);
+        assert_eq!(schedule.count(), 6);
+    }
+
+    #[test]
+    fn serializes_days_in_week_order() {
+        let json = serde_json::to_string(&WeeklySchedule::default()).unwrap();
+        assert_eq!(
+            json,
+            r#"{"monday":[],"tuesday":[],"wednesday":[],"thursday":[],"friday":[],"saturday":[],"sunday":[],"random":[]}"#
+        );
+    }
+
+    #[test]
+    fn times_are_normalized() {
+        assert_eq!(parse_time("21:00").as_deref(), Some("21:00"));
+        assert_eq!(parse_time("(8.05 WIB)").as_deref(), Some("08:05"));
+        assert_eq!(parse_time("Season 2"), None);
+        assert_eq!(parse_time("25:00"), None);
+    }
+}
+
+pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
+    router
+}
\ No newline at end of file
@@ -3,19 +3,23 @@ use std::sync::Arc;
 
 // External crate imports
 use axum::{
-    extract::{Query, State},
+    extract::State,
     http::StatusCode,
     response::IntoResponse,
     Json, Router,
 };
-use crate::helpers::{internal_err, Cache, fetch_html_with_retry, parse_html};
+use crate::extractors::validated::not_blank;
+use crate::extractors::ValidatedQuery;
+use crate::helpers::{upstream_err, Cache, fetch_html_with_retry, parse_html};
 use crate::helpers::scraping::{selector, text_from_or, attr_from_or, extract_slug, text, extract_parentheses};
 use crate::routes::AppState;
+use crate::scraping::debug::DebugQuery;
 use crate::scraping::urls::get_otakudesu_url;
 
 use serde::{Deserialize, Serialize};
 use tracing::info;
 use utoipa::ToSchema;
+use validator::Validate;
 
 
 #[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
@@ -30,26 +34,29 @@ pub struct AnimeItem {
     pub rating: String,
 }
 
-#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
-pub struct Pagination {
-    pub current_page: u32,
-    pub last_visible_page: u32,
-    pub has_next_page: bool,
-    pub next_page: Option<u32>,
-    pub has_previous_page: bool,
-    pub previous_page: Option<u32>,
-}
+pub use crate::helpers::pagination::Pagination;
 
 #[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
 pub struct SearchResponse {
     pub status: String,
     pub data: Vec<AnimeItem>,
     pub pagination: Pagination,
+    /// Scrape source; only with `?debug=1`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Upstream URL the results were scraped from; only with `?debug=1`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fetched_url: Option<String>,
 }
 
-#[derive(Deserialize, ToSchema)]
+#[derive(Deserialize, ToSchema, Validate)]
 pub struct SearchQuery {
-    pub q: Option<String>,
+    /// Search text; required and non-blank.
+    #[serde(default)]
+    #[validate(custom(function = "not_blank"))]
+    pub q: String,
+    #[serde(flatten)]
+    pub debug: DebugQuery,
 }
 
 const CACHE_TTL: u64 = 300; // 5 minutes
@@ -57,39 +64,34 @@ const CACHE_TTL: u64 = 300; // 5 minutes
 #[utoipa::path(
     get,
     params(
-        ("q" = Option<String>, Query, description = "Search parameter for filtering results", example = "sample_value")
+        ("q" = String, Query, description = "Search parameter for filtering results", example = "sample_value"),
+        DebugQuery
     ),
     path = "/api/anime/search",
     tag = "anime",
     operation_id = "anime_search",
     responses(
         (status = 200, description = "Searches for anime based on query parameters.", body = SearchResponse),
+        (status = 422, description = "Missing or empty `q`"),
         (status = 500, description = "Internal Server Error", body = String)
     )
 )]
 pub async fn search(
     State(app_state): State<Arc<AppState>>,
-    Query(params): Query<SearchQuery>,
+    ValidatedQuery(params): ValidatedQuery<SearchQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     let start = std::time::Instant::now();
-    let query = params.q.unwrap_or_else(|| "one".to_string());
+    let query = params.q.trim().to_string();
     info!("Starting search for query: {}", query);
 
     let cache_key = format!("anime:search:{}", query);
     let cache = Cache::new(&app_state.redis_pool);
+    let url = search_url(&query);
 
     // Use get_or_set pattern - much cleaner!
-    let response = cache
-        .get_or_set(&cache_key, CACHE_TTL, || async {
-            let url = format!(
-                "{}/?s={}&post_type=anime",
-                get_otakudesu_url(),
-                urlencoding::encode(&query)
-            );
-
-            let (mut data, pagination) = fetch_and_parse_search(&url)
-                .await
-                .map_err(|e| format!("Fetch error: {}", e))?;
+    let mut response = cache
+        .get_or_fetch(&cache_key, CACHE_TTL, || async {
+            let (mut data, pagination) = fetch_and_parse_search(&url).await?;
 
             // Convert all poster URLs to CDN URLs
             // Convert all poster URLs to CDN URLs
@@ -116,10 +118,13 @@ pub async fn search(
                 status: "Ok".to_string(),
                 data,
                 pagination,
+                source: None,
+                fetched_url: None,
             })
         })
         .await
-        .map_err(internal_err)?;
+        .map_err(|e| upstream_err(&e))?;
+    (response.source, response.fetched_url) = params.debug.origin("otakudesu", &url);
 
     let duration = start.elapsed();
     info!(
@@ -130,10 +135,19 @@ pub async fn search(
     Ok(Json(response).into_response())
 }
 
-async fn fetch_and_parse_search(
+/// Otakudesu's search page for `query`.
+pub(crate) fn search_url(query: &str) -> String {
+    format!(
+        "{}/?s={}&post_type=anime",
+        get_otakudesu_url(),
+        urlencoding::encode(query)
+    )
+}
+
+pub(crate) async fn fetch_and_parse_search(
     url: &str,
 ) -> Result<(Vec<AnimeItem>, Pagination), Box<dyn std::error::Error + Send + Sync>> {
-    let html = fetch_html_with_retry(url).await.map_err(|e| format!("Failed to fetch HTML: {}", e))?;
+    let html = fetch_html_with_retry(url).await?;
 
     match tokio::task::spawn_blocking(move || parse_search_html(&html)).await {
         Ok(inner_result) => inner_result,
@@ -200,18 +214,7 @@ fn parse_search_html(
 
     let has_next_page = document.select(&next_selector).next().is_some();
 
-    let pagination = Pagination {
-        current_page: 1,
-        last_visible_page: 1,
-        has_next_page,
-        next_page: if has_next_page {
-            Some(2)
-        } else {
-            None
-        },
-        has_previous_page: false,
-        previous_page: None,
-    };
+    let pagination = Pagination::from_page(1, 1, has_next_page);
 
     Ok((anime_list, pagination))
 }
@@ -18,6 +18,7 @@ use crate::routes::AppState;
 // Import shared models and parsers
 use crate::models::anime2::{CompleteAnimeItem, Pagination};
 use crate::scraping::anime2 as parsers;
+use crate::scraping::urls::get_alqanime_url;
 
 
 const CACHE_TTL: u64 = 300; // 5 minutes
@@ -47,10 +48,7 @@ pub async fn slug(
 
     let response = cache
         .get_or_set(&cache_key, CACHE_TTL, || async {
-            let url = format!(
-                "https://alqanime.si/anime/page/{}/?status=completed&order=update",
-                slug
-            );
+            let url = page_url(&slug);
 
             let html = fetch_html_with_retry(&url)
                 .await
@@ -96,7 +94,16 @@ pub async fn slug(
     Ok(response)
 }
 
-fn parse_anime_page(
+/// The source's completed-anime list, page `slug`.
+pub(crate) fn page_url(slug: &str) -> String {
+    format!(
+        "{}/anime/page/{}/?status=completed&order=update",
+        get_alqanime_url(),
+        slug
+    )
+}
+
+pub(crate) fn parse_anime_page(
     html: &str,
     slug: &str,
 ) -> Result<(Vec<CompleteAnimeItem>, Pagination), String> {
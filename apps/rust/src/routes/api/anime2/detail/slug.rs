@@ -1,7 +1,11 @@
-use crate::helpers::{internal_err, Cache, fetch_html_with_retry, parse_html};
+use crate::helpers::{upstream_err, Cache, fetch_html_with_retry, parse_html};
 use crate::services::images::cache::{get_cached_or_original, cache_image_urls_batch_lazy};
-use crate::helpers::scraping::{selector, text_from_or, extract_slug, text, attr};
+use crate::helpers::scraping::{
+    attr, extract_img_src, extract_slug, selectors, split_labeled_list, text, text_from_or,
+};
+use scraper::{ElementRef, Html};
 use crate::routes::AppState;
+use crate::scraping::link_filter::{LinkHostFilter, LINK_HOST_FILTER};
 use axum::extract::State;
 use axum::http::StatusCode;
 use axum::{extract::Path, response::IntoResponse, Json, Router};
@@ -27,6 +31,8 @@ pub struct Link {
 
 #[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
 pub struct DownloadItem {
+    /// Title of the download block, e.g. "Episode 08" or the batch name.
+    pub title: String,
     pub resolution: String,
     pub links: Vec<Link>,
 }
@@ -91,10 +97,9 @@ pub async fn slug(
     let cache = Cache::new(&app_state.redis_pool);
 
     let response = cache
-        .get_or_set(&cache_key, CACHE_TTL, || async {
+        .get_or_fetch(&cache_key, CACHE_TTL, || async {
             let mut data = fetch_anime_detail(slug.clone())
-                .await
-                .map_err(|e| e.to_string())?;
+                .await?;
 
             // 1. Cache posters
             data.poster = get_cached_or_original(
@@ -132,7 +137,7 @@ pub async fn slug(
             })
         })
         .await
-        .map_err(|e| internal_err(&e))?;
+        .map_err(|e| upstream_err(&e))?;
 
     Ok(Json(response).into_response())
 }
@@ -142,9 +147,7 @@ async fn fetch_anime_detail(
 ) -> Result<AnimeDetailData, Box<dyn std::error::Error + Send + Sync>> {
     let url = format!("https://alqanime.net/{}/", slug);
 
-    let html = fetch_html_with_retry(&url)
-        .await
-        .map_err(|e| format!("Failed to fetch HTML with retry: {}", e))?;
+    let html = fetch_html_with_retry(&url).await?;
     let slug_clone = slug.clone();
 
     match tokio::task::spawn_blocking(move || {
@@ -165,80 +168,74 @@ fn parse_anime_detail_document(
 
     let document = parse_html(html);
 
-    let title_selector = selector(".entry-title").unwrap();
-    let alt_title_selector = selector(".alter").unwrap();
-    let poster_selector = selector(".thumb img, .thumbook img, .wp-post-image, .ts-post-image")
-        .unwrap();
-    let poster2_selector =
-        selector(".bigcover img, .bixbox.animefull .bigcover .ime img").unwrap();
-    let spe_span_selector = selector(".info-content .spe span").unwrap();
-    let a_selector = selector("a").unwrap();
-    let synopsis_selector = selector(".entry-content p").unwrap();
-    let genre_selector = selector(".genxed a").unwrap();
-    let download_container_selector = selector(".soraddl.dlone").unwrap();
-    let resolution_selector = selector(".res").unwrap();
-    let link_selector = selector(".slink a").unwrap();
-    let h3_selector = selector("h3").unwrap();
-    let recommendation_selector = selector(".listupd .bs").unwrap();
-    let rec_title_selector = selector(".ntitle").unwrap();
-    let rec_img_selector = selector("img").unwrap();
-    let status_selector = selector(".status").unwrap();
-    let type_selector = selector(".typez").unwrap();
-
-    let title = text_from_or(&document.root_element(), &title_selector, "");
-
-    let alternative_title = text_from_or(&document.root_element(), &alt_title_selector, "");
+    selectors! {
+        TITLE_SELECTOR = ".entry-title";
+        ALT_TITLE_SELECTOR = ".alter";
+        // Any img inside the thumb container: lazy-load plugins vary the class
+        // (`lazyload`, `lazyloaded`, `ts-post-image`...) so don't depend on it.
+        POSTER_SELECTOR = ".thumb img, .thumbook img, [itemprop=\"image\"] img, img.wp-post-image, img.ts-post-image";
+        POSTER2_SELECTOR = ".bigcover img, .ime img";
+        SPE_SPAN_SELECTOR = ".info-content .spe span";
+        A_SELECTOR = "a";
+        SYNOPSIS_SELECTOR = ".entry-content p";
+        GENRE_SELECTOR = ".genxed a";
+        RECOMMENDATION_SELECTOR = ".listupd .bs";
+        REC_TITLE_SELECTOR = ".ntitle";
+        REC_IMG_SELECTOR = "img";
+        STATUS_SELECTOR = ".status";
+        TYPE_SELECTOR = ".typez";
+    }
+
+    let title = text_from_or(&document.root_element(), &TITLE_SELECTOR, "");
+
+    let alternative_title = text_from_or(&document.root_element(), &ALT_TITLE_SELECTOR, "");
 
     let poster = document
-        .select(&poster_selector)
-        .next()
-        .and_then(|e| {
-            attr(&e, "src")
-                .or_else(|| attr(&e, "data-src"))
-                .or_else(|| attr(&e, "data-lazy-src"))
-        })
+        .select(&POSTER_SELECTOR)
+        .find_map(|e| extract_img_src(&e))
         .unwrap_or_default();
 
     let poster2 = document
-        .select(&poster2_selector)
-        .next()
-        .and_then(|e| {
-            attr(&e, "src")
-                .or_else(|| attr(&e, "data-src"))
-                .or_else(|| attr(&e, "data-lazy-src"))
-        })
+        .select(&POSTER2_SELECTOR)
+        .find_map(|e| extract_img_src(&e))
         .unwrap_or_default();
 
     let r#type = document
-        .select(&spe_span_selector)
-        .find(|e| text(&e).contains("Tipe:"))
-        .and_then(|span| span.select(&a_selector).next())
+        .select(&SPE_SPAN_SELECTOR)
+        .find(|e| text(e).contains("Tipe:"))
+        .and_then(|span| span.select(&A_SELECTOR).next())
         .map(|e| text(&e))
         .unwrap_or_default();
 
     let release_date = document
-        .select(&spe_span_selector)
-        .find(|e| text(&e).contains("Dirilis:"))
+        .select(&SPE_SPAN_SELECTOR)
+        .find(|e| text(e).contains("Dirilis:"))
         .map(|e| text(&e))
         .unwrap_or_default();
 
     let status = document
-        .select(&spe_span_selector)
-        .find(|e| text(&e).contains("Status:"))
+        .select(&SPE_SPAN_SELECTOR)
+        .find(|e| text(e).contains("Status:"))
         .map(|e| text(&e))
         .unwrap_or_default();
 
-    let synopsis = text_from_or(&document.root_element(), &synopsis_selector, "");
+    let synopsis = text_from_or(&document.root_element(), &SYNOPSIS_SELECTOR, "");
 
     let studio = document
-        .select(&spe_span_selector)
-        .find(|e| text(&e).contains("Studio:"))
-        .and_then(|span| span.select(&a_selector).next())
+        .select(&SPE_SPAN_SELECTOR)
+        .find(|e| text(e).contains("Studio:"))
+        .and_then(|span| span.select(&A_SELECTOR).next())
         .map(|e| text(&e))
         .unwrap_or_default();
 
+    let producers = document
+        .select(&SPE_SPAN_SELECTOR)
+        .find(|e| text(e).contains("Produser:"))
+        .map(|e| split_labeled_list(&text(&e), "Produser:"))
+        .unwrap_or_default();
+
     let mut genres = Vec::new();
-    for element in document.select(&genre_selector) {
+    for element in document.select(&GENRE_SELECTOR) {
         let name = text(&element);
         let anime_url = attr(&element, "href").unwrap_or_default();
         let genre_slug = extract_slug(&anime_url);
@@ -249,67 +246,14 @@ fn parse_anime_detail_document(
         });
     }
 
-    let mut batch = Vec::new();
-    let mut ova = Vec::new();
-    let mut downloads = Vec::new();
-
-    for element in document.select(&download_container_selector) {
-        let title = element
-            .select(&h3_selector)
-            .next()
-            .map(|e| text(&e))
-            .unwrap_or_else(|| "Unknown".to_string());
-
-        let category = title.to_lowercase();
-        let is_batch = category.contains("batch");
-        let is_ova = category.contains("ova");
-
-        // If we want to group by "Episode" (or Batch title), we collect all links for this block
-        // but we must preserve resolution info in the link name since DownloadItem has only one 'resolution' field
-        // which we will use for the Title.
-        
-        let mut all_links = Vec::new();
-
-        // Iterate over table rows to get resolution context
-        let row_selector = selector("table tr").unwrap();
-        for row in element.select(&row_selector) {
-            let resolution = text_from_or(&row, &resolution_selector, "");
-            
-            for link_element in row.select(&link_selector) {
-                let provider = text(&link_element);
-                let url = attr(&link_element, "href").unwrap_or_default();
-                
-                // Format name as "Resolution - Provider" (e.g., "360p - AceFile")
-                let name = if !resolution.is_empty() {
-                    format!("{} - {}", resolution, provider)
-                } else {
-                    provider
-                };
-
-                all_links.push(Link { name, url });
-            }
-        }
-
-        let download_item = DownloadItem {
-            resolution: title, // Use the 'resolution' field to store the Episode Title
-            links: all_links,
-        };
-
-        if is_batch {
-            batch.push(download_item);
-        } else if is_ova {
-            ova.push(download_item);
-        } else {
-            downloads.push(download_item);
-        }
-    }
+    let (batch, ova, downloads) = parse_download_groups(&document, &LINK_HOST_FILTER);
 
     let mut recommendations = Vec::new();
-    for element in document.select(&recommendation_selector) {
-        let title = text_from_or(&element, &rec_title_selector, "");
+    for element in document.select(&RECOMMENDATION_SELECTOR) {
+        let title = text_from_or(&element, &REC_TITLE_SELECTOR, "");
 
         let anime_url = element
-            .select(&a_selector)
+            .select(&A_SELECTOR)
             .next()
             .and_then(|e| attr(&e, "href"))
             .unwrap_or_default();
@@ -317,14 +261,13 @@ fn parse_anime_detail_document(
         let rec_slug = extract_slug(&anime_url);
 
         let poster = element
-            .select(&rec_img_selector)
-            .next()
-            .and_then(|e| attr(&e, "data-src").or_else(|| attr(&e, "src")))
+            .select(&REC_IMG_SELECTOR)
+            .find_map(|e| extract_img_src(&e))
             .unwrap_or_default();
 
-        let status = text_from_or(&element, &status_selector, "");
+        let status = text_from_or(&element, &STATUS_SELECTOR, "");
 
-        let r#type = text_from_or(&element, &type_selector, "");
+        let r#type = text_from_or(&element, &TYPE_SELECTOR, "");
 
         recommendations.push(Recommendation {
             title,
@@ -352,7 +295,7 @@ fn parse_anime_detail_document(
         synopsis,
         studio,
         genres,
-        producers: vec![],
+        producers,
         recommendations,
         batch,
         ova,
@@ -360,6 +303,233 @@ fn parse_anime_detail_document(
     })
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DownloadSection {
+    Batch,
+    Ova,
+    Episode,
+}
+
+impl DownloadSection {
+    fn classify(label: &str) -> Option<Self> {
+        let label = label.to_lowercase();
+        if label.contains("batch") {
+            Some(Self::Batch)
+        } else if label.split(|c: char| !c.is_alphanumeric()).any(|w| w == "ova" || w == "ovas") {
+            Some(Self::Ova)
+        } else {
+            None
+        }
+    }
+}
+
+fn is_section_header(element: &ElementRef) -> bool {
+    let value = element.value();
+    matches!(value.name(), "h1" | "h2" | "h3" | "h4")
+        || value.classes().any(|c| c.starts_with("sorattl") || c == "releases")
+}
+
+/// Finds the nearest header that precedes `element` in document order without
+/// descending into earlier download blocks, so a batch block's own `<h3>` does
+/// not leak into the episode blocks that follow it.
+fn preceding_header(element: &ElementRef) -> Option<String> {
+    let mut current = Some(*element);
+    while let Some(node) = current {
+        for sibling in node.prev_siblings().filter_map(ElementRef::wrap) {
+            if is_section_header(&sibling) {
+                return Some(text(&sibling));
+            }
+        }
+        current = node.parent().and_then(ElementRef::wrap);
+    }
+    None
+}
+
+/// Splits the `.soraddl` download blocks into (batch, ova, downloads).
+///
+/// Each resolution row of a block becomes one `DownloadItem` carrying the
+/// block title (e.g. "Episode 08"), the row's resolution ("720p") and its
+/// provider links. The section is taken from the block title when it says
+/// batch/OVA, otherwise from the nearest preceding header element. Links
+/// rejected by `filter` are dropped, and so are rows left without links.
+fn parse_download_groups(
+    document: &Html,
+    filter: &LinkHostFilter,
+) -> (Vec<DownloadItem>, Vec<DownloadItem>, Vec<DownloadItem>) {
+    selectors! {
+        CONTAINER_SELECTOR = ".soraddl";
+        TITLE_SELECTOR = "h3, .sorattl, .sorattlx";
+        ROW_SELECTOR = "table tr, .soraurlx";
+        RESOLUTION_SELECTOR = ".res, strong";
+        LINK_SELECTOR = ".slink a, a";
+    }
+
+    let mut batch = Vec::new();
+    let mut ova = Vec::new();
+    let mut downloads = Vec::new();
+
+    for container in document.select(&CONTAINER_SELECTOR) {
+        let title = container
+            .select(&TITLE_SELECTOR)
+            .next()
+            .map(|e| text(&e))
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| "Unknown".to_string());
+
+        let section = DownloadSection::classify(&title)
+            .or_else(|| preceding_header(&container).and_then(|h| DownloadSection::classify(&h)))
+            .unwrap_or(DownloadSection::Episode);
+
+        let target = match section {
+            DownloadSection::Batch => &mut batch,
+            DownloadSection::Ova => &mut ova,
+            DownloadSection::Episode => &mut downloads,
+        };
+
+        for row in container.select(&ROW_SELECTOR) {
+            let resolution = text_from_or(&row, &RESOLUTION_SELECTOR, "");
+
+            let mut seen = std::collections::HashSet::new();
+            let mut links = Vec::new();
+            for link_element in row.select(&LINK_SELECTOR) {
+                let url = attr(&link_element, "href").unwrap_or_default();
+                if url.is_empty() || !filter.permits(&url) || !seen.insert(url.clone()) {
+                    continue;
+                }
+                links.push(Link { name: text(&link_element), url });
+            }
+
+            if !links.is_empty() {
+                target.push(DownloadItem {
+                    title: title.clone(),
+                    resolution,
+                    links,
+                });
+            }
+        }
+    }
+
+    (batch, ova, downloads)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Trimmed alqanime.net detail page: a batch block under its own header,
+    /// an OVA block announced only by the preceding `<h2>`, and two episodes.
+    const ALQANIME_DETAIL: &str = r#"
+<div class="bixbox">
+  <div class="bigcover"><div class="ime"><img class="lazyloaded" src="data:image/svg+xml;base64,AAAA" data-lazy-src="https://i1.wp.com/alqanime.net/cover.jpg"></div></div>
+  <div class="thumbook"><div class="thumb" itemprop="image"><img class="ts-post-image wp-post-image attachment-medium_large" src="data:image/gif;base64,R0lGOD" data-src="https://i0.wp.com/alqanime.net/poster.jpg"></div></div>
+  <h1 class="entry-title">Tamon-kun Ima Docchi!?</h1>
+  <div class="info-content"><div class="spe">
+    <span><b>Status:</b> Ongoing</span>
+    <span><b>Studio:</b> <a href="https://alqanime.net/studio/j-c-staff/">J.C.Staff</a></span>
+    <span><b>Produser:</b> Aniplex, Shueisha,  Lantis </span>
+  </div></div>
+  <div class="soraddl dlone">
+    <div class="sorattl"><h3>Tamon-kun Ima Docchi!? Batch Episode 01-12</h3></div>
+    <div class="content"><table><tbody>
+      <tr><td class="res">480p</td><td class="slink"><a href="https://gofile.io/d/batch480">GoFile</a></td></tr>
+      <tr><td class="res">720p</td><td class="slink"><a href="https://gofile.io/d/batch720">GoFile</a> <a href="https://reshare.pm/d/batch720">ReShare</a></td></tr>
+    </tbody></table></div>
+  </div>
+  <h2 class="releases">Download OVA</h2>
+  <div class="soraddl dlone">
+    <div class="sorattl"><h3>Tamon-kun Ima Docchi!? Special 01</h3></div>
+    <div class="content"><table><tbody>
+      <tr><td class="res">720p</td><td class="slink"><a href="https://gofile.io/d/ova720">GoFile</a></td></tr>
+    </tbody></table></div>
+  </div>
+  <h2 class="releases">Download Episode</h2>
+  <div class="soraddl dlone">
+    <div class="sorattl"><h3>Episode 08</h3></div>
+    <div class="content"><table><tbody>
+      <tr><td class="res">360p</td><td class="slink"><a href="https://acefile.co/f/08-360">AceFile</a></td></tr>
+      <tr><td class="res">1080p</td><td class="slink"><a href="https://gofile.io/d/08-1080">GoFile</a></td></tr>
+    </tbody></table></div>
+  </div>
+  <div class="soraddl dlone">
+    <div class="sorattl"><h3>Episode 07</h3></div>
+    <div class="content"><table><tbody>
+      <tr><td class="res">720p</td><td class="slink"><a href="https://gofile.io/d/07-720">GoFile</a></td></tr>
+    </tbody></table></div>
+  </div>
+</div>
+"#;
+
+    #[test]
+    fn groups_downloads_by_section() {
+        let data = parse_anime_detail_document(ALQANIME_DETAIL, "tamon-kun").unwrap();
+
+        let batch: Vec<_> = data.batch.iter().map(|d| d.resolution.as_str()).collect();
+        assert_eq!(batch, ["480p", "720p"]);
+        assert!(data.batch.iter().all(|d| d.title == "Tamon-kun Ima Docchi!? Batch Episode 01-12"));
+        let names: Vec<_> = data.batch[1].links.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, ["GoFile", "ReShare"]);
+
+        assert_eq!(data.ova.len(), 1);
+        assert_eq!(data.ova[0].title, "Tamon-kun Ima Docchi!? Special 01");
+        assert_eq!(data.ova[0].resolution, "720p");
+        assert_eq!(data.ova[0].links[0].name, "GoFile");
+
+        let episodes: Vec<_> = data
+            .downloads
+            .iter()
+            .map(|d| (d.title.as_str(), d.resolution.as_str()))
+            .collect();
+        assert_eq!(
+            episodes,
+            [("Episode 08", "360p"), ("Episode 08", "1080p"), ("Episode 07", "720p")]
+        );
+        assert_eq!(data.downloads[1].links[0].name, "GoFile");
+        assert_eq!(data.downloads[1].links[0].url, "https://gofile.io/d/08-1080");
+    }
+
+    #[test]
+    fn denied_hosts_are_removed_from_groups() {
+        let deny = vec!["reshare.pm".to_string(), "acefile.co".to_string()];
+        let filter = LinkHostFilter::new(&[], &deny);
+        let (batch, _, downloads) = parse_download_groups(&parse_html(ALQANIME_DETAIL), &filter);
+
+        let urls: Vec<_> = batch.iter().flat_map(|d| &d.links).map(|l| l.url.as_str()).collect();
+        assert_eq!(urls, ["https://gofile.io/d/batch480", "https://gofile.io/d/batch720"]);
+
+        // The 360p row only had an AceFile link, so it is gone entirely.
+        let rows: Vec<_> = downloads.iter().map(|d| d.resolution.as_str()).collect();
+        assert_eq!(rows, ["1080p", "720p"]);
+    }
+
+    #[test]
+    fn parses_producers() {
+        let data = parse_anime_detail_document(ALQANIME_DETAIL, "tamon-kun").unwrap();
+        assert_eq!(data.producers, ["Aniplex", "Shueisha", "Lantis"]);
+        assert_eq!(data.studio, "J.C.Staff");
+
+        let bare = parse_anime_detail_document("<div class=\"info-content\"></div>", "x").unwrap();
+        assert!(bare.producers.is_empty());
+    }
+
+    #[test]
+    fn extracts_lazy_loaded_posters() {
+        let data = parse_anime_detail_document(ALQANIME_DETAIL, "tamon-kun").unwrap();
+        assert_eq!(data.poster, "https://i0.wp.com/alqanime.net/poster.jpg");
+        assert_eq!(data.poster2, "https://i1.wp.com/alqanime.net/cover.jpg");
+
+        let plain = r#"<div class="thumb"><img class="lazy" srcset="https://x.test/a.jpg 1x, https://x.test/b.jpg 2x"></div>"#;
+        let data = parse_anime_detail_document(plain, "x").unwrap();
+        assert_eq!(data.poster, "https://x.test/a.jpg");
+    }
+
+    #[test]
+    fn classifies_section_labels() {
+        assert_eq!(DownloadSection::classify("Download Batch"), Some(DownloadSection::Batch));
+        assert_eq!(DownloadSection::classify("OVA 2"), Some(DownloadSection::Ova));
+        assert_eq!(DownloadSection::classify("Nova Episode 3"), None);
+    }
+}
+
 pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
     router
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 use crate::helpers::api_response::{internal_err, ApiResult, ApiResponse};
-use crate::helpers::{default_backoff, transient, Cache};
+use crate::helpers::scraping::selectors;
+use crate::helpers::{scrape_backoff, transient, Cache};
 use crate::infra::proxy::fetch_with_proxy;
 use crate::models::anime2::{FilterAnimeItem, Pagination};
 use crate::routes::AppState;
@@ -8,7 +9,7 @@ use axum::Router;
 use backoff::future::retry;
 use once_cell::sync::Lazy;
 use regex::Regex;
-use scraper::{Html, Selector};
+use scraper::Html;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::sync::Arc;
@@ -33,17 +34,17 @@ pub struct FilterQuery {
     pub order: Option<String>,
 }
 
-// Static selectors using once_cell (replacing lazy_static)
-static ITEM_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("article.bs").unwrap());
-static TITLE_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse(".tt h2").unwrap());
-static IMG_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("img").unwrap());
-static SCORE_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse(".numscore").unwrap());
-static STATUS_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse(".status").unwrap());
-static TYPE_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse(".type").unwrap());
-static LINK_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("a").unwrap());
-static PAGINATION_SELECTOR: Lazy<Selector> =
-    Lazy::new(|| Selector::parse(".pagination .page-numbers:not(.next)").unwrap());
-static NEXT_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse(".pagination .next").unwrap());
+selectors! {
+    ITEM_SELECTOR = "article.bs";
+    TITLE_SELECTOR = ".tt h2";
+    IMG_SELECTOR = "img";
+    SCORE_SELECTOR = ".numscore";
+    STATUS_SELECTOR = ".status";
+    TYPE_SELECTOR = ".type";
+    LINK_SELECTOR = "a";
+    PAGINATION_SELECTOR = ".pagination .page-numbers:not(.next)";
+    NEXT_SELECTOR = ".pagination .next";
+}
 static SLUG_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"/([^/]+)/?$").unwrap());
 
 const CACHE_TTL: u64 = 300;
@@ -159,7 +160,7 @@ async fn fetch_filtered_anime(
         url.push_str(&format!("&type={}", t));
     }
 
-    let backoff = default_backoff();
+    let backoff = scrape_backoff();
     let fetch_operation = || async {
         info!("Fetching: {}", url);
         match fetch_with_proxy(&url).await {
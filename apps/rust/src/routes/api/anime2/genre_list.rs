@@ -1,5 +1,5 @@
-use crate::helpers::{internal_err, Cache, fetch_html_with_retry, parse_html};
-use crate::helpers::scraping::{selector, text, attr};
+use crate::helpers::{upstream_err, Cache, fetch_html_with_retry, parse_html};
+use crate::helpers::scraping::{selectors, text, attr};
 use crate::routes::AppState;
 use axum::extract::State;
 use axum::http::StatusCode;
@@ -48,8 +48,8 @@ pub async fn genres(
     let cache = Cache::new(&app_state.redis_pool);
 
     let response = cache
-        .get_or_set(cache_key, CACHE_TTL, || async {
-            let genres = fetch_genres().await.map_err(|e| e.to_string())?;
+        .get_or_fetch(cache_key, CACHE_TTL, || async {
+            let genres = fetch_genres().await?;
 
             Ok(GenresResponse {
                 status: "Ok".to_string(),
@@ -57,7 +57,7 @@ pub async fn genres(
             })
         })
         .await
-        .map_err(|e| internal_err(&e))?;
+        .map_err(|e| upstream_err(&e))?;
 
     Ok(Json(response).into_response())
 }
@@ -75,10 +75,12 @@ async fn fetch_genres() -> Result<Vec<Genre>, Box<dyn std::error::Error + Send +
 fn parse_genres(html: &str) -> Result<Vec<Genre>, Box<dyn std::error::Error + Send + Sync>> {
     let document = parse_html(html);
     let mut genres = Vec::new();
-    let genre_label_selector = selector("label[for^=\"genre-\"]").unwrap();
+    selectors! {
+        GENRE_LABEL_SELECTOR = "label[for^=\"genre-\"]";
+    }
 
     // Parse genre labels from advanced search page
-    for element in document.select(&genre_label_selector) {
+    for element in document.select(&GENRE_LABEL_SELECTOR) {
         let name = text(&element).trim().to_string();
         let for_attr = attr(&element, "for").unwrap_or_default();
 
@@ -1,4 +1,4 @@
-use crate::helpers::{internal_err, Cache, fetch_html_with_retry};
+use crate::helpers::{upstream_err, Cache, fetch_html_pair};
 use crate::routes::AppState;
 use axum::extract::State;
 use axum::http::StatusCode;
@@ -10,16 +10,12 @@ use tracing::info;
 use utoipa::ToSchema;
 
 // Import shared models and parsers
-use crate::models::anime2::{OngoingAnimeItem, CompleteAnimeItem};
 use crate::scraping::anime2 as parsers;
 use crate::scraping::anime::cache as cache_utils;
 
 
-#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
-pub struct Anime2Data {
-    pub ongoing_anime: Vec<OngoingAnimeItem>,
-    pub complete_anime: Vec<CompleteAnimeItem>,
-}
+/// Same home page shape as `/api/anime`, shared with the Leptos client.
+pub type Anime2Data = crate::models::HomeData;
 
 #[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
 pub struct Anime2Response {
@@ -49,8 +45,8 @@ pub async fn anime2(
     let cache = Cache::new(&app_state.redis_pool);
 
     let response = cache
-        .get_or_set(CACHE_KEY, CACHE_TTL, || async {
-            let mut data = fetch_anime_data().await.map_err(|e| e.to_string())?;
+        .get_or_fetch(CACHE_KEY, CACHE_TTL, || async {
+            let mut data = fetch_anime_data().await?;
 
             // Use shared cache utility for batch poster caching
             let ongoing_posters: Vec<String> = data
@@ -91,7 +87,7 @@ pub async fn anime2(
             })
         })
         .await
-        .map_err(|e| internal_err(&e))?;
+        .map_err(|e| upstream_err(&e))?;
 
     Ok(Json(response))
 }
@@ -100,13 +96,7 @@ async fn fetch_anime_data() -> Result<Anime2Data, Box<dyn std::error::Error + Se
     let ongoing_url = "https://alqanime.si/anime/?status=ongoing&type=&order=update";
     let complete_url = "https://alqanime.si/anime/?status=completed&type=&order=update";
 
-    let (ongoing_html, complete_html) = tokio::join!(
-        fetch_html_with_retry(ongoing_url),
-        fetch_html_with_retry(complete_url)
-    );
-
-    let ongoing_html = ongoing_html?;
-    let complete_html = complete_html?;
+    let (ongoing_html, complete_html) = fetch_html_pair(ongoing_url, complete_url).await?;
 
     let ongoing_anime =
         tokio::task::spawn_blocking(move || parsers::parse_ongoing_anime(&ongoing_html)).await??;
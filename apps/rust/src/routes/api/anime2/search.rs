@@ -2,45 +2,52 @@ use crate::helpers::api_response::{internal_err, ApiResult, ApiResponse};
 use crate::helpers::{fetch_html_with_retry, parse_html, Cache};
 use crate::routes::AppState;
 use axum::extract::State;
-use axum::{extract::Query, Router};
+use axum::Router;
+use crate::extractors::validated::not_blank;
+use crate::extractors::ValidatedQuery;
 
 use serde::Deserialize;
 use serde_json::json;
 use std::sync::Arc;
 use tracing::info;
 use utoipa::ToSchema;
+use validator::Validate;
 
 // Import shared models and parsers
-use crate::models::anime2::{PaginationWithStringPages, SearchAnimeItem};
+use crate::models::anime2::{Pagination, SearchAnimeItem};
 use crate::scraping::anime2 as parsers;
 use crate::scraping::anime::cache as cache_utils;
 
 
 const CACHE_TTL: u64 = 300; // 5 minutes
 
-#[derive(Deserialize, ToSchema)]
+#[derive(Deserialize, ToSchema, Validate)]
 pub struct SearchQuery {
-    pub q: Option<String>,
+    /// Search text; required and non-blank.
+    #[serde(default)]
+    #[validate(custom(function = "not_blank"))]
+    pub q: String,
 }
 
 #[utoipa::path(
     get,
     params(
-        ("q" = Option<String>, Query, description = "Search parameter for filtering results", example = "sample_value")
+        ("q" = String, Query, description = "Search parameter for filtering results", example = "sample_value")
     ),
     path = "/api/anime2/search",
     tag = "anime2",
     operation_id = "anime2_search",
     responses(
         (status = 200, description = "Searches for anime2 based on query parameters.", body = ApiResponse<Vec<SearchAnimeItem>>),
+        (status = 422, description = "Missing or empty `q`"),
         (status = 500, description = "Internal Server Error", body = String)
     )
 )]
 pub async fn search(
     State(app_state): State<Arc<AppState>>,
-    Query(params): Query<SearchQuery>,
+    ValidatedQuery(params): ValidatedQuery<SearchQuery>,
 ) -> ApiResult<Vec<SearchAnimeItem>> {
-    let query = params.q.unwrap_or_else(|| "one".to_string());
+    let query = params.q.trim().to_string();
     info!("Starting search for query: {}", query);
 
     let cache_key = format!("anime2:search:{}", query);
@@ -71,7 +78,7 @@ pub async fn search(
 
 async fn fetch_and_parse_search(
     url: &str,
-) -> Result<(Vec<SearchAnimeItem>, PaginationWithStringPages), Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<(Vec<SearchAnimeItem>, Pagination), Box<dyn std::error::Error + Send + Sync>> {
     let html = fetch_html_with_retry(url).await?;
     let (data, pagination) = tokio::task::spawn_blocking(move || {
         parse_search_document(&html)
@@ -83,7 +90,7 @@ async fn fetch_and_parse_search(
 
 fn parse_search_document(
     html: &str,
-) -> Result<(Vec<SearchAnimeItem>, PaginationWithStringPages), Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<(Vec<SearchAnimeItem>, Pagination), Box<dyn std::error::Error + Send + Sync>> {
     let document = parse_html(html);
 
     // Parse anime items using shared parser
@@ -91,7 +98,7 @@ fn parse_search_document(
 
     // Parse pagination using shared parser
     let current_page = 1; // Search results always start at page 1
-    let pagination = parsers::parse_pagination_with_string(&document, current_page);
+    let pagination = parsers::parse_pagination(&document, current_page);
 
     Ok((data, pagination))
 }
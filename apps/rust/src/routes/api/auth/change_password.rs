@@ -76,8 +76,10 @@ fn validate_password_strength(password: &str) -> Result<(), AppError> {
     path = "/api/auth/change-password",
     tag = "auth",
     operation_id = "auth_change_password",
+    security(("bearer_auth" = [])),
     responses(
         (status = 200, description = "Change user password (authenticated)", body = ChangePasswordResponse),
+        (status = 401, description = "Missing, expired or invalid token"),
         (status = 500, description = "Internal Server Error", body = String)
     )
 )]
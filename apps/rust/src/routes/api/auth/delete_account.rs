@@ -61,8 +61,10 @@ fn extract_token(headers: &HeaderMap) -> Result<String, AppError> {
     path = "/api/auth/account",
     tag = "auth",
     operation_id = "auth_delete_account",
+    security(("bearer_auth" = [])),
     responses(
         (status = 200, description = "Delete user account", body = DeleteAccountResponse),
+        (status = 401, description = "Missing, expired or invalid token"),
         (status = 500, description = "Internal Server Error", body = String)
     )
 )]
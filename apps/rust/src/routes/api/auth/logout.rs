@@ -57,8 +57,10 @@ fn extract_token(headers: &HeaderMap) -> Result<String, AppError> {
     path = "/api/auth/logout",
     tag = "auth",
     operation_id = "auth_logout",
+    security(("bearer_auth" = [])),
     responses(
         (status = 200, description = "Logout user and invalidate tokens", body = LogoutResponse),
+        (status = 401, description = "Missing, expired or invalid token"),
         (status = 500, description = "Internal Server Error", body = String)
     )
 )]
@@ -32,8 +32,10 @@ fn extract_token(headers: &HeaderMap) -> Result<String, AppError> {
     path = "/api/auth/me",
     tag = "auth",
     operation_id = "auth_me",
+    security(("bearer_auth" = [])),
     responses(
         (status = 200, description = "Get current authenticated user", body = UserResponse),
+        (status = 401, description = "Missing, expired or invalid token"),
         (status = 500, description = "Internal Server Error", body = String)
     )
 )]
@@ -53,8 +53,10 @@ fn extract_token(headers: &HeaderMap) -> Result<String, AppError> {
     path = "/api/auth/profile",
     tag = "auth",
     operation_id = "auth_update_profile",
+    security(("bearer_auth" = [])),
     responses(
         (status = 200, description = "Update user profile", body = UpdateProfileResponse),
+        (status = 401, description = "Missing, expired or invalid token"),
         (status = 500, description = "Internal Server Error", body = String)
     )
 )]
@@ -82,8 +82,10 @@ impl From<ProfileStorageError> for AppError {
     path = "/api/auth/profile/image",
     tag = "auth",
     operation_id = "auth_upload_profile_image",
+    security(("bearer_auth" = [])),
     responses(
         (status = 200, description = "Upload profile image"),
+        (status = 401, description = "Missing, expired or invalid token"),
         (status = 500, description = "Internal Server Error", body = String)
     )
 )]
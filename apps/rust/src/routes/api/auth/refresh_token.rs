@@ -2,6 +2,7 @@
 
 use axum::{extract::State, response::IntoResponse, Json, Router};
 use chrono::Utc;
+use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use utoipa::ToSchema;
@@ -9,9 +10,11 @@ use uuid::Uuid;
 
 // SeaORM imports
 use crate::entities::user;
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
 
 use crate::routes::AppState;
+use crate::core::config::CONFIG;
 use crate::core::jwt::{encode_jwt, Claims};
 use crate::core::error::AppError;
 
@@ -37,13 +40,51 @@ pub struct RefreshResponse {
     pub expires_in: i64,
 }
 
+/// How long a rotated-out refresh token is remembered for reuse detection.
+const ROTATED_TOKEN_TTL_SECS: u64 = 30 * 24 * 3600;
+
+fn rotated_key(token: &str) -> String {
+    format!("auth:refresh:rotated:{}", token)
+}
+
+/// Swaps `old` for `new` only if `old` is still `user_id`'s current token.
+///
+/// The conditional update makes concurrent refreshes with the same token
+/// race safely: exactly one of them rotates, the rest see `false`.
+async fn rotate_refresh_token(
+    db: &DatabaseConnection,
+    user_id: &str,
+    old: &str,
+    new: &str,
+) -> Result<bool, DbErr> {
+    let result = user::Entity::update_many()
+        .col_expr(user::Column::RefreshToken, Expr::value(new))
+        .filter(user::Column::Id.eq(user_id))
+        .filter(user::Column::RefreshToken.eq(old))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected == 1)
+}
+
+/// Revokes every refresh token of `user_id` after a rotated token was replayed.
+async fn revoke_refresh_tokens(db: &DatabaseConnection, user_id: &str) -> Result<(), DbErr> {
+    user::Entity::update_many()
+        .col_expr(user::Column::RefreshToken, Expr::value(Option::<String>::None))
+        .filter(user::Column::Id.eq(user_id))
+        .exec(db)
+        .await
+        .map(|_| ())
+}
+
 #[utoipa::path(
     post,
     path = "/api/auth/refresh",
     tag = "auth",
     operation_id = "auth_refresh",
+    request_body = RefreshRequest,
     responses(
         (status = 200, description = "Refresh JWT access token", body = RefreshResponse),
+        (status = 401, description = "Refresh token unknown, revoked or already rotated"),
         (status = 500, description = "Internal Server Error", body = String)
     )
 )]
@@ -51,16 +92,53 @@ pub async fn refresh(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<RefreshRequest>,
 ) -> Result<impl IntoResponse, AppError> {
+    let db = state.sea_orm();
+
     // Verify refresh token from user.refreshToken field
-    let user_model = user::Entity::find()
+    let Some(user_model) = user::Entity::find()
         .filter(user::Column::RefreshToken.eq(&payload.refresh_token))
-        .one(state.sea_orm())
+        .one(db)
         .await
         .map_err(|e| AppError::DatabaseError(e.to_string()))?
-        .ok_or(AppError::InvalidToken)?;
+    else {
+        // A token we rotated out earlier being presented again means it was
+        // copied: revoke the whole session so neither party can keep refreshing.
+        let mut redis_conn = state.redis_pool.get().await?;
+        let reused_by: Option<String> = redis_conn
+            .get(rotated_key(&payload.refresh_token))
+            .await
+            .map_err(AppError::RedisError)?;
+        if let Some(user_id) = reused_by {
+            tracing::warn!(user_id = %user_id, "Refresh token reuse detected, revoking session");
+            revoke_refresh_tokens(db, &user_id)
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        }
+        return Err(AppError::InvalidToken);
+    };
+
+    // Remember the old token before it stops being current, so a replay is
+    // caught even if this request fails after the rotation. A failure here
+    // leaves the token untouched and the client free to retry. If the
+    // rotation below loses, the token was rotated or revoked concurrently and
+    // the marker is still true, so it stays.
+    let mut redis_conn = state.redis_pool.get().await?;
+    redis_conn
+        .set_ex::<_, _, ()>(rotated_key(&payload.refresh_token), &user_model.id, ROTATED_TOKEN_TTL_SECS)
+        .await
+        .map_err(AppError::RedisError)?;
+
+    // Generate new refresh token and rotate it in atomically
+    let new_refresh_token = Uuid::new_v4().to_string();
+    let rotated = rotate_refresh_token(db, &user_model.id, &payload.refresh_token, &new_refresh_token)
+        .await
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    if !rotated {
+        return Err(AppError::InvalidToken);
+    }
 
     // Generate new access token
-    let token_expiry = 24 * 3600; // 24 hours
+    let token_expiry = CONFIG.access_token_ttl_seconds.max(1) as i64;
     let exp = (Utc::now().timestamp() + token_expiry) as usize;
 
     let claims = Claims {
@@ -72,17 +150,6 @@ pub async fn refresh(
 
     let access_token = encode_jwt(claims)?;
 
-    // Generate new refresh token
-    let new_refresh_token = Uuid::new_v4().to_string();
-
-    // Update user's refresh token
-    let mut user_active: user::ActiveModel = user_model.into();
-    user_active.refresh_token = Set(Some(new_refresh_token.clone()));
-    user_active
-        .update(state.sea_orm())
-        .await
-        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
-
     Ok(Json(RefreshResponse {
         access_token,
         refresh_token: new_refresh_token,
@@ -91,6 +158,85 @@ pub async fn refresh(
     }))
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::app::test_state_with_redis;
+    use crate::testing::redis::fake_redis;
+    use axum::http::StatusCode;
+    use sea_orm::{
+        ActiveModelTrait, ConnectionTrait, Database, DatabaseBackend, IntoActiveModel,
+        MockDatabase, MockExecResult, Schema,
+    };
+
+    fn exec(rows_affected: u64) -> MockExecResult {
+        MockExecResult {
+            last_insert_id: 0,
+            rows_affected,
+        }
+    }
+
+    #[tokio::test]
+    async fn rotation_succeeds_once_per_token() {
+        // First refresh swaps the token; a replay finds nothing to update.
+        let db = MockDatabase::new(DatabaseBackend::MySql)
+            .append_exec_results([exec(1), exec(0)])
+            .into_connection();
+
+        assert!(rotate_refresh_token(&db, "u1", "old", "new").await.unwrap());
+        assert!(!rotate_refresh_token(&db, "u1", "old", "newer").await.unwrap());
+    }
+
+    async fn refresh_with(state: &Arc<AppState>, token: &str) -> Result<String, StatusCode> {
+        let request = RefreshRequest { refresh_token: token.to_string() };
+        match refresh(State(state.clone()), Json(request)).await {
+            Ok(_) => Ok(user_token(state).await.expect("rotation cleared the token")),
+            Err(err) => Err(err.into_response().status()),
+        }
+    }
+
+    async fn user_token(state: &Arc<AppState>) -> Option<String> {
+        user::Entity::find_by_id("u1")
+            .one(state.sea_orm())
+            .await
+            .unwrap()
+            .unwrap()
+            .refresh_token
+    }
+
+    #[tokio::test]
+    async fn replaying_a_rotated_token_revokes_the_session() {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        let backend = db.get_database_backend();
+        db.execute(backend.build(&Schema::new(backend).create_table_from_entity(user::Entity)))
+            .await
+            .unwrap();
+        user::Model {
+            id: "u1".to_string(),
+            name: Some("u1".to_string()),
+            email: Some("u1@example.com".to_string()),
+            email_verified: None,
+            image: None,
+            password: None,
+            refresh_token: Some("first".to_string()),
+            role: "user".to_string(),
+        }
+        .into_active_model()
+        .insert(&db)
+        .await
+        .unwrap();
+        let (pool, _store) = fake_redis().await;
+        let state = test_state_with_redis(db, pool);
+
+        let second = refresh_with(&state, "first").await.unwrap();
+        assert_ne!(second, "first");
+
+        assert_eq!(refresh_with(&state, "first").await, Err(StatusCode::UNAUTHORIZED));
+        assert_eq!(user_token(&state).await, None);
+        assert_eq!(refresh_with(&state, &second).await, Err(StatusCode::UNAUTHORIZED));
+    }
+}
+
 pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
     router
 }
\ No newline at end of file
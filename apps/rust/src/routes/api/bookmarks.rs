@@ -0,0 +1,168 @@
+//! Bookmarks with resume progress for the signed-in user.
+//!
+//! All three routes sit behind [`require_auth`](crate::middleware::auth::require_auth)
+//! and only ever see the caller's own bookmarks.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::core::error::AppError;
+use crate::entities::bookmarks;
+use crate::middleware::auth::CurrentUser;
+use crate::routes::AppState;
+use crate::services::bookmarks::{self as service, BookmarkKind, NewBookmark};
+
+/// Bookmark to create, or progress to record on an existing one.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BookmarkRequest {
+    pub kind: BookmarkKind,
+    /// Anime or komik slug.
+    pub slug: String,
+    /// Episode or chapter slug/number the user is at.
+    pub episode_or_chapter: Option<String>,
+    /// Kept as stored when omitted.
+    pub title: Option<String>,
+    /// Kept as stored when omitted.
+    pub poster: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BookmarkResponse {
+    pub id: String,
+    pub kind: BookmarkKind,
+    pub slug: String,
+    pub episode_or_chapter: Option<String>,
+    pub title: String,
+    pub poster: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl BookmarkResponse {
+    /// `None` for rows whose `content_type` predates the anime/komik kinds.
+    fn from_model(model: bookmarks::Model) -> Option<Self> {
+        Some(Self {
+            kind: BookmarkKind::parse(&model.content_type)?,
+            id: model.id,
+            slug: model.slug,
+            episode_or_chapter: model.episode_or_chapter,
+            title: model.title,
+            poster: model.poster,
+            updated_at: model.updated_at,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct BookmarkListQuery {
+    /// Only bookmarks of this kind (`anime` or `komik`).
+    pub kind: Option<BookmarkKind>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/bookmarks",
+    tag = "bookmarks",
+    operation_id = "bookmarks_upsert",
+    security(("bearer_auth" = [])),
+    request_body = BookmarkRequest,
+    responses(
+        (status = 200, description = "Bookmark created, or its progress updated", body = BookmarkResponse),
+        (status = 400, description = "Empty slug", body = String),
+        (status = 401, description = "Missing, expired or invalid token"),
+        (status = 500, description = "Internal Server Error", body = String)
+    )
+)]
+pub async fn upsert_bookmark(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Json(payload): Json<BookmarkRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let slug = payload.slug.trim().to_string();
+    if slug.is_empty() {
+        return Err(AppError::BadRequest("slug must not be empty".to_string()));
+    }
+
+    let bookmark = NewBookmark {
+        kind: payload.kind,
+        slug,
+        episode_or_chapter: payload.episode_or_chapter,
+        title: payload.title,
+        poster: payload.poster,
+    };
+    let saved = service::upsert(state.sea_orm(), &user.user_id, bookmark)
+        .await
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    BookmarkResponse::from_model(saved)
+        .map(Json)
+        .ok_or_else(|| AppError::Other("Saved bookmark has an unknown kind".to_string()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/bookmarks",
+    tag = "bookmarks",
+    operation_id = "bookmarks_list",
+    security(("bearer_auth" = [])),
+    params(BookmarkListQuery),
+    responses(
+        (status = 200, description = "The caller's bookmarks, most recently updated first", body = Vec<BookmarkResponse>),
+        (status = 401, description = "Missing, expired or invalid token"),
+        (status = 500, description = "Internal Server Error", body = String)
+    )
+)]
+pub async fn list_bookmarks(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Query(query): Query<BookmarkListQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let rows = service::list(state.sea_orm(), &user.user_id, query.kind)
+        .await
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    let bookmarks: Vec<BookmarkResponse> = rows.into_iter().filter_map(BookmarkResponse::from_model).collect();
+    Ok(Json(bookmarks))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/bookmarks/{id}",
+    tag = "bookmarks",
+    operation_id = "bookmarks_delete",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Bookmark ID")
+    ),
+    responses(
+        (status = 204, description = "Bookmark deleted"),
+        (status = 401, description = "Missing, expired or invalid token"),
+        (status = 404, description = "No such bookmark for this user"),
+        (status = 500, description = "Internal Server Error", body = String)
+    )
+)]
+pub async fn delete_bookmark(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let deleted = service::delete(state.sea_orm(), &user.user_id, &id)
+        .await
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    if !deleted {
+        return Err(AppError::NotFound("Bookmark not found".to_string()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
+    router
+}
\ No newline at end of file
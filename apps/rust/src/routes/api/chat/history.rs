@@ -0,0 +1,149 @@
+//! Handler for paginated chat room history.
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::core::error::AppError;
+use crate::entities::chat_message_room;
+use crate::middleware::auth::CurrentUser;
+use crate::routes::AppState;
+use crate::services::chat as chat_service;
+
+const DEFAULT_LIMIT: u64 = 50;
+
+#[derive(Deserialize, IntoParams, ToSchema, Debug)]
+pub struct HistoryQuery {
+    /// Room to read.
+    pub room: String,
+    /// Message id from a previous `next_cursor`; omit for the newest page.
+    pub before: Option<String>,
+    /// Page size (1-100, default 50).
+    pub limit: Option<u64>,
+}
+
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct ChatHistoryMessage {
+    pub id: String,
+    pub room_id: String,
+    pub user_id: String,
+    pub content: String,
+    pub created_at: String,
+}
+
+impl From<chat_message_room::Model> for ChatHistoryMessage {
+    fn from(row: chat_message_room::Model) -> Self {
+        Self {
+            id: row.id,
+            room_id: row.room_id,
+            user_id: row.user_id,
+            content: row.content,
+            created_at: row.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema, Debug, Clone)]
+pub struct ChatHistoryResponse {
+    pub room: String,
+    /// Oldest first.
+    pub messages: Vec<ChatHistoryMessage>,
+    /// Pass as `before` to load older messages; null when there are none.
+    pub next_cursor: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/chat/history",
+    tag = "chat",
+    operation_id = "chat_history",
+    security(("bearer_auth" = [])),
+    params(HistoryQuery),
+    responses(
+        (status = 200, description = "A page of room history older than the cursor", body = ChatHistoryResponse),
+        (status = 400, description = "Missing room"),
+        (status = 401, description = "Missing, expired or invalid token"),
+        (status = 403, description = "Private room the caller is not a member of, or no such room"),
+        (status = 500, description = "Internal Server Error", body = String)
+    )
+)]
+pub async fn history(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Query(query): Query<HistoryQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    if query.room.trim().is_empty() {
+        return Err(AppError::BadRequest("room is required".to_string()));
+    }
+    let allowed = chat_service::can_join_room(state.sea_orm(), &query.room, Some(&user.user_id))
+        .await
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    if !allowed {
+        return Err(AppError::Forbidden);
+    }
+
+    let page = chat_service::load_messages_paginated(
+        state.sea_orm(),
+        &query.room,
+        query.before.as_deref(),
+        query.limit.unwrap_or(DEFAULT_LIMIT),
+    )
+    .await
+    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    Ok(Json(ChatHistoryResponse {
+        room: query.room,
+        messages: page.messages.into_iter().map(Into::into).collect(),
+        next_cursor: page.next_cursor,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::chat_room;
+    use crate::testing::app::test_state;
+    use axum::http::StatusCode;
+    use sea_orm::{DatabaseBackend, MockDatabase, Value};
+    use std::collections::BTreeMap;
+
+    #[tokio::test]
+    async fn non_members_cannot_read_private_rooms() {
+        let at = chrono::Utc::now();
+        let db = MockDatabase::new(DatabaseBackend::MySql)
+            .append_query_results([vec![chat_room::Model {
+                id: "staff".to_string(),
+                name: "staff".to_string(),
+                description: None,
+                is_private: 1,
+                created_at: at,
+                updated_at: at,
+            }]])
+            .append_query_results([vec![BTreeMap::from([(
+                "num_items".to_string(),
+                Value::Int(Some(0)),
+            )])]])
+            .into_connection();
+        let user = CurrentUser {
+            user_id: "outsider".to_string(),
+            email: "outsider@example.com".to_string(),
+            name: "outsider".to_string(),
+        };
+        let query = HistoryQuery { room: "staff".to_string(), before: None, limit: None };
+
+        let err = history(State(test_state(db)), user, Query(query))
+            .await
+            .err()
+            .expect("a non-member read a private room");
+        assert_eq!(err.into_response().status(), StatusCode::FORBIDDEN);
+    }
+}
+
+pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
+    router
+}
\ No newline at end of file
@@ -0,0 +1,14 @@
+/// THIS FILE IS AUTOMATICALLY GENERATED BY build.rs
+/// DO NOT EDIT THIS FILE MANUALLY
+
+pub mod history;
+pub mod messages;
+
+/// Register routes for this directory
+use axum::Router;
+use std::sync::Arc;
+use crate::routes::AppState;
+
+pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
+    history::register_routes(messages::register_routes(router))
+}
@@ -1,8 +1,9 @@
 //! Handler for the komik chapter endpoint.
 
-use crate::helpers::{internal_err, Cache, fetch_html_with_retry, parse_html};
+use crate::core::error::AppError;
+use crate::helpers::{upstream_err, Cache, fetch_html_with_retry, parse_html};
 use crate::services::images::cache::cache_image_urls_batch_lazy;
-use crate::helpers::scraping::{selector, text, attr};
+use crate::helpers::scraping::{cached_selector, selectors, text, attr};
 use crate::routes::AppState;
 use crate::scraping::urls::get_komik_url;
 use axum::extract::State;
@@ -16,13 +17,40 @@ use tracing::{info};
 use utoipa::ToSchema;
 
 
+/// How a reader should lay out chapter pages.
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadingMode {
+    /// Long strip, top to bottom (manhwa, manhua).
+    Vertical,
+    /// One page at a time, right to left (manga).
+    #[default]
+    Paged,
+}
+
+impl ReadingMode {
+    /// Reading mode for a komik type such as `manga`, `manhwa` or `manhua`.
+    pub fn for_komik_type(komik_type: &str) -> Self {
+        match komik_type.trim().to_ascii_lowercase().as_str() {
+            "manhwa" | "manhua" | "webtoon" => ReadingMode::Vertical,
+            _ => ReadingMode::Paged,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
 pub struct ChapterData {
     pub title: String,
     pub next_chapter_id: String,
     pub prev_chapter_id: String,
     pub list_chapter: String,
+    /// Page images in reading order.
     pub images: Vec<String>,
+    /// `manga`, `manhwa` or `manhua` when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub komik_type: Option<String>,
+    #[serde(default)]
+    pub reading_mode: ReadingMode,
 }
 
 #[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
@@ -35,20 +63,40 @@ pub struct ChapterResponse {
 pub struct ChapterQuery {
     /// URL-friendly identifier for the chapter (typically the chapter slug or URL path)
     pub chapter_url: Option<String>,
+    /// Komik type (`manga`, `manhwa`, `manhua`); overrides the type detected on the page.
+    #[serde(rename = "type")]
+    pub komik_type: Option<String>,
 }
 
 const CACHE_TTL: u64 = 300; // 5 minutes
 
+/// Page image selectors, tried in order until one yields images. The reader
+/// container has been renamed before, so older layouts stay as fallbacks.
+const IMAGE_SELECTORS: [&str; 3] = [
+    "#Baca_Komik img",
+    "#chimg-auh img",
+    "#readerarea img, .main-reading-area img",
+];
+
+/// Attributes that may carry a page URL, in order. Lazy loaders leave a
+/// placeholder in `src` and put the real URL in one of the others.
+const IMAGE_ATTRS: [&str; 3] = ["src", "data-src", "data-lazy-src"];
+
+/// Start of the error returned when no selector finds a page image.
+const NO_CHAPTER_IMAGES: &str = "No chapter images found";
+
 #[utoipa::path(
     get,
     params(
-        ("chapter_url" = Option<String>, Query, description = "Chapter-specific identifier", example = "sample_value")
+        ("chapter_url" = Option<String>, Query, description = "Chapter-specific identifier", example = "sample_value"),
+        ("type" = Option<String>, Query, description = "Komik type used to pick the reading mode", example = "manhwa")
     ),
     path = "/api/komik/chapter",
     tag = "komik",
     operation_id = "komik_chapter",
     responses(
         (status = 200, description = "Retrieves chapter data for a specific komik chapter.", body = ChapterResponse),
+        (status = 502, description = "The chapter page had no recognizable page images", body = String),
         (status = 500, description = "Internal Server Error", body = String)
     )
 )]
@@ -62,11 +110,10 @@ pub async fn chapter(
     let cache_key = format!("komik:chapter:{}", chapter_url);
     let cache = Cache::new(&app_state.redis_pool);
 
-    let response = cache
-        .get_or_set(&cache_key, CACHE_TTL, || async {
+    let mut response = cache
+        .get_or_fetch(&cache_key, CACHE_TTL, || async {
             let mut data = fetch_komik_chapter(chapter_url.clone())
-                .await
-                .map_err(|e| e.to_string())?;
+                .await?;
 
             // Cache all images in background (lazy)
             // This returns original URLs immediately but triggers caching for next time
@@ -84,7 +131,15 @@ pub async fn chapter(
             })
         })
         .await
-        .map_err(|e| internal_err(&e))?;
+        .map_err(|e| match e {
+            AppError::Other(msg) if msg.starts_with(NO_CHAPTER_IMAGES) => (StatusCode::BAD_GATEWAY, msg),
+            e => upstream_err(&e),
+        })?;
+
+    if let Some(komik_type) = params.komik_type.filter(|t| !t.trim().is_empty()) {
+        response.data.reading_mode = ReadingMode::for_komik_type(&komik_type);
+        response.data.komik_type = Some(komik_type.to_ascii_lowercase());
+    }
 
     Ok(Json(response))
 }
@@ -111,13 +166,14 @@ fn parse_komik_chapter_document(
     let _start_time = std::time::Instant::now();
     info!("Starting to parse komik chapter document");
 
-    let title_selector = selector("title").unwrap();
-    let prev_chapter_selector = selector(".nxpr a:not(.rl):not([href*='#Chapter']), .chprev a, a.prev").unwrap();
-    let next_chapter_selector = selector(".nxpr a.rl, .nxpr a.next, .chnext a, a.next").unwrap();
-    let image_selector = selector("#Baca_Komik img").unwrap();
+    selectors! {
+        TITLE_SELECTOR = "title";
+        PREV_CHAPTER_SELECTOR = ".nxpr a:not(.rl):not([href*='#Chapter']), .chprev a, a.prev";
+        NEXT_CHAPTER_SELECTOR = ".nxpr a.rl, .nxpr a.next, .chnext a, a.next";
+    }
 
     let title = document
-        .select(&title_selector)
+        .select(&TITLE_SELECTOR)
         .next()
         .map(|e| {
             let full_title = text(&e);
@@ -135,7 +191,7 @@ fn parse_komik_chapter_document(
         .unwrap_or_default();
 
     let next_chapter_id = document
-        .select(&next_chapter_selector)
+        .select(&NEXT_CHAPTER_SELECTOR)
         .next()
         .and_then(|e| attr(&e, "href"))
         .map(|href| {
@@ -193,7 +249,7 @@ fn parse_komik_chapter_document(
     } else {
         // Fall back to HTML parsing if URL pattern doesn't match
         document
-            .select(&prev_chapter_selector)
+            .select(&PREV_CHAPTER_SELECTOR)
             .next()
             .and_then(|e| attr(&e, "href"))
             .map(|href| {
@@ -214,8 +270,31 @@ fn parse_komik_chapter_document(
 
     let list_chapter = get_list_chapter_from_url(chapter_url);
 
-    let mut images = Vec::new();
-    let forbidden_images = [
+    let images = extract_chapter_images(&document);
+    if images.is_empty() {
+        return Err(format!("{} on {}", NO_CHAPTER_IMAGES, chapter_url).into());
+    }
+
+    let komik_type = detect_komik_type(&document);
+    let reading_mode = komik_type
+        .as_deref()
+        .map(ReadingMode::for_komik_type)
+        .unwrap_or_default();
+
+    Ok(ChapterData {
+        title,
+        next_chapter_id,
+        prev_chapter_id,
+        list_chapter,
+        images,
+        komik_type,
+        reading_mode,
+    })
+}
+
+/// Whether `src` is a placeholder rather than a page image.
+fn is_placeholder(src: &str) -> bool {
+    const FORBIDDEN_IMAGES: [&str; 7] = [
         "https://flagcdn.com/32x24/jp.png",
         "https://flagcdn.com/32x24/kr.png",
         "https://flagcdn.com/32x24/cn.png",
@@ -224,30 +303,151 @@ fn parse_komik_chapter_document(
         "/asset/img/komikuplus2.jpg",
         "https://komiku.org/asset/img/Loading.gif",
     ];
-    for el in document.select(&image_selector) {
-        if let Some(src) = attr(&el, "src")
-            .or_else(|| attr(&el, "data-src"))
-            .or_else(|| attr(&el, "data-lazy-src"))
-            .or_else(|| {
-                attr(&el, "srcset")
-                    .and_then(|s| s.split_whitespace().next().map(|s| s.to_string()))
-            })
-        {
-            if !forbidden_images.contains(&src.as_str()) {
-                images.push(src);
+    src.is_empty() || src.starts_with("data:") || FORBIDDEN_IMAGES.contains(&src)
+}
+
+/// Page URL of one `<img>`: the first of [`IMAGE_ATTRS`] that isn't a
+/// placeholder, then the first `srcset` candidate.
+fn image_source(el: &scraper::ElementRef) -> Option<String> {
+    IMAGE_ATTRS
+        .iter()
+        .filter_map(|name| attr(el, name))
+        .map(|src| src.trim().to_string())
+        .find(|src| !is_placeholder(src))
+        .or_else(|| {
+            attr(el, "srcset")
+                .and_then(|s| s.split_whitespace().next().map(|s| s.to_string()))
+                .filter(|src| !is_placeholder(src))
+        })
+}
+
+/// Page images in reading order from the first of [`IMAGE_SELECTORS`] that
+/// yields any.
+fn extract_chapter_images(document: &scraper::Html) -> Vec<String> {
+    for css in IMAGE_SELECTORS {
+        let image_selector = cached_selector(css);
+        // (explicit page number, document position, url)
+        let mut pages: Vec<(Option<u32>, usize, String)> = Vec::new();
+        for (position, el) in document.select(&image_selector).enumerate() {
+            if let Some(src) = image_source(&el) {
+                if !pages.iter().any(|(_, _, u)| *u == src) {
+                    let page = attr(&el, "data-index")
+                        .or_else(|| attr(&el, "id"))
+                        .and_then(|v| v.trim().parse::<u32>().ok());
+                    pages.push((page, position, src));
+                }
             }
         }
+        if pages.is_empty() {
+            continue;
+        }
+        // Lazy loaders sometimes emit pages out of order; trust explicit page
+        // numbers only when every image has one.
+        if pages.iter().all(|(page, _, _)| page.is_some()) {
+            pages.sort_by_key(|(page, position, _)| (*page, *position));
+        }
+        return pages.into_iter().map(|(_, _, url)| url).collect();
     }
+    Vec::new()
+}
 
-    Ok(ChapterData {
-        title,
-        next_chapter_id,
-        prev_chapter_id,
-        list_chapter,
-        images,
+/// Komiku marks the origin with a country flag: jp = manga, kr = manhwa, cn = manhua.
+fn detect_komik_type(document: &scraper::Html) -> Option<String> {
+    selectors! {
+        FLAG_SELECTOR = "img[src*='flagcdn.com']";
+    }
+    document.select(&FLAG_SELECTOR).find_map(|flag| {
+        let src = attr(&flag, "src")?;
+        let komik_type = if src.ends_with("/jp.png") {
+            "manga"
+        } else if src.ends_with("/kr.png") {
+            "manhwa"
+        } else if src.ends_with("/cn.png") {
+            "manhua"
+        } else {
+            return None;
+        };
+        Some(komik_type.to_string())
     })
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapter_page(flag: &str, images: &str) -> String {
+        format!(
+            r#"<html><head><title>Chapter 5 | Komik Solo Leveling - Komiku</title></head><body>
+            <img src="https://flagcdn.com/32x24/{}.png">
+            <div id="Baca_Komik">{}</div></body></html>"#,
+            flag, images
+        )
+    }
+
+    #[test]
+    fn manhwa_chapter_reads_vertically() {
+        let html = chapter_page("kr", r#"<img src="https://img.test/1.jpg">"#);
+        let data = parse_komik_chapter_document(&html, "solo-leveling-chapter-5").unwrap();
+        assert_eq!(data.komik_type.as_deref(), Some("manhwa"));
+        assert_eq!(data.reading_mode, ReadingMode::Vertical);
+    }
+
+    #[test]
+    fn manga_chapter_is_paged_and_ordered() {
+        let html = chapter_page(
+            "jp",
+            r#"<img id="2" src="https://img.test/2.jpg">
+               <img id="1" src="https://img.test/1.jpg">
+               <img id="3" src="https://img.test/3.jpg">
+               <img id="4" src="https://img.test/1.jpg">"#,
+        );
+        let data = parse_komik_chapter_document(&html, "one-piece-chapter-5").unwrap();
+        assert_eq!(data.reading_mode, ReadingMode::Paged);
+        assert_eq!(
+            data.images,
+            ["https://img.test/1.jpg", "https://img.test/2.jpg", "https://img.test/3.jpg"]
+        );
+        assert_eq!(
+            serde_json::to_value(data.reading_mode).unwrap(),
+            serde_json::json!("paged")
+        );
+    }
+
+    #[test]
+    fn lazy_loaded_pages_use_the_data_attributes() {
+        let html = chapter_page(
+            "kr",
+            r#"<img src="https://komiku.org/asset/img/Loading.gif" data-src="https://img.test/1.jpg">
+               <img src="data:image/gif;base64,R0lGODlhAQABAAAAACw=" data-lazy-src="https://img.test/2.jpg">
+               <img data-src=" https://img.test/3.jpg ">"#,
+        );
+        let data = parse_komik_chapter_document(&html, "solo-leveling-chapter-5").unwrap();
+        assert_eq!(
+            data.images,
+            ["https://img.test/1.jpg", "https://img.test/2.jpg", "https://img.test/3.jpg"]
+        );
+    }
+
+    #[test]
+    fn alternate_container_is_used_when_the_primary_is_empty() {
+        let html = r#"<html><head><title>Chapter 5 | Komik Solo Leveling - Komiku</title></head><body>
+            <div id="Baca_Komik"><img src="https://komiku.org/asset/img/Loading.gif"></div>
+            <div id="chimg-auh">
+                <img class="lazy" src="data:image/png;base64,iVBORw0KGgo=" data-src="https://img.test/a.jpg">
+                <img class="lazy" data-src="https://img.test/b.jpg">
+            </div></body></html>"#;
+        let data = parse_komik_chapter_document(html, "solo-leveling-chapter-5").unwrap();
+        assert_eq!(data.images, ["https://img.test/a.jpg", "https://img.test/b.jpg"]);
+    }
+
+    #[test]
+    fn a_page_without_images_is_an_error() {
+        let html = chapter_page("jp", r#"<p>Gambar sedang dimuat</p>"#);
+        let err = parse_komik_chapter_document(&html, "one-piece-chapter-5").unwrap_err();
+        assert!(err.to_string().starts_with(NO_CHAPTER_IMAGES), "{}", err);
+    }
+}
+
 pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
     router
 }
\ No newline at end of file
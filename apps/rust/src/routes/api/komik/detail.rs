@@ -1,8 +1,8 @@
 //! Handler for the detail endpoint.
 
-use crate::helpers::{internal_err, Cache, fetch_html_with_retry, parse_html};
+use crate::helpers::{upstream_err, Cache, fetch_html_with_retry, parse_html};
 use crate::services::images::cache::get_cached_or_original;
-use crate::helpers::scraping::{selector, text_from_or, text, attr};
+use crate::helpers::scraping::{selectors, text_from_or, text, attr};
 use crate::routes::AppState;
 use crate::scraping::urls::get_komik_url;
 use axum::{
@@ -83,7 +83,9 @@ fn find_table_row_with_text<'a>(
 ) -> Option<String> {
     let lower_text_fragments: Vec<String> =
         text_fragments.iter().map(|&s| s.to_lowercase()).collect();
-    let td_last_selector = selector("td:last-child").unwrap();
+    selectors! {
+        TD_LAST_SELECTOR = "td:last-child";
+    }
 
     info_rows
         .iter() 
@@ -94,7 +96,7 @@ fn find_table_row_with_text<'a>(
                 .any(|fragment| row_text.contains(fragment))
         })
         .and_then(|row| {
-            text_from_or(row, &td_last_selector, "").trim().to_string().into()
+            text_from_or(row, &TD_LAST_SELECTOR, "").trim().to_string().into()
         })
 }
 
@@ -125,10 +127,9 @@ pub async fn detail(
     let cache = Cache::new(&app_state.redis_pool);
 
     let response = cache
-        .get_or_set(&cache_key, CACHE_TTL, || async {
+        .get_or_fetch(&cache_key, CACHE_TTL, || async {
             let mut data = fetch_komik_detail(komik_id.clone())
-                .await
-                .map_err(|e| e.to_string())?;
+                .await?;
 
             // Cache poster image
             if !data.poster.is_empty() {
@@ -144,7 +145,7 @@ pub async fn detail(
             Ok(DetailResponse { status: true, data })
         })
         .await
-        .map_err(|e| internal_err(&e))?;
+        .map_err(|e| upstream_err(&e))?;
 
     Ok(Json(response))
 }
@@ -194,21 +195,23 @@ fn parse_komik_detail_document(
         clean_text(full_text[default_index..].to_string())
     }
 
-    let title_selector = selector("div#Judul h1 span[itemprop=\"name\"]").unwrap();
-    let h1_selector = selector("h1").unwrap();
-    let title_tag_selector = selector("title").unwrap();
-    let info_row_selector = selector("table.inftable tr").unwrap();
-    let poster_selector = selector("section#Informasi .ims img").unwrap();
-    let desc_selector = selector("p.desc").unwrap();
-    let chapter_list_selector = selector("tbody#daftarChapter tr").unwrap();
-    let date_link_selector = selector("td.tanggalseries").unwrap();
-    let judul2_selector = selector("div.judul2").unwrap();
-    let genre_selector = selector("ul.genre li a").unwrap();
-    let chapter_link_selector = selector("td.judulseries a").unwrap();
+    selectors! {
+        TITLE_SELECTOR = "div#Judul h1 span[itemprop=\"name\"]";
+        H1_SELECTOR = "h1";
+        TITLE_TAG_SELECTOR = "title";
+        INFO_ROW_SELECTOR = "table.inftable tr";
+        POSTER_SELECTOR = "section#Informasi .ims img";
+        DESC_SELECTOR = "p.desc";
+        CHAPTER_LIST_SELECTOR = "tbody#daftarChapter tr";
+        DATE_LINK_SELECTOR = "td.tanggalseries";
+        JUDUL2_SELECTOR = "div.judul2";
+        GENRE_SELECTOR = "ul.genre li a";
+        CHAPTER_LINK_SELECTOR = "td.judulseries a";
+    }
 
     // Improved title extraction with fallback options
     let title = document
-        .select(&title_selector)
+        .select(&TITLE_SELECTOR)
         .next()
         .map(|e| {
             let text = clean_text(text(&e));
@@ -223,14 +226,14 @@ fn parse_komik_detail_document(
         .or_else(|| {
             // Fallback to try to extract title from h1 elements
             document
-                .select(&h1_selector)
+                .select(&H1_SELECTOR)
                 .next()
                 .map(|e| clean_text(text(&e)))
         })
         .or_else(|| {
             // Final fallback to document title
             document
-                .select(&title_tag_selector)
+                .select(&TITLE_TAG_SELECTOR)
                 .next()
                 .map(|e| {
                     let text = clean_text(text(&e));
@@ -244,7 +247,7 @@ fn parse_komik_detail_document(
         .unwrap_or_default();
 
     let info_rows_vec: Vec<scraper::ElementRef> = document
-        .select(&info_row_selector)
+        .select(&INFO_ROW_SELECTOR)
         .collect();
     let info_rows = &info_rows_vec[..]; 
 
@@ -313,14 +316,14 @@ fn parse_komik_detail_document(
         .unwrap_or_default();
 
     let poster = document
-        .select(&poster_selector)
+        .select(&POSTER_SELECTOR)
         .next()
         .and_then(|e| attr(&e, "src"))
         .map(|s| s.split('?').next().unwrap_or(&s).to_string())
         .unwrap_or_default();
 
     let description = document
-        .select(&desc_selector)
+        .select(&DESC_SELECTOR)
         .map(|e| clean_text(text(&e)))
         .filter(|t| t.len() > 50) // avoid tiny fragments
         .collect::<Vec<String>>()
@@ -334,10 +337,10 @@ fn parse_komik_detail_document(
         .unwrap_or_else(|| {
             // Fallback to last chapter date if no specific release date found
             document
-                .select(&chapter_list_selector)
+                .select(&CHAPTER_LIST_SELECTOR)
                 .next_back()
                 .and_then(|last| {
-                    last.select(&date_link_selector)
+                    last.select(&DATE_LINK_SELECTOR)
                         .next()
                 })
                 .map(|e| clean_text(text(&e)))
@@ -348,7 +351,7 @@ fn parse_komik_detail_document(
         .unwrap_or_else(|| {
             // Fallback to chapter count if no specific total found
             let count = document
-                .select(&chapter_list_selector)
+                .select(&CHAPTER_LIST_SELECTOR)
                 .count();
             if count > 0 {
                 count.to_string()
@@ -361,7 +364,7 @@ fn parse_komik_detail_document(
         .or_else(|| {
             // Look for updated date in the "judul2" class which contains "pembaca • X waktu lalu"
             document
-                .select(&judul2_selector)
+                .select(&JUDUL2_SELECTOR)
                 .next()
                 .map(|e| {
                     let text_str = clean_text(text(&e));
@@ -372,11 +375,11 @@ fn parse_komik_detail_document(
         .unwrap_or_else(|| {
             // Fallback to first chapter date if no specific updated date found
             document
-                .select(&chapter_list_selector)
+                .select(&CHAPTER_LIST_SELECTOR)
                 .next()
                 .and_then(|first| {
                     first
-                        .select(&date_link_selector)
+                        .select(&DATE_LINK_SELECTOR)
                         .next()
                 })
                 .map(|e| clean_text(text(&e)))
@@ -384,7 +387,7 @@ fn parse_komik_detail_document(
         });
 
     let mut genres = Vec::new();
-    for element in document.select(&genre_selector) {
+    for element in document.select(&GENRE_SELECTOR) {
         let genre = clean_text(text(&element));
         if !genre.is_empty() {
             genres.push(genre);
@@ -393,13 +396,13 @@ fn parse_komik_detail_document(
 
     // Optimized chapter parsing with refined selectors
     let raw_chapter_data: Vec<(String, String, String)> = document
-        .select(&chapter_list_selector)
+        .select(&CHAPTER_LIST_SELECTOR)
         .filter_map(|el| {
             let chapter_link_element = el
-                .select(&chapter_link_selector)
+                .select(&CHAPTER_LINK_SELECTOR)
                 .next();
             let date_element = el
-                .select(&date_link_selector)
+                .select(&DATE_LINK_SELECTOR)
                 .next();
 
             let chapter_text = chapter_link_element
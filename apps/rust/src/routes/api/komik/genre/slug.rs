@@ -1,38 +1,19 @@
-use crate::helpers::{internal_err, Cache, fetch_html_with_retry, parse_html};
-use crate::helpers::scraping::{selector, text_from_or, attr_from_or, text, attr};
+use crate::helpers::{upstream_err, Cache, fetch_html_with_retry};
+use crate::routes::api::komik::manga::slug::parse_manga_list_document;
 use crate::routes::AppState;
 use crate::scraping::urls::get_komik_api_url;
 use axum::extract::{Query, State};
 use axum::http::StatusCode;
 use axum::{extract::Path, response::IntoResponse, Json, Router};
 
-use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{info};
 use utoipa::ToSchema;
 
 
-#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
-pub struct KomikItem {
-    pub title: String,
-    pub slug: String,
-    pub poster: String,
-    pub chapter: String,
-    pub score: String,
-    pub r#type: String,
-    pub komik_url: String,
-}
-
-#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
-pub struct Pagination {
-    pub current_page: u32,
-    pub last_visible_page: u32,
-    pub has_next_page: bool,
-    pub next_page: Option<u32>,
-    pub has_previous_page: bool,
-    pub previous_page: Option<u32>,
-}
+/// Same cards and pagination as `GET /api/komik/manga`.
+pub use api_models::{KomikItem, Pagination};
 
 #[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
 pub struct GenreKomikResponse {
@@ -68,17 +49,16 @@ pub async fn slug(
     Path(genre_slug): Path<String>,
     Query(params): Query<GenreQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let page = params.page.unwrap_or(1);
+    let page = params.page.unwrap_or(1).max(1);
     info!("komik genre request: {}, page: {}", genre_slug, page);
 
-    let cache_key = format!("komik:genre:{}:{}:v2", genre_slug, page);
+    let cache_key = format!("komik:genre:{}:{}:v3", genre_slug, page);
     let cache = Cache::new(&app_state.redis_pool);
 
     let response = cache
-        .get_or_set(&cache_key, CACHE_TTL, || async {
+        .get_or_fetch(&cache_key, CACHE_TTL, || async {
             let (mut komik_list, pagination) = fetch_genre_komik(&genre_slug, page)
-                .await
-                .map_err(|e| e.to_string())?;
+                .await?;
 
             // Convert all poster URLs to CDN URLs
             // Fire-and-forget background caching for posters to ensure max API speed
@@ -108,7 +88,7 @@ pub async fn slug(
             })
         })
         .await
-        .map_err(|e| internal_err(&e))?;
+        .map_err(|e| upstream_err(&e))?;
 
     Ok(Json(response).into_response())
 }
@@ -130,97 +110,55 @@ async fn fetch_genre_komik(
 
     let html = fetch_html_with_retry(&url).await?;
     let (komik_list, pagination) =
-        tokio::task::spawn_blocking(move || parse_genre_page(&html, page)).await??;
+        tokio::task::spawn_blocking(move || parse_manga_list_document(&html, page)).await??;
 
     Ok((komik_list, pagination))
 }
 
-fn parse_genre_page(
-    html: &str,
-    current_page: u32,
-) -> Result<(Vec<KomikItem>, Pagination), Box<dyn std::error::Error + Send + Sync>> {
-    let document = parse_html(html);
-    let mut komik_list = Vec::new();
-
-    let item_selector = selector("article, .ls4, .ls2").unwrap();
-    let title_selector = selector("h3 a, h4 a").unwrap();
-    let img_selector = selector("img.lazy, img").unwrap();
-    let chapter_selector = selector(".ls4s a, .ls24, .ls2l a, .new1 a").unwrap();
-    let score_selector = selector(".up, .numscore, .epx").unwrap();
-    let type_selector = selector(".ls3p, .type").unwrap();
-    let link_selector = selector("h3 a, h4 a, a").unwrap();
-    let pagination_selector = selector(".paging a, .pagination a:not(.next)").unwrap();
-    let next_selector = selector(".paging a.next, .pagination .next").unwrap();
-    let slug_regex = Regex::new(r"/([^/]+)/?$").unwrap();
-
-    for element in document.select(&item_selector) {
-        let title = text_from_or(&element, &title_selector, "");
-
-        let poster = element
-            .select(&img_selector)
-            .next()
-            .and_then(|e| attr(&e, "data-src").or(attr(&e, "src")))
-            .unwrap_or_else(|| "".to_string())
-            .to_string();
-
-        let chapter = text_from_or(&element, &chapter_selector, "N/A");
-
-        let score = text_from_or(&element, &score_selector, "N/A");
-
-        let komik_type = text_from_or(&element, &type_selector, "Unknown");
-
-        let komik_url = attr_from_or(&element, &link_selector, "href", "");
-
-        let slug = slug_regex
-            .captures(&komik_url)
-            .and_then(|cap| cap.get(1))
-            .map(|m| m.as_str())
-            .unwrap_or("")
-            .to_string();
-
-        if !title.is_empty() {
-            komik_list.push(KomikItem {
-                title,
-                slug,
-                poster,
-                chapter,
-                score,
-                r#type: komik_type,
-                komik_url,
-            });
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn genre_page_parses_cards_and_pagination() {
+        let (items, pagination) =
+            parse_manga_list_document(include_str!("../../../../scraping/fixtures/komik_genre_action.html"), 2).unwrap();
+
+        assert_eq!(
+            items,
+            vec![
+                KomikItem {
+                    title: "One Piece".to_string(),
+                    poster: "https://thumbnail.komiku.org/uploads/manga/one-piece.jpg".to_string(),
+                    chapter: "Chapter 1130".to_string(),
+                    date: "2 hari lalu".to_string(),
+                    reader_count: "12jt pembaca".to_string(),
+                    r#type: "Manga".to_string(),
+                    slug: "one-piece-indonesia".to_string(),
+                },
+                KomikItem {
+                    title: "Solo Leveling".to_string(),
+                    poster: "https://thumbnail.komiku.org/uploads/manga/solo-leveling.jpg"
+                        .to_string(),
+                    chapter: "Chapter 200".to_string(),
+                    date: "1 tahun lalu".to_string(),
+                    reader_count: "8jt pembaca".to_string(),
+                    r#type: "Manhwa".to_string(),
+                    slug: "solo-leveling".to_string(),
+                },
+            ]
+        );
+        assert_eq!(pagination, Pagination::from_page(2, 3, true));
     }
 
-    let last_visible_page = document
-        .select(&pagination_selector)
-        .next_back()
-        .map(|e| {
-            text(&e)
-                .trim()
-                .parse::<u32>()
-                .unwrap_or(1)
-        })
-        .unwrap_or(1);
-
-    let has_next_page = document.select(&next_selector).next().is_some();
-    let pagination = Pagination {
-        current_page,
-        last_visible_page,
-        has_next_page,
-        next_page: if has_next_page {
-            Some(current_page + 1)
-        } else {
-            None
-        },
-        has_previous_page: current_page > 1,
-        previous_page: if current_page > 1 {
-            Some(current_page - 1)
-        } else {
-            None
-        },
-    };
+    #[test]
+    fn last_genre_page_has_no_next_page() {
+        let html = include_str!("../../../../scraping/fixtures/komik_genre_action.html").replace("hx-get", "data-done");
 
-    Ok((komik_list, pagination))
+        let (_, pagination) = parse_manga_list_document(&html, 4).unwrap();
+
+        assert_eq!(pagination, Pagination::from_page(4, 4, false));
+    }
 }
 
 pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
@@ -1,5 +1,5 @@
-use crate::helpers::{internal_err, Cache, fetch_html_with_retry, parse_html};
-use crate::helpers::scraping::{selector, text_from_or, attr_from};
+use crate::helpers::{upstream_err, Cache, fetch_html_with_retry, parse_html};
+use crate::helpers::scraping::{selectors, text_from_or, attr_from};
 use crate::routes::AppState;
 use crate::scraping::urls::get_komik_api_url;
 use axum::extract::State;
@@ -46,15 +46,15 @@ pub async fn genres(
     let cache = Cache::new(&app_state.redis_pool);
 
     let response = cache
-        .get_or_set(cache_key, CACHE_TTL, || async {
-            let genres = fetch_genres().await.map_err(|e| e.to_string())?;
+        .get_or_fetch(cache_key, CACHE_TTL, || async {
+            let genres = fetch_genres().await?;
             Ok(GenresResponse {
                 status: "Ok".to_string(),
                 data: genres,
             })
         })
         .await
-        .map_err(|e| internal_err(&e))?;
+        .map_err(|e| upstream_err(&e))?;
 
     Ok(Json(response).into_response())
 }
@@ -72,18 +72,20 @@ fn parse_genres(html: &str) -> Result<Vec<Genre>, Box<dyn std::error::Error + Se
     let document = parse_html(html);
     let mut genres = Vec::new();
 
-    let genre_selector = selector("#Genre .ls3, section#Genre .ls3, .ls3").unwrap();
-    let genre_name_selector = selector(".ls3p h4, h4").unwrap();
-    let genre_link_selector = selector("a[href*='/genre/']").unwrap();
+    selectors! {
+        GENRE_SELECTOR = "#Genre .ls3, section#Genre .ls3, .ls3";
+        GENRE_NAME_SELECTOR = ".ls3p h4, h4";
+        GENRE_LINK_SELECTOR = "a[href*='/genre/']";
+    }
     let slug_regex = Regex::new(r"/genre/([^/]+)").unwrap();
 
     // Each .ls3 contains a genre with image, name in h4, and link
-    for element in document.select(&genre_selector) {
+    for element in document.select(&GENRE_SELECTOR) {
         // Get name from h4 inside ls3p
-        let name = text_from_or(&element, &genre_name_selector, "");
+        let name = text_from_or(&element, &GENRE_NAME_SELECTOR, "");
 
         // Get link from a with /genre/ in href
-        let href = attr_from(&element, &genre_link_selector, "href").unwrap_or_default();
+        let href = attr_from(&element, &GENRE_LINK_SELECTOR, "href").unwrap_or_default();
 
         let slug = slug_regex
             .captures(&href)
@@ -105,6 +107,25 @@ fn parse_genres(html: &str) -> Result<Vec<Genre>, Box<dyn std::error::Error + Se
     Ok(genres)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn genre_index_parses_names_and_slugs() {
+        let genres = parse_genres(include_str!("../../../scraping/fixtures/komik_genres.html")).unwrap();
+
+        let pairs: Vec<_> = genres
+            .iter()
+            .map(|g| (g.name.as_str(), g.slug.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![("Action", "action"), ("Slice of Life", "slice-of-life")]
+        );
+    }
+}
+
 pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
     router
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
-use crate::helpers::{internal_err, Cache, fetch_html_with_retry, parse_html};
-use crate::helpers::scraping::{selector, text_from_or, text, attr};
+use crate::helpers::{upstream_err, Cache, fetch_html_with_retry, parse_html};
+use crate::helpers::scraping::{selectors, text_from_or, text, attr};
+use crate::core::error::AppError;
 use crate::routes::AppState;
 use crate::scraping::urls::get_komik_api_url;
 use axum::extract::State;
@@ -7,38 +8,14 @@ use axum::http::StatusCode;
 use axum::{extract::Query, response::IntoResponse, Json, Router};
 
 use regex::Regex;
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use std::sync::Arc;
 use tracing::{info};
 use utoipa::ToSchema;
 
 
-#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
-pub struct ManhwaItem {
-    pub title: String,
-    pub poster: String,
-    pub chapter: String,
-    pub date: String,
-    pub reader_count: String,
-    pub r#type: String,
-    pub slug: String,
-}
-
-#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
-pub struct Pagination {
-    pub current_page: u32,
-    pub last_visible_page: u32,
-    pub has_next_page: bool,
-    pub next_page: Option<u32>,
-    pub has_previous_page: bool,
-    pub previous_page: Option<u32>,
-}
-
-#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
-pub struct ManhwaResponse {
-    pub data: Vec<ManhwaItem>,
-    pub pagination: Pagination,
-}
+/// Listing types shared with the Leptos client.
+pub use api_models::{KomikItem as ManhwaItem, KomikList as ManhwaResponse, Pagination};
 
 #[derive(Deserialize, ToSchema)]
 pub struct QueryParams {
@@ -69,51 +46,58 @@ pub async fn list(
     let page = params.page.unwrap_or(1);
     info!("Starting manhwa list request for page {}", page);
 
-    let cache_key = format!("komik:manhwa:{}", page);
+    let cache_key = list_cache_key(page);
 
     let cache = Cache::new(&app_state.redis_pool);
 
     let response = cache
-        .get_or_set(&cache_key, CACHE_TTL, || async {
-            let base_api_url = get_komik_api_url();
-            let url = if page == 1 {
-                format!("{}/manga/?tipe=manhwa", base_api_url)
-            } else {
-                format!("{}/manga/page/{}/?tipe=manhwa", base_api_url, page)
-            };
-
-            let (mut data, pagination) = fetch_and_parse_manhwa_list(&url, page)
-                .await
-                .map_err(|e| e.to_string())?;
-
-            // Convert all poster URLs to CDN URLs
-            // Fire-and-forget background caching for posters to ensure max API speed
-            let db = app_state.db.clone();
-            let redis = app_state.redis_pool.clone();
-
-            let posters: Vec<String> = data.iter().map(|i| i.poster.clone()).collect();
-            let cached_posters = crate::services::images::cache::cache_image_urls_batch_lazy(
-                db,
-                &redis,
-                posters,
-                Some(app_state.image_processing_semaphore.clone()),
-            )
-            .await;
-
-            for (i, item) in data.iter_mut().enumerate() {
-                if let Some(url) = cached_posters.get(i) {
-                    item.poster = url.clone();
-                }
-            }
-
-            Ok(ManhwaResponse { data, pagination })
-        })
+        .get_or_fetch(&cache_key, CACHE_TTL, || build_manhwa_list(&app_state, page))
         .await
-        .map_err(|e| internal_err(&e))?;
+        .map_err(|e| upstream_err(&e))?;
 
     Ok(Json(response).into_response())
 }
 
+/// Cache key for page `page` of the manhwa list.
+pub fn list_cache_key(page: u32) -> String {
+    format!("komik:manhwa:{}", page)
+}
+
+/// Fetches page `page` of the manhwa list with poster URLs rewritten to the CDN.
+pub async fn build_manhwa_list(app_state: &AppState, page: u32) -> Result<ManhwaResponse, AppError> {
+    let base_api_url = get_komik_api_url();
+    let url = if page == 1 {
+        format!("{}/manga/?tipe=manhwa", base_api_url)
+    } else {
+        format!("{}/manga/page/{}/?tipe=manhwa", base_api_url, page)
+    };
+
+    let (mut data, pagination) = fetch_and_parse_manhwa_list(&url, page)
+        .await?;
+
+    // Convert all poster URLs to CDN URLs
+    // Fire-and-forget background caching for posters to ensure max API speed
+    let db = app_state.db.clone();
+    let redis = app_state.redis_pool.clone();
+
+    let posters: Vec<String> = data.iter().map(|i| i.poster.clone()).collect();
+    let cached_posters = crate::services::images::cache::cache_image_urls_batch_lazy(
+        db,
+        &redis,
+        posters,
+        Some(app_state.image_processing_semaphore.clone()),
+    )
+    .await;
+
+    for (i, item) in data.iter_mut().enumerate() {
+        if let Some(url) = cached_posters.get(i) {
+            item.poster = url.clone();
+        }
+    }
+
+    Ok(ManhwaResponse { data, pagination })
+}
+
 async fn fetch_and_parse_manhwa_list(
     url: &str,
     page: u32,
@@ -133,22 +117,26 @@ fn parse_manhwa_list_document(
     let document = parse_html(html);
     let mut data = Vec::new();
 
-    let animpost_selector = selector("div.bge, .listupd .bge").unwrap();
-    let title_selector = selector(".kan h3, .kan a h3, .tt h3").unwrap();
-    let img_selector = selector(".bgei img").unwrap();
-    let _chapter_selector = selector(".new1 a span:last-child, .new1 span, .lch").unwrap();
-    let date_selector = selector(".judul2, .kan span.judul2, .mdis .date").unwrap();
-    let type_selector = selector(".tpe1_inf b, .tpe1_inf span.type, .mdis .type").unwrap();
-    let link_selector = selector(".bgei a, .kan a").unwrap();
+    selectors! {
+        ANIMPOST_SELECTOR = "div.bge, .listupd .bge";
+        TITLE_SELECTOR = ".kan h3, .kan a h3, .tt h3";
+        IMG_SELECTOR = ".bgei img";
+        _CHAPTER_SELECTOR = ".new1 a span:last-child, .new1 span, .lch";
+        DATE_SELECTOR = ".judul2, .kan span.judul2, .mdis .date";
+        TYPE_SELECTOR = ".tpe1_inf b, .tpe1_inf span.type, .mdis .type";
+        LINK_SELECTOR = ".bgei a, .kan a";
+    }
     let chapter_regex = Regex::new(r"\d+(\.\d+)?").unwrap();
-    let next_page_span_selector = selector("body > span[hx-get]").unwrap();
+    selectors! {
+        NEXT_PAGE_SPAN_SELECTOR = "body > span[hx-get]";
+    }
     let page_number_regex = Regex::new(r"/page/(\d+)/").unwrap();
 
-    for element in document.select(&animpost_selector) {
-        let title = text_from_or(&element, &title_selector, "");
+    for element in document.select(&ANIMPOST_SELECTOR) {
+        let title = text_from_or(&element, &TITLE_SELECTOR, "");
 
         let mut poster = element
-            .select(&img_selector)
+            .select(&IMG_SELECTOR)
             .next()
             .and_then(|e| {
                 attr(&e, "src")
@@ -164,7 +152,7 @@ fn parse_manhwa_list_document(
 
         let chapter = {
             let mut found_chapter = String::new();
-            for chapter_element in element.select(&link_selector) {
+            for chapter_element in element.select(&LINK_SELECTOR) {
                 let text = text(&chapter_element);
                 if text.contains("Chapter") {
                     let processed_text = text
@@ -186,16 +174,16 @@ fn parse_manhwa_list_document(
             found_chapter
         };
 
-        let full_date_string = text_from_or(&element, &date_selector, "");
+        let full_date_string = text_from_or(&element, &DATE_SELECTOR, "");
 
         let parts: Vec<&str> = full_date_string.split(" • ").collect();
         let date = parts.get(1).unwrap_or(&"").to_string();
         let pembaca = parts.first().unwrap_or(&"").to_string();
 
-        let r#type = text_from_or(&element, &type_selector, "");
+        let r#type = text_from_or(&element, &TYPE_SELECTOR, "");
 
         let slug = element
-            .select(&link_selector)
+            .select(&LINK_SELECTOR)
             .next()
             .and_then(|e| attr(&e, "href"))
             .map(|href| {
@@ -222,39 +210,23 @@ fn parse_manhwa_list_document(
         });
     }
 
-    let has_previous_page = current_page > 1;
-    let previous_page = if has_previous_page {
-        Some(current_page - 1)
-    } else {
-        None
-    };
-
-    let mut has_next_page = false;
-    let mut next_page: Option<u32> = None;
-
-    if let Some(next_span) = document.select(&next_page_span_selector).next() {
-        if let Some(hx_get_url) = attr(&next_span, "hx-get") {
-            if let Some(captures) = page_number_regex.captures(&hx_get_url) {
-                if let Some(page_str) = captures.get(1) {
-                    if let Ok(page_num) = page_str.as_str().parse::<u32>() {
-                        has_next_page = true;
-                        next_page = Some(page_num);
-                    }
-                }
-            }
-        }
-    }
-
-    let last_visible_page = next_page.unwrap_or(current_page);
+    // The infinite-scroll loader only links the next page.
+    let next_page = document
+        .select(&NEXT_PAGE_SPAN_SELECTOR)
+        .next()
+        .and_then(|span| attr(&span, "hx-get"))
+        .and_then(|url| {
+            page_number_regex
+                .captures(&url)
+                .and_then(|c| c.get(1))
+                .and_then(|m| m.as_str().parse::<u32>().ok())
+        });
 
-    let pagination = Pagination {
+    let pagination = Pagination::from_page(
         current_page,
-        last_visible_page,
-        has_next_page,
-        next_page,
-        has_previous_page,
-        previous_page,
-    };
+        next_page.unwrap_or(current_page),
+        next_page.is_some(),
+    );
 
     Ok((data, pagination))
 }
@@ -1,5 +1,5 @@
-use crate::helpers::{internal_err, Cache, fetch_html_with_retry, parse_html};
-use crate::helpers::scraping::{selector, text_from_or, attr_from_or, text, attr};
+use crate::helpers::{upstream_err, Cache, fetch_html_with_retry, parse_html};
+use crate::helpers::scraping::{selectors, text_from_or, attr_from_or, text, attr};
 use crate::routes::AppState;
 use crate::scraping::urls::get_komik_api_url;
 use axum::extract::{Query, State};
@@ -78,10 +78,9 @@ pub async fn popular(
 
     let period_clone = period.clone();
     let response = cache
-        .get_or_set(&cache_key, CACHE_TTL, || async {
+        .get_or_fetch(&cache_key, CACHE_TTL, || async {
             let (mut komik_list, pagination) = fetch_popular_komik(page, &period)
-                .await
-                .map_err(|e| e.to_string())?;
+                .await?;
 
             // Convert all poster URLs to CDN URLs
             // Fire-and-forget background caching for posters to ensure max API speed
@@ -111,7 +110,7 @@ pub async fn popular(
             })
         })
         .await
-        .map_err(|e| internal_err(&e))?;
+        .map_err(|e| upstream_err(&e))?;
 
     Ok(Json(response).into_response())
 }
@@ -146,34 +145,36 @@ fn parse_popular_page(
     let mut komik_list = Vec::new();
     let mut rank: u32 = ((current_page - 1) * 20) + 1;
 
-    let item_selector = selector("article, .ls4, .ls2").unwrap();
-    let title_selector = selector("h3 a, h4 a").unwrap();
-    let img_selector = selector("img.lazy, img").unwrap();
-    let score_selector = selector(".up, .numscore, .epx").unwrap();
-    let chapter_selector = selector(".ls4s a, .ls24, .ls2l a, .new1 a").unwrap();
-    let type_selector = selector(".ls3p, .type").unwrap();
-    let link_selector = selector("h3 a, h4 a, a").unwrap();
-    let pagination_selector = selector(".paging a, .pagination a:not(.next)").unwrap();
-    let next_selector = selector(".paging a.next, .pagination .next").unwrap();
+    selectors! {
+        ITEM_SELECTOR = "article, .ls4, .ls2";
+        TITLE_SELECTOR = "h3 a, h4 a";
+        IMG_SELECTOR = "img.lazy, img";
+        SCORE_SELECTOR = ".up, .numscore, .epx";
+        CHAPTER_SELECTOR = ".ls4s a, .ls24, .ls2l a, .new1 a";
+        TYPE_SELECTOR = ".ls3p, .type";
+        LINK_SELECTOR = "h3 a, h4 a, a";
+        PAGINATION_SELECTOR = ".paging a, .pagination a:not(.next)";
+        NEXT_SELECTOR = ".paging a.next, .pagination .next";
+    }
     let slug_regex = Regex::new(r"/([^/]+)/?$").unwrap();
 
-    for element in document.select(&item_selector) {
-        let title = text_from_or(&element, &title_selector, "");
+    for element in document.select(&ITEM_SELECTOR) {
+        let title = text_from_or(&element, &TITLE_SELECTOR, "");
 
         let poster = element
-            .select(&img_selector)
+            .select(&IMG_SELECTOR)
             .next()
             .and_then(|e| attr(&e, "data-src").or(attr(&e, "src")))
             .unwrap_or_else(|| "".to_string())
             .to_string();
 
-        let score = text_from_or(&element, &score_selector, "N/A");
+        let score = text_from_or(&element, &SCORE_SELECTOR, "N/A");
 
-        let chapter = text_from_or(&element, &chapter_selector, "N/A");
+        let chapter = text_from_or(&element, &CHAPTER_SELECTOR, "N/A");
 
-        let komik_type = text_from_or(&element, &type_selector, "Manga");
+        let komik_type = text_from_or(&element, &TYPE_SELECTOR, "Manga");
 
-        let komik_url = attr_from_or(&element, &link_selector, "href", "");
+        let komik_url = attr_from_or(&element, &LINK_SELECTOR, "href", "");
 
         let slug = slug_regex
             .captures(&komik_url)
@@ -198,7 +199,7 @@ fn parse_popular_page(
     }
 
     let last_visible_page = document
-        .select(&pagination_selector)
+        .select(&PAGINATION_SELECTOR)
         .next_back()
         .map(|e| {
             text(&e)
@@ -208,7 +209,7 @@ fn parse_popular_page(
         })
         .unwrap_or(1);
 
-    let has_next_page = document.select(&next_selector).next().is_some();
+    let has_next_page = document.select(&NEXT_SELECTOR).next().is_some();
     let pagination = Pagination {
         current_page,
         last_visible_page,
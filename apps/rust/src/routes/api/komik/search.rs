@@ -1,16 +1,19 @@
-use crate::helpers::{internal_err, parse_html, Cache, fetch_html_with_retry};
-use crate::helpers::scraping::{selector, text_from_or, attr_from, attr_from_or, text};
+use crate::helpers::{upstream_err, parse_html, Cache, fetch_html_with_retry};
+use crate::helpers::scraping::{selectors, text_from_or, attr_from, attr_from_or, text};
 
 use crate::routes::AppState;
 use crate::scraping::urls::get_komik_api_url;
 use axum::http::StatusCode;
-use axum::{extract::Query, response::IntoResponse, Json, Router};
+use axum::{response::IntoResponse, Json, Router};
+use crate::extractors::validated::not_blank;
+use crate::extractors::ValidatedQuery;
 
 
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::info;
 use utoipa::ToSchema;
+use validator::Validate;
 
 
 #[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
@@ -24,15 +27,7 @@ pub struct MangaItem {
     pub slug: String,
 }
 
-#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
-pub struct Pagination {
-    pub current_page: u32,
-    pub last_visible_page: u32,
-    pub has_next_page: bool,
-    pub next_page: Option<u32>,
-    pub has_previous_page: bool,
-    pub previous_page: Option<u32>,
-}
+pub use crate::helpers::pagination::Pagination;
 
 #[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
 pub struct SearchResponse {
@@ -40,9 +35,14 @@ pub struct SearchResponse {
     pub pagination: Pagination,
 }
 
-#[derive(Deserialize, ToSchema)]
+#[derive(Deserialize, ToSchema, Validate)]
 pub struct SearchQuery {
-    pub query: Option<String>,
+    /// Search text; required and non-blank. Also accepted as `q`.
+    #[serde(default, alias = "q")]
+    #[validate(custom(function = "not_blank"))]
+    pub query: String,
+    /// Page number (starts from 1)
+    #[validate(range(min = 1, message = "page must be at least 1"))]
     pub page: Option<u32>,
 }
 
@@ -53,7 +53,7 @@ const CACHE_TTL: u64 = 300; // 5 minutes
 #[utoipa::path(
     get,
     params(
-        ("query" = Option<String>, Query, description = "Search parameter for filtering results", example = "sample_value"),
+        ("query" = String, Query, description = "Search parameter for filtering results", example = "sample_value"),
         ("page" = Option<u32>, Query, description = "Page number for pagination (starts from 1)", example = 1, minimum = 1)
     ),
     path = "/api/komik/search",
@@ -61,14 +61,15 @@ const CACHE_TTL: u64 = 300; // 5 minutes
     operation_id = "komik_search",
     responses(
         (status = 200, description = "Searches for komik based on query parameters.", body = SearchResponse),
+        (status = 422, description = "Missing or empty `query`, or `page` below 1"),
         (status = 500, description = "Internal Server Error", body = String)
     )
 )]
 pub async fn search(
     State(app_state): State<Arc<AppState>>,
-    Query(params): Query<SearchQuery>,
+    ValidatedQuery(params): ValidatedQuery<SearchQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let query = params.query.unwrap_or_default();
+    let query = params.query.trim().to_string();
     let page = params.page.unwrap_or(1);
     info!(
         "Starting komik search for query: '{}', page: {}",
@@ -79,25 +80,10 @@ pub async fn search(
     let cache = Cache::new(&app_state.redis_pool);
 
     let response = cache
-        .get_or_set(&cache_key, CACHE_TTL, || async {
-            let base_url = get_komik_api_url();
-            let url = if page == 1 {
-                format!(
-                    "{}/?post_type=manga&s={}",
-                    base_url,
-                    urlencoding::encode(&query)
-                )
-            } else {
-                format!(
-                    "{}/page/{}/?post_type=manga&s={}",
-                    base_url,
-                    page,
-                    urlencoding::encode(&query)
-                )
-            };
+        .get_or_fetch(&cache_key, CACHE_TTL, || async {
+            let url = search_url(&query, page);
             let (mut data, pagination) = fetch_and_parse_search(&url, page)
-                .await
-                .map_err(|e| e.to_string())?;
+                .await?;
 
             // Convert all poster URLs to CDN URLs
             // Fire-and-forget background caching for posters to ensure max API speed
@@ -122,12 +108,31 @@ pub async fn search(
             Ok(SearchResponse { data, pagination })
         })
         .await
-        .map_err(|e| internal_err(&e))?;
+        .map_err(|e| upstream_err(&e))?;
 
     Ok(Json(response).into_response())
 }
 
-async fn fetch_and_parse_search(
+/// The komik source's search page `page` for `query`.
+pub(crate) fn search_url(query: &str, page: u32) -> String {
+    let base_url = get_komik_api_url();
+    if page == 1 {
+        format!(
+            "{}/?post_type=manga&s={}",
+            base_url,
+            urlencoding::encode(query)
+        )
+    } else {
+        format!(
+            "{}/page/{}/?post_type=manga&s={}",
+            base_url,
+            page,
+            urlencoding::encode(query)
+        )
+    }
+}
+
+pub(crate) async fn fetch_and_parse_search(
     url: &str,
     page: u32,
 ) -> Result<(Vec<MangaItem>, Pagination), Box<dyn std::error::Error + Send + Sync>> {
@@ -147,32 +152,33 @@ fn parse_search_document(
     let document = parse_html(html);
     let mut data = Vec::new();
 
-    let animpost_selector = selector("div.bge, .listupd .bge").unwrap();
-    let title_selector = selector("div.kan h3, div.kan a h3, .tt h3").unwrap();
-    let img_selector = selector("div.bgei img").unwrap();
-    let chapter_selector = selector("div.new1 a span:last-child, .new1 span, .lch").unwrap();
-    let score_selector = selector(".up, .epx, .numscore").unwrap();
-    let date_selector = selector("div.kan span.judul2, .mdis .date").unwrap();
-    let type_selector = selector("div.tpe1_inf b, .tpe1_inf span.type, .mdis .type").unwrap();
-    let link_selector = selector("div.bgei a, div.kan a").unwrap();
-    let next_selector = selector(".pagination > a.next, .pagination > .next.page-numbers, .hpage .next").unwrap();
-    let prev_selector = selector(".pagination > a.prev, .pagination > .prev.page-numbers, .hpage .prev").unwrap();
-    let page_selectors = selector(".pagination > a, .pagination > .page-numbers:not(.next):not(.prev), .hpage a").unwrap();
+    selectors! {
+        ANIMPOST_SELECTOR = "div.bge, .listupd .bge";
+        TITLE_SELECTOR = "div.kan h3, div.kan a h3, .tt h3";
+        IMG_SELECTOR = "div.bgei img";
+        CHAPTER_SELECTOR = "div.new1 a span:last-child, .new1 span, .lch";
+        SCORE_SELECTOR = ".up, .epx, .numscore";
+        DATE_SELECTOR = "div.kan span.judul2, .mdis .date";
+        TYPE_SELECTOR = "div.tpe1_inf b, .tpe1_inf span.type, .mdis .type";
+        LINK_SELECTOR = "div.bgei a, div.kan a";
+        NEXT_SELECTOR = ".pagination > a.next, .pagination > .next.page-numbers, .hpage .next";
+        PAGE_SELECTORS = ".pagination > a, .pagination > .page-numbers:not(.next):not(.prev), .hpage a";
+    }
 
-    for element in document.select(&animpost_selector) {
-        let title = text_from_or(&element, &title_selector, "");
+    for element in document.select(&ANIMPOST_SELECTOR) {
+        let title = text_from_or(&element, &TITLE_SELECTOR, "");
 
-        let poster = attr_from_or(&element, &img_selector, "src", "");
+        let poster = attr_from_or(&element, &IMG_SELECTOR, "src", "");
 
-        let chapter = text_from_or(&element, &chapter_selector, "N/A");
+        let chapter = text_from_or(&element, &CHAPTER_SELECTOR, "N/A");
 
-        let score = text_from_or(&element, &score_selector, "N/A");
+        let score = text_from_or(&element, &SCORE_SELECTOR, "N/A");
 
-        let date = text_from_or(&element, &date_selector, "N/A");
+        let date = text_from_or(&element, &DATE_SELECTOR, "N/A");
 
-        let r#type = text_from_or(&element, &type_selector, "");
+        let r#type = text_from_or(&element, &TYPE_SELECTOR, "");
 
-        let slug = attr_from(&element, &link_selector, "href")
+        let slug = attr_from(&element, &LINK_SELECTOR, "href")
             .and_then(|href| href.split('/').nth(3).map(String::from))
             .unwrap_or_default();
 
@@ -191,37 +197,13 @@ fn parse_search_document(
 
     // Pagination logic
     let last_visible_page = document
-        .select(&page_selectors)
+        .select(&PAGE_SELECTORS)
         .last()
         .and_then(|e| text(&e).parse::<u32>().ok())
         .unwrap_or(current_page);
 
-    let has_next_page = document.select(&next_selector).next().is_some();
-    let next_page = if has_next_page {
-        Some(current_page + 1)
-    } else {
-        None
-    };
-
-    let has_previous_page = document.select(&prev_selector).next().is_some();
-    let previous_page = if has_previous_page {
-        if current_page > 1 {
-            Some(current_page - 1)
-        } else {
-            None
-        }
-    } else {
-        None
-    };
-
-    let pagination = Pagination {
-        current_page,
-        last_visible_page,
-        has_next_page,
-        next_page,
-        has_previous_page,
-        previous_page,
-    };
+    let has_next_page = document.select(&NEXT_SELECTOR).next().is_some();
+    let pagination = Pagination::from_page(current_page, last_visible_page, has_next_page);
 
     Ok((data, pagination))
 }
@@ -5,14 +5,25 @@ use axum::Router;
 use std::sync::Arc;
 use crate::routes::AppState;
 
+pub mod admin;
 pub mod anime;
 pub mod anime2;
 pub mod auth;
+pub mod bookmarks;
+pub mod chat;
 pub mod komik;
+pub mod preferences;
 pub mod proxy;
+pub mod search;
 pub mod social;
 pub mod tools;
 
+use crate::routes::api::admin::dashboard::DashboardResponse;
+use crate::routes::api::admin::dashboard::JobStatusEntry;
+use crate::routes::api::admin::dashboard::SourceStatus;
+use crate::routes::api::admin::dashboard::WebSocketSummary;
+use crate::routes::api::admin::migrations::MigrationsResponse;
+use crate::routes::api::admin::selectors::SelectorReloadResponse;
 use crate::routes::api::anime2::detail::slug::AnimeDetailData;
 use crate::routes::api::anime2::detail::slug::DetailResponse;
 use crate::routes::api::anime2::detail::slug::DownloadItem;
@@ -24,18 +35,20 @@ use crate::routes::api::anime2::filter::FiltersApplied;
 use crate::routes::api::anime2::genre::slug::GenreQuery;
 use crate::routes::api::anime2::genre_list::Genre as Genre_1;
 use crate::routes::api::anime2::genre_list::GenresResponse;
-use crate::routes::api::anime2::index::Anime2Data;
 use crate::routes::api::anime2::index::Anime2Response;
 use crate::routes::api::anime2::latest::LatestQuery;
 use crate::routes::api::anime2::search::SearchQuery;
 use crate::routes::api::anime::complete_anime::slug::CompleteAnimeItem;
 use crate::routes::api::anime::complete_anime::slug::ListResponse;
-use crate::routes::api::anime::complete_anime::slug::Pagination;
+use crate::routes::api::anime::detail::batch::BatchDetailError;
+use crate::routes::api::anime::detail::batch::BatchDetailRequest;
+use crate::routes::api::anime::detail::batch::BatchDetailResponse;
 use crate::routes::api::anime::detail::slug::AnimeDetailData as AnimeDetailData_1;
 use crate::routes::api::anime::detail::slug::DetailResponse as DetailResponse_1;
 use crate::routes::api::anime::detail::slug::EpisodeList;
 use crate::routes::api::anime::detail::slug::Genre as Genre_2;
 use crate::routes::api::anime::detail::slug::Recommendation as Recommendation_1;
+use crate::routes::api::anime::export::ExportQuery;
 use crate::routes::api::anime::full::slug::AnimeFullData;
 use crate::routes::api::anime::full::slug::AnimeInfo;
 use crate::routes::api::anime::full::slug::DownloadLink;
@@ -44,21 +57,17 @@ use crate::routes::api::anime::full::slug::FullResponse;
 use crate::routes::api::anime::genre::slug::AnimeItem;
 use crate::routes::api::anime::genre::slug::GenreAnimeResponse;
 use crate::routes::api::anime::genre::slug::GenreQuery as GenreQuery_1;
-use crate::routes::api::anime::genre::slug::Pagination as Pagination_1;
 use crate::routes::api::anime::genre_list::Genre as Genre_3;
 use crate::routes::api::anime::genre_list::GenresResponse as GenresResponse_1;
-use crate::routes::api::anime::index::AnimeData;
-use crate::routes::api::anime::index::CompleteAnimeItem as CompleteAnimeItem_1;
-use crate::routes::api::anime::index::OngoingAnimeItem;
 use crate::routes::api::anime::latest::LatestAnimeItem;
 use crate::routes::api::anime::latest::LatestAnimeResponse;
 use crate::routes::api::anime::latest::LatestQuery as LatestQuery_1;
-use crate::routes::api::anime::latest::Pagination as Pagination_2;
-use crate::routes::api::anime::ongoing_anime::slug::OngoingAnimeItem as OngoingAnimeItem_1;
+use crate::routes::api::anime::ongoing_anime::slug::OngoingAnimeItem;
 use crate::routes::api::anime::ongoing_anime::slug::OngoingAnimeResponse;
-use crate::routes::api::anime::ongoing_anime::slug::Pagination as Pagination_3;
+use crate::routes::api::anime::schedule::ScheduleItem;
+use crate::routes::api::anime::schedule::ScheduleResponse;
+use crate::routes::api::anime::schedule::WeeklySchedule;
 use crate::routes::api::anime::search::AnimeItem as AnimeItem_1;
-use crate::routes::api::anime::search::Pagination as Pagination_4;
 use crate::routes::api::anime::search::SearchQuery as SearchQuery_1;
 use crate::routes::api::anime::search::SearchResponse;
 use crate::routes::api::auth::change_password::ChangePasswordRequest;
@@ -82,6 +91,13 @@ use crate::routes::api::auth::reset_password::ResetPasswordResponse;
 use crate::routes::api::auth::verify::ResendVerificationRequest;
 use crate::routes::api::auth::verify::VerifyQuery;
 use crate::routes::api::auth::verify::VerifyResponse;
+use crate::routes::api::bookmarks::BookmarkListQuery;
+use crate::routes::api::bookmarks::BookmarkRequest;
+use crate::routes::api::bookmarks::BookmarkResponse;
+use crate::routes::api::chat::history::ChatHistoryMessage;
+use crate::routes::api::chat::history::ChatHistoryResponse;
+use crate::routes::api::chat::history::HistoryQuery;
+use crate::routes::api::chat::messages::MessageSearchQuery;
 use crate::routes::api::komik::chapter::ChapterData;
 use crate::routes::api::komik::chapter::ChapterQuery;
 use crate::routes::api::komik::chapter::ChapterResponse;
@@ -92,36 +108,29 @@ use crate::routes::api::komik::detail::DetailResponse as DetailResponse_2;
 use crate::routes::api::komik::detail::KomikDetailRequest;
 use crate::routes::api::komik::genre::slug::GenreKomikResponse;
 use crate::routes::api::komik::genre::slug::GenreQuery as GenreQuery_2;
-use crate::routes::api::komik::genre::slug::KomikItem;
-use crate::routes::api::komik::genre::slug::Pagination as Pagination_5;
 use crate::routes::api::komik::genre_list::Genre as Genre_4;
 use crate::routes::api::komik::genre_list::GenresResponse as GenresResponse_2;
-use crate::routes::api::komik::manga::slug::MangaItem;
-use crate::routes::api::komik::manga::slug::MangaResponse;
-use crate::routes::api::komik::manga::slug::Pagination as Pagination_6;
 use crate::routes::api::komik::manga::slug::QueryParams;
-use crate::routes::api::komik::manhua::slug::ManhuaItem;
-use crate::routes::api::komik::manhua::slug::ManhuaResponse;
-use crate::routes::api::komik::manhua::slug::Pagination as Pagination_7;
 use crate::routes::api::komik::manhua::slug::QueryParams as QueryParams_1;
-use crate::routes::api::komik::manhwa::slug::ManhwaItem;
-use crate::routes::api::komik::manhwa::slug::ManhwaResponse;
-use crate::routes::api::komik::manhwa::slug::Pagination as Pagination_8;
 use crate::routes::api::komik::manhwa::slug::QueryParams as QueryParams_2;
-use crate::routes::api::komik::popular::Pagination as Pagination_9;
+use crate::routes::api::komik::popular::Pagination;
 use crate::routes::api::komik::popular::PopularKomikItem;
 use crate::routes::api::komik::popular::PopularKomikResponse;
 use crate::routes::api::komik::popular::PopularQuery;
-use crate::routes::api::komik::search::MangaItem as MangaItem_1;
-use crate::routes::api::komik::search::Pagination as Pagination_10;
+use crate::routes::api::komik::search::MangaItem;
 use crate::routes::api::komik::search::SearchQuery as SearchQuery_2;
 use crate::routes::api::komik::search::SearchResponse as SearchResponse_1;
+use crate::routes::api::preferences::UpdatePreferencesRequest;
 use crate::routes::api::proxy::croxy::ProxyParams;
+use crate::routes::api::proxy::hls::StreamParams;
 use crate::routes::api::proxy::image_cache::ImageCacheBatchRequest;
 use crate::routes::api::proxy::image_cache::ImageCacheBatchResponse;
 use crate::routes::api::proxy::image_cache::ImageCacheRequest;
 use crate::routes::api::proxy::image_cache::ImageCacheResponse;
 use crate::routes::api::proxy::image_cache::ImageCacheResult;
+use crate::routes::api::proxy::imageproxy::ImageProxyParams;
+use crate::routes::api::search::SearchQuery as SearchQuery_3;
+use crate::routes::api::search::SearchResultsResponse;
 use crate::routes::api::social::CommentResponse;
 use crate::routes::api::social::CreatePostRequest;
 use crate::routes::api::social::LikeResponse;
@@ -129,18 +138,27 @@ use crate::routes::api::social::PostResponse;
 use crate::routes::api::social::UserResponse;
 use crate::routes::api::tools::compress::CompressData;
 use crate::routes::api::tools::compress::CompressQuery;
-use crate::routes::api::tools::drivepng::ListResponse as ListResponse_1;
-use crate::routes::api::tools::uploader::ListResponse as ListResponse_2;
+use crate::routes::api::tools::compress::CompressUpload;
+use crate::routes::api::tools::drivepng::DrivePngQuery;
+use crate::routes::api::tools::uploader::ListResponse as ListResponse_1;
+use crate::routes::api::tools::uploader::UploadResponse;
 
 #[derive(utoipa::OpenApi)]
     #[openapi(
         paths(
               crate::routes::api::tools::compress::compress,
+              crate::routes::api::tools::compress::compress_upload,
               crate::routes::api::tools::drivepng::drivepng,
               crate::routes::api::tools::uploader::uploader,
+              crate::routes::api::tools::uploader::upload,
+              crate::routes::api::tools::uploader::download,
+              crate::routes::api::tools::uploader::head,
               crate::routes::api::proxy::croxy::fetch_with_proxy_only,
+              crate::routes::api::proxy::hls::hls,
+              crate::routes::api::proxy::hls::videoproxy,
               crate::routes::api::proxy::image_cache::image_cache,
               crate::routes::api::proxy::image_cache::image_cache_batch,
+              crate::routes::api::proxy::imageproxy::imageproxy,
               crate::routes::api::komik::manhwa::slug::list,
               crate::routes::api::komik::manhua::slug::list,
               crate::routes::api::komik::manga::slug::list,
@@ -151,6 +169,8 @@ use crate::routes::api::tools::uploader::ListResponse as ListResponse_2;
               crate::routes::api::komik::genre_list::genres,
               crate::routes::api::komik::popular::popular,
               crate::routes::api::komik::search::search,
+              crate::routes::api::chat::history::history,
+              crate::routes::api::chat::messages::search,
               crate::routes::api::auth::change_password::change_password,
               crate::routes::api::auth::delete_account::delete_account,
               crate::routes::api::auth::forgot_password::forgot_password,
@@ -175,13 +195,27 @@ use crate::routes::api::tools::uploader::ListResponse as ListResponse_2;
               crate::routes::api::anime2::search::search,
               crate::routes::api::anime::ongoing_anime::slug::slug,
               crate::routes::api::anime::genre::slug::slug,
+              crate::routes::api::anime::genre::slug::page,
               crate::routes::api::anime::full::slug::slug,
+              crate::routes::api::anime::detail::batch::batch,
               crate::routes::api::anime::detail::slug::slug,
+              crate::routes::api::anime::detail::slug::head,
               crate::routes::api::anime::complete_anime::slug::slug,
               crate::routes::api::anime::index::anime,
+              crate::routes::api::anime::export::export,
               crate::routes::api::anime::genre_list::genres,
               crate::routes::api::anime::latest::latest,
+              crate::routes::api::anime::schedule::schedule,
               crate::routes::api::anime::search::search,
+              crate::routes::api::admin::dashboard::dashboard,
+              crate::routes::api::admin::migrations::migrations,
+              crate::routes::api::admin::selectors::reload,
+              crate::routes::api::bookmarks::upsert_bookmark,
+              crate::routes::api::bookmarks::list_bookmarks,
+              crate::routes::api::bookmarks::delete_bookmark,
+              crate::routes::api::preferences::get_preferences,
+              crate::routes::api::preferences::update_preferences,
+              crate::routes::api::search::search,
               crate::routes::api::social::get_posts,
               crate::routes::api::social::create_post,
               crate::routes::api::social::delete_post,
@@ -189,6 +223,12 @@ use crate::routes::api::tools::uploader::ListResponse as ListResponse_2;
         ),
         components(
             schemas(
+                  DashboardResponse,
+                  JobStatusEntry,
+                  SourceStatus,
+                  WebSocketSummary,
+                  MigrationsResponse,
+                  SelectorReloadResponse,
                   AnimeDetailData,
                   DetailResponse,
                   DownloadItem,
@@ -200,18 +240,20 @@ use crate::routes::api::tools::uploader::ListResponse as ListResponse_2;
                   GenreQuery,
                   Genre_1,
                   GenresResponse,
-                  Anime2Data,
                   Anime2Response,
                   LatestQuery,
                   SearchQuery,
                   CompleteAnimeItem,
                   ListResponse,
-                  Pagination,
+                  BatchDetailError,
+                  BatchDetailRequest,
+                  BatchDetailResponse,
                   AnimeDetailData_1,
                   DetailResponse_1,
                   EpisodeList,
                   Genre_2,
                   Recommendation_1,
+                  ExportQuery,
                   AnimeFullData,
                   AnimeInfo,
                   DownloadLink,
@@ -220,21 +262,17 @@ use crate::routes::api::tools::uploader::ListResponse as ListResponse_2;
                   AnimeItem,
                   GenreAnimeResponse,
                   GenreQuery_1,
-                  Pagination_1,
                   Genre_3,
                   GenresResponse_1,
-                  AnimeData,
-                  CompleteAnimeItem_1,
-                  OngoingAnimeItem,
                   LatestAnimeItem,
                   LatestAnimeResponse,
                   LatestQuery_1,
-                  Pagination_2,
-                  OngoingAnimeItem_1,
+                  OngoingAnimeItem,
                   OngoingAnimeResponse,
-                  Pagination_3,
+                  ScheduleItem,
+                  ScheduleResponse,
+                  WeeklySchedule,
                   AnimeItem_1,
-                  Pagination_4,
                   SearchQuery_1,
                   SearchResponse,
                   ChangePasswordRequest,
@@ -258,6 +296,13 @@ use crate::routes::api::tools::uploader::ListResponse as ListResponse_2;
                   ResendVerificationRequest,
                   VerifyQuery,
                   VerifyResponse,
+                  BookmarkListQuery,
+                  BookmarkRequest,
+                  BookmarkResponse,
+                  ChatHistoryMessage,
+                  ChatHistoryResponse,
+                  HistoryQuery,
+                  MessageSearchQuery,
                   ChapterData,
                   ChapterQuery,
                   ChapterResponse,
@@ -268,36 +313,29 @@ use crate::routes::api::tools::uploader::ListResponse as ListResponse_2;
                   KomikDetailRequest,
                   GenreKomikResponse,
                   GenreQuery_2,
-                  KomikItem,
-                  Pagination_5,
                   Genre_4,
                   GenresResponse_2,
-                  MangaItem,
-                  MangaResponse,
-                  Pagination_6,
                   QueryParams,
-                  ManhuaItem,
-                  ManhuaResponse,
-                  Pagination_7,
                   QueryParams_1,
-                  ManhwaItem,
-                  ManhwaResponse,
-                  Pagination_8,
                   QueryParams_2,
-                  Pagination_9,
+                  Pagination,
                   PopularKomikItem,
                   PopularKomikResponse,
                   PopularQuery,
-                  MangaItem_1,
-                  Pagination_10,
+                  MangaItem,
                   SearchQuery_2,
                   SearchResponse_1,
+                  UpdatePreferencesRequest,
                   ProxyParams,
+                  StreamParams,
                   ImageCacheBatchRequest,
                   ImageCacheBatchResponse,
                   ImageCacheRequest,
                   ImageCacheResponse,
                   ImageCacheResult,
+                  ImageProxyParams,
+                  SearchQuery_3,
+                  SearchResultsResponse,
                   CommentResponse,
                   CreatePostRequest,
                   LikeResponse,
@@ -305,14 +343,13 @@ use crate::routes::api::tools::uploader::ListResponse as ListResponse_2;
                   UserResponse,
                   CompressData,
                   CompressQuery,
+                  CompressUpload,
+                  DrivePngQuery,
                   ListResponse_1,
-                  ListResponse_2
+                  UploadResponse
             )
         ),
         modifiers(&SecurityAddon),
-        security((
-            "bearer_auth" = []
-        )),
         servers(
             (url = "https://ws.asepharyana.tech", description = "Production Server"),
             (url = "http://rust-api:4091", description = "Docker Service"),
@@ -321,15 +358,19 @@ use crate::routes::api::tools::uploader::ListResponse as ListResponse_2;
         info(
             title = "Freefire API",
             version = "0.0.1",
-            description = "Free API for anime, manga, and more"
+            description = "Free API for anime, manga, and more. Every `/api/...` path is also served at `/api/v1/...` and `/api/v2/...`; the unversioned paths are deprecated aliases of v1."
         ),
         tags(
-            (name = "auth", description = "Authentication endpoints")
+            (name = "auth", description = "Authentication endpoints"),
+            (name = "bookmarks", description = "Bookmarks and resume progress of the signed-in user"),
+            (name = "preferences", description = "UI preferences of the signed-in user")
         )
     )]
     #[allow(dead_code)]
     pub struct ApiDoc;
 
+    /// Registers the `bearer_auth` scheme that protected paths reference with
+    /// `security(("bearer_auth" = []))`; public paths declare no requirement.
     struct SecurityAddon;
 
     impl utoipa::Modify for SecurityAddon {
@@ -351,19 +392,31 @@ use crate::routes::api::tools::uploader::ListResponse as ListResponse_2;
 
 pub fn create_api_routes() -> Router<Arc<AppState>> {
     let mut router = Router::new();
+    router = admin::register_routes(router);
     router = anime::register_routes(router);
     router = anime2::register_routes(router);
     router = auth::register_routes(router);
+    router = bookmarks::register_routes(router);
+    router = chat::register_routes(router);
     router = komik::register_routes(router);
+    router = preferences::register_routes(router);
     router = proxy::register_routes(router);
+    router = search::register_routes(router);
     router = social::register_routes(router);
     router = tools::register_routes(router);
     router = router.route("/api/compress", axum::routing::get(crate::routes::api::tools::compress::compress));
+    router = router.route("/api/compress", axum::routing::post(crate::routes::api::tools::compress::compress_upload).layer(axum::extract::DefaultBodyLimit::max(crate::routes::api::tools::compress::MAX_UPLOAD_SIZE)));
     router = router.route("/api/drivepng", axum::routing::get(crate::routes::api::tools::drivepng::drivepng));
     router = router.route("/api/uploader", axum::routing::get(crate::routes::api::tools::uploader::uploader));
+    router = router.route("/api/uploader", axum::routing::post(crate::routes::api::tools::uploader::upload).layer(axum::extract::DefaultBodyLimit::max(crate::routes::api::tools::uploader::MAX_UPLOAD_SIZE)).route_layer(axum::middleware::from_fn(crate::middleware::auth::require_auth)));
+    router = router.route("/api/uploader/{*file_name}", axum::routing::get(crate::routes::api::tools::uploader::download));
+    router = router.route("/api/uploader/{*file_name}", axum::routing::head(crate::routes::api::tools::uploader::head));
     router = router.route("/api/proxy/croxy", axum::routing::get(crate::routes::api::proxy::croxy::fetch_with_proxy_only));
+    router = router.route("/api/hls", axum::routing::get(crate::routes::api::proxy::hls::hls));
+    router = router.route("/api/videoproxy", axum::routing::get(crate::routes::api::proxy::hls::videoproxy));
     router = router.route("/api/proxy/image-cache", axum::routing::post(crate::routes::api::proxy::image_cache::image_cache));
     router = router.route("/api/proxy/image-cache/batch", axum::routing::post(crate::routes::api::proxy::image_cache::image_cache_batch));
+    router = router.route("/api/imageproxy", axum::routing::get(crate::routes::api::proxy::imageproxy::imageproxy));
     router = router.route("/api/komik/manhwa", axum::routing::get(crate::routes::api::komik::manhwa::slug::list));
     router = router.route("/api/komik/manhua", axum::routing::get(crate::routes::api::komik::manhua::slug::list));
     router = router.route("/api/komik/manga", axum::routing::get(crate::routes::api::komik::manga::slug::list));
@@ -374,6 +427,8 @@ pub fn create_api_routes() -> Router<Arc<AppState>> {
     router = router.route("/api/komik/genres", axum::routing::get(crate::routes::api::komik::genre_list::genres));
     router = router.route("/api/komik/popular", axum::routing::get(crate::routes::api::komik::popular::popular));
     router = router.route("/api/komik/search", axum::routing::get(crate::routes::api::komik::search::search));
+    router = router.route("/api/chat/history", axum::routing::get(crate::routes::api::chat::history::history).route_layer(axum::middleware::from_fn(crate::middleware::auth::require_auth)));
+    router = router.route("/api/chat/messages", axum::routing::get(crate::routes::api::chat::messages::search).route_layer(axum::middleware::from_fn(crate::middleware::auth::require_auth)));
     router = router.route("/api/auth/change-password", axum::routing::post(crate::routes::api::auth::change_password::change_password));
     router = router.route("/api/auth/account", axum::routing::delete(crate::routes::api::auth::delete_account::delete_account));
     router = router.route("/api/auth/forgot-password", axum::routing::post(crate::routes::api::auth::forgot_password::forgot_password));
@@ -398,13 +453,27 @@ pub fn create_api_routes() -> Router<Arc<AppState>> {
     router = router.route("/api/anime2/search", axum::routing::get(crate::routes::api::anime2::search::search));
     router = router.route("/api/anime/ongoing-anime/{slug}", axum::routing::get(crate::routes::api::anime::ongoing_anime::slug::slug));
     router = router.route("/api/anime/genre/{slug}", axum::routing::get(crate::routes::api::anime::genre::slug::slug));
+    router = router.route("/api/anime/genre/{slug}/{page}", axum::routing::get(crate::routes::api::anime::genre::slug::page));
     router = router.route("/api/anime/full/{slug}", axum::routing::get(crate::routes::api::anime::full::slug::slug));
+    router = router.route("/api/anime/detail/batch", axum::routing::post(crate::routes::api::anime::detail::batch::batch));
     router = router.route("/api/anime/detail/{slug}", axum::routing::get(crate::routes::api::anime::detail::slug::slug));
+    router = router.route("/api/anime/detail/{slug}", axum::routing::head(crate::routes::api::anime::detail::slug::head));
     router = router.route("/api/anime/complete-anime/{slug}", axum::routing::get(crate::routes::api::anime::complete_anime::slug::slug));
     router = router.route("/api/anime", axum::routing::get(crate::routes::api::anime::index::anime));
+    router = router.route("/api/anime/export", axum::routing::get(crate::routes::api::anime::export::export));
     router = router.route("/api/anime/genres", axum::routing::get(crate::routes::api::anime::genre_list::genres));
     router = router.route("/api/anime/latest", axum::routing::get(crate::routes::api::anime::latest::latest));
+    router = router.route("/api/anime/schedule", axum::routing::get(crate::routes::api::anime::schedule::schedule));
     router = router.route("/api/anime/search", axum::routing::get(crate::routes::api::anime::search::search));
+    router = router.route("/api/admin/dashboard", axum::routing::get(crate::routes::api::admin::dashboard::dashboard));
+    router = router.route("/api/admin/migrations", axum::routing::get(crate::routes::api::admin::migrations::migrations));
+    router = router.route("/api/admin/selectors/reload", axum::routing::post(crate::routes::api::admin::selectors::reload));
+    router = router.route("/api/bookmarks", axum::routing::post(crate::routes::api::bookmarks::upsert_bookmark).route_layer(axum::middleware::from_fn(crate::middleware::auth::require_auth)));
+    router = router.route("/api/bookmarks", axum::routing::get(crate::routes::api::bookmarks::list_bookmarks).route_layer(axum::middleware::from_fn(crate::middleware::auth::require_auth)));
+    router = router.route("/api/bookmarks/{id}", axum::routing::delete(crate::routes::api::bookmarks::delete_bookmark).route_layer(axum::middleware::from_fn(crate::middleware::auth::require_auth)));
+    router = router.route("/api/me/preferences", axum::routing::get(crate::routes::api::preferences::get_preferences).route_layer(axum::middleware::from_fn(crate::middleware::auth::require_auth)));
+    router = router.route("/api/me/preferences", axum::routing::put(crate::routes::api::preferences::update_preferences).route_layer(axum::middleware::from_fn(crate::middleware::auth::require_auth)));
+    router = router.route("/api/search", axum::routing::get(crate::routes::api::search::search));
     router = router.route("/api/social/posts", axum::routing::get(crate::routes::api::social::get_posts));
     router = router.route("/api/social/posts", axum::routing::post(crate::routes::api::social::create_post));
     router = router.route("/api/social/posts/{id}", axum::routing::delete(crate::routes::api::social::delete_post));
@@ -0,0 +1,71 @@
+//! UI preferences of the signed-in user, so the web app's theme follows them
+//! across devices.
+//!
+//! Both routes sit behind [`require_auth`](crate::middleware::auth::require_auth).
+
+use axum::{extract::State, response::IntoResponse, Json, Router};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+use crate::core::error::AppError;
+use crate::middleware::auth::CurrentUser;
+use crate::routes::AppState;
+use crate::services::preferences::{self as service, Preferences, Theme};
+
+/// Preferences to save.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdatePreferencesRequest {
+    pub theme: Theme,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/me/preferences",
+    tag = "preferences",
+    operation_id = "preferences_get",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "The caller's preferences; defaults if never saved", body = Preferences),
+        (status = 401, description = "Missing, expired or invalid token"),
+        (status = 500, description = "Internal Server Error", body = String)
+    )
+)]
+pub async fn get_preferences(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+) -> Result<impl IntoResponse, AppError> {
+    let preferences = service::get(state.sea_orm(), &user.user_id)
+        .await
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    Ok(Json(preferences))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/me/preferences",
+    tag = "preferences",
+    operation_id = "preferences_update",
+    security(("bearer_auth" = [])),
+    request_body = UpdatePreferencesRequest,
+    responses(
+        (status = 200, description = "Preferences saved", body = Preferences),
+        (status = 401, description = "Missing, expired or invalid token"),
+        (status = 422, description = "Unknown theme"),
+        (status = 500, description = "Internal Server Error", body = String)
+    )
+)]
+pub async fn update_preferences(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Json(payload): Json<UpdatePreferencesRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let saved = service::set_theme(state.sea_orm(), &user.user_id, payload.theme)
+        .await
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    Ok(Json(saved))
+}
+
+pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
+    router
+}
\ No newline at end of file
@@ -8,7 +8,8 @@ use serde::Deserialize;
 use std::sync::Arc;
 use utoipa::ToSchema;
 
-use crate::infra::proxy::fetch_with_proxy;
+use crate::infra::proxy::{fetch_target_with_proxy, validate_target};
+use crate::infra::proxy_limits::ProxyLimits;
 use crate::routes::AppState;
 use crate::core::error::AppError;
 
@@ -29,16 +30,23 @@ pub struct ProxyParams {
     operation_id = "fetch_with_proxy_only",
     responses(
         (status = 200, description = "Handles GET requests for the proxy endpoint.", body = Vec<u8>),
-        (status = 500, description = "Internal Server Error", body = String)
+        (status = 403, description = "Target URL is not allowed", body = String),
+        (status = 413, description = "Upstream body exceeds the proxy size limit", body = String),
+        (status = 500, description = "Internal Server Error", body = String),
+        (status = 504, description = "Upstream did not respond in time", body = String)
     )
 )]
 pub async fn fetch_with_proxy_only(
     _: State<Arc<AppState>>,
     Query(params): Query<ProxyParams>,
 ) -> Result<Response, AppError> {
-    let slug = params.url;
-    match fetch_with_proxy(&slug).await {
+    let slug = validate_target(&params.url).await?.to_string();
+    // The fetch enforces the proxy timeout and body limit itself, so a fetch
+    // that overruns is dropped rather than left running to fill the cache.
+    match fetch_target_with_proxy(&slug).await {
         Ok(fetch_result) => {
+            // A gzipped upstream can still inflate past the limit.
+            ProxyLimits::from_config().check_len(&slug, Some(fetch_result.data.len() as u64))?;
             let mut response_builder = Response::builder().status(StatusCode::OK);
 
             if let Some(content_type) = fetch_result.content_type {
@@ -47,8 +55,16 @@ pub async fn fetch_with_proxy_only(
 
             Ok(response_builder.body(fetch_result.data.into())?)
         }
+        Err(e @ AppError::TimeoutError(_)) => {
+            tracing::warn!(url = %slug, error = %e, "Proxy fetch timed out");
+            Err(e)
+        }
+        Err(e @ AppError::BlockedTarget(_)) => {
+            tracing::warn!(url = %slug, error = %e, "Proxy target redirected somewhere it may not go");
+            Err(e)
+        }
         Err(e) => {
-            eprintln!("Proxy fetch error: {:?}", e);
+            tracing::error!(url = %slug, error = ?e, "Proxy fetch failed");
             Err(AppError::Other(format!(
                 "Failed to fetch URL via proxy: {}",
                 e
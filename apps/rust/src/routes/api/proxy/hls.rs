@@ -0,0 +1,361 @@
+//! HLS playlist and media proxy.
+//!
+//! Resolved stream URLs are often CORS-locked or only reachable from our
+//! network, so the browser player fetches them through us instead:
+//!
+//! - `GET /api/hls?url=` fetches an `.m3u8` and rewrites every URI in it
+//!   (variant and rendition playlists, segments, keys, init maps) to
+//!   `/api/videoproxy?url=<absolute upstream URL>`.
+//! - `GET /api/videoproxy?url=` streams the upstream bytes, forwarding `Range`.
+//!   When the upstream is itself a playlist it's rewritten the same way, so a
+//!   master playlist's media playlists route their segments through us too.
+//!
+//! Every redirect hop is validated like the requested URL, and a playlist is
+//! only passed on once every host it references has passed the same check.
+
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::Response,
+    Router,
+};
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use url::Url;
+use utoipa::ToSchema;
+
+use crate::core::error::AppError;
+use crate::helpers::http::common_headers;
+use crate::infra::proxy::{send_validated, validate_target};
+use crate::infra::proxy_limits::ProxyLimits;
+use crate::routes::AppState;
+
+/// Content type of the rewritten playlists.
+pub const HLS_CONTENT_TYPE: &str = "application/vnd.apple.mpegurl";
+
+/// Path the rewritten URIs point at.
+pub const VIDEO_PROXY_PATH: &str = "/api/videoproxy";
+
+/// Upstream response headers passed through to the player.
+const FORWARDED_HEADERS: [header::HeaderName; 5] = [
+    header::CONTENT_TYPE,
+    header::CONTENT_LENGTH,
+    header::CONTENT_RANGE,
+    header::ACCEPT_RANGES,
+    header::CACHE_CONTROL,
+];
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct StreamParams {
+    /// Upstream playlist or media URL.
+    url: String,
+}
+
+/// `/api/videoproxy?url=` for `target`.
+fn proxied(target: &Url) -> String {
+    format!("{}?url={}", VIDEO_PROXY_PATH, urlencoding::encode(target.as_str()))
+}
+
+/// Rewrites every URI in the playlist `body` fetched from `base` to go through
+/// the video proxy. Relative URIs are resolved against `base` first; URIs that
+/// can't be resolved are left untouched.
+pub fn rewrite_playlist(body: &str, base: &Url) -> String {
+    map_playlist_uris(body, base, |url| Some(proxied(&url)))
+}
+
+/// Every URI in the playlist `body`, resolved against `base`.
+fn playlist_targets(body: &str, base: &Url) -> Vec<Url> {
+    let mut targets = Vec::new();
+    map_playlist_uris(body, base, |url| {
+        targets.push(url);
+        None
+    });
+    targets
+}
+
+/// Replaces each URI of the playlist (variant and rendition playlists,
+/// segments, keys, init maps) with what `f` returns for it, resolved against
+/// `base`. URIs that can't be resolved, or that `f` maps to `None`, stay as
+/// they are.
+fn map_playlist_uris(body: &str, base: &Url, mut f: impl FnMut(Url) -> Option<String>) -> String {
+    static URI_ATTR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"URI="([^"]*)""#).unwrap());
+
+    let mut rewrite = |uri: &str| base.join(uri.trim()).ok().and_then(&mut f);
+
+    let mut out = String::with_capacity(body.len() * 2);
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            out.push_str(line);
+        } else if trimmed.starts_with('#') {
+            // Tags such as EXT-X-KEY, EXT-X-MAP and EXT-X-MEDIA carry URI="..."
+            let line = URI_ATTR.replace_all(line, |caps: &Captures| match rewrite(&caps[1]) {
+                Some(uri) => format!("URI=\"{}\"", uri),
+                None => caps[0].to_string(),
+            });
+            out.push_str(&line);
+        } else {
+            match rewrite(trimmed) {
+                Some(uri) => out.push_str(&uri),
+                None => out.push_str(line),
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Validates every host a playlist points at before it is handed to the
+/// player, so no proxied URI in it leads to a blocked target. Each origin is
+/// checked once.
+async fn check_playlist_targets(body: &str, base: &Url) -> Result<(), AppError> {
+    let mut seen = HashSet::new();
+    for url in playlist_targets(body, base) {
+        if seen.insert(url.origin().ascii_serialization()) {
+            validate_target(url.as_str()).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether an upstream response is an HLS playlist rather than media.
+fn is_playlist(url: &Url, content_type: Option<&str>) -> bool {
+    let content_type = content_type.unwrap_or_default().to_ascii_lowercase();
+    content_type.contains("mpegurl") || url.path().to_ascii_lowercase().ends_with(".m3u8")
+}
+
+/// What `/api/videoproxy` got back before it starts streaming.
+enum Upstream {
+    /// A playlist, already read and rewritten.
+    Playlist(Response),
+    /// Media to stream through.
+    Media(reqwest::Response),
+}
+
+/// GETs `url` with every redirect hop validated.
+async fn fetch_upstream(url: &str, range: Option<&HeaderValue>) -> Result<reqwest::Response, AppError> {
+    send_validated(url, |request| {
+        let request = request.headers(common_headers());
+        match range {
+            Some(range) => request.header(header::RANGE, range.clone()),
+            None => request,
+        }
+    })
+    .await
+}
+
+async fn playlist_response(upstream: reqwest::Response, limits: &ProxyLimits) -> Result<Response, AppError> {
+    // Redirects change the base relative URIs resolve against.
+    let base = upstream.url().clone();
+    let status = upstream.status();
+    if !status.is_success() {
+        return Err(AppError::Other(format!("Upstream playlist {} returned {}", base, status)));
+    }
+    let body = limits.read_body(base.as_str(), upstream).await?;
+    let body = String::from_utf8_lossy(&body);
+    if !body.trim_start().starts_with("#EXTM3U") {
+        return Err(AppError::BadRequest(format!("{} is not an HLS playlist", base)));
+    }
+    check_playlist_targets(&body, &base).await?;
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, HLS_CONTENT_TYPE)
+        .header(header::CACHE_CONTROL, "no-cache")
+        .body(rewrite_playlist(&body, &base).into())?)
+}
+
+/// Fetches an HLS playlist and rewrites its URIs through the video proxy.
+#[utoipa::path(
+    get,
+    params(
+        ("url" = String, Query, description = "Upstream .m3u8 URL", example = "https://cdn.example.com/master.m3u8")
+    ),
+    path = "/api/hls",
+    tag = "proxy",
+    operation_id = "hls_playlist",
+    responses(
+        (status = 200, description = "Rewritten playlist", body = String, content_type = "application/vnd.apple.mpegurl"),
+        (status = 400, description = "Upstream is not an HLS playlist", body = String),
+        (status = 403, description = "Target URL, a redirect or a URI in the playlist is not allowed", body = String),
+        (status = 413, description = "Upstream body exceeds the proxy size limit", body = String),
+        (status = 500, description = "Internal Server Error", body = String),
+        (status = 504, description = "Upstream did not respond in time", body = String)
+    )
+)]
+pub async fn hls(
+    _: State<Arc<AppState>>,
+    Query(params): Query<StreamParams>,
+) -> Result<Response, AppError> {
+    let limits = ProxyLimits::from_config();
+    // The playlist body is read (and its targets checked) under the same
+    // deadline as the request.
+    limits
+        .within_timeout(&params.url, async {
+            let upstream = fetch_upstream(&params.url, None).await?;
+            playlist_response(upstream, &limits).await
+        })
+        .await
+}
+
+/// Streams an upstream media file, or rewrites it if it is a playlist.
+#[utoipa::path(
+    get,
+    params(
+        ("url" = String, Query, description = "Upstream segment, key or playlist URL", example = "https://cdn.example.com/seg-1.ts")
+    ),
+    path = "/api/videoproxy",
+    tag = "proxy",
+    operation_id = "video_proxy",
+    responses(
+        (status = 200, description = "Upstream bytes", body = Vec<u8>),
+        (status = 206, description = "Requested byte range", body = Vec<u8>),
+        (status = 403, description = "Target URL is not allowed", body = String),
+        (status = 413, description = "Upstream body exceeds the proxy size limit", body = String),
+        (status = 500, description = "Internal Server Error", body = String),
+        (status = 504, description = "Upstream did not respond in time", body = String)
+    )
+)]
+pub async fn videoproxy(
+    _: State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<StreamParams>,
+) -> Result<Response, AppError> {
+    let limits = ProxyLimits::from_config();
+    // A playlist is read whole within the deadline; media is streamed past
+    // it, bounded only by size.
+    let upstream = limits
+        .within_timeout(&params.url, async {
+            let upstream = fetch_upstream(&params.url, headers.get(header::RANGE)).await?;
+            let content_type = upstream
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            if is_playlist(upstream.url(), content_type.as_deref()) {
+                return playlist_response(upstream, &limits).await.map(Upstream::Playlist);
+            }
+            Ok(Upstream::Media(upstream))
+        })
+        .await?;
+    let upstream = match upstream {
+        Upstream::Media(upstream) => upstream,
+        Upstream::Playlist(playlist) => return Ok(playlist),
+    };
+    limits.check_len(&params.url, upstream.content_length())?;
+
+    let mut response = Response::builder().status(upstream.status());
+    for name in FORWARDED_HEADERS {
+        if let Some(value) = upstream.headers().get(&name) {
+            response = response.header(name, value.clone());
+        }
+    }
+    // Upstreams may stream without a Content-Length, so the budget is also
+    // enforced on the bytes actually sent.
+    Ok(response.body(Body::from_stream(limits.limit_stream(upstream.bytes_stream())))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upstream_of(line: &str) -> String {
+        let encoded = line
+            .split("url=")
+            .nth(1)
+            .and_then(|rest| rest.split('"').next())
+            .unwrap();
+        urlencoding::decode(encoded).unwrap().into_owned()
+    }
+
+    #[test]
+    fn rewrites_master_and_media_playlists() {
+        let master_url = Url::parse("https://cdn.test/show/ep1/master.m3u8").unwrap();
+        let master = "\
+#EXTM3U
+#EXT-X-VERSION:6
+#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aud\",NAME=\"jp\",URI=\"audio/jp.m3u8\"
+#EXT-X-STREAM-INF:BANDWIDTH=800000,RESOLUTION=854x480,AUDIO=\"aud\"
+480p/index.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=2400000,RESOLUTION=1280x720,AUDIO=\"aud\"
+https://other.test/720p/index.m3u8?token=a&b=1
+";
+        let rewritten = rewrite_playlist(master, &master_url);
+        let lines: Vec<&str> = rewritten.lines().collect();
+
+        assert_eq!(lines[0], "#EXTM3U");
+        assert_eq!(lines[1], "#EXT-X-VERSION:6");
+        assert!(lines[2].contains("URI=\"/api/videoproxy?url="));
+        assert_eq!(upstream_of(lines[2]), "https://cdn.test/show/ep1/audio/jp.m3u8");
+        assert!(lines[3].starts_with("#EXT-X-STREAM-INF:BANDWIDTH=800000"));
+        assert!(lines[4].starts_with("/api/videoproxy?url="));
+        assert_eq!(upstream_of(lines[4]), "https://cdn.test/show/ep1/480p/index.m3u8");
+        assert_eq!(upstream_of(lines[6]), "https://other.test/720p/index.m3u8?token=a&b=1");
+
+        // The 480p media playlist, as fetched through the proxied URI above.
+        let media_url = Url::parse(&upstream_of(lines[4])).unwrap();
+        let media = "\
+#EXTM3U
+#EXT-X-TARGETDURATION:6
+#EXT-X-KEY:METHOD=AES-128,URI=\"../keys/k1.bin\",IV=0x1
+#EXT-X-MAP:URI=\"init.mp4\"
+#EXTINF:6.0,
+seg-0.m4s
+#EXTINF:6.0,
+/abs/seg-1.m4s
+#EXT-X-ENDLIST
+";
+        let rewritten = rewrite_playlist(media, &media_url);
+        let lines: Vec<&str> = rewritten.lines().collect();
+
+        assert_eq!(upstream_of(lines[2]), "https://cdn.test/show/ep1/keys/k1.bin");
+        assert!(lines[2].ends_with(",IV=0x1"));
+        assert_eq!(upstream_of(lines[3]), "https://cdn.test/show/ep1/480p/init.mp4");
+        assert_eq!(lines[4], "#EXTINF:6.0,");
+        assert_eq!(upstream_of(lines[5]), "https://cdn.test/show/ep1/480p/seg-0.m4s");
+        assert_eq!(upstream_of(lines[7]), "https://cdn.test/abs/seg-1.m4s");
+        assert_eq!(lines[8], "#EXT-X-ENDLIST");
+        assert!(!rewritten.contains("\nseg-0.m4s"));
+    }
+
+    #[test]
+    fn collects_every_nested_target() {
+        let base = Url::parse("https://cdn.test/ep1/index.m3u8").unwrap();
+        let media = "\
+#EXTM3U
+#EXT-X-KEY:METHOD=AES-128,URI=\"http://169.254.169.254/key\"
+#EXTINF:6.0,
+seg-0.ts
+";
+        let targets: Vec<String> = playlist_targets(media, &base).iter().map(Url::to_string).collect();
+        assert_eq!(targets, ["http://169.254.169.254/key", "https://cdn.test/ep1/seg-0.ts"]);
+    }
+
+    #[tokio::test]
+    async fn playlists_pointing_at_internal_hosts_are_refused() {
+        let base = Url::parse("http://93.184.216.34/ep1/index.m3u8").unwrap();
+        let media = "#EXTM3U\n#EXTINF:6.0,\nhttp://127.0.0.1:6379/seg-0.ts\n";
+        let err = check_playlist_targets(media, &base).await.unwrap_err();
+        assert!(matches!(err, AppError::BlockedTarget(_)), "{:?}", err);
+
+        let public = "#EXTM3U\n#EXTINF:6.0,\nseg-0.ts\n#EXTINF:6.0,\nseg-1.ts\n";
+        assert!(check_playlist_targets(public, &base).await.is_ok());
+    }
+
+    #[test]
+    fn detects_playlists_by_type_or_extension() {
+        let ts = Url::parse("https://cdn.test/seg.ts").unwrap();
+        let m3u8 = Url::parse("https://cdn.test/index.M3U8?x=1").unwrap();
+        assert!(is_playlist(&m3u8, None));
+        assert!(is_playlist(&ts, Some("application/x-mpegURL")));
+        assert!(!is_playlist(&ts, Some("video/mp2t")));
+    }
+}
+
+pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
+    router
+}
\ No newline at end of file
@@ -2,11 +2,16 @@
 //!
 //! POST /api/proxy/image-cache - Cache an image and return CDN URL
 
-use axum::{extract::State, response::IntoResponse, Json, Router};
+use axum::{
+    extract::State,
+    response::{IntoResponse, Response},
+    Json, Router,
+};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use utoipa::ToSchema;
 
+use crate::infra::proxy::validate_target;
 use crate::services::images::cache::ImageCache;
 use crate::routes::AppState;
 
@@ -70,14 +75,19 @@ pub struct ImageCacheResult {
     path = "/api/proxy/image-cache",
     request_body = ImageCacheRequest,
     responses(
-        (status = 200, description = "Image cached successfully", body = ImageCacheResponse)
+        (status = 200, description = "Image cached successfully", body = ImageCacheResponse),
+        (status = 403, description = "Image URL is not allowed", body = String)
     ),
     tag = "proxy"
 )]
 pub async fn image_cache(
     State(state): State<Arc<AppState>>,
     Json(req): Json<ImageCacheRequest>,
-) -> impl IntoResponse {
+) -> Response {
+    if let Err(e) = validate_target(&req.url).await {
+        return e.into_response();
+    }
+
     let cache = ImageCache::new(state.db.clone(), state.redis_pool.clone())
         .with_semaphore(state.image_processing_semaphore.clone());
 
@@ -89,7 +99,8 @@ pub async fn image_cache(
             cdn_url,
             from_cache: true,
             pending: None,
-        });
+        })
+        .into_response();
     }
 
     // Lazy mode: return original URL immediately, upload in background
@@ -115,7 +126,8 @@ pub async fn image_cache(
             cdn_url: req.url,
             from_cache: false,
             pending: Some(true),
-        });
+        })
+        .into_response();
     }
 
     // Blocking mode: wait for upload
@@ -126,7 +138,8 @@ pub async fn image_cache(
             cdn_url,
             from_cache: false,
             pending: None,
-        }),
+        })
+        .into_response(),
         Err(e) => {
             tracing::error!("ImageCache error: {}", e);
             Json(ImageCacheResponse {
@@ -136,6 +149,7 @@ pub async fn image_cache(
                 from_cache: false,
                 pending: None,
             })
+            .into_response()
         }
     }
 }
@@ -148,13 +162,20 @@ pub async fn image_cache(
     operation_id = "proxy_image_cache_batch",
     request_body = ImageCacheBatchRequest,
     responses(
-        (status = 200, description = "Batch image caching successful", body = ImageCacheBatchResponse)
+        (status = 200, description = "Batch image caching successful", body = ImageCacheBatchResponse),
+        (status = 403, description = "One of the image URLs is not allowed", body = String)
     )
 )]
 pub async fn image_cache_batch(
     State(state): State<Arc<AppState>>,
     Json(req): Json<ImageCacheBatchRequest>,
-) -> impl IntoResponse {
+) -> Response {
+    for url in &req.urls {
+        if let Err(e) = validate_target(url).await {
+            return e.into_response();
+        }
+    }
+
     let cache = ImageCache::new(state.db.clone(), state.redis_pool.clone());
     let mut results = Vec::with_capacity(req.urls.len());
 
@@ -175,6 +196,7 @@ pub async fn image_cache_batch(
         success: true,
         results,
     })
+    .into_response()
 }
 
 /// Register routes for this endpoint
@@ -0,0 +1,196 @@
+//! Image proxy for `<img>` tags.
+//!
+//! `GET /api/imageproxy?url=` streams an upstream image through us. By default
+//! the upstream must answer with an `image/*` Content-Type; anything else
+//! (typically an HTML error or block page that would render as a broken
+//! image) is refused with 415. `strict=false` forwards whatever comes back.
+//!
+//! Only the poster hosts in `APP__IMAGE_PROXY_HOSTS` are fetched, and every
+//! redirect hop is validated like the first request.
+
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::header,
+    response::Response,
+    Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::time::Instant;
+use utoipa::ToSchema;
+
+use crate::core::error::AppError;
+use crate::helpers::http::{common_image_headers, forwardable_response_headers};
+use crate::core::config::CONFIG;
+use crate::infra::proxy::send_validated_within;
+use crate::infra::proxy_limits::ProxyLimits;
+use crate::routes::AppState;
+
+fn default_strict() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ImageProxyParams {
+    /// Upstream image URL.
+    url: String,
+    /// Refuse non-image responses (default `true`).
+    #[serde(default = "default_strict")]
+    strict: bool,
+}
+
+/// Whether `content_type` is an image type.
+fn is_image(content_type: Option<&str>) -> bool {
+    content_type.is_some_and(|ct| ct.trim().to_ascii_lowercase().starts_with("image/"))
+}
+
+/// Turns the upstream response into ours: checks its type when `strict`,
+/// drops hop-by-hop and cookie headers, and streams the body within `limits`,
+/// cutting it off at `deadline`.
+pub async fn image_response(
+    url: &str,
+    upstream: reqwest::Response,
+    strict: bool,
+    limits: &ProxyLimits,
+    deadline: Instant,
+) -> Result<Response, AppError> {
+    let content_type = upstream
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    if strict && !is_image(content_type.as_deref()) {
+        return Err(AppError::UnsupportedMediaType(format!(
+            "{} returned {} instead of an image",
+            url,
+            content_type.as_deref().unwrap_or("no Content-Type")
+        )));
+    }
+    limits.check_len(url, upstream.content_length())?;
+
+    let mut response = Response::builder().status(upstream.status());
+    if let Some(headers) = response.headers_mut() {
+        *headers = forwardable_response_headers(upstream.headers());
+    }
+    Ok(response.body(Body::from_stream(limits.limit_stream_until(deadline, upstream.bytes_stream())))?)
+}
+
+/// Streams an upstream image, refusing non-image responses unless `strict=false`.
+#[utoipa::path(
+    get,
+    params(
+        ("url" = String, Query, description = "Upstream image URL", example = "https://cdn.example.com/cover.jpg"),
+        ("strict" = Option<bool>, Query, description = "Refuse non-image responses with 415 (default true)")
+    ),
+    path = "/api/imageproxy",
+    tag = "proxy",
+    operation_id = "image_proxy",
+    responses(
+        (status = 200, description = "Upstream image bytes", body = Vec<u8>),
+        (status = 403, description = "Target URL is not an allowed poster host", body = String),
+        (status = 413, description = "Upstream body exceeds the proxy size limit", body = String),
+        (status = 415, description = "Upstream did not return an image", body = String),
+        (status = 500, description = "Internal Server Error", body = String),
+        (status = 504, description = "Upstream did not respond in time", body = String)
+    )
+)]
+pub async fn imageproxy(
+    _: State<Arc<AppState>>,
+    Query(params): Query<ImageProxyParams>,
+) -> Result<Response, AppError> {
+    let limits = ProxyLimits::from_config();
+    let deadline = Instant::now() + limits.timeout;
+    let send = send_validated_within(&params.url, &CONFIG.image_proxy_hosts, |request| {
+        request.headers(common_image_headers())
+    });
+    let upstream = limits.within_timeout(&params.url, send).await?;
+    image_response(&params.url, upstream, params.strict, &limits, deadline).await
+}
+
+pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
+    router
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, response::IntoResponse, routing::get};
+    use std::time::Duration;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    fn limits() -> ProxyLimits {
+        ProxyLimits { timeout: Duration::from_secs(5), max_body_bytes: 1 << 20 }
+    }
+
+    /// `/cover.png` is an image that also sets a cookie; `/blocked` is the
+    /// kind of HTML page an upstream serves instead of the image.
+    async fn serve_upstream() -> String {
+        let app = Router::new()
+            .route(
+                "/cover.png",
+                get(|| async {
+                    (
+                        [(header::CONTENT_TYPE, "image/png"), (header::SET_COOKIE, "sid=secret")],
+                        PNG,
+                    )
+                }),
+            )
+            .route(
+                "/blocked",
+                get(|| async { axum::response::Html("<html><title>Internet Positif</title></html>") }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    async fn proxy(url: &str, strict: bool) -> Response {
+        let upstream = reqwest::get(url).await.unwrap();
+        let limits = limits();
+        match image_response(url, upstream, strict, &limits, Instant::now() + limits.timeout).await {
+            Ok(response) => response,
+            Err(e) => e.into_response(),
+        }
+    }
+
+    async fn body_of(response: Response) -> Vec<u8> {
+        axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec()
+    }
+
+    #[tokio::test]
+    async fn images_are_forwarded_without_upstream_cookies() {
+        let base = serve_upstream().await;
+        let response = proxy(&format!("{}/cover.png", base), true).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/png");
+        assert!(response.headers().get(header::SET_COOKIE).is_none());
+        assert_eq!(body_of(response).await, PNG);
+    }
+
+    #[tokio::test]
+    async fn html_is_refused_unless_strict_is_off() {
+        let base = serve_upstream().await;
+        let url = format!("{}/blocked", base);
+
+        let refused = proxy(&url, true).await;
+        assert_eq!(refused.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let bypassed = proxy(&url, false).await;
+        assert_eq!(bypassed.status(), StatusCode::OK);
+        assert!(String::from_utf8(body_of(bypassed).await).unwrap().contains("Internet Positif"));
+    }
+
+    #[test]
+    fn strict_is_the_default() {
+        let parse = |uri: &str| Query::<ImageProxyParams>::try_from_uri(&uri.parse().unwrap()).unwrap().0;
+        assert!(parse("/api/imageproxy?url=https%3A%2F%2Fcdn.test%2Fa.jpg").strict);
+        assert!(!parse("/api/imageproxy?url=x&strict=false").strict);
+        assert!(is_image(Some("Image/WebP")));
+        assert!(!is_image(Some("text/html; charset=utf-8")));
+        assert!(!is_image(None));
+    }
+}
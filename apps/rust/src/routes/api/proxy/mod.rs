@@ -2,7 +2,9 @@
 /// DO NOT EDIT THIS FILE MANUALLY
 
 pub mod croxy;
+pub mod hls;
 pub mod image_cache;
+pub mod imageproxy;
 
 /// Register routes for this directory
 use axum::Router;
@@ -10,5 +12,5 @@ use std::sync::Arc;
 use crate::routes::AppState;
 
 pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
-    croxy::register_routes(image_cache::register_routes(router))
+    croxy::register_routes(hls::register_routes(image_cache::register_routes(imageproxy::register_routes(router))))
 }
@@ -0,0 +1,214 @@
+//! Title search over the local index of scraped anime and komik.
+//!
+//! Answers from [`search_index`] without touching the source sites. Only
+//! when the index has nothing for the query (it starts empty and only holds
+//! what has been scraped) does it run the source's own search, index those
+//! results and rank them the same way.
+
+use axum::{extract::State, response::IntoResponse, Json, Router};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{info, warn};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::core::error::AppError;
+use crate::extractors::validated::not_blank;
+use crate::extractors::ValidatedQuery;
+use crate::routes::api::{anime, komik};
+use crate::routes::AppState;
+use crate::services::search_index::{self, SearchEntry, SearchHit, SearchKind};
+
+pub const ENDPOINT_METHOD: &str = "get";
+pub const ENDPOINT_PATH: &str = "/api/search";
+pub const ENDPOINT_DESCRIPTION: &str = "Ranked title search across cached anime and komik";
+pub const ENDPOINT_TAG: &str = "search";
+pub const OPERATION_ID: &str = "search";
+pub const SUCCESS_RESPONSE_BODY: &str = "Json<SearchResultsResponse>";
+
+const DEFAULT_LIMIT: usize = 20;
+
+#[derive(Deserialize, IntoParams, ToSchema, Validate, Debug, Default)]
+pub struct SearchQuery {
+    /// Search text; required and non-blank.
+    #[serde(default)]
+    #[validate(custom(function = "not_blank"))]
+    pub q: String,
+    /// What to search (`anime` or `komik`, default `anime`).
+    #[serde(default)]
+    pub kind: SearchKind,
+    /// Maximum hits (1-50, default 20).
+    #[validate(range(min = 1, max = 50, message = "limit must be between 1 and 50"))]
+    pub limit: Option<usize>,
+}
+
+/// Where the hits came from.
+#[derive(Serialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchSource {
+    /// The local index.
+    Index,
+    /// The source site's search, because the index had no match.
+    Live,
+}
+
+#[derive(Serialize, ToSchema, Debug)]
+pub struct SearchResultsResponse {
+    pub status: String,
+    pub kind: SearchKind,
+    pub source: SearchSource,
+    /// Best match first.
+    pub data: Vec<SearchHit>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/search",
+    tag = "search",
+    operation_id = "search",
+    params(SearchQuery),
+    responses(
+        (status = 200, description = "Ranked title search across cached anime and komik", body = SearchResultsResponse),
+        (status = 422, description = "Missing or empty `q`, or `limit` outside 1-50"),
+        (status = 502, description = "The index had no match and the source's search failed", body = String)
+    )
+)]
+pub async fn search(
+    State(app_state): State<Arc<AppState>>,
+    ValidatedQuery(params): ValidatedQuery<SearchQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let query = params.q.trim();
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT);
+    let pool = &app_state.redis_pool;
+
+    let indexed = search_index::load_entries(pool, params.kind)
+        .await
+        .unwrap_or_else(|e| {
+            warn!(
+                "Search index for {} unavailable: {}",
+                params.kind.as_str(),
+                e
+            );
+            Vec::new()
+        });
+    let hits = search_index::rank(&indexed, query, limit);
+    if !hits.is_empty() {
+        return Ok(Json(response(params.kind, SearchSource::Index, hits)));
+    }
+
+    info!(
+        "No indexed {} matches '{}' among {} titles; searching live",
+        params.kind.as_str(),
+        query,
+        indexed.len()
+    );
+    let live = live_entries(params.kind, query).await.map_err(|e| {
+        AppError::BadGateway(format!(
+            "Live {} search failed: {}",
+            params.kind.as_str(),
+            e
+        ))
+    })?;
+    if let Err(e) = search_index::index_entries(pool, &live).await {
+        warn!(
+            "Failed to index live {} results: {}",
+            params.kind.as_str(),
+            e
+        );
+    }
+
+    Ok(Json(response(
+        params.kind,
+        SearchSource::Live,
+        search_index::rank(&live, query, limit),
+    )))
+}
+
+fn response(kind: SearchKind, source: SearchSource, data: Vec<SearchHit>) -> SearchResultsResponse {
+    SearchResultsResponse {
+        status: "Ok".to_string(),
+        kind,
+        source,
+        data,
+    }
+}
+
+/// First page of the source site's own search for `query`.
+async fn live_entries(kind: SearchKind, query: &str) -> Result<Vec<SearchEntry>, String> {
+    match kind {
+        SearchKind::Anime => {
+            let (items, _) =
+                anime::search::fetch_and_parse_search(&anime::search::search_url(query))
+                    .await
+                    .map_err(|e| e.to_string())?;
+            Ok(items
+                .iter()
+                .map(|a| SearchEntry::new(kind, &a.slug, &a.title, &a.poster))
+                .collect())
+        }
+        SearchKind::Komik => {
+            let (items, _) =
+                komik::search::fetch_and_parse_search(&komik::search::search_url(query, 1), 1)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            Ok(items
+                .iter()
+                .map(|k| SearchEntry::new(kind, &k.slug, &k.title, &k.poster))
+                .collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(q: &str, limit: Option<usize>) -> SearchQuery {
+        SearchQuery {
+            q: q.to_string(),
+            limit,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn query_requires_text_and_a_sane_limit() {
+        assert!(query("one piece", None).validate().is_ok());
+        assert!(query("one piece", Some(50)).validate().is_ok());
+        assert!(query("   ", None).validate().is_err());
+        assert!(query("one piece", Some(0)).validate().is_err());
+        assert!(query("one piece", Some(51)).validate().is_err());
+    }
+
+    #[test]
+    fn kind_defaults_to_anime() {
+        let parsed: SearchQuery = serde_json::from_str(r#"{"q":"x"}"#).unwrap();
+        assert_eq!(parsed.kind, SearchKind::Anime);
+        let parsed: SearchQuery = serde_json::from_str(r#"{"q":"x","kind":"komik"}"#).unwrap();
+        assert_eq!(parsed.kind, SearchKind::Komik);
+    }
+
+    #[test]
+    fn hits_serialize_flat_with_their_source() {
+        let hits = search_index::rank(
+            &[SearchEntry::new(
+                SearchKind::Komik,
+                "solo-leveling",
+                "Solo Leveling",
+                "p.jpg",
+            )],
+            "solo leveling",
+            DEFAULT_LIMIT,
+        );
+        let json =
+            serde_json::to_value(response(SearchKind::Komik, SearchSource::Index, hits)).unwrap();
+        assert_eq!(json["kind"], "komik");
+        assert_eq!(json["source"], "index");
+        assert_eq!(json["data"][0]["slug"], "solo-leveling");
+        assert_eq!(json["data"][0]["score"], 1.0);
+    }
+}
+
+pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
+    router
+}
\ No newline at end of file
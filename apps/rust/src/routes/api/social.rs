@@ -1,10 +1,10 @@
 use axum::{
-    extract::{State, Path},
+    extract::{Path, Query, State},
     response::IntoResponse,
     Json, Router,
 };
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set, 
+    ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set,
     ModelTrait,
 };
 use serde::{Deserialize, Serialize};
@@ -16,6 +16,9 @@ use utoipa::ToSchema;
 use crate::entities::{posts, user, likes, comments};
 use crate::routes::AppState;
 use crate::core::error::AppError;
+use crate::helpers::query::{
+    count_capped, PageQuery, PaginateExt, PaginationMeta, MAX_COUNTED_ROWS, MAX_PER_PAGE,
+};
 use crate::middleware::auth::AuthMiddleware;
 
 // ... DTOs preserved ...
@@ -27,8 +30,14 @@ pub struct PostResponse {
     pub content: String,
     pub image_url: Option<String>,
     pub created_at: String,
+    /// The first page of likes; see `like_count` for how many there are.
     pub likes: Vec<LikeResponse>,
+    /// Likes on the post, counted up to 10000.
+    pub like_count: u64,
+    /// The oldest page of comments; see `comment_count` for how many there are.
     pub comments: Vec<CommentResponse>,
+    /// Comments on the post, counted up to 10000.
+    pub comment_count: u64,
     pub user: Option<UserResponse>,
 }
 
@@ -63,18 +72,25 @@ pub struct CreatePostRequest {
     get,
     path = "/api/social/posts",
     tag = "social",
+    params(PageQuery),
     responses(
-        (status = 200, description = "List all posts", body = Vec<PostResponse>),
+        (status = 200, description = "One page of posts, newest first. Pagination is reported in the X-Page, X-Per-Page, X-Total-Count and X-Total-Pages headers; totals stop at 10000.", body = Vec<PostResponse>),
         (status = 500, description = "Internal Server Error")
     )
 )]
 pub async fn get_posts(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<PageQuery>,
 ) -> Result<impl IntoResponse, AppError> {
-    // ... existing implementation ...
-    // 1. Fetch all posts
+    // 1. Fetch one page of posts
+    let total = count_capped(posts::Entity::find(), state.sea_orm(), MAX_COUNTED_ROWS)
+        .await
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    let pagination = query.pagination().with_total(total);
+
     let posts = posts::Entity::find()
         .order_by_desc(posts::Column::CreatedAt)
+        .apply_pagination(&pagination)
         .all(state.sea_orm())
         .await
         .map_err(|e| AppError::DatabaseError(e.to_string()))?;
@@ -87,14 +103,31 @@ pub async fn get_posts(
             .await
             .unwrap_or(None);
 
+        let like_count = count_capped(
+            likes::Entity::find().filter(likes::Column::PostId.eq(&post.id)),
+            state.sea_orm(),
+            MAX_COUNTED_ROWS,
+        )
+        .await
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
         let likes = likes::Entity::find()
             .filter(likes::Column::PostId.eq(&post.id))
+            .limit(MAX_PER_PAGE)
             .all(state.sea_orm())
             .await
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
+        let comment_count = count_capped(
+            comments::Entity::find().filter(comments::Column::PostId.eq(&post.id)),
+            state.sea_orm(),
+            MAX_COUNTED_ROWS,
+        )
+        .await
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
         let comments_models = comments::Entity::find()
             .filter(comments::Column::PostId.eq(&post.id))
+            .order_by_asc(comments::Column::CreatedAt)
+            .limit(MAX_PER_PAGE)
             .all(state.sea_orm())
             .await
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
@@ -130,7 +163,9 @@ pub async fn get_posts(
             image_url: post.image_url,
             created_at: post.created_at.to_string(),
             likes: likes_resp,
+            like_count,
             comments,
+            comment_count,
             user: author.map(|u| UserResponse {
                 id: u.id,
                 name: u.name.unwrap_or_default(),
@@ -139,7 +174,7 @@ pub async fn get_posts(
         });
     }
 
-    Ok(Json(response_posts))
+    Ok((PaginationMeta::from(&pagination).headers(), Json(response_posts)))
 }
 
 #[utoipa::path(
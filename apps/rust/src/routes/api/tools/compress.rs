@@ -1,18 +1,25 @@
 //! Handler for the compress endpoint.
 
-use crate::helpers::api_response::{internal_err, ApiResult, ApiResponse};
+use crate::core::config::CONFIG;
+use crate::core::error::AppError;
+use crate::helpers::api_response::{internal_err, ApiError, ApiResponse};
+use crate::helpers::async_utils::AbortOnDrop;
+use crate::helpers::file::TempFileGuard;
 use crate::routes::AppState;
-use axum::{extract::Query, Router};
-use image::ImageFormat;
+use crate::services::images::{self, compress::CompressedImage};
+use crate::storage::Storage;
+use axum::extract::{Multipart, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::Router;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256}; // Switched to Sha256
-use std::io::Cursor;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::fs;
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::{mpsc, Mutex, OwnedSemaphorePermit};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
@@ -26,6 +33,9 @@ pub const ENDPOINT_TAG: &str = "compress";
 pub const OPERATION_ID: &str = "compress";
 pub const SUCCESS_RESPONSE_BODY: &str = "ApiResponse<CompressData>";
 
+/// Largest file `POST /api/compress` accepts (1 GB).
+pub const MAX_UPLOAD_SIZE: usize = 1024 * 1024 * 1024;
+
 static CACHE_DIR: Lazy<PathBuf> = Lazy::new(|| {
     let mut path = std::env::temp_dir();
     path.push("compress-cache");
@@ -170,44 +180,25 @@ async fn compress_image(
         }
     }
 
-    // Load image
-    let img = image::load_from_memory(buffer)?;
-    let mut quality = 85;
-    let mut best_buffer = Vec::new();
-
-    for i in 0..8 {
-        tracing::info!(
-            "Attempt {} for image compression, current quality: {}",
-            i + 1,
-            quality
-        );
-        let mut output = Cursor::new(Vec::new());
-        img.write_to(&mut output, ImageFormat::Jpeg)?;
-        let output_vec = output.into_inner();
-        let size_bytes = output_vec.len() as f64;
-
-        if size_bytes > target_bytes * 1.05 {
-            quality = ((quality as f64) * 0.9) as u8;
-        } else if size_bytes < target_bytes * 0.95 {
-            quality = ((quality as f64) * 1.1).min(100.0) as u8;
-            best_buffer = output_vec;
-        } else {
-            fs::write(&cache_path, &output_vec).await?;
-            let size_reduction =
-                (((buffer.len() - output_vec.len()) as f64) / (buffer.len() as f64)) * 100.0;
-            tracing::info!("Image compressed successfully for cache key: {}", cache_key);
-            return Ok((output_vec, size_reduction));
-        }
-    }
-
-    fs::write(&cache_path, &best_buffer).await?;
-    let size_reduction =
-        (((buffer.len() - best_buffer.len()) as f64) / (buffer.len() as f64)) * 100.0;
+    // Decoding and the quality search are CPU-bound.
+    let input = buffer.to_vec();
+    let target = target_bytes.max(1.0) as usize;
+    let compressed =
+        tokio::task::spawn_blocking(move || images::compress::compress_to_target(&input, target))
+            .await??;
     tracing::info!(
-        "Image compression finished after all attempts for cache key: {}",
+        "Image compressed to {} bytes ({}x{}, quality {}) for cache key: {}",
+        compressed.bytes.len(),
+        compressed.width,
+        compressed.height,
+        compressed.quality,
         cache_key
     );
-    Ok((best_buffer, size_reduction))
+
+    fs::write(&cache_path, &compressed.bytes).await?;
+    let size_reduction =
+        ((buffer.len() as f64 - compressed.bytes.len() as f64) / (buffer.len() as f64)) * 100.0;
+    Ok((compressed.bytes, size_reduction))
 }
 
 #[cfg(feature = "ffmpeg")]
@@ -252,25 +243,31 @@ async fn compress_video(
         .tempfile_in(CACHE_DIR.as_path())?;
     fs::write(temp_input.path(), buffer).await?;
 
+    // ffmpeg is killed and these files removed if the request is cancelled.
     let output_filename = format!("ffmpeg_output_{}.{}", Uuid::new_v4(), ext);
-    let temp_output_path = CACHE_DIR.join(&output_filename);
+    let temp_output = TempFileGuard::new(CACHE_DIR.join(&output_filename));
+    let temp_output_path = temp_output.path().to_path_buf();
 
     // Get video duration
     #[cfg(target_os = "windows")]
-    let duration_output = std::process::Command::new("cmd")
+    let duration_output = tokio::process::Command::new("cmd")
         .arg("/C")
         .arg("ffmpeg")
         .arg("-i")
         .arg(temp_input.path())
         .current_dir(CACHE_DIR.as_path())
-        .output()?;
+        .kill_on_drop(true)
+        .output()
+        .await?;
 
     #[cfg(not(target_os = "windows"))]
-    let duration_output = std::process::Command::new("ffmpeg")
+    let duration_output = tokio::process::Command::new("ffmpeg")
         .arg("-i")
         .arg(temp_input.path())
         .current_dir(CACHE_DIR.as_path())
-        .output()?;
+        .kill_on_drop(true)
+        .output()
+        .await?;
     let stderr = String::from_utf8_lossy(&duration_output.stderr);
     let duration_regex = regex::Regex::new(r"Duration: (\d{2}):(\d{2}):(\d{2})\.(\d{2})")?;
     let caps = duration_regex
@@ -288,9 +285,15 @@ async fn compress_video(
     let target_bitrate = target_bits / total_seconds; // bits per second
 
     // First Pass
-    let log_file_path = CACHE_DIR.join(format!("{}_passlog", cache_key));
+    let log_file = TempFileGuard::new(CACHE_DIR.join(format!("{}_passlog", cache_key)));
+    let log_file_path = log_file.path().to_path_buf();
+    // ffmpeg writes its two-pass stats here by default
+    let _pass_logs = [
+        TempFileGuard::new(CACHE_DIR.join("ffmpeg2pass-0.log")),
+        TempFileGuard::new(CACHE_DIR.join("ffmpeg2pass-0.log.mbtree")),
+    ];
     #[cfg(target_os = "windows")]
-    let status_pass1 = std::process::Command::new("cmd")
+    let status_pass1 = tokio::process::Command::new("cmd")
         .arg("/C")
         .arg("ffmpeg")
         .arg("-y") // Overwrite output files without asking
@@ -309,10 +312,12 @@ async fn compress_video(
         .arg("mp4") // Output format for pass 1 (can be null or dummy)
         .arg(format!("{}", log_file_path.display())) // Output to log file (dummy)
         .current_dir(CACHE_DIR.as_path())
-        .output()?;
+        .kill_on_drop(true)
+        .output()
+        .await?;
 
     #[cfg(not(target_os = "windows"))]
-    let status_pass1 = std::process::Command::new("ffmpeg")
+    let status_pass1 = tokio::process::Command::new("ffmpeg")
         .arg("-y") // Overwrite output files without asking
         .arg("-i")
         .arg(temp_input.path())
@@ -329,7 +334,9 @@ async fn compress_video(
         .arg("mp4") // Output format for pass 1 (can be null or dummy)
         .arg(format!("{}", log_file_path.display())) // Output to log file (dummy)
         .current_dir(CACHE_DIR.as_path())
-        .output()?;
+        .kill_on_drop(true)
+        .output()
+        .await?;
 
     if !status_pass1.status.success() {
         return Err(format!(
@@ -341,7 +348,7 @@ async fn compress_video(
 
     // Second Pass
     #[cfg(target_os = "windows")]
-    let status_pass2 = std::process::Command::new("cmd")
+    let status_pass2 = tokio::process::Command::new("cmd")
         .arg("/C")
         .arg("ffmpeg")
         .arg("-y") // Overwrite output files without asking
@@ -359,10 +366,12 @@ async fn compress_video(
         .arg("aac")
         .arg(&temp_output_path)
         .current_dir(CACHE_DIR.as_path())
-        .output()?;
+        .kill_on_drop(true)
+        .output()
+        .await?;
 
     #[cfg(not(target_os = "windows"))]
-    let status_pass2 = std::process::Command::new("ffmpeg")
+    let status_pass2 = tokio::process::Command::new("ffmpeg")
         .arg("-y") // Overwrite output files without asking
         .arg("-i")
         .arg(temp_input.path())
@@ -378,7 +387,9 @@ async fn compress_video(
         .arg("aac")
         .arg(&temp_output_path)
         .current_dir(CACHE_DIR.as_path())
-        .output()?;
+        .kill_on_drop(true)
+        .output()
+        .await?;
 
     if !status_pass2.status.success() {
         return Err(format!(
@@ -388,16 +399,10 @@ async fn compress_video(
         .into());
     }
 
-    // Clean up log file
-    fs::remove_file(&log_file_path).await?;
-    fs::remove_file(CACHE_DIR.join("ffmpeg2pass-0.log")).await?; // ffmpeg creates this by default
-    fs::remove_file(CACHE_DIR.join("ffmpeg2pass-0.log.mbtree")).await?; // and this
-
     let result_buffer = fs::read(&temp_output_path).await?;
     let size_reduction =
         (((buffer.len() - result_buffer.len()) as f64) / (buffer.len() as f64)) * 100.0;
     tracing::info!("Video compressed successfully for cache key: {}", cache_key);
-    fs::remove_file(&temp_output_path).await?; // Cleanup
     Ok((result_buffer, size_reduction))
 }
 
@@ -412,21 +417,47 @@ async fn compress_video(
     Err("Video compression requires ffmpeg feature".into())
 }
 
+/// Compression is CPU/FFmpeg heavy: callers hold an image processing permit
+/// for its whole duration, and fail fast with 503 rather than queueing forever.
+async fn acquire_processing_permit(state: &AppState) -> Result<OwnedSemaphorePermit, Response> {
+    let wait = std::time::Duration::from_secs(CONFIG.image_processing_wait_seconds);
+    match tokio::time::timeout(
+        wait,
+        state.image_processing_semaphore.clone().acquire_owned(),
+    )
+    .await
+    {
+        Ok(Ok(permit)) => Ok(permit),
+        Ok(Err(_)) | Err(_) => {
+            tracing::warn!("No image processing slot available within {:?}", wait);
+            Err((
+                [(header::RETRY_AFTER, wait.as_secs().max(1).to_string())],
+                ApiError::service_unavailable("Server sibuk, coba lagi nanti"),
+            )
+                .into_response())
+        }
+    }
+}
+
 #[utoipa::path(
     get,
     params(
-        ("url" = String, Query, description = "Parameter for resource identification", example = "sample_value"),
-        ("size" = String, Query, description = "Parameter for resource identification", example = "sample_value")
+        ("url" = String, Query, description = "URL of the image or video to compress", example = "https://example.com/photo.jpg"),
+        ("size" = String, Query, description = "Target size: a percentage of the original (`50%`), `KB` or `MB`", example = "500KB")
     ),
     path = "/api/compress",
     tag = "compress",
     operation_id = "compress",
     responses(
-        (status = 200, description = "Compress images and videos from URL", body = ApiResponse<CompressData>),
-        (status = 500, description = "Internal Server Error", body = String)
+        (status = 200, description = "Link to the compressed file; POST /api/compress takes an upload instead", body = ApiResponse<CompressData>),
+        (status = 500, description = "Internal Server Error", body = String),
+        (status = 503, description = "All image processing slots busy; retry after the Retry-After header", body = String)
     )
 )]
-pub async fn compress(Query(params): Query<CompressQuery>) -> ApiResult<CompressData> {
+pub async fn compress(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<CompressQuery>,
+) -> Result<ApiResponse<CompressData>, Response> {
     tracing::info!(
         "Received compress request for URL: {} with size: {}",
         params.url,
@@ -435,13 +466,13 @@ pub async fn compress(Query(params): Query<CompressQuery>) -> ApiResult<Compress
     // Validate parameters
     if params.url.is_empty() || params.size.is_empty() {
         tracing::warn!("Missing URL or size parameter in compress request.");
-        return Err(internal_err("Parameter url dan size diperlukan"));
+        return Err(internal_err("Parameter url dan size diperlukan").into_response());
     }
 
     let queue_len = QUEUE_SENDER.capacity();
     if queue_len == 0 {
         tracing::warn!("Compression queue is full.");
-        return Err(internal_err("Server sibuk, coba lagi nanti"));
+        return Err(internal_err("Server sibuk, coba lagi nanti").into_response());
     }
     tracing::info!("Compression queue available slots: {}", queue_len);
 
@@ -449,16 +480,30 @@ pub async fn compress(Query(params): Query<CompressQuery>) -> ApiResult<Compress
     let url = params.url.clone();
     let size_param = params.size.clone();
 
+    let _permit = acquire_processing_permit(&state).await?;
+
+    // Runs on its own task, aborted if the client disconnects: axum drops
+    // this future, the task's temp file guards clean up and ffmpeg is killed.
     tracing::info!("Processing compression for URL: {}", url);
-    match process_compression(url, size_param).await {
+    let work = AbortOnDrop::spawn(process_compression(
+        url,
+        size_param,
+        state.resolve::<Storage>(),
+    ));
+    let result = match work.await {
+        Ok(result) => result,
+        Err(e) => Err(e.into()),
+    };
+    match result {
         Ok(link) => {
             tracing::info!("Compression successful. Link: {}", link);
             Ok(ApiResponse::success(CompressData { link: Some(link) }))
         }
         Err(e) => {
             tracing::error!("Compression failed: {}", e);
-            Ok(ApiResponse::success(CompressData { link: None })) // Returning success with empty link?? Or should be error? 
+            // Returning success with empty link?? Or should be error?
             // Original code returned success struct with error field. ApiResponse has error field too.
+            Ok(ApiResponse::success(CompressData { link: None }))
         }
     }
 }
@@ -466,6 +511,7 @@ pub async fn compress(Query(params): Query<CompressQuery>) -> ApiResult<Compress
 async fn process_compression(
     url: String,
     size_param: String,
+    storage: Option<Arc<Storage>>,
 ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
     tracing::info!("Fetching file from URL: {}", url);
     // Fetch file
@@ -524,11 +570,11 @@ async fn process_compression(
         .unwrap_or("")
         .to_lowercase();
 
-    // Save original buffer to temporary file for debugging
+    // Save original buffer to temporary file for debugging; removed when done
     let original_filename = format!("original_debug.{}.{}", Uuid::new_v4(), ext);
-    let original_path = CACHE_DIR.join(&original_filename);
-    fs::write(&original_path, &buffer).await?;
-    tracing::info!("Original buffer saved to: {}", original_path.display());
+    let original = TempFileGuard::new(CACHE_DIR.join(&original_filename));
+    fs::write(original.path(), &buffer).await?;
+    tracing::info!("Original buffer saved to: {}", original.path().display());
 
     // Validate buffer content type for video files using infer
     if ext == "mp4" || ext == "mov" || ext == "avi" {
@@ -549,12 +595,207 @@ async fn process_compression(
         }
     }
 
-    tracing::info!("File extension detected: {}", ext);
     let cache_key = generate_cache_key(&url, &size_param);
-    let (size_value, unit) = parse_size_param(&size_param)?;
+    let compressed = compress_bytes(&buffer, &ext, &size_param, &cache_key).await?;
+    let (compressed_buffer, ext, mime) = (compressed.bytes, compressed.ext, compressed.mime);
+
+    if let Some(storage) = storage {
+        let path = format!("compress/{}.{}", cache_key.trim_end_matches(".cache"), ext);
+        storage
+            .put_with_mime(&path, &compressed_buffer, &mime)
+            .await?;
+        return Ok(storage.url(&path).await?);
+    }
+
+    // Without configured storage, fall back to a local file
+    let filename = format!("compressed_debug.{}.{}", Uuid::new_v4(), ext);
+    let local_file = TempFileGuard::new(CACHE_DIR.join(&filename));
+    tracing::info!(
+        "Saving compressed file to local path: {}",
+        local_file.path().display()
+    );
+    fs::write(local_file.path(), &compressed_buffer).await?;
+
+    Ok(local_file.keep().to_string_lossy().into_owned())
+}
+
+/// Multipart form accepted by `POST /api/compress`.
+#[derive(Deserialize, ToSchema)]
+pub struct CompressUpload {
+    /// The image or video to compress.
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+    /// Target size: a percentage of the upload (`50%`), `KB` or `MB`.
+    pub size: String,
+}
+
+/// Extension the pipeline uses for an uploaded file of type `mime`, or
+/// `None` if it isn't media we can compress.
+fn upload_extension(mime: &str) -> Option<&'static str> {
+    let essence = mime.split(';').next().unwrap_or_default().trim();
+    match essence.to_ascii_lowercase().as_str() {
+        "image/jpeg" | "image/jpg" => Some("jpg"),
+        "image/png" => Some("png"),
+        "video/mp4" => Some("mp4"),
+        "video/quicktime" => Some("mov"),
+        "video/x-msvideo" | "video/avi" => Some("avi"),
+        _ => None,
+    }
+}
+
+fn unsupported_media(mime: &str) -> AppError {
+    AppError::UnsupportedMediaType(format!(
+        "Cannot compress {}; upload a JPEG/PNG image or an MP4/MOV/AVI video",
+        mime
+    ))
+}
+
+/// Reads the `file` and `size` fields, rejecting non-media and anything over
+/// [`MAX_UPLOAD_SIZE`] as soon as it shows.
+async fn read_upload(
+    mut multipart: Multipart,
+) -> Result<(Vec<u8>, &'static str, String), AppError> {
+    let mut file = None;
+    let mut size = None;
+
+    while let Some(mut field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Failed to read multipart: {}", e)))?
+    {
+        match field.name() {
+            Some("file") => {
+                let declared = field
+                    .content_type()
+                    .unwrap_or("application/octet-stream")
+                    .to_string();
+                let ext =
+                    upload_extension(&declared).ok_or_else(|| unsupported_media(&declared))?;
+
+                let mut data = Vec::new();
+                while let Some(chunk) = field
+                    .chunk()
+                    .await
+                    .map_err(|e| AppError::BadRequest(format!("Failed to read file: {}", e)))?
+                {
+                    if data.len() + chunk.len() > MAX_UPLOAD_SIZE {
+                        return Err(AppError::PayloadTooLarge(format!(
+                            "File exceeds the {} byte limit",
+                            MAX_UPLOAD_SIZE
+                        )));
+                    }
+                    data.extend_from_slice(&chunk);
+                }
+
+                // Trust the bytes over the client's Content-Type.
+                if let Some(kind) = infer::get(&data) {
+                    if upload_extension(kind.mime_type()).is_none() {
+                        return Err(unsupported_media(kind.mime_type()));
+                    }
+                }
+                file = Some((data, ext));
+            }
+            Some("size") => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|e| AppError::BadRequest(format!("Failed to read size: {}", e)))?;
+                size = Some(text.trim().to_string());
+            }
+            _ => {}
+        }
+    }
+
+    let (data, ext) = file.filter(|(data, _)| !data.is_empty()).ok_or_else(|| {
+        AppError::BadRequest("No file provided. Use field name 'file'".to_string())
+    })?;
+    let size = size
+        .filter(|size| !size.is_empty())
+        .ok_or_else(|| AppError::BadRequest("Field 'size' is required".to_string()))?;
+    Ok((data, ext, size))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/compress",
+    tag = "compress",
+    operation_id = "compress_upload",
+    request_body(content = CompressUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "The compressed file, as JPEG for images", content_type = "application/octet-stream"),
+        (status = 400, description = "Missing file or size, or an invalid size", body = String),
+        (status = 413, description = "Upload larger than 1 GB", body = String),
+        (status = 415, description = "Not a JPEG/PNG image or MP4/MOV/AVI video", body = String),
+        (status = 500, description = "Internal Server Error", body = String),
+        (status = 503, description = "All image processing slots busy; retry after the Retry-After header", body = String)
+    )
+)]
+pub async fn compress_upload(
+    State(state): State<Arc<AppState>>,
+    multipart: Multipart,
+) -> Result<Response, Response> {
+    let (data, ext, size_param) = read_upload(multipart)
+        .await
+        .map_err(IntoResponse::into_response)?;
+    parse_size_param(&size_param)
+        .map_err(|e| AppError::BadRequest(e.to_string()).into_response())?;
+    tracing::info!(
+        "Received compress upload of {} bytes ({}) with size: {}",
+        data.len(),
+        ext,
+        size_param
+    );
+
+    let _permit = acquire_processing_permit(&state).await?;
+
+    // Identical uploads share the cache entry, like identical URLs do.
+    let cache_key = generate_cache_key(&format!("{:x}", Sha256::digest(&data)), &size_param);
+    let work =
+        AbortOnDrop::spawn(
+            async move { compress_bytes(&data, ext, &size_param, &cache_key).await },
+        );
+    let compressed = match work.await {
+        Ok(result) => result,
+        Err(e) => Err(e.into()),
+    }
+    .map_err(|e| {
+        tracing::error!("Compression failed: {}", e);
+        AppError::Other(format!("Compression failed: {}", e)).into_response()
+    })?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, compressed.mime),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"compressed.{}\"", compressed.ext),
+            ),
+        ],
+        compressed.bytes,
+    )
+        .into_response())
+}
+
+/// A compressed file and how to serve it.
+struct Compressed {
+    bytes: Vec<u8>,
+    ext: String,
+    mime: String,
+}
+
+/// Compresses `buffer`, a file of type `ext`, to the size asked for by
+/// `size_param`. Shared by the by-URL and by-upload endpoints.
+async fn compress_bytes(
+    buffer: &[u8],
+    ext: &str,
+    size_param: &str,
+    cache_key: &str,
+) -> Result<Compressed, Box<dyn std::error::Error + Send + Sync>> {
+    tracing::info!("File extension detected: {}", ext);
+    let (size_value, unit) = parse_size_param(size_param)?;
     let original_bytes = buffer.len() as f64;
 
-    let compressed_buffer = match ext.as_str() {
+    let compressed_buffer = match ext {
         "jpg" | "jpeg" | "png" => {
             tracing::info!("Compressing image file.");
             let target_bytes = match unit {
@@ -562,7 +803,7 @@ async fn process_compression(
                 SizeUnit::MB => size_value * 1024.0 * 1024.0,
                 SizeUnit::KB => size_value * 1024.0,
             };
-            compress_image(&buffer, target_bytes, &cache_key).await?.0
+            compress_image(buffer, target_bytes, cache_key).await?.0
         }
         "mp4" | "mov" | "avi" => {
             tracing::info!("Compressing video file.");
@@ -571,7 +812,7 @@ async fn process_compression(
                 SizeUnit::MB => size_value * 1024.0 * 1024.0,
                 SizeUnit::KB => size_value * 1024.0,
             };
-            compress_video(&buffer, target_bytes, &cache_key, &ext)
+            compress_video(buffer, target_bytes, cache_key, ext)
                 .await?
                 .0
         }
@@ -586,17 +827,133 @@ async fn process_compression(
         return Err("Compressed file is empty".into());
     }
 
-    // Save to local file for debugging
-    let filename = format!("compressed_debug.{}.{}", Uuid::new_v4(), ext);
-    let local_path = CACHE_DIR.join(&filename);
-    tracing::info!(
-        "Saving compressed file to local path: {}",
-        local_path.display()
-    );
-    fs::write(&local_path, &compressed_buffer).await?;
+    // Images are always re-encoded as JPEG, whatever they came in as.
+    let (ext, mime) = match ext {
+        "jpg" | "jpeg" | "png" => (CompressedImage::EXTENSION, CompressedImage::MIME),
+        other => (
+            other,
+            mime_guess::from_ext(other)
+                .first_raw()
+                .unwrap_or("application/octet-stream"),
+        ),
+    };
+
+    Ok(Compressed {
+        bytes: compressed_buffer,
+        ext: ext.to_string(),
+        mime: mime.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::app::test_state;
+    use axum::{body::Body, http::StatusCode, routing::post};
+    use image::{ImageFormat, Rgb, RgbImage};
+    use sea_orm::DatabaseConnection;
+    use tower::ServiceExt;
+
+    const BOUNDARY: &str = "compress-test-boundary";
+
+    fn app() -> Router {
+        Router::new()
+            .route("/api/compress", post(compress_upload))
+            .with_state(test_state(DatabaseConnection::Disconnected))
+    }
+
+    fn noisy_png() -> Vec<u8> {
+        let mut seed: u32 = 7;
+        let image = RgbImage::from_fn(256, 256, |x, y| {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            Rgb([x as u8, y as u8, (seed >> 24) as u8])
+        });
+        let mut png = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut png, ImageFormat::Png).unwrap();
+        png.into_inner()
+    }
 
-    // Return the local file path as the link for debugging
-    Ok(local_path.to_string_lossy().into_owned())
+    fn upload(content_type: &str, file: &[u8], size: Option<&str>) -> axum::http::Request<Body> {
+        let mut body = format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"upload\"\r\n\
+             Content-Type: {t}\r\n\r\n",
+            b = BOUNDARY,
+            t = content_type
+        )
+        .into_bytes();
+        body.extend_from_slice(file);
+        body.extend_from_slice(b"\r\n");
+        if let Some(size) = size {
+            body.extend_from_slice(
+                format!(
+                    "--{}\r\nContent-Disposition: form-data; name=\"size\"\r\n\r\n{}\r\n",
+                    BOUNDARY, size
+                )
+                .as_bytes(),
+            );
+        }
+        body.extend_from_slice(format!("--{}--\r\n", BOUNDARY).as_bytes());
+        axum::http::Request::post("/api/compress")
+            .header(
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={}", BOUNDARY),
+            )
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn uploaded_images_come_back_compressed() {
+        let png = noisy_png();
+        let response = app()
+            .oneshot(upload("image/png", &png, Some("30%")))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/jpeg");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(body.len() as f64 <= png.len() as f64 * 0.3);
+        assert_eq!(image::guess_format(&body).unwrap(), ImageFormat::Jpeg);
+    }
+
+    #[tokio::test]
+    async fn non_media_and_incomplete_uploads_are_rejected() {
+        for (request, status) in [
+            (
+                upload("text/plain", b"hello", Some("50%")),
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ),
+            // Declared as an image, but the bytes are a PDF.
+            (
+                upload("image/png", b"%PDF-1.7 not an image", Some("50%")),
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ),
+            (
+                upload("image/png", &noisy_png(), None),
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                upload("image/png", &noisy_png(), Some("tiny")),
+                StatusCode::BAD_REQUEST,
+            ),
+        ] {
+            let response = app().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), status);
+        }
+    }
+
+    #[test]
+    fn upload_types_map_to_pipeline_extensions() {
+        assert_eq!(upload_extension("image/jpeg"), Some("jpg"));
+        assert_eq!(
+            upload_extension("Video/QuickTime; codecs=avc1"),
+            Some("mov")
+        );
+        assert_eq!(upload_extension("application/pdf"), None);
+    }
 }
 
 pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
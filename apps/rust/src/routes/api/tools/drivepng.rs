@@ -1,50 +1,371 @@
-//! Handler for the drivepng endpoint.
+//! Google Drive images as PNG.
+//!
+//! `GET /api/drivepng?id=` downloads a publicly shared Drive file through the
+//! `uc?export=download` endpoint and serves it as `image/png`. Files that
+//! start with the PNG signature are streamed through as they arrive; other
+//! image formats are decoded and re-encoded under an image processing
+//! permit. Anything that isn't an image is refused.
 
+use crate::core::config::CONFIG;
+use crate::core::error::AppError;
+use crate::helpers::http::common_image_headers;
+use crate::infra::http_client::http_client_slow;
+use crate::infra::proxy_limits::ProxyLimits;
 use crate::routes::AppState;
-use axum::{response::IntoResponse, Json, Router};
-use serde::{Deserialize, Serialize};
-use serde_json;
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+    Router,
+};
+use bytes::{Bytes, BytesMut};
+use futures::{stream, StreamExt};
+use image::ImageFormat;
+use serde::Deserialize;
+use std::io::Cursor;
 use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::time::Instant;
 use utoipa::ToSchema;
 
 pub const ENDPOINT_METHOD: &str = "get";
 pub const ENDPOINT_PATH: &str = "/api/drivepng";
-pub const ENDPOINT_DESCRIPTION: &str = "Handles GET requests for the drivepng endpoint.";
+pub const ENDPOINT_DESCRIPTION: &str = "Serve a publicly shared Google Drive image as PNG";
 pub const ENDPOINT_TAG: &str = "drivepng";
 pub const OPERATION_ID: &str = "drivepng";
-pub const SUCCESS_RESPONSE_BODY: &str = "Json<ListResponse>";
+pub const SUCCESS_RESPONSE_BODY: &str = "Vec<u8>";
 
-/// Response structure for the Drivepng endpoint.
-/// Replace `serde_json::Value` with your actual data types and implement `utoipa::ToSchema` for complex types.
-#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
-pub struct ListResponse {
-    /// Success message
-    pub message: String,
-    /// List of items - replace with actual Vec<T> where T implements ToSchema
-    pub data: Vec<serde_json::Value>,
-    /// Total number of items
-    pub total: Option<u64>,
+const DRIVE_BASE_URL: &str = "https://drive.google.com";
+
+/// Drive files can be replaced or unshared, so caches keep them for a day
+/// rather than forever.
+const CACHE_CONTROL: &str = "public, max-age=86400";
+
+/// The first bytes of every PNG file.
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DrivePngQuery {
+    /// Drive file id, as in `https://drive.google.com/file/d/{id}/view`.
+    pub id: Option<String>,
+}
+
+/// Drive file ids are URL-safe base64-ish tokens.
+fn valid_file_id(id: &str) -> bool {
+    (10..=128).contains(&id.len())
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn png_response(body: Body, len: Option<u64>) -> Response {
+    let mut response = (
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static("image/png")),
+            (header::CACHE_CONTROL, HeaderValue::from_static(CACHE_CONTROL)),
+        ],
+        body,
+    )
+        .into_response();
+    if let Some(len) = len {
+        response.headers_mut().insert(header::CONTENT_LENGTH, len.into());
+    }
+    response
+}
+
+/// Re-encodes `bytes` as PNG, or refuses them if they aren't an image we
+/// can decode.
+fn to_png(bytes: &[u8]) -> Result<Vec<u8>, AppError> {
+    let not_an_image = || AppError::UnsupportedMediaType("Drive file is not a supported image".to_string());
+    let format = image::guess_format(bytes).map_err(|_| not_an_image())?;
+    if format == ImageFormat::Png {
+        return Ok(bytes.to_vec());
+    }
+    let image = image::load_from_memory_with_format(bytes, format).map_err(|_| not_an_image())?;
+    let mut png = Cursor::new(Vec::new());
+    image
+        .write_to(&mut png, ImageFormat::Png)
+        .map_err(|e| AppError::Other(format!("Failed to encode PNG: {}", e)))?;
+    Ok(png.into_inner())
+}
+
+/// Reads `response` until it has yielded at least `n` bytes or ended.
+async fn read_head(response: &mut reqwest::Response, n: usize) -> Result<BytesMut, AppError> {
+    let mut head = BytesMut::new();
+    while head.len() < n {
+        match response.chunk().await? {
+            Some(chunk) => head.extend_from_slice(&chunk),
+            None => break,
+        }
+    }
+    Ok(head)
+}
+
+/// [`to_png`] on a blocking thread, once `semaphore` grants a permit within
+/// `APP__IMAGE_PROCESSING_WAIT_SECONDS`.
+async fn convert(bytes: Bytes, semaphore: &Arc<Semaphore>) -> Result<Vec<u8>, AppError> {
+    let wait = std::time::Duration::from_secs(CONFIG.image_processing_wait_seconds);
+    let _permit = match tokio::time::timeout(wait, semaphore.clone().acquire_owned()).await {
+        Ok(Ok(permit)) => permit,
+        Ok(Err(_)) | Err(_) => {
+            return Err(AppError::ServiceUnavailable(
+                "No image processing slot available, try again later".to_string(),
+            ))
+        }
+    };
+    tokio::task::spawn_blocking(move || to_png(&bytes)).await?
+}
+
+/// Downloads Drive file `id` from `base_url` and answers with it as PNG.
+/// A streamed PNG is cut off once the proxy timeout has passed since the
+/// request started.
+async fn fetch_png(
+    base_url: &str,
+    id: &str,
+    limits: &ProxyLimits,
+    semaphore: &Arc<Semaphore>,
+) -> Result<Response, AppError> {
+    let deadline = Instant::now() + limits.timeout;
+    let url = format!("{}/uc?export=download&id={}", base_url, id);
+    let request = http_client_slow().client().get(&url).headers(common_image_headers());
+    let mut upstream = limits
+        .within_timeout(&url, async {
+            request
+                .send()
+                .await
+                .map_err(|e| AppError::BadGateway(format!("Failed to reach Google Drive: {}", e)))
+        })
+        .await?;
+
+    match upstream.status() {
+        status if status.is_success() => {}
+        reqwest::StatusCode::NOT_FOUND => {
+            return Err(AppError::NotFound(format!("Drive file {} not found", id)));
+        }
+        status => {
+            return Err(AppError::BadGateway(format!("Google Drive answered {}", status)));
+        }
+    }
+
+    let content_type = upstream
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if content_type.starts_with("text/html") {
+        // Drive's sign-in, quota and virus-scan pages are all HTML.
+        return Err(AppError::BadGateway(format!(
+            "Google Drive returned a page instead of file {}; is it shared publicly?",
+            id
+        )));
+    }
+
+    // Drive often labels downloads application/octet-stream, so the bytes
+    // decide whether this is an image.
+    limits.check_len(&url, upstream.content_length())?;
+    let len = upstream.content_length();
+    let head = limits
+        .within_timeout(&url, read_head(&mut upstream, PNG_SIGNATURE.len()))
+        .await?
+        .freeze();
+    if head.starts_with(PNG_SIGNATURE) {
+        let body = stream::once(async { Ok::<_, reqwest::Error>(head) }).chain(upstream.bytes_stream());
+        return Ok(png_response(Body::from_stream(limits.limit_stream_until(deadline, body)), len));
+    }
+
+    let rest = limits.read_body(&url, upstream).await?;
+    let png = convert([head, rest].concat().into(), semaphore).await?;
+    let len = png.len() as u64;
+    Ok(png_response(Body::from(png), Some(len)))
 }
 
 #[utoipa::path(
     get,
+    params(
+        ("id" = String, Query, description = "Google Drive file id of a publicly shared image", example = "1a2B3c4D5e6F7g8H9i0J")
+    ),
     path = "/api/drivepng",
     tag = "drivepng",
     operation_id = "drivepng",
     responses(
-        (status = 200, description = "Handles GET requests for the drivepng endpoint.", body = ListResponse),
-        (status = 500, description = "Internal Server Error", body = String)
+        (status = 200, description = "The image as PNG", content_type = "image/png", body = Vec<u8>),
+        (status = 400, description = "Missing or malformed id", body = String),
+        (status = 404, description = "No such Drive file", body = String),
+        (status = 413, description = "File exceeds the proxy size limit", body = String),
+        (status = 415, description = "Drive file is not an image", body = String),
+        (status = 502, description = "Google Drive could not be reached or refused the download", body = String),
+        (status = 503, description = "No image processing slot became free in time", body = String),
+        (status = 504, description = "Google Drive did not respond in time", body = String)
     )
 )]
-pub async fn drivepng() -> impl IntoResponse {
-    Json(ListResponse {
-        message: "Hello from drivepng!".to_string(),
-        data: vec![],
-        total: None,
-    })
+pub async fn drivepng(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<DrivePngQuery>,
+) -> Result<Response, AppError> {
+    let id = params.id.as_deref().map(str::trim).unwrap_or_default();
+    if id.is_empty() {
+        return Err(AppError::BadRequest("Parameter id is required".to_string()));
+    }
+    if !valid_file_id(id) {
+        return Err(AppError::BadRequest(format!("Invalid Drive file id: {}", id)));
+    }
+    fetch_png(
+        DRIVE_BASE_URL,
+        id,
+        &ProxyLimits::from_config(),
+        &state.image_processing_semaphore,
+    )
+    .await
 }
 
-/// Handles GET requests for the drivepng endpoint.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::app::test_state;
+    use axum::{http::StatusCode, routing::get};
+    use image::{Rgb, RgbImage};
+    use sea_orm::DatabaseConnection;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn limits() -> ProxyLimits {
+        ProxyLimits { timeout: Duration::from_secs(5), max_body_bytes: 1 << 20 }
+    }
+
+    fn permits() -> Arc<Semaphore> {
+        Arc::new(Semaphore::new(1))
+    }
+
+    fn encoded(format: ImageFormat) -> Vec<u8> {
+        let image = RgbImage::from_fn(8, 6, |x, y| Rgb([x as u8 * 30, y as u8 * 40, 90]));
+        let mut out = Cursor::new(Vec::new());
+        image.write_to(&mut out, format).unwrap();
+        out.into_inner()
+    }
+
+    /// A stand-in for Drive's `uc` endpoint keyed on the `id` parameter.
+    async fn serve_drive() -> String {
+        let uc = |Query(query): Query<HashMap<String, String>>| async move {
+            let png = || ([(header::CONTENT_TYPE, "image/png")], encoded(ImageFormat::Png)).into_response();
+            match query.get("id").map(String::as_str) {
+                Some("png-file-0001") => png(),
+                Some("jpeg-file-0001") => {
+                    ([(header::CONTENT_TYPE, "application/octet-stream")], encoded(ImageFormat::Jpeg)).into_response()
+                }
+                Some("text-file-0001") => {
+                    ([(header::CONTENT_TYPE, "application/octet-stream")], "just text").into_response()
+                }
+                Some("fake-png-00001") => ([(header::CONTENT_TYPE, "image/png")], "not a png at all").into_response(),
+                Some("jpeg-as-png-01") => {
+                    ([(header::CONTENT_TYPE, "image/png")], encoded(ImageFormat::Jpeg)).into_response()
+                }
+                Some("stalled-png-01") => {
+                    // The signature, then nothing more.
+                    let body = stream::once(async { Ok::<_, std::io::Error>(Bytes::from_static(PNG_SIGNATURE)) })
+                        .chain(stream::pending());
+                    ([(header::CONTENT_TYPE, "image/png")], Body::from_stream(body)).into_response()
+                }
+                Some("private-file-1") => axum::response::Html("<html>Sign in</html>").into_response(),
+                Some("broken-file-01") => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+                _ => StatusCode::NOT_FOUND.into_response(),
+            }
+        };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, Router::new().route("/uc", get(uc))).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    fn status_of(result: Result<Response, AppError>) -> StatusCode {
+        match result {
+            Ok(response) => response.status(),
+            Err(e) => e.into_response().status(),
+        }
+    }
+
+    async fn body_of(response: Response) -> Vec<u8> {
+        axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec()
+    }
+
+    #[tokio::test]
+    async fn images_come_back_as_png() {
+        let base = serve_drive().await;
+
+        let response = fetch_png(&base, "png-file-0001", &limits(), &permits()).await.unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/png");
+        assert_eq!(response.headers()[header::CACHE_CONTROL], CACHE_CONTROL);
+        assert_eq!(body_of(response).await, encoded(ImageFormat::Png));
+
+        // Converted whatever Drive labelled them.
+        for id in ["jpeg-file-0001", "jpeg-as-png-01"] {
+            let response = fetch_png(&base, id, &limits(), &permits()).await.unwrap();
+            assert_eq!(response.headers()[header::CONTENT_TYPE], "image/png");
+            let png = body_of(response).await;
+            assert_eq!(image::guess_format(&png).unwrap(), ImageFormat::Png, "{}", id);
+            let decoded = image::load_from_memory(&png).unwrap();
+            assert_eq!((decoded.width(), decoded.height()), (8, 6));
+        }
+    }
+
+    #[tokio::test]
+    async fn conversions_wait_for_a_processing_permit() {
+        let base = serve_drive().await;
+        let closed = permits();
+        closed.close();
+
+        let converted = fetch_png(&base, "jpeg-file-0001", &limits(), &closed).await;
+        assert_eq!(status_of(converted), StatusCode::SERVICE_UNAVAILABLE);
+
+        // PNGs pass straight through and need no permit.
+        let streamed = fetch_png(&base, "png-file-0001", &limits(), &closed).await;
+        assert_eq!(status_of(streamed), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn stalled_pngs_end_at_the_deadline() {
+        let base = serve_drive().await;
+        let limits = ProxyLimits { timeout: Duration::from_millis(300), ..limits() };
+
+        let response = fetch_png(&base, "stalled-png-01", &limits, &permits()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = tokio::time::timeout(
+            Duration::from_secs(5),
+            axum::body::to_bytes(response.into_body(), usize::MAX),
+        )
+        .await
+        .expect("stalled body was never cut off");
+        assert!(body.is_err());
+    }
+
+    #[tokio::test]
+    async fn failures_map_to_statuses() {
+        let base = serve_drive().await;
+        for (id, status) in [
+            ("missing-file-1", StatusCode::NOT_FOUND),
+            ("broken-file-01", StatusCode::BAD_GATEWAY),
+            ("private-file-1", StatusCode::BAD_GATEWAY),
+            ("text-file-0001", StatusCode::UNSUPPORTED_MEDIA_TYPE),
+            ("fake-png-00001", StatusCode::UNSUPPORTED_MEDIA_TYPE),
+        ] {
+            assert_eq!(status_of(fetch_png(&base, id, &limits(), &permits()).await), status, "{}", id);
+        }
+
+        // Nothing listens on port 1.
+        let unreachable = fetch_png("http://127.0.0.1:1", "png-file-0001", &limits(), &permits()).await;
+        assert_eq!(status_of(unreachable), StatusCode::BAD_GATEWAY);
+    }
+
+    #[tokio::test]
+    async fn ids_are_required_and_validated() {
+        for id in [None, Some("  "), Some("../../etc/passwd"), Some("short")] {
+            let query = DrivePngQuery { id: id.map(str::to_string) };
+            let state = test_state(DatabaseConnection::Disconnected);
+            assert_eq!(status_of(drivepng(State(state), Query(query)).await), StatusCode::BAD_REQUEST, "{:?}", id);
+        }
+    }
+}
 
 pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
     router
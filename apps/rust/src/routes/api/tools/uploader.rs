@@ -1,12 +1,26 @@
 //! Handler for the uploader endpoint.
 
+use crate::core::error::AppError;
+use crate::di::Inject;
+use crate::events::{FileUploaded, EVENT_BUS};
+use crate::middleware::auth::CurrentUser;
 use crate::routes::AppState;
-use axum::{response::IntoResponse, Json, Router};
+use crate::storage::{FileMetadata, Storage, StorageError};
+use axum::{
+    body::Body,
+    extract::{Multipart, Path},
+    http::header,
+    response::{IntoResponse, Response},
+    Json, Router,
+};
 use serde::{Deserialize, Serialize};
 use serde_json;
 use std::sync::Arc;
 use utoipa::ToSchema;
 
+/// Maximum accepted upload size (25MB).
+pub const MAX_UPLOAD_SIZE: usize = 25 * 1024 * 1024;
+
 pub const ENDPOINT_METHOD: &str = "get";
 pub const ENDPOINT_PATH: &str = "/api/uploader";
 pub const ENDPOINT_DESCRIPTION: &str = "Handles GET requests for the uploader endpoint.";
@@ -44,7 +58,338 @@ pub async fn uploader() -> impl IntoResponse {
     })
 }
 
-/// Handles GET requests for the uploader endpoint.
+/// Response for a stored upload.
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+pub struct UploadResponse {
+    pub url: String,
+    pub path: String,
+    pub size: usize,
+}
+
+/// Keeps the client's file name usable as a storage key.
+fn sanitize_file_name(name: &str) -> String {
+    let cleaned: String = name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect();
+    let cleaned = cleaned.trim_start_matches('.');
+    if cleaned.is_empty() {
+        "upload".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/uploader",
+    tag = "uploader",
+    operation_id = "uploader_upload",
+    security(("bearer_auth" = [])),
+    request_body(content_type = "multipart/form-data", description = "File in the `file` field"),
+    responses(
+        (status = 200, description = "File stored", body = UploadResponse),
+        (status = 401, description = "Missing, expired or invalid token"),
+        (status = 500, description = "Internal Server Error", body = String),
+        (status = 503, description = "File storage is not configured", body = String)
+    )
+)]
+pub async fn upload(
+    user: CurrentUser,
+    storage: Inject<Storage>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Failed to read multipart: {}", e)))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let file_name = sanitize_file_name(field.file_name().unwrap_or("upload"));
+        let mime = field
+            .content_type()
+            .unwrap_or("application/octet-stream")
+            .to_string();
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| AppError::BadRequest(format!("Failed to read file: {}", e)))?;
+
+        if data.len() > MAX_UPLOAD_SIZE {
+            return Err(AppError::BadRequest(format!(
+                "File too large: {} bytes (max: {} bytes)",
+                data.len(),
+                MAX_UPLOAD_SIZE
+            )));
+        }
+
+        let path = format!("uploads/{}/{}-{}", user.user_id, uuid::Uuid::new_v4(), file_name);
+        storage
+            .put_with_mime(&path, &data, &mime)
+            .await
+            .map_err(|e| AppError::Other(format!("Failed to store upload: {}", e)))?;
+        let url = storage
+            .url(&path)
+            .await
+            .map_err(|e| AppError::Other(format!("Failed to resolve upload URL: {}", e)))?;
+
+        EVENT_BUS
+            .publish(FileUploaded {
+                url: url.clone(),
+                path: path.clone(),
+                size: data.len(),
+                mime,
+                user_id: user.user_id.clone(),
+            })
+            .await;
+
+        return Ok(Json(UploadResponse {
+            url,
+            path,
+            size: data.len(),
+        }));
+    }
+
+    Err(AppError::BadRequest("No file provided. Use field name 'file'".to_string()))
+}
+
+/// Storage key of a file served at `/api/uploader/{file_name}`.
+fn upload_key(file_name: &str) -> Result<String, AppError> {
+    let file_name = file_name.trim_start_matches('/');
+    if file_name.is_empty() || file_name.split('/').any(|part| part.is_empty() || part == "..") {
+        return Err(AppError::BadRequest(format!("Invalid file name: {}", file_name)));
+    }
+    Ok(format!("uploads/{}", file_name))
+}
+
+fn storage_error(e: StorageError) -> AppError {
+    match e {
+        StorageError::NotFound(path) => AppError::NotFound(format!("File not found: {}", path)),
+        StorageError::InvalidPath(reason) => AppError::BadRequest(reason),
+        e => AppError::Other(format!("Failed to read upload: {}", e)),
+    }
+}
+
+/// A file response; `body` is empty for HEAD.
+///
+/// The stored type is whatever the uploader claimed, so files are always
+/// served as attachments the browser must not sniff; an uploaded HTML page
+/// never renders on our origin.
+fn file_response(metadata: &FileMetadata, key: &str, body: Body) -> Response {
+    let mime = metadata
+        .mime_type
+        .clone()
+        .unwrap_or_else(|| mime_guess::from_path(key).first_or_octet_stream().to_string());
+    (
+        [
+            (header::CONTENT_TYPE, mime),
+            (header::CONTENT_LENGTH, metadata.size.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", sanitize_file_name(key)),
+            ),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/uploader/{file_name}",
+    tag = "uploader",
+    operation_id = "uploader_download",
+    params(("file_name" = String, Path, allow_reserved, description = "Upload path as returned by POST /api/uploader, without the `uploads/` prefix")),
+    responses(
+        (status = 200, description = "The file, streamed as an attachment", content_type = "application/octet-stream"),
+        (status = 400, description = "Invalid file name", body = String),
+        (status = 404, description = "No such file", body = String),
+        (status = 503, description = "File storage is not configured", body = String)
+    )
+)]
+pub async fn download(
+    storage: Inject<Storage>,
+    Path(file_name): Path<String>,
+) -> Result<Response, AppError> {
+    let key = upload_key(&file_name)?;
+    let file = storage.stream(&key).await.map_err(storage_error)?;
+    Ok(file_response(&file.metadata, &key, Body::from_stream(file.body)))
+}
+
+#[utoipa::path(
+    head,
+    path = "/api/uploader/{file_name}",
+    tag = "uploader",
+    operation_id = "uploader_head",
+    params(("file_name" = String, Path, allow_reserved, description = "Upload path as returned by POST /api/uploader, without the `uploads/` prefix")),
+    responses(
+        (status = 200, description = "Headers of the file (Content-Type, Content-Length) without its body"),
+        (status = 400, description = "Invalid file name"),
+        (status = 404, description = "No such file"),
+        (status = 503, description = "File storage is not configured")
+    )
+)]
+pub async fn head(
+    storage: Inject<Storage>,
+    Path(file_name): Path<String>,
+) -> Result<Response, AppError> {
+    let key = upload_key(&file_name)?;
+    let metadata = storage.metadata(&key).await.map_err(storage_error)?;
+    Ok(file_response(&metadata, &key, Body::empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::di::ServiceContainer;
+    use axum::{http::{Request, StatusCode}, routing::{get, post}};
+    use tower::ServiceExt;
+
+    const BOUNDARY: &str = "upload-test-boundary";
+
+    /// The uploader on a bare container, as the signed-in user `u1`.
+    fn app(container: ServiceContainer) -> Router {
+        let user = CurrentUser {
+            user_id: "u1".to_string(),
+            email: "u1@example.com".to_string(),
+            name: "U1".to_string(),
+        };
+        Router::new()
+            .route("/api/uploader", post(upload))
+            .layer(axum::Extension(user))
+            .with_state(Arc::new(container))
+    }
+
+    fn multipart_request(file_name: &str, content: &str) -> Request<Body> {
+        multipart_request_as(file_name, "text/plain", content)
+    }
+
+    fn multipart_request_as(file_name: &str, mime: &str, content: &str) -> Request<Body> {
+        let body = format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{f}\"\r\n\
+             Content-Type: {m}\r\n\r\n{c}\r\n--{b}--\r\n",
+            b = BOUNDARY,
+            f = file_name,
+            m = mime,
+            c = content
+        );
+        Request::post("/api/uploader")
+            .header("content-type", format!("multipart/form-data; boundary={}", BOUNDARY))
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn uploads_go_to_the_injected_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let container = ServiceContainer::new();
+        container.register(Storage::new(
+            crate::storage::LocalDriver::new(dir.path().to_str().unwrap()).with_base_url("https://files.test"),
+        ));
+
+        let response = app(container).oneshot(multipart_request("notes.txt", "hello")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let uploaded: UploadResponse = serde_json::from_slice(&body).unwrap();
+
+        assert!(uploaded.path.starts_with("uploads/u1/") && uploaded.path.ends_with("-notes.txt"));
+        assert_eq!(uploaded.url, format!("https://files.test/{}", uploaded.path));
+        assert_eq!(std::fs::read_to_string(dir.path().join(&uploaded.path)).unwrap(), "hello");
+    }
+
+    #[tokio::test]
+    async fn missing_storage_is_503() {
+        let response = app(ServiceContainer::new()).oneshot(multipart_request("a.txt", "x")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    fn files_app(dir: &std::path::Path) -> Router {
+        let container = ServiceContainer::new();
+        container.register(Storage::local(dir.to_str().unwrap()));
+        Router::new()
+            .route("/api/uploader/{*file_name}", get(download).head(head))
+            .with_state(Arc::new(container))
+    }
+
+    #[tokio::test]
+    async fn head_reports_size_without_a_body() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("uploads/u1")).unwrap();
+        std::fs::write(dir.path().join("uploads/u1/a-notes.txt"), "hello world").unwrap();
+
+        let request = Request::head("/api/uploader/u1/a-notes.txt").body(Body::empty()).unwrap();
+        let response = files_app(dir.path()).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_LENGTH], "11");
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/plain");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(body.is_empty());
+
+        let request = Request::get("/api/uploader/u1/a-notes.txt").body(Body::empty()).unwrap();
+        let response = files_app(dir.path()).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_LENGTH], "11");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"hello world");
+    }
+
+    #[tokio::test]
+    async fn html_uploads_download_as_attachments() {
+        let dir = tempfile::tempdir().unwrap();
+        let container = ServiceContainer::new();
+        container.register(Storage::local(dir.path().to_str().unwrap()));
+        let request = multipart_request_as("page.html", "text/html", "<script>alert(1)</script>");
+        let response = app(container).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let uploaded: UploadResponse = serde_json::from_slice(&body).unwrap();
+
+        let uri = format!("/api/{}", uploaded.path.replacen("uploads/", "uploader/", 1));
+        for request in [Request::get(&uri), Request::head(&uri)] {
+            let response = files_app(dir.path())
+                .oneshot(request.body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let disposition = response.headers()[header::CONTENT_DISPOSITION].to_str().unwrap();
+            assert!(disposition.starts_with("attachment;"), "{}", disposition);
+            assert!(disposition.ends_with("-page.html\""), "{}", disposition);
+            assert_eq!(response.headers()[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+        }
+    }
+
+    #[tokio::test]
+    async fn missing_and_invalid_files_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        for (uri, status) in [
+            ("/api/uploader/u1/missing.png", StatusCode::NOT_FOUND),
+            ("/api/uploader/u1/../../secret", StatusCode::BAD_REQUEST),
+        ] {
+            for request in [Request::get(uri), Request::head(uri)] {
+                let response = files_app(dir.path())
+                    .oneshot(request.body(Body::empty()).unwrap())
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), status, "{}", uri);
+            }
+        }
+    }
+
+    #[test]
+    fn file_names_are_sanitized() {
+        assert_eq!(sanitize_file_name("../../etc/passwd"), "passwd");
+        assert_eq!(sanitize_file_name("C:\\tmp\\my file.png"), "my_file.png");
+        assert_eq!(sanitize_file_name(".."), "upload");
+    }
+}
 
 pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
     router
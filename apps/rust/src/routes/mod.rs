@@ -2,9 +2,12 @@ pub mod api;
 pub mod ws;
 use std::sync::Arc;
 
+use axum::Router;
 use deadpool_redis::Pool;
 use sea_orm::DatabaseConnection;
 
+use crate::di::{HasServices, ServiceContainer};
+
 #[allow(dead_code)]
 pub struct AppState {
     pub jwt_secret: String,
@@ -14,6 +17,11 @@ pub struct AppState {
     pub chat_tx: tokio::sync::broadcast::Sender<crate::routes::ws::models::WsMessage>,
     pub image_processing_semaphore: Arc<tokio::sync::Semaphore>,
     pub room_manager: Arc<crate::ws::room::RoomManager>,
+    pub chat_rooms: Arc<crate::routes::ws::chat::ChatRooms>,
+    /// Per-source upstream circuit breakers (same registry the proxy uses).
+    pub source_breakers: Arc<crate::circuit_breaker::SourceBreakers>,
+    /// Shared services by type; see [`crate::di::AppServices`].
+    pub services: Arc<ServiceContainer>,
 }
 
 impl AppState {
@@ -21,4 +29,63 @@ impl AppState {
     pub fn sea_orm(&self) -> &DatabaseConnection {
         &self.db
     }
+
+    /// The registered `T`, if any. Handlers can take
+    /// [`Inject<T>`](crate::di::Inject) instead.
+    pub fn resolve<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.services.resolve::<T>()
+    }
+}
+
+impl HasServices for Arc<AppState> {
+    fn services(&self) -> &ServiceContainer {
+        &self.services
+    }
+}
+
+/// Routes whose `/api/v2` response differs from v1, with paths relative to
+/// `/api/v2` (e.g. `/anime/ongoing`). Every other `/api/v2` path is served by
+/// the v1 handler; see [`crate::routing::versioned_api`].
+pub fn create_v2_routes() -> Router<Arc<AppState>> {
+    Router::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::api::ApiDoc;
+    use utoipa::OpenApi;
+
+    #[test]
+    fn openapi_declares_bearer_auth_on_protected_paths_only() {
+        let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+        let scheme = &doc["components"]["securitySchemes"]["bearer_auth"];
+        assert_eq!(scheme["type"], "http");
+        assert_eq!(scheme["scheme"], "bearer");
+        assert_eq!(scheme["bearerFormat"], "JWT");
+        assert!(doc.get("security").is_none(), "no document-wide requirement");
+
+        let requires_token = |path: &str, method: &str| {
+            doc["paths"][path][method]["security"]
+                .as_array()
+                .is_some_and(|reqs| reqs.iter().any(|r| r.get("bearer_auth").is_some()))
+        };
+        for (path, method) in [
+            ("/api/uploader", "post"),
+            ("/api/bookmarks", "get"),
+            ("/api/bookmarks", "post"),
+            ("/api/bookmarks/{id}", "delete"),
+            ("/api/me/preferences", "get"),
+            ("/api/me/preferences", "put"),
+            ("/api/auth/me", "get"),
+            ("/api/social/posts", "post"),
+            ("/api/chat/history", "get"),
+            ("/api/chat/messages", "get"),
+        ] {
+            assert!(requires_token(path, method), "{} {} should require bearer_auth", method, path);
+        }
+        for (path, method) in [("/api/auth/login", "post"), ("/api/anime", "get")] {
+            assert!(!requires_token(path, method), "{} {} should be public", method, path);
+        }
+    }
 }
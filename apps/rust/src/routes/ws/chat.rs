@@ -1,67 +1,630 @@
 use axum::{
     extract::{
-        ws::{Message, WebSocket, WebSocketUpgrade},
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
         State,
     },
-    response::IntoResponse,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     routing::get,
     Router,
 };
-use futures::{sink::SinkExt, stream::StreamExt};
-use std::sync::Arc;
+use chrono::Utc;
+use dashmap::DashMap;
+use futures::{
+    sink::{Sink, SinkExt},
+    stream::StreamExt,
+};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, Notify};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
 
-use super::models::WsMessage;
+use super::models::{ChatMessage, WsMessage};
+use super::relay::{self, RecentIds, RelayEnvelope};
+use super::resume::{self, ResumeSession};
+use crate::core::config::CONFIG;
+use crate::middleware::auth::{authenticate, CurrentUser};
+use crate::observability::metrics::record_ws_idle_disconnect;
 use crate::routes::AppState;
+use crate::services::chat::{self as chat_service, HistoryPage};
+
+/// Messages replayed to a client when it joins a room, and at most the
+/// missed messages replayed when it resumes.
+const HISTORY_ON_JOIN: u64 = 50;
+/// Per-room broadcast buffer; slow clients lagging further than this skip ahead.
+const ROOM_CHANNEL_CAPACITY: usize = 256;
+/// Frames a connection may send per [`RATE_WINDOW`].
+const RATE_LIMIT: usize = 10;
+const RATE_WINDOW: Duration = Duration::from_secs(10);
+/// Largest text frame accepted, in bytes.
+const MAX_FRAME_BYTES: usize = 8 * 1024;
+/// Longest chat message content, in characters.
+const MAX_CONTENT_CHARS: usize = 2000;
+/// Rejected frames after which the connection is closed.
+const MAX_VIOLATIONS: u32 = 5;
+/// How long the writer gets to flush once the reader has finished.
+const WRITER_DRAIN: Duration = Duration::from_secs(1);
+
+/// Per-room broadcast channels carrying serialized `WsMessage` JSON.
+///
+/// Also owns the chat side of shutdown: once [`ChatRooms::begin_shutdown`] is
+/// called new upgrades are refused, every connection is sent a close frame,
+/// and [`ChatRooms::wait_for_saves`] lets the server wait for messages that
+/// are still being persisted.
+///
+/// Chat messages go through [`ChatRooms::deliver`], which remembers recent
+/// message ids so a message that also comes back over the Redis relay (see
+/// [`super::relay`]) reaches each subscriber only once.
+#[derive(Default)]
+pub struct ChatRooms {
+    rooms: DashMap<String, broadcast::Sender<String>>,
+    shutdown: CancellationToken,
+    saves_in_flight: AtomicUsize,
+    saves_done: Notify,
+    connections: AtomicUsize,
+    origin: InstanceId,
+    delivered: Mutex<RecentIds>,
+}
+
+/// Random id identifying this instance on the chat relay.
+struct InstanceId(String);
+
+impl Default for InstanceId {
+    fn default() -> Self {
+        Self(uuid::Uuid::new_v4().to_string())
+    }
+}
+
+/// Decrements the in-flight save count when a tracked save finishes.
+struct SaveGuard<'a>(&'a ChatRooms);
+
+impl Drop for SaveGuard<'_> {
+    fn drop(&mut self) {
+        if self.0.saves_in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.saves_done.notify_waiters();
+        }
+    }
+}
+
+/// Counts an open WebSocket connection until dropped.
+pub struct ConnectionGuard<'a>(&'a ChatRooms);
+
+impl Drop for ConnectionGuard<'_> {
+    fn drop(&mut self) {
+        self.0.connections.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl ChatRooms {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Subscribe to `room`, creating its channel on first use.
+    pub fn subscribe(&self, room: &str) -> broadcast::Receiver<String> {
+        self.rooms
+            .entry(room.to_string())
+            .or_insert_with(|| broadcast::channel(ROOM_CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+
+    /// Send `payload` to every subscriber of `room`. Returns the number reached.
+    pub fn publish(&self, room: &str, payload: String) -> usize {
+        self.rooms
+            .get(room)
+            .and_then(|tx| tx.send(payload).ok())
+            .unwrap_or(0)
+    }
+
+    /// Send chat message `id` to the subscribers of `room` unless it was
+    /// already delivered. Returns the number reached (0 for a duplicate).
+    pub fn deliver(&self, room: &str, id: &str, payload: String) -> usize {
+        let fresh = match self.delivered.lock() {
+            Ok(mut delivered) => delivered.insert(id),
+            Err(poisoned) => poisoned.into_inner().insert(id),
+        };
+        if !fresh {
+            tracing::debug!("Dropping duplicate delivery of message {} in {}", id, room);
+            return 0;
+        }
+        self.publish(room, payload)
+    }
 
+    /// Wraps a message delivered here for the relay.
+    pub fn envelope(&self, room: &str, id: &str, payload: String) -> RelayEnvelope {
+        RelayEnvelope {
+            origin: self.origin.0.clone(),
+            room: room.to_string(),
+            id: id.to_string(),
+            payload,
+        }
+    }
+
+    /// Delivers a relayed message, ignoring the echo of our own messages.
+    pub fn accept_relay(&self, envelope: RelayEnvelope) -> usize {
+        if envelope.origin == self.origin.0 {
+            return 0;
+        }
+        self.deliver(&envelope.room, &envelope.id, envelope.payload)
+    }
+
+    /// Drop the channel of `room` once nobody is subscribed.
+    pub fn prune(&self, room: &str) {
+        self.rooms.remove_if(room, |_, tx| tx.receiver_count() == 0);
+    }
+
+    pub fn room_count(&self) -> usize {
+        self.rooms.len()
+    }
+
+    /// Counts a connection as open for as long as the guard lives.
+    pub fn track_connection(&self) -> ConnectionGuard<'_> {
+        self.connections.fetch_add(1, Ordering::SeqCst);
+        ConnectionGuard(self)
+    }
+
+    /// Open chat WebSocket connections on this instance.
+    pub fn connection_count(&self) -> usize {
+        self.connections.load(Ordering::SeqCst)
+    }
+
+    pub fn is_closing(&self) -> bool {
+        self.shutdown.is_cancelled()
+    }
+
+    /// Stops accepting connections and tells every open connection to close.
+    pub fn begin_shutdown(&self) {
+        if !self.shutdown.is_cancelled() {
+            tracing::info!("🛑 Closing chat connections");
+            self.shutdown.cancel();
+        }
+    }
+
+    /// Token cancelled by [`ChatRooms::begin_shutdown`].
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown.clone()
+    }
+
+    /// Runs `save` on its own task so it completes even if the connection that
+    /// issued it goes away, and counts it towards [`ChatRooms::wait_for_saves`].
+    pub async fn track_save<F>(self: &Arc<Self>, save: F) -> Option<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.saves_in_flight.fetch_add(1, Ordering::SeqCst);
+        let rooms = self.clone();
+        tokio::spawn(async move {
+            let _guard = SaveGuard(&rooms);
+            save.await
+        })
+        .await
+        .ok()
+    }
+
+    pub fn saves_in_flight(&self) -> usize {
+        self.saves_in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Waits until no tracked save is running. Returns `false` if `timeout`
+    /// elapsed first.
+    pub async fn wait_for_saves(&self, timeout: Duration) -> bool {
+        tokio::time::timeout(timeout, async {
+            loop {
+                let notified = self.saves_done.notified();
+                tokio::pin!(notified);
+                notified.as_mut().enable();
+                if self.saves_in_flight() == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+}
+
+/// Keep-alive for one connection: the writer pings every `interval` and
+/// closes the socket once nothing has been received for `timeout`.
+#[derive(Clone)]
+struct Heartbeat {
+    interval: Duration,
+    timeout: Duration,
+    last_seen: Arc<Mutex<Instant>>,
+}
+
+impl Heartbeat {
+    fn new(interval: Duration, timeout: Duration) -> Self {
+        Self {
+            interval,
+            timeout,
+            last_seen: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// From `APP__WS_PING_INTERVAL_SECONDS` and `APP__WS_IDLE_TIMEOUT_SECONDS`.
+    fn from_config() -> Self {
+        Self::new(
+            Duration::from_secs(CONFIG.ws_ping_interval_seconds.max(1)),
+            Duration::from_secs(CONFIG.ws_idle_timeout_seconds.max(1)),
+        )
+    }
+
+    /// The peer sent a frame.
+    fn seen(&self) {
+        let now = Instant::now();
+        match self.last_seen.lock() {
+            Ok(mut last) => *last = now,
+            Err(poisoned) => *poisoned.into_inner() = now,
+        }
+    }
+
+    fn idle_for(&self) -> Duration {
+        match self.last_seen.lock() {
+            Ok(last) => last.elapsed(),
+            Err(poisoned) => poisoned.into_inner().elapsed(),
+        }
+    }
+}
+
+/// Limits on what one connection may send: frame size, frame rate and
+/// message length. Each rejection counts as a violation; after
+/// [`MAX_VIOLATIONS`] the connection is closed.
+#[derive(Default)]
+struct FrameLimiter {
+    /// Arrival times of the frames admitted within the last window.
+    recent: VecDeque<Instant>,
+    violations: u32,
+}
+
+impl FrameLimiter {
+    /// Admits a text frame of `len` bytes arriving at `now`, or says why not.
+    fn admit(&mut self, len: usize, now: Instant) -> Result<(), String> {
+        while self
+            .recent
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) >= RATE_WINDOW)
+        {
+            self.recent.pop_front();
+        }
+        if len > MAX_FRAME_BYTES {
+            return self.reject(format!("Message too large (max {} bytes)", MAX_FRAME_BYTES));
+        }
+        if self.recent.len() >= RATE_LIMIT {
+            return self.reject(format!(
+                "Too many messages (max {} per {}s); slow down",
+                RATE_LIMIT,
+                RATE_WINDOW.as_secs()
+            ));
+        }
+        self.recent.push_back(now);
+        Ok(())
+    }
+
+    /// Checks chat message `content` before it is saved.
+    fn check_content(&mut self, content: &str) -> Result<(), String> {
+        if content.trim().is_empty() {
+            return self.reject("Message is empty".to_string());
+        }
+        if content.chars().count() > MAX_CONTENT_CHARS {
+            return self.reject(format!(
+                "Message too long (max {} characters)",
+                MAX_CONTENT_CHARS
+            ));
+        }
+        Ok(())
+    }
+
+    fn reject(&mut self, reason: String) -> Result<(), String> {
+        self.violations += 1;
+        Err(reason)
+    }
+
+    fn exhausted(&self) -> bool {
+        self.violations >= MAX_VIOLATIONS
+    }
+}
+
+/// Identity comes from the token (Bearer header or `token` cookie) presented
+/// on the upgrade request; without one the connection is a guest that may
+/// read public rooms but not post. Private rooms admit only their members.
 pub async fn chat_websocket_handler(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| websocket_connection(socket, state))
+    headers: HeaderMap,
+) -> Response {
+    if state.chat_rooms.is_closing() {
+        return (StatusCode::SERVICE_UNAVAILABLE, "Server is shutting down").into_response();
+    }
+    let user = authenticate(&headers).ok();
+    ws.on_upgrade(move |socket| websocket_connection(socket, state, user))
 }
 
-async fn websocket_connection(socket: WebSocket, state: Arc<AppState>) {
-    let (mut sender, mut receiver) = socket.split();
+/// The room a connection has joined and the task forwarding its broadcasts.
+struct Membership {
+    room_id: String,
+    user_id: String,
+    user_name: String,
+    forward: JoinHandle<()>,
+}
 
-    // Subscribe to broadcast channel
-    let mut rx = state.chat_tx.subscribe();
+impl Membership {
+    fn session(&self) -> ResumeSession {
+        ResumeSession {
+            room_id: self.room_id.clone(),
+            user_id: self.user_id.clone(),
+            user_name: self.user_name.clone(),
+        }
+    }
+}
 
-    // Task for receiving messages from broadcast and sending to client
-    let mut send_task = tokio::spawn(async move {
-        while let Ok(msg) = rx.recv().await {
-            let text = serde_json::to_string(&msg).unwrap_or_default();
-            if sender.send(Message::Text(text.into())).await.is_err() {
+fn to_json(msg: &WsMessage) -> String {
+    serde_json::to_string(msg).unwrap_or_default()
+}
+
+/// Forwards queued text frames to `sink` and pings the peer every
+/// heartbeat interval, until the queue closes, the peer goes away, or
+/// `shutdown` fires. Shutdown and an idle peer both end with a 1001 close
+/// frame. The reader cancels `closed` to drop a peer that broke the frame
+/// limits: what is queued is flushed, then a 1008 close frame is sent.
+/// The writer cancels `closed` itself on the way out so the reader stops
+/// too.
+async fn write_loop<S>(
+    mut sink: S,
+    mut out_rx: mpsc::Receiver<String>,
+    shutdown: CancellationToken,
+    heartbeat: Heartbeat,
+    closed: CancellationToken,
+) where
+    S: Sink<Message> + Unpin,
+{
+    let _closed = closed.clone().drop_guard();
+    let mut ping =
+        tokio::time::interval_at(Instant::now() + heartbeat.interval, heartbeat.interval);
+    ping.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            biased;
+            _ = shutdown.cancelled() => {
+                let frame = CloseFrame {
+                    code: close_code::AWAY,
+                    reason: "server shutting down".into(),
+                };
+                let _ = sink.send(Message::Close(Some(frame))).await;
+                break;
+            }
+            _ = closed.cancelled() => {
+                // Flush the error frames explaining why first.
+                while let Ok(text) = out_rx.try_recv() {
+                    if sink.send(Message::Text(text.into())).await.is_err() {
+                        return;
+                    }
+                }
+                let frame = CloseFrame {
+                    code: close_code::POLICY,
+                    reason: "message limits exceeded".into(),
+                };
+                let _ = sink.send(Message::Close(Some(frame))).await;
                 break;
             }
+            _ = ping.tick() => {
+                let idle = heartbeat.idle_for();
+                if idle >= heartbeat.timeout {
+                    tracing::info!("Closing chat connection idle for {:?}", idle);
+                    record_ws_idle_disconnect();
+                    let frame = CloseFrame {
+                        code: close_code::AWAY,
+                        reason: "idle timeout".into(),
+                    };
+                    let _ = sink.send(Message::Close(Some(frame))).await;
+                    break;
+                }
+                if sink.send(Message::Ping(Default::default())).await.is_err() {
+                    break;
+                }
+            }
+            next = out_rx.recv() => match next {
+                Some(text) => {
+                    if sink.send(Message::Text(text.into())).await.is_err() {
+                        break;
+                    }
+                }
+                None => break,
+            },
         }
-    });
+    }
+}
+
+async fn websocket_connection(socket: WebSocket, state: Arc<AppState>, user: Option<CurrentUser>) {
+    let rooms = state.chat_rooms.clone();
+    let _connection = rooms.track_connection();
+    let (sender, mut receiver) = socket.split();
+
+    // Single writer: room broadcasts and direct replies both go through `out_tx`.
+    let (out_tx, out_rx) = mpsc::channel::<String>(ROOM_CHANNEL_CAPACITY);
+    let heartbeat = Heartbeat::from_config();
+    let closed = CancellationToken::new();
+    let mut send_task = tokio::spawn(write_loop(
+        sender,
+        out_rx,
+        state.chat_rooms.shutdown_token(),
+        heartbeat.clone(),
+        closed.clone(),
+    ));
+
+    // Whatever a `join` frame claims, this is who the connection is.
+    let (user_id, user_name) = match &user {
+        Some(u) => (u.user_id.clone(), u.name.clone()),
+        None => (format!("guest-{}", &resume::new_token()[..8]), "Guest".to_string()),
+    };
+    let mut token = resume::new_token();
+    let _ = out_tx
+        .send(to_json(&WsMessage::Session {
+            token: token.clone(),
+        }))
+        .await;
 
-    // Task for receiving messages from client and broadcasting
-    let tx = state.chat_tx.clone();
+    let recv_state = state.clone();
     let mut recv_task = tokio::spawn(async move {
-        while let Some(Ok(msg)) = receiver.next().await {
-            if let Message::Text(text) = msg {
-                if let Ok(ws_msg) = serde_json::from_str::<WsMessage>(&text) {
-                    let _ = tx.send(ws_msg);
+        let state = recv_state;
+        let mut membership: Option<Membership> = None;
+        let mut limiter = FrameLimiter::default();
+
+        loop {
+            let msg = tokio::select! {
+                // The writer gave up (idle peer, shutdown); stop reading so
+                // the membership below is released.
+                _ = closed.cancelled() => break,
+                next = receiver.next() => match next {
+                    Some(Ok(msg)) => msg,
+                    _ => break,
+                },
+            };
+            heartbeat.seen();
+            let text = match msg {
+                Message::Text(text) => text,
+                // Pings are answered by the protocol layer; either way the
+                // peer is alive, which `seen` already noted.
+                Message::Ping(_) | Message::Pong(_) | Message::Binary(_) => continue,
+                Message::Close(_) => break,
+            };
+            if let Err(reason) = limiter.admit(text.len(), Instant::now()) {
+                if reject(&out_tx, &limiter, &closed, reason).await {
+                    break;
+                }
+                continue;
+            }
+            let ws_msg = match serde_json::from_str::<WsMessage>(&text) {
+                Ok(m) => m,
+                Err(e) => {
+                    let _ = out_tx
+                        .send(to_json(&WsMessage::Error { message: format!("Invalid message: {}", e) }))
+                        .await;
+                    continue;
+                }
+            };
+
+            match ws_msg {
+                WsMessage::Join { room_id, .. } => {
+                    if !may_join(&state, &out_tx, &room_id, user.as_ref()).await {
+                        continue;
+                    }
+                    if let Some(old) = membership.take() {
+                        leave_room(&state, old);
+                    }
+                    let member =
+                        join_room(&state, &out_tx, room_id, user_id.clone(), user_name.clone(), None).await;
+                    resume::save(&state, &token, &member.session()).await;
+                    membership = Some(member);
+                }
+                WsMessage::Leave { .. } => {
+                    if let Some(old) = membership.take() {
+                        leave_room(&state, old);
+                        resume::forget(&state, &token).await;
+                    }
+                }
+                WsMessage::Resume { token: resumed, last_id } => {
+                    let Some(session) = resume::load(&state, &resumed).await else {
+                        let _ = out_tx
+                            .send(to_json(&WsMessage::Error { message: "Session expired; join the room again".to_string() }))
+                            .await;
+                        continue;
+                    };
+                    // Authenticated connections can't take over someone else's session.
+                    if user.as_ref().is_some_and(|u| u.user_id != session.user_id) {
+                        let _ = out_tx
+                            .send(to_json(&WsMessage::Error { message: "Session belongs to another user".to_string() }))
+                            .await;
+                        continue;
+                    }
+                    // Membership may have been revoked since the session was saved.
+                    if !may_join(&state, &out_tx, &session.room_id, user.as_ref()).await {
+                        continue;
+                    }
+                    if let Some(old) = membership.take() {
+                        leave_room(&state, old);
+                    }
+                    let ResumeSession { room_id, user_id, user_name } = session;
+                    let member = join_room(&state, &out_tx, room_id, user_id, user_name, last_id.as_deref()).await;
+                    token = resumed;
+                    resume::save(&state, &token, &member.session()).await;
+                    membership = Some(member);
+                    let _ = out_tx
+                        .send(to_json(&WsMessage::Session { token: token.clone() }))
+                        .await;
+                }
+                WsMessage::Send { .. } | WsMessage::Message { .. } if user.is_none() => {
+                    let _ = out_tx
+                        .send(to_json(&WsMessage::Error { message: "Authentication required to send messages".to_string() }))
+                        .await;
+                }
+                WsMessage::Send { content, message_type } => {
+                    if let Err(reason) = limiter.check_content(&content) {
+                        if reject(&out_tx, &limiter, &closed, reason).await {
+                            break;
+                        }
+                        continue;
+                    }
+                    let Some(member) = membership.as_ref() else {
+                        let _ = out_tx
+                            .send(to_json(&WsMessage::Error { message: "Join a room first".to_string() }))
+                            .await;
+                        continue;
+                    };
+                    post_message(&state, member, content, message_type.unwrap_or_else(|| "text".to_string())).await;
                 }
+                WsMessage::Message { room_id, message } => {
+                    if let Err(reason) = limiter.check_content(&message.content) {
+                        if reject(&out_tx, &limiter, &closed, reason).await {
+                            break;
+                        }
+                        continue;
+                    }
+                    // Only accepted for the joined room; never relayed across rooms.
+                    match membership.as_ref() {
+                        Some(member) if member.room_id == room_id => {
+                            post_message(&state, member, message.content, message.message_type).await;
+                        }
+                        _ => {
+                            let _ = out_tx
+                                .send(to_json(&WsMessage::Error { message: format!("Not a member of room {}", room_id) }))
+                                .await;
+                        }
+                    }
+                }
+                _ => {}
             }
         }
+
+        if let Some(old) = membership.take() {
+            // The resume window starts when the connection drops.
+            resume::save(&state, &token, &old.session()).await;
+            leave_room(&state, old);
+        }
     });
 
     // Wait for either task to finish and ensure proper cleanup
     tokio::select! {
         result = &mut send_task => {
-            recv_task.abort();
-            // Wait for recv_task to actually finish
+            // The reader sees `closed` and leaves its room on the way out.
             let _ = recv_task.await;
             if let Err(e) = result {
                 tracing::warn!("Send task error: {:?}", e);
             }
         },
         result = &mut recv_task => {
-            send_task.abort();
-            // Wait for send_task to actually finish
-            let _ = send_task.await;
+            // Let the writer flush (and send any close frame) before giving up on it.
+            if tokio::time::timeout(WRITER_DRAIN, &mut send_task).await.is_err() {
+                send_task.abort();
+                let _ = send_task.await;
+            }
             if let Err(e) = result {
                 tracing::warn!("Recv task error: {:?}", e);
             }
@@ -71,6 +634,415 @@ async fn websocket_connection(socket: WebSocket, state: Arc<AppState>) {
     tracing::info!("WebSocket connection closed and cleaned up");
 }
 
+/// Tells the peer why its frame was refused. Returns `true` once it has
+/// run out of violations, after asking the writer to close the socket.
+async fn reject(
+    out_tx: &mpsc::Sender<String>,
+    limiter: &FrameLimiter,
+    closed: &CancellationToken,
+    reason: String,
+) -> bool {
+    let _ = out_tx
+        .send(to_json(&WsMessage::Error { message: reason }))
+        .await;
+    if !limiter.exhausted() {
+        return false;
+    }
+    tracing::warn!(
+        "Closing chat connection after {} rejected frames",
+        limiter.violations
+    );
+    closed.cancel();
+    true
+}
+
+/// Whether `user` may enter `room_id`; tells the peer why not when it can't.
+async fn may_join(
+    state: &Arc<AppState>,
+    out_tx: &mpsc::Sender<String>,
+    room_id: &str,
+    user: Option<&CurrentUser>,
+) -> bool {
+    let user_id = user.map(|u| u.user_id.as_str());
+    let message = match chat_service::can_join_room(state.sea_orm(), room_id, user_id).await {
+        Ok(true) => return true,
+        Ok(false) => format!("Cannot join room {}", room_id),
+        Err(e) => {
+            tracing::warn!("Failed to check access to room {}: {}", room_id, e);
+            "Could not join the room; try again".to_string()
+        }
+    };
+    let _ = out_tx.send(to_json(&WsMessage::Error { message })).await;
+    false
+}
+
+/// Subscribes to `room_id` and sends this client its history: the newest
+/// page, or only the messages after `last_seen` when resuming.
+async fn join_room(
+    state: &Arc<AppState>,
+    out_tx: &mpsc::Sender<String>,
+    room_id: String,
+    user_id: String,
+    user_name: String,
+    last_seen: Option<&str>,
+) -> Membership {
+    let mut rx = state.chat_rooms.subscribe(&room_id);
+
+    match history(state, &room_id, last_seen).await {
+        Ok(page) => {
+            let messages = page.messages.into_iter().map(ChatMessage::from).collect();
+            let _ = out_tx
+                .send(to_json(&WsMessage::History {
+                    room_id: room_id.clone(),
+                    messages,
+                    next_cursor: page.next_cursor,
+                }))
+                .await;
+        }
+        Err(e) => tracing::warn!("Failed to load history for room {}: {}", room_id, e),
+    }
+
+    let forward_tx = out_tx.clone();
+    let forward = tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(text) => {
+                    if forward_tx.send(text).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Chat client lagged, skipped {} messages", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+
+    state.chat_rooms.publish(
+        &room_id,
+        to_json(&WsMessage::UserJoined {
+            room_id: room_id.clone(),
+            user_id: user_id.clone(),
+            user_name: user_name.clone(),
+        }),
+    );
+
+    Membership { room_id, user_id, user_name, forward }
+}
+
+async fn history(
+    state: &Arc<AppState>,
+    room_id: &str,
+    last_seen: Option<&str>,
+) -> Result<HistoryPage, sea_orm::DbErr> {
+    let db = state.sea_orm();
+    if let Some(last_seen) = last_seen {
+        if let Some(missed) =
+            chat_service::load_messages_after(db, room_id, last_seen, HISTORY_ON_JOIN).await?
+        {
+            return Ok(missed);
+        }
+        // Not a message of this room (or since deleted): start over.
+        tracing::debug!("Unknown resume cursor {} in {}", last_seen, room_id);
+    }
+    chat_service::load_messages_paginated(db, room_id, None, HISTORY_ON_JOIN).await
+}
+
+fn leave_room(state: &Arc<AppState>, member: Membership) {
+    member.forward.abort();
+    state.chat_rooms.publish(
+        &member.room_id,
+        to_json(&WsMessage::UserLeft {
+            room_id: member.room_id.clone(),
+            user_id: member.user_id,
+            user_name: member.user_name,
+        }),
+    );
+    state.chat_rooms.prune(&member.room_id);
+}
+
+async fn post_message(state: &Arc<AppState>, member: &Membership, content: String, message_type: String) {
+    // Tracked so shutdown waits for the insert before closing the pool.
+    let save = {
+        let db = state.db.clone();
+        let (room_id, user_id, content) = (member.room_id.clone(), member.user_id.clone(), content.clone());
+        async move { chat_service::save_message(&db, &room_id, &user_id, &content).await }
+    };
+    let (id, created_at) = match state.chat_rooms.track_save(save).await {
+        Some(Ok(saved)) => (saved.id, saved.created_at),
+        Some(Err(e)) => {
+            tracing::warn!("Failed to persist chat message in {}: {}", member.room_id, e);
+            (uuid::Uuid::new_v4().to_string(), Utc::now())
+        }
+        None => {
+            tracing::warn!("Chat message save task for {} did not complete", member.room_id);
+            (uuid::Uuid::new_v4().to_string(), Utc::now())
+        }
+    };
+
+    let message = ChatMessage {
+        id: id.clone(),
+        room_id: member.room_id.clone(),
+        user_id: member.user_id.clone(),
+        user_name: member.user_name.clone(),
+        content,
+        message_type,
+        created_at,
+    };
+    let payload = to_json(&WsMessage::Message { room_id: member.room_id.clone(), message });
+    state.chat_rooms.deliver(&member.room_id, &id, payload.clone());
+    relay::publish(state, &state.chat_rooms.envelope(&member.room_id, &id, payload)).await;
+}
+
 pub fn register_routes(router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
     router.route("/ws/chat", get(chat_websocket_handler))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn broadcasts_are_scoped_to_room() {
+        let rooms = ChatRooms::new();
+        let mut a = rooms.subscribe("a");
+        let mut b = rooms.subscribe("b");
+
+        assert_eq!(rooms.publish("a", "hello a".to_string()), 1);
+        assert_eq!(a.recv().await.unwrap(), "hello a");
+        assert!(b.try_recv().is_err());
+
+        assert_eq!(rooms.publish("missing", "nobody".to_string()), 0);
+    }
+
+    #[test]
+    fn prune_drops_rooms_without_subscribers() {
+        let rooms = ChatRooms::new();
+        let rx = rooms.subscribe("a");
+        rooms.prune("a");
+        assert_eq!(rooms.room_count(), 1);
+
+        drop(rx);
+        rooms.prune("a");
+        assert_eq!(rooms.room_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn shutdown_waits_for_pending_save() {
+        let rooms = Arc::new(ChatRooms::new());
+        let saved = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let flag = saved.clone();
+        let pending = tokio::spawn({
+            let rooms = rooms.clone();
+            async move {
+                rooms
+                    .track_save(async move {
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        flag.store(true, Ordering::SeqCst);
+                    })
+                    .await
+            }
+        });
+        tokio::task::yield_now().await;
+        assert_eq!(rooms.saves_in_flight(), 1);
+
+        rooms.begin_shutdown();
+        // The connection that issued the save may be torn down meanwhile.
+        pending.abort();
+
+        assert!(rooms.wait_for_saves(Duration::from_secs(1)).await);
+        assert!(saved.load(Ordering::SeqCst));
+        assert_eq!(rooms.saves_in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn wait_for_saves_times_out() {
+        let rooms = Arc::new(ChatRooms::new());
+        let stuck = tokio::spawn({
+            let rooms = rooms.clone();
+            async move { rooms.track_save(std::future::pending::<()>()).await }
+        });
+        tokio::task::yield_now().await;
+
+        assert!(!rooms.wait_for_saves(Duration::from_millis(20)).await);
+        stuck.abort();
+    }
+
+    #[tokio::test]
+    async fn writer_sends_close_frame_on_shutdown() {
+        let rooms = ChatRooms::new();
+        let (sink, mut frames) = futures::channel::mpsc::unbounded::<Message>();
+        let (out_tx, out_rx) = mpsc::channel(8);
+
+        let heartbeat = Heartbeat::new(Duration::from_secs(60), Duration::from_secs(60));
+        let writer = tokio::spawn(write_loop(
+            sink,
+            out_rx,
+            rooms.shutdown_token(),
+            heartbeat,
+            CancellationToken::new(),
+        ));
+        out_tx.send("before".to_string()).await.unwrap();
+        assert!(matches!(frames.next().await, Some(Message::Text(t)) if t.as_str() == "before"));
+
+        rooms.begin_shutdown();
+        writer.await.unwrap();
+
+        match frames.next().await {
+            Some(Message::Close(Some(frame))) => assert_eq!(frame.code, close_code::AWAY),
+            other => panic!("expected close frame, got {:?}", other),
+        }
+        assert!(frames.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn writer_pings_live_peers_and_closes_idle_ones() {
+        let (sink, mut frames) = futures::channel::mpsc::unbounded::<Message>();
+        let (_out_tx, out_rx) = mpsc::channel::<String>(8);
+        let heartbeat = Heartbeat::new(Duration::from_millis(20), Duration::from_millis(50));
+        let closed = CancellationToken::new();
+
+        let writer = tokio::spawn(write_loop(
+            sink,
+            out_rx,
+            CancellationToken::new(),
+            heartbeat.clone(),
+            closed.clone(),
+        ));
+
+        // A peer that keeps answering is pinged and kept.
+        for _ in 0..4 {
+            assert!(matches!(frames.next().await, Some(Message::Ping(_))));
+            heartbeat.seen();
+        }
+        assert!(!closed.is_cancelled());
+
+        // Once it goes quiet, the writer closes the socket and tells the reader.
+        let close = loop {
+            match frames.next().await {
+                Some(Message::Ping(_)) => continue,
+                other => break other,
+            }
+        };
+        match close {
+            Some(Message::Close(Some(frame))) => {
+                assert_eq!(frame.code, close_code::AWAY);
+                assert_eq!(frame.reason.as_str(), "idle timeout");
+            }
+            other => panic!("expected close frame, got {:?}", other),
+        }
+        writer.await.unwrap();
+        assert!(closed.is_cancelled());
+        assert!(heartbeat.idle_for() >= Duration::from_millis(50));
+    }
+
+    #[test]
+    fn frames_over_the_rate_limit_are_rejected_until_the_window_passes() {
+        let mut limiter = FrameLimiter::default();
+        let start = Instant::now();
+        for i in 0..RATE_LIMIT {
+            assert!(limiter
+                .admit(10, start + Duration::from_millis(i as u64))
+                .is_ok());
+        }
+
+        let tripped = limiter
+            .admit(10, start + Duration::from_secs(1))
+            .unwrap_err();
+        assert!(tripped.contains("Too many messages"), "{}", tripped);
+        assert_eq!(limiter.violations, 1);
+
+        // The first frames age out of the window and make room again.
+        assert!(limiter
+            .admit(10, start + RATE_WINDOW + Duration::from_millis(1))
+            .is_ok());
+    }
+
+    #[test]
+    fn oversize_frames_and_content_are_rejected() {
+        let mut limiter = FrameLimiter::default();
+        let now = Instant::now();
+        assert!(limiter.admit(MAX_FRAME_BYTES, now).is_ok());
+        let too_big = limiter.admit(MAX_FRAME_BYTES + 1, now).unwrap_err();
+        assert!(too_big.contains("too large"), "{}", too_big);
+
+        assert!(limiter
+            .check_content(&"é".repeat(MAX_CONTENT_CHARS))
+            .is_ok());
+        assert!(limiter
+            .check_content(&"a".repeat(MAX_CONTENT_CHARS + 1))
+            .is_err());
+        assert!(limiter.check_content("   ").is_err());
+        // Rejected frames don't count towards the rate.
+        assert_eq!(limiter.recent.len(), 1);
+        assert_eq!(limiter.violations, 3);
+    }
+
+    #[tokio::test]
+    async fn repeat_offenders_get_errors_then_a_policy_close() {
+        let (sink, mut frames) = futures::channel::mpsc::unbounded::<Message>();
+        let (out_tx, out_rx) = mpsc::channel::<String>(16);
+        let closed = CancellationToken::new();
+        let heartbeat = Heartbeat::new(Duration::from_secs(60), Duration::from_secs(60));
+        let writer = tokio::spawn(write_loop(
+            sink,
+            out_rx,
+            CancellationToken::new(),
+            heartbeat,
+            closed.clone(),
+        ));
+
+        let mut limiter = FrameLimiter::default();
+        let mut kicked = false;
+        for _ in 0..MAX_VIOLATIONS {
+            let reason = limiter
+                .admit(MAX_FRAME_BYTES + 1, Instant::now())
+                .unwrap_err();
+            kicked = reject(&out_tx, &limiter, &closed, reason).await;
+        }
+        assert!(kicked);
+        writer.await.unwrap();
+
+        let mut errors = 0;
+        loop {
+            match frames.next().await {
+                Some(Message::Text(text)) => {
+                    assert!(text.as_str().contains("too large"));
+                    errors += 1;
+                }
+                Some(Message::Close(Some(frame))) => {
+                    assert_eq!(frame.code, close_code::POLICY);
+                    break;
+                }
+                other => panic!("unexpected frame {:?}", other),
+            }
+        }
+        assert_eq!(errors, MAX_VIOLATIONS);
+    }
+
+    #[test]
+    fn resume_round_trips_the_session_token() {
+        let msg: WsMessage =
+            serde_json::from_str(r#"{"type":"resume","token":"abc","last_id":"m42"}"#).unwrap();
+        assert!(matches!(
+            msg,
+            WsMessage::Resume { token, last_id } if token == "abc" && last_id.as_deref() == Some("m42")
+        ));
+        // A client that saw nothing yet may omit the cursor.
+        let msg: WsMessage = serde_json::from_str(r#"{"type":"resume","token":"abc"}"#).unwrap();
+        assert!(matches!(msg, WsMessage::Resume { last_id: None, .. }));
+
+        let session = to_json(&WsMessage::Session {
+            token: "abc".to_string(),
+        });
+        assert_eq!(session, r#"{"type":"session","token":"abc"}"#);
+    }
+
+    #[test]
+    fn join_accepts_room_alias() {
+        let msg: WsMessage = serde_json::from_str(r#"{"type":"join","room":"general"}"#).unwrap();
+        assert!(matches!(msg, WsMessage::Join { room_id, .. } if room_id == "general"));
+    }
+}
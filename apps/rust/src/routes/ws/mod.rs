@@ -1,5 +1,7 @@
 pub mod chat;
 pub mod models;
+pub mod relay;
+pub mod resume;
 
 use crate::routes::AppState;
 use axum::Router;
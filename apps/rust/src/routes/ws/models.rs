@@ -22,6 +22,20 @@ pub struct ChatMessage {
     pub created_at: DateTime<Utc>,
 }
 
+impl From<crate::entities::chat_message_room::Model> for ChatMessage {
+    fn from(row: crate::entities::chat_message_room::Model) -> Self {
+        Self {
+            id: row.id,
+            room_id: row.room_id,
+            user_id: row.user_id,
+            user_name: String::new(),
+            content: row.content,
+            message_type: "text".to_string(),
+            created_at: row.created_at,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RoomMember {
     pub room_id: String,
@@ -69,18 +83,48 @@ pub struct MessageQuery {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum WsMessage {
     Join {
+        #[serde(alias = "room")]
         room_id: String,
+        /// Accepted from older clients but ignored: the server assigns identity.
+        #[serde(default)]
         user_id: String,
+        #[serde(default)]
         user_name: String,
     },
     Leave {
         room_id: String,
         user_id: String,
     },
+    /// Server -> client: the token to `resume` this session with after a
+    /// drop. Sent on connect and again after a successful resume.
+    Session {
+        token: String,
+    },
+    /// Client -> server: rejoin the room of a dropped session and get the
+    /// messages after `last_id` instead of the usual history page.
+    Resume {
+        token: String,
+        #[serde(default)]
+        last_id: Option<String>,
+    },
+    /// Client -> server: post `content` to the joined room.
+    Send {
+        content: String,
+        #[serde(default)]
+        message_type: Option<String>,
+    },
     Message {
+        #[serde(alias = "room")]
         room_id: String,
         message: ChatMessage,
     },
+    /// Server -> client: newest page of room history, sent once after joining.
+    /// Older pages are available from `GET /api/chat/history`.
+    History {
+        room_id: String,
+        messages: Vec<ChatMessage>,
+        next_cursor: Option<String>,
+    },
     UserJoined {
         room_id: String,
         user_id: String,
@@ -0,0 +1,176 @@
+//! Cross-instance chat fan-out over Redis pub/sub.
+//!
+//! Every chat message is delivered to local subscribers first and then
+//! published on [`RELAY_CHANNEL`] wrapped in a [`RelayEnvelope`]. Each instance
+//! subscribes to the channel and hands envelopes to
+//! [`ChatRooms::accept_relay`], which drops the echo of messages this instance
+//! originated and anything already delivered under the same message id, so a
+//! client sees each message exactly once whichever path it arrives on.
+
+use futures::StreamExt;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use super::chat::ChatRooms;
+use crate::routes::AppState;
+
+/// Redis channel carrying [`RelayEnvelope`] JSON between instances.
+pub const RELAY_CHANNEL: &str = "chat:relay";
+
+/// How many recent message ids are remembered for deduplication.
+const RECENT_IDS_CAPACITY: usize = 4096;
+
+/// A chat payload as relayed between instances.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayEnvelope {
+    /// Instance that first delivered the message.
+    pub origin: String,
+    pub room: String,
+    /// Server message id the payload is deduplicated by.
+    pub id: String,
+    /// Serialized `WsMessage` JSON sent to clients as-is.
+    pub payload: String,
+}
+
+/// Bounded set of recently seen ids; the oldest id is forgotten first.
+#[derive(Debug)]
+pub struct RecentIds {
+    capacity: usize,
+    order: VecDeque<String>,
+    seen: HashSet<String>,
+}
+
+impl RecentIds {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            order: VecDeque::new(),
+            seen: HashSet::new(),
+        }
+    }
+
+    /// Records `id`. Returns `false` if it was already recorded.
+    pub fn insert(&mut self, id: &str) -> bool {
+        if self.seen.contains(id) {
+            return false;
+        }
+        if self.order.len() == self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        self.order.push_back(id.to_string());
+        self.seen.insert(id.to_string());
+        true
+    }
+}
+
+impl Default for RecentIds {
+    fn default() -> Self {
+        Self::new(RECENT_IDS_CAPACITY)
+    }
+}
+
+/// Publishes `envelope` for the other instances. Failures are logged; local
+/// subscribers already have the message.
+pub async fn publish(state: &AppState, envelope: &RelayEnvelope) {
+    let Ok(json) = serde_json::to_string(envelope) else {
+        return;
+    };
+    let result = match state.redis_pool.get().await {
+        Ok(mut conn) => conn.publish::<_, _, ()>(RELAY_CHANNEL, json).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    if let Err(e) = result {
+        debug!("Chat relay publish failed for {}: {}", envelope.room, e);
+    }
+}
+
+/// Subscribes to [`RELAY_CHANNEL`] and feeds envelopes into `rooms` until chat
+/// shutdown begins, reconnecting after connection errors.
+pub fn spawn_subscriber(rooms: Arc<ChatRooms>, redis_url: String) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let shutdown = rooms.shutdown_token();
+        let client = match redis::Client::open(redis_url) {
+            Ok(client) => client,
+            Err(e) => {
+                warn!("Chat relay disabled: invalid Redis URL: {}", e);
+                return;
+            }
+        };
+
+        while !shutdown.is_cancelled() {
+            let run = async {
+                let mut pubsub = client.get_async_pubsub().await?;
+                pubsub.subscribe(RELAY_CHANNEL).await?;
+                info!("📡 Chat relay subscribed to {}", RELAY_CHANNEL);
+
+                let mut messages = pubsub.on_message();
+                while let Some(msg) = messages.next().await {
+                    let Ok(raw) = msg.get_payload::<String>() else { continue };
+                    match serde_json::from_str::<RelayEnvelope>(&raw) {
+                        Ok(envelope) => {
+                            rooms.accept_relay(envelope);
+                        }
+                        Err(e) => debug!("Ignoring malformed chat relay payload: {}", e),
+                    }
+                }
+                Ok::<_, redis::RedisError>(())
+            };
+
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                result = run => {
+                    if let Err(e) = result {
+                        warn!("Chat relay connection lost: {}", e);
+                    }
+                    tokio::time::sleep(Duration::from_secs(2)).await;
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_ids_forget_the_oldest() {
+        let mut ids = RecentIds::new(2);
+        assert!(ids.insert("a"));
+        assert!(!ids.insert("a"));
+        assert!(ids.insert("b"));
+        assert!(ids.insert("c"));
+        // "a" was evicted to make room for "c".
+        assert!(ids.insert("a"));
+        assert!(!ids.insert("c"));
+    }
+
+    #[tokio::test]
+    async fn local_and_relayed_copies_are_delivered_once() {
+        let rooms = ChatRooms::new();
+        let other = ChatRooms::new();
+        let mut client = rooms.subscribe("lobby");
+
+        // Posted on this instance: delivered locally, then echoed back by Redis.
+        assert_eq!(rooms.deliver("lobby", "m1", "first".to_string()), 1);
+        let echo = rooms.envelope("lobby", "m1", "first".to_string());
+        assert_eq!(rooms.accept_relay(echo.clone()), 0);
+
+        // Posted on another instance: arrives via Redis, possibly twice.
+        let remote = other.envelope("lobby", "m2", "second".to_string());
+        assert_eq!(rooms.accept_relay(remote.clone()), 1);
+        assert_eq!(rooms.accept_relay(remote), 0);
+        // A stale copy of m1 relayed by some other instance is dropped too.
+        assert_eq!(rooms.accept_relay(RelayEnvelope { origin: "elsewhere".to_string(), ..echo }), 0);
+
+        assert_eq!(client.recv().await.unwrap(), "first");
+        assert_eq!(client.recv().await.unwrap(), "second");
+        assert!(client.try_recv().is_err());
+    }
+}
@@ -0,0 +1,89 @@
+//! Resume tokens for chat connections that drop.
+//!
+//! Every connection is sent a [`WsMessage::Session`](super::models::WsMessage)
+//! token when it opens. While it is in a room, the room and identity are kept
+//! in Redis under that token for `APP__WS_RESUME_TTL_SECONDS` after it was
+//! last saved (on join and again on disconnect). A client that reconnects
+//! within that window sends `resume` with the token and the id of the last
+//! message it saw, and is put back in the room with only the messages it
+//! missed instead of the usual history page.
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::core::config::CONFIG;
+use crate::helpers::Cache;
+use crate::routes::AppState;
+
+const KEY_PREFIX: &str = "chat:resume:";
+
+/// What a resumed connection picks up.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResumeSession {
+    pub room_id: String,
+    pub user_id: String,
+    pub user_name: String,
+}
+
+/// A fresh unguessable token.
+pub fn new_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Whether `token` looks like one [`new_token`] issued; anything else never
+/// reaches Redis.
+pub fn is_valid_token(token: &str) -> bool {
+    token.len() == 32 && token.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn key(token: &str) -> String {
+    format!("{}{}", KEY_PREFIX, token)
+}
+
+/// Stores `session` under `token`, restarting its TTL. Failures are logged:
+/// the connection works without resume.
+pub async fn save(state: &AppState, token: &str, session: &ResumeSession) {
+    let ttl = CONFIG.ws_resume_ttl_seconds.max(1);
+    if let Err(e) = Cache::new(&state.redis_pool)
+        .set_with_ttl(&key(token), session, ttl)
+        .await
+    {
+        warn!("Failed to save chat resume session: {}", e);
+    }
+}
+
+/// The session stored under `token`, unless it expired or never existed.
+pub async fn load(state: &AppState, token: &str) -> Option<ResumeSession> {
+    if !is_valid_token(token) {
+        return None;
+    }
+    Cache::new(&state.redis_pool).get(&key(token)).await
+}
+
+/// Drops the session after the client left its room on purpose.
+pub async fn forget(state: &AppState, token: &str) {
+    if let Err(e) = Cache::new(&state.redis_pool).delete(&key(token)).await {
+        debug!("Failed to drop chat resume session: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issued_tokens_are_valid_and_distinct() {
+        let (a, b) = (new_token(), new_token());
+        assert!(is_valid_token(&a) && is_valid_token(&b));
+        assert_ne!(a, b);
+        assert_eq!(key(&a), format!("chat:resume:{}", a));
+    }
+
+    #[test]
+    fn foreign_tokens_are_rejected() {
+        assert!(!is_valid_token(""));
+        assert!(!is_valid_token("not-a-token"));
+        assert!(!is_valid_token(&"z".repeat(32)));
+        assert!(!is_valid_token(&format!("{}*", "a".repeat(31))));
+    }
+}
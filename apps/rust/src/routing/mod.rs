@@ -2,4 +2,6 @@
 
 pub mod versioning;
 
-pub use versioning::{extract_version, versioned_routes, ApiVersion, VersionedApi};
+pub use versioning::{
+    extract_version, unversioned_path, versioned_api, versioned_routes, ApiVersion, VersionedApi,
+};
@@ -1,8 +1,25 @@
 //! API versioning utilities.
 //!
 //! Provides helpers for versioned API routes.
+//!
+//! The application's handlers are registered under plain `/api/...` paths and
+//! make up v1. [`versioned_api`] serves them at `/api/v1/...`, lets a v2 router
+//! override individual paths under `/api/v2/...` (everything else in v2 falls
+//! through to v1), and keeps the unversioned `/api/...` paths working with a
+//! `Deprecation` header pointing clients at `/api/v1`.
+
+use axum::{
+    extract::Request,
+    http::{header::HeaderName, HeaderValue, Uri},
+    middleware::Next,
+    response::Response,
+    Router,
+};
+use std::borrow::Cow;
+use tower::ServiceExt;
 
-use axum::Router;
+/// `Deprecation` response header (RFC 9745).
+pub const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
 
 /// API version prefix.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -133,3 +150,120 @@ pub fn extract_version(path: &str) -> Option<ApiVersion> {
         None
     }
 }
+
+/// `path` with a leading `/api/v1` or `/api/v2` reduced to `/api`, so code
+/// keyed on the unversioned paths (rate-limit groups, for one) treats every
+/// version alike.
+pub fn unversioned_path(path: &str) -> Cow<'_, str> {
+    for prefix in [ApiVersion::V1.prefix(), ApiVersion::V2.prefix()] {
+        if let Some(rest) = path.strip_prefix(prefix) {
+            if rest.is_empty() || rest.starts_with('/') {
+                return Cow::Owned(format!("/api{}", rest));
+            }
+        }
+    }
+    Cow::Borrowed(path)
+}
+
+/// `uri` with `/api` put back in front of a path its nesting prefix was
+/// stripped from, keeping the query.
+fn under_api(uri: &Uri) -> Uri {
+    let path_and_query = uri.path_and_query().map_or("/", |pq| pq.as_str());
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query = format!("/api{}", path_and_query).parse().ok();
+    Uri::from_parts(parts).unwrap_or_else(|_| uri.clone())
+}
+
+/// Marks responses from the unversioned `/api/...` paths as deprecated in
+/// favour of the same path under `/api/v1`.
+async fn deprecate_unversioned(req: Request, next: Next) -> Response {
+    let successor = req
+        .uri()
+        .path()
+        .strip_prefix("/api")
+        .map(|rest| format!("<{}{}>; rel=\"successor-version\"", ApiVersion::V1.prefix(), rest));
+
+    let mut response = next.run(req).await;
+    let headers = response.headers_mut();
+    headers.insert(DEPRECATION, HeaderValue::from_static("true"));
+    if let Some(link) = successor.and_then(|v| HeaderValue::from_str(&v).ok()) {
+        headers.insert(axum::http::header::LINK, link);
+    }
+    response
+}
+
+/// Mounts the API by version.
+///
+/// `v1` holds the current handlers, registered under `/api/...`; they are
+/// served at `/api/v1/...` and, deprecated, at `/api/...`. `v2` holds routes
+/// relative to `/api/v2` (e.g. `/anime/ongoing`) for endpoints whose v2
+/// response differs; other `/api/v2/...` paths are answered by v1.
+pub fn versioned_api(v1: Router, v2: Router) -> Router {
+    let v1_service = v1.clone().map_request(|mut req: Request| {
+        *req.uri_mut() = under_api(req.uri());
+        req
+    });
+
+    Router::new()
+        .nest_service(ApiVersion::V1.prefix(), v1_service.clone())
+        .nest_service(ApiVersion::V2.prefix(), v2.fallback_service(v1_service))
+        .merge(v1.layer(axum::middleware::from_fn(deprecate_unversioned)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, extract::Path, http::StatusCode, routing::get};
+
+    /// v1 has a detail and a list route; v2 only changes the list.
+    fn app() -> Router {
+        let v1 = Router::new()
+            .route("/api/anime/detail/{slug}", get(|Path(slug): Path<String>| async move { format!("v1 {}", slug) }))
+            .route("/api/anime/ongoing", get(|| async { "v1 ongoing" }));
+        let v2 = Router::new().route("/anime/ongoing", get(|| async { "v2 ongoing" }));
+        versioned_api(v1, v2)
+    }
+
+    async fn get_path(uri: &str) -> (StatusCode, Option<String>, String) {
+        let response = app().oneshot(axum::http::Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+        let status = response.status();
+        let deprecation = response.headers().get(DEPRECATION).map(|v| v.to_str().unwrap().to_string());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, deprecation, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn v1_serves_the_current_handlers() {
+        assert_eq!(get_path("/api/v1/anime/detail/frieren?debug=1").await, (StatusCode::OK, None, "v1 frieren".to_string()));
+        assert_eq!(get_path("/api/v1/anime/ongoing").await.2, "v1 ongoing");
+    }
+
+    #[tokio::test]
+    async fn v2_overrides_some_paths_and_falls_back_to_v1() {
+        assert_eq!(get_path("/api/v2/anime/ongoing").await.2, "v2 ongoing");
+        assert_eq!(get_path("/api/v2/anime/detail/frieren").await.2, "v1 frieren");
+        assert_eq!(get_path("/api/v2/anime/nope").await.0, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn unversioned_paths_are_deprecated_aliases_of_v1() {
+        let response = app()
+            .oneshot(axum::http::Request::get("/api/anime/detail/frieren").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[DEPRECATION], "true");
+        assert_eq!(
+            response.headers()[axum::http::header::LINK],
+            "</api/v1/anime/detail/frieren>; rel=\"successor-version\""
+        );
+    }
+
+    #[test]
+    fn versions_are_stripped_for_path_keyed_checks() {
+        assert_eq!(unversioned_path("/api/v1/anime/ongoing"), "/api/anime/ongoing");
+        assert_eq!(unversioned_path("/api/v2/komik/detail"), "/api/komik/detail");
+        assert_eq!(unversioned_path("/api/version"), "/api/version");
+        assert_eq!(unversioned_path("/api/anime"), "/api/anime");
+    }
+}
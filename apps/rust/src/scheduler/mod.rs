@@ -4,8 +4,12 @@
 
 pub mod cleanup_cache;
 pub mod cleanup_rooms;
+pub mod prewarm;
+pub mod prune;
 pub mod runner;
 
 pub use cleanup_cache::CleanupOldCache;
 pub use cleanup_rooms::CleanupEmptyRooms;
+pub use prewarm::PrewarmListCaches;
+pub use prune::{PruneSettings, PruneStaleData};
 pub use runner::{ScheduledTask, Scheduler};
@@ -0,0 +1,274 @@
+//! Scheduled job that keeps the list endpoints' caches warm.
+
+use serde::Serialize;
+use std::future::Future;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::helpers::cache::{Cache, Tagged};
+use crate::helpers::cache_ttl::CACHE_TTL_VERY_SHORT;
+use crate::observability::metrics::record_prewarm_run;
+use crate::routes::api::{anime, komik};
+use crate::routes::AppState;
+use crate::services::search_index::{self, SearchDocuments, SearchEntry, SearchKind};
+
+/// Re-fetches the anime ongoing/complete lists and the first page of the
+/// manga/manhwa/manhua lists every `interval_minutes` and writes them under
+/// the same keys the handlers read, so user requests are cache hits.
+///
+/// A source whose fetch fails is skipped; its previously cached response is
+/// left in place. Entries are written with a TTL of two intervals so one
+/// failed run does not let them expire. The titles of every refreshed list
+/// are added to the search index.
+pub struct PrewarmListCaches {
+    state: Arc<AppState>,
+    interval_minutes: u64,
+}
+
+impl PrewarmListCaches {
+    pub fn new(state: Arc<AppState>, interval_minutes: u64) -> Self {
+        Self {
+            state,
+            interval_minutes: interval_minutes.clamp(1, 59),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        "prewarm_list_caches"
+    }
+
+    /// Cron expression firing every `interval_minutes`.
+    pub fn schedule(&self) -> String {
+        prewarm_schedule(self.interval_minutes)
+    }
+
+    /// Refreshes every source once. Returns how many succeeded.
+    pub async fn run(&self) -> usize {
+        let state = self.state.as_ref();
+        let cache = Cache::new(&state.redis_pool);
+
+        let results = [
+            self.prewarm(
+                &cache,
+                "anime:index",
+                anime::index::INDEX_CACHE_KEY.to_string(),
+                async { anime::index::build_anime_index(state).await.and_then(Tagged::new) },
+            )
+            .await,
+            self.prewarm(
+                &cache,
+                "anime:ongoing",
+                anime::ongoing_anime::slug::page_cache_key("1"),
+                anime::ongoing_anime::slug::build_ongoing_page("1".to_string()),
+            )
+            .await,
+            self.prewarm(
+                &cache,
+                "anime:complete",
+                anime::complete_anime::slug::page_cache_key("1"),
+                anime::complete_anime::slug::build_complete_page("1".to_string()),
+            )
+            .await,
+            self.prewarm(
+                &cache,
+                "komik:manga",
+                komik::manga::slug::list_cache_key(1),
+                komik::manga::slug::build_manga_list(state, 1),
+            )
+            .await,
+            self.prewarm(
+                &cache,
+                "komik:manhwa",
+                komik::manhwa::slug::list_cache_key(1),
+                komik::manhwa::slug::build_manhwa_list(state, 1),
+            )
+            .await,
+            self.prewarm(
+                &cache,
+                "komik:manhua",
+                komik::manhua::slug::list_cache_key(1),
+                komik::manhua::slug::build_manhua_list(state, 1),
+            )
+            .await,
+        ];
+
+        let refreshed = results.iter().filter(|ok| **ok).count();
+        info!("🔥 Pre-warmed {}/{} list caches", refreshed, results.len());
+        refreshed
+    }
+
+    async fn prewarm<T, E, B>(&self, cache: &Cache<'_>, source: &str, key: String, build: B) -> bool
+    where
+        T: Serialize + SearchDocuments,
+        E: std::fmt::Display,
+        B: Future<Output = Result<T, E>>,
+    {
+        let ttl = prewarm_ttl(self.interval_minutes);
+        let pool = &self.state.redis_pool;
+        refresh(source, build, |value| async move {
+            cache.set_with_ttl(&key, &value, ttl).await?;
+            // A stale index only costs search results, not the refresh.
+            if let Err(e) = search_index::index_entries(pool, &value.search_entries()).await {
+                warn!("Failed to index {} for search: {}", source, e);
+            }
+            Ok(())
+        })
+        .await
+    }
+}
+
+impl<T: SearchDocuments> SearchDocuments for Tagged<T> {
+    fn search_entries(&self) -> Vec<SearchEntry> {
+        self.value.search_entries()
+    }
+}
+
+impl SearchDocuments for anime::index::AnimeDataResponse {
+    fn search_entries(&self) -> Vec<SearchEntry> {
+        let Some(data) = &self.data else {
+            return Vec::new();
+        };
+        let ongoing = data
+            .ongoing_anime
+            .iter()
+            .map(|a| SearchEntry::new(SearchKind::Anime, &a.slug, &a.title, &a.poster));
+        let complete = data
+            .complete_anime
+            .iter()
+            .map(|a| SearchEntry::new(SearchKind::Anime, &a.slug, &a.title, &a.poster));
+        ongoing.chain(complete).collect()
+    }
+}
+
+impl SearchDocuments for anime::ongoing_anime::slug::OngoingAnimeResponse {
+    fn search_entries(&self) -> Vec<SearchEntry> {
+        self.data
+            .iter()
+            .map(|a| SearchEntry::new(SearchKind::Anime, &a.slug, &a.title, &a.poster))
+            .collect()
+    }
+}
+
+impl SearchDocuments for anime::complete_anime::slug::ListResponse {
+    fn search_entries(&self) -> Vec<SearchEntry> {
+        self.data
+            .iter()
+            .map(|a| SearchEntry::new(SearchKind::Anime, &a.slug, &a.title, &a.poster))
+            .collect()
+    }
+}
+
+/// Manga, manhwa and manhua pages all share this type.
+impl SearchDocuments for komik::manga::slug::MangaResponse {
+    fn search_entries(&self) -> Vec<SearchEntry> {
+        self.data
+            .iter()
+            .map(|k| SearchEntry::new(SearchKind::Komik, &k.slug, &k.title, &k.poster))
+            .collect()
+    }
+}
+
+/// Cron expression (with seconds) firing every `minutes`, clamped to 1..=59.
+pub fn prewarm_schedule(minutes: u64) -> String {
+    format!("0 */{} * * * *", minutes.clamp(1, 59))
+}
+
+/// TTL for pre-warmed entries: two intervals, never below the handlers' own.
+fn prewarm_ttl(minutes: u64) -> u64 {
+    (minutes * 60 * 2).max(CACHE_TTL_VERY_SHORT)
+}
+
+/// Builds one source and hands the result to `store`. On a failed build
+/// `store` is never called, so the previously cached value survives.
+async fn refresh<T, E, B, S, SF>(source: &str, build: B, store: S) -> bool
+where
+    E: std::fmt::Display,
+    B: Future<Output = Result<T, E>>,
+    S: FnOnce(T) -> SF,
+    SF: Future<Output = Result<(), String>>,
+{
+    let start = std::time::Instant::now();
+    let outcome = match build.await {
+        Ok(value) => store(value).await,
+        Err(e) => Err(e.to_string()),
+    };
+
+    match outcome {
+        Ok(()) => {
+            info!("✓ Pre-warmed {}", source);
+            record_prewarm_run(source, true, start.elapsed().as_secs_f64());
+            true
+        }
+        Err(e) => {
+            warn!("Pre-warm of {} failed, keeping cached data: {}", source, e);
+            record_prewarm_run(source, false, start.elapsed().as_secs_f64());
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn failed_build_keeps_cached_value() {
+        let cached = Mutex::new(Some("good".to_string()));
+
+        let ok = refresh("komik:manhwa", async { Err::<String, _>("HTTP 502".to_string()) }, |v| {
+            *cached.lock().unwrap() = Some(v);
+            async { Ok(()) }
+        })
+        .await;
+        assert!(!ok);
+        assert_eq!(cached.lock().unwrap().as_deref(), Some("good"));
+
+        let ok = refresh("komik:manhwa", async { Ok::<_, String>("fresh".to_string()) }, |v| {
+            *cached.lock().unwrap() = Some(v);
+            async { Ok(()) }
+        })
+        .await;
+        assert!(ok);
+        assert_eq!(cached.lock().unwrap().as_deref(), Some("fresh"));
+    }
+
+    #[test]
+    fn schedule_and_ttl_follow_interval() {
+        assert_eq!(prewarm_schedule(4), "0 */4 * * * *");
+        assert_eq!(prewarm_schedule(0), "0 */1 * * * *");
+        assert_eq!(prewarm_schedule(120), "0 */59 * * * *");
+        assert_eq!(prewarm_ttl(1), CACHE_TTL_VERY_SHORT);
+        assert_eq!(prewarm_ttl(10), 1200);
+    }
+
+    #[test]
+    fn index_lists_become_anime_search_entries() {
+        let item = |slug: &str, title: &str| anime::index::OngoingAnimeItem {
+            title: title.to_string(),
+            slug: slug.to_string(),
+            poster: format!("https://cdn.example.com/{}.jpg", slug),
+            current_episode: "Episode 3".to_string(),
+            anime_url: String::new(),
+        };
+        let response = anime::index::AnimeDataResponse::success(anime::index::AnimeData {
+            ongoing_anime: vec![item("one-piece", "One Piece"), item("frieren", "Frieren")],
+            complete_anime: Vec::new(),
+        });
+
+        let entries = Tagged::new(response).unwrap().search_entries();
+        let titles: Vec<&str> = entries.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, ["One Piece", "Frieren"]);
+        assert!(entries.iter().all(|e| e.kind == SearchKind::Anime));
+        assert_eq!(entries[0].poster, "https://cdn.example.com/one-piece.jpg");
+    }
+
+    #[tokio::test]
+    async fn store_failure_is_reported() {
+        let ok = refresh("anime:index", async { Ok::<_, String>(1) }, |_| async {
+            Err("redis down".to_string())
+        })
+        .await;
+        assert!(!ok);
+    }
+}
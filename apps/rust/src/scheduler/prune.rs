@@ -0,0 +1,339 @@
+//! Scheduled job that deletes stale cache entries and expired rows.
+
+use chrono::{DateTime, Utc};
+use deadpool_redis::redis::{cmd, AsyncCommands};
+use deadpool_redis::Pool;
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::core::config::CONFIG;
+use crate::entities::{email_verification_token, image_cache, password_reset_token, session};
+use crate::infra::redis::DistLock;
+use crate::observability::metrics::record_pruned;
+use crate::routes::AppState;
+
+/// Cache key patterns the job may prune. Sessions, rate limits, locks and
+/// token blacklists live under other prefixes and are never touched.
+const CACHE_PATTERNS: [&str; 5] = ["anime:*", "anime2:*", "komik:*", "fetch:*", "img_cache:*"];
+
+/// Keys requested per `SCAN` round trip.
+const SCAN_BATCH: usize = 500;
+
+/// Redis key of the lock that keeps the job to one instance at a time.
+const LOCK_KEY: &str = "lock:prune_stale_data";
+
+/// How often the job runs and what counts as stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PruneSettings {
+    pub interval_minutes: u64,
+    /// Cache entries idle for longer than this are deleted, even if their
+    /// TTL hasn't run out.
+    pub cache_soft_ttl: Duration,
+    /// Rows are deleted this long after they were created or expired.
+    pub retention: Duration,
+}
+
+impl PruneSettings {
+    /// Settings from `APP__PRUNE_*`.
+    pub fn from_config() -> Self {
+        Self {
+            interval_minutes: CONFIG.prune_interval_minutes,
+            cache_soft_ttl: Duration::from_secs(CONFIG.prune_cache_soft_ttl_hours * 3600),
+            retention: Duration::from_secs(CONFIG.prune_retention_days * 86_400),
+        }
+    }
+}
+
+/// Every `interval_minutes`, deletes Redis cache entries nobody has touched
+/// within the soft TTL and DB rows past the retention window:
+///
+/// - `image_cache` rows created before the window, or already expired
+/// - sessions, email verification and password reset tokens that expired
+///   before the window
+///
+/// Runs under a [`DistLock`] so a multi-node deploy prunes once per
+/// tick; instances that don't get the lock skip the run.
+pub struct PruneStaleData {
+    state: Arc<AppState>,
+    settings: PruneSettings,
+}
+
+impl PruneStaleData {
+    pub fn new(state: Arc<AppState>, settings: PruneSettings) -> Self {
+        Self { state, settings }
+    }
+
+    pub fn name(&self) -> &'static str {
+        "prune_stale_data"
+    }
+
+    /// Cron expression firing every `interval_minutes`.
+    pub fn schedule(&self) -> String {
+        prune_schedule(self.settings.interval_minutes)
+    }
+
+    /// Prunes once if this instance gets the lock. Returns what was deleted
+    /// per target, or `None` if the run was skipped.
+    pub async fn run(&self) -> Option<Vec<(&'static str, u64)>> {
+        let pool = &self.state.redis_pool;
+        // Held for at most one interval, so a crashed run can't block the next.
+        let lock_ttl = Duration::from_secs(self.settings.interval_minutes.max(1) * 60);
+        let lock = match DistLock::new(pool.clone())
+            .try_acquire(LOCK_KEY, lock_ttl)
+            .await
+        {
+            Ok(Some(lock)) => lock,
+            Ok(None) => {
+                info!("Prune skipped: another instance holds {}", LOCK_KEY);
+                return None;
+            }
+            Err(e) => {
+                warn!("Prune skipped: could not take {}: {}", LOCK_KEY, e);
+                return None;
+            }
+        };
+
+        let mut deleted = Vec::new();
+        match prune_cache(pool, self.settings.cache_soft_ttl).await {
+            Ok(count) => deleted.push(("redis", count)),
+            Err(e) => warn!("Failed to prune stale cache entries: {}", e),
+        }
+        for (target, result) in
+            prune_rows(&self.state.db, Utc::now(), self.settings.retention).await
+        {
+            match result {
+                Ok(count) => deleted.push((target, count)),
+                Err(e) => warn!("Failed to prune {}: {}", target, e),
+            }
+        }
+
+        match lock.release().await {
+            Ok(true) => {}
+            Ok(false) => warn!(
+                "Prune outlived {}; another instance may have run too",
+                LOCK_KEY
+            ),
+            Err(e) => warn!(
+                "Failed to release {} (it expires on its own): {}",
+                LOCK_KEY, e
+            ),
+        }
+
+        for (target, count) in &deleted {
+            record_pruned(target, *count);
+        }
+        let summary: Vec<String> = deleted
+            .iter()
+            .map(|(t, c)| format!("{}={}", t, c))
+            .collect();
+        info!("🧹 Pruned stale data: {}", summary.join(", "));
+        Some(deleted)
+    }
+}
+
+/// Cron expression (with seconds) firing every `minutes`: every N minutes
+/// below an hour, else every N whole hours (at most daily).
+pub fn prune_schedule(minutes: u64) -> String {
+    match minutes {
+        0..=59 => format!("0 */{} * * * *", minutes.max(1)),
+        _ => format!("0 0 */{} * * *", (minutes / 60).clamp(1, 23)),
+    }
+}
+
+/// Deletes cache keys idle for longer than `soft_ttl`. Uses `SCAN`, so
+/// Redis keeps serving other clients while it runs.
+async fn prune_cache(pool: &Pool, soft_ttl: Duration) -> Result<u64, String> {
+    let mut conn = pool.get().await.map_err(|e| e.to_string())?;
+    let mut deleted = 0;
+
+    for pattern in CACHE_PATTERNS {
+        let mut cursor: u64 = 0;
+        loop {
+            let (next, keys): (u64, Vec<String>) = cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(pattern)
+                .arg("COUNT")
+                .arg(SCAN_BATCH)
+                .query_async(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            for key in keys {
+                // Errors under an LFU eviction policy, where Redis keeps no
+                // idle time; such keys are left to their TTL.
+                let idle: Option<u64> = cmd("OBJECT")
+                    .arg("IDLETIME")
+                    .arg(&key)
+                    .query_async(&mut *conn)
+                    .await
+                    .unwrap_or(None);
+                if idle.is_some_and(|idle| idle > soft_ttl.as_secs()) {
+                    let removed: u64 = conn.del(&key).await.unwrap_or(0);
+                    deleted += removed;
+                }
+            }
+
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+    }
+
+    Ok(deleted)
+}
+
+/// Deletes every table's rows past `retention` as of `now`.
+async fn prune_rows(
+    db: &DatabaseConnection,
+    now: DateTime<Utc>,
+    retention: Duration,
+) -> Vec<(&'static str, Result<u64, DbErr>)> {
+    let cutoff = chrono::Duration::from_std(retention)
+        .ok()
+        .and_then(|retention| now.checked_sub_signed(retention))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC);
+    vec![
+        ("image_cache", prune_image_cache(db, now, cutoff).await),
+        (
+            "session",
+            delete_before::<session::Entity>(db, session::Column::Expires, cutoff).await,
+        ),
+        (
+            "email_verification_token",
+            delete_before::<email_verification_token::Entity>(
+                db,
+                email_verification_token::Column::ExpiresAt,
+                cutoff,
+            )
+            .await,
+        ),
+        (
+            "password_reset_token",
+            delete_before::<password_reset_token::Entity>(
+                db,
+                password_reset_token::Column::ExpiresAt,
+                cutoff,
+            )
+            .await,
+        ),
+    ]
+}
+
+/// Image cache rows created before `cutoff` or already expired at `now`.
+async fn prune_image_cache(
+    db: &DatabaseConnection,
+    now: DateTime<Utc>,
+    cutoff: DateTime<Utc>,
+) -> Result<u64, DbErr> {
+    let result = image_cache::Entity::delete_many()
+        .filter(
+            Condition::any()
+                .add(image_cache::Column::CreatedAt.lt(cutoff))
+                .add(image_cache::Column::ExpiresAt.lt(now)),
+        )
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected)
+}
+
+/// `E`'s rows whose `column` timestamp is before `cutoff`.
+async fn delete_before<E: EntityTrait>(
+    db: &DatabaseConnection,
+    column: E::Column,
+    cutoff: DateTime<Utc>,
+) -> Result<u64, DbErr> {
+    let result = E::delete_many().filter(column.lt(cutoff)).exec(db).await?;
+    Ok(result.rows_affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{ActiveModelTrait, ConnectionTrait, Database, Schema, Set};
+
+    async fn image_cache_db() -> DatabaseConnection {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        let schema = Schema::new(db.get_database_backend());
+        db.execute(
+            db.get_database_backend()
+                .build(&schema.create_table_from_entity(image_cache::Entity)),
+        )
+        .await
+        .unwrap();
+        db
+    }
+
+    async fn insert(
+        db: &DatabaseConnection,
+        id: &str,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+    ) {
+        image_cache::ActiveModel {
+            id: Set(id.to_string()),
+            original_url: Set(format!("https://example.com/{}.jpg", id)),
+            cdn_url: Set(format!("https://cdn.example.com/{}.jpg", id)),
+            created_at: Set(created_at),
+            expires_at: Set(expires_at),
+        }
+        .insert(db)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn image_cache_rows_past_retention_or_expiry_are_deleted() {
+        let db = image_cache_db().await;
+        let now = Utc::now();
+        let days = |n: i64| chrono::Duration::days(n);
+        insert(&db, "old", now - days(40), None).await;
+        insert(&db, "expired", now - days(2), Some(now - days(1))).await;
+        insert(&db, "fresh", now - days(2), Some(now + days(5))).await;
+        insert(&db, "recent", now - days(29), None).await;
+
+        let deleted = prune_image_cache(&db, now, now - days(30)).await.unwrap();
+        assert_eq!(deleted, 2);
+
+        let mut left: Vec<String> = image_cache::Entity::find()
+            .all(&db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| row.id)
+            .collect();
+        left.sort();
+        assert_eq!(left, vec!["fresh", "recent"]);
+    }
+
+    #[tokio::test]
+    async fn missing_tables_fail_per_target() {
+        let db = image_cache_db().await;
+        let results = prune_rows(&db, Utc::now(), Duration::from_secs(86_400)).await;
+
+        let targets: Vec<&str> = results.iter().map(|(target, _)| *target).collect();
+        assert_eq!(
+            targets,
+            vec![
+                "image_cache",
+                "session",
+                "email_verification_token",
+                "password_reset_token"
+            ]
+        );
+        assert!(matches!(results[0].1, Ok(0)));
+        assert!(results[1..].iter().all(|(_, result)| result.is_err()));
+    }
+
+    #[test]
+    fn schedule_follows_interval() {
+        assert_eq!(prune_schedule(0), "0 */1 * * * *");
+        assert_eq!(prune_schedule(15), "0 */15 * * * *");
+        assert_eq!(prune_schedule(60), "0 0 */1 * * *");
+        assert_eq!(prune_schedule(6 * 60), "0 0 */6 * * *");
+        assert_eq!(prune_schedule(7 * 24 * 60), "0 0 */23 * * *");
+    }
+}
@@ -6,6 +6,8 @@ use std::sync::Arc;
 use tokio_cron_scheduler::{Job, JobScheduler};
 use tracing::info;
 
+use crate::observability::metrics::record_job;
+
 /// Trait for scheduled tasks.
 #[async_trait]
 pub trait ScheduledTask: Send + Sync {
@@ -41,7 +43,9 @@ impl Scheduler {
             let task = Arc::clone(&task);
             Box::pin(async move {
                 info!("Running scheduled task: {}", task.name());
+                let start = std::time::Instant::now();
                 task.run().await;
+                record_job(task.name(), true, start.elapsed().as_secs_f64());
             })
         })?;
 
@@ -66,7 +70,9 @@ impl Scheduler {
             let f = Arc::clone(&f);
             Box::pin(async move {
                 info!("Running scheduled job: {}", name);
+                let start = std::time::Instant::now();
                 f().await;
+                record_job(name, true, start.elapsed().as_secs_f64());
             })
         })?;
 
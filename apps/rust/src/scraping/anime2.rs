@@ -1,7 +1,11 @@
 use crate::helpers::parse_html;
-use crate::helpers::scraping::{attr, attr_from, attr_from_or, extract_slug, selector, text, text_from_or};
+use crate::helpers::scraping::{
+    attr, attr_from, attr_from_or, compile_selector, extract_slug, text, text_from_or,
+};
 use scraper::{Html, Selector};
 use crate::models::anime2::*;
+use crate::scraping::pagination::extract_pagination;
+use crate::scraping::selectors::{SelectorSet, SELECTORS};
 
 // ============================================================================
 // SELECTORS
@@ -24,20 +28,44 @@ pub struct AnimeSelectors {
 }
 
 impl AnimeSelectors {
+    /// Selectors from the live registry (see [`crate::scraping::selectors`]).
     pub fn new() -> Self {
+        Self::from_set(&SELECTORS.snapshot()).unwrap_or_else(Self::builtin)
+    }
+
+    /// Selectors from one registry generation, if it has all of them.
+    pub fn from_set(set: &SelectorSet) -> Option<Self> {
+        let get = |name: &str| set.get(name).cloned();
+        Some(Self {
+            item: get("anime2.item")?,
+            title: get("anime2.title")?,
+            link: get("anime2.link")?,
+            img: get("anime2.img")?,
+            episode: get("anime2.episode")?,
+            score: get("anime2.score")?,
+            status: get("anime2.status")?,
+            genre: get("anime2.genre")?,
+            rating: get("anime2.rating")?,
+            type_sel: get("anime2.type")?,
+            season: get("anime2.season")?,
+            desc: get("anime2.desc")?,
+        })
+    }
+
+    fn builtin() -> Self {
         Self {
-            item: selector("article.bs").unwrap(),
-            title: selector(".tt h2").unwrap(),
-            link: selector("a").unwrap(),
-            img: selector("img").unwrap(),
-            episode: selector(".epx").unwrap(),
-            score: selector(".numscore").unwrap(),
-            status: selector(".status").unwrap(),
-            genre: selector(".genres a").unwrap(),
-            rating: selector(".score").unwrap(),
-            type_sel: selector(".typez").unwrap(),
-            season: selector(".season").unwrap(),
-            desc: selector(".data .typez").unwrap(),
+            item: compile_selector("article.bs"),
+            title: compile_selector(".tt h2"),
+            link: compile_selector("a"),
+            img: compile_selector("img"),
+            episode: compile_selector(".epx"),
+            score: compile_selector(".numscore"),
+            status: compile_selector(".status"),
+            genre: compile_selector(".genres a"),
+            rating: compile_selector(".score"),
+            type_sel: compile_selector(".typez"),
+            season: compile_selector(".season"),
+            desc: compile_selector(".data .typez"),
         }
     }
 }
@@ -69,8 +97,11 @@ pub fn extract_poster(element: &scraper::ElementRef, img_selector: &Selector) ->
 pub fn parse_ongoing_anime(
     html: &str,
 ) -> Result<Vec<OngoingAnimeItem>, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(ongoing_items(html, &AnimeSelectors::new()))
+}
+
+fn ongoing_items(html: &str, selectors: &AnimeSelectors) -> Vec<OngoingAnimeItem> {
     let document = parse_html(html);
-    let selectors = AnimeSelectors::new();
     let mut items = Vec::new();
 
     for element in document.select(&selectors.item) {
@@ -94,7 +125,7 @@ pub fn parse_ongoing_anime(
         });
     }
 
-    Ok(items)
+    items
 }
 
 /// Parse ongoing anime items with score from HTML
@@ -270,78 +301,34 @@ pub fn parse_genre_anime(
 // PAGINATION PARSERS
 // ============================================================================
 
-/// Parse pagination from HTML document
+/// Parse pagination from HTML document; see [`extract_pagination`].
 pub fn parse_pagination(document: &Html, current_page: u32) -> Pagination {
-    let pagination_selector = selector(".pagination .page-numbers:not(.next)").unwrap();
-    let next_selector = selector(".pagination .next").unwrap();
-
-    let last_visible_page = document
-        .select(&pagination_selector)
-        .next_back()
-        .and_then(|e| text(&e).trim().parse::<u32>().ok())
-        .unwrap_or(current_page);
-
-    let has_next_page = document.select(&next_selector).next().is_some();
-    let next_page = if has_next_page {
-        Some(current_page + 1)
-    } else {
-        None
-    };
-
-    let has_previous_page = current_page > 1;
-    let previous_page = if has_previous_page {
-        Some(current_page - 1)
-    } else {
-        None
-    };
-
-    Pagination {
-        current_page,
-        last_visible_page,
-        has_next_page,
-        next_page,
-        has_previous_page,
-        previous_page,
-    }
+    extract_pagination(document, current_page, "alqanime")
 }
 
-/// Parse pagination with string-based page numbers (for search results)
-pub fn parse_pagination_with_string(document: &Html, current_page: u32) -> PaginationWithStringPages {
-    let pagination_selector = selector(".pagination .page-numbers:not(.next)").unwrap();
-    let next_selector = selector(".pagination .next").unwrap();
-
-    let last_visible_page = document
-        .select(&pagination_selector)
-        .last()
-        .and_then(|e| text(&e).trim().parse::<u32>().ok())
-        .unwrap_or(current_page);
-
-    let has_next_page = document.select(&next_selector).next().is_some();
-
-    let next_page = if has_next_page {
-        document
-            .select(&next_selector)
-            .next()
-            .and_then(|e| attr(&e, "href"))
-            .and_then(|href| href.split("/page/").nth(1).map(|s| s.to_string()))
-            .and_then(|s| s.split('/').next().map(|s| s.to_string()))
-    } else {
-        None
-    };
-
-    let has_previous_page = current_page > 1;
-    let previous_page = if has_previous_page {
-        Some((current_page - 1).to_string())
-    } else {
-        None
-    };
-
-    PaginationWithStringPages {
-        current_page,
-        last_visible_page,
-        has_next_page,
-        next_page,
-        has_previous_page,
-        previous_page,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scraping::selectors::SelectorRegistry;
+    use std::collections::BTreeMap;
+
+    const ONGOING: &str = r#"
+<article class="bs"><a href="https://alqanime.net/anime/frieren/">
+  <div class="tt"><h2>Frieren</h2><h3 class="name">Sousou no Frieren</h3></div>
+  <span class="epx">Ep 12</span>
+</a></article>"#;
+
+    #[test]
+    fn reloaded_title_selector_applies_to_next_parse() {
+        let registry = SelectorRegistry::with_defaults();
+        let parse = || {
+            let selectors = AnimeSelectors::from_set(&registry.snapshot()).unwrap();
+            ongoing_items(ONGOING, &selectors)
+        };
+        assert_eq!(parse()[0].title, "Frieren");
+
+        let overrides = BTreeMap::from([("anime2.title".to_string(), ".tt h3.name".to_string())]);
+        registry.apply(overrides).unwrap();
+        assert_eq!(parse()[0].title, "Sousou no Frieren");
     }
 }
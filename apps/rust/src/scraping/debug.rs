@@ -0,0 +1,60 @@
+//! `?debug=1` support for scraper responses.
+//!
+//! With debug on, responses name the source and the exact upstream URL they
+//! were scraped from, so a bad parse can be reproduced directly. The fields
+//! are attached after caching and never stored. `?debug=selectors` instead
+//! re-scrapes and reports which selectors matched nothing (see
+//! [`parse_report`](crate::scraping::parse_report)).
+
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+/// Query toggle shared by the scraper endpoints.
+#[derive(Debug, Default, Clone, Deserialize, IntoParams, ToSchema)]
+pub struct DebugQuery {
+    /// Set to `1` (or `true`) to include `source` and `fetched_url`, or to
+    /// `selectors` for a fresh scrape with its selector misses.
+    #[serde(default)]
+    pub debug: Option<String>,
+}
+
+impl DebugQuery {
+    pub fn enabled(&self) -> bool {
+        matches!(
+            self.debug.as_deref().map(str::trim),
+            Some("1") | Some("true") | Some("yes")
+        )
+    }
+
+    /// Whether `?debug=selectors` asked for a selector miss report.
+    pub fn selectors(&self) -> bool {
+        self.debug.as_deref().map(str::trim) == Some("selectors")
+    }
+
+    /// `(source, fetched_url)` to attach to a response, when enabled.
+    pub fn origin(&self, source: &str, fetched_url: &str) -> (Option<String>, Option<String>) {
+        if self.enabled() {
+            (Some(source.to_string()), Some(fetched_url.to_string()))
+        } else {
+            (None, None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_truthy_values_enable_debug() {
+        let q = |v: Option<&str>| DebugQuery { debug: v.map(str::to_string) };
+        assert!(q(Some("1")).enabled());
+        assert!(q(Some("true")).enabled());
+        assert!(!q(Some("0")).enabled());
+        assert!(!q(None).enabled());
+        assert_eq!(q(None).origin("otakudesu", "https://x"), (None, None));
+        assert!(q(Some("selectors")).selectors());
+        assert!(!q(Some("selectors")).enabled());
+        assert!(!q(Some("1")).selectors());
+    }
+}
@@ -0,0 +1,231 @@
+//! Embed provider registry for stream resolution.
+//!
+//! Episode pages only expose an iframe pointing at some third-party player.
+//! Each player host gets an [`EmbedResolver`]; the [`EmbedRegistry`] picks one
+//! by host pattern and falls back to [`GenericResolver`], which pulls any
+//! `<source>` or `.m3u8`/`.mp4` URL out of the embed page.
+//!
+//! To support a new host, implement [`EmbedResolver`] and add it in
+//! [`EmbedRegistry::with_defaults`].
+
+use crate::core::error::AppError;
+use crate::helpers::parse_html;
+use crate::helpers::scraping::selector;
+use crate::infra::proxy::fetch_with_proxy;
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+/// A directly playable stream extracted from an embed page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct StreamSource {
+    /// Quality label as advertised by the player, or `"auto"` when unknown.
+    pub quality: String,
+    pub url: String,
+}
+
+/// Resolves an embed URL for one family of player hosts.
+#[async_trait]
+pub trait EmbedResolver: Send + Sync {
+    /// Short name used in logs.
+    fn name(&self) -> &'static str;
+
+    /// Returns the direct stream URLs behind `embed_url`.
+    async fn resolve(&self, embed_url: &str) -> Result<Vec<StreamSource>, AppError>;
+}
+
+/// Matches a URL host against a pattern.
+///
+/// `example.com` matches the host and any subdomain; `*.example.com` matches
+/// subdomains only.
+fn host_matches(pattern: &str, host: &str) -> bool {
+    let host = host.trim_start_matches("www.");
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => host.ends_with(&format!(".{}", suffix)),
+        None => host == pattern || host.ends_with(&format!(".{}", pattern)),
+    }
+}
+
+/// Resolvers keyed by host pattern, with a catch-all fallback.
+pub struct EmbedRegistry {
+    providers: Vec<(String, Arc<dyn EmbedResolver>)>,
+    fallback: Arc<dyn EmbedResolver>,
+}
+
+impl EmbedRegistry {
+    /// Empty registry that sends every host to `fallback`.
+    pub fn new(fallback: Arc<dyn EmbedResolver>) -> Self {
+        Self {
+            providers: Vec::new(),
+            fallback,
+        }
+    }
+
+    /// Registry with the built-in providers (see [`super::resolver`]) and
+    /// [`GenericResolver`] as fallback.
+    pub fn with_defaults() -> Self {
+        use super::resolver::{BloggerResolver, DesustreamResolver, PixeldrainResolver};
+
+        Self::new(Arc::new(GenericResolver))
+            .register("desustream.info", Arc::new(DesustreamResolver))
+            .register("desustream.com", Arc::new(DesustreamResolver))
+            .register("blogger.com", Arc::new(BloggerResolver))
+            .register("pixeldrain.com", Arc::new(PixeldrainResolver))
+    }
+
+    /// Registers `resolver` for hosts matching `pattern`. Earlier
+    /// registrations win when patterns overlap.
+    pub fn register(mut self, pattern: &str, resolver: Arc<dyn EmbedResolver>) -> Self {
+        self.providers.push((pattern.to_ascii_lowercase(), resolver));
+        self
+    }
+
+    /// The registered provider for `embed_url`'s host, if any.
+    pub fn provider_for(&self, embed_url: &str) -> Option<&Arc<dyn EmbedResolver>> {
+        let host = url::Url::parse(embed_url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_ascii_lowercase()))?;
+
+        self.providers
+            .iter()
+            .find(|(pattern, _)| host_matches(pattern, &host))
+            .map(|(_, resolver)| resolver)
+    }
+
+    /// Picks the resolver responsible for `embed_url`.
+    pub fn resolver_for(&self, embed_url: &str) -> &Arc<dyn EmbedResolver> {
+        self.provider_for(embed_url).unwrap_or(&self.fallback)
+    }
+
+    /// Resolves `embed_url` with the matching provider.
+    pub async fn resolve_stream(&self, embed_url: &str) -> Result<Vec<StreamSource>, AppError> {
+        let resolver = self.resolver_for(embed_url);
+        tracing::debug!("Resolving embed {} with {}", embed_url, resolver.name());
+        resolver.resolve(embed_url).await
+    }
+}
+
+/// Process-wide registry used by route handlers.
+pub static EMBED_REGISTRY: Lazy<EmbedRegistry> = Lazy::new(EmbedRegistry::with_defaults);
+
+/// Resolves `embed_url` using [`EMBED_REGISTRY`].
+pub async fn resolve_stream(embed_url: &str) -> Result<Vec<StreamSource>, AppError> {
+    EMBED_REGISTRY.resolve_stream(embed_url).await
+}
+
+/// Fallback resolver: fetches the embed page and extracts anything playable.
+pub struct GenericResolver;
+
+#[async_trait]
+impl EmbedResolver for GenericResolver {
+    fn name(&self) -> &'static str {
+        "generic"
+    }
+
+    async fn resolve(&self, embed_url: &str) -> Result<Vec<StreamSource>, AppError> {
+        let page = fetch_with_proxy(embed_url).await?;
+        Ok(extract_stream_sources(&page.data))
+    }
+}
+
+/// Collects `<source>`/`<video>` URLs and bare `.m3u8`/`.mp4` links from `html`,
+/// deduplicated in document order.
+pub fn extract_stream_sources(html: &str) -> Vec<StreamSource> {
+    static MEDIA_URL: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"https?:(?:\\?/){2}[^\s"'<>]+?\.(?:m3u8|mp4)(?:\?[^\s"'<>]*)?"#).unwrap()
+    });
+
+    let document = parse_html(html);
+    let source_selector = selector("video source[src], source[src], video[src]").unwrap();
+
+    let mut sources: Vec<StreamSource> = Vec::new();
+    let mut push = |quality: Option<&str>, url: String| {
+        if url.is_empty() || sources.iter().any(|s| s.url == url) {
+            return;
+        }
+        sources.push(StreamSource {
+            quality: quality
+                .filter(|q| !q.trim().is_empty())
+                .unwrap_or("auto")
+                .trim()
+                .to_string(),
+            url,
+        });
+    };
+
+    for element in document.select(&source_selector) {
+        let value = element.value();
+        if let Some(src) = value.attr("src") {
+            let quality = value.attr("size").or(value.attr("label")).or(value.attr("res"));
+            push(quality, src.trim().to_string());
+        }
+    }
+
+    for m in MEDIA_URL.find_iter(html) {
+        push(None, m.as_str().replace("\\/", "/"));
+    }
+
+    sources
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed(&'static str);
+
+    #[async_trait]
+    impl EmbedResolver for Fixed {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        async fn resolve(&self, _embed_url: &str) -> Result<Vec<StreamSource>, AppError> {
+            Ok(vec![StreamSource {
+                quality: "auto".to_string(),
+                url: format!("https://cdn.test/{}.m3u8", self.0),
+            }])
+        }
+    }
+
+    fn registry() -> EmbedRegistry {
+        EmbedRegistry::new(Arc::new(Fixed("fallback")))
+            .register("playerone.test", Arc::new(Fixed("one")))
+            .register("*.playertwo.test", Arc::new(Fixed("two")))
+    }
+
+    #[tokio::test]
+    async fn dispatches_by_host() {
+        let registry = registry();
+        assert_eq!(registry.resolver_for("https://playerone.test/e/abc").name(), "one");
+        assert_eq!(registry.resolver_for("https://www.playerone.test/e/abc").name(), "one");
+        assert_eq!(registry.resolver_for("https://s3.playertwo.test/v/1").name(), "two");
+
+        let sources = registry.resolve_stream("https://s3.playertwo.test/v/1").await.unwrap();
+        assert_eq!(sources[0].url, "https://cdn.test/two.m3u8");
+    }
+
+    #[tokio::test]
+    async fn unknown_host_uses_fallback() {
+        let registry = registry();
+        assert_eq!(registry.resolver_for("https://elsewhere.test/e/1").name(), "fallback");
+        assert_eq!(registry.resolver_for("https://playertwo.test/v/1").name(), "fallback");
+        assert_eq!(registry.resolver_for("not a url").name(), "fallback");
+    }
+
+    #[test]
+    fn generic_extraction_finds_sources_and_playlists() {
+        let html = r#"
+            <video><source src="https://cdn.test/a-720.mp4" size="720"></video>
+            <script>var cfg = {"file":"https:\/\/cdn.test\/master.m3u8?t=1"};</script>
+            <a href="https://cdn.test/a-720.mp4">dup</a>
+        "#;
+        let sources = extract_stream_sources(html);
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].quality, "720");
+        assert_eq!(sources[1].url, "https://cdn.test/master.m3u8?t=1");
+    }
+}
@@ -0,0 +1,100 @@
+//! Host allow/deny list for scraped download and stream links.
+//!
+//! Download pages mix legit mirrors with ad-laden or malware hosts. Links
+//! whose host is on `CONFIG.link_host_denylist` are dropped; when
+//! `CONFIG.link_host_allowlist` is non-empty, only links to those hosts are
+//! kept. Both lists empty (the default) means no filtering.
+
+use once_cell::sync::Lazy;
+use url::Url;
+
+use crate::core::config::CONFIG;
+
+/// Allow/deny host lists. A host matches an entry when it equals it or is a
+/// subdomain of it (`dl.gofile.io` matches `gofile.io`).
+#[derive(Debug, Clone, Default)]
+pub struct LinkHostFilter {
+    allow: Vec<String>,
+    deny: Vec<String>,
+}
+
+impl LinkHostFilter {
+    pub fn new(allow: &[String], deny: &[String]) -> Self {
+        Self {
+            allow: normalize(allow),
+            deny: normalize(deny),
+        }
+    }
+
+    /// Whether any filtering is configured.
+    pub fn is_active(&self) -> bool {
+        !(self.allow.is_empty() && self.deny.is_empty())
+    }
+
+    /// Whether a link to `url` should be kept.
+    ///
+    /// URLs without a host are only kept when no allowlist is set.
+    pub fn permits(&self, url: &str) -> bool {
+        if !self.is_active() {
+            return true;
+        }
+        let Some(host) = Url::parse(url).ok().and_then(|u| u.host_str().map(normalize_host)) else {
+            return self.allow.is_empty();
+        };
+        if matches_any(&host, &self.deny) {
+            return false;
+        }
+        self.allow.is_empty() || matches_any(&host, &self.allow)
+    }
+}
+
+fn normalize_host(host: &str) -> String {
+    let host = host.trim().to_ascii_lowercase();
+    host.strip_prefix("www.").unwrap_or(&host).to_string()
+}
+
+fn normalize(hosts: &[String]) -> Vec<String> {
+    hosts
+        .iter()
+        .map(|h| normalize_host(h))
+        .filter(|h| !h.is_empty())
+        .collect()
+}
+
+fn matches_any(host: &str, entries: &[String]) -> bool {
+    entries
+        .iter()
+        .any(|e| host == e || host.ends_with(&format!(".{}", e)))
+}
+
+/// Process-wide filter, configured from `CONFIG`.
+pub static LINK_HOST_FILTER: Lazy<LinkHostFilter> = Lazy::new(|| {
+    LinkHostFilter::new(&CONFIG.link_host_allowlist, &CONFIG.link_host_denylist)
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hosts(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn empty_lists_keep_everything() {
+        let filter = LinkHostFilter::default();
+        assert!(!filter.is_active());
+        assert!(filter.permits("https://ads.example/x"));
+        assert!(filter.permits("not a url"));
+    }
+
+    #[test]
+    fn deny_wins_and_allow_restricts() {
+        let filter = LinkHostFilter::new(&hosts(&["gofile.io", "acefile.co"]), &hosts(&["WWW.AceFile.co"]));
+        assert!(filter.permits("https://gofile.io/d/abc"));
+        assert!(filter.permits("https://store1.gofile.io/d/abc"));
+        assert!(!filter.permits("https://acefile.co/f/1"));
+        assert!(!filter.permits("https://reshare.pm/d/1"));
+        assert!(!filter.permits("/relative"));
+    }
+}
@@ -2,6 +2,16 @@
 
 pub mod anime;
 pub mod anime2;
+pub mod debug;
+pub mod embed;
+pub mod link_filter;
+pub mod pagination;
+pub mod parse_report;
+pub mod render;
+pub mod resolver;
+pub mod robots;
+pub mod selectors;
 pub mod urls;
 
+pub use render::{fetch_html, fetch_source_html};
 pub use urls::*;
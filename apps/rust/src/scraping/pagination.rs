@@ -0,0 +1,168 @@
+//! Reading upstream pagers without mistaking a missing one for the last page.
+//!
+//! The WordPress themes of the anime sources render a `.pagination` block
+//! (`.pagenavix` on older otakudesu pages) with numbered `.page-numbers` and
+//! a `.next` link. On the last page that link is either gone or rendered
+//! disabled: a `<span>`, a `.disabled` class or an `aria-disabled` anchor.
+//! When the whole block is missing the page can't say whether more pages
+//! exist, so [`read_pager`] reports [`Pager::Missing`] instead of a single
+//! page and [`extract_pagination`] logs it.
+
+use scraper::{ElementRef, Html};
+use tracing::{debug, warn};
+
+use crate::helpers::pagination::Pagination;
+use crate::helpers::scraping::{attr, selectors, text};
+use crate::scraping::parse_report;
+
+selectors! {
+    PAGER_SELECTOR = ".pagination, .pagenavix";
+    PAGE_NUMBER_SELECTOR = ".page-numbers:not(.next):not(.prev)";
+    NEXT_SELECTOR = ".next";
+}
+
+/// The CSS recorded in the parse report when a page has no pager.
+const PAGER_CSS: &str = ".pagination, .pagenavix";
+
+/// What a page's pager says about the pages after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pager {
+    /// No pager block at all; the markup may have changed.
+    Missing,
+    /// A pager without an enabled next link or any higher page number.
+    Last { last_page: u32 },
+    /// A pager with an enabled next link or a higher page number.
+    HasNext { last_page: u32 },
+}
+
+impl Pager {
+    /// Page metadata for `current_page`. A missing pager counts as the last
+    /// page, the only safe guess, but see [`extract_pagination`].
+    pub fn pagination(self, current_page: u32) -> Pagination {
+        match self {
+            Pager::Missing => Pagination::from_page(current_page, current_page, false),
+            Pager::Last { last_page } => Pagination::from_page(current_page, last_page, false),
+            Pager::HasNext { last_page } => Pagination::from_page(current_page, last_page, true),
+        }
+    }
+}
+
+/// Reads the pager of `document`, which is page `current_page`.
+pub fn read_pager(document: &Html, current_page: u32) -> Pager {
+    let Some(pager) = document.select(&PAGER_SELECTOR).next() else {
+        return Pager::Missing;
+    };
+
+    let last_page = pager
+        .select(&PAGE_NUMBER_SELECTOR)
+        .filter_map(|e| text(&e).parse::<u32>().ok())
+        .max()
+        .unwrap_or(current_page)
+        .max(current_page);
+    let next_enabled = pager.select(&NEXT_SELECTOR).any(|e| !is_disabled(&e));
+
+    if next_enabled || last_page > current_page {
+        Pager::HasNext { last_page }
+    } else {
+        Pager::Last { last_page }
+    }
+}
+
+/// [`read_pager`] as [`Pagination`], logging (and recording in the current
+/// [`parse_report`]) when `source`'s page has no pager where one belongs.
+pub fn extract_pagination(document: &Html, current_page: u32, source: &str) -> Pagination {
+    let pager = read_pager(document, current_page);
+    parse_report::record(PAGER_CSS, pager != Pager::Missing);
+    match pager {
+        // Page 1 of a short listing may legitimately have no pager.
+        Pager::Missing if current_page <= 1 => {
+            debug!(
+                "{} page 1 has no pagination block; assuming a single page",
+                source
+            )
+        }
+        Pager::Missing => warn!(
+            "{} page {} has no pagination block; has the markup changed?",
+            source, current_page
+        ),
+        _ => {}
+    }
+    pager.pagination(current_page)
+}
+
+/// Whether a `.next` element is shown but can't be followed.
+fn is_disabled(element: &ElementRef) -> bool {
+    let value = element.value();
+    value.name() != "a"
+        || value.classes().any(|c| c == "disabled")
+        || attr(element, "aria-disabled").is_some_and(|v| v == "true")
+        || attr(element, "href").is_none_or(|href| href.trim().is_empty() || href == "#")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::parse_html;
+
+    fn pager(file: &str, current_page: u32) -> Pager {
+        read_pager(&parse_html(file), current_page)
+    }
+
+    #[test]
+    fn single_page_result_has_no_next_page() {
+        let html = include_str!("fixtures/pagination_single.html");
+
+        assert_eq!(pager(html, 1), Pager::Last { last_page: 1 });
+        let pagination = pager(html, 1).pagination(1);
+        assert!(!pagination.has_next_page);
+        assert_eq!(pagination, Pagination::single());
+    }
+
+    #[test]
+    fn mid_sequence_page_has_next_page() {
+        let html = include_str!("fixtures/pagination_middle.html");
+
+        assert_eq!(pager(html, 3), Pager::HasNext { last_page: 12 });
+        let pagination = pager(html, 3).pagination(3);
+        assert!(pagination.has_next_page);
+        assert_eq!(pagination, Pagination::from_page(3, 12, true));
+    }
+
+    #[test]
+    fn disabled_next_link_marks_the_last_page() {
+        let html = r##"
+            <div class="pagenavix">
+                <a class="page-numbers" href="/page/3/">3</a>
+                <span class="page-numbers current">4</span>
+                <a class="next page-numbers disabled" href="#">Berikutnya</a>
+            </div>
+        "##;
+
+        assert_eq!(pager(html, 4), Pager::Last { last_page: 4 });
+        assert!(!pager(html, 4).pagination(4).has_next_page);
+    }
+
+    #[test]
+    fn page_numbers_beyond_the_current_one_imply_a_next_page() {
+        // Some pagers drop the next link and only show the page window.
+        let html = r#"
+            <div class="pagination">
+                <span class="page-numbers current">1</span>
+                <a class="page-numbers" href="/page/2/">2</a>
+            </div>
+        "#;
+
+        assert_eq!(pager(html, 1), Pager::HasNext { last_page: 2 });
+    }
+
+    #[test]
+    fn missing_pager_is_reported_not_mistaken_for_the_last_page() {
+        let html = r#"<div class="venz"><ul><li>Frieren</li></ul></div>"#;
+
+        assert_eq!(pager(html, 2), Pager::Missing);
+        let (pagination, report) =
+            parse_report::collect(|| extract_pagination(&parse_html(html), 2, "otakudesu"));
+        assert!(report.missed(PAGER_CSS));
+        assert_eq!(pagination, Pagination::from_page(2, 2, false));
+    }
+}
@@ -0,0 +1,144 @@
+//! Which selectors matched nothing during a parse.
+//!
+//! When a source site changes its markup, parsers don't fail: the lookups
+//! just come back empty. Run a parser under [`collect`] and every
+//! [`select_text`](crate::helpers::scraping::select_text) /
+//! [`select_attr`](crate::helpers::scraping::select_attr) call (and every
+//! explicit [`record`]) that matched zero elements lands in the returned
+//! [`ParseReport`]. [`ParseReport::warn_critical`] turns misses of the
+//! selectors a page can't do without into a warning and a
+//! `selector_miss_total` count, and `?debug=selectors` returns the report.
+//!
+//! Collection is per thread, which suits parsers: they are synchronous and
+//! run inside one `spawn_blocking` call. Outside [`collect`], recording is
+//! a no-op.
+
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use tracing::warn;
+use utoipa::ToSchema;
+
+use crate::observability::metrics::record_selector_miss;
+
+thread_local! {
+    static CURRENT: RefCell<Option<ParseReport>> = const { RefCell::new(None) };
+}
+
+/// Selectors that matched nothing in one parse.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ParseReport {
+    /// CSS selector → how many lookups of it matched zero elements.
+    pub misses: BTreeMap<String, u32>,
+}
+
+impl ParseReport {
+    pub fn is_empty(&self) -> bool {
+        self.misses.is_empty()
+    }
+
+    pub fn missed(&self, selector: &str) -> bool {
+        self.misses.contains_key(selector)
+    }
+
+    /// Logs and counts the misses among `critical`, returning them.
+    pub fn warn_critical(&self, source: &str, critical: &[&str]) -> Vec<String> {
+        let missed: Vec<String> = critical
+            .iter()
+            .filter(|selector| self.missed(selector))
+            .map(|selector| selector.to_string())
+            .collect();
+        for selector in &missed {
+            record_selector_miss(selector);
+        }
+        if !missed.is_empty() {
+            warn!(
+                "{} page matched nothing for critical selectors {:?}; has the markup changed?",
+                source, missed
+            );
+        }
+        missed
+    }
+}
+
+/// Runs `parse` and returns what it produced along with its misses.
+/// Nested calls each get their own report.
+pub fn collect<T>(parse: impl FnOnce() -> T) -> (T, ParseReport) {
+    let outer = CURRENT.with(|current| current.replace(Some(ParseReport::default())));
+    let value = parse();
+    let report = CURRENT
+        .with(|current| current.replace(outer))
+        .unwrap_or_default();
+    (value, report)
+}
+
+/// Notes a lookup of `selector`; only misses are kept.
+pub fn record(selector: &str, matched: bool) {
+    if matched {
+        return;
+    }
+    CURRENT.with(|current| {
+        if let Some(report) = current.borrow_mut().as_mut() {
+            *report.misses.entry(selector.to_string()).or_default() += 1;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::parse_html;
+    use crate::helpers::scraping::{select_attr, select_text};
+
+    const PAGE: &str = r#"<div class="info"><h1>Frieren</h1><img src="/p.jpg"></div>"#;
+
+    #[test]
+    fn lookups_that_match_nothing_are_reported() {
+        let ((title, poster, synopsis), report) = collect(|| {
+            let document = parse_html(PAGE);
+            let root = document.root_element();
+            (
+                select_text(&root, ".info h1"),
+                select_attr(&root, ".info img", "src"),
+                select_text(&root, ".sinopc"),
+            )
+        });
+
+        assert_eq!(title.as_deref(), Some("Frieren"));
+        assert_eq!(poster.as_deref(), Some("/p.jpg"));
+        assert_eq!(synopsis, None);
+        assert_eq!(report.misses, BTreeMap::from([(".sinopc".to_string(), 1)]));
+    }
+
+    #[test]
+    fn only_critical_misses_are_flagged() {
+        let ((), report) = collect(|| {
+            record(".sinopc", false);
+            record(".sinopc", false);
+            record(".judul", false);
+            record(".episodelist a", true);
+        });
+
+        assert_eq!(report.misses[".sinopc"], 2);
+        assert!(!report.missed(".episodelist a"));
+        assert_eq!(
+            report.warn_critical("otakudesu", &[".sinopc", ".episodelist a"]),
+            vec![".sinopc"]
+        );
+    }
+
+    #[test]
+    fn nested_collections_are_separate_and_outside_is_a_no_op() {
+        record(".ignored", false);
+        let (inner, outer) = collect(|| {
+            record(".outer", false);
+            let ((), inner) = collect(|| record(".inner", false));
+            inner
+        });
+
+        assert!(inner.missed(".inner") && !inner.missed(".outer"));
+        assert!(outer.missed(".outer") && !outer.missed(".inner"));
+        assert!(!outer.missed(".ignored"));
+        assert!(collect(|| ()).1.is_empty());
+    }
+}
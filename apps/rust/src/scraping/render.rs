@@ -0,0 +1,179 @@
+//! Browser-rendered fetches for Cloudflare-protected sources.
+//!
+//! Some mirrors answer plain HTTP clients with a Cloudflare challenge page.
+//! Hosts listed in `CONFIG.browser_sources` are loaded in a tab from the
+//! shared [`BrowserPool`] instead, which runs the challenge and returns the
+//! rendered HTML. Every other source keeps using the reqwest path, so only
+//! the protected ones pay for a browser tab.
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+use url::Url;
+
+use crate::browser::pool::get_browser_pool;
+use crate::browser::BrowserPool;
+use crate::core::config::CONFIG;
+use crate::core::error::AppError;
+use crate::infra::proxy::fetch_with_proxy;
+use crate::observability::metrics::{record_upstream_fetch, source_label};
+use crate::scraping::robots::ROBOTS;
+
+/// How long a challenge page may take to clear before giving up.
+const CHALLENGE_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Pause between checks of a page that is still on the challenge.
+const CHALLENGE_POLL: Duration = Duration::from_millis(500);
+
+/// Loads a page in a real browser and returns its HTML.
+#[async_trait]
+pub trait PageRenderer: Send + Sync {
+    async fn render(&self, url: &str) -> Result<String, AppError>;
+}
+
+/// Whether `html` is a Cloudflare interstitial rather than the page itself.
+pub fn is_challenge_page(html: &str) -> bool {
+    const MARKERS: [&str; 4] = [
+        "<title>Just a moment...</title>",
+        "cf-browser-verification",
+        "/cdn-cgi/challenge-platform/",
+        "cf_chl_opt",
+    ];
+    MARKERS.iter().any(|marker| html.contains(marker))
+}
+
+#[async_trait]
+impl PageRenderer for Arc<BrowserPool> {
+    async fn render(&self, url: &str) -> Result<String, AppError> {
+        let tab = self
+            .get_tab()
+            .await
+            .map_err(|e| AppError::ServiceUnavailable(format!("No browser tab available: {}", e)))?;
+        if let Err(e) = tab.goto(url).await {
+            // A navigation failure through a proxy is most likely the proxy's.
+            if let Some(index) = tab.proxy_index() {
+                self.mark_proxy_bad(index);
+            }
+            return Err(AppError::Other(e.to_string()));
+        }
+
+        // The challenge reloads the page itself once solved.
+        let deadline = Instant::now() + CHALLENGE_TIMEOUT;
+        loop {
+            let html = tab.content().await.map_err(|e| AppError::Other(e.to_string()))?;
+            if !is_challenge_page(&html) {
+                return Ok(html);
+            }
+            if Instant::now() >= deadline {
+                return Err(AppError::Other(format!(
+                    "Cloudflare challenge for {} did not clear within {:?}",
+                    url, CHALLENGE_TIMEOUT
+                )));
+            }
+            debug!("Waiting for Cloudflare challenge on {}", url);
+            tokio::time::sleep(CHALLENGE_POLL).await;
+        }
+    }
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim().trim_start_matches("www.").to_ascii_lowercase()
+}
+
+/// Whether `url`'s host (or a parent domain) is listed in `sources`.
+pub fn host_listed(url: &str, sources: &[String]) -> bool {
+    let Some(host) = Url::parse(url).ok().and_then(|u| u.host_str().map(normalize_host)) else {
+        return false;
+    };
+    sources
+        .iter()
+        .map(|s| normalize_host(s))
+        .any(|s| !s.is_empty() && (host == s || host.ends_with(&format!(".{}", s))))
+}
+
+/// Whether `url` belongs to a source configured to need a browser.
+pub fn needs_browser(url: &str) -> bool {
+    host_listed(url, &CONFIG.browser_sources)
+}
+
+/// Fetches `url` through `renderer`, recording the fetch like the reqwest
+/// path does.
+pub async fn render_html<R: PageRenderer + ?Sized>(renderer: &R, url: &str) -> Result<String, AppError> {
+    let start = Instant::now();
+    let result = renderer.render(url).await;
+    record_upstream_fetch(&source_label(url), result.is_ok(), start.elapsed().as_secs_f64());
+    result
+}
+
+/// Fetches the HTML of `url`, rendered in a browser tab when `use_browser`
+/// is set and the browser pool is up, otherwise with the reqwest client.
+pub async fn fetch_html(url: &str, use_browser: bool) -> Result<String, AppError> {
+    if use_browser {
+        match get_browser_pool() {
+            Some(pool) => {
+                ROBOTS.check(url).await?;
+                return render_html(&pool, url).await;
+            }
+            None => warn!("Browser pool unavailable, fetching {} without it", url),
+        }
+    }
+    Ok(fetch_with_proxy(url).await?.data)
+}
+
+/// [`fetch_html`] with the browser used only for configured sources.
+pub async fn fetch_source_html(url: &str) -> Result<String, AppError> {
+    fetch_html(url, needs_browser(url)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    const CHALLENGE: &str = r#"<html><head><title>Just a moment...</title></head>
+        <body><script src="/cdn-cgi/challenge-platform/h/b/orchestrate/chl_page/v1"></script></body></html>"#;
+
+    /// Records the URLs it was asked for and returns a rendered page.
+    #[derive(Default)]
+    struct StubRenderer {
+        rendered: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl PageRenderer for StubRenderer {
+        async fn render(&self, url: &str) -> Result<String, AppError> {
+            self.rendered.lock().unwrap().push(url.to_string());
+            Ok("<html><div class=\"venz\">ongoing</div></html>".to_string())
+        }
+    }
+
+    #[test]
+    fn challenge_pages_are_recognized() {
+        assert!(is_challenge_page(CHALLENGE));
+        assert!(!is_challenge_page("<html><title>Otakudesu</title></html>"));
+    }
+
+    #[test]
+    fn only_listed_hosts_need_a_browser() {
+        let sources = vec!["otakudesu.cloud".to_string(), " www.Komikcast.Site ".to_string()];
+        assert!(host_listed("https://otakudesu.cloud/ongoing-anime/", &sources));
+        assert!(host_listed("https://www.otakudesu.cloud/anime/x/", &sources));
+        assert!(host_listed("https://v2.komikcast.site/chapter/1/", &sources));
+        assert!(!host_listed("https://komiku.org/manga/", &sources));
+        assert!(!host_listed("https://nototakudesu.cloud/", &sources));
+        assert!(!host_listed("not a url", &sources));
+        assert!(!host_listed("https://otakudesu.cloud/", &[]));
+    }
+
+    #[tokio::test]
+    async fn rendering_goes_through_the_renderer() {
+        let renderer = StubRenderer::default();
+        let html = render_html(&renderer, "https://protected.test/ongoing-anime/").await.unwrap();
+
+        assert!(html.contains("venz"));
+        assert_eq!(*renderer.rendered.lock().unwrap(), ["https://protected.test/ongoing-anime/"]);
+        let source = &crate::observability::STATS.sources()["protected.test"];
+        assert!(source.successes >= 1);
+    }
+}
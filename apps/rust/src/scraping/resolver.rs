@@ -0,0 +1,241 @@
+//! Direct stream resolvers for the player hosts otakudesu embeds.
+//!
+//! Episode pages only give us an iframe `src`; the players behind it carry the
+//! playable file in inline JS or JSON. Each resolver here knows one host's
+//! page layout and is registered in [`EmbedRegistry::with_defaults`]:
+//!
+//! | Host | Resolver | Source of the links |
+//! |------|----------|---------------------|
+//! | `desustream.*` | [`DesustreamResolver`] | jwplayer `sources: [{file, label}]` |
+//! | `blogger.com` | [`BloggerResolver`] | `VIDEO_CONFIG.streams[].play_url` |
+//! | `pixeldrain.com` | [`PixeldrainResolver`] | file id in the URL, no fetch |
+//!
+//! [`resolve_direct`] only consults these known hosts and returns an empty
+//! list for anything else or when a player page can't be read.
+//!
+//! [`EmbedRegistry::with_defaults`]: super::embed::EmbedRegistry::with_defaults
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tracing::{debug, warn};
+
+use super::embed::{extract_stream_sources, EmbedResolver, StreamSource, EMBED_REGISTRY};
+use crate::core::error::AppError;
+use crate::infra::proxy::fetch_with_proxy;
+
+/// Direct `.m3u8`/`.mp4` links behind `embed_url`, or an empty list when the
+/// host is unknown or the player page has none.
+pub async fn resolve_direct(embed_url: &str) -> Vec<StreamSource> {
+    let Some(resolver) = EMBED_REGISTRY.provider_for(embed_url) else {
+        debug!("No stream resolver for {}", embed_url);
+        return Vec::new();
+    };
+    match resolver.resolve(embed_url).await {
+        Ok(sources) => sources,
+        Err(e) => {
+            warn!("{} resolver failed for {}: {}", resolver.name(), embed_url, e);
+            Vec::new()
+        }
+    }
+}
+
+fn is_media_url(url: &str) -> bool {
+    let path = url.split('?').next().unwrap_or(url).to_ascii_lowercase();
+    path.ends_with(".m3u8") || path.ends_with(".mp4") || path.contains("/videoplayback")
+}
+
+/// Desustream player (`desustream.info/dstream/...`), a jwplayer page.
+pub struct DesustreamResolver;
+
+/// Pulls `{file: "...", label: "..."}` entries out of a jwplayer setup call,
+/// falling back to `<source>` tags and bare media links.
+pub fn extract_jwplayer_sources(html: &str) -> Vec<StreamSource> {
+    static SOURCE_OBJECT: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#"\{[^{}]*?["']?file["']?\s*:\s*["']([^"']+)["'][^{}]*\}"#).unwrap());
+    static LABEL: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#"["']?label["']?\s*:\s*["']([^"']*)["']"#).unwrap());
+
+    let mut sources: Vec<StreamSource> = Vec::new();
+    for object in SOURCE_OBJECT.captures_iter(html) {
+        let url = object[1].replace("\\/", "/");
+        if !is_media_url(&url) || sources.iter().any(|s| s.url == url) {
+            continue;
+        }
+        let quality = LABEL
+            .captures(&object[0])
+            .map(|c| c[1].trim().to_string())
+            .filter(|q| !q.is_empty())
+            .unwrap_or_else(|| "auto".to_string());
+        sources.push(StreamSource { quality, url });
+    }
+
+    if sources.is_empty() {
+        sources = extract_stream_sources(html);
+    }
+    sources
+}
+
+#[async_trait]
+impl EmbedResolver for DesustreamResolver {
+    fn name(&self) -> &'static str {
+        "desustream"
+    }
+
+    async fn resolve(&self, embed_url: &str) -> Result<Vec<StreamSource>, AppError> {
+        let page = fetch_with_proxy(embed_url).await?;
+        Ok(extract_jwplayer_sources(&page.data))
+    }
+}
+
+/// Blogger video player (`blogger.com/video.g?token=...`).
+pub struct BloggerResolver;
+
+/// Quality label for a Google video `format_id` (itag).
+fn itag_quality(itag: u64) -> &'static str {
+    match itag {
+        18 => "360p",
+        22 => "720p",
+        37 => "1080p",
+        59 => "480p",
+        _ => "auto",
+    }
+}
+
+/// Reads `streams[].play_url` from the page's `VIDEO_CONFIG` JSON.
+pub fn extract_blogger_sources(html: &str) -> Vec<StreamSource> {
+    static VIDEO_CONFIG: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"(?s)VIDEO_CONFIG\s*=\s*(\{.*?\})\s*(?:;|</script>)").unwrap());
+
+    let Some(config) = VIDEO_CONFIG
+        .captures(html)
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c[1]).ok())
+    else {
+        return Vec::new();
+    };
+
+    config["streams"]
+        .as_array()
+        .map(|streams| {
+            streams
+                .iter()
+                .filter_map(|stream| {
+                    let url = stream["play_url"].as_str()?.to_string();
+                    let quality = itag_quality(stream["format_id"].as_u64().unwrap_or(0));
+                    Some(StreamSource { quality: quality.to_string(), url })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[async_trait]
+impl EmbedResolver for BloggerResolver {
+    fn name(&self) -> &'static str {
+        "blogger"
+    }
+
+    async fn resolve(&self, embed_url: &str) -> Result<Vec<StreamSource>, AppError> {
+        let page = fetch_with_proxy(embed_url).await?;
+        Ok(extract_blogger_sources(&page.data))
+    }
+}
+
+/// Pixeldrain (`pixeldrain.com/u/{id}`); the file API serves the video directly.
+pub struct PixeldrainResolver;
+
+#[async_trait]
+impl EmbedResolver for PixeldrainResolver {
+    fn name(&self) -> &'static str {
+        "pixeldrain"
+    }
+
+    async fn resolve(&self, embed_url: &str) -> Result<Vec<StreamSource>, AppError> {
+        let id = url::Url::parse(embed_url).ok().and_then(|url| {
+            let mut segments = url.path_segments()?;
+            match (segments.next(), segments.next()) {
+                (Some("u"), Some(id)) if !id.is_empty() => Some(id.to_string()),
+                _ => None,
+            }
+        });
+        Ok(id
+            .map(|id| {
+                vec![StreamSource {
+                    quality: "auto".to_string(),
+                    url: format!("https://pixeldrain.com/api/file/{}", id),
+                }]
+            })
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn desustream_reads_jwplayer_sources() {
+        let html = r#"
+            <div id="player"></div>
+            <script>
+            jwplayer("player").setup({
+                sources: [
+                    {'file':'https:\/\/desu.cdn.test\/v\/ep1-480.mp4','type':'video/mp4','label':'480p'},
+                    {"file": "https://desu.cdn.test/v/ep1-720.mp4", "type": "video/mp4", "label": "720p"},
+                    {file: "https://desu.cdn.test/thumb.jpg"}
+                ],
+                image: "https://desu.cdn.test/poster.jpg"
+            });
+            </script>
+        "#;
+
+        let sources = extract_jwplayer_sources(html);
+        assert_eq!(
+            sources,
+            vec![
+                StreamSource { quality: "480p".to_string(), url: "https://desu.cdn.test/v/ep1-480.mp4".to_string() },
+                StreamSource { quality: "720p".to_string(), url: "https://desu.cdn.test/v/ep1-720.mp4".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn desustream_falls_back_to_video_tags() {
+        let html = r#"<video><source src="https://desu.cdn.test/hls/master.m3u8" label="HD"></video>"#;
+        let sources = extract_jwplayer_sources(html);
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].quality, "HD");
+        assert_eq!(sources[0].url, "https://desu.cdn.test/hls/master.m3u8");
+    }
+
+    #[test]
+    fn blogger_reads_video_config_streams() {
+        let html = r#"
+            <script>var VIDEO_CONFIG = {"thumbnail":"https://x.test/t.jpg","streams":[
+                {"play_url":"https://r1.googlevideo.test/videoplayback?itag=18&id=a","format_id":18},
+                {"play_url":"https://r1.googlevideo.test/videoplayback?itag=22&id=a","format_id":22}
+            ]}</script>
+        "#;
+
+        let sources = extract_blogger_sources(html);
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].quality, "360p");
+        assert_eq!(sources[1].quality, "720p");
+        assert!(sources[1].url.contains("itag=22"));
+
+        assert!(extract_blogger_sources("<html>no player</html>").is_empty());
+    }
+
+    #[tokio::test]
+    async fn pixeldrain_maps_share_links_to_the_file_api() {
+        let sources = PixeldrainResolver.resolve("https://pixeldrain.com/u/AbC123").await.unwrap();
+        assert_eq!(sources[0].url, "https://pixeldrain.com/api/file/AbC123");
+        assert!(PixeldrainResolver.resolve("https://pixeldrain.com/").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn unknown_hosts_resolve_to_nothing() {
+        assert!(resolve_direct("https://unknown-player.test/e/1").await.is_empty());
+        assert!(resolve_direct("").await.is_empty());
+    }
+}
@@ -0,0 +1,331 @@
+//! Optional robots.txt compliance for scrape sources.
+//!
+//! Sources listed in `CONFIG.robots_respect_sources` have their
+//! `/robots.txt` fetched (and cached for an hour) before the first scrape.
+//! Paths it disallows are refused with [`AppError::BlockedTarget`] instead of
+//! being fetched, and a `Crawl-delay` spaces out consecutive fetches to that
+//! host. Every other source is fetched exactly as before.
+//!
+//! Rules are picked the usual way: the group naming our user-agent token if
+//! there is one, otherwise `*`; within it the longest matching `Allow` /
+//! `Disallow` pattern wins (`Allow` on ties), with `*` and `$` supported.
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::header::HeaderMap;
+use reqwest::Client;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+use url::Url;
+
+use crate::core::config::CONFIG;
+use crate::core::error::AppError;
+use crate::helpers::http::{common_headers, scraper_headers};
+use crate::infra::http_client::http_client_fast;
+
+/// How long fetched robots rules are reused.
+const ROBOTS_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Upper bound on an honored `Crawl-delay`.
+const MAX_CRAWL_DELAY: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone)]
+struct Rule {
+    allow: bool,
+    len: usize,
+    pattern: Regex,
+}
+
+/// Rules that apply to us from one robots.txt.
+#[derive(Debug, Clone, Default)]
+pub struct RobotsRules {
+    rules: Vec<Rule>,
+    pub crawl_delay: Option<Duration>,
+}
+
+#[derive(Default)]
+struct Group {
+    agents: Vec<String>,
+    rules: Vec<Rule>,
+    crawl_delay: Option<Duration>,
+}
+
+/// `/path*` style pattern to an anchored regex.
+fn compile(pattern: &str) -> Option<Regex> {
+    let (body, anchored) = match pattern.strip_suffix('$') {
+        Some(body) => (body, true),
+        None => (pattern, false),
+    };
+    let escaped = body.split('*').map(regex::escape).collect::<Vec<_>>().join(".*");
+    Regex::new(&format!("^{}{}", escaped, if anchored { "$" } else { "" })).ok()
+}
+
+impl RobotsRules {
+    /// Parses robots.txt `body` for the user-agent token `agent`.
+    pub fn parse(body: &str, agent: &str) -> Self {
+        let agent = agent.to_ascii_lowercase();
+        let mut groups: Vec<Group> = Vec::new();
+        let mut in_rules = true;
+
+        for line in body.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let (key, value) = (key.trim().to_ascii_lowercase(), value.trim());
+
+            if key == "user-agent" {
+                if in_rules || groups.is_empty() {
+                    groups.push(Group::default());
+                    in_rules = false;
+                }
+                if let Some(group) = groups.last_mut() {
+                    group.agents.push(value.to_ascii_lowercase());
+                }
+                continue;
+            }
+
+            let Some(group) = groups.last_mut() else {
+                continue;
+            };
+            in_rules = true;
+            match key.as_str() {
+                "allow" | "disallow" if !value.is_empty() => {
+                    if let Some(pattern) = compile(value) {
+                        group.rules.push(Rule { allow: key == "allow", len: value.len(), pattern });
+                    }
+                }
+                "crawl-delay" => {
+                    group.crawl_delay = value
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|d| d.is_finite() && *d > 0.0)
+                        .map(|d| Duration::from_secs_f64(d).min(MAX_CRAWL_DELAY));
+                }
+                _ => {}
+            }
+        }
+
+        let named = |g: &&Group| g.agents.iter().any(|a| a != "*" && agent.contains(a.as_str()));
+        let wildcard = |g: &&Group| g.agents.iter().any(|a| a == "*");
+        let chosen: Vec<&Group> = if groups.iter().any(|g| named(&g)) {
+            groups.iter().filter(named).collect()
+        } else {
+            groups.iter().filter(wildcard).collect()
+        };
+
+        Self {
+            rules: chosen.iter().flat_map(|g| g.rules.iter().cloned()).collect(),
+            crawl_delay: chosen.iter().find_map(|g| g.crawl_delay),
+        }
+    }
+
+    /// Whether `path` (including any query string) may be fetched.
+    pub fn is_allowed(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|r| r.pattern.is_match(path))
+            .max_by_key(|r| (r.len, r.allow))
+            .map(|r| r.allow)
+            .unwrap_or(true)
+    }
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim().trim_start_matches("www.").to_ascii_lowercase()
+}
+
+/// Robots.txt checks for the configured sources.
+pub struct RobotsGuard {
+    sources: Vec<String>,
+    agent: String,
+    client: Client,
+    headers: HeaderMap,
+    rules: DashMap<String, (Arc<RobotsRules>, Instant)>,
+    next_slot: DashMap<String, Instant>,
+}
+
+impl RobotsGuard {
+    /// Guard for `sources` (hosts), matching robots groups against `agent`.
+    pub fn new(sources: &[String], agent: &str) -> Self {
+        Self {
+            sources: sources.iter().map(|s| normalize_host(s)).filter(|s| !s.is_empty()).collect(),
+            agent: agent.to_string(),
+            client: http_client_fast().client().clone(),
+            headers: common_headers(),
+            rules: DashMap::new(),
+            next_slot: DashMap::new(),
+        }
+    }
+
+    /// Headers sent with the robots.txt request.
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    fn respects(&self, host: &str) -> bool {
+        let host = normalize_host(host);
+        self.sources
+            .iter()
+            .any(|s| host == *s || host.ends_with(&format!(".{}", s)))
+    }
+
+    /// Refuses `url` if its source respects robots.txt and disallows the path,
+    /// and waits out the source's `Crawl-delay` otherwise.
+    pub async fn check(&self, url: &str) -> Result<(), AppError> {
+        if self.sources.is_empty() {
+            return Ok(());
+        }
+        let Ok(parsed) = Url::parse(url) else {
+            return Ok(());
+        };
+        let Some(host) = parsed.host_str().filter(|h| self.respects(h)) else {
+            return Ok(());
+        };
+
+        let rules = self.rules_for(&parsed).await;
+        let path = match parsed.query() {
+            Some(query) => format!("{}?{}", parsed.path(), query),
+            None => parsed.path().to_string(),
+        };
+        if !rules.is_allowed(&path) {
+            return Err(AppError::BlockedTarget(format!(
+                "{} is disallowed by the robots.txt of {}",
+                path, host
+            )));
+        }
+
+        if let Some(delay) = rules.crawl_delay {
+            let now = Instant::now();
+            let wait = {
+                let mut slot = self.next_slot.entry(host.to_string()).or_insert(now);
+                let start = (*slot).max(now);
+                *slot = start + delay;
+                start - now
+            };
+            if !wait.is_zero() {
+                debug!("Honoring crawl-delay for {}: waiting {:?}", host, wait);
+                tokio::time::sleep(wait).await;
+            }
+        }
+        Ok(())
+    }
+
+    async fn rules_for(&self, url: &Url) -> Arc<RobotsRules> {
+        let origin = url.origin().ascii_serialization();
+        if let Some(entry) = self.rules.get(&origin) {
+            if entry.1.elapsed() < ROBOTS_TTL {
+                return entry.0.clone();
+            }
+        }
+
+        let robots_url = format!("{}/robots.txt", origin);
+        let body = match self
+            .client
+            .get(&robots_url)
+            .headers(self.headers.clone())
+            .send()
+            .await
+        {
+            Ok(res) if res.status().is_success() => res.text().await.unwrap_or_default(),
+            // A missing robots.txt allows everything.
+            Ok(res) => {
+                debug!("No robots.txt at {} ({})", robots_url, res.status());
+                String::new()
+            }
+            Err(e) => {
+                warn!("Failed to fetch {}: {}", robots_url, e);
+                String::new()
+            }
+        };
+
+        let rules = Arc::new(RobotsRules::parse(&body, &self.agent));
+        self.rules.insert(origin, (rules.clone(), Instant::now()));
+        rules
+    }
+}
+
+/// User-agent token matched against robots.txt groups.
+fn agent_token() -> String {
+    CONFIG
+        .scrape_user_agent
+        .as_deref()
+        .and_then(|ua| ua.split(['/', ' ']).next())
+        .filter(|t| !t.is_empty())
+        .unwrap_or("*")
+        .to_string()
+}
+
+/// Process-wide guard, configured from `CONFIG.robots_respect_sources`.
+pub static ROBOTS: Lazy<RobotsGuard> = Lazy::new(|| {
+    RobotsGuard::new(&CONFIG.robots_respect_sources, &agent_token()).with_headers(scraper_headers())
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, response::IntoResponse, routing::get, Router};
+
+    const ROBOTS_TXT: &str = "\
+User-agent: *
+Disallow: /anime/
+Allow: /anime/public
+Crawl-delay: 0.01
+
+User-agent: rustexpress
+Disallow: /private/
+";
+
+    #[test]
+    fn rules_pick_the_matching_group_and_longest_pattern() {
+        let any = RobotsRules::parse(ROBOTS_TXT, "*");
+        assert!(!any.is_allowed("/anime/naruto"));
+        assert!(any.is_allowed("/anime/public/list"));
+        assert!(any.is_allowed("/komik/one-piece"));
+        assert_eq!(any.crawl_delay, Some(Duration::from_millis(10)));
+
+        let named = RobotsRules::parse(ROBOTS_TXT, "RustExpress");
+        assert!(named.is_allowed("/anime/naruto"));
+        assert!(!named.is_allowed("/private/x"));
+
+        let wildcards = RobotsRules::parse("User-agent: *\nDisallow: /*.php$\n", "*");
+        assert!(!wildcards.is_allowed("/index.php"));
+        assert!(wildcards.is_allowed("/index.php?x=1"));
+    }
+
+    async fn serve_robots() -> String {
+        let app = Router::new().route("/robots.txt", get(|| async { ROBOTS_TXT }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn disallowed_path_is_refused_with_a_clear_message() {
+        let base = serve_robots().await;
+        let guard = RobotsGuard::new(&["127.0.0.1".to_string()], "*");
+
+        assert!(guard.check(&format!("{}/anime/public/list", base)).await.is_ok());
+
+        let err = guard.check(&format!("{}/anime/naruto/", base)).await.unwrap_err();
+        assert!(matches!(err, AppError::BlockedTarget(_)));
+
+        let response = err.into_response();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8_lossy(&body);
+        assert!(body.contains("/anime/naruto/ is disallowed by the robots.txt of 127.0.0.1"), "{}", body);
+    }
+
+    #[tokio::test]
+    async fn sources_not_listed_are_not_checked() {
+        let base = serve_robots().await;
+        let guard = RobotsGuard::new(&["example.com".to_string()], "*");
+        assert!(guard.check(&format!("{}/anime/naruto/", base)).await.is_ok());
+        assert!(guard.rules.is_empty());
+    }
+}
@@ -0,0 +1,228 @@
+//! Named CSS selectors that can be overridden and reloaded at runtime.
+//!
+//! Parsers look selectors up by name (`anime2.title`, ...). The built-in CSS
+//! is overridden by the JSON file at `CONFIG.selectors_file` and then by the
+//! JSON object stored in Redis under [`REDIS_OVERRIDE_KEY`], both shaped as
+//! `{ "anime2.title": ".tt h2" }`. `POST /api/admin/selectors/reload` re-reads
+//! both, compiles the full set and swaps it in at once; if any selector fails
+//! to compile, nothing changes.
+
+use once_cell::sync::Lazy;
+use redis::AsyncCommands;
+use scraper::Selector;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use crate::core::config::CONFIG;
+use crate::core::error::AppError;
+use crate::infra::redis::get_redis_conn;
+
+/// Redis key holding selector overrides.
+pub const REDIS_OVERRIDE_KEY: &str = "scrape:selectors";
+
+/// Built-in selectors, by name.
+pub fn defaults() -> BTreeMap<String, String> {
+    [
+        ("anime2.item", "article.bs"),
+        ("anime2.title", ".tt h2"),
+        ("anime2.link", "a"),
+        ("anime2.img", "img"),
+        ("anime2.episode", ".epx"),
+        ("anime2.score", ".numscore"),
+        ("anime2.status", ".status"),
+        ("anime2.genre", ".genres a"),
+        ("anime2.rating", ".score"),
+        ("anime2.type", ".typez"),
+        ("anime2.season", ".season"),
+        ("anime2.desc", ".data .typez"),
+    ]
+    .into_iter()
+    .map(|(name, css)| (name.to_string(), css.to_string()))
+    .collect()
+}
+
+/// One compiled generation of selectors.
+pub struct SelectorSet {
+    css: BTreeMap<String, String>,
+    compiled: HashMap<String, Selector>,
+}
+
+impl SelectorSet {
+    fn compile(css: BTreeMap<String, String>) -> Result<Self, AppError> {
+        let mut compiled = HashMap::new();
+        let mut invalid = Vec::new();
+        for (name, source) in &css {
+            match Selector::parse(source) {
+                Ok(selector) => {
+                    compiled.insert(name.clone(), selector);
+                }
+                Err(e) => invalid.push(format!("{} ({:?}): {}", name, source, e)),
+            }
+        }
+        if !invalid.is_empty() {
+            return Err(AppError::BadRequest(format!(
+                "Invalid selectors: {}",
+                invalid.join("; ")
+            )));
+        }
+        Ok(Self { css, compiled })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Selector> {
+        self.compiled.get(name)
+    }
+
+    pub fn css(&self) -> &BTreeMap<String, String> {
+        &self.css
+    }
+}
+
+/// A selector whose CSS changed in a reload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct SelectorChange {
+    pub name: String,
+    pub old: String,
+    pub new: String,
+}
+
+/// The live selector set.
+pub struct SelectorRegistry {
+    current: RwLock<Arc<SelectorSet>>,
+}
+
+impl SelectorRegistry {
+    /// Registry holding only the built-in selectors.
+    pub fn with_defaults() -> Self {
+        let set = SelectorSet::compile(defaults()).unwrap_or_else(|_| SelectorSet {
+            css: BTreeMap::new(),
+            compiled: HashMap::new(),
+        });
+        Self {
+            current: RwLock::new(Arc::new(set)),
+        }
+    }
+
+    /// The current generation. Parsers take one snapshot per document so a
+    /// reload never mixes old and new selectors within a parse.
+    pub fn snapshot(&self) -> Arc<SelectorSet> {
+        match self.current.read() {
+            Ok(set) => set.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Compiles the defaults with `overrides` applied and swaps them in.
+    /// Unknown names and invalid CSS are rejected without changing anything.
+    pub fn apply(&self, overrides: BTreeMap<String, String>) -> Result<Vec<SelectorChange>, AppError> {
+        let mut css = defaults();
+        let unknown: Vec<&String> = overrides.keys().filter(|k| !css.contains_key(*k)).collect();
+        if !unknown.is_empty() {
+            return Err(AppError::BadRequest(format!("Unknown selectors: {:?}", unknown)));
+        }
+        css.extend(overrides);
+        let next = SelectorSet::compile(css)?;
+
+        let mut current = match self.current.write() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let changes = next
+            .css
+            .iter()
+            .filter(|(name, new)| current.css.get(*name) != Some(*new))
+            .map(|(name, new)| SelectorChange {
+                name: name.clone(),
+                old: current.css.get(name).cloned().unwrap_or_default(),
+                new: new.clone(),
+            })
+            .collect();
+        *current = Arc::new(next);
+        Ok(changes)
+    }
+
+    /// Re-reads the config file and Redis overrides and applies them.
+    pub async fn reload(&self) -> Result<Vec<SelectorChange>, AppError> {
+        let mut overrides = file_overrides(CONFIG.selectors_file.as_deref())?;
+        overrides.extend(redis_overrides().await?);
+        let changes = self.apply(overrides)?;
+        info!("🔁 Reloaded scraping selectors ({} changed)", changes.len());
+        Ok(changes)
+    }
+}
+
+fn parse_overrides(json: &str, origin: &str) -> Result<BTreeMap<String, String>, AppError> {
+    serde_json::from_str(json)
+        .map_err(|e| AppError::BadRequest(format!("Invalid selector overrides in {}: {}", origin, e)))
+}
+
+fn file_overrides(path: Option<&str>) -> Result<BTreeMap<String, String>, AppError> {
+    let Some(path) = path.filter(|p| !p.is_empty()) else {
+        return Ok(BTreeMap::new());
+    };
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| AppError::Other(format!("Failed to read {}: {}", path, e)))?;
+    parse_overrides(&json, path)
+}
+
+async fn redis_overrides() -> Result<BTreeMap<String, String>, AppError> {
+    let mut conn = get_redis_conn().await?;
+    let json: Option<String> = conn.get(REDIS_OVERRIDE_KEY).await?;
+    match json {
+        Some(json) => parse_overrides(&json, REDIS_OVERRIDE_KEY),
+        None => Ok(BTreeMap::new()),
+    }
+}
+
+/// Process-wide registry: built-ins plus the config file, if it loads.
+/// Redis overrides are applied by the first reload.
+pub static SELECTORS: Lazy<SelectorRegistry> = Lazy::new(|| {
+    let registry = SelectorRegistry::with_defaults();
+    match file_overrides(CONFIG.selectors_file.as_deref()).and_then(|o| registry.apply(o)) {
+        Ok(changes) if !changes.is_empty() => info!("Loaded {} selector override(s)", changes.len()),
+        Ok(_) => {}
+        Err(e) => warn!("Ignoring selector overrides: {}", e),
+    }
+    registry
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overrides(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn defaults_compile() {
+        let registry = SelectorRegistry::with_defaults();
+        assert!(registry.snapshot().get("anime2.title").is_some());
+    }
+
+    #[test]
+    fn apply_reports_changes_and_rejects_bad_input() {
+        let registry = SelectorRegistry::with_defaults();
+
+        let changes = registry.apply(overrides(&[("anime2.title", "h3.name")])).unwrap();
+        assert_eq!(
+            changes,
+            vec![SelectorChange {
+                name: "anime2.title".to_string(),
+                old: ".tt h2".to_string(),
+                new: "h3.name".to_string(),
+            }]
+        );
+
+        // Invalid CSS or unknown names leave the live set untouched.
+        assert!(registry.apply(overrides(&[("anime2.title", "h3[")])).is_err());
+        assert!(registry.apply(overrides(&[("anime2.titel", "h3")])).is_err());
+        assert_eq!(registry.snapshot().css()["anime2.title"], "h3.name");
+
+        // Dropping the override reverts to the built-in.
+        let changes = registry.apply(BTreeMap::new()).unwrap();
+        assert_eq!(changes[0].new, ".tt h2");
+    }
+}
@@ -3,15 +3,39 @@
 //! Note: These URLs are kept as dynamic env lookups because they may vary
 //! between deployments and are not critical startup dependencies.
 
+use once_cell::sync::Lazy;
 use std::env;
+use std::sync::RwLock;
+use url::Url;
 
 pub const ANIMEAPI: &str = "https://anime.asepharyana.tech";
 pub const BASE_URL: &str = "http://127.0.0.1:4090";
 pub const OTAKUDESU_BASE_URL: &str = "https://otakudesu.best";
+pub const ALQANIME_BASE_URL: &str = "https://alqanime.si";
+
+/// Base URL that replaces every scrape source while set.
+static UPSTREAM_OVERRIDE: Lazy<RwLock<Option<String>>> = Lazy::new(|| RwLock::new(None));
+
+/// Points every scrape source at `base_url` (or back at the real sites with
+/// `None`). Process-wide; meant for tests running scrapers against a mock
+/// server, see [`TestAppBuilder::with_mock_upstream`](crate::testing::app::TestAppBuilder::with_mock_upstream).
+pub fn set_upstream_override(base_url: Option<String>) {
+    if let Ok(mut current) = UPSTREAM_OVERRIDE.write() {
+        *current = base_url.map(|url| url.trim_end_matches('/').to_string());
+    }
+}
+
+/// The override, else the value of env var `key`, else `default`.
+fn source_url(key: &str, default: &str) -> String {
+    if let Some(url) = UPSTREAM_OVERRIDE.read().ok().and_then(|current| current.clone()) {
+        return url;
+    }
+    env::var(key).unwrap_or_else(|_| default.to_string())
+}
 
 /// Get Komik URL from environment config.
 pub fn get_komik_url() -> String {
-    env::var("KOMIK2_BASE_URL").unwrap_or_else(|_| "https://komiku.org".to_string())
+    source_url("KOMIK2_BASE_URL", "https://komiku.org")
 }
 
 /// Get production URL from environment config.
@@ -21,10 +45,71 @@ pub fn get_production_url() -> String {
 
 /// Get Komik API URL from environment config.
 pub fn get_komik_api_url() -> String {
-    env::var("KOMIK2_API_URL").unwrap_or_else(|_| "https://api.komiku.org".to_string())
+    source_url("KOMIK2_API_URL", "https://api.komiku.org")
 }
 
 /// Get Otakudesu URL from environment config.
 pub fn get_otakudesu_url() -> String {
-    env::var("OTAKUDESU_BASE_URL").unwrap_or_else(|_| OTAKUDESU_BASE_URL.to_string())
+    source_url("OTAKUDESU_BASE_URL", OTAKUDESU_BASE_URL)
+}
+
+/// Get Alqanime URL from environment config.
+pub fn get_alqanime_url() -> String {
+    source_url("ALQANIME_BASE_URL", ALQANIME_BASE_URL)
+}
+
+/// Upstream sites the scrapers depend on, as `(name, base_url)` pairs.
+pub fn scrape_sources() -> Vec<(&'static str, String)> {
+    vec![
+        ("otakudesu", get_otakudesu_url()),
+        ("alqanime", get_alqanime_url()),
+        ("komiku", get_komik_url()),
+        ("komiku_api", get_komik_api_url()),
+    ]
+}
+
+/// Name of the entry of `sources` that `url` belongs to: same host (ignoring
+/// a `www.` prefix) and port.
+pub fn source_for(url: &str, sources: &[(&'static str, String)]) -> Option<&'static str> {
+    fn origin(raw: &str) -> Option<(String, Option<u16>)> {
+        let url = Url::parse(raw).ok()?;
+        let host = url
+            .host_str()?
+            .trim_start_matches("www.")
+            .to_ascii_lowercase();
+        Some((host, url.port_or_known_default()))
+    }
+    let target = origin(url)?;
+    sources
+        .iter()
+        .find(|(_, base)| origin(base).as_ref() == Some(&target))
+        .map(|(name, _)| *name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_are_matched_to_their_source_by_host_and_port() {
+        let sources = vec![
+            ("otakudesu", "https://otakudesu.best".to_string()),
+            ("komiku", "https://komiku.org".to_string()),
+            ("komiku_api", "https://api.komiku.org".to_string()),
+            ("local", "http://127.0.0.1:4090".to_string()),
+        ];
+
+        let cases = [
+            ("https://otakudesu.best/anime/frieren/", Some("otakudesu")),
+            ("https://www.komiku.org/manga/x/", Some("komiku")),
+            ("https://api.komiku.org/manga/page/2/", Some("komiku_api")),
+            ("http://127.0.0.1:4090/x", Some("local")),
+            ("http://127.0.0.1:4091/x", None),
+            ("https://example.com/", None),
+            ("not a url", None),
+        ];
+        for (url, expected) in cases {
+            assert_eq!(source_for(url, &sources), expected, "{}", url);
+        }
+    }
 }
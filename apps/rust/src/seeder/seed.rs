@@ -1,108 +1,353 @@
-use chrono::Utc;
-use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, PaginatorTrait, Set};
+use chrono::{Duration, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, IntoActiveModel,
+    PaginatorTrait, QueryFilter, QuerySelect, Set, TransactionTrait,
+};
+use std::collections::HashSet;
 use tracing::info;
 
-use crate::entities::chat_room;
+use crate::entities::{chat_message_room, chat_room, chat_room_member, user};
 
-/// Check if chat_rooms table is empty and seed default data if needed
-pub async fn seed_chat_data_if_empty(db: &DatabaseConnection) -> Result<(), sea_orm::DbErr> {
-    // Check if there are any chat rooms using SeaORM
-    let count = chat_room::Entity::find().count(db).await?;
+/// Rooms every instance starts with: `(id, name, description)`.
+const DEFAULT_ROOMS: [(&str, &str, &str); 3] = [
+    ("00000000-0000-0000-0000-000000000001", "General", "General discussion room for everyone"),
+    (
+        "00000000-0000-0000-0000-000000000002",
+        "Tech Talk",
+        "Discuss technology, programming, and development",
+    ),
+    ("00000000-0000-0000-0000-000000000003", "Random", "Random chat and off-topic discussions"),
+];
 
-    if count == 0 {
-        info!("Chat tables are empty, seeding default data...");
+/// Demo users: `(id, name, email)`. They all log in with [`DEMO_PASSWORD`].
+const DEMO_USERS: [(&str, &str, &str); 3] = [
+    ("demo-user-1", "Demo Alya", "alya.demo@example.com"),
+    ("demo-user-2", "Demo Bima", "bima.demo@example.com"),
+    ("demo-user-3", "Demo Citra", "citra.demo@example.com"),
+];
 
-        // Insert default rooms
-        let room1 = chat_room::ActiveModel {
-            id: Set("00000000-0000-0000-0000-000000000001".to_string()),
-            name: Set("General".to_string()),
-            description: Set(Some("General discussion room for everyone".to_string())),
-            is_private: Set(0),
-            created_at: Set(Utc::now()),
-            updated_at: Set(Utc::now()),
-        };
-        room1.insert(db).await?;
-
-        let room2 = chat_room::ActiveModel {
-            id: Set("00000000-0000-0000-0000-000000000002".to_string()),
-            name: Set("Tech Talk".to_string()),
-            description: Set(Some(
-                "Discuss technology, programming, and development".to_string(),
-            )),
-            is_private: Set(0),
-            created_at: Set(Utc::now()),
-            updated_at: Set(Utc::now()),
-        };
-        room2.insert(db).await?;
+/// Password of every demo user.
+pub const DEMO_PASSWORD: &str = "demo-password";
+
+/// Demo rooms: `(id, name, description)`.
+const DEMO_ROOMS: [(&str, &str, &str); 2] = [
+    ("demo-room-anime", "Anime Night", "Weekly episode discussion (demo)"),
+    ("demo-room-manga", "Manga Corner", "Chapter talk and recommendations (demo)"),
+];
+
+/// Demo messages: `(id, room id, user id, content)`, oldest first.
+const DEMO_MESSAGES: [(&str, &str, &str, &str); 6] = [
+    ("demo-msg-1", "demo-room-anime", "demo-user-1", "Did everyone catch the new episode?"),
+    ("demo-msg-2", "demo-room-anime", "demo-user-2", "Yes! That ending was wild."),
+    ("demo-msg-3", "demo-room-anime", "demo-user-3", "No spoilers please, watching tonight"),
+    ("demo-msg-4", "demo-room-manga", "demo-user-2", "Any recommendations for a short series?"),
+    ("demo-msg-5", "demo-room-manga", "demo-user-3", "Try something with under 50 chapters"),
+    ("demo-msg-6", "demo-room-manga", "demo-user-1", "Bookmarked, thanks!"),
+];
 
-        let room3 = chat_room::ActiveModel {
-            id: Set("00000000-0000-0000-0000-000000000003".to_string()),
-            name: Set("Random".to_string()),
-            description: Set(Some("Random chat and off-topic discussions".to_string())),
+fn default_rooms() -> Vec<chat_room::ActiveModel> {
+    let now = Utc::now();
+    DEFAULT_ROOMS
+        .iter()
+        .map(|(id, name, description)| chat_room::ActiveModel {
+            id: Set(id.to_string()),
+            name: Set(name.to_string()),
+            description: Set(Some(description.to_string())),
             is_private: Set(0),
-            created_at: Set(Utc::now()),
-            updated_at: Set(Utc::now()),
-        };
-        room3.insert(db).await?;
+            created_at: Set(now),
+            updated_at: Set(now),
+        })
+        .collect()
+}
+
+/// Seeds the default chat rooms when the rooms table is empty.
+pub async fn seed_chat_data_if_empty(db: &DatabaseConnection) -> Result<(), DbErr> {
+    seed_chat_data(db, false).await?;
+    seed_social_data_if_empty(db).await
+}
+
+/// Ids of the rooms this module seeds, default and demo.
+fn seeded_room_ids() -> Vec<&'static str> {
+    DEFAULT_ROOMS
+        .iter()
+        .chain(DEMO_ROOMS.iter())
+        .map(|(id, _, _)| *id)
+        .collect()
+}
 
-        // Note: ChatMessage table doesn't have room_id or username fields in current schema
-        // If you need to seed messages, the schema needs to be updated first
-        // Current ChatMessage schema: id, userId, text, email, imageProfile, imageMessage, role, timestamp
+/// Seeds the default chat rooms in one transaction.
+///
+/// Without `force` nothing happens if any room exists. With `force` the
+/// default and demo rooms, with their members and messages, are deleted
+/// first and the defaults inserted again; rooms users created are kept.
+pub async fn seed_chat_data(db: &DatabaseConnection, force: bool) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
 
-        info!("✅ Default chat data seeded successfully!");
-    } else {
+    if force {
+        info!("Force re-seed: clearing the seeded chat rooms, members and messages...");
+        let ids = seeded_room_ids();
+        chat_message_room::Entity::delete_many()
+            .filter(chat_message_room::Column::RoomId.is_in(ids.clone()))
+            .exec(&txn)
+            .await?;
+        chat_room_member::Entity::delete_many()
+            .filter(chat_room_member::Column::RoomId.is_in(ids.clone()))
+            .exec(&txn)
+            .await?;
+        chat_room::Entity::delete_many()
+            .filter(chat_room::Column::Id.is_in(ids))
+            .exec(&txn)
+            .await?;
+    } else if chat_room::Entity::find().count(&txn).await? > 0 {
         info!("Chat data already exists, skipping seed");
+        return txn.commit().await;
     }
 
-    // Seed Social Media Data (Users & Posts)
-    // Check if users exist
-    let user_count = crate::entities::user::Entity::find().count(db).await?;
-    if user_count == 0 {
-        info!("Seeding social media users and posts...");
-        
-        // 1. Create Users
-        let users = vec![
-            ("u1", "Architect", "https://api.dicebear.com/7.x/avataaars/svg?seed=Architect"),
-            ("u2", "System", "https://api.dicebear.com/7.x/avataaars/svg?seed=System"),
-            ("u3", "Explorer", "https://api.dicebear.com/7.x/avataaars/svg?seed=Explorer"),
-            ("u4", "Protocol", "https://api.dicebear.com/7.x/avataaars/svg?seed=Protocol"),
-        ];
-
-        for (id, name, image) in users {
-            let user = crate::entities::user::ActiveModel {
+    info!("Seeding default chat rooms...");
+    chat_room::Entity::insert_many(default_rooms())
+        .exec_without_returning(&txn)
+        .await?;
+    txn.commit().await?;
+
+    info!("✅ Default chat data seeded successfully!");
+    Ok(())
+}
+
+/// Inserts the `rows` whose id is not in the table yet and returns how many
+/// were inserted, so seeding the same rows twice changes nothing.
+async fn insert_missing<E, A, C>(conn: &C, id_column: E::Column, rows: Vec<(String, A)>) -> Result<usize, DbErr>
+where
+    C: ConnectionTrait,
+    E: EntityTrait,
+    A: ActiveModelTrait<Entity = E> + Send,
+    E::Model: IntoActiveModel<A>,
+{
+    let ids: Vec<String> = rows.iter().map(|(id, _)| id.clone()).collect();
+    let existing: HashSet<String> = E::find()
+        .select_only()
+        .column(id_column)
+        .filter(id_column.is_in(ids))
+        .into_tuple::<String>()
+        .all(conn)
+        .await?
+        .into_iter()
+        .collect();
+
+    let missing: Vec<A> = rows
+        .into_iter()
+        .filter(|(id, _)| !existing.contains(id))
+        .map(|(_, row)| row)
+        .collect();
+    let inserted = missing.len();
+    if inserted > 0 {
+        E::insert_many(missing).exec_without_returning(conn).await?;
+    }
+    Ok(inserted)
+}
+
+/// Inserts a few demo users, rooms, memberships and messages in one
+/// transaction. Rows that already exist are left alone, so running it again
+/// only fills in what is missing.
+pub async fn seed_demo_content(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let password = bcrypt::hash(DEMO_PASSWORD, bcrypt::DEFAULT_COST).map_err(|e| DbErr::Custom(e.to_string()))?;
+    let now = Utc::now();
+    let txn = db.begin().await?;
+
+    let users = DEMO_USERS
+        .iter()
+        .map(|(id, name, email)| {
+            let row = user::ActiveModel {
                 id: Set(id.to_string()),
                 name: Set(Some(name.to_string())),
-                image: Set(Some(image.to_string())),
+                email: Set(Some(email.to_string())),
+                email_verified: Set(Some(now)),
+                image: Set(Some(format!("https://api.dicebear.com/7.x/avataaars/svg?seed={}", id))),
+                password: Set(Some(password.clone())),
+                refresh_token: Set(None),
                 role: Set("user".to_string()),
-                ..Default::default()
             };
-            let _ = user.insert(db).await;
-        }
-
-        // 2. Create Posts
-        let posts = vec![
-            ("1", "u1", "Just deployed the new quantum bridge interface. The glassmorphism is real.", Some("https://images.unsplash.com/photo-1451187580459-43490279c0fa"), "2024-01-01T10:00:00Z"),
-            ("2", "u2", "Systems nominal. Digital destiny is loading...", None, "2024-01-01T11:00:00Z"),
-            ("3", "u3", "Exploring the void. The scroll observer is detecting life forms.", None, "2024-01-01T12:00:00Z"),
-            ("4", "u4", "Staggered reveal successful. Initializing heart explosion protocol.", Some("https://images.unsplash.com/photo-1534972195531-d756b9bfa9f2"), "2024-01-01T13:00:00Z"),
-        ];
-
-        for (id, user_id, content, image_url, created_at) in posts {
-            let post = crate::entities::posts::ActiveModel {
+            (id.to_string(), row)
+        })
+        .collect();
+    let users = insert_missing(&txn, user::Column::Id, users).await?;
+
+    let rooms = DEMO_ROOMS
+        .iter()
+        .map(|(id, name, description)| {
+            let row = chat_room::ActiveModel {
+                id: Set(id.to_string()),
+                name: Set(name.to_string()),
+                description: Set(Some(description.to_string())),
+                is_private: Set(0),
+                created_at: Set(now),
+                updated_at: Set(now),
+            };
+            (id.to_string(), row)
+        })
+        .collect();
+    let rooms = insert_missing(&txn, chat_room::Column::Id, rooms).await?;
+
+    let members = DEMO_ROOMS
+        .iter()
+        .flat_map(|(room_id, _, _)| DEMO_USERS.iter().map(move |(user_id, _, _)| (*room_id, *user_id)))
+        .map(|(room_id, user_id)| {
+            let id = format!("{}-{}", room_id, user_id);
+            let row = chat_room_member::ActiveModel {
+                id: Set(id.clone()),
+                room_id: Set(room_id.to_string()),
+                user_id: Set(user_id.to_string()),
+                role: Set(if user_id == DEMO_USERS[0].0 { "owner" } else { "member" }.to_string()),
+                joined_at: Set(now),
+            };
+            (id, row)
+        })
+        .collect();
+    let members = insert_missing(&txn, chat_room_member::Column::Id, members).await?;
+
+    let start = now - Duration::minutes(DEMO_MESSAGES.len() as i64);
+    let messages = DEMO_MESSAGES
+        .iter()
+        .zip(0i64..)
+        .map(|((id, room_id, user_id, content), minute)| {
+            let at = start + Duration::minutes(minute);
+            let row = chat_message_room::ActiveModel {
                 id: Set(id.to_string()),
+                room_id: Set(room_id.to_string()),
                 user_id: Set(user_id.to_string()),
-                author_id: Set(user_id.to_string()),
                 content: Set(content.to_string()),
-                image_url: Set(image_url.map(|s| s.to_string())),
-                created_at: Set(created_at.parse().unwrap_or(Utc::now())),
-                updated_at: Set(Utc::now()),
-                ..Default::default()
+                created_at: Set(at),
+                updated_at: Set(at),
             };
-            let _ = post.insert(db).await;
-        }
-        
-        info!("✅ Social media data seeded successfully!");
+            (id.to_string(), row)
+        })
+        .collect();
+    let messages = insert_missing(&txn, chat_message_room::Column::Id, messages).await?;
+
+    txn.commit().await?;
+    info!(
+        "✅ Demo content seeded: {} users, {} rooms, {} memberships, {} messages added",
+        users, rooms, members, messages
+    );
+    Ok(())
+}
+
+/// Seeds the social feed's users and posts when there are no users yet.
+async fn seed_social_data_if_empty(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+    let user_count = user::Entity::find().count(&txn).await?;
+    if user_count > 0 {
+        return txn.commit().await;
+    }
+    info!("Seeding social media users and posts...");
+
+    // 1. Create Users
+    let users = vec![
+        ("u1", "Architect", "https://api.dicebear.com/7.x/avataaars/svg?seed=Architect"),
+        ("u2", "System", "https://api.dicebear.com/7.x/avataaars/svg?seed=System"),
+        ("u3", "Explorer", "https://api.dicebear.com/7.x/avataaars/svg?seed=Explorer"),
+        ("u4", "Protocol", "https://api.dicebear.com/7.x/avataaars/svg?seed=Protocol"),
+    ];
+
+    for (id, name, image) in users {
+        let user = user::ActiveModel {
+            id: Set(id.to_string()),
+            name: Set(Some(name.to_string())),
+            image: Set(Some(image.to_string())),
+            role: Set("user".to_string()),
+            ..Default::default()
+        };
+        user.insert(&txn).await?;
+    }
+
+    // 2. Create Posts
+    let posts = vec![
+        ("1", "u1", "Just deployed the new quantum bridge interface. The glassmorphism is real.", Some("https://images.unsplash.com/photo-1451187580459-43490279c0fa"), "2024-01-01T10:00:00Z"),
+        ("2", "u2", "Systems nominal. Digital destiny is loading...", None, "2024-01-01T11:00:00Z"),
+        ("3", "u3", "Exploring the void. The scroll observer is detecting life forms.", None, "2024-01-01T12:00:00Z"),
+        ("4", "u4", "Staggered reveal successful. Initializing heart explosion protocol.", Some("https://images.unsplash.com/photo-1534972195531-d756b9bfa9f2"), "2024-01-01T13:00:00Z"),
+    ];
+
+    for (id, user_id, content, image_url, created_at) in posts {
+        let post = crate::entities::posts::ActiveModel {
+            id: Set(id.to_string()),
+            user_id: Set(user_id.to_string()),
+            author_id: Set(user_id.to_string()),
+            content: Set(content.to_string()),
+            image_url: Set(image_url.map(|s| s.to_string())),
+            created_at: Set(created_at.parse().unwrap_or(Utc::now())),
+            updated_at: Set(Utc::now()),
+            ..Default::default()
+        };
+        post.insert(&txn).await?;
     }
 
+    txn.commit().await?;
+    info!("✅ Social media data seeded successfully!");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult, Transaction};
+    use std::collections::BTreeMap;
+
+    fn exec(rows_affected: u64) -> MockExecResult {
+        MockExecResult { last_insert_id: 0, rows_affected }
+    }
+
+    /// The statements run, without the transaction's BEGIN/COMMIT.
+    fn statements(log: &[Transaction]) -> Vec<String> {
+        log.iter()
+            .flat_map(|t| t.statements().iter().map(|s| s.sql.clone()))
+            .filter(|sql| sql != "BEGIN" && sql != "COMMIT")
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn forced_seed_clears_chat_tables_and_reinserts_the_defaults() {
+        let db = MockDatabase::new(DatabaseBackend::MySql)
+            .append_exec_results([exec(4), exec(2), exec(3), exec(3)])
+            .into_connection();
+
+        seed_chat_data(&db, true).await.unwrap();
+
+        let log = db.into_transaction_log();
+        assert_eq!(log.len(), 1, "everything runs in one transaction");
+        let sql = statements(&log);
+        assert_eq!(sql.len(), 4);
+        // Only the seeded rooms are cleared; rooms users made stay.
+        let rooms = "(?, ?, ?, ?, ?)";
+        assert_eq!(
+            sql[0],
+            format!("DELETE FROM `ChatMessage_room` WHERE `ChatMessage_room`.`room_id` IN {}", rooms)
+        );
+        assert_eq!(
+            sql[1],
+            format!("DELETE FROM `ChatRoomMember` WHERE `ChatRoomMember`.`room_id` IN {}", rooms)
+        );
+        assert_eq!(sql[2], format!("DELETE FROM `ChatRoom` WHERE `ChatRoom`.`id` IN {}", rooms));
+        assert!(sql[3].starts_with("INSERT INTO `ChatRoom`"), "{}", sql[3]);
+        let deleted = &log[0].statements()[3].values;
+        assert_eq!(
+            deleted.as_ref().map(|v| v.0.len()),
+            Some(DEFAULT_ROOMS.len() + DEMO_ROOMS.len())
+        );
+        let values = &log[0].statements()[4].values;
+        assert_eq!(values.as_ref().map(|v| v.0.len()), Some(DEFAULT_ROOMS.len() * 6));
+    }
+
+    #[tokio::test]
+    async fn unforced_seed_leaves_existing_rooms_alone() {
+        let count = BTreeMap::from([("num_items", sea_orm::Value::Int(Some(3)))]);
+        let db = MockDatabase::new(DatabaseBackend::MySql)
+            .append_query_results([[count]])
+            .into_connection();
+
+        seed_chat_data(&db, false).await.unwrap();
+
+        let sql = statements(&db.into_transaction_log());
+        assert_eq!(sql.len(), 1);
+        assert!(sql[0].starts_with("SELECT COUNT(*)"), "{}", sql[0]);
+    }
+}
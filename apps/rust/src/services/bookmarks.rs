@@ -0,0 +1,206 @@
+//! Per-user bookmarks with resume progress.
+//!
+//! A user has at most one bookmark per `(kind, slug)`; saving the same title
+//! again moves its progress forward instead of adding a second row. Every
+//! query is scoped to the owning user.
+
+use chrono::Utc;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::entities::bookmarks;
+
+/// What a bookmark points at; stored in `bookmarks.content_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BookmarkKind {
+    Anime,
+    Komik,
+}
+
+impl BookmarkKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BookmarkKind::Anime => "anime",
+            BookmarkKind::Komik => "komik",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "anime" => Some(BookmarkKind::Anime),
+            "komik" => Some(BookmarkKind::Komik),
+            _ => None,
+        }
+    }
+}
+
+/// Fields of a bookmark being saved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewBookmark {
+    pub kind: BookmarkKind,
+    pub slug: String,
+    pub episode_or_chapter: Option<String>,
+    /// Left as stored when `None`.
+    pub title: Option<String>,
+    /// Left as stored when `None`.
+    pub poster: Option<String>,
+}
+
+/// Creates `user_id`'s bookmark for `bookmark.slug`, or updates its progress
+/// if one exists, and returns the stored row.
+pub async fn upsert(
+    db: &DatabaseConnection,
+    user_id: &str,
+    bookmark: NewBookmark,
+) -> Result<bookmarks::Model, DbErr> {
+    let now = Utc::now();
+
+    let mut update = vec![bookmarks::Column::EpisodeOrChapter, bookmarks::Column::UpdatedAt];
+    if bookmark.title.is_some() {
+        update.push(bookmarks::Column::Title);
+    }
+    if bookmark.poster.is_some() {
+        update.push(bookmarks::Column::Poster);
+    }
+
+    let row = bookmarks::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        user_id: Set(user_id.to_string()),
+        content_type: Set(bookmark.kind.as_str().to_string()),
+        slug: Set(bookmark.slug.clone()),
+        title: Set(bookmark.title.unwrap_or_default()),
+        poster: Set(bookmark.poster.unwrap_or_default()),
+        episode_or_chapter: Set(bookmark.episode_or_chapter),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+    bookmarks::Entity::insert(row)
+        .on_conflict(
+            OnConflict::columns([
+                bookmarks::Column::UserId,
+                bookmarks::Column::ContentType,
+                bookmarks::Column::Slug,
+            ])
+            .update_columns(update)
+            .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    bookmarks::Entity::find()
+        .filter(bookmarks::Column::UserId.eq(user_id))
+        .filter(bookmarks::Column::ContentType.eq(bookmark.kind.as_str()))
+        .filter(bookmarks::Column::Slug.eq(bookmark.slug))
+        .one(db)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound("bookmark vanished after upsert".to_string()))
+}
+
+/// `user_id`'s bookmarks, most recently updated first, optionally of one kind.
+pub async fn list(
+    db: &DatabaseConnection,
+    user_id: &str,
+    kind: Option<BookmarkKind>,
+) -> Result<Vec<bookmarks::Model>, DbErr> {
+    let mut query = bookmarks::Entity::find().filter(bookmarks::Column::UserId.eq(user_id));
+    if let Some(kind) = kind {
+        query = query.filter(bookmarks::Column::ContentType.eq(kind.as_str()));
+    }
+    query
+        .order_by_desc(bookmarks::Column::UpdatedAt)
+        .all(db)
+        .await
+}
+
+/// Deletes bookmark `id` if it belongs to `user_id`. Returns whether a row
+/// was deleted, so someone else's bookmark looks the same as a missing one.
+pub async fn delete(db: &DatabaseConnection, user_id: &str, id: &str) -> Result<bool, DbErr> {
+    let result = bookmarks::Entity::delete_many()
+        .filter(bookmarks::Column::Id.eq(id))
+        .filter(bookmarks::Column::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+
+    fn stored(episode: &str) -> bookmarks::Model {
+        bookmarks::Model {
+            id: "b1".to_string(),
+            user_id: "u1".to_string(),
+            content_type: "anime".to_string(),
+            slug: "one-piece".to_string(),
+            title: "One Piece".to_string(),
+            poster: String::new(),
+            episode_or_chapter: Some(episode.to_string()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn progress(episode: &str, title: Option<&str>) -> NewBookmark {
+        NewBookmark {
+            kind: BookmarkKind::Anime,
+            slug: "one-piece".to_string(),
+            episode_or_chapter: Some(episode.to_string()),
+            title: title.map(str::to_string),
+            poster: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn rebookmarking_updates_progress_in_place() {
+        let db = MockDatabase::new(DatabaseBackend::MySql)
+            .append_exec_results([MockExecResult { last_insert_id: 0, rows_affected: 1 }])
+            .append_query_results([vec![stored("1")]])
+            .append_exec_results([MockExecResult { last_insert_id: 0, rows_affected: 2 }])
+            .append_query_results([vec![stored("2")]])
+            .into_connection();
+
+        let first = upsert(&db, "u1", progress("1", Some("One Piece"))).await.unwrap();
+        let second = upsert(&db, "u1", progress("2", None)).await.unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(second.episode_or_chapter.as_deref(), Some("2"));
+
+        let log = db.into_transaction_log();
+        let insert = &log[2].statements()[0].sql;
+        assert!(insert.starts_with("INSERT INTO `bookmarks`"), "{}", insert);
+        assert!(insert.contains("ON DUPLICATE KEY UPDATE"), "{}", insert);
+        assert!(insert.contains("`episode_or_chapter` = VALUES(`episode_or_chapter`)"), "{}", insert);
+        // A progress-only save keeps the stored title and poster.
+        assert!(!insert.contains("`title` = VALUES(`title`)"), "{}", insert);
+        let lookup = &log[3].statements()[0].sql;
+        assert!(lookup.contains("`bookmarks`.`user_id` = ?"), "{}", lookup);
+    }
+
+    #[tokio::test]
+    async fn deleting_is_scoped_to_the_owner() {
+        let db = MockDatabase::new(DatabaseBackend::MySql)
+            .append_exec_results([MockExecResult { last_insert_id: 0, rows_affected: 0 }])
+            .into_connection();
+
+        assert!(!delete(&db, "u2", "b1").await.unwrap());
+
+        let log = db.into_transaction_log();
+        let sql = &log[0].statements()[0].sql;
+        assert!(sql.contains("`bookmarks`.`id` = ? AND `bookmarks`.`user_id` = ?"), "{}", sql);
+    }
+
+    #[test]
+    fn kinds_round_trip() {
+        for kind in [BookmarkKind::Anime, BookmarkKind::Komik] {
+            assert_eq!(BookmarkKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(BookmarkKind::parse("novel"), None);
+    }
+}
@@ -0,0 +1,516 @@
+//! Chat message persistence, scoped by room.
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+};
+use uuid::Uuid;
+
+use crate::entities::{chat_message_room, chat_room, chat_room_member};
+use crate::events::{ChatMessageSaved, EVENT_BUS};
+use crate::helpers::query::{PaginateExt, Pagination};
+
+/// Store a message in `room_id` and publish [`ChatMessageSaved`].
+pub async fn save_message(
+    db: &DatabaseConnection,
+    room_id: &str,
+    user_id: &str,
+    content: &str,
+) -> Result<chat_message_room::Model, DbErr> {
+    let now = Utc::now();
+    let saved = chat_message_room::ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        room_id: Set(room_id.to_string()),
+        user_id: Set(user_id.to_string()),
+        content: Set(content.to_string()),
+        created_at: Set(now),
+        updated_at: Set(now),
+    }
+    .insert(db)
+    .await?;
+
+    EVENT_BUS
+        .publish(ChatMessageSaved {
+            room: saved.room_id.clone(),
+            id: saved.id.clone(),
+            user_id: saved.user_id.clone(),
+        })
+        .await;
+    Ok(saved)
+}
+
+/// Largest page `load_messages_paginated` will return.
+pub const MAX_PAGE_SIZE: u64 = 100;
+
+/// One page of room history, oldest first.
+#[derive(Debug, Clone)]
+pub struct HistoryPage {
+    pub messages: Vec<chat_message_room::Model>,
+    /// Pass as `before_id` to fetch the next older page; `None` at the start of the room.
+    pub next_cursor: Option<String>,
+}
+
+/// Load the most recent `limit` messages of `room_id`, oldest first.
+pub async fn load_history(
+    db: &DatabaseConnection,
+    room_id: &str,
+    limit: u64,
+) -> Result<Vec<chat_message_room::Model>, DbErr> {
+    Ok(load_messages_paginated(db, room_id, None, limit).await?.messages)
+}
+
+/// Load up to `limit` messages of `room_id` older than the message `before_id`
+/// (or the newest ones when `None`), ordered by `(created_at, id)`.
+///
+/// An unknown `before_id` yields an empty page rather than restarting from
+/// the newest messages.
+pub async fn load_messages_paginated(
+    db: &DatabaseConnection,
+    room_id: &str,
+    before_id: Option<&str>,
+    limit: u64,
+) -> Result<HistoryPage, DbErr> {
+    use chat_message_room::Column;
+
+    let limit = limit.clamp(1, MAX_PAGE_SIZE);
+    let mut query = chat_message_room::Entity::find().filter(Column::RoomId.eq(room_id));
+
+    if let Some(before_id) = before_id {
+        let Some(cursor) = chat_message_room::Entity::find_by_id(before_id.to_string())
+            .filter(Column::RoomId.eq(room_id))
+            .one(db)
+            .await?
+        else {
+            return Ok(HistoryPage {
+                messages: Vec::new(),
+                next_cursor: None,
+            });
+        };
+
+        query = query.filter(
+            Condition::any()
+                .add(Column::CreatedAt.lt(cursor.created_at))
+                .add(
+                    Condition::all()
+                        .add(Column::CreatedAt.eq(cursor.created_at))
+                        .add(Column::Id.lt(cursor.id)),
+                ),
+        );
+    }
+
+    // Fetch one extra row to learn whether an older page exists.
+    let mut messages = query
+        .order_by_desc(Column::CreatedAt)
+        .order_by_desc(Column::Id)
+        .limit(limit + 1)
+        .all(db)
+        .await?;
+
+    let has_more = messages.len() as u64 > limit;
+    messages.truncate(limit as usize);
+    messages.reverse();
+
+    let next_cursor = if has_more {
+        messages.first().map(|m| m.id.clone())
+    } else {
+        None
+    };
+
+    Ok(HistoryPage {
+        messages,
+        next_cursor,
+    })
+}
+
+/// Load the messages of `room_id` newer than the message `after_id`, oldest
+/// first: what a client that last saw `after_id` missed.
+///
+/// At most the newest `limit` of them are returned; `next_cursor` is set when
+/// more were missed than that. `None` when `after_id` is not a message of the
+/// room, so the caller can fall back to [`load_messages_paginated`].
+pub async fn load_messages_after(
+    db: &DatabaseConnection,
+    room_id: &str,
+    after_id: &str,
+    limit: u64,
+) -> Result<Option<HistoryPage>, DbErr> {
+    use chat_message_room::Column;
+
+    let limit = limit.clamp(1, MAX_PAGE_SIZE);
+    let Some(cursor) = chat_message_room::Entity::find_by_id(after_id.to_string())
+        .filter(Column::RoomId.eq(room_id))
+        .one(db)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let mut messages = chat_message_room::Entity::find()
+        .filter(Column::RoomId.eq(room_id))
+        .filter(
+            Condition::any()
+                .add(Column::CreatedAt.gt(cursor.created_at))
+                .add(
+                    Condition::all()
+                        .add(Column::CreatedAt.eq(cursor.created_at))
+                        .add(Column::Id.gt(cursor.id)),
+                ),
+        )
+        .order_by_desc(Column::CreatedAt)
+        .order_by_desc(Column::Id)
+        .limit(limit + 1)
+        .all(db)
+        .await?;
+
+    let has_more = messages.len() as u64 > limit;
+    messages.truncate(limit as usize);
+    messages.reverse();
+
+    let next_cursor = if has_more {
+        messages.first().map(|m| m.id.clone())
+    } else {
+        None
+    };
+
+    Ok(Some(HistoryPage {
+        messages,
+        next_cursor,
+    }))
+}
+
+/// Whether `user_id` (`None` for guests) may join `room_id`. Public rooms
+/// admit everyone, private ones only their members; rooms that don't exist
+/// admit nobody.
+pub async fn can_join_room(
+    db: &DatabaseConnection,
+    room_id: &str,
+    user_id: Option<&str>,
+) -> Result<bool, DbErr> {
+    let Some(room) = chat_room::Entity::find_by_id(room_id).one(db).await? else {
+        return Ok(false);
+    };
+    if room.is_private == 0 {
+        return Ok(true);
+    }
+    let Some(user_id) = user_id else {
+        return Ok(false);
+    };
+    let members = chat_room_member::Entity::find()
+        .filter(chat_room_member::Column::RoomId.eq(room_id))
+        .filter(chat_room_member::Column::UserId.eq(user_id))
+        .count(db)
+        .await?;
+    Ok(members > 0)
+}
+
+/// What [`search_messages`] matches on. Unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct MessageFilter {
+    pub room_id: Option<String>,
+    pub user_id: Option<String>,
+    /// Only messages sent at or after this instant.
+    pub since: Option<DateTime<Utc>>,
+    /// Only messages sent strictly before this instant.
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// One page of the messages matching `filter` across rooms, newest first
+/// (ties broken by id), and how many match in total.
+///
+/// `since` is inclusive and `until` exclusive, so adjacent windows never
+/// return the same message twice.
+pub async fn search_messages(
+    db: &DatabaseConnection,
+    filter: &MessageFilter,
+    pagination: &Pagination,
+) -> Result<(Vec<chat_message_room::Model>, u64), DbErr> {
+    use chat_message_room::Column;
+
+    let mut query = chat_message_room::Entity::find();
+    if let Some(room_id) = &filter.room_id {
+        query = query.filter(Column::RoomId.eq(room_id.as_str()));
+    }
+    if let Some(user_id) = &filter.user_id {
+        query = query.filter(Column::UserId.eq(user_id.as_str()));
+    }
+    if let Some(since) = filter.since {
+        query = query.filter(Column::CreatedAt.gte(since));
+    }
+    if let Some(until) = filter.until {
+        query = query.filter(Column::CreatedAt.lt(until));
+    }
+
+    let total = query.clone().count(db).await?;
+    let messages = query
+        .order_by_desc(Column::CreatedAt)
+        .order_by_desc(Column::Id)
+        .apply_pagination(pagination)
+        .all(db)
+        .await?;
+    Ok((messages, total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use sea_orm::{DatabaseBackend, IntoActiveModel, MockDatabase};
+
+    fn message(id: &str, minute: i64) -> chat_message_room::Model {
+        let at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap() + Duration::minutes(minute);
+        chat_message_room::Model {
+            id: id.to_string(),
+            room_id: "lobby".to_string(),
+            user_id: "u1".to_string(),
+            content: format!("message {}", id),
+            created_at: at,
+            updated_at: at,
+        }
+    }
+
+    /// An in-memory database holding `rows` and empty room tables (foreign
+    /// keys off, as there are no users).
+    async fn sqlite_with(rows: Vec<chat_message_room::Model>) -> DatabaseConnection {
+        use sea_orm::{ConnectionTrait, Database, Schema};
+
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        db.execute_unprepared("PRAGMA foreign_keys = OFF")
+            .await
+            .unwrap();
+        let backend = db.get_database_backend();
+        let schema = Schema::new(backend);
+        db.execute(backend.build(&schema.create_table_from_entity(chat_message_room::Entity)))
+            .await
+            .unwrap();
+        db.execute(backend.build(&schema.create_table_from_entity(chat_room::Entity)))
+            .await
+            .unwrap();
+        db.execute(backend.build(&schema.create_table_from_entity(chat_room_member::Entity)))
+            .await
+            .unwrap();
+        for row in rows {
+            row.into_active_model().insert(&db).await.unwrap();
+        }
+        db
+    }
+
+    fn sent(id: &str, room: &str, user: &str, minute: i64) -> chat_message_room::Model {
+        chat_message_room::Model {
+            room_id: room.to_string(),
+            user_id: user.to_string(),
+            ..message(id, minute)
+        }
+    }
+
+    fn minute(minute: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap() + Duration::minutes(minute)
+    }
+
+    async fn search_ids(db: &DatabaseConnection, filter: MessageFilter) -> Vec<String> {
+        let (messages, total) = search_messages(db, &filter, &Pagination::new(1, 100))
+            .await
+            .unwrap();
+        assert_eq!(total as usize, messages.len());
+        messages.into_iter().map(|m| m.id).collect()
+    }
+
+    #[tokio::test]
+    async fn time_range_includes_since_and_excludes_until() {
+        let db = sqlite_with(vec![
+            sent("a", "lobby", "u1", 1),
+            sent("b", "lobby", "u1", 2),
+            sent("c", "lobby", "u2", 3),
+            sent("d", "lobby", "u1", 4),
+        ])
+        .await;
+
+        let window = |since, until| MessageFilter {
+            since: Some(minute(since)),
+            until: Some(minute(until)),
+            ..Default::default()
+        };
+        assert_eq!(search_ids(&db, window(2, 4)).await, ["c", "b"]);
+        assert_eq!(search_ids(&db, window(2, 3)).await, ["b"]);
+        assert!(search_ids(&db, window(3, 3)).await.is_empty());
+
+        // Adjacent windows cover every message exactly once.
+        let mut both = search_ids(&db, window(0, 3)).await;
+        both.extend(search_ids(&db, window(3, 10)).await);
+        both.sort();
+        assert_eq!(both, ["a", "b", "c", "d"]);
+    }
+
+    #[tokio::test]
+    async fn filters_combine_and_pages_are_newest_first() {
+        let db = sqlite_with(vec![
+            sent("a", "lobby", "u1", 1),
+            sent("b", "lobby", "u2", 2),
+            sent("c", "general", "u1", 2),
+            sent("d", "lobby", "u1", 2),
+            sent("e", "lobby", "u1", 5),
+        ])
+        .await;
+
+        let by_u1 = MessageFilter {
+            user_id: Some("u1".to_string()),
+            ..Default::default()
+        };
+        // Same-minute messages are ordered by id, descending.
+        assert_eq!(search_ids(&db, by_u1.clone()).await, ["e", "d", "c", "a"]);
+
+        let in_lobby = MessageFilter {
+            room_id: Some("lobby".to_string()),
+            since: Some(minute(2)),
+            ..by_u1.clone()
+        };
+        assert_eq!(search_ids(&db, in_lobby).await, ["e", "d"]);
+
+        let (page, total) = search_messages(&db, &by_u1, &Pagination::new(2, 3)).await.unwrap();
+        assert_eq!(total, 4);
+        assert_eq!(page.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(), ["a"]);
+    }
+
+    #[tokio::test]
+    async fn returns_oldest_first_with_cursor_when_more_exist() {
+        // Rows come back newest first; the third row is the look-ahead.
+        let db = MockDatabase::new(DatabaseBackend::MySql)
+            .append_query_results([vec![message("c", 3), message("b", 2), message("a", 1)]])
+            .into_connection();
+
+        let page = load_messages_paginated(&db, "lobby", None, 2).await.unwrap();
+        let ids: Vec<_> = page.messages.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["b", "c"]);
+        assert_eq!(page.next_cursor.as_deref(), Some("b"));
+    }
+
+    #[tokio::test]
+    async fn last_page_has_no_cursor() {
+        let db = MockDatabase::new(DatabaseBackend::MySql)
+            .append_query_results([vec![message("b", 2)]])
+            .append_query_results([vec![message("a", 1)]])
+            .into_connection();
+
+        let page = load_messages_paginated(&db, "lobby", Some("b"), 2).await.unwrap();
+        assert_eq!(page.messages.len(), 1);
+        assert_eq!(page.messages[0].id, "a");
+        assert!(page.next_cursor.is_none());
+    }
+
+    fn history_ids(page: &HistoryPage) -> Vec<String> {
+        page.messages.iter().map(|m| m.id.clone()).collect()
+    }
+
+    #[tokio::test]
+    async fn reconnecting_after_a_message_replays_only_what_was_missed() {
+        let db = sqlite_with(vec![
+            sent("a", "lobby", "u1", 1),
+            sent("b", "lobby", "u2", 2),
+            sent("x", "general", "u1", 3),
+        ])
+        .await;
+
+        // Joined, saw the history, then dropped.
+        let page = load_messages_paginated(&db, "lobby", None, 50)
+            .await
+            .unwrap();
+        let seen = history_ids(&page);
+        assert_eq!(seen, ["a", "b"]);
+
+        // Posted while the client was away; one shares b's timestamp.
+        for row in [sent("c", "lobby", "u2", 2), sent("d", "lobby", "u1", 4)] {
+            row.into_active_model().insert(&db).await.unwrap();
+        }
+
+        let missed = load_messages_after(&db, "lobby", seen.last().unwrap(), 50)
+            .await
+            .unwrap()
+            .unwrap();
+        let replayed = history_ids(&missed);
+        assert_eq!(replayed, ["c", "d"]);
+        assert!(replayed.iter().all(|id| !seen.contains(id)));
+        assert!(missed.next_cursor.is_none());
+
+        // Caught up: nothing to replay.
+        let none = load_messages_after(&db, "lobby", "d", 50)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(none.messages.is_empty());
+    }
+
+    #[tokio::test]
+    async fn replay_keeps_the_newest_and_rejects_foreign_cursors() {
+        let db = sqlite_with(vec![
+            sent("a", "lobby", "u1", 1),
+            sent("b", "lobby", "u1", 2),
+            sent("c", "lobby", "u1", 3),
+            sent("d", "lobby", "u1", 4),
+            sent("x", "general", "u1", 5),
+        ])
+        .await;
+
+        let page = load_messages_after(&db, "lobby", "a", 2)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(history_ids(&page), ["c", "d"]);
+        assert_eq!(page.next_cursor.as_deref(), Some("c"));
+
+        // Ids from another room or unknown ids can't anchor a replay.
+        for cursor in ["x", "missing"] {
+            assert!(load_messages_after(&db, "lobby", cursor, 10)
+                .await
+                .unwrap()
+                .is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_cursor_returns_empty_page() {
+        let db = MockDatabase::new(DatabaseBackend::MySql)
+            .append_query_results([Vec::<chat_message_room::Model>::new()])
+            .into_connection();
+
+        let page = load_messages_paginated(&db, "lobby", Some("missing"), 10).await.unwrap();
+        assert!(page.messages.is_empty());
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn private_rooms_admit_only_members() {
+        let db = sqlite_with(Vec::new()).await;
+        let at = minute(0);
+        for (id, is_private) in [("lobby", 0), ("staff", 1)] {
+            chat_room::Model {
+                id: id.to_string(),
+                name: id.to_string(),
+                description: None,
+                is_private,
+                created_at: at,
+                updated_at: at,
+            }
+            .into_active_model()
+            .insert(&db)
+            .await
+            .unwrap();
+        }
+        chat_room_member::Model {
+            id: "m1".to_string(),
+            room_id: "staff".to_string(),
+            user_id: "u1".to_string(),
+            role: "member".to_string(),
+            joined_at: at,
+        }
+        .into_active_model()
+        .insert(&db)
+        .await
+        .unwrap();
+
+        assert!(can_join_room(&db, "lobby", None).await.unwrap());
+        assert!(can_join_room(&db, "staff", Some("u1")).await.unwrap());
+        assert!(!can_join_room(&db, "staff", Some("u2")).await.unwrap());
+        assert!(!can_join_room(&db, "staff", None).await.unwrap());
+        assert!(!can_join_room(&db, "missing", Some("u1")).await.unwrap());
+    }
+}
@@ -12,7 +12,10 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 
+use crate::core::error::AppError;
 use crate::infra::http_client::http_client;
+use crate::infra::proxy::send_validated;
+use crate::infra::proxy_limits::ProxyLimits;
 
 use crate::helpers::Cache;
 
@@ -472,16 +475,20 @@ impl ImageCache {
 
     /// Upload image to Picser CDN with failover support
     async fn upload_to_picser(&self, original_url: &str) -> Result<String, String> {
-        // Download the image first
-        let image_bytes = self
-            .client
-            .get(original_url)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to download image: {}", e))?
-            .bytes()
+        // Download the image first. Poster URLs come from scraped pages, so
+        // every hop is validated like any other proxied target and the body
+        // is held to the proxy limits.
+        let limits = ProxyLimits::from_config();
+        let image_bytes = limits
+            .within_timeout(original_url, async {
+                let response = send_validated(original_url, |request| request).await?;
+                if !response.status().is_success() {
+                    return Err(AppError::Other(format!("upstream answered {}", response.status())));
+                }
+                limits.read_body(original_url, response).await
+            })
             .await
-            .map_err(|e| format!("Failed to read image bytes: {}", e))?;
+            .map_err(|e| format!("Failed to download image: {}", e))?;
 
         // Determine filename from URL
         let filename = self.extract_filename(original_url);
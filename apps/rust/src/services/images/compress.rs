@@ -0,0 +1,194 @@
+//! In-process image compression to a target size.
+//!
+//! The image is re-encoded as JPEG at the highest quality whose output fits
+//! the byte budget, found by binary search. When even the lowest quality is
+//! too large at the current dimensions, the image is downscaled by the
+//! estimated factor and searched again. Everything runs on the `image` crate,
+//! so no external encoder is involved; callers on the async runtime should
+//! use `spawn_blocking`.
+//!
+//! Output is always JPEG: the `image` crate's WebP encoder is lossless only,
+//! so it has no quality to search over.
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageResult, RgbImage};
+
+/// Lowest JPEG quality tried before downscaling.
+pub const MIN_QUALITY: u8 = 10;
+/// Highest JPEG quality used.
+pub const MAX_QUALITY: u8 = 92;
+/// Downscale rounds before settling for the smallest output produced.
+const MAX_DOWNSCALES: usize = 6;
+/// Images are never shrunk below this many pixels on their longer side.
+const MIN_DIMENSION: u32 = 16;
+
+/// Result of [`compress_to_target`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedImage {
+    /// JPEG bytes.
+    pub bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub quality: u8,
+}
+
+impl CompressedImage {
+    pub const MIME: &'static str = "image/jpeg";
+    pub const EXTENSION: &'static str = "jpg";
+}
+
+fn encode(image: &RgbImage, quality: u8) -> ImageResult<Vec<u8>> {
+    let mut out = Vec::new();
+    JpegEncoder::new_with_quality(&mut out, quality).encode_image(image)?;
+    Ok(out)
+}
+
+/// Highest quality in `MIN_QUALITY..=MAX_QUALITY` whose encoding fits
+/// `target_bytes`, or `Err` with the lowest-quality encoding if none does.
+fn best_quality(
+    image: &RgbImage,
+    target_bytes: usize,
+) -> ImageResult<Result<CompressedImage, CompressedImage>> {
+    let found = |bytes: Vec<u8>, quality: u8| CompressedImage {
+        bytes,
+        width: image.width(),
+        height: image.height(),
+        quality,
+    };
+
+    let smallest = encode(image, MIN_QUALITY)?;
+    if smallest.len() > target_bytes {
+        return Ok(Err(found(smallest, MIN_QUALITY)));
+    }
+
+    let (mut fits, mut fits_quality) = (smallest, MIN_QUALITY);
+    let (mut low, mut high) = (MIN_QUALITY + 1, MAX_QUALITY);
+    while low <= high {
+        let quality = low + (high - low) / 2;
+        let bytes = encode(image, quality)?;
+        if bytes.len() <= target_bytes {
+            (fits, fits_quality) = (bytes, quality);
+            low = quality + 1;
+        } else {
+            high = quality - 1;
+        }
+    }
+    Ok(Ok(found(fits, fits_quality)))
+}
+
+/// Re-encodes `input` (any format the `image` crate decodes) as a JPEG of at
+/// most `target_bytes`, keeping as much quality and resolution as fits.
+///
+/// If the budget can't be met even at [`MIN_DIMENSION`], the smallest
+/// encoding produced is returned; callers can compare its length.
+pub fn compress_to_target(input: &[u8], target_bytes: usize) -> ImageResult<CompressedImage> {
+    let mut image: RgbImage = image::load_from_memory(input)?.to_rgb8();
+    let mut smallest: Option<CompressedImage> = None;
+
+    for _ in 0..=MAX_DOWNSCALES {
+        match best_quality(&image, target_bytes)? {
+            Ok(compressed) => return Ok(compressed),
+            Err(too_big) => {
+                // JPEG size grows roughly with pixel count, so scale each side
+                // by the square root of the overshoot, with a little margin.
+                let factor = ((target_bytes as f64 / too_big.bytes.len() as f64).sqrt() * 0.9)
+                    .clamp(0.1, 0.9);
+                let width = ((image.width() as f64 * factor) as u32).max(1);
+                let height = ((image.height() as f64 * factor) as u32).max(1);
+                let at_floor = width.max(height) < MIN_DIMENSION;
+                if smallest
+                    .as_ref()
+                    .is_none_or(|s| too_big.bytes.len() < s.bytes.len())
+                {
+                    smallest = Some(too_big);
+                }
+                if at_floor {
+                    break;
+                }
+                image = DynamicImage::ImageRgb8(image)
+                    .resize_exact(width, height, FilterType::Triangle)
+                    .to_rgb8();
+            }
+        }
+    }
+
+    match smallest {
+        Some(smallest) => Ok(smallest),
+        None => best_quality(&image, target_bytes).map(|r| r.unwrap_or_else(|smallest| smallest)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, Rgb};
+    use std::io::Cursor;
+
+    /// A 512x512 PNG with gradients and deterministic noise, so JPEG size
+    /// responds smoothly to quality like a photo would.
+    fn photo_like_png() -> Vec<u8> {
+        let mut seed: u32 = 0x2545_f491;
+        let image = RgbImage::from_fn(512, 512, |x, y| {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            let noise = (seed >> 26) as u8;
+            Rgb([
+                ((x / 2) as u8).wrapping_add(noise),
+                ((y / 2) as u8).wrapping_add(noise / 2),
+                (((x + y) / 4) as u8).wrapping_add(noise),
+            ])
+        });
+        let mut png = Cursor::new(Vec::new());
+        DynamicImage::ImageRgb8(image)
+            .write_to(&mut png, ImageFormat::Png)
+            .unwrap();
+        png.into_inner()
+    }
+
+    #[test]
+    fn kb_targets_converge_within_tolerance() {
+        let png = photo_like_png();
+        for target_kb in [30usize, 60] {
+            let target = target_kb * 1024;
+            let out = compress_to_target(&png, target).unwrap();
+
+            assert!(
+                out.bytes.len() <= target,
+                "{} KB target gave {} bytes",
+                target_kb,
+                out.bytes.len()
+            );
+            assert!(
+                out.bytes.len() as f64 >= target as f64 * 0.85,
+                "{} KB target gave only {} bytes at quality {}",
+                target_kb,
+                out.bytes.len(),
+                out.quality
+            );
+            assert_eq!((out.width, out.height), (512, 512));
+            assert_eq!(image::guess_format(&out.bytes).unwrap(), ImageFormat::Jpeg);
+        }
+    }
+
+    #[test]
+    fn tiny_targets_downscale() {
+        let png = photo_like_png();
+        let target = 4 * 1024;
+        let out = compress_to_target(&png, target).unwrap();
+
+        assert!(out.bytes.len() <= target, "{} bytes", out.bytes.len());
+        assert!(out.width < 512 && out.height < 512);
+        assert_eq!(out.width, out.height);
+    }
+
+    #[test]
+    fn generous_targets_keep_top_quality() {
+        let out = compress_to_target(&photo_like_png(), 10 * 1024 * 1024).unwrap();
+        assert_eq!(out.quality, MAX_QUALITY);
+    }
+
+    #[test]
+    fn garbage_is_an_error() {
+        assert!(compress_to_target(b"not an image", 1024).is_err());
+    }
+}
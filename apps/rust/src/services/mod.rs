@@ -1,2 +1,6 @@
+pub mod bookmarks;
+pub mod chat;
 pub mod images;
+pub mod preferences;
+pub mod search_index;
 pub mod storage;
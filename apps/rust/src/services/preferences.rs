@@ -0,0 +1,152 @@
+//! Per-user UI preferences, synced across the user's devices.
+//!
+//! One row per user in `user_preferences`; users who never saved anything
+//! have no row and get [`Preferences::default`].
+
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{DatabaseConnection, DbErr, EntityTrait, Set};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::entities::user_preferences;
+
+/// Colour scheme of the web app; stored in `user_preferences.theme`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Light,
+    Dark,
+    /// Follow the device's `prefers-color-scheme`.
+    #[default]
+    System,
+}
+
+impl Theme {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+            Theme::System => "system",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "light" => Some(Theme::Light),
+            "dark" => Some(Theme::Dark),
+            "system" => Some(Theme::System),
+            _ => None,
+        }
+    }
+}
+
+/// A user's stored preferences.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Preferences {
+    pub theme: Theme,
+    /// When they were last saved; `None` if never.
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Preferences {
+    fn from_model(model: user_preferences::Model) -> Self {
+        Self {
+            // A theme this build doesn't know falls back to the default.
+            theme: Theme::parse(&model.theme).unwrap_or_default(),
+            updated_at: Some(model.updated_at),
+        }
+    }
+}
+
+/// `user_id`'s preferences, or the defaults if none were saved.
+pub async fn get(db: &DatabaseConnection, user_id: &str) -> Result<Preferences, DbErr> {
+    Ok(user_preferences::Entity::find_by_id(user_id.to_string())
+        .one(db)
+        .await?
+        .map(Preferences::from_model)
+        .unwrap_or_default())
+}
+
+/// Saves `theme` as `user_id`'s theme and returns the stored preferences.
+pub async fn set_theme(
+    db: &DatabaseConnection,
+    user_id: &str,
+    theme: Theme,
+) -> Result<Preferences, DbErr> {
+    let row = user_preferences::ActiveModel {
+        user_id: Set(user_id.to_string()),
+        theme: Set(theme.as_str().to_string()),
+        updated_at: Set(Utc::now()),
+    };
+    user_preferences::Entity::insert(row)
+        .on_conflict(
+            OnConflict::column(user_preferences::Column::UserId)
+                .update_columns([
+                    user_preferences::Column::Theme,
+                    user_preferences::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    get(db, user_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+
+    fn stored(theme: &str) -> user_preferences::Model {
+        user_preferences::Model {
+            user_id: "u1".to_string(),
+            theme: theme.to_string(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn saved_theme_is_read_back() {
+        let row = stored("dark");
+        let db = MockDatabase::new(DatabaseBackend::MySql)
+            .append_query_results([Vec::<user_preferences::Model>::new()])
+            .append_exec_results([MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .append_query_results([vec![row.clone()]])
+            .append_query_results([vec![row]])
+            .into_connection();
+
+        assert_eq!(get(&db, "u1").await.unwrap(), Preferences::default());
+        let saved = set_theme(&db, "u1", Theme::Dark).await.unwrap();
+        assert_eq!(saved.theme, Theme::Dark);
+        assert!(saved.updated_at.is_some());
+        assert_eq!(get(&db, "u1").await.unwrap(), saved);
+
+        let log = db.into_transaction_log();
+        let insert = &log[1].statements()[0].sql;
+        assert!(
+            insert.starts_with("INSERT INTO `user_preferences`"),
+            "{}",
+            insert
+        );
+        assert!(insert.contains("`theme` = VALUES(`theme`)"), "{}", insert);
+        let lookup = &log[2].statements()[0].sql;
+        assert!(
+            lookup.contains("`user_preferences`.`user_id` = ?"),
+            "{}",
+            lookup
+        );
+    }
+
+    #[test]
+    fn themes_round_trip() {
+        for theme in [Theme::Light, Theme::Dark, Theme::System] {
+            assert_eq!(Theme::parse(theme.as_str()), Some(theme));
+        }
+        assert_eq!(Theme::parse("sepia"), None);
+    }
+}
@@ -0,0 +1,358 @@
+//! Local title search over scraped anime/komik metadata.
+//!
+//! Every title the app scrapes (list pages via the prewarm job, anime details
+//! as they are loaded) is recorded in a Redis hash per kind, keyed by slug.
+//! [`rank`] then scores a query against those titles and their alternative
+//! titles without touching the source sites. Matching is case- and
+//! punctuation-insensitive and tolerates small typos.
+
+use deadpool_redis::redis::{cmd, AsyncCommands};
+use deadpool_redis::Pool;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::helpers::cache_ttl::CACHE_TTL_VERY_LONG;
+
+/// Index entries outlive any list cache; each write pushes this back.
+const INDEX_TTL: u64 = CACHE_TTL_VERY_LONG * 7;
+
+/// Hits scoring below this are dropped.
+pub const MIN_SCORE: f32 = 0.3;
+
+/// Alternative titles count a little less than the main one.
+const ALTERNATIVE_TITLE_WEIGHT: f32 = 0.9;
+
+/// Which index a title lives in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchKind {
+    #[default]
+    Anime,
+    Komik,
+}
+
+impl SearchKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SearchKind::Anime => "anime",
+            SearchKind::Komik => "komik",
+        }
+    }
+
+    fn index_key(self) -> String {
+        format!("search:{}", self.as_str())
+    }
+}
+
+/// One searchable title.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SearchEntry {
+    pub kind: SearchKind,
+    pub slug: String,
+    pub title: String,
+    #[serde(default)]
+    pub alternative_titles: Vec<String>,
+    #[serde(default)]
+    pub poster: String,
+}
+
+impl SearchEntry {
+    pub fn new(kind: SearchKind, slug: &str, title: &str, poster: &str) -> Self {
+        Self {
+            kind,
+            slug: slug.to_string(),
+            title: title.to_string(),
+            alternative_titles: Vec::new(),
+            poster: poster.to_string(),
+        }
+    }
+
+    pub fn with_alternative_titles(mut self, titles: Vec<String>) -> Self {
+        self.alternative_titles = titles;
+        self
+    }
+}
+
+/// A ranked match.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub entry: SearchEntry,
+    /// 0 to 1; 1 is an exact title match.
+    pub score: f32,
+}
+
+/// Responses whose titles belong in the index.
+pub trait SearchDocuments {
+    fn search_entries(&self) -> Vec<SearchEntry>;
+}
+
+/// Adds or refreshes `entries`. Alternative titles already on record are
+/// kept when an entry brings none (list pages don't show them).
+pub async fn index_entries(pool: &Pool, entries: &[SearchEntry]) -> Result<usize, String> {
+    let mut by_kind: HashMap<SearchKind, Vec<&SearchEntry>> = HashMap::new();
+    for entry in entries
+        .iter()
+        .filter(|e| !e.slug.is_empty() && !e.title.is_empty())
+    {
+        by_kind.entry(entry.kind).or_default().push(entry);
+    }
+    if by_kind.is_empty() {
+        return Ok(0);
+    }
+
+    let mut conn = pool.get().await.map_err(|e| e.to_string())?;
+    let mut indexed = 0;
+    for (kind, entries) in by_kind {
+        let key = kind.index_key();
+        let slugs: Vec<&str> = entries.iter().map(|e| e.slug.as_str()).collect();
+        let existing: Vec<Option<String>> = cmd("HMGET")
+            .arg(&key)
+            .arg(&slugs)
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut fields = Vec::with_capacity(entries.len());
+        for (entry, existing) in entries.into_iter().zip(existing) {
+            let mut entry = entry.clone();
+            let known = existing.and_then(|raw| serde_json::from_str::<SearchEntry>(&raw).ok());
+            if let Some(known) = known.filter(|_| entry.alternative_titles.is_empty()) {
+                entry.alternative_titles = known.alternative_titles;
+            }
+            let json = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+            fields.push((entry.slug, json));
+        }
+
+        indexed += fields.len();
+        let _: () = conn
+            .hset_multiple(&key, &fields)
+            .await
+            .map_err(|e| e.to_string())?;
+        let _: () = conn
+            .expire(&key, INDEX_TTL as i64)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(indexed)
+}
+
+/// Every indexed entry of `kind`; empty when the index is cold.
+pub async fn load_entries(pool: &Pool, kind: SearchKind) -> Result<Vec<SearchEntry>, String> {
+    let mut conn = pool.get().await.map_err(|e| e.to_string())?;
+    let values: Vec<String> = conn
+        .hvals(kind.index_key())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(values
+        .iter()
+        .filter_map(|raw| serde_json::from_str(raw).ok())
+        .collect())
+}
+
+/// Lowercased alphanumeric words of `text`.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Levenshtein distance, giving up (returning `max + 1`) once it exceeds `max`.
+fn edit_distance(a: &str, b: &str, max: usize) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    if a.len().abs_diff(b.len()) > max {
+        return max + 1;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        if current.iter().min().copied().unwrap_or(0) > max {
+            return max + 1;
+        }
+        previous = current;
+    }
+    previous[b.len()].min(max + 1)
+}
+
+/// How well one query word matches one title word: 1 for equal, less for
+/// a prefix, less again for a typo, 0 otherwise.
+fn word_score(query: &str, word: &str) -> f32 {
+    if query == word {
+        return 1.0;
+    }
+    if word.starts_with(query) {
+        return 0.8;
+    }
+    let allowed = match query.chars().count() {
+        0..=3 => 0,
+        4..=6 => 1,
+        _ => 2,
+    };
+    if allowed > 0 && edit_distance(query, word, allowed) <= allowed {
+        return 0.6;
+    }
+    0.0
+}
+
+/// Score of `query` against one title, from 0 to 1.
+fn title_score(query: &[String], title: &str) -> f32 {
+    let title = words(title);
+    if query.is_empty() || title.is_empty() {
+        return 0.0;
+    }
+    if query == title.as_slice() {
+        return 1.0;
+    }
+    let phrase = query.join(" ");
+    let joined = title.join(" ");
+    if joined.starts_with(&phrase) {
+        return 0.9;
+    }
+
+    // Every query word must find a title word; the weakest match and how
+    // much of the title the query covers decide the rest.
+    let mut total = 0.0;
+    for q in query {
+        let best = title.iter().map(|w| word_score(q, w)).fold(0.0, f32::max);
+        if best == 0.0 {
+            return 0.0;
+        }
+        total += best;
+    }
+    let matched = total / query.len() as f32;
+    let coverage = (query.len() as f32 / title.len() as f32).min(1.0);
+    let contiguous = if joined.contains(&phrase) { 0.1 } else { 0.0 };
+    (0.5 * matched + 0.2 * coverage + contiguous).min(0.85)
+}
+
+/// Best score of `query` against `entry`'s title and alternative titles.
+fn entry_score(query: &[String], entry: &SearchEntry) -> f32 {
+    let alternative = entry
+        .alternative_titles
+        .iter()
+        .map(|t| title_score(query, t) * ALTERNATIVE_TITLE_WEIGHT)
+        .fold(0.0, f32::max);
+    title_score(query, &entry.title).max(alternative)
+}
+
+/// Up to `limit` entries matching `query`, best first. Ties go to the
+/// shorter title, then alphabetical order, so results are stable.
+pub fn rank(entries: &[SearchEntry], query: &str, limit: usize) -> Vec<SearchHit> {
+    let query = words(query);
+    let mut hits: Vec<SearchHit> = entries
+        .iter()
+        .map(|entry| SearchHit {
+            score: entry_score(&query, entry),
+            entry: entry.clone(),
+        })
+        .filter(|hit| hit.score >= MIN_SCORE)
+        .collect();
+
+    hits.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.entry.title.len().cmp(&b.entry.title.len()))
+            .then_with(|| a.entry.title.cmp(&b.entry.title))
+    });
+    hits.truncate(limit);
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anime(slug: &str, title: &str, alternative: &[&str]) -> SearchEntry {
+        SearchEntry::new(SearchKind::Anime, slug, title, "")
+            .with_alternative_titles(alternative.iter().map(|t| t.to_string()).collect())
+    }
+
+    fn catalog() -> Vec<SearchEntry> {
+        vec![
+            anime("one-piece", "One Piece", &[]),
+            anime("one-punch-man", "One Punch Man", &[]),
+            anime("one-piece-film-red", "One Piece Film: Red", &[]),
+            anime(
+                "frieren",
+                "Sousou no Frieren",
+                &["Frieren: Beyond Journey's End"],
+            ),
+            anime("kaiju-8", "Kaijuu 8-gou", &["Kaiju No. 8"]),
+            anime("naruto", "Naruto", &[]),
+        ]
+    }
+
+    fn slugs(hits: &[SearchHit]) -> Vec<&str> {
+        hits.iter().map(|h| h.entry.slug.as_str()).collect()
+    }
+
+    #[test]
+    fn exact_beats_prefix_beats_partial() {
+        let hits = rank(&catalog(), "one piece", 10);
+        assert_eq!(slugs(&hits), ["one-piece", "one-piece-film-red"]);
+        assert_eq!(hits[0].score, 1.0);
+        assert!(hits[1].score < hits[0].score);
+
+        // "one" prefixes all three; the shortest title wins the tie.
+        let hits = rank(&catalog(), "one", 10);
+        assert_eq!(
+            slugs(&hits),
+            ["one-piece", "one-punch-man", "one-piece-film-red"]
+        );
+    }
+
+    #[test]
+    fn alternative_titles_match_but_rank_below_main_titles() {
+        let hits = rank(&catalog(), "frieren", 10);
+        assert_eq!(slugs(&hits), ["frieren"]);
+
+        let mut entries = catalog();
+        entries.push(anime("frieren-recap", "Frieren", &[]));
+        let hits = rank(&entries, "Frieren", 10);
+        assert_eq!(slugs(&hits), ["frieren-recap", "frieren"]);
+
+        let hits = rank(&catalog(), "kaiju no 8", 10);
+        assert_eq!(slugs(&hits), ["kaiju-8"]);
+        assert!((hits[0].score - ALTERNATIVE_TITLE_WEIGHT).abs() < 1e-6);
+    }
+
+    #[test]
+    fn typos_and_punctuation_are_tolerated() {
+        assert_eq!(slugs(&rank(&catalog(), "narutoo", 10)), ["naruto"]);
+        assert_eq!(
+            slugs(&rank(&catalog(), "ONE-PUNCH man!", 10)),
+            ["one-punch-man"]
+        );
+        assert_eq!(slugs(&rank(&catalog(), "frieern", 10)), ["frieren"]);
+        // Short words must match exactly or as a prefix.
+        assert!(rank(&catalog(), "onx", 10).is_empty());
+    }
+
+    #[test]
+    fn unrelated_and_blank_queries_find_nothing() {
+        assert!(rank(&catalog(), "bleach", 10).is_empty());
+        assert!(rank(&catalog(), "  ", 10).is_empty());
+        // Every query word has to match something.
+        assert!(rank(&catalog(), "one bleach", 10).is_empty());
+    }
+
+    #[test]
+    fn limit_keeps_the_best() {
+        assert_eq!(slugs(&rank(&catalog(), "one", 1)), ["one-piece"]);
+    }
+
+    #[test]
+    fn edit_distance_gives_up_past_max() {
+        assert_eq!(edit_distance("kitten", "sitting", 3), 3);
+        assert_eq!(edit_distance("kitten", "sitting", 1), 2);
+        assert_eq!(edit_distance("a", "abcdef", 2), 3);
+    }
+}
@@ -1,7 +1,8 @@
 //! Storage driver trait definition.
 
-use super::FileMetadata;
+use super::{FileMetadata, FileStream};
 use async_trait::async_trait;
+use futures::stream;
 
 /// Errors that can occur during storage operations.
 #[derive(Debug, thiserror::Error)]
@@ -48,6 +49,24 @@ pub trait StorageDriver: Send + Sync {
     /// Get a file's content.
     async fn get(&self, path: &str) -> Result<Vec<u8>, StorageError>;
 
+    /// Open a file for streaming, with its metadata.
+    ///
+    /// The default reads the whole file with [`get`](Self::get); drivers that
+    /// can should override it to stream.
+    async fn stream(&self, path: &str) -> Result<FileStream, StorageError> {
+        let content = self.get(path).await?;
+        let metadata = FileMetadata {
+            size: content.len() as u64,
+            mime_type: None,
+            modified: None,
+            created: None,
+        };
+        Ok(FileStream {
+            metadata,
+            body: Box::pin(stream::once(async move { Ok(content.into()) })),
+        })
+    }
+
     /// Check if a file exists.
     async fn exists(&self, path: &str) -> Result<bool, StorageError>;
 
@@ -1,10 +1,12 @@
 //! Local filesystem storage driver.
 
 use super::driver::{StorageDriver, StorageError};
-use super::FileMetadata;
+use super::{FileMetadata, FileStream};
 use async_trait::async_trait;
+use futures::TryStreamExt;
 use std::path::{Path, PathBuf};
 use tokio::fs;
+use tokio_util::io::ReaderStream;
 
 /// Local filesystem storage driver.
 #[derive(Clone)]
@@ -66,6 +68,15 @@ impl StorageDriver for LocalDriver {
         Ok(content)
     }
 
+    async fn stream(&self, path: &str) -> Result<FileStream, StorageError> {
+        let metadata = self.metadata(path).await?;
+        let file = fs::File::open(self.full_path(path)?).await?;
+        Ok(FileStream {
+            metadata,
+            body: Box::pin(ReaderStream::new(file).map_err(StorageError::from)),
+        })
+    }
+
     async fn exists(&self, path: &str) -> Result<bool, StorageError> {
         let full_path = self.full_path(path)?;
         Ok(full_path.exists())
@@ -95,6 +106,9 @@ impl StorageDriver for LocalDriver {
     async fn metadata(&self, path: &str) -> Result<FileMetadata, StorageError> {
         let full_path = self.full_path(path)?;
         let meta = fs::metadata(&full_path).await?;
+        if !meta.is_file() {
+            return Err(StorageError::NotFound(path.to_string()));
+        }
 
         let mime_type = mime_guess::from_path(&full_path)
             .first()
@@ -31,6 +31,9 @@ pub use driver::{StorageDriver, StorageError};
 pub use local::LocalDriver;
 pub use s3::{S3Config, S3Driver};
 
+use bytes::Bytes;
+use futures::Stream;
+use std::pin::Pin;
 use std::sync::Arc;
 
 /// High-level storage interface.
@@ -72,6 +75,11 @@ impl Storage {
         self.driver.get(path).await
     }
 
+    /// Open a file for streaming, with its metadata.
+    pub async fn stream(&self, path: &str) -> Result<FileStream, StorageError> {
+        self.driver.stream(path).await
+    }
+
     /// Check if a file exists.
     pub async fn exists(&self, path: &str) -> Result<bool, StorageError> {
         self.driver.exists(path).await
@@ -120,3 +128,12 @@ pub struct FileMetadata {
     /// Created timestamp
     pub created: Option<i64>,
 }
+
+/// A file's content as a stream of chunks.
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes, StorageError>> + Send>>;
+
+/// An opened file: its metadata and a stream of its content.
+pub struct FileStream {
+    pub metadata: FileMetadata,
+    pub body: ByteStream,
+}
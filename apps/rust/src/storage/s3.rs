@@ -20,8 +20,9 @@
 //! ```
 
 use super::driver::{StorageDriver, StorageError};
-use super::FileMetadata;
+use super::{FileMetadata, FileStream};
 use async_trait::async_trait;
+use futures::TryStreamExt;
 use hmac::{Hmac, Mac};
 use reqwest::Client;
 use sha2::{Digest, Sha256};
@@ -191,6 +192,33 @@ impl S3Driver {
     }
 }
 
+/// File metadata from the headers of a GET or HEAD response.
+fn metadata_from_headers(headers: &reqwest::header::HeaderMap) -> FileMetadata {
+    let size = headers
+        .get("content-length")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(0);
+
+    let mime_type = headers
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+
+    let modified = headers
+        .get("last-modified")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| chrono::DateTime::parse_from_rfc2822(s).ok())
+        .map(|dt| dt.timestamp());
+
+    FileMetadata {
+        size,
+        mime_type,
+        modified,
+        created: None,
+    }
+}
+
 fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
     let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC key length is always valid");
     mac.update(data);
@@ -260,6 +288,42 @@ impl StorageDriver for S3Driver {
         Ok(bytes.to_vec())
     }
 
+    async fn stream(&self, path: &str) -> Result<FileStream, StorageError> {
+        let url = self.url(path);
+        let payload_hash = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+        let headers = self.sign_request("GET", path, &[], payload_hash);
+
+        let mut req = self.client.get(&url);
+
+        for (key, value) in headers {
+            req = req.header(&key, &value);
+        }
+
+        let response = req.send().await.map_err(|e| {
+            tracing::error!("S3 GET error: {}", e);
+            StorageError::IoError(e.to_string())
+        })?;
+
+        if response.status().as_u16() == 404 {
+            return Err(StorageError::NotFound(path.to_string()));
+        }
+
+        if !response.status().is_success() {
+            let status = response.status();
+            return Err(StorageError::IoError(format!("S3 error: {}", status)));
+        }
+
+        Ok(FileStream {
+            metadata: metadata_from_headers(response.headers()),
+            body: Box::pin(
+                response
+                    .bytes_stream()
+                    .map_err(|e| StorageError::IoError(e.to_string())),
+            ),
+        })
+    }
+
     async fn exists(&self, path: &str) -> Result<bool, StorageError> {
         let url = self.url(path);
         let payload_hash = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
@@ -336,32 +400,12 @@ impl StorageDriver for S3Driver {
             return Err(StorageError::NotFound(path.to_string()));
         }
 
-        let size = response
-            .headers()
-            .get("content-length")
-            .and_then(|v| v.to_str().ok())
-            .and_then(|s| s.parse::<u64>().ok())
-            .unwrap_or(0);
-
-        let mime_type = response
-            .headers()
-            .get("content-type")
-            .and_then(|v| v.to_str().ok())
-            .map(String::from);
-
-        let modified = response
-            .headers()
-            .get("last-modified")
-            .and_then(|v| v.to_str().ok())
-            .and_then(|s| chrono::DateTime::parse_from_rfc2822(s).ok())
-            .map(|dt| dt.timestamp());
-
-        Ok(FileMetadata {
-            size,
-            mime_type,
-            modified,
-            created: None,
-        })
+        if !response.status().is_success() {
+            let status = response.status();
+            return Err(StorageError::IoError(format!("S3 error: {}", status)));
+        }
+
+        Ok(metadata_from_headers(response.headers()))
     }
 
     async fn list(&self, _directory: &str) -> Result<Vec<String>, StorageError> {
@@ -2,6 +2,12 @@
 //!
 //! Provides a `TestApp` struct for integration testing that boots
 //! the application in-memory with a test configuration.
+//!
+//! [`TestAppBuilder::build_api`] serves the real `/api` routes on a test
+//! [`AppState`]: Redis points at a closed port (so every cache lookup misses),
+//! the database is disconnected unless [`TestAppBuilder::with_sqlite_memory`]
+//! is used, and [`TestAppBuilder::with_mock_upstream`] sends the scrapers to a
+//! local mock server instead of the live sites.
 
 use axum::{
     body::Body,
@@ -9,9 +15,79 @@ use axum::{
     response::Response,
     Router,
 };
+use deadpool_redis::{Manager, Pool};
+use sea_orm::{Database, DatabaseConnection};
 use serde::{de::DeserializeOwned, Serialize};
+use std::sync::{Arc, Mutex, MutexGuard, Once};
 use tower::ServiceExt;
 
+use crate::circuit_breaker::breaker::CircuitBreakerConfig;
+use crate::circuit_breaker::SourceBreakers;
+use crate::di::AppServices;
+use crate::routes::AppState;
+use crate::scraping::urls::set_upstream_override;
+
+/// Redis URL of the test state; nothing listens there.
+const UNREACHABLE_REDIS: &str = "redis://127.0.0.1:1";
+
+/// Held by every [`TestApp`] with a mock upstream, since the scrape source
+/// override it installs is process-wide.
+static UPSTREAM_LOCK: Mutex<()> = Mutex::new(());
+
+/// Fills in the settings [`CONFIG`](crate::core::config::CONFIG) requires, so
+/// code reaching for it under test loads instead of exiting. Values already
+/// in the environment are kept.
+pub fn init_test_env() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        for (key, value) in [
+            ("DATABASE_URL", "sqlite::memory:"),
+            ("JWT_SECRET", "test-secret-test-secret-test-secret"),
+            ("REDIS_URL", UNREACHABLE_REDIS),
+        ] {
+            if std::env::var_os(key).is_none() {
+                std::env::set_var(key, value);
+            }
+        }
+    });
+}
+
+/// An [`AppState`] over `db` with no Redis, storage or shared breakers.
+pub fn test_state(db: DatabaseConnection) -> Arc<AppState> {
+    let redis_pool = Pool::builder(Manager::new(UNREACHABLE_REDIS).expect("Invalid test Redis URL"))
+        .runtime(deadpool_redis::Runtime::Tokio1)
+        .build()
+        .expect("Failed to build test Redis pool");
+    test_state_with_redis(db, redis_pool)
+}
+
+/// Like [`test_state`], but with `redis_pool` (e.g. a
+/// [`fake_redis`](crate::testing::redis::fake_redis)) as its Redis.
+pub fn test_state_with_redis(db: DatabaseConnection, redis_pool: Pool) -> Arc<AppState> {
+    init_test_env();
+    let db = Arc::new(db);
+    let source_breakers = Arc::new(SourceBreakers::new(CircuitBreakerConfig::default()));
+    let services = AppServices {
+        db: db.clone(),
+        redis_pool: redis_pool.clone(),
+        storage: None,
+        http_client: crate::infra::HTTP_CLIENT.clone(),
+        source_breakers: source_breakers.clone(),
+    };
+
+    Arc::new(AppState {
+        jwt_secret: crate::core::config::CONFIG.jwt_secret.clone(),
+        redis_pool,
+        db,
+        chat_tx: tokio::sync::broadcast::channel(16).0,
+        image_processing_semaphore: Arc::new(tokio::sync::Semaphore::new(1)),
+        room_manager: Arc::new(crate::ws::room::RoomManager::new()),
+        chat_rooms: Arc::new(crate::routes::ws::chat::ChatRooms::new()),
+        source_breakers,
+        services: Arc::new(services.into_container()),
+    })
+}
+
 /// A test application instance for integration testing.
 ///
 /// # Example
@@ -32,6 +108,9 @@ use tower::ServiceExt;
 /// ```
 pub struct TestApp {
     router: Router,
+    state: Option<Arc<AppState>>,
+    /// Keeps other mock-upstream apps waiting until this one is dropped.
+    upstream_guard: Option<MutexGuard<'static, ()>>,
 }
 
 impl TestApp {
@@ -39,7 +118,25 @@ impl TestApp {
     ///
     /// This sets up the router without starting a server.
     pub fn with_router(router: Router) -> Self {
-        Self { router }
+        Self { router, state: None, upstream_guard: None }
+    }
+
+    /// State the routes run on, when built with [`TestAppBuilder::build_api`].
+    pub fn state(&self) -> Option<&Arc<AppState>> {
+        self.state.as_ref()
+    }
+
+    /// Make a request and parse the response body as JSON.
+    ///
+    /// Bodies that aren't JSON (e.g. plain-text errors) come back as a JSON
+    /// string, so failures can still be asserted on and printed.
+    pub async fn request_json(&self, method: Method, path: &str) -> (StatusCode, serde_json::Value) {
+        let response = self.request(method, path, Body::empty()).await;
+        let status = response.status();
+        let bytes = response.bytes().await;
+        let body = serde_json::from_slice(&bytes)
+            .unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(&bytes).to_string()));
+        (status, body)
     }
 
     /// Make a GET request.
@@ -185,6 +282,15 @@ impl TestResponse {
         serde_json::from_slice(&bytes).expect("Failed to parse response as JSON")
     }
 
+    /// Assert the response body is JSON matching `expected_shape`.
+    ///
+    /// See [`crate::testing::shape`] for the shape syntax.
+    pub async fn assert_json_shape(self, expected_shape: &serde_json::Value) -> serde_json::Value {
+        let body: serde_json::Value = self.json().await;
+        crate::testing::assert_json_shape(&body, expected_shape);
+        body
+    }
+
     /// Assert the response body contains a string.
     pub async fn assert_body_contains(self, expected: &str) -> Self {
         let body = self.text().await;
@@ -202,20 +308,72 @@ impl TestResponse {
     }
 }
 
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        if self.upstream_guard.is_some() {
+            set_upstream_override(None);
+        }
+    }
+}
+
 /// Builder for TestApp with custom configuration.
 pub struct TestAppBuilder {
-    // Future: Add configuration options
+    mock_upstream: Option<String>,
+    sqlite_memory: bool,
 }
 
 impl TestAppBuilder {
     /// Create a new test app builder.
     pub fn new() -> Self {
-        Self {}
+        Self { mock_upstream: None, sqlite_memory: false }
+    }
+
+    /// Scrape from `server_url` (e.g. a wiremock server) instead of the live
+    /// sites, until the app is dropped.
+    ///
+    /// The override is process-wide, so building a second such app blocks
+    /// until the first is dropped.
+    pub fn with_mock_upstream(mut self, server_url: impl Into<String>) -> Self {
+        self.mock_upstream = Some(server_url.into());
+        self
+    }
+
+    /// Back the app with a fresh, empty in-memory SQLite database. Tests
+    /// create the tables they need through [`TestApp::state`].
+    pub fn with_sqlite_memory(mut self) -> Self {
+        self.sqlite_memory = true;
+        self
     }
 
     /// Build the test app with a router.
     pub fn build(self, router: Router) -> TestApp {
-        TestApp::with_router(router)
+        let upstream_guard = self.apply_upstream();
+        TestApp { router, state: None, upstream_guard }
+    }
+
+    /// Build the test app serving the `/api` routes on a [`test_state`].
+    pub async fn build_api(self) -> TestApp {
+        let db = if self.sqlite_memory {
+            Database::connect("sqlite::memory:")
+                .await
+                .expect("Failed to open in-memory SQLite database")
+        } else {
+            DatabaseConnection::Disconnected
+        };
+        let state = test_state(db);
+        let router = crate::routes::api::create_api_routes().with_state(state.clone());
+
+        let upstream_guard = self.apply_upstream();
+        TestApp { router, state: Some(state), upstream_guard }
+    }
+
+    /// Takes the upstream lock and installs the override, if any.
+    fn apply_upstream(&self) -> Option<MutexGuard<'static, ()>> {
+        let url = self.mock_upstream.as_ref()?;
+        // A test that panicked holding the lock has already reset the override.
+        let guard = UPSTREAM_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        set_upstream_override(Some(url.clone()));
+        Some(guard)
     }
 }
 
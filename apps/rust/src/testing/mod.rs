@@ -4,5 +4,8 @@
 //! including a test application builder and assertion helpers.
 
 pub mod app;
+pub mod redis;
+pub mod shape;
 
-pub use app::TestApp;
+pub use app::{TestApp, TestAppBuilder};
+pub use shape::{assert_json_shape, check_json_shape};
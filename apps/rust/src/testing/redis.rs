@@ -0,0 +1,128 @@
+//! An in-process fake Redis for tests that need one to answer.
+//!
+//! [`fake_redis`] speaks just enough RESP for the commands the app sends:
+//! `GET`, `SETEX`, `EXISTS`, `DEL`, the `SET NX PX` and scripts of
+//! [`DistLock`](crate::infra::redis::DistLock), and connection setup.
+
+use deadpool_redis::redis::Script;
+use deadpool_redis::{Manager, Pool};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+use crate::infra::redis::{EXTEND_LOCK_SCRIPT, RELEASE_LOCK_SCRIPT};
+
+/// Key -> (value, expiry) of the fake server.
+pub type Store = Arc<Mutex<HashMap<String, (String, Instant)>>>;
+
+enum Reply {
+    Ok,
+    Bulk(Option<String>),
+    Int(i64),
+    Error(String),
+}
+
+/// Reads one command as sent by redis-rs: an array of bulk strings.
+async fn read_command<R: AsyncBufReadExt + Unpin>(reader: &mut R) -> Option<Vec<String>> {
+    let mut line = String::new();
+    reader.read_line(&mut line).await.ok()?;
+    let count: usize = line.trim_end().strip_prefix('*')?.parse().ok()?;
+    let mut args = Vec::with_capacity(count);
+    for _ in 0..count {
+        line.clear();
+        reader.read_line(&mut line).await.ok()?;
+        let len: usize = line.trim_end().strip_prefix('$')?.parse().ok()?;
+        let mut arg = vec![0; len + 2];
+        reader.read_exact(&mut arg).await.ok()?;
+        arg.truncate(len);
+        args.push(String::from_utf8(arg).ok()?);
+    }
+    Some(args)
+}
+
+/// Runs one command against `store`, with expiry. Scripts are matched by
+/// hash and run natively, as real Redis would run the Lua.
+fn execute(store: &Store, args: &[String]) -> Reply {
+    let mut store = store.lock().unwrap();
+    let now = Instant::now();
+    store.retain(|_, (_, expires)| *expires > now);
+    let name = args[0].to_ascii_uppercase();
+    match (name.as_str(), &args[1..]) {
+        ("PING", [echo]) => Reply::Bulk(Some(echo.clone())),
+        ("GET", [key]) => Reply::Bulk(store.get(key).map(|(value, _)| value.clone())),
+        ("EXISTS", [key]) => Reply::Int(i64::from(store.contains_key(key))),
+        ("DEL", [key]) => Reply::Int(i64::from(store.remove(key).is_some())),
+        ("SETEX", [key, seconds, value]) => {
+            let expires = now + Duration::from_secs(seconds.parse().unwrap());
+            store.insert(key.clone(), (value.clone(), expires));
+            Reply::Ok
+        }
+        ("SET", [key, value, nx, px, millis])
+            if nx.eq_ignore_ascii_case("NX") && px.eq_ignore_ascii_case("PX") =>
+        {
+            if store.contains_key(key) {
+                return Reply::Bulk(None);
+            }
+            let expires = now + Duration::from_millis(millis.parse().unwrap());
+            store.insert(key.clone(), (value.clone(), expires));
+            Reply::Ok
+        }
+        ("SET", _) => Reply::Error("ERR fake only supports SET NX PX".to_string()),
+        ("EVALSHA", [hash, _, key, token, rest @ ..]) => {
+            let held = store.get(key).is_some_and(|(value, _)| value == token);
+            if *hash == Script::new(RELEASE_LOCK_SCRIPT).get_hash() {
+                Reply::Int(i64::from(held && store.remove(key).is_some()))
+            } else if *hash == Script::new(EXTEND_LOCK_SCRIPT).get_hash() && held {
+                let millis: u64 = rest[0].parse().unwrap();
+                store.get_mut(key).unwrap().1 = now + Duration::from_millis(millis);
+                Reply::Int(1)
+            } else if *hash == Script::new(EXTEND_LOCK_SCRIPT).get_hash() {
+                Reply::Int(0)
+            } else {
+                Reply::Error("NOSCRIPT No matching script".to_string())
+            }
+        }
+        // Connection setup (CLIENT SETINFO and friends).
+        _ => Reply::Ok,
+    }
+}
+
+/// Serves a fake Redis on a free port and returns a pool for it, along with
+/// its store for the test to seed and inspect.
+pub async fn fake_redis() -> (Pool, Store) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("redis://{}", listener.local_addr().unwrap());
+    let store: Store = Arc::default();
+    let shared = store.clone();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            let store = shared.clone();
+            // Replies are small separate writes; without this Nagle holds
+            // each one back until the previous is acked, ~40ms later.
+            socket.set_nodelay(true).ok();
+            tokio::spawn(async move {
+                let (read, mut write) = socket.into_split();
+                let mut reader = BufReader::new(read);
+                while let Some(args) = read_command(&mut reader).await {
+                    let reply = match execute(&store, &args) {
+                        Reply::Ok => "+OK\r\n".to_string(),
+                        Reply::Bulk(Some(v)) => format!("${}\r\n{}\r\n", v.len(), v),
+                        Reply::Bulk(None) => "$-1\r\n".to_string(),
+                        Reply::Int(n) => format!(":{}\r\n", n),
+                        Reply::Error(e) => format!("-{}\r\n", e),
+                    };
+                    if write.write_all(reply.as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    let pool = Pool::builder(Manager::new(url).unwrap())
+        .runtime(deadpool_redis::Runtime::Tokio1)
+        .build()
+        .unwrap();
+    (pool, store)
+}
@@ -0,0 +1,217 @@
+//! JSON shape assertions.
+//!
+//! Check the structure of a JSON response (field presence and types)
+//! without pinning scraped values, so tests survive upstream content changes.
+//!
+//! # Shape syntax
+//!
+//! - `"string"`, `"number"`, `"bool"`, `"null"`, `"array"`, `"object"`, `"any"`:
+//!   the value must have that JSON type (`"any"` only requires presence).
+//! - A trailing `?` (e.g. `"string?"`) also accepts `null`.
+//! - `{ "field": <shape>, ... }`: the value must be an object containing every
+//!   listed field; extra fields are ignored.
+//! - `[<shape>]`: the value must be an array whose elements all match `<shape>`.
+//!   `[]` accepts any array.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use rustexpress::testing::assert_json_shape;
+//! use serde_json::json;
+//!
+//! assert_json_shape(
+//!     &body,
+//!     &json!({
+//!         "status": "string?",
+//!         "data": {
+//!             "title": "string",
+//!             "genres": [{ "name": "string", "slug": "string" }]
+//!         }
+//!     }),
+//! );
+//! ```
+
+use serde_json::Value;
+
+/// Assert that `value` matches `expected_shape`, panicking with the path of
+/// the first mismatch.
+pub fn assert_json_shape(value: &Value, expected_shape: &Value) {
+    let result = check_json_shape(value, expected_shape);
+    assert!(
+        result.is_ok(),
+        "JSON shape mismatch: {}\nactual: {}",
+        result.err().unwrap_or_default(),
+        value
+    );
+}
+
+/// Check that `value` matches `expected_shape`.
+///
+/// Returns a description of the first mismatch, prefixed with its JSON path.
+pub fn check_json_shape(value: &Value, expected_shape: &Value) -> Result<(), String> {
+    check_at("$", value, expected_shape)
+}
+
+fn check_at(path: &str, value: &Value, shape: &Value) -> Result<(), String> {
+    match shape {
+        Value::String(type_name) => check_type(path, value, type_name),
+        Value::Object(fields) => {
+            let object = value
+                .as_object()
+                .ok_or_else(|| format!("{}: expected object, got {}", path, type_of(value)))?;
+            for (key, field_shape) in fields {
+                let field_path = format!("{}.{}", path, key);
+                match object.get(key) {
+                    Some(field_value) => check_at(&field_path, field_value, field_shape)?,
+                    None if is_nullable(field_shape) => {}
+                    None => return Err(format!("{}: missing field", field_path)),
+                }
+            }
+            Ok(())
+        }
+        Value::Array(items) => {
+            let array = value
+                .as_array()
+                .ok_or_else(|| format!("{}: expected array, got {}", path, type_of(value)))?;
+            if let Some(item_shape) = items.first() {
+                for (i, item) in array.iter().enumerate() {
+                    check_at(&format!("{}[{}]", path, i), item, item_shape)?;
+                }
+            }
+            Ok(())
+        }
+        other => Err(format!(
+            "{}: invalid shape {}, expected a type name, object or array",
+            path, other
+        )),
+    }
+}
+
+fn check_type(path: &str, value: &Value, type_name: &str) -> Result<(), String> {
+    let (base, nullable) = match type_name.strip_suffix('?') {
+        Some(base) => (base, true),
+        None => (type_name, false),
+    };
+
+    if nullable && value.is_null() {
+        return Ok(());
+    }
+
+    let matches = match base {
+        "any" => true,
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "bool" | "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        unknown => return Err(format!("{}: unknown type '{}' in shape", path, unknown)),
+    };
+
+    if matches {
+        Ok(())
+    } else {
+        Err(format!("{}: expected {}, got {}", path, type_name, type_of(value)))
+    }
+}
+
+/// Optional fields (`"string?"`) may be absent, matching
+/// `skip_serializing_if = "Option::is_none"` on response structs.
+fn is_nullable(shape: &Value) -> bool {
+    matches!(shape, Value::String(s) if s.ends_with('?'))
+}
+
+fn type_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn detail_shape() -> Value {
+        json!({
+            "status": "string?",
+            "data": {
+                "title": "string",
+                "poster": "string",
+                "episode_lists": [{ "episode": "string", "slug": "string" }],
+                "recommendations": "array"
+            }
+        })
+    }
+
+    #[test]
+    fn test_matching_shape_passes() {
+        let body = json!({
+            "status": "Ok",
+            "data": {
+                "title": "One Piece",
+                "poster": "https://example.com/op.jpg",
+                "episode_lists": [
+                    { "episode": "Episode 1", "slug": "op-ep-1" },
+                    { "episode": "Episode 2", "slug": "op-ep-2", "extra": true }
+                ],
+                "recommendations": [],
+                "synopsis": "ignored"
+            }
+        });
+        assert_json_shape(&body, &detail_shape());
+
+        // Optional fields may be null or absent
+        let body = json!({
+            "data": {
+                "title": "Naruto",
+                "poster": "",
+                "episode_lists": [],
+                "recommendations": []
+            }
+        });
+        assert!(check_json_shape(&body, &detail_shape()).is_ok());
+    }
+
+    #[test]
+    fn test_missing_field_fails_with_path() {
+        let body = json!({
+            "status": "Ok",
+            "data": {
+                "title": "One Piece",
+                "episode_lists": [],
+                "recommendations": []
+            }
+        });
+        let err = check_json_shape(&body, &detail_shape()).unwrap_err();
+        assert_eq!(err, "$.data.poster: missing field");
+    }
+
+    #[test]
+    fn test_wrong_type_in_array_fails_with_index() {
+        let body = json!({
+            "data": {
+                "title": "One Piece",
+                "poster": "",
+                "episode_lists": [
+                    { "episode": "Episode 1", "slug": "op-ep-1" },
+                    { "episode": 2, "slug": "op-ep-2" }
+                ],
+                "recommendations": []
+            }
+        });
+        let err = check_json_shape(&body, &detail_shape()).unwrap_err();
+        assert_eq!(err, "$.data.episode_lists[1].episode: expected string, got number");
+    }
+
+    #[test]
+    #[should_panic(expected = "JSON shape mismatch: $.data: missing field")]
+    fn test_assert_panics_with_clear_message() {
+        assert_json_shape(&json!({ "status": "Ok" }), &detail_shape());
+    }
+}
@@ -0,0 +1,35 @@
+// Auto-generated from the OpenAPI schemas by `cargo run --bin gen-types`.
+// Do not edit manually!
+
+export type ApiResponse_MangaDetail = Envelope & { data: MangaDetail };
+
+export interface Author {
+  name: string;
+}
+
+export interface Chapter {
+  number: number;
+  pages?: { count: number; urls?: (string | null)[] };
+  slug: string;
+}
+
+export interface Envelope {
+  message?: string | null;
+  success: boolean;
+}
+
+/** Manga detail, serialized with `rename_all = "camelCase"`. */
+export interface MangaDetail {
+  altTitles?: string[];
+  author?: null | Author;
+  chapters: Chapter[];
+  coverUrl: string;
+  extra?: Record<string, number>;
+  rating?: number | null;
+  readingMode: ReadingMode;
+  title: string;
+  /** Upstream host. */
+  'x-source'?: string;
+}
+
+export type ReadingMode = 'vertical' | 'paged';
@@ -1,7 +1,11 @@
 //! TypeScript type generation from Rust structs.
 //!
-//! Uses ts-rs to generate TypeScript definitions for API types.
+//! Uses ts-rs to generate TypeScript definitions for API types. API response
+//! shapes are exported from the OpenAPI schemas via [`export_types`]
+//! (`cargo run --bin gen-types`).
 
 pub mod generator;
+pub mod openapi;
 
 pub use generator::generate_typescript_types;
+pub use openapi::{export_types, write_types};
@@ -0,0 +1,249 @@
+//! TypeScript interfaces generated from the OpenAPI component schemas.
+//!
+//! Every `ToSchema` type registered in [`ApiDoc`] ends up in
+//! `components.schemas`, already shaped the way serde serializes it
+//! (`rename_all`, `skip_serializing_if`, `Option`). Converting those schemas
+//! keeps the `.d.ts` output in step with the JSON the handlers actually send.
+
+use serde_json::{Map, Value};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use tracing::info;
+use utoipa::OpenApi;
+
+use crate::routes::api::ApiDoc;
+
+const HEADER: &str = "// Auto-generated from the OpenAPI schemas by `cargo run --bin gen-types`.\n\
+                      // Do not edit manually!\n";
+
+/// Renders every component schema of [`ApiDoc`] as TypeScript declarations.
+pub fn export_types() -> String {
+    let doc = serde_json::to_value(ApiDoc::openapi()).unwrap_or_default();
+    let schemas = doc
+        .pointer("/components/schemas")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    render_schemas(&schemas)
+}
+
+/// Writes [`export_types`] to `output_path`, creating parent directories.
+pub fn write_types(output_path: &str) -> anyhow::Result<()> {
+    if let Some(parent) = Path::new(output_path).parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(output_path, export_types())?;
+    info!("✅ TypeScript API types written to {}", output_path);
+    Ok(())
+}
+
+/// Renders a `components.schemas` map, one declaration per schema, in name order.
+pub fn render_schemas(schemas: &Map<String, Value>) -> String {
+    let mut names: Vec<&String> = schemas.keys().collect();
+    names.sort();
+
+    let mut out = String::from(HEADER);
+    for name in names {
+        out.push('\n');
+        out.push_str(&render_declaration(&type_name(name), &schemas[name]));
+    }
+    out
+}
+
+fn render_declaration(name: &str, schema: &Value) -> String {
+    let mut out = String::new();
+    push_doc(&mut out, schema, "");
+
+    match schema.get("properties").and_then(Value::as_object) {
+        Some(props) if is_plain_object(schema) => {
+            let _ = writeln!(out, "export interface {} {{", name);
+            out.push_str(&render_properties(props, schema, "  "));
+            out.push_str("}\n");
+        }
+        _ => {
+            let _ = writeln!(out, "export type {} = {};", name, ts_type(schema));
+        }
+    }
+    out
+}
+
+/// An object schema that maps cleanly onto an `interface`.
+fn is_plain_object(schema: &Value) -> bool {
+    ["allOf", "oneOf", "anyOf", "enum"]
+        .iter()
+        .all(|k| schema.get(k).is_none())
+}
+
+fn render_properties(props: &Map<String, Value>, schema: &Value, indent: &str) -> String {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let mut keys: Vec<&String> = props.keys().collect();
+    keys.sort();
+
+    let mut out = String::new();
+    for key in keys {
+        let prop = &props[key];
+        push_doc(&mut out, prop, indent);
+        let optional = if required.contains(&key.as_str()) { "" } else { "?" };
+        let _ = writeln!(out, "{}{}{}: {};", indent, property_name(key), optional, ts_type(prop));
+    }
+    out
+}
+
+fn push_doc(out: &mut String, schema: &Value, indent: &str) {
+    if let Some(desc) = schema.get("description").and_then(Value::as_str) {
+        let desc = desc.trim();
+        if desc.is_empty() {
+            return;
+        }
+        if desc.contains('\n') {
+            let _ = writeln!(out, "{}/**", indent);
+            for line in desc.lines() {
+                let _ = writeln!(out, "{} * {}", indent, line.trim_end());
+            }
+            let _ = writeln!(out, "{} */", indent);
+        } else {
+            let _ = writeln!(out, "{}/** {} */", indent, desc);
+        }
+    }
+}
+
+/// Converts one JSON schema into a TypeScript type expression.
+fn ts_type(schema: &Value) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return type_name(reference.rsplit('/').next().unwrap_or(reference));
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        let mut members: Vec<String> = values.iter().map(literal).collect();
+        if schema.get("type").and_then(Value::as_array).is_some_and(|t| t.iter().any(|t| t == "null")) {
+            members.push("null".to_string());
+        }
+        return union(members);
+    }
+    for (key, sep) in [("oneOf", " | "), ("anyOf", " | "), ("allOf", " & ")] {
+        if let Some(parts) = schema.get(key).and_then(Value::as_array) {
+            let members: Vec<String> = parts.iter().map(ts_type).map(|t| wrap(&t)).collect();
+            return dedup(members).join(sep);
+        }
+    }
+
+    match schema.get("type") {
+        Some(Value::String(t)) => primitive(t, schema),
+        Some(Value::Array(types)) => union(
+            types
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|t| primitive(t, schema))
+                .collect(),
+        ),
+        _ if schema.get("properties").is_some() => primitive("object", schema),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn primitive(kind: &str, schema: &Value) -> String {
+    match kind {
+        "string" => "string".to_string(),
+        "integer" | "number" => "number".to_string(),
+        "boolean" => "boolean".to_string(),
+        "null" => "null".to_string(),
+        "array" => {
+            let item = schema.get("items").map(ts_type).unwrap_or_else(|| "unknown".to_string());
+            format!("{}[]", wrap(&item))
+        }
+        "object" => match (
+            schema.get("properties").and_then(Value::as_object),
+            schema.get("additionalProperties"),
+        ) {
+            (Some(props), _) if !props.is_empty() => inline_object(props, schema),
+            (_, Some(extra)) if extra.is_object() => format!("Record<string, {}>", ts_type(extra)),
+            _ => "Record<string, unknown>".to_string(),
+        },
+        _ => "unknown".to_string(),
+    }
+}
+
+/// `{ a: string; b?: number }` for anonymous nested objects.
+fn inline_object(props: &Map<String, Value>, schema: &Value) -> String {
+    let fields: Vec<String> = render_properties(props, schema, "")
+        .lines()
+        .filter(|l| !(l.starts_with("/**") || l.starts_with(" *")))
+        .map(|l| l.trim_end_matches(';').to_string())
+        .collect();
+    format!("{{ {} }}", fields.join("; "))
+}
+
+fn literal(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("'{}'", s.replace('\'', "\\'")),
+        other => other.to_string(),
+    }
+}
+
+fn union(members: Vec<String>) -> String {
+    match dedup(members) {
+        m if m.is_empty() => "unknown".to_string(),
+        m => m.join(" | "),
+    }
+}
+
+fn dedup(members: Vec<String>) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for m in members {
+        if !out.contains(&m) {
+            out.push(m);
+        }
+    }
+    out
+}
+
+/// Parenthesises unions/intersections so they can be used as an array item
+/// or combined with another operator.
+fn wrap(ty: &str) -> String {
+    if (ty.contains(" | ") || ty.contains(" & ")) && !ty.starts_with('{') {
+        format!("({})", ty)
+    } else {
+        ty.to_string()
+    }
+}
+
+/// Schema names such as `ApiResponse_AnimeData` or `a.b.C` as valid identifiers.
+fn type_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect()
+}
+
+fn property_name(key: &str) -> String {
+    let valid = key.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if valid {
+        key.to_string()
+    } else {
+        format!("'{}'", key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_golden_file() {
+        let fixture: Value = serde_json::from_str(include_str!("fixtures/components.json")).unwrap();
+        let rendered = render_schemas(fixture["components"]["schemas"].as_object().unwrap());
+        assert_eq!(rendered, include_str!("fixtures/components.d.ts"));
+    }
+
+    #[test]
+    fn exports_scraper_response_types() {
+        let types = export_types();
+        assert!(types.contains("export interface AnimeDetailData {"));
+        assert!(types.contains("export interface ChapterData {"));
+    }
+}
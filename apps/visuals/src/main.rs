@@ -1,9 +1,11 @@
 use bevy::prelude::*;
 use bevy::window::WindowResolution;
 
-use bevy::asset::AssetMetaCheck;
+use bevy::asset::{AssetLoadFailedEvent, AssetMetaCheck, LoadState};
 use bevy::color::palettes::css::*;
+use bevy::input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll, MouseScrollUnit};
 use rand::RngExt;
+use std::sync::Mutex;
 
 // CinematicState removed to allow infinite simulation without state transition.
 
@@ -22,6 +24,19 @@ enum PlanetType {
 }
 
 impl PlanetType {
+    fn name(&self) -> &'static str {
+        match self {
+            PlanetType::Mercury => "Mercury",
+            PlanetType::Venus => "Venus",
+            PlanetType::Earth => "Earth",
+            PlanetType::Mars => "Mars",
+            PlanetType::Jupiter => "Jupiter",
+            PlanetType::Saturn => "Saturn",
+            PlanetType::Uranus => "Uranus",
+            PlanetType::Neptune => "Neptune",
+        }
+    }
+
     fn texture_path(&self) -> &'static str {
         match self {
             PlanetType::Mercury => "mercury.jpg",
@@ -83,6 +98,7 @@ struct Sun;
 
 #[derive(Component)]
 struct Planet {
+    kind: PlanetType,
     orbit_radius: f32,
     orbit_speed: f32,
     angle: f32,
@@ -91,15 +107,158 @@ struct Planet {
 #[derive(Component)]
 struct BackgroundStar;
 
+/// Screen-space name tag that follows `planet`.
+#[derive(Component)]
+struct PlanetLabel {
+    planet: Entity,
+}
+
+const LABEL_COLOR: Color = Color::srgba(0.8, 0.9, 1.0, 0.8);
+const SELECTED_LABEL_COLOR: Color = Color::srgb(1.0, 0.8, 0.3);
+/// Label font size at [`LABEL_REFERENCE_DISTANCE`]; closer planets get
+/// bigger labels, farther ones smaller, within the min/max below.
+const LABEL_FONT_SIZE: f32 = 16.0;
+const LABEL_REFERENCE_DISTANCE: f32 = 700.0;
+const MIN_LABEL_FONT_SIZE: f32 = 12.0;
+const MAX_LABEL_FONT_SIZE: f32 = 22.0;
+/// Cursor travel, in pixels, beyond which a press counts as a drag rather
+/// than a click.
+const CLICK_SLOP: f32 = 5.0;
+/// Smallest radius clicks are tested against, so tiny planets like Mercury
+/// can still be hit.
+const MIN_PICK_RADIUS: f32 = 12.0;
+
+/// The planet last clicked, if any.
+#[derive(Resource, Default)]
+struct SelectedPlanet(Option<Entity>);
+
 #[derive(Component)]
 struct CinematicCamera;
 
+/// A body whose material uses `texture`, drawn in `fallback_color` instead
+/// if the texture can't be loaded.
+#[derive(Component)]
+struct TexturedBody {
+    texture: Handle<Image>,
+    fallback_color: Color,
+}
+
+/// Textures still loading, and the paths of those that failed.
+#[derive(Resource, Default)]
+struct TextureLoading {
+    pending: Vec<Handle<Image>>,
+    failed: Vec<String>,
+    settled: bool,
+}
+
 #[derive(Resource)]
 struct CinematicTimer {
     elapsed: f32,
     duration: f32,
 }
 
+/// Seconds without input after which interactive mode hands the camera back
+/// to the cinematic path.
+const INTERACTIVE_IDLE_TIMEOUT: f32 = 20.0;
+/// Seconds the cinematic path takes to ease back in after interactive mode.
+const CINEMATIC_RESUME_SECS: f32 = 2.0;
+const ORBIT_SENSITIVITY: f32 = 0.005;
+const ZOOM_PER_LINE: f32 = 0.1;
+const MIN_CAMERA_RADIUS: f32 = 120.0;
+const MAX_CAMERA_RADIUS: f32 = 3000.0;
+
+/// Who drives the `CinematicCamera`: the scripted path (the default) or the
+/// user, via mouse drag (orbit) and scroll (zoom). Toggled with `C` or the
+/// `CAMERA_INTERACTIVE` / `CAMERA_CINEMATIC` parent messages.
+#[derive(Resource)]
+struct CameraControl {
+    interactive: bool,
+    /// Orbit around the sun while interactive.
+    yaw: f32,
+    pitch: f32,
+    radius: f32,
+    /// Seconds since the last input while interactive.
+    idle: f32,
+    /// 0 right after leaving interactive mode, easing to 1 as the
+    /// cinematic path takes over again.
+    resume: f32,
+}
+
+impl Default for CameraControl {
+    fn default() -> Self {
+        Self {
+            interactive: false,
+            yaw: 0.0,
+            pitch: 0.0,
+            radius: 700.0,
+            idle: 0.0,
+            resume: 1.0,
+        }
+    }
+}
+
+impl CameraControl {
+    /// Takes over from wherever the camera currently is, so it doesn't jump.
+    fn enter_interactive(&mut self, from: &Transform) {
+        let offset = from.translation;
+        self.radius = offset.length().clamp(MIN_CAMERA_RADIUS, MAX_CAMERA_RADIUS);
+        self.yaw = offset.z.atan2(offset.x);
+        self.pitch = (offset.y / offset.length().max(f32::EPSILON)).asin();
+        self.idle = 0.0;
+        self.interactive = true;
+    }
+
+    fn leave_interactive(&mut self) {
+        self.interactive = false;
+        self.resume = 0.0;
+    }
+
+    fn orbit_translation(&self) -> Vec3 {
+        Vec3::new(
+            self.pitch.cos() * self.yaw.cos(),
+            self.pitch.sin(),
+            self.pitch.cos() * self.yaw.sin(),
+        ) * self.radius
+    }
+}
+
+const MIN_TIME_SCALE: f32 = 0.125;
+const MAX_TIME_SCALE: f32 = 64.0;
+
+/// How fast the planets orbit. `Space` pauses, `+`/`-` double or halve the
+/// speed; the embedding page can send `SIM_PAUSE`, `SIM_RESUME` and
+/// `SIM_SPEED:<scale>`.
+#[derive(Resource)]
+struct SimulationControl {
+    time_scale: f32,
+    paused: bool,
+}
+
+impl Default for SimulationControl {
+    fn default() -> Self {
+        Self {
+            time_scale: 1.0,
+            paused: false,
+        }
+    }
+}
+
+impl SimulationControl {
+    fn set_time_scale(&mut self, scale: f32) {
+        if scale.is_finite() {
+            self.time_scale = scale.clamp(MIN_TIME_SCALE, MAX_TIME_SCALE);
+        }
+    }
+}
+
+/// A string `postMessage`d to this window by the embedding page.
+#[derive(Message)]
+struct ParentMessage(String);
+
+/// Messages received by the browser listener, waiting to be turned into
+/// [`ParentMessage`]s on the next frame.
+static PARENT_INBOX: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
@@ -114,7 +273,7 @@ fn main() {
             ..default()
         }).set(AssetPlugin {
             file_path: "assets".to_string(),
-            meta_check: AssetMetaCheck::Never,
+            meta_check: asset_meta_check(),
             ..default()
         }).set(bevy::render::RenderPlugin {
             render_creation: bevy::render::settings::RenderCreation::Automatic(bevy::render::settings::WgpuSettings {
@@ -125,29 +284,139 @@ fn main() {
         }))
         .insert_resource(ClearColor(Color::BLACK))
         .insert_resource(CinematicTimer { elapsed: 0.0, duration: 10.0 })
-        .add_systems(Startup, (setup, signal_readiness))
+        .init_resource::<TextureLoading>()
+        .init_resource::<CameraControl>()
+        .init_resource::<SelectedPlanet>()
+        .init_resource::<SimulationControl>()
+        .add_message::<ParentMessage>()
+        .add_systems(Startup, (setup, signal_readiness, listen_to_parent))
         .add_systems(Update, (
-            update_cinematic_timer,
-            orbital_mechanics,
-            cinematic_camera_movement,
+            forward_parent_messages,
+            track_texture_loading,
+            (
+                toggle_camera_mode,
+                interactive_camera,
+                update_cinematic_timer.run_if(camera_is_cinematic),
+                cinematic_camera_movement.run_if(camera_is_cinematic),
+            )
+                .chain()
+                .after(forward_parent_messages),
+            (simulation_controls, orbital_mechanics)
+                .chain()
+                .after(forward_parent_messages),
+            (select_planet_on_click, position_planet_labels)
+                .chain()
+                .after(orbital_mechanics)
+                .after(cinematic_camera_movement)
+                .after(interactive_camera),
         ))
         .run();
 }
 
-fn signal_readiness() {
+/// The static host serves no `.meta` files, so Bevy must not ask for them
+/// unless built with the `asset-meta-check` feature.
+fn asset_meta_check() -> AssetMetaCheck {
+    if cfg!(feature = "asset-meta-check") {
+        AssetMetaCheck::Always
+    } else {
+        AssetMetaCheck::Never
+    }
+}
+
+/// Sends `message` to the page embedding the canvas, if any.
+fn post_to_parent(message: &str) {
     #[cfg(target_arch = "wasm32")]
     {
         if let Some(window) = web_sys::window() {
             if let Some(parent) = window.parent().ok().flatten() {
-                let _ = parent.post_message(&wasm_bindgen::JsValue::from_str("PROTOCOL_READY"), "*");
+                let _ = parent.post_message(&wasm_bindgen::JsValue::from_str(message), "*");
+            }
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = message;
+}
+
+fn signal_readiness() {
+    post_to_parent("PROTOCOL_READY");
+}
+
+/// Queues every string message the embedding page sends into
+/// [`PARENT_INBOX`].
+fn listen_to_parent() {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use wasm_bindgen::closure::Closure;
+        use wasm_bindgen::JsCast;
+
+        let on_message = Closure::<dyn FnMut(web_sys::MessageEvent)>::new(|ev: web_sys::MessageEvent| {
+            if let Some(msg) = ev.data().as_string() {
+                PARENT_INBOX.lock().unwrap_or_else(|e| e.into_inner()).push(msg);
+            }
+        });
+        if let Some(window) = web_sys::window() {
+            let _ = window.add_event_listener_with_callback("message", on_message.as_ref().unchecked_ref());
+        }
+        on_message.forget(); // Keep the listener alive
+    }
+}
+
+fn forward_parent_messages(mut messages: MessageWriter<ParentMessage>) {
+    let received = std::mem::take(&mut *PARENT_INBOX.lock().unwrap_or_else(|e| e.into_inner()));
+    for msg in received {
+        messages.write(ParentMessage(msg));
+    }
+}
+
+/// Switches bodies whose texture failed to load (e.g. a 404 on WASM) to
+/// their solid fallback colour, and once every texture has loaded or
+/// failed, tells the parent page `ASSETS_FAILED` if any did.
+fn track_texture_loading(
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<TextureLoading>,
+    mut failures: MessageReader<AssetLoadFailedEvent<Image>>,
+    bodies: Query<(&TexturedBody, &MeshMaterial3d<StandardMaterial>)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for failure in failures.read() {
+        warn!("Texture {} failed to load: {}", failure.path, failure.error);
+        loading.failed.push(failure.path.to_string());
+
+        for (body, material) in &bodies {
+            if body.texture.id() != failure.id {
+                continue;
+            }
+            if let Some(mut material) = materials.get_mut(&material.0) {
+                material.base_color_texture = None;
+                material.base_color = body.fallback_color;
             }
         }
     }
+
+    if loading.settled {
+        return;
+    }
+    loading.pending.retain(|texture| {
+        !matches!(
+            asset_server.load_state(texture.id()),
+            LoadState::Loaded | LoadState::Failed(_)
+        )
+    });
+    if loading.pending.is_empty() {
+        loading.settled = true;
+        if loading.failed.is_empty() {
+            info!("All textures loaded");
+        } else {
+            error!("Textures failed to load: {}", loading.failed.join(", "));
+            post_to_parent("ASSETS_FAILED");
+        }
+    }
 }
 
 fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    mut loading: ResMut<TextureLoading>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
@@ -169,16 +438,21 @@ fn setup(
 
     // The Sun: Realistic Texture + High Emissive + Unlit
     let sun_texture = asset_server.load("sun.jpg");
+    loading.pending.push(sun_texture.clone());
 
     commands.spawn((
         Mesh3d(meshes.add(Sphere::new(45.0))),
         MeshMaterial3d(materials.add(StandardMaterial {
             base_color: Color::from(ORANGE),
-            base_color_texture: Some(sun_texture),
+            base_color_texture: Some(sun_texture.clone()),
             emissive: LinearRgba::new(1.0, 0.4, 0.1, 1.0) * 12.0, // Boosted emissive for Bloom
             unlit: true,
             ..default()
         })),
+        TexturedBody {
+            texture: sun_texture,
+            fallback_color: Color::from(ORANGE),
+        },
         Sun,
     ));
 
@@ -201,7 +475,8 @@ fn setup(
         let angle = rand::rng().random_range(0.0..std::f32::consts::TAU);
 
         let path = planet_type.texture_path();
-        let planet_texture = asset_server.load(path);
+        let planet_texture: Handle<Image> = asset_server.load(path);
+        loading.pending.push(planet_texture.clone());
 
         let fallback_color = match planet_type {
             PlanetType::Mercury => SILVER,
@@ -229,33 +504,58 @@ fn setup(
             Mesh3d(meshes.add(Sphere::new(1.0))),
             MeshMaterial3d(materials.add(StandardMaterial {
                 base_color: Color::from(fallback_color),
-                base_color_texture: Some(planet_texture),
+                base_color_texture: Some(planet_texture.clone()),
                 metallic: 0.1,
                 perceptual_roughness: 0.8,
                 ..default()
             })),
+            TexturedBody {
+                texture: planet_texture,
+                fallback_color: Color::from(fallback_color),
+            },
             transform,
             Planet {
+                kind: planet_type,
                 orbit_radius,
                 orbit_speed,
                 angle,
             },
         )).id();
 
+        commands.spawn((
+            Text::new(planet_type.name()),
+            TextFont {
+                font_size: LABEL_FONT_SIZE,
+                ..default()
+            },
+            TextColor(LABEL_COLOR),
+            Node {
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            Visibility::Hidden,
+            PlanetLabel { planet: planet_entity },
+        ));
+
         // Special Case: Saturn's Rings
         if matches!(planet_type, PlanetType::Saturn) {
-            let ring_texture = asset_server.load("saturn_ring.jpg");
+            let ring_texture: Handle<Image> = asset_server.load("saturn_ring.jpg");
+            loading.pending.push(ring_texture.clone());
 
             commands.entity(planet_entity).with_children(|parent| {
                 parent.spawn((
                     Mesh3d(meshes.add(Annulus::new(1.2, 2.8))), // Realistic thin ring disc
                     MeshMaterial3d(materials.add(StandardMaterial {
-                        base_color_texture: Some(ring_texture),
+                        base_color_texture: Some(ring_texture.clone()),
                         alpha_mode: AlphaMode::Blend,
                         cull_mode: None, // Visible from both sides
                         unlit: false,
                         ..default()
                     })),
+                    TexturedBody {
+                        texture: ring_texture,
+                        fallback_color: Color::from(GOLD).with_alpha(0.6),
+                    },
                     Transform::from_rotation(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)),
                 ));
             });
@@ -297,11 +597,50 @@ fn setup(
 
 // cleanup_intro removed. Simulation continues indefinitely.
 
+fn simulation_controls(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut messages: MessageReader<ParentMessage>,
+    mut control: ResMut<SimulationControl>,
+) {
+    if keys.just_pressed(KeyCode::Space) {
+        control.paused = !control.paused;
+    }
+    if keys.any_just_pressed([KeyCode::Equal, KeyCode::NumpadAdd]) {
+        let scale = control.time_scale * 2.0;
+        control.set_time_scale(scale);
+    }
+    if keys.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        let scale = control.time_scale / 2.0;
+        control.set_time_scale(scale);
+    }
+
+    for ParentMessage(msg) in messages.read() {
+        match msg.as_str() {
+            "SIM_PAUSE" => control.paused = true,
+            "SIM_RESUME" => control.paused = false,
+            _ => {
+                if let Some(scale) = msg.strip_prefix("SIM_SPEED:").and_then(|s| s.trim().parse().ok()) {
+                    control.set_time_scale(scale);
+                }
+            }
+        }
+    }
+
+    if control.is_changed() {
+        let state = if control.paused { "paused" } else { "running" };
+        info!("Simulation {} at x{}", state, control.time_scale);
+    }
+}
+
 fn orbital_mechanics(
     time: Res<Time>,
+    control: Res<SimulationControl>,
     mut query: Query<(&mut Transform, &mut Planet)>,
 ) {
-    let dt = time.delta_secs();
+    if control.paused {
+        return;
+    }
+    let dt = time.delta_secs() * control.time_scale;
     for (mut transform, mut planet) in &mut query {
         planet.angle += planet.orbit_speed * dt;
         transform.translation.x = planet.orbit_radius * planet.angle.cos();
@@ -312,6 +651,199 @@ fn orbital_mechanics(
     }
 }
 
+fn camera_is_cinematic(control: Res<CameraControl>) -> bool {
+    !control.interactive
+}
+
+fn toggle_camera_mode(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut messages: MessageReader<ParentMessage>,
+    mut control: ResMut<CameraControl>,
+    camera: Query<&Transform, With<CinematicCamera>>,
+) {
+    let mut wanted = keys.just_pressed(KeyCode::KeyC).then_some(!control.interactive);
+    for ParentMessage(msg) in messages.read() {
+        match msg.as_str() {
+            "CAMERA_INTERACTIVE" => wanted = Some(true),
+            "CAMERA_CINEMATIC" => wanted = Some(false),
+            _ => {}
+        }
+    }
+
+    match wanted {
+        Some(true) if !control.interactive => {
+            let Ok(transform) = camera.single() else {
+                return;
+            };
+            control.enter_interactive(transform);
+            info!("Camera: interactive");
+        }
+        Some(false) if control.interactive => {
+            control.leave_interactive();
+            info!("Camera: cinematic");
+        }
+        _ => {}
+    }
+}
+
+/// Drag to orbit the sun, scroll to zoom. Hands back to the cinematic path
+/// after [`INTERACTIVE_IDLE_TIMEOUT`] seconds without input.
+fn interactive_camera(
+    time: Res<Time>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    motion: Res<AccumulatedMouseMotion>,
+    scroll: Res<AccumulatedMouseScroll>,
+    mut control: ResMut<CameraControl>,
+    mut query: Query<&mut Transform, With<CinematicCamera>>,
+) {
+    if !control.interactive {
+        return;
+    }
+
+    let dragging = buttons.pressed(MouseButton::Left) && motion.delta != Vec2::ZERO;
+    if dragging {
+        control.yaw += motion.delta.x * ORBIT_SENSITIVITY;
+        control.pitch = (control.pitch + motion.delta.y * ORBIT_SENSITIVITY).clamp(-1.4, 1.4);
+    }
+
+    let lines = match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y,
+        MouseScrollUnit::Pixel => scroll.delta.y / 100.0,
+    };
+    if lines != 0.0 {
+        control.radius = (control.radius * (1.0 - lines * ZOOM_PER_LINE))
+            .clamp(MIN_CAMERA_RADIUS, MAX_CAMERA_RADIUS);
+    }
+
+    if dragging || lines != 0.0 {
+        control.idle = 0.0;
+    } else {
+        control.idle += time.delta_secs();
+        if control.idle >= INTERACTIVE_IDLE_TIMEOUT {
+            control.leave_interactive();
+            info!("Camera: cinematic (idle)");
+            return;
+        }
+    }
+
+    let Ok(mut transform) = query.single_mut() else {
+        return;
+    };
+    transform.translation = control.orbit_translation();
+    transform.look_at(Vec3::ZERO, Dir3::Y);
+}
+
+/// Keeps each label just above its planet on screen, sized by distance so
+/// far planets stay readable, and hides labels of planets behind the camera.
+fn position_planet_labels(
+    camera: Query<(&Camera, &GlobalTransform), With<CinematicCamera>>,
+    planets: Query<(&GlobalTransform, &Planet)>,
+    selected: Res<SelectedPlanet>,
+    mut labels: Query<(&PlanetLabel, &mut Node, &mut TextFont, &mut TextColor, &mut Visibility)>,
+) {
+    let Ok((camera, camera_transform)) = camera.single() else {
+        return;
+    };
+
+    for (label, mut node, mut font, mut color, mut visibility) in &mut labels {
+        let Ok((planet_transform, planet)) = planets.get(label.planet) else {
+            continue;
+        };
+        let anchor = planet_transform.translation() + Vec3::Y * planet.kind.size() * 1.5;
+        let Ok(screen) = camera.world_to_viewport(camera_transform, anchor) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+
+        let distance = camera_transform.translation().distance(anchor).max(1.0);
+        let size = (LABEL_FONT_SIZE * LABEL_REFERENCE_DISTANCE / distance)
+            .clamp(MIN_LABEL_FONT_SIZE, MAX_LABEL_FONT_SIZE)
+            .round();
+        if font.font_size != size {
+            font.font_size = size;
+        }
+
+        // Roughly centre the text above the anchor
+        let half_width = planet.kind.name().len() as f32 * size * 0.3;
+        node.left = Val::Px(screen.x - half_width);
+        node.top = Val::Px(screen.y - size * 1.2);
+
+        let wanted = if selected.0 == Some(label.planet) {
+            SELECTED_LABEL_COLOR
+        } else {
+            LABEL_COLOR
+        };
+        if color.0 != wanted {
+            color.0 = wanted;
+        }
+    }
+}
+
+/// On a click (not a drag), casts a ray from the cursor and selects the
+/// nearest planet it hits, telling the parent page `PLANET_SELECTED:<name>`.
+fn select_planet_on_click(
+    buttons: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window>,
+    camera: Query<(&Camera, &GlobalTransform), With<CinematicCamera>>,
+    planets: Query<(Entity, &GlobalTransform, &Planet)>,
+    mut selected: ResMut<SelectedPlanet>,
+    mut pressed_at: Local<Option<Vec2>>,
+) {
+    let Ok(window) = windows.single() else {
+        return;
+    };
+    let cursor = window.cursor_position();
+    if buttons.just_pressed(MouseButton::Left) {
+        *pressed_at = cursor;
+    }
+    if !buttons.just_released(MouseButton::Left) {
+        return;
+    }
+    let (Some(start), Some(cursor)) = (pressed_at.take(), cursor) else {
+        return;
+    };
+    if start.distance(cursor) > CLICK_SLOP {
+        return;
+    }
+
+    let Ok((camera, camera_transform)) = camera.single() else {
+        return;
+    };
+    let Ok(ray) = camera.viewport_to_world(camera_transform, cursor) else {
+        return;
+    };
+
+    let hit = planets
+        .iter()
+        .filter_map(|(entity, transform, planet)| {
+            ray_hits_sphere(ray, transform.translation(), planet.kind.size().max(MIN_PICK_RADIUS))
+                .map(|distance| (distance, entity, planet.kind))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0));
+
+    if let Some((_, entity, kind)) = hit {
+        selected.0 = Some(entity);
+        info!("Selected {}", kind.name());
+        post_to_parent(&format!("PLANET_SELECTED:{}", kind.name()));
+    }
+}
+
+/// Distance along `ray` to a sphere at `center`, if the ray hits it.
+fn ray_hits_sphere(ray: Ray3d, center: Vec3, radius: f32) -> Option<f32> {
+    let to_center = center - ray.origin;
+    let along = to_center.dot(*ray.direction);
+    if along < 0.0 {
+        return None;
+    }
+    let miss_sq = to_center.length_squared() - along * along;
+    let radius_sq = radius * radius;
+    if miss_sq > radius_sq {
+        return None;
+    }
+    Some(along - (radius_sq - miss_sq).sqrt())
+}
+
 fn update_cinematic_timer(
     time: Res<Time>,
     mut timer: ResMut<CinematicTimer>,
@@ -320,8 +852,10 @@ fn update_cinematic_timer(
 }
 
 fn cinematic_camera_movement(
+    time: Res<Time>,
     mut query: Query<&mut Transform, With<CinematicCamera>>,
     timer: Res<CinematicTimer>,
+    mut control: ResMut<CameraControl>,
 ) {
     let mut transform = query.single_mut().unwrap();
 
@@ -346,9 +880,7 @@ fn cinematic_camera_movement(
     let drift_height = if t > d { ((t - d) * 0.5).cos() * 100.0 } else { 0.0 };
     let height = base_height + drift_height;
 
-    transform.translation.x = radius * rot_angle.cos();
-    transform.translation.z = radius * rot_angle.sin();
-    transform.translation.y = height;
+    let target = Vec3::new(radius * rot_angle.cos(), height, radius * rot_angle.sin());
 
     // Look at sun with a slight dynamic offset to keep it "cinematic"
     let look_offset = if t > d {
@@ -356,7 +888,16 @@ fn cinematic_camera_movement(
     } else {
         Vec3::ZERO
     };
-    
+
+    // Ease back onto the path after the user let go of the camera
+    if control.resume < 1.0 {
+        control.resume = (control.resume + time.delta_secs() / CINEMATIC_RESUME_SECS).min(1.0);
+        let ease = control.resume * control.resume * (3.0 - 2.0 * control.resume);
+        transform.translation = transform.translation.lerp(target, ease);
+    } else {
+        transform.translation = target;
+    }
+
     transform.look_at(Vec3::ZERO + look_offset, Dir3::Y);
 }
 